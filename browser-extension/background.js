@@ -0,0 +1,98 @@
+// Axis Tab Bridge
+// - Sends the active tab's URL and title to Axis (POST http://127.0.0.1:<port>/v1/browser/tab) when it changes or finishes loading
+// - Page text is only sent when Axis asks for it (BROWSER_TAB): the worker keeps a GET /v1/browser/wait open
+//   and answers a request with POST /v1/browser/text
+// - Needs api_server_enabled and browser_bridge_enabled in Axis, and the browser extension token (options page)
+// - Incognito tabs and non-http(s) pages are never sent
+
+const MAX_TEXT_CHARS = 200000;
+
+function browserName() {
+  const ua = navigator.userAgent;
+  if (ua.includes("Edg/")) return "msedge";
+  if (ua.includes("OPR/")) return "opera";
+  if (ua.includes("Firefox/")) return "firefox";
+  return "chrome";
+}
+
+async function pageText(tabId) {
+  try {
+    const [result] = await chrome.scripting.executeScript({
+      target: { tabId },
+      func: (max) => (document.body ? document.body.innerText : "").slice(0, max),
+      args: [MAX_TEXT_CHARS],
+    });
+    return result?.result || "";
+  } catch (e) {
+    // chrome:// pages, the Web Store and PDFs cannot be scripted: Axis fetches the URL instead
+    return "";
+  }
+}
+
+function sendable(tab) {
+  return tab && !tab.incognito && /^https?:/.test(tab.url || "");
+}
+
+async function post(path, body) {
+  const { port = 7341, token = "" } = await chrome.storage.local.get(["port", "token"]);
+  if (!token) return;
+  try {
+    await fetch(`http://127.0.0.1:${port}${path}`, {
+      method: "POST",
+      headers: { "Content-Type": "application/json", Authorization: `Bearer ${token}` },
+      body: JSON.stringify(body),
+    });
+  } catch (e) {
+    // Axis is not running
+  }
+}
+
+async function send(tab) {
+  if (!sendable(tab)) return;
+  await post("/v1/browser/tab", { url: tab.url, title: tab.title || "", browser: browserName() });
+  waitForRequests();
+}
+
+// One long-poll loop at a time; Axis answers {"want_text": true} when BROWSER_TAB needs the page text
+let waiting = false;
+
+async function waitForRequests() {
+  if (waiting) return;
+  waiting = true;
+  try {
+    for (;;) {
+      const { port = 7341, token = "" } = await chrome.storage.local.get(["port", "token"]);
+      if (!token) return;
+      const res = await fetch(`http://127.0.0.1:${port}/v1/browser/wait`, {
+        headers: { Authorization: `Bearer ${token}` },
+      });
+      if (!res.ok) return;
+      const { want_text } = await res.json();
+      if (!want_text) continue;
+      const [tab] = await chrome.tabs.query({ active: true, lastFocusedWindow: true });
+      if (!sendable(tab)) continue;
+      await post("/v1/browser/text", { url: tab.url, text: await pageText(tab.id) });
+    }
+  } catch (e) {
+    // Axis is not running; the next tab event starts the loop again
+  } finally {
+    waiting = false;
+  }
+}
+
+chrome.tabs.onActivated.addListener(async ({ tabId }) => {
+  send(await chrome.tabs.get(tabId));
+});
+
+chrome.tabs.onUpdated.addListener((_tabId, change, tab) => {
+  if (change.status === "complete" && tab.active) send(tab);
+});
+
+chrome.windows.onFocusChanged.addListener(async (windowId) => {
+  if (windowId === chrome.windows.WINDOW_ID_NONE) return;
+  const [tab] = await chrome.tabs.query({ active: true, windowId });
+  send(tab);
+});
+
+chrome.runtime.onStartup.addListener(waitForRequests);
+waitForRequests();
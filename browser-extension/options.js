@@ -0,0 +1,12 @@
+const port = document.getElementById("port");
+const token = document.getElementById("token");
+
+chrome.storage.local.get(["port", "token"]).then((v) => {
+  if (v.port) port.value = v.port;
+  if (v.token) token.value = v.token;
+});
+
+document.getElementById("save").addEventListener("click", async () => {
+  await chrome.storage.local.set({ port: Number(port.value) || 7341, token: token.value.trim() });
+  document.getElementById("status").textContent = "Saved";
+});
@@ -0,0 +1,195 @@
+// src-tauri/crates/axis-core/src/apps.rs
+//
+// EXEC のアプリ名の解決
+// - 利用者の別名 (settings.app_aliases。OS ごと): "browser" → "chrome.exe" / "エディタ" → "code"
+//   EXEC はまず別名を引き、無ければ組み込みの名前 → スタートメニューの検索 (src-tauri の shell.rs)
+// - 別名の先がパス / 実行ファイル名 (is_command) ならそのまま起動。それ以外はアプリ名としてスタートメニューを探す
+// - LEARN_ALIAS: <name> = <target> で Worker も覚えられる（check_learned で確かめ、必ず確認してから settings に保存）
+//   Worker の別名は組み込みの名前を上書きできず、先にスクリプト / ショートカットは使えない
+// - 名前の大文字小文字と前後の空白は区別しない
+// - AppEntry / find: インストール済みアプリの一覧 (src-tauri の app_catalog.rs が memory.db に持つ) からのあいまい検索
+//   完全一致 > 前方一致 > 単語の頭 > 部分一致 > 打ち間違い（1〜2 文字違い）。同点なら起動回数の多い方 → 短い名前
+// - shortlist: Worker に見せる一覧（最近使った順 → 名前順）
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const MAX_ALIAS_CHARS: usize = 60;
+
+const COMMAND_EXTS: &[&str] = &[".exe", ".bat", ".cmd", ".lnk", ".msc", ".app"];
+
+// LEARN_ALIAS の先にできないもの（中身を書き換えられるスクリプト / ショートカット）
+const SCRIPT_EXTS: &[&str] = &[
+    ".bat", ".cmd", ".lnk", ".url", ".ps1", ".vbs", ".vbe", ".js", ".jse", ".wsf", ".hta", ".msi",
+    ".scr", ".pif", ".com", ".sh", ".command",
+];
+
+// EXEC の組み込みの名前 (src-tauri の shell::execute_command)。LEARN_ALIAS では上書きさせない
+pub const BUILTIN_NAMES: &[&str] = &[
+    "calc", "calculator", "電卓", "notepad", "memo", "メモ", "メモ帳", "explorer", "folder",
+    "エクスプローラー", "cmd", "terminal", "ターミナル", "taskmgr", "タスクマネージャー",
+];
+
+pub fn alias_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+pub fn resolve<'a>(aliases: &'a BTreeMap<String, String>, request: &str) -> Option<&'a str> {
+    let key = alias_key(request);
+    aliases
+        .iter()
+        .find(|(name, _)| alias_key(name) == key)
+        .map(|(_, target)| target.trim())
+        .filter(|target| !target.is_empty())
+}
+
+// 保存する (名前, 先)
+pub fn check_alias(name: &str, target: &str) -> Result<(String, String), String> {
+    let name = alias_key(name);
+    let target = target.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_ALIAS_CHARS {
+        return Err(format!(
+            "alias name must be 1-{} characters",
+            MAX_ALIAS_CHARS
+        ));
+    }
+    if target.is_empty() {
+        return Err(format!("alias '{}' needs an app to open", name));
+    }
+    if [&name, &target].iter().any(|s| s.contains(['\n', '\r'])) {
+        return Err("alias must be on one line".to_string());
+    }
+    if alias_key(&target) == name {
+        return Err(format!("'{}' cannot be an alias of itself", name));
+    }
+    Ok((name, target))
+}
+
+// Worker の LEARN_ALIAS: check_alias に加えて、組み込みの名前の上書きとスクリプトの先を断る
+// （パスの置き場所は src-tauri の settings::learn_alias が確かめる）
+pub fn check_learned(name: &str, target: &str) -> Result<(String, String), String> {
+    let (name, target) = check_alias(name, target)?;
+    if BUILTIN_NAMES.contains(&name.as_str()) {
+        return Err(format!("'{}' is a built-in app name and cannot be changed", name));
+    }
+    let lower = target.to_lowercase();
+    if let Some(ext) = SCRIPT_EXTS.iter().find(|ext| lower.ends_with(*ext)) {
+        return Err(format!("'{}' files cannot be an alias target", ext));
+    }
+    if target.split(['\\', '/']).any(|part| part == "..") {
+        return Err("alias target cannot contain '..'".to_string());
+    }
+    Ok((name, target))
+}
+
+// "chrome.exe" / "C:\Tools\app.exe" / "/usr/bin/code" は起動するもの。"code" / "Visual Studio Code" はアプリ名
+pub fn is_command(target: &str) -> bool {
+    let target = target.trim().to_lowercase();
+    target.contains(['\\', '/']) || COMMAND_EXTS.iter().any(|ext| target.ends_with(ext))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppEntry {
+    pub id: String, // Windows は AppID（shell:AppsFolder\<id> で起動）
+    pub name: String,
+    pub launches: u32,
+    pub last_launched: Option<i64>,
+}
+
+// これ未満は別のアプリとみなす
+pub const MIN_SCORE: f32 = 0.5;
+
+fn words(s: &str) -> Vec<&str> {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+// 0.0〜1.0
+pub fn score(query: &str, name: &str) -> f32 {
+    let (query, name) = (alias_key(query), alias_key(name));
+    if query.is_empty() || name.is_empty() {
+        return 0.0;
+    }
+    if query == name {
+        return 1.0;
+    }
+    if name.starts_with(&query) {
+        return 0.9;
+    }
+    let name_words = words(&name);
+    let query_words = words(&query);
+    if !query_words.is_empty()
+        && query_words
+            .iter()
+            .all(|q| name_words.iter().any(|w| w.starts_with(q)))
+    {
+        return 0.8;
+    }
+    if name.contains(&query) {
+        return 0.7;
+    }
+    // "chorme" → "Google Chrome": 名前の単語 / 名前全体との打ち間違い
+    let typo = name_words
+        .iter()
+        .copied()
+        .chain([name.as_str()])
+        .map(|w| similarity(&query, w))
+        .fold(0.0, f32::max);
+    if typo >= 0.75 {
+        return typo * 0.65;
+    }
+    0.0
+}
+
+// 1 - 編集距離 / 長い方の文字数（隣どうしの入れ替えは 1 回と数える）
+fn similarity(a: &str, b: &str) -> f32 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let len = a.len().max(b.len());
+    if len == 0 {
+        return 1.0;
+    }
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    1.0 - d[a.len()][b.len()] as f32 / len as f32
+}
+
+pub fn find<'a>(catalog: &'a [AppEntry], query: &str) -> Option<&'a AppEntry> {
+    catalog
+        .iter()
+        .map(|app| (score(query, &app.name), app))
+        .filter(|(s, _)| *s >= MIN_SCORE)
+        .max_by(|(sa, a), (sb, b)| {
+            sa.total_cmp(sb)
+                .then(a.launches.cmp(&b.launches))
+                .then(b.name.chars().count().cmp(&a.name.chars().count()))
+        })
+        .map(|(_, app)| app)
+}
+
+pub fn shortlist(catalog: &[AppEntry], max: usize) -> Vec<&AppEntry> {
+    let mut list: Vec<&AppEntry> = catalog.iter().collect();
+    list.sort_by(|a, b| {
+        b.last_launched
+            .cmp(&a.last_launched)
+            .then_with(|| alias_key(&a.name).cmp(&alias_key(&b.name)))
+    });
+    list.truncate(max);
+    list
+}
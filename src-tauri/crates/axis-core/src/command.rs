@@ -0,0 +1,1114 @@
+// src-tauri/crates/axis-core/src/command.rs
+//
+// Worker の出力からアクションを読み出す（書式は prompts/worker.md）
+//   response := action ( " && " action )*          "NO" と空は飛ばす
+//   action   := ["EXECUTE "] NAME [":" argument]   NAME は GRAMMAR の表のもの
+//   argument := text ["|||" content]
+// - "..." で囲んだ引数は 1 つのまま（中の && / @ / ||| では切らない。\" と \\ だけエスケープ）
+// - "|||" の後ろ (content) は && で切らない。後ろが次のアクション (NAME: / LOOK 等) の時だけ切る
+//   （コードやメモの中の "make && make install" や "a: b" はそのまま）
+// - \&& はどこでも文字どおりの "&&"
+// - 書式の誤りは Action::Invalid に。理由と正しい書式をアクションの結果としてモデルに返す
+// - SAVE / RUN / GIT / HA などの引数もここで型のあるフィールドに分ける（実行側で文字列を切り直さない）
+// 新しいアクションは GRAMMAR / Action / Action::name / parse_action に足す
+// - プラグインのアクション (src-tauri の plugins.rs) は set_plugin_actions で登録した NAME。"NAME: <argument>" で Action::Plugin に
+// （実行は src-tauri の adapter.rs、外の文面の後の扱いは policy.rs。どちらも match なので足し忘れはコンパイルで分かる）
+
+use crate::files::{self, Mode};
+use crate::{apps, keys};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Takes {
+    Nothing,  // LOOK
+    Optional, // "PROCESSES" / "PROCESSES: cpu" / "PROCESSES cpu"
+    Colon,    // "NEWS" / "NEWS: rust"（"NEWS today is ..." は文なのでアクションにしない）
+    Required, // "EXEC: <app>"
+}
+
+// IMAGE_GEN の ||| の後ろ
+const ASPECTS: &[&str] = &["1:1", "16:9", "9:16"];
+// GIT: の操作
+const GIT_OPS: &[&str] = &["status", "diff", "log", "repos"];
+
+// (NAME, 引数, 書式)。書式は Invalid の文面に出す
+const GRAMMAR: &[(&str, Takes, &str)] = &[
+    ("LOOK", Takes::Nothing, "LOOK"),
+    ("RECORD_SCREEN", Takes::Optional, "RECORD_SCREEN: <seconds>"),
+    ("APPS", Takes::Nothing, "APPS"),
+    ("BROWSER_TAB", Takes::Nothing, "BROWSER_TAB"),
+    ("UNDO_LAST", Takes::Nothing, "UNDO_LAST"),
+    ("PROCESSES", Takes::Optional, "PROCESSES: <memory|cpu>"),
+    ("DISK", Takes::Optional, "DISK: <path>"),
+    (
+        "ACTIVITY",
+        Takes::Optional,
+        "ACTIVITY: <today|yesterday|YYYY-MM-DD>",
+    ),
+    ("WEATHER", Takes::Optional, "WEATHER: <place>"),
+    ("NEWS", Takes::Colon, "NEWS: <topic>"),
+    ("CALENDAR", Takes::Colon, "CALENDAR: <hours>"),
+    (
+        "CHECK_EMAIL",
+        Takes::Optional,
+        "CHECK_EMAIL: <max messages>",
+    ),
+    ("SEARCH", Takes::Required, "SEARCH: <query>"),
+    ("FETCH", Takes::Required, "FETCH: <http(s) url>"),
+    (
+        "IMAGE_GEN",
+        Takes::Required,
+        "IMAGE_GEN: <prompt> [||| <1:1|16:9|9:16>]",
+    ),
+    (
+        "SAVE",
+        Takes::Required,
+        "SAVE: <filename> [mode] ||| <content>",
+    ),
+    (
+        "GENERATE_FROM_TEMPLATE",
+        Takes::Required,
+        "GENERATE_FROM_TEMPLATE: <template> ||| {json fields}",
+    ),
+    (
+        "SCHEDULE",
+        Takes::Required,
+        "SCHEDULE: <when> ||| <message>",
+    ),
+    ("EXEC", Takes::Required, "EXEC: <app>"),
+    (
+        "LEARN_ALIAS",
+        Takes::Required,
+        "LEARN_ALIAS: <name> = <app or exe>",
+    ),
+    (
+        "TYPE",
+        Takes::Required,
+        "TYPE: <text> @ <window> [> <field>]",
+    ),
+    ("KILL", Takes::Required, "KILL: <process name or PID>"),
+    ("FOCUS", Takes::Required, "FOCUS: <window title or app>"),
+    (
+        "MINIMIZE",
+        Takes::Required,
+        "MINIMIZE: <window title or app>",
+    ),
+    (
+        "OPEN_FILE",
+        Takes::Required,
+        "OPEN_FILE: <filename or full path>",
+    ),
+    (
+        "REVEAL_IN_EXPLORER",
+        Takes::Required,
+        "REVEAL_IN_EXPLORER: <filename or full path>",
+    ),
+    ("PRESS", Takes::Required, "PRESS: <key|ctrl+shift+s|tab x3>"),
+    ("CLICK", Takes::Required, "CLICK: <x>,<y> [right|double]"),
+    ("PLAN", Takes::Required, "PLAN: <goal>"),
+    ("MACRO", Takes::Required, "MACRO: <name> [param=value ...]"),
+    (
+        "BACKGROUND",
+        Takes::Required,
+        "BACKGROUND: RUN: <command> @ <folder> or BACKGROUND: <prompt>",
+    ),
+    ("RUN", Takes::Required, "RUN: <command> @ <folder>"),
+    ("GIT", Takes::Required, "GIT: <status|diff|log> <repo>"),
+    ("COMMIT", Takes::Required, "COMMIT: <repo> ||| <message>"),
+    (
+        "HA",
+        Takes::Required,
+        "HA: <service> <entity_id> [key=value ...]",
+    ),
+    (
+        "MEDIA",
+        Takes::Required,
+        "MEDIA: <play|pause|next|prev|volume <n>>",
+    ),
+    ("WAIT", Takes::Required, "WAIT: <ms>"),
+    (
+        "WAITFOR",
+        Takes::Required,
+        "WAITFOR: <window|text> <title or text> [<seconds>s]",
+    ),
+];
+
+// WAIT の上限（長い待ちは WAITFOR か SCHEDULE で）
+pub const WAIT_MAX_MS: u64 = 60_000;
+
+// WAITFOR の秒数（書かなければ既定）
+pub const WAITFOR_DEFAULT_SECS: u64 = 10;
+pub const WAITFOR_MAX_SECS: u64 = 120;
+
+// "10s" → 10。数字だけ / 数字で終わる名前は秒数にしない
+fn wait_secs(word: &str) -> Option<u64> {
+    let digits = word.strip_suffix('s')?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+// パーサが作り、src-tauri の実行 (adapter.rs) と確認ポリシー (policy.rs) がそのまま受け取る
+// フロントへは {"type": "KILL", "target": "chrome.exe"} の形（type は NAME と同じ）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Action {
+    Look,
+    // 既定 10
+    RecordScreen {
+        seconds: u64,
+    },
+    Apps,
+    // ブラウザ拡張から届いた今のタブ (src-tauri の connectors/browser.rs)
+    BrowserTab,
+    // 既定 "memory"
+    Processes {
+        sort_by: String,
+    },
+    Disk {
+        path: String,
+    },
+    Activity {
+        day: Option<String>,
+    },
+    Search {
+        query: String,
+    },
+    Weather {
+        place: String,
+    },
+    News {
+        topic: String,
+    },
+    // 既定 24
+    Calendar {
+        hours: i64,
+    },
+    // 既定 10
+    CheckEmail {
+        limit: usize,
+    },
+    Fetch {
+        url: String,
+    },
+    // path はデスクトップの中のファイル名 (files.rs)
+    Save {
+        path: String,
+        mode: Mode,
+        content: String,
+    },
+    // aspect は "1:1" / "16:9" / "9:16"（None は設定の既定）
+    ImageGen {
+        prompt: String,
+        aspect: Option<String>,
+    },
+    #[serde(rename = "GENERATE_FROM_TEMPLATE")]
+    Template {
+        name: String,
+        fields: String,
+    },
+    // message は "PROMPT: <prompt>" / "BRIEFING" も（src-tauri の scheduler.rs）
+    Schedule {
+        when: String,
+        message: String,
+    },
+    Exec {
+        app: String,
+    },
+    // EXEC: <name> で開くアプリを覚える（apps.rs。今の OS の settings.app_aliases に保存）
+    LearnAlias {
+        name: String,
+        target: String,
+    },
+    Type {
+        text: String,
+        target: Option<String>,
+    },
+    // KILL から REVEAL_IN_EXPLORER までは確認ポリシー (settings.confirm_actions) を通す
+    Kill {
+        target: String,
+    },
+    Focus {
+        window: String,
+    },
+    Minimize {
+        window: String,
+    },
+    OpenFile {
+        path: String,
+    },
+    #[serde(rename = "REVEAL_IN_EXPLORER")]
+    Reveal {
+        path: String,
+    },
+    // パーサは出さない（カレンダーの会議リンクを確認待ちに積む時だけ）
+    OpenUrl {
+        url: String,
+    },
+    Press {
+        key: String,
+    },
+    Click {
+        target: String,
+    },
+    UndoLast,
+    Plan {
+        goal: String,
+    },
+    Macro {
+        name: String,
+        params: BTreeMap<String, String>,
+    },
+    // kind: "run"（argument はコマンド、cwd はフォルダ）/ "ask"（argument はプロンプト）
+    Background {
+        kind: String,
+        argument: String,
+        cwd: Option<String>,
+    },
+    // cwd はフォルダかリポジトリの名前
+    Run {
+        command: String,
+        cwd: Option<String>,
+    },
+    // op: status / diff / log / repos（repos は repo なし）
+    Git {
+        op: String,
+        repo: String,
+    },
+    // repo が空なら既定のリポジトリ。確認待ちに積む時は解決したパス (src-tauri の connectors/git.rs)
+    Commit {
+        repo: String,
+        message: String,
+    },
+    // service が "states" なら一覧（entity_id は空）
+    #[serde(rename = "HA")]
+    HomeAssistant {
+        service: String,
+        entity_id: String,
+        data: BTreeMap<String, String>,
+    },
+    // action: play / pause / toggle / next / prev / volume / mute。volume は 0-100
+    Media {
+        action: String,
+        volume: Option<u32>,
+    },
+    Wait {
+        ms: u64,
+    },
+    // 条件まで待つ。kind: "window"（タイトルかプロセス名にその文字のウィンドウが出るまで）/ "text"（前面のウィンドウにその文字が出るまで）
+    WaitFor {
+        kind: String,
+        target: String,
+        timeout_secs: u64,
+    },
+    // プラグインのアクション。name は登録した NAME ("JIRA" など)
+    Plugin {
+        name: String,
+        argument: String,
+    },
+    // 書式の誤り。message は理由と正しい書式
+    Invalid {
+        message: String,
+    },
+    // アクションではない文（会話の返事など）
+    Unknown,
+}
+
+impl Action {
+    // NAME ("EXEC" / "SAVE" ...)。serde の type と同じ（Plugin だけは type "PLUGIN" で、これは登録した NAME）
+    pub fn name(&self) -> &str {
+        match self {
+            Action::Look => "LOOK",
+            Action::RecordScreen { .. } => "RECORD_SCREEN",
+            Action::Apps => "APPS",
+            Action::BrowserTab => "BROWSER_TAB",
+            Action::Processes { .. } => "PROCESSES",
+            Action::Disk { .. } => "DISK",
+            Action::Activity { .. } => "ACTIVITY",
+            Action::Search { .. } => "SEARCH",
+            Action::Weather { .. } => "WEATHER",
+            Action::News { .. } => "NEWS",
+            Action::Calendar { .. } => "CALENDAR",
+            Action::CheckEmail { .. } => "CHECK_EMAIL",
+            Action::Fetch { .. } => "FETCH",
+            Action::Save { .. } => "SAVE",
+            Action::ImageGen { .. } => "IMAGE_GEN",
+            Action::Template { .. } => "GENERATE_FROM_TEMPLATE",
+            Action::Schedule { .. } => "SCHEDULE",
+            Action::Exec { .. } => "EXEC",
+            Action::LearnAlias { .. } => "LEARN_ALIAS",
+            Action::Type { .. } => "TYPE",
+            Action::Kill { .. } => "KILL",
+            Action::Focus { .. } => "FOCUS",
+            Action::Minimize { .. } => "MINIMIZE",
+            Action::OpenFile { .. } => "OPEN_FILE",
+            Action::Reveal { .. } => "REVEAL_IN_EXPLORER",
+            Action::OpenUrl { .. } => "OPEN_URL",
+            Action::Press { .. } => "PRESS",
+            Action::Click { .. } => "CLICK",
+            Action::UndoLast => "UNDO_LAST",
+            Action::Plan { .. } => "PLAN",
+            Action::Macro { .. } => "MACRO",
+            Action::Background { .. } => "BACKGROUND",
+            Action::Run { .. } => "RUN",
+            Action::Git { .. } => "GIT",
+            Action::Commit { .. } => "COMMIT",
+            Action::HomeAssistant { .. } => "HA",
+            Action::Media { .. } => "MEDIA",
+            Action::Wait { .. } => "WAIT",
+            Action::WaitFor { .. } => "WAITFOR",
+            Action::Plugin { name, .. } => name,
+            Action::Invalid { .. } => "INVALID",
+            Action::Unknown => "UNKNOWN",
+        }
+    }
+
+    // 確認ダイアログ / 通知 / 監査記録に出す引数（SAVE の本文は出さない）
+    pub fn argument(&self) -> String {
+        match self {
+            Action::Look
+            | Action::Apps
+            | Action::BrowserTab
+            | Action::UndoLast
+            | Action::Unknown => String::new(),
+            Action::Activity { day } => day.clone().unwrap_or_default(),
+            Action::Calendar { hours } => hours.to_string(),
+            Action::RecordScreen { seconds } => seconds.to_string(),
+            Action::CheckEmail { limit } => limit.to_string(),
+            Action::Wait { ms } => ms.to_string(),
+            Action::WaitFor {
+                kind,
+                target,
+                timeout_secs,
+            } => format!("{} {} ({}s)", kind, target, timeout_secs),
+            Action::Type {
+                text,
+                target: Some(target),
+            } => format!("{} @ {}", text, target),
+            Action::LearnAlias { name, target } => format!("{} = {}", name, target),
+            Action::Save { path, mode, .. } => match mode {
+                Mode::Create => path.clone(),
+                _ => format!("{} [{}]", path, mode.keyword()),
+            },
+            Action::Schedule { when, message }
+            | Action::Commit {
+                repo: when,
+                message,
+            } => {
+                format!("{} ||| {}", when, message)
+            }
+            Action::Macro { name, params } => join_words(name, [], params),
+            Action::Background {
+                kind,
+                argument,
+                cwd,
+            } => {
+                let run = with_cwd(argument, cwd);
+                if kind == "run" {
+                    format!("RUN: {}", run)
+                } else {
+                    run
+                }
+            }
+            Action::Run { command, cwd } => with_cwd(command, cwd),
+            Action::Git { op, repo } => join_words(op, [repo.as_str()], &BTreeMap::new()),
+            Action::HomeAssistant {
+                service,
+                entity_id,
+                data,
+            } => join_words(service, [entity_id.as_str()], data),
+            Action::Media { action, volume } => match volume {
+                Some(v) => format!("{} {}", action, v),
+                None => action.clone(),
+            },
+            Action::Processes { sort_by: a }
+            | Action::Disk { path: a }
+            | Action::Search { query: a }
+            | Action::Weather { place: a }
+            | Action::News { topic: a }
+            | Action::Fetch { url: a }
+            | Action::ImageGen { prompt: a, .. }
+            | Action::Template { name: a, .. }
+            | Action::Exec { app: a }
+            | Action::Type { text: a, .. }
+            | Action::Kill { target: a }
+            | Action::Focus { window: a }
+            | Action::Minimize { window: a }
+            | Action::OpenFile { path: a }
+            | Action::Reveal { path: a }
+            | Action::OpenUrl { url: a }
+            | Action::Press { key: a }
+            | Action::Click { target: a }
+            | Action::Plan { goal: a }
+            | Action::Plugin { argument: a, .. }
+            | Action::Invalid { message: a } => a.clone(),
+        }
+    }
+}
+
+// "cargo test @ repo"（cwd が無ければ command だけ）
+fn with_cwd(command: &str, cwd: &Option<String>) -> String {
+    match cwd {
+        Some(cwd) => format!("{} @ {}", command, cwd),
+        None => command.to_string(),
+    }
+}
+
+// "morning city=Tokyo" / "turn_on light.living brightness_pct=40"（空の語は飛ばす）
+fn join_words<'a>(
+    head: &str,
+    words: impl IntoIterator<Item = &'a str>,
+    params: &BTreeMap<String, String>,
+) -> String {
+    std::iter::once(head.to_string())
+        .chain(words.into_iter().map(str::to_string))
+        .chain(params.iter().map(|(k, v)| format!("{}={}", k, v)))
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    pub raw: String,
+    pub action: Action,
+}
+
+// プラグインのアクションの NAME（読み込みの度に入れ替える）
+static PLUGIN_ACTIONS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+fn grammar(name: &str) -> Option<&'static (&'static str, Takes, &'static str)> {
+    GRAMMAR.iter().find(|(n, _, _)| *n == name)
+}
+
+// 組み込みのアクションの NAME か（プラグインはこれと重ねられない）
+pub fn is_builtin(name: &str) -> bool {
+    grammar(name).is_some()
+}
+
+pub fn set_plugin_actions(names: Vec<String>) {
+    if let Ok(mut actions) = PLUGIN_ACTIONS.write() {
+        *actions = names;
+    }
+}
+
+// 組み込み + プラグインのアクションの NAME（untrusted.rs が外の文面から潰す）
+pub fn action_names() -> Vec<String> {
+    let mut names: Vec<String> = GRAMMAR.iter().map(|(n, _, _)| n.to_string()).collect();
+    if let Ok(actions) = PLUGIN_ACTIONS.read() {
+        names.extend(actions.iter().cloned());
+    }
+    names
+}
+
+fn is_plugin_action(name: &str) -> bool {
+    !name.is_empty()
+        && PLUGIN_ACTIONS
+            .read()
+            .is_ok_and(|actions| actions.iter().any(|a| a == name))
+}
+
+// 引数の取り方（プラグインは "NAME: <argument>" だけ）
+fn takes(name: &str) -> Option<Takes> {
+    match grammar(name) {
+        Some(&(_, takes, _)) => Some(takes),
+        None if is_plugin_action(name) => Some(Takes::Required),
+        None => None,
+    }
+}
+
+// 出力にアクションの印が入っているか（NAME: / 引数なしで書けるものは NAME だけでも）
+pub fn has_actions(response: &str) -> bool {
+    let builtin = GRAMMAR.iter().any(|(name, takes, _)| match takes {
+        Takes::Required => response.contains(&format!("{}:", name)),
+        Takes::Colon => {
+            response.contains(&format!("{}:", name)) || response.lines().any(|l| l.trim() == *name)
+        }
+        _ => response.contains(name),
+    });
+    builtin
+        || PLUGIN_ACTIONS.read().is_ok_and(|actions| {
+            actions
+                .iter()
+                .any(|name| response.contains(&format!("{}:", name)))
+        })
+}
+
+pub fn parse_response(response: &str) -> Vec<Command> {
+    split_actions(response)
+        .iter()
+        .map(|c| c.trim())
+        .filter(|c| *c != "NO" && !c.is_empty())
+        .map(Command::parse)
+        .collect()
+}
+
+// 先頭の NAME（前の "EXECUTE " は落とす）と残り
+fn split_name(cmd: &str) -> (&str, &str) {
+    let leading = |s: &str| {
+        s.find(|c: char| !(c.is_ascii_uppercase() || c == '_'))
+            .unwrap_or(s.len())
+    };
+    let cmd = cmd
+        .strip_prefix("EXECUTE ")
+        .filter(|rest| takes(&rest[..leading(rest)]).is_some())
+        .unwrap_or(cmd);
+    cmd.split_at(leading(cmd))
+}
+
+// content の中の && の後ろが次のアクションか
+fn starts_action(rest: &str) -> bool {
+    let (name, after) = split_name(rest.trim_start());
+    let after_trimmed = after.trim_start();
+    match takes(name) {
+        Some(Takes::Required) => after.starts_with(':'),
+        Some(_) => {
+            after.starts_with(':') || after_trimmed.is_empty() || after_trimmed.starts_with("&&")
+        }
+        None => false,
+    }
+}
+
+// && で切る。引用の中と content の中（後ろが次のアクションでないもの）は切らない
+fn split_actions(response: &str) -> Vec<String> {
+    let mut actions = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut content = false;
+    let mut rest = response;
+    while let Some(c) = rest.chars().next() {
+        if let Some(r) = rest.strip_prefix("\\&&") {
+            current.push_str("&&");
+            rest = r;
+            continue;
+        }
+        if !content {
+            if quoted && (rest.starts_with("\\\"") || rest.starts_with("\\\\")) {
+                current.push_str(&rest[..2]);
+                rest = &rest[2..];
+                continue;
+            }
+            if c == '"' {
+                quoted = !quoted;
+            } else if !quoted && rest.starts_with("|||") {
+                content = true;
+                current.push_str("|||");
+                rest = &rest[3..];
+                continue;
+            }
+        }
+        if let Some(after) = rest.strip_prefix("&&") {
+            let spaced = current.chars().last().is_none_or(char::is_whitespace)
+                && after.chars().next().is_none_or(char::is_whitespace);
+            if spaced && !quoted && (!content || starts_action(after)) {
+                actions.push(std::mem::take(&mut current));
+                content = false;
+                rest = after;
+                continue;
+            }
+        }
+        current.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    actions.push(current);
+    actions
+}
+
+// 引用の外で最初 / 最後の sep の位置
+fn find_unquoted(s: &str, sep: &str, last: bool) -> Option<usize> {
+    let mut found = None;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ if !quoted && s[i..].starts_with(sep) => {
+                found = Some(i);
+                if !last {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+// 全体を "..." で囲んだものは中身に。一部だけ囲んだものはそのまま
+fn unquote(s: &str) -> Result<String, String> {
+    let s = s.trim();
+    let Some(inner) = s.strip_prefix('"') else {
+        return Ok(s.to_string());
+    };
+    let mut out = String::new();
+    let mut chars = inner.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if matches!(inner[i + 1..].chars().next(), Some('"' | '\\')) => {
+                if let Some((_, escaped)) = chars.next() {
+                    out.push(escaped);
+                }
+            }
+            '"' if inner[i + 1..].trim().is_empty() => return Ok(out),
+            '"' => return Ok(s.to_string()),
+            _ => out.push(c),
+        }
+    }
+    Err(format!("unterminated quote in {}", s))
+}
+
+fn number<T: std::str::FromStr>(arg: &str, default: T) -> Option<T> {
+    let arg = unquote(arg).ok()?;
+    if arg.is_empty() {
+        return Some(default);
+    }
+    arg.parse().ok()
+}
+
+// 空白で切る。引用の中では切らない（引用はそのまま残す）
+fn split_words(s: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if quoted => escaped = true,
+            '"' => {
+                quoted = !quoted;
+                start.get_or_insert(i);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if let Some(from) = start.take() {
+                    words.push(&s[from..i]);
+                }
+            }
+            _ => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+    if let Some(from) = start {
+        words.push(&s[from..]);
+    }
+    words
+}
+
+// key=value（value は "..." で囲んでもいい）。key=value でなければ None
+fn key_value(word: &str) -> Result<Option<(String, String)>, String> {
+    match word.split_once('=') {
+        Some((key, value)) if !key.is_empty() && !key.starts_with('"') => {
+            Ok(Some((key.to_string(), unquote(value)?)))
+        }
+        _ => Ok(None),
+    }
+}
+
+// RUN: / BACKGROUND: RUN: の "<command> @ <folder>"（UI から裏に回す RUN もこれで）
+pub fn run_target(arg: &str) -> Result<(String, Option<String>), String> {
+    let (command, cwd) = match find_unquoted(arg, " @ ", true) {
+        Some(i) => (unquote(&arg[..i])?, Some(unquote(&arg[i + 3..])?)),
+        None => (unquote(arg)?, None),
+    };
+    if command.is_empty() {
+        return Err("RUN needs a command".to_string());
+    }
+    Ok((command, cwd.filter(|d| !d.is_empty())))
+}
+
+// MEDIA: の "volume 30" / "vol 30%" / "toggle" などを (action, volume) に（UI のメディア操作もこれで）
+pub fn parse_media(command: &str) -> Result<(&'static str, Option<u32>), String> {
+    let mut words = command.split_whitespace();
+    let verb = words.next().unwrap_or("").to_lowercase();
+    let action = match verb.as_str() {
+        "play" | "resume" => "play",
+        "pause" | "stop" => "pause",
+        "toggle" | "playpause" => "toggle",
+        "next" | "skip" => "next",
+        "prev" | "previous" | "back" => "prev",
+        "volume" | "vol" => "volume",
+        "mute" => "mute",
+        _ => {
+            return Err(format!(
+                "unknown media command '{}': use play / pause / next / prev / volume <n>",
+                command.trim()
+            ))
+        }
+    };
+    let volume = if action == "volume" {
+        let n = words
+            .next()
+            .map(|w| w.trim_end_matches('%'))
+            .and_then(|w| w.parse::<u32>().ok())
+            .ok_or("MEDIA: volume needs a number (0-100)")?;
+        Some(n.min(100))
+    } else {
+        None
+    };
+    Ok((action, volume))
+}
+
+fn parse_action(cmd: &str) -> Result<Action, String> {
+    let (name, rest) = split_name(cmd);
+    if is_plugin_action(name) && grammar(name).is_none() {
+        let Some(arg) = rest.strip_prefix(':') else {
+            return Err(format!("{} needs ':' (use '{}: <argument>')", name, name));
+        };
+        return Ok(Action::Plugin {
+            name: name.to_string(),
+            argument: unquote(arg)?,
+        });
+    }
+    let Some(&(name, takes, usage)) = grammar(name) else {
+        // "FOO: ..." はアクションのつもり
+        if name.len() >= 2 && rest.starts_with(':') {
+            return Err(format!("unknown action '{}'", name));
+        }
+        return Ok(Action::Unknown);
+    };
+    let invalid = |why: String| Err(format!("{} (use '{}')", why, usage));
+    let arg = match rest.strip_prefix(':') {
+        Some(arg) => arg.trim(),
+        None if takes == Takes::Required => return invalid(format!("{} needs ':'", name)),
+        None if takes == Takes::Colon && !rest.trim().is_empty() => return Ok(Action::Unknown),
+        None if rest.is_empty() || rest.starts_with(char::is_whitespace) => rest.trim(),
+        // "LOOKUP" / "APPS2" のような別の語
+        None => return Ok(Action::Unknown),
+    };
+    if takes == Takes::Nothing && !arg.is_empty() {
+        return invalid(format!("{} takes no argument", name));
+    }
+    let text = unquote(arg)?;
+    if takes == Takes::Required && text.is_empty() {
+        return invalid(format!("{} needs an argument", name));
+    }
+
+    let action = match name {
+        "LOOK" => Action::Look,
+        "RECORD_SCREEN" => match number(arg, 10) {
+            Some(seconds) if seconds > 0 => Action::RecordScreen { seconds },
+            _ => return invalid("RECORD_SCREEN takes a number of seconds".to_string()),
+        },
+        "APPS" => Action::Apps,
+        "BROWSER_TAB" => Action::BrowserTab,
+        "UNDO_LAST" => Action::UndoLast,
+        "PROCESSES" => Action::Processes {
+            sort_by: if text.is_empty() {
+                "memory".to_string()
+            } else {
+                text
+            },
+        },
+        "DISK" => Action::Disk { path: text },
+        "ACTIVITY" => Action::Activity {
+            day: (!text.is_empty()).then_some(text),
+        },
+        "WEATHER" => Action::Weather { place: text },
+        "NEWS" => Action::News { topic: text },
+        "CALENDAR" => match number(arg, 24) {
+            Some(hours) => Action::Calendar { hours },
+            None => return invalid("CALENDAR takes a number of hours".to_string()),
+        },
+        "CHECK_EMAIL" => match number(arg, 10) {
+            Some(limit) => Action::CheckEmail { limit },
+            None => return invalid("CHECK_EMAIL takes a number of messages".to_string()),
+        },
+        "SEARCH" => Action::Search { query: text },
+        "FETCH" if text.starts_with("http://") || text.starts_with("https://") => {
+            Action::Fetch { url: text }
+        }
+        "FETCH" => return invalid(format!("'{}' is not an http(s) URL", text)),
+        "IMAGE_GEN" => {
+            let (prompt, aspect) = match find_unquoted(arg, "|||", false) {
+                Some(at) => (unquote(&arg[..at])?, Some(arg[at + 3..].trim().to_string())),
+                None => (text, None),
+            };
+            if prompt.is_empty() {
+                return invalid("IMAGE_GEN needs a prompt".to_string());
+            }
+            if let Some(a) = aspect.as_deref().filter(|a| !ASPECTS.contains(a)) {
+                return invalid(format!(
+                    "'{}' is not an aspect ratio (1:1 / 16:9 / 9:16)",
+                    a
+                ));
+            }
+            Action::ImageGen { prompt, aspect }
+        }
+        "SAVE" | "GENERATE_FROM_TEMPLATE" => {
+            let Some(at) = find_unquoted(arg, "|||", false) else {
+                return invalid(format!("{} is missing '|||'", name));
+            };
+            let spec = unquote(&arg[..at])?;
+            let content = arg[at + 3..].trim().to_string();
+            if spec.is_empty() {
+                return invalid(format!("{} is missing the name before '|||'", name));
+            }
+            if name == "SAVE" {
+                match files::parse_target(&spec) {
+                    Ok((path, mode)) => Action::Save {
+                        path,
+                        mode,
+                        content,
+                    },
+                    Err(e) => return invalid(e),
+                }
+            } else {
+                Action::Template {
+                    name: spec,
+                    fields: content,
+                }
+            }
+        }
+        "SCHEDULE" | "COMMIT" => {
+            let Some(at) = find_unquoted(arg, "|||", false) else {
+                return invalid(format!("{} is missing '|||'", name));
+            };
+            let (head, message) = (unquote(&arg[..at])?, arg[at + 3..].trim().to_string());
+            if message.is_empty() {
+                return invalid(format!("{} needs a message after '|||'", name));
+            }
+            if name == "COMMIT" {
+                Action::Commit {
+                    repo: head,
+                    message,
+                }
+            } else if head.is_empty() {
+                return invalid("SCHEDULE is missing the time before '|||'".to_string());
+            } else {
+                Action::Schedule {
+                    when: head,
+                    message,
+                }
+            }
+        }
+        "EXEC" => Action::Exec { app: text },
+        "LEARN_ALIAS" => {
+            let Some(i) = find_unquoted(arg, "=", false) else {
+                return invalid("LEARN_ALIAS needs '<name> = <app>'".to_string());
+            };
+            let (name, target) = (unquote(&arg[..i])?, unquote(&arg[i + 1..])?);
+            match apps::check_learned(&name, &target) {
+                Ok((name, target)) => Action::LearnAlias { name, target },
+                Err(e) => return invalid(format!("LEARN_ALIAS: {}", e)),
+            }
+        }
+        "TYPE" => {
+            // 最後の " @" の後ろが宛先（メールアドレスの @ では切らない）
+            let at = find_unquoted(arg, "@", true).filter(|&i| i == 0 || arg[..i].ends_with(' '));
+            let (text, target) = match at {
+                Some(i) => (unquote(&arg[..i])?, Some(unquote(&arg[i + 1..])?)),
+                None => (text, None),
+            };
+            if text.is_empty() {
+                return invalid("TYPE needs some text".to_string());
+            }
+            Action::Type {
+                text,
+                target: target.filter(|t| !t.is_empty()),
+            }
+        }
+        "KILL" => Action::Kill { target: text },
+        "FOCUS" => Action::Focus { window: text },
+        "MINIMIZE" => Action::Minimize { window: text },
+        "OPEN_FILE" => Action::OpenFile { path: text },
+        "REVEAL_IN_EXPLORER" => Action::Reveal { path: text },
+        "PRESS" => match keys::parse(&text) {
+            Ok(_) => Action::Press { key: text },
+            Err(e) => return invalid(format!("PRESS: {}", e)),
+        },
+        "CLICK" => Action::Click { target: text },
+        "PLAN" => Action::Plan { goal: text },
+        "MACRO" => {
+            // 名前に空白があってもいいように、key=value の前までを名前にする
+            let mut name_words = Vec::new();
+            let mut params = BTreeMap::new();
+            for word in split_words(arg) {
+                match key_value(word)? {
+                    Some((key, value)) => {
+                        params.insert(key, value);
+                    }
+                    None if params.is_empty() => name_words.push(unquote(word)?),
+                    None => return invalid(format!("MACRO parameter '{}' is not key=value", word)),
+                }
+            }
+            if name_words.is_empty() {
+                return invalid("MACRO needs a name".to_string());
+            }
+            Action::Macro {
+                name: name_words.join(" "),
+                params,
+            }
+        }
+        "BACKGROUND" => match arg.strip_prefix("RUN:") {
+            Some(run) => match run_target(run.trim()) {
+                Ok((command, cwd)) => Action::Background {
+                    kind: "run".to_string(),
+                    argument: command,
+                    cwd,
+                },
+                Err(e) => return invalid(format!("BACKGROUND: {}", e)),
+            },
+            None => Action::Background {
+                kind: "ask".to_string(),
+                argument: text,
+                cwd: None,
+            },
+        },
+        // RUN: "make && make install" @ repo
+        "RUN" => match run_target(arg) {
+            Ok((command, cwd)) => Action::Run { command, cwd },
+            Err(e) => return invalid(e),
+        },
+        "GIT" => {
+            let words = split_words(arg);
+            let op = words.first().map(|w| w.to_lowercase()).unwrap_or_default();
+            if !GIT_OPS.contains(&op.as_str()) {
+                return invalid(format!(
+                    "unknown GIT command '{}': use status / diff / log / repos",
+                    op
+                ));
+            }
+            let repo = match find_unquoted(arg, " ", false) {
+                Some(i) if op != "repos" => unquote(&arg[i..])?,
+                _ => String::new(),
+            };
+            Action::Git { op, repo }
+        }
+        "HA" => {
+            let words = split_words(arg);
+            let service = words.first().copied().unwrap_or("");
+            if service.eq_ignore_ascii_case("states") {
+                Action::HomeAssistant {
+                    service: "states".to_string(),
+                    entity_id: String::new(),
+                    data: BTreeMap::new(),
+                }
+            } else {
+                let Some(entity_id) = words.get(1) else {
+                    return invalid("HA needs <service> <entity_id>".to_string());
+                };
+                let mut data = BTreeMap::new();
+                for word in &words[2..] {
+                    match key_value(word)? {
+                        Some((key, value)) => {
+                            data.insert(key, value);
+                        }
+                        None => return invalid(format!("HA data '{}' is not key=value", word)),
+                    }
+                }
+                Action::HomeAssistant {
+                    service: service.to_string(),
+                    entity_id: unquote(entity_id)?,
+                    data,
+                }
+            }
+        }
+        "MEDIA" => match parse_media(&text) {
+            Ok((action, volume)) => Action::Media {
+                action: action.to_string(),
+                volume,
+            },
+            Err(e) => return invalid(e),
+        },
+        "WAIT" => match text.parse() {
+            Ok(ms) if ms <= WAIT_MAX_MS => Action::Wait { ms },
+            Ok(_) => return invalid(format!("WAIT waits at most {} ms", WAIT_MAX_MS)),
+            Err(_) => return invalid("WAIT takes milliseconds".to_string()),
+        },
+        "WAITFOR" => {
+            let (kind, rest) = arg.split_once(char::is_whitespace).unwrap_or((arg, ""));
+            let kind = kind.to_lowercase();
+            if !matches!(kind.as_str(), "window" | "text") {
+                return invalid(format!(
+                    "WAITFOR waits for a window or text, not '{}'",
+                    kind
+                ));
+            }
+            // 最後の語が "10s" の形の時だけ timeout（"Windows 11" や "Chapter 3" は名前のまま）
+            let rest = rest.trim();
+            let (target, timeout_secs) = match rest
+                .rsplit_once(char::is_whitespace)
+                .and_then(|(target, last)| Some((target, wait_secs(last)?)))
+            {
+                Some((target, secs)) => (target.trim(), secs),
+                None => (rest, WAITFOR_DEFAULT_SECS),
+            };
+            let target = unquote(target)?;
+            if target.is_empty() {
+                return invalid(format!("WAITFOR {} needs something to wait for", kind));
+            }
+            if !(1..=WAITFOR_MAX_SECS).contains(&timeout_secs) {
+                return invalid(format!("WAITFOR waits 1-{} seconds", WAITFOR_MAX_SECS));
+            }
+            Action::WaitFor {
+                kind,
+                target,
+                timeout_secs,
+            }
+        }
+        _ => Action::Unknown,
+    };
+    Ok(action)
+}
+
+impl Command {
+    pub fn parse(cmd: &str) -> Command {
+        let cmd = cmd.trim();
+        Command {
+            raw: cmd.to_string(),
+            action: parse_action(cmd).unwrap_or_else(|message| Action::Invalid { message }),
+        }
+    }
+
+    // 先頭の NAME ("EXEC" / "SAVE" / "LOOK" ...)。監査記録 / 確認待ちの表示用
+    pub fn name(&self) -> &str {
+        split_name(&self.raw).0
+    }
+
+    // NAME の後ろ（":" は落とす）
+    pub fn argument(&self) -> &str {
+        split_name(&self.raw).1.trim_start_matches(':').trim()
+    }
+
+    // trace / ResponseMeta.actions 用の短い表記（SAVE の本文は落とす）
+    pub fn label(&self) -> String {
+        if let Some((head, _)) = self.raw.split_once("|||") {
+            return head.trim().to_string();
+        }
+        if self.raw.chars().count() > 120 {
+            return self.raw.chars().take(120).collect::<String>() + "…";
+        }
+        self.raw.clone()
+    }
+
+    // 外の文面を持ち込むアクションなら、その source (untrusted::wrap の印に出す)
+    pub fn untrusted_source(&self) -> Option<&'static str> {
+        match self.action {
+            Action::Search { .. } => Some("search"),
+            Action::Fetch { .. } => Some("web_page"),
+            Action::BrowserTab => Some("browser_tab"),
+            Action::News { .. } => Some("news"),
+            Action::CheckEmail { .. } => Some("email"),
+            Action::Calendar { .. } => Some("calendar"),
+            // 画面に映っている文面（開いているメールやページ、チャットも読む）
+            Action::Look => Some("screen"),
+            Action::RecordScreen { .. } => Some("screen_recording"),
+            Action::WaitFor { ref kind, .. } if kind == "text" => Some("screen_text"),
+            // サードパーティのプログラムの出力
+            Action::Plugin { .. } => Some("plugin"),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// src-tauri/crates/axis-core/src/context.rs
+//
+// Worker / Commander に渡す文脈の組み立て
+// - 直近の履歴: 同じセッションの最後の HISTORY_TURNS 往復を "User: / Axis:" で（無ければ "None"）
+// - recall: 過去の会話の FTS の当たりから、直近の履歴に既に入っているものを省いて
+//   外の文面を読んだ答え（囲んだまま保存してある）は囲みを保ったまま切る (untrusted::clip)
+// - task_input: 履歴 + メモリ + recall + 依頼文
+
+use crate::untrusted;
+
+// 文脈に入れる直近の往復
+pub const HISTORY_TURNS: usize = 5;
+// recall 1 件あたりの長さ
+const RECALL_CHARS: usize = 300;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Turn {
+    pub user: String,
+    pub axis: String,
+}
+
+// turns は古い順
+pub fn history_text(turns: &[Turn]) -> String {
+    let recent = &turns[turns.len().saturating_sub(HISTORY_TURNS)..];
+    if recent.is_empty() {
+        return "None".to_string();
+    }
+    recent
+        .iter()
+        .map(|t| format!("User: {}\nAxis: {}", t.user, t.axis))
+        .collect::<Vec<_>>()
+        .join("\n---\n")
+}
+
+pub fn recall_section(recall: &[String], history_text: &str) -> String {
+    let lines: String = recall
+        .iter()
+        .filter(|c| !history_text.contains(c.as_str()))
+        .map(|c| format!("- {}\n", untrusted::clip(c, RECALL_CHARS)))
+        .collect();
+    if lines.is_empty() {
+        String::new()
+    } else {
+        format!("[Recall: related past messages]\n{}", lines)
+    }
+}
+
+pub fn task_input(history_text: &str, memory: &str, recall: &str, input: &str) -> String {
+    format!(
+        "Context:\n{}\n{}\n{}\n\nUser Request: {}",
+        history_text, memory, recall, input
+    )
+}
@@ -0,0 +1,88 @@
+// src-tauri/crates/axis-core/src/dispatch.rs
+//
+// Commander (Llama) の振り分け結果
+// - 返事の中の { ... } を RoutingDecision として読む。読めなければ gpt に（strategy "fallback"）
+// - reroute: 選ばれたプロバイダに届かなければ local、タイムアウト続き / 予算切れなら別のクラウドか local へ
+//   (到達性 / 健康状態は Availability で外から渡す。src-tauri は system::check_network / health / budget)
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+// 使えない時の振り替え先の候補（この順に）
+const CLOUD: &[&str] = &["gpt", "gemini", "grok"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoutingDecision {
+    pub target: String,
+
+    #[serde(default = "default_strategy")]
+    pub strategy: String,
+
+    #[serde(default = "default_reason")]
+    pub reason: String,
+
+    #[serde(default)]
+    pub task_type: String, // JSONに無ければ "": 空文字
+}
+
+pub fn default_strategy() -> String {
+    "general".to_string()
+}
+
+fn default_reason() -> String {
+    "Default decision".to_string()
+}
+
+pub trait Availability {
+    fn reachable(&self, provider: &str) -> bool;
+    // 使えないなら (strategy, 理由)。strategy は "health" / "budget"
+    fn unavailable(&self, provider: &str) -> Option<(&'static str, String)>;
+}
+
+pub fn parse_decision(raw: &str) -> RoutingDecision {
+    let raw = raw.trim();
+    let json = match (raw.find('{'), raw.rfind('}')) {
+        (Some(start), Some(end)) if start <= end => &raw[start..=end],
+        _ => raw,
+    };
+    serde_json::from_str(json).unwrap_or(RoutingDecision {
+        target: "gpt".to_string(),
+        strategy: "fallback".to_string(),
+        reason: "JSON Parse Failed".to_string(),
+        task_type: "unknown".to_string(),
+    })
+}
+
+// requested: 答え直しでプロバイダが指定されている（使えなくてもそのまま）
+pub fn reroute(decision: &mut RoutingDecision, availability: &impl Availability, requested: bool) {
+    // 選ばれたプロバイダだけ落ちている場合もローカルへ
+    if decision.target != "local" && !availability.reachable(&decision.target) {
+        info!(
+            "📡 [Network] {} unreachable, routing to local LLM",
+            decision.target
+        );
+        decision.target = "local".to_string();
+        decision.strategy = "offline".to_string();
+    }
+
+    if requested || matches!(decision.target.as_str(), "local" | "ensemble") {
+        return;
+    }
+    if let Some((strategy, why)) = availability.unavailable(&decision.target) {
+        let alternative = CLOUD
+            .iter()
+            .copied()
+            .find(|p| {
+                *p != decision.target
+                    && availability.reachable(p)
+                    && availability.unavailable(p).is_none()
+            })
+            .unwrap_or("local");
+        info!(
+            "🩺 [Routing] {}: {}, routing to {}",
+            decision.target, why, alternative
+        );
+        decision.target = alternative.to_string();
+        decision.strategy = strategy.to_string();
+    }
+}
@@ -0,0 +1,65 @@
+// src-tauri/crates/axis-core/src/engine.rs
+//
+// Worker が出したアクションを順に実行する流れ（実際の操作は ActionExecutor に任せる）
+// - 出力は context に積む（最終レポートの材料 = src-tauri の system_context）
+// - 外の文面 (Command::untrusted_source) の出力は untrusted::seal で囲み、untrusted_seen に
+//   同じ返事の後ろの操作は読む前に書かれたものなので止めない
+// - tainted（このセッションの履歴 / メモリに外の文面が入っている。呼ぶ側が untrusted::contains で決める）なら
+//   操作と外への送信は policy::after_untrusted に従って hold に回す
+// - finished は実行した後 / hold した後に毎回（trace / 監査記録用）
+
+use crate::command::{self, Command};
+use crate::policy::{self, Hold};
+use crate::untrusted;
+use std::future::Future;
+
+pub trait ActionExecutor {
+    // 出力は context の末尾に足す
+    fn execute(&mut self, cmd: &Command, context: &mut String) -> impl Future<Output = ()> + Send;
+
+    // 実行せずに確認待ちに積む / 止める。context に足す文面を返す
+    fn hold(&mut self, cmd: &Command, hold: Hold) -> String;
+
+    // output はこのアクション 1 つ分の context。executed = false は hold したもの
+    fn finished(&mut self, _cmd: &Command, _output: &str, _executed: bool) {}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ActionRun {
+    pub context: String,
+    pub executed: Vec<String>, // Command::label（ResponseMeta.actions）
+    pub held: Vec<String>,
+    pub untrusted_seen: bool,
+}
+
+// アクションが無ければ None
+pub async fn run_actions<E: ActionExecutor + Send>(
+    executor: &mut E,
+    response: &str,
+    tainted: bool,
+) -> Option<ActionRun> {
+    if !command::has_actions(response) {
+        return None;
+    }
+    let mut run = ActionRun::default();
+    for cmd in command::parse_response(response) {
+        let before = run.context.len();
+        if tainted {
+            if let Some(hold) = policy::after_untrusted(&cmd) {
+                let note = executor.hold(&cmd, hold);
+                run.context.push_str(&note);
+                run.held.push(cmd.label());
+                executor.finished(&cmd, &run.context[before..], false);
+                continue;
+            }
+        }
+        run.executed.push(cmd.label());
+        executor.execute(&cmd, &mut run.context).await;
+        if let Some(source) = cmd.untrusted_source() {
+            untrusted::seal(&mut run.context, before, source);
+            run.untrusted_seen = true;
+        }
+        executor.finished(&cmd, &run.context[before..], true);
+    }
+    Some(run)
+}
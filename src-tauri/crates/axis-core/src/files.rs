@@ -0,0 +1,255 @@
+// src-tauri/crates/axis-core/src/files.rs
+//
+// SAVE: <filename> [mode] ||| <content> で書く中身を決める（書くのは src-tauri の undo::write_file）
+// - mode なし: 新規作成のみ。既にあれば書かずにエラー（どうするかユーザーに聞かせる文面）
+// - [overwrite] / [append] / [new]（"name (2).ext"）/ [patch]（unified diff を文脈で探して当てる）
+// - xlsx / pdf のように中身を組み立てるものは plan_write の build に任せる
+// - 読むのは FileSystem 越し（テストは MemoryFs）
+
+use crate::fs::FileSystem;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// Action::Save の mode ("overwrite" など)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    Create,
+    Overwrite,
+    Append,
+    New,
+    Patch,
+}
+
+impl Mode {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_lowercase().as_str() {
+            "" | "create" => Ok(Mode::Create),
+            "overwrite" | "replace" => Ok(Mode::Overwrite),
+            "append" => Ok(Mode::Append),
+            "new" | "copy" => Ok(Mode::New),
+            "patch" | "diff" => Ok(Mode::Patch),
+            other => Err(format!(
+                "unknown SAVE mode '{}': use overwrite / append / new / patch",
+                other
+            )),
+        }
+    }
+
+    // SAVE: <filename> [mode] に書く語
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Mode::Create => "create",
+            Mode::Overwrite => "overwrite",
+            Mode::Append => "append",
+            Mode::New => "new",
+            Mode::Patch => "patch",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Mode::Create => "created",
+            Mode::Overwrite => "overwritten",
+            Mode::Append => "appended",
+            Mode::New => "created as a new file",
+            Mode::Patch => "patched",
+        }
+    }
+}
+
+// 書くのはデスクトップの中だけ（絶対パスや .. で RUN のフォルダや別の場所に置かせない）
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("SAVE needs a filename".to_string());
+    }
+    let path = Path::new(name);
+    if path.has_root()
+        || name.contains(':')
+        || path
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(format!(
+            "'{}' is outside the Desktop: SAVE takes a file name (or a folder under the Desktop)",
+            name
+        ));
+    }
+    Ok(())
+}
+
+// "report.md [append]" → ("report.md", Append)
+pub fn parse_target(spec: &str) -> Result<(String, Mode), String> {
+    let spec = spec.trim();
+    let (name, mode) = match spec.strip_suffix(']').and_then(|s| s.rsplit_once('[')) {
+        Some((name, mode)) => (name.trim(), Mode::parse(mode)?),
+        None => (spec, Mode::Create),
+    };
+    check_name(name)?;
+    Ok((name.to_string(), mode))
+}
+
+// "memo.txt" → "memo (2).txt"（空いている番号まで）
+pub fn unused_name(fs: &impl FileSystem, path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    (2..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+        .find(|p| !fs.is_file(p))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+struct Hunk {
+    old_start: usize, // 1 始まり（0 = 不明）
+    old: Vec<String>,
+    new: Vec<String>,
+}
+
+fn parse_hunks(patch: &str) -> Result<Vec<Hunk>, String> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    for line in patch.lines() {
+        if let Some(rest) = line.strip_prefix("@@") {
+            // "@@ -12,5 +12,6 @@"
+            let old_start = rest
+                .split_whitespace()
+                .find_map(|w| w.strip_prefix('-'))
+                .and_then(|w| w.split(',').next())
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(0);
+            hunks.push(Hunk {
+                old_start,
+                old: Vec::new(),
+                new: Vec::new(),
+            });
+            continue;
+        }
+        let Some(h) = hunks.last_mut() else {
+            // --- / +++ / diff / index の見出し
+            continue;
+        };
+        if line.starts_with("---") || line.starts_with("+++") || line.starts_with('\\') {
+            continue;
+        }
+        if let Some(l) = line.strip_prefix('+') {
+            h.new.push(l.to_string());
+        } else if let Some(l) = line.strip_prefix('-') {
+            h.old.push(l.to_string());
+        } else {
+            // 文脈行（先頭の空白が落ちた空行もここ）
+            let l = line.strip_prefix(' ').unwrap_or(line).to_string();
+            h.old.push(l.clone());
+            h.new.push(l);
+        }
+    }
+    if hunks.is_empty() {
+        return Err("patch has no hunks (use a unified diff with @@ lines)".to_string());
+    }
+    Ok(hunks)
+}
+
+// 行番号の近くから探して、見つかった所を差し替える（行末の空白は無視）
+pub fn apply_patch(original: &str, patch: &str) -> Result<String, String> {
+    let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+    let mut delta: isize = 0;
+    for (i, h) in parse_hunks(patch)?.iter().enumerate() {
+        let hint = (h.old_start.saturating_sub(1) as isize + delta).max(0) as usize;
+        let at = if h.old.is_empty() {
+            hint.min(lines.len())
+        } else {
+            let same = |pos: usize| {
+                h.old
+                    .iter()
+                    .zip(&lines[pos..])
+                    .all(|(a, b)| a.trim_end() == b.trim_end())
+            };
+            (0..=lines.len().saturating_sub(h.old.len()))
+                .filter(|&pos| pos + h.old.len() <= lines.len() && same(pos))
+                .min_by_key(|&pos| pos.abs_diff(hint))
+                .ok_or_else(|| {
+                    format!(
+                        "hunk {} does not match the file (starts with '{}')",
+                        i + 1,
+                        h.old.first().map(String::as_str).unwrap_or("")
+                    )
+                })?
+        };
+        lines.splice(at..at + h.old.len(), h.new.iter().cloned());
+        delta += h.new.len() as isize - h.old.len() as isize;
+    }
+    // Windows で作ったファイルは CRLF のまま
+    let eol = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut out = lines.join(eol);
+    if original.ends_with('\n') || original.is_empty() {
+        out.push_str(eol);
+    }
+    Ok(out)
+}
+
+// (書き込み先, 書く中身)。build は Create / Overwrite / New の中身を作る（テキストならそのまま）
+pub fn plan_write(
+    fs: &impl FileSystem,
+    path: &Path,
+    name: &str,
+    mode: Mode,
+    content: &str,
+    build: impl FnOnce(&str) -> Result<Vec<u8>, String>,
+) -> Result<(PathBuf, Vec<u8>), String> {
+    let exists = fs.is_file(path);
+    let mut path = path.to_path_buf();
+    let bytes = match mode {
+        Mode::Create if exists => {
+            return Err(format!(
+                "{} already exists ({} bytes). Nothing was written. Ask the user whether to overwrite it, append to it, save a new copy, or patch it (SAVE: {} [overwrite|append|new|patch] ||| ...)",
+                path.display(),
+                fs.size(&path),
+                name
+            ));
+        }
+        Mode::Create | Mode::Overwrite => build(content)?,
+        Mode::New => {
+            if exists {
+                path = unused_name(fs, &path);
+            }
+            build(content)?
+        }
+        Mode::Append => {
+            let mut current = if exists {
+                fs.read(&path).map_err(|e| e.to_string())?
+            } else {
+                Vec::new()
+            };
+            if !current.is_empty() && !current.ends_with(b"\n") {
+                current.push(b'\n');
+            }
+            current.extend_from_slice(content.as_bytes());
+            current.push(b'\n');
+            current
+        }
+        Mode::Patch => {
+            if !exists {
+                return Err(format!(
+                    "{} does not exist; patch mode only edits existing files",
+                    path.display()
+                ));
+            }
+            let current = fs
+                .read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|b| String::from_utf8(b).map_err(|e| e.to_string()))
+                .map_err(|e| format!("{} is not a text file: {}", path.display(), e))?;
+            apply_patch(&current, content)?.into_bytes()
+        }
+    };
+    Ok((path, bytes))
+}
@@ -0,0 +1,76 @@
+// src-tauri/crates/axis-core/src/fs.rs
+//
+// ファイルシステムの trait（SAVE の書き込み内容を決める files.rs が読む側だけ使う）
+// - StdFs: 本物のディスク（src-tauri の filegen.rs / templates.rs）
+// - MemoryFs: テスト用のメモリ上のファイル
+// 書き込み自体は src-tauri の undo::write_file（ジャーナルとバックアップが要るので）
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub trait FileSystem {
+    fn is_file(&self, path: &Path) -> bool;
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    fn size(&self, path: &Path) -> u64;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdFs;
+
+impl FileSystem for StdFs {
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn size(&self, path: &Path) -> u64 {
+        path.metadata().map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryFs {
+    files: Mutex<BTreeMap<PathBuf, Vec<u8>>>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, path: impl Into<PathBuf>, bytes: impl Into<Vec<u8>>) {
+        if let Ok(mut files) = self.files.lock() {
+            files.insert(path.into(), bytes.into());
+        }
+    }
+}
+
+impl FileSystem for MemoryFs {
+    fn is_file(&self, path: &Path) -> bool {
+        self.files
+            .lock()
+            .map(|f| f.contains_key(path))
+            .unwrap_or(false)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files
+            .lock()
+            .ok()
+            .and_then(|f| f.get(path).cloned())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, path.display().to_string()))
+    }
+
+    fn size(&self, path: &Path) -> u64 {
+        self.files
+            .lock()
+            .ok()
+            .and_then(|f| f.get(path).map(|b| b.len() as u64))
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,154 @@
+// src-tauri/crates/axis-core/src/keys.rs
+//
+// PRESS: の書式。"enter" / "ctrl+shift+s" / "f5" / "alt+left" / "tab x3" / "ctrl+k, ctrl+c"
+// - "," で区切った順に押す。1 手は "+" でつないだ修飾キーとキー 1 つ。後ろの " xN" で N 回 (1〜MAX_REPEAT)
+// - 修飾キーだけの手 ("win" / "alt+shift") は最後の 1 つをキーとして押す
+// - "+" / "," そのものは "plus" / "comma"。キー名の大文字小文字は区別しない
+// - 実際のキー送信は src-tauri の shell::press_key (enigo)。Worker のアクションは command.rs で先に書式を確かめる
+
+pub const MAX_REPEAT: u32 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Ctrl,
+    Alt,
+    Shift,
+    Meta, // Windows キー
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char), // 文字のキー（英字は小文字）
+    F(u8),      // F1〜F24
+    Enter,
+    Tab,
+    Space,
+    Backspace,
+    Delete,
+    Insert,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    CapsLock,
+    PrintScreen,
+    Modifier(Modifier),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Press {
+    pub modifiers: Vec<Modifier>,
+    pub key: Key,
+    pub times: u32,
+}
+
+fn modifier(name: &str) -> Option<Modifier> {
+    match name {
+        "ctrl" | "control" => Some(Modifier::Ctrl),
+        "alt" | "option" => Some(Modifier::Alt),
+        "shift" => Some(Modifier::Shift),
+        "win" | "windows" | "super" | "meta" | "cmd" => Some(Modifier::Meta),
+        _ => None,
+    }
+}
+
+fn key(name: &str) -> Option<Key> {
+    let key = match name {
+        "enter" | "return" => Key::Enter,
+        "tab" => Key::Tab,
+        "space" => Key::Space,
+        "backspace" | "bksp" => Key::Backspace,
+        "delete" | "del" => Key::Delete,
+        "insert" | "ins" => Key::Insert,
+        "esc" | "escape" => Key::Escape,
+        "up" | "arrowup" | "uparrow" => Key::Up,
+        "down" | "arrowdown" | "downarrow" => Key::Down,
+        "left" | "arrowleft" | "leftarrow" => Key::Left,
+        "right" | "arrowright" | "rightarrow" => Key::Right,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" | "pgup" => Key::PageUp,
+        "pagedown" | "pgdn" => Key::PageDown,
+        "capslock" => Key::CapsLock,
+        "printscreen" | "prtsc" | "print" => Key::PrintScreen,
+        "plus" => Key::Char('+'),
+        "comma" => Key::Char(','),
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Key::Char(c),
+                (Some('f'), Some(_)) => {
+                    let n: u8 = name[1..].parse().ok()?;
+                    if !(1..=24).contains(&n) {
+                        return None;
+                    }
+                    Key::F(n)
+                }
+                _ => return None,
+            }
+        }
+    };
+    Some(key)
+}
+
+// "tab x3" → ("tab", 3)
+fn repeat(step: &str) -> Result<(&str, u32), String> {
+    let Some((chord, last)) = step.rsplit_once(char::is_whitespace) else {
+        return Ok((step, 1));
+    };
+    let Some(n) = last
+        .strip_prefix(['x', 'X', '×'])
+        .filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    else {
+        return Ok((step, 1));
+    };
+    let times: u32 = n.parse().unwrap_or(0);
+    if !(1..=MAX_REPEAT).contains(&times) {
+        return Err(format!("repeat '{}' must be x1〜x{}", last, MAX_REPEAT));
+    }
+    Ok((chord.trim_end(), times))
+}
+
+fn chord(step: &str) -> Result<Press, String> {
+    let (chord, times) = repeat(step.trim())?;
+    let mut modifiers = Vec::new();
+    let mut found = None;
+    for part in chord.split('+').map(str::trim) {
+        if part.is_empty() {
+            return Err(format!("'{}' has an empty key (use 'plus' for +)", chord));
+        }
+        let lower = part.to_lowercase();
+        if let Some(m) = modifier(&lower) {
+            if !modifiers.contains(&m) {
+                modifiers.push(m);
+            }
+            continue;
+        }
+        if found.is_some() {
+            return Err(format!("'{}' has more than one key", chord));
+        }
+        found = Some(key(&lower).ok_or_else(|| format!("unknown key '{}'", part))?);
+    }
+    let key = match found {
+        Some(k) => k,
+        None => Key::Modifier(modifiers.pop().ok_or("no key")?),
+    };
+    Ok(Press {
+        modifiers,
+        key,
+        times,
+    })
+}
+
+pub fn parse(spec: &str) -> Result<Vec<Press>, String> {
+    let steps: Vec<&str> = spec.split(',').map(str::trim).collect();
+    if steps.iter().any(|s| s.is_empty()) {
+        return Err(format!("'{}' has an empty step", spec.trim()));
+    }
+    steps.into_iter().map(chord).collect()
+}
@@ -0,0 +1,33 @@
+// src-tauri/crates/axis-core/src/lib.rs
+//
+// Axis のオーケストレーションの中身（Tauri / AppHandle に依存しない部分）
+// - dispatch: Commander の返事の解釈と、届かない / 使えないプロバイダからの振り替え
+// - context: Worker に渡す文脈（直近の履歴 / メモリ / recall）の組み立て
+// - command: Worker の出力 "SEARCH: x && EXEC: y" をアクションの一覧に
+// - engine: アクションの実行の流れ（ActionExecutor に任せる。外の文面を読んだ後は保留）
+// - provider: モデル呼び出しの trait (ModelProvider)。Worker のフェイルオーバーと最終レポート
+// - untrusted / policy: 外から来た文面の無害化と、その後の操作の扱い
+// - orchestrator: 依頼 1 回分の流れ（文脈 → 振り分け → Worker → アクション → レポート）。Tauri 側は Host trait
+// - trace: その記録（振り分け / Worker の出力 / アクション毎の結果 / フェーズ毎の所要時間）
+// - replay: 記録した返事 / アクション結果だけで全体 (振り分け → アクション → レポート) を通す
+// - apps: EXEC のアプリ名の解決（利用者の別名 / LEARN_ALIAS）
+// - keys: PRESS: のキーの書式（修飾キーの組み合わせ / ファンクションキー / 矢印 / 繰り返し）
+// - fs / files: ファイルシステムの trait と SAVE の書き込み内容の決定
+// - vision: 画像を読むモデルの trait (VisionModel)。点数での順番とフォールバック。UI Automation の木の文面化
+// src-tauri (axis_os_lib) はこれらの trait を実装するだけの薄い層 (adapter.rs)。テストは tests/ にモックで
+
+pub mod apps;
+pub mod command;
+pub mod context;
+pub mod dispatch;
+pub mod engine;
+pub mod files;
+pub mod fs;
+pub mod keys;
+pub mod orchestrator;
+pub mod policy;
+pub mod provider;
+pub mod replay;
+pub mod trace;
+pub mod untrusted;
+pub mod vision;
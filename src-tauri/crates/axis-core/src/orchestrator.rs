@@ -0,0 +1,435 @@
+// src-tauri/crates/axis-core/src/orchestrator.rs
+//
+// ask_axis 1 回分の流れ（src-tauri の lib.rs answer は Host / ActionExecutor を用意して呼ぶだけ）
+// - 文脈: 直近の履歴 (history_turns。外の文面を読んだ答えは囲む) / メモリ / recall を振り分けと並行に
+// - 振り分け: 答え直しの指定 → fast path → セッション固定 → Commander が使えない → Commander → オフライン
+//   どれで決めたかは routing_source、Commander を通らない時の返事は preset_routing
+// - Worker (provider::run_worker) → 見直し (Host::review) → アクション (engine::run_actions) → 最終レポート
+// - trace (trace.rs) はここで組み立てる。アクション毎の結果と所要時間も executor を包んで記録
+// - 保存 (ログ / trace / 実績 / メモリ) は Host::persist。Answered と executor（添付などを集めたもの）を渡す
+
+use crate::command::Command;
+use crate::context::{self, Turn};
+use crate::dispatch::{self, Availability, RoutingDecision};
+use crate::engine::{self, ActionExecutor, ActionRun};
+use crate::policy::Hold;
+use crate::provider::{self, ModelProvider};
+use crate::trace::Trace;
+use crate::untrusted;
+use serde_json::json;
+use std::future::Future;
+use std::time::Instant;
+use tracing::{error, info};
+
+// 過去のやり取り 1 往復（古い順に渡す）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PastTurn {
+    pub user: String,
+    pub axis: String,
+    pub untrusted: bool, // 外の文面を読んだ答え (ResponseMeta.untrusted)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Request {
+    pub log_id: String,
+    pub session_id: String,
+    pub input: String,
+    pub created_at: i64,
+    pub provider: Option<String>, // 答え直しで指定されたプロバイダ（Commander を通さない）
+    pub regenerating: bool,       // 答え直しの枝 (regenerate_response)
+    pub online: bool,
+    pub confirm_after_untrusted: bool,
+    pub untrusted_input: bool, // 依頼そのものが外から (HTTP API / MCP)。設定に関係なく操作は確認に回す
+}
+
+#[derive(Debug, Clone)]
+pub struct Answered {
+    pub decision: RoutingDecision,
+    pub pinned: Option<String>, // セッションで固定されていたプロバイダ
+    pub worker_ok: bool,        // 選ばれたモデル自身が答えられたか（failover 前。実績記録用）
+    pub worker_latency_ms: i64, // 同上
+    pub failover: bool,
+    pub answer: String,
+    pub actions: Vec<String>, // 実行したアクション (Command::label)
+    pub read_untrusted: bool, // 外の文面を読んだ（答えは囲んで履歴 / メモリに残す）
+    pub trace: Trace,
+}
+
+impl Answered {
+    // 実際に答えた振り分け先（クラウドがタイムアウトしてローカルで答えたら "local"）
+    pub fn answered_target(&self) -> &str {
+        if self.failover {
+            "local"
+        } else {
+            &self.decision.target
+        }
+    }
+
+    // 文脈に戻す所（履歴 / メモリ / recall）に残す答え
+    pub fn stored_answer(&self) -> String {
+        if self.read_untrusted {
+            untrusted::wrap("past_answer", &self.answer)
+        } else {
+            self.answer.clone()
+        }
+    }
+}
+
+// Tauri 側の状態（ログ / 設定 / メモリ / プロンプト）に触る所。src-tauri は adapter::Host
+pub trait Host: Availability + Sync {
+    type Models: ModelProvider;
+    type Executor: ActionExecutor + Send;
+    type Saved;
+
+    fn models(&self) -> &Self::Models;
+
+    // 同じセッションのやり取り（古い順。答え直しの枝は除く）
+    fn history(&self) -> Vec<PastTurn>;
+    fn memory_context(&self, input: &str) -> impl Future<Output = String> + Send;
+    // 過去の会話の FTS の当たり
+    fn recall(&self, input: &str) -> impl Future<Output = Vec<String>> + Send;
+
+    // set_session_provider で固定されたプロバイダ
+    fn pinned_provider(&self) -> Option<String>;
+    // 挨拶や「続けて」など Commander を呼ばずに決まる振り分け
+    fn fast_route(&self, input: &str) -> Option<RoutingDecision>;
+    // Commander 自身が決めた振り分け（似た依頼の次回は dispatch を省く）
+    fn remember_route(&self, input: &str, decision: &RoutingDecision);
+    // Commander (Llama) 自体がタイムアウト続き / 予算切れなら (strategy, 理由)
+    fn commander_unavailable(&self) -> Option<(&'static str, String)>;
+    fn commander(
+        &self,
+        system: &str,
+        input: &str,
+    ) -> impl Future<Output = Result<String, String>> + Send;
+
+    fn dispatch_prompt(&self, history_text: &str) -> String;
+    fn worker_prompt(&self) -> String;
+    fn report_prompt(&self, target: &str) -> String;
+    fn prompt_hash(&self, prompt: &str) -> String;
+
+    // コード / 数学を別モデルに見直させる。見直さない依頼なら None
+    fn review(
+        &self,
+        target: &str,
+        task_type: &str,
+        input: &str,
+        output: &str,
+    ) -> impl Future<Output = Option<String>> + Send;
+
+    fn persist(&self, answered: Answered, executor: Self::Executor) -> Result<Self::Saved, String>;
+}
+
+// past は古い順。最後の HISTORY_TURNS 往復だけ
+pub fn history_turns(past: &[PastTurn]) -> Vec<Turn> {
+    past[past.len().saturating_sub(context::HISTORY_TURNS)..]
+        .iter()
+        .map(|t| Turn {
+            user: t.user.clone(),
+            // 外の文面を読んだ答えは囲んで渡す（このセッションは汚れたものとして扱われる）
+            axis: if t.untrusted {
+                untrusted::wrap("past_answer", &t.axis)
+            } else {
+                t.axis.clone()
+            },
+        })
+        .collect()
+}
+
+// どの経路で振り分けたか（trace 用。answer の dispatch と同じ順）
+pub fn routing_source<'a>(
+    provider: Option<&str>,
+    fast_route: Option<&RoutingDecision>,
+    pinned: Option<&str>,
+    commander_unavailable: Option<&'a str>,
+    online: bool,
+) -> &'a str {
+    if provider.is_some() {
+        "regenerate"
+    } else if fast_route.is_some() {
+        "fast_path"
+    } else if pinned.is_some() {
+        "pinned"
+    } else if let Some(strategy) = commander_unavailable {
+        strategy
+    } else if online {
+        "commander"
+    } else {
+        "offline"
+    }
+}
+
+// Commander を通さない時の返事（Commander を呼ぶ時は None）
+pub fn preset_routing(
+    provider: Option<&str>,
+    fast_route: Option<&RoutingDecision>,
+    pinned: Option<&str>,
+    commander_unavailable: Option<&(&str, String)>,
+    online: bool,
+) -> Option<String> {
+    let preset = if let Some(provider) = provider {
+        info!("🔀 [Commander] regenerating with {}", provider);
+        json!({
+            "target": provider,
+            "strategy": "regenerate",
+            "reason": "指定したプロバイダで答え直し"
+        })
+    } else if let Some(route) = fast_route {
+        info!(
+            "⚡ [Commander] {}: {} ({})",
+            route.reason, route.target, route.task_type
+        );
+        json!(route)
+    } else if let Some(provider) = pinned {
+        info!("📌 [Commander] session pinned to {}", provider);
+        json!({
+            "target": provider,
+            "strategy": "pinned",
+            "reason": "セッションでプロバイダが固定されています"
+        })
+    } else if let Some((strategy, why)) = commander_unavailable {
+        info!("🩺 [Commander] skipped: {}", why);
+        json!({
+            "target": "gpt",
+            "strategy": strategy,
+            "reason": format!("Commander unavailable ({})", why)
+        })
+    } else if online {
+        return None;
+    } else {
+        json!({
+            "target": "local",
+            "strategy": "offline",
+            "reason": "オフラインのためローカル LLM で応答"
+        })
+    };
+    Some(preset.to_string())
+}
+
+// Worker の返事から、ルールの朗読や分類の前置きを落とす
+pub fn sanitize_output(s: &str) -> String {
+    let mut out = s.trim().to_string();
+
+    // よくある「CONVERSATION: ...」系はプレフィックスを剥がす
+    if let Some(rest) = out.strip_prefix("CONVERSATION:") {
+        out = rest.trim().to_string();
+    }
+
+    // ルール朗読・分類文が混ざるケースを切り落とす（"Here's a natural response:" 以降だけ採用）
+    if let Some(pos) = out.rfind("Here's a natural response:") {
+        out = out[(pos + "Here's a natural response:".len())..]
+            .trim()
+            .to_string();
+    }
+
+    // それでも「To classify...」等が残る場合は、最後の引用や最後段落を優先（雑に長文を捨てる）
+    // ※安全側：何も見つからなければそのまま返す
+    if out.contains("To classify") || out.contains("[Phase") || out.contains("Therefore,") {
+        // 最後の空行以降を返す（最後段落）
+        if let Some(pos) = out.rfind("\n\n") {
+            out = out[(pos + 2)..].trim().to_string();
+        }
+    }
+
+    out
+}
+
+// アクション毎の結果と所要時間を trace に残す
+struct Traced<'a, E> {
+    inner: &'a mut E,
+    trace: &'a mut Trace,
+    started: Instant,
+}
+
+impl<E: ActionExecutor + Send> ActionExecutor for Traced<'_, E> {
+    async fn execute(&mut self, cmd: &Command, context: &mut String) {
+        self.started = Instant::now();
+        self.inner.execute(cmd, context).await;
+    }
+
+    fn hold(&mut self, cmd: &Command, hold: Hold) -> String {
+        self.started = Instant::now();
+        self.inner.hold(cmd, hold)
+    }
+
+    fn finished(&mut self, cmd: &Command, output: &str, executed: bool) {
+        self.inner.finished(cmd, output, executed);
+        self.trace.action(cmd.label(), output, self.started);
+    }
+}
+
+pub async fn answer<H: Host>(
+    host: &H,
+    mut executor: H::Executor,
+    req: &Request,
+) -> Result<H::Saved, String> {
+    let started = Instant::now();
+    let input = req.input.as_str();
+    let history_text = context::history_text(&history_turns(&host.history()));
+
+    let pinned = host.pinned_provider();
+    // 答え直しは選び直しなので fast path を使わない
+    let fast_route = if pinned.is_none() && !req.regenerating {
+        host.fast_route(input)
+    } else {
+        None
+    };
+    let commander_unavailable = if req.online {
+        host.commander_unavailable()
+    } else {
+        None
+    };
+    let source = routing_source(
+        req.provider.as_deref(),
+        fast_route.as_ref(),
+        pinned.as_deref(),
+        commander_unavailable
+            .as_ref()
+            .map(|(strategy, _)| *strategy),
+        req.online,
+    );
+    let dispatch_prompt = host.dispatch_prompt(&history_text);
+    let mut trace = Trace {
+        log_id: req.log_id.clone(),
+        session_id: req.session_id.clone(),
+        created_at: req.created_at,
+        input: input.to_string(),
+        routing_source: source.to_string(),
+        dispatch_prompt: (source == "commander").then(|| dispatch_prompt.clone()),
+        ..Default::default()
+    };
+
+    let context_started = Instant::now();
+    let dispatch = async {
+        let dispatch_started = Instant::now();
+        let preset = preset_routing(
+            req.provider.as_deref(),
+            fast_route.as_ref(),
+            pinned.as_deref(),
+            commander_unavailable.as_ref(),
+            req.online,
+        );
+        let raw = match preset {
+            Some(raw) => raw,
+            None => {
+                info!("👑 [Commander] Llama dispatching...");
+                // JSON解析失敗時の安全策
+                host.commander(&dispatch_prompt, input)
+                    .await
+                    .unwrap_or_else(|_| {
+                        json!({
+                            "target": "gpt",
+                            "strategy": "fallback",
+                            "reason": "Llama returned invalid JSON"
+                        })
+                        .to_string()
+                    })
+            }
+        };
+        (raw, dispatch_started.elapsed().as_millis() as i64)
+    };
+
+    // ルーティング LLM 呼び出し / メモリ検索 / FTS recall を同時に待つ
+    let ((routing_raw, dispatch_ms), memory_context, recall) =
+        tokio::join!(dispatch, host.memory_context(input), host.recall(input));
+    trace.phase("dispatch", dispatch_ms);
+    trace.phase_since("context", context_started);
+    trace.routing_raw = routing_raw.clone();
+    // 直近の履歴に既に入っているものは省く
+    let recall_context = context::recall_section(&recall, &history_text);
+
+    let mut decision = dispatch::parse_decision(&routing_raw);
+    // 届かない / タイムアウト続き / 予算切れのプロバイダは避ける（答え直しで指定された時はそのまま）
+    dispatch::reroute(&mut decision, host, req.provider.is_some());
+    info!("👉 Routing: {} ({})", decision.target, decision.reason);
+    trace.routing = serde_json::to_value(&decision).unwrap_or_default();
+
+    if source == "commander"
+        && !req.regenerating
+        && decision.strategy == dispatch::default_strategy()
+        && !decision.target.is_empty()
+    {
+        host.remember_route(input, &decision);
+    }
+
+    // Worker（クラウド側がタイムアウトしたらローカル LLM で一度だけやり直す）
+    let system = host.worker_prompt();
+    let task_input = context::task_input(&history_text, &memory_context, &recall_context, input);
+    // 履歴 / メモリ / recall に外の文面を読んだ答えが入っていれば、この依頼の操作と外への送信は確認に回す
+    let tainted =
+        req.untrusted_input || (req.confirm_after_untrusted && untrusted::contains(&task_input));
+    trace.tainted = tainted;
+    trace.worker_prompt_hash = host.prompt_hash(&system);
+
+    let worker_started = Instant::now();
+    let run = provider::run_worker(host.models(), &decision.target, &system, &task_input).await;
+    trace.phase_since("worker", worker_started);
+    let output = run.result.unwrap_or_else(|e| {
+        error!("❌ Worker Error: {}", e);
+        format!("Error: {}", e)
+    });
+    info!(output = %output, "🤖 [Output]");
+    let mut output = sanitize_output(&output);
+
+    // 検証フェーズ: コード / 数学は別モデルに見直させてから出す
+    let answered_target = if run.failover {
+        "local"
+    } else {
+        decision.target.as_str()
+    };
+    let critic_started = Instant::now();
+    if let Some(checked) = host
+        .review(answered_target, &decision.task_type, input, &output)
+        .await
+    {
+        output = checked;
+        trace.phase_since("critic", critic_started);
+    }
+    trace.worker_output = output.clone();
+
+    // アクションと最終レポート
+    let mut answer = output.clone();
+    let actions_started = Instant::now();
+    let mut traced = Traced {
+        inner: &mut executor,
+        trace: &mut trace,
+        started: Instant::now(),
+    };
+    let action_run = engine::run_actions(&mut traced, &output, tainted).await;
+    let mut read_untrusted = false;
+    let mut actions = Vec::new();
+    if let Some(ActionRun {
+        context,
+        executed,
+        untrusted_seen,
+        ..
+    }) = action_run
+    {
+        trace.phase_since("actions", actions_started);
+        actions = executed;
+        read_untrusted = untrusted_seen;
+        if !context.is_empty() {
+            let report_started = Instant::now();
+            let system = host.report_prompt(&decision.target);
+            answer = provider::report(host.models(), &decision.target, &system, &context).await;
+            trace.phase_since("report", report_started);
+            trace.report = answer.clone();
+        }
+    }
+
+    trace.worker_target = answered_target.to_string();
+    trace.failover = run.failover;
+    trace.total_ms = started.elapsed().as_millis() as i64;
+    let answered = Answered {
+        decision,
+        pinned,
+        worker_ok: run.ok,
+        worker_latency_ms: run.latency_ms,
+        failover: run.failover,
+        answer,
+        actions,
+        read_untrusted,
+        trace,
+    };
+    host.persist(answered, executor)
+}
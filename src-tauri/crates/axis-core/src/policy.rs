@@ -0,0 +1,62 @@
+// src-tauri/crates/axis-core/src/policy.rs
+//
+// 外の文面 (untrusted.rs) が文脈に入っている（汚れた）セッションで出てきた操作の扱い
+// - 手元を読むだけのもの (LOOK / APPS / GIT / CALENDAR ...) はそのまま続ける
+// - 操作と、モデルが選んだ文面を外に送るもの (SEARCH / FETCH / WEATHER / NEWS / IMAGE_GEN) → Hold::Confirm
+// - 別のセッションでモデルを動かし直すもの (PLAN / BACKGROUND) → Hold::Refuse（汚れが確認なしの所へ移るので）
+// - COMMIT は元から必ず確認するので何もしない
+// 確認待ちの実際のキュー / 実行は src-tauri の policy.rs
+
+use crate::command::{Action, Command};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hold {
+    Confirm,
+    Refuse,
+}
+
+pub fn after_untrusted(cmd: &Command) -> Option<Hold> {
+    match cmd.action {
+        Action::Look
+        | Action::RecordScreen { .. }
+        | Action::Apps
+        | Action::BrowserTab
+        | Action::Processes { .. }
+        | Action::Disk { .. }
+        | Action::Activity { .. }
+        | Action::Calendar { .. }
+        | Action::CheckEmail { .. }
+        | Action::Git { .. }
+        | Action::Wait { .. }
+        | Action::WaitFor { .. }
+        | Action::Commit { .. }
+        | Action::Invalid { .. }
+        | Action::Unknown => None,
+        Action::Search { .. }
+        | Action::Fetch { .. }
+        | Action::Weather { .. }
+        | Action::News { .. }
+        | Action::ImageGen { .. }
+        | Action::Exec { .. }
+        | Action::Type { .. }
+        | Action::Click { .. }
+        | Action::Press { .. }
+        | Action::Kill { .. }
+        | Action::Focus { .. }
+        | Action::Minimize { .. }
+        | Action::OpenFile { .. }
+        | Action::Reveal { .. }
+        | Action::OpenUrl { .. }
+        | Action::Plugin { .. }
+        | Action::Run { .. }
+        | Action::Save { .. }
+        | Action::Template { .. }
+        | Action::Schedule { .. }
+        | Action::LearnAlias { .. }
+        | Action::UndoLast
+        | Action::Macro { .. }
+        | Action::HomeAssistant { .. }
+        | Action::Media { .. } => Some(Hold::Confirm),
+        Action::Plan { .. } | Action::Background { .. } => Some(Hold::Refuse),
+    }
+}
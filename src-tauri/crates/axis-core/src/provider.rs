@@ -0,0 +1,77 @@
+// src-tauri/crates/axis-core/src/provider.rs
+//
+// モデル呼び出しの trait と、それを使う Worker / 最終レポートの流れ
+// - target は Commander の振り分け先 ("gpt" / "gemini" / "grok" / "local" / "ensemble" / それ以外は Llama)
+// - run_worker: クラウド側がタイムアウトしたら "local" で一度だけやり直す（failover）
+// - report: アクションの結果から最終回答を作る（grok は grok、それ以外は gpt。失敗したら "Done."）
+
+use std::future::Future;
+use std::time::Instant;
+use tracing::info;
+
+// 最終レポートに渡す依頼文の頭（replay.rs はこれで Worker と見分ける）
+pub const REPORT_PROMPT: &str = "Report the result based on log:\n";
+
+pub trait ModelProvider: Sync {
+    fn complete(
+        &self,
+        target: &str,
+        system: &str,
+        user: &str,
+    ) -> impl Future<Output = Result<String, String>> + Send;
+
+    // failover するかどうか（src-tauri は ai::is_timeout_error）
+    fn is_timeout(&self, error: &str) -> bool;
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkerRun {
+    pub result: Result<String, String>,
+    pub ok: bool,        // 選ばれたモデル自身が答えられたか（failover 前。実績記録用）
+    pub latency_ms: i64, // 同上
+    pub failover: bool,
+}
+
+pub async fn run_worker(
+    provider: &impl ModelProvider,
+    target: &str,
+    system: &str,
+    user: &str,
+) -> WorkerRun {
+    let started = Instant::now();
+    let result = provider.complete(target, system, user).await;
+    let ok = result.is_ok();
+    let latency_ms = started.elapsed().as_millis() as i64;
+
+    let failover = matches!(&result, Err(e) if provider.is_timeout(e)) && target != "local";
+    let result = match result {
+        Err(e) if failover => {
+            info!("⏱️ [Worker] {} — failing over to local LLM", e);
+            provider
+                .complete("local", system, user)
+                .await
+                .map_err(|local_err| format!("{} / fallback: {}", e, local_err))
+        }
+        other => other,
+    };
+    WorkerRun {
+        result,
+        ok,
+        latency_ms,
+        failover,
+    }
+}
+
+pub async fn report(
+    provider: &impl ModelProvider,
+    target: &str,
+    system: &str,
+    context: &str,
+) -> String {
+    let prompt = format!("{}{}", REPORT_PROMPT, context);
+    let via = if target == "grok" { "grok" } else { "gpt" };
+    provider
+        .complete(via, system, &prompt)
+        .await
+        .unwrap_or("Done.".to_string())
+}
@@ -0,0 +1,195 @@
+// src-tauri/crates/axis-core/src/replay.rs
+//
+// 記録 (src-tauri の trace.rs) から決まった返事を返して、依頼 1 回分をもう一度通す
+// - Commander の返事 / Worker の出力 / 最終レポートは記録のまま（どの API も呼ばない）
+// - アクションは実行せず、記録した結果を順に返す（外の文面は untrusted::unwrap してから囲み直す）
+// - 振り分け / 読み出したアクション / 囲み方が記録と違えば mismatches に（回帰テスト用）
+//   到達性 / 健康状態 / 予算で振り替えた分 (offline / health / budget) は環境のせいなので記録の先をそのまま使う
+
+use crate::command::Command;
+use crate::dispatch::{self, RoutingDecision};
+use crate::engine::{self, ActionExecutor, ActionRun};
+use crate::policy::Hold;
+use crate::provider::{self, ModelProvider, REPORT_PROMPT};
+use crate::untrusted;
+use serde::{Deserialize, Serialize};
+
+// 記録の時に環境で振り替えた strategy
+const REROUTED: &[&str] = &["offline", "health", "budget"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedAction {
+    pub command: String, // Command::label
+    pub result: String,  // このアクション 1 つ分の出力（trim 済み。長いものは「…」で切ってある）
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    pub routing_raw: String,
+    pub routing: Option<RoutingDecision>, // 振り替えも反映した最終的な振り分け
+    pub worker_output: String,            // Phase 3 に渡したもの（critic の見直し後）
+    pub actions: Vec<RecordedAction>,
+    pub report: String, // アクションが無ければ空
+    #[serde(default)]
+    pub tainted: bool, // 文脈に外の文面が入っていた（操作を確認に回す。engine::run_actions）
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Replayed {
+    pub decision: RoutingDecision,
+    pub actions: Vec<String>,
+    pub held: Vec<String>,
+    pub answer: String,
+    pub mismatches: Vec<String>,
+}
+
+// target に関係なく、Worker には worker_output、レポートには report
+pub struct ReplayProvider<'a>(pub &'a Recording);
+
+impl ModelProvider for ReplayProvider<'_> {
+    async fn complete(&self, _target: &str, _system: &str, user: &str) -> Result<String, String> {
+        let (canned, what) = if user.starts_with(REPORT_PROMPT) {
+            (&self.0.report, "report")
+        } else {
+            (&self.0.worker_output, "worker output")
+        };
+        if canned.is_empty() {
+            return Err(format!("[Replay] no recorded {}", what));
+        }
+        Ok(canned.clone())
+    }
+
+    fn is_timeout(&self, _error: &str) -> bool {
+        false
+    }
+}
+
+pub struct ReplayExecutor<'a> {
+    recording: &'a Recording,
+    next: usize,
+    pub mismatches: Vec<String>,
+}
+
+impl<'a> ReplayExecutor<'a> {
+    pub fn new(recording: &'a Recording) -> Self {
+        Self {
+            recording,
+            next: 0,
+            mismatches: Vec::new(),
+        }
+    }
+
+    // 次の記録。コマンドが違えば mismatch にして、結果は返さない
+    fn take(&mut self, cmd: &Command) -> Option<&'a RecordedAction> {
+        let recorded = self.recording.actions.get(self.next);
+        self.next += 1;
+        match recorded {
+            Some(r) if r.command == cmd.label() => Some(r),
+            Some(r) => {
+                self.mismatches.push(format!(
+                    "action {}: ran '{}' but '{}' was recorded",
+                    self.next,
+                    cmd.label(),
+                    r.command
+                ));
+                None
+            }
+            None => {
+                self.mismatches.push(format!(
+                    "action {}: ran '{}' but nothing was recorded",
+                    self.next,
+                    cmd.label()
+                ));
+                None
+            }
+        }
+    }
+}
+
+impl ActionExecutor for ReplayExecutor<'_> {
+    async fn execute(&mut self, cmd: &Command, context: &mut String) {
+        match self.take(cmd) {
+            Some(r) => {
+                context.push_str(untrusted::unwrap(&r.result));
+                context.push('\n');
+            }
+            None => context.push_str(&format!(
+                "[Replay] no recorded result for {}\n",
+                cmd.label()
+            )),
+        }
+    }
+
+    fn hold(&mut self, cmd: &Command, _hold: Hold) -> String {
+        match self.take(cmd) {
+            Some(r) if r.result.starts_with("[Policy]") => format!("{}\n", r.result),
+            Some(r) => {
+                self.mismatches.push(format!(
+                    "action {}: '{}' was held but ran when recorded",
+                    self.next, r.command
+                ));
+                format!("{}\n", r.result)
+            }
+            None => format!("[Replay] {} held\n", cmd.label()),
+        }
+    }
+
+    fn finished(&mut self, cmd: &Command, output: &str, _executed: bool) {
+        let Some(r) = self.recording.actions.get(self.next.wrapping_sub(1)) else {
+            return;
+        };
+        // 記録で切ってあるものは比べられない
+        if r.command == cmd.label() && !r.result.ends_with('…') && output.trim() != r.result {
+            self.mismatches.push(format!(
+                "action {}: output of '{}' differs from the recording",
+                self.next, r.command
+            ));
+        }
+    }
+}
+
+pub async fn replay(recording: &Recording, confirm_after_untrusted: bool) -> Replayed {
+    let tainted = recording.tainted && confirm_after_untrusted;
+    let mut mismatches = Vec::new();
+    let mut decision = dispatch::parse_decision(&recording.routing_raw);
+    if let Some(recorded) = &recording.routing {
+        if REROUTED.contains(&recorded.strategy.as_str()) {
+            decision = recorded.clone();
+        } else if decision.target != recorded.target {
+            mismatches.push(format!(
+                "routing: {} but {} was recorded",
+                decision.target, recorded.target
+            ));
+        }
+    }
+
+    let provider = ReplayProvider(recording);
+    let run = provider::run_worker(&provider, &decision.target, "", "").await;
+    let worker_output = run.result.unwrap_or_else(|e| format!("Error: {}", e));
+
+    let mut executor = ReplayExecutor::new(recording);
+    let action_run =
+        engine::run_actions(&mut executor, &worker_output, tainted).await;
+    mismatches.append(&mut executor.mismatches);
+    for r in recording.actions.iter().skip(executor.next) {
+        mismatches.push(format!("recorded action '{}' was not run", r.command));
+    }
+
+    let mut answer = worker_output;
+    let ActionRun {
+        context,
+        executed,
+        held,
+        ..
+    } = action_run.unwrap_or_default();
+    if !context.is_empty() {
+        answer = provider::report(&provider, &decision.target, "", &context).await;
+    }
+    Replayed {
+        decision,
+        actions: executed,
+        held,
+        answer,
+        mismatches,
+    }
+}
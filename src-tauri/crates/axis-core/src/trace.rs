@@ -0,0 +1,102 @@
+// src-tauri/crates/axis-core/src/trace.rs
+//
+// ask_axis 1 回分の処理の記録（なぜその振り分け / その動きになったかを後から追う用）
+// - Commander に渡したプロンプト / 返ってきた生の文字列 / 解釈したルーティング
+//   Commander を通らなかった時 (fast_path / pinned / regenerate / offline) はプロンプトなし
+// - Worker のシステムプロンプトは長いのでハッシュだけ（prompts/*.md を変えた前後の区別が付けば十分）
+// - Worker が出した文 (critic の後) / アクション毎の結果（system_context に足された分）と所要時間 / 最終レポート
+//   replay.rs はこれだけで依頼をもう一度通す (Trace::recording)
+// - フェーズ毎の所要時間: dispatch / context / worker / critic / actions / report
+// 組み立ては orchestrator.rs、保存と読み出しは src-tauri の trace.rs
+
+use crate::replay::{RecordedAction, Recording};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+// アクション結果はこれ以上は切る（検索結果や web ページを丸ごと持つと大きい）
+const MAX_RESULT_CHARS: usize = 4000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Trace {
+    pub log_id: String,
+    pub session_id: String,
+    pub created_at: i64,
+    pub input: String,
+    // commander / fast_path / pinned / regenerate / offline
+    pub routing_source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dispatch_prompt: Option<String>,
+    pub routing_raw: String,
+    pub routing: serde_json::Value, // 最終的な RoutingDecision（到達できずローカルへ回したのも反映）
+    pub worker_target: String,
+    pub worker_model: String,
+    pub worker_prompt_hash: String,
+    pub failover: bool,
+    #[serde(default)]
+    pub worker_output: String,
+    #[serde(default)]
+    pub actions: Vec<ActionTrace>,
+    #[serde(default)]
+    pub phases: Vec<PhaseTiming>,
+    #[serde(default)]
+    pub report: String, // アクションが無ければ空
+    #[serde(default)]
+    pub tainted: bool, // 文脈に外の文面が入っていた（操作は確認に回した）
+    pub total_ms: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActionTrace {
+    pub command: String,
+    pub result: String,
+    pub latency_ms: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PhaseTiming {
+    pub name: String,
+    pub latency_ms: i64,
+}
+
+impl Trace {
+    pub fn phase(&mut self, name: &str, latency_ms: i64) {
+        self.phases.push(PhaseTiming {
+            name: name.to_string(),
+            latency_ms,
+        });
+    }
+
+    pub fn phase_since(&mut self, name: &str, started: Instant) {
+        self.phase(name, started.elapsed().as_millis() as i64);
+    }
+
+    pub fn recording(&self) -> Recording {
+        Recording {
+            routing_raw: self.routing_raw.clone(),
+            routing: serde_json::from_value(self.routing.clone()).ok(),
+            worker_output: self.worker_output.clone(),
+            actions: self
+                .actions
+                .iter()
+                .map(|a| RecordedAction {
+                    command: a.command.clone(),
+                    result: a.result.clone(),
+                })
+                .collect(),
+            report: self.report.clone(),
+            tainted: self.tainted,
+        }
+    }
+
+    pub fn action(&mut self, command: String, result: &str, started: Instant) {
+        let mut result = result.trim().to_string();
+        if result.chars().count() > MAX_RESULT_CHARS {
+            result = result.chars().take(MAX_RESULT_CHARS).collect::<String>() + "…";
+        }
+        self.actions.push(ActionTrace {
+            command,
+            result,
+            latency_ms: started.elapsed().as_millis() as i64,
+        });
+    }
+}
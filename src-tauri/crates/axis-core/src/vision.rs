@@ -0,0 +1,175 @@
+// src-tauri/crates/axis-core/src/vision.rs
+//
+// 画面 / 画像を読むモデルの trait と、その選び方
+// - VisionModel: 名前 ("gpt" / "gemini" / "claude" / "local" / "llama") で画像の説明を頼む（src-tauri の vision.rs が実装）
+// - order: 指定があればそれを先頭に、残りはプロファイルの点数 (VisionScore::weighted) の高い順
+// - describe: 順に試し、失敗したら次へ。全部だめなら最後のエラーに失敗の一覧を付けて返す
+// - keyframes: 画面の録画 (record_screen) から送るコマ。最初と最後 + 変化の大きいコマ
+// - UiNode / outline: UI Automation で読んだ前面ウィンドウのコントロールの木 (src-tauri の uia.rs) を LOOK 用の文面に
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use tracing::info;
+
+pub trait VisionModel: Sync {
+    fn describe(
+        &self,
+        name: &str,
+        prompt: &str,
+        png_base64: &str,
+    ) -> impl Future<Output = Result<String, String>> + Send;
+}
+
+// 0.0〜1.0。cost は高いほど安い（model_profiles.json と同じ向き）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct VisionScore {
+    pub quality: f32,
+    pub speed: f32,
+    pub cost: f32,
+}
+
+impl VisionScore {
+    pub fn weighted(&self) -> f32 {
+        self.quality * 0.6 + self.speed * 0.2 + self.cost * 0.2
+    }
+}
+
+// preferred は "auto" か空なら点数だけで。同点は渡された順のまま
+pub fn order(preferred: &str, candidates: &[(String, VisionScore)]) -> Vec<String> {
+    let mut ranked: Vec<&(String, VisionScore)> = candidates.iter().collect();
+    ranked.sort_by(|a, b| b.1.weighted().total_cmp(&a.1.weighted()));
+    let mut names: Vec<String> = ranked.into_iter().map(|(name, _)| name.clone()).collect();
+    if let Some(i) = names.iter().position(|n| n == preferred) {
+        let first = names.remove(i);
+        names.insert(0, first);
+    }
+    names
+}
+
+#[derive(Debug, Clone)]
+pub struct VisionRun {
+    pub result: Result<String, String>,
+    pub used: Option<String>,
+    pub failures: Vec<String>, // "<name>: <error>"
+}
+
+pub async fn describe(
+    model: &impl VisionModel,
+    order: &[String],
+    prompt: &str,
+    png_base64: &str,
+) -> VisionRun {
+    let mut failures = Vec::new();
+    for name in order {
+        match model.describe(name, prompt, png_base64).await {
+            Ok(text) => {
+                return VisionRun {
+                    result: Ok(text),
+                    used: Some(name.clone()),
+                    failures,
+                }
+            }
+            Err(e) => {
+                info!("👁️ [Vision] {} failed, trying the next one: {}", name, e);
+                failures.push(format!("{}: {}", name, e));
+            }
+        }
+    }
+    let error = if failures.is_empty() {
+        "no vision model is available".to_string()
+    } else {
+        format!("all vision models failed ({})", failures.join(" / "))
+    };
+    VisionRun {
+        result: Err(error),
+        used: None,
+        failures,
+    }
+}
+
+// これ未満の変化（0.0〜1.0。縮小した画面の平均の差）は同じ場面とみなす
+pub const MIN_CHANGE: f32 = 0.02;
+
+// changes[i] はコマ i と i-1 の差（changes[0] は使わない）。戻り値は昇順のコマ番号で、最大 max 個
+pub fn keyframes(changes: &[f32], max: usize) -> Vec<usize> {
+    let n = changes.len();
+    if n == 0 || max == 0 {
+        return Vec::new();
+    }
+    let mut picked = vec![0];
+    if n > 1 && max > 1 {
+        picked.push(n - 1);
+    }
+    let mut candidates: Vec<usize> = (1..n.saturating_sub(1))
+        .filter(|&i| changes[i] >= MIN_CHANGE)
+        .collect();
+    candidates.sort_by(|&a, &b| changes[b].total_cmp(&changes[a]).then(a.cmp(&b)));
+    for i in candidates {
+        if picked.len() >= max {
+            break;
+        }
+        picked.push(i);
+    }
+    picked.sort_unstable();
+    picked
+}
+
+// UI Automation の要素 1 つ。role は "button" / "edit" / "document" などの種類
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UiNode {
+    pub role: String,
+    pub name: String,
+    pub value: String, // 入力欄の中身 / 文書の本文（パスワード欄は読まない）
+    pub children: Vec<UiNode>,
+}
+
+// 要素 1 つの value はここまで（エディタの本文などは長い）
+pub const VALUE_CHARS: usize = 4000;
+
+// 字下げした "- role "name": value" の一覧。名前も値も無い入れ物は行を出さずに子を 1 段浅く
+// 同じ文面が続くもの（ラベルと中身の部品が同じ名前を持つ等）は省く。max_chars を超えたら切る
+pub fn outline(root: &UiNode, max_chars: usize) -> String {
+    let mut out = String::new();
+    let mut last = String::new();
+    let truncated = write_node(root, 0, max_chars, &mut out, &mut last);
+    if truncated {
+        out.push_str("[... truncated]\n");
+    }
+    out
+}
+
+// もう書けなくなったら true
+fn write_node(
+    node: &UiNode,
+    depth: usize,
+    max: usize,
+    out: &mut String,
+    last: &mut String,
+) -> bool {
+    let name = node.name.trim();
+    let value: String = node.value.trim().chars().take(VALUE_CHARS).collect();
+    let mut child_depth = depth;
+    if !name.is_empty() || !value.is_empty() {
+        let indent = "  ".repeat(depth);
+        // 複数行の値は続きの行も字下げ
+        let value = value.replace('\n', &format!("\n{}  ", indent));
+        let text = match (name.is_empty(), value.is_empty() || value == name) {
+            (false, true) => format!("\"{}\"", name),
+            (false, false) => format!("\"{}\": {}", name, value),
+            _ => value,
+        };
+        if text != *last {
+            let line = format!("{}- {} {}\n", indent, node.role, text);
+            if out.len() + line.len() > max {
+                return true;
+            }
+            out.push_str(&line);
+            *last = text;
+        }
+        child_depth += 1;
+    }
+    node.children
+        .iter()
+        .any(|child| write_node(child, child_depth, max, out, last))
+}
@@ -0,0 +1,125 @@
+// EXEC のアプリ名の解決 (apps.rs)
+
+use axis_core::apps::{self, AppEntry};
+use std::collections::BTreeMap;
+
+fn aliases(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn resolve_ignores_case_and_spaces() {
+    let map = aliases(&[
+        ("Browser", "chrome.exe"),
+        ("エディタ", "code"),
+        ("empty", " "),
+    ]);
+    assert_eq!(apps::resolve(&map, " browser "), Some("chrome.exe"));
+    assert_eq!(apps::resolve(&map, "エディタ"), Some("code"));
+    assert_eq!(apps::resolve(&map, "empty"), None);
+    assert_eq!(apps::resolve(&map, "notepad"), None);
+}
+
+#[test]
+fn check_alias_normalizes_and_rejects_bad_names() {
+    assert_eq!(
+        apps::check_alias(" Mail ", " outlook.exe "),
+        Ok(("mail".to_string(), "outlook.exe".to_string()))
+    );
+    assert!(apps::check_alias("", "code").is_err());
+    assert!(apps::check_alias(&"x".repeat(61), "code").is_err());
+    assert!(apps::check_alias("a\nb", "code").is_err());
+    assert!(apps::check_alias("Code", "code").is_err());
+}
+
+#[test]
+fn learned_aliases_cannot_shadow_builtins_or_run_scripts() {
+    assert!(apps::check_learned("mail", "outlook.exe").is_ok());
+    assert!(apps::check_learned("Notepad", "evil.exe").is_err());
+    assert!(apps::check_learned("build", "run.bat").is_err());
+    assert!(apps::check_learned("docs", r"C:\Users\me\Desktop\docs.LNK").is_err());
+    assert!(apps::check_learned("tool", r"C:\Program Files\..\Users\me\tool.exe").is_err());
+    // 設定ファイルに利用者が書いたものは今までどおり
+    assert!(apps::check_alias("build", "run.bat").is_ok());
+}
+
+#[test]
+fn commands_are_paths_or_executables() {
+    assert!(apps::is_command("chrome.exe"));
+    assert!(apps::is_command(r"C:\Program Files\Tool\Tool.EXE"));
+    assert!(apps::is_command("/usr/bin/code"));
+    assert!(!apps::is_command("code"));
+    assert!(!apps::is_command("Visual Studio Code"));
+}
+
+fn app(name: &str, launches: u32, last_launched: Option<i64>) -> AppEntry {
+    AppEntry {
+        id: format!("id.{}", name),
+        name: name.to_string(),
+        launches,
+        last_launched,
+    }
+}
+
+fn catalog() -> Vec<AppEntry> {
+    vec![
+        app("Google Chrome", 0, None),
+        app("Visual Studio Code", 3, Some(20)),
+        app("Visual Studio 2022", 0, None),
+        app("Spotify", 1, Some(10)),
+        app("Microsoft Teams", 0, None),
+        app("Notepad++", 0, None),
+    ]
+}
+
+fn found(query: &str) -> Option<String> {
+    apps::find(&catalog(), query).map(|a| a.name.clone())
+}
+
+#[test]
+fn find_ranks_exact_prefix_words_and_substrings() {
+    assert_eq!(found("spotify").as_deref(), Some("Spotify"));
+    assert_eq!(found("google").as_deref(), Some("Google Chrome"));
+    assert_eq!(found("chrome").as_deref(), Some("Google Chrome"));
+    assert_eq!(found("vs code").as_deref(), None);
+    assert_eq!(found("studio code").as_deref(), Some("Visual Studio Code"));
+    assert_eq!(found("teams").as_deref(), Some("Microsoft Teams"));
+    assert_eq!(found("pad++").as_deref(), Some("Notepad++"));
+}
+
+#[test]
+fn find_tolerates_typos_but_not_strangers() {
+    assert_eq!(found("chorme").as_deref(), Some("Google Chrome"));
+    assert_eq!(found("spotfy").as_deref(), Some("Spotify"));
+    assert_eq!(found("photoshop"), None);
+    assert_eq!(found(""), None);
+}
+
+#[test]
+fn ties_go_to_the_most_launched_app() {
+    assert_eq!(
+        found("visual studio").as_deref(),
+        Some("Visual Studio Code")
+    );
+}
+
+#[test]
+fn shortlist_puts_recent_apps_first() {
+    let list = catalog();
+    let names: Vec<&str> = apps::shortlist(&list, 4)
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "Visual Studio Code",
+            "Spotify",
+            "Google Chrome",
+            "Microsoft Teams"
+        ]
+    );
+}
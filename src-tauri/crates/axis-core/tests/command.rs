@@ -0,0 +1,767 @@
+// Worker の出力の読み出し (command.rs)
+
+use axis_core::command::{self, Action, Command};
+use axis_core::files::Mode;
+use std::collections::BTreeMap;
+
+fn actions(response: &str) -> Vec<Action> {
+    command::parse_response(response)
+        .into_iter()
+        .map(|c| c.action)
+        .collect()
+}
+
+fn action(cmd: &str) -> Action {
+    Command::parse(cmd).action
+}
+
+fn invalid(cmd: &str) -> String {
+    match action(cmd) {
+        Action::Invalid { message } => message,
+        other => panic!("{} parsed as {:?}", cmd, other),
+    }
+}
+
+fn s(v: &str) -> String {
+    v.to_string()
+}
+
+// ---------- 区切り ----------
+
+#[test]
+fn splits_on_and_and_skips_no() {
+    assert_eq!(
+        actions("SEARCH: rust async && NO &&  && EXEC: notepad"),
+        [
+            Action::Search {
+                query: s("rust async")
+            },
+            Action::Exec { app: s("notepad") }
+        ]
+    );
+}
+
+#[test]
+fn needs_spaces_around_and() {
+    assert_eq!(
+        actions("RUN: make&&make install"),
+        [Action::Run {
+            command: s("make&&make install"),
+            cwd: None
+        }]
+    );
+    assert_eq!(actions("LOOK &&APPS").len(), 1);
+    assert_eq!(actions("LOOK\n&&\nAPPS"), [Action::Look, Action::Apps]);
+}
+
+#[test]
+fn escaped_and_is_literal() {
+    assert_eq!(
+        actions(r"TYPE: this \&& that && PRESS: enter"),
+        [
+            Action::Type {
+                text: s("this && that"),
+                target: None
+            },
+            Action::Press { key: s("enter") }
+        ]
+    );
+}
+
+#[test]
+fn quoted_arguments_keep_and() {
+    assert_eq!(
+        actions(r#"RUN: "make && make install" @ C:\dev\app && LOOK"#),
+        [
+            Action::Run {
+                command: s("make && make install"),
+                cwd: Some(s(r"C:\dev\app"))
+            },
+            Action::Look
+        ]
+    );
+}
+
+#[test]
+fn content_keeps_and_and_colons() {
+    let cmds = command::parse_response(
+        "SAVE: build.sh ||| cargo build && cargo test\necho done: ok && EXEC: notepad",
+    );
+    assert_eq!(cmds.len(), 2);
+    assert_eq!(
+        cmds[0].action,
+        Action::Save {
+            path: s("build.sh"),
+            mode: Mode::Create,
+            content: s("cargo build && cargo test\necho done: ok")
+        }
+    );
+    assert_eq!(cmds[0].label(), "SAVE: build.sh");
+    assert_eq!(cmds[1].action, Action::Exec { app: s("notepad") });
+}
+
+#[test]
+fn content_splits_before_bare_actions() {
+    assert_eq!(
+        actions("SAVE: a.md ||| # News && LOOK"),
+        [
+            Action::Save {
+                path: s("a.md"),
+                mode: Mode::Create,
+                content: s("# News")
+            },
+            Action::Look
+        ]
+    );
+    // 後ろが文なら NEWS でも切らない
+    assert_eq!(
+        actions("SAVE: a.md ||| x && NEWS is good"),
+        [Action::Save {
+            path: s("a.md"),
+            mode: Mode::Create,
+            content: s("x && NEWS is good")
+        }]
+    );
+}
+
+#[test]
+fn content_may_mention_actions() {
+    assert_eq!(
+        actions("SAVE: howto.md ||| Use SAVE: x ||| y and EXEC: app"),
+        [Action::Save {
+            path: s("howto.md"),
+            mode: Mode::Create,
+            content: s("Use SAVE: x ||| y and EXEC: app")
+        }]
+    );
+}
+
+#[test]
+fn schedule_message_keeps_and() {
+    assert_eq!(
+        actions("SCHEDULE: daily 22:00 ||| PROMPT: summarize mail && calendar"),
+        [Action::Schedule {
+            when: s("daily 22:00"),
+            message: s("PROMPT: summarize mail && calendar")
+        }]
+    );
+}
+
+#[test]
+fn unicode_is_kept() {
+    assert_eq!(
+        actions("SEARCH: 東京の天気 && TYPE: こんにちは @ メモ帳"),
+        [
+            Action::Search {
+                query: s("東京の天気")
+            },
+            Action::Type {
+                text: s("こんにちは"),
+                target: Some(s("メモ帳"))
+            }
+        ]
+    );
+}
+
+#[test]
+fn has_actions_needs_a_marker() {
+    assert!(command::has_actions("LOOK"));
+    assert!(command::has_actions("Sure. SAVE: a.txt ||| hi"));
+    assert!(command::has_actions("PRESS: enter"));
+    assert!(command::has_actions("WAIT: 500"));
+    assert!(!command::has_actions("Hello there!"));
+    assert!(!command::has_actions("Press enter to continue"));
+    // NEWS / CALENDAR は NAME: か 1 行に NAME だけ
+    assert!(command::has_actions("NEWS"));
+    assert!(command::has_actions("NEWS: rust"));
+    assert!(!command::has_actions("The NEWS today is about rust"));
+    assert!(!command::has_actions("My CALENDAR is full"));
+}
+
+// ---------- NAME ----------
+
+#[test]
+fn execute_prefix_is_dropped() {
+    let cmd = Command::parse("EXECUTE SAVE: notes.md [append] ||| hello");
+    assert_eq!(
+        cmd.action,
+        Action::Save {
+            path: s("notes.md"),
+            mode: Mode::Append,
+            content: s("hello")
+        }
+    );
+    assert_eq!(cmd.name(), "SAVE");
+    assert_eq!(cmd.label(), "EXECUTE SAVE: notes.md [append]");
+}
+
+#[test]
+fn prose_is_unknown() {
+    assert_eq!(action("Hello! How can I help?"), Action::Unknown);
+    assert_eq!(action("LOOKUP the word"), Action::Unknown);
+    assert_eq!(action("APPS2"), Action::Unknown);
+    assert_eq!(action("I"), Action::Unknown);
+}
+
+#[test]
+fn unknown_action_is_invalid() {
+    assert_eq!(invalid("OPEN_URL: https://x"), "unknown action 'OPEN_URL'");
+}
+
+#[test]
+fn loose_arguments() {
+    assert_eq!(
+        action("PROCESSES"),
+        Action::Processes {
+            sort_by: s("memory")
+        }
+    );
+    assert_eq!(
+        action("PROCESSES cpu"),
+        Action::Processes { sort_by: s("cpu") }
+    );
+    assert_eq!(
+        action("PROCESSES: cpu"),
+        Action::Processes { sort_by: s("cpu") }
+    );
+    assert_eq!(action("DISK"), Action::Disk { path: s("") });
+    assert_eq!(
+        action(r"DISK: C:\Users"),
+        Action::Disk {
+            path: s(r"C:\Users")
+        }
+    );
+    assert_eq!(action("ACTIVITY"), Action::Activity { day: None });
+    assert_eq!(
+        action("ACTIVITY: yesterday"),
+        Action::Activity {
+            day: Some(s("yesterday"))
+        }
+    );
+    assert_eq!(action("WEATHER"), Action::Weather { place: s("") });
+    assert_eq!(action("NEWS: rust"), Action::News { topic: s("rust") });
+    assert_eq!(action("NEWS"), Action::News { topic: s("") });
+    assert_eq!(action("NEWS today is quiet"), Action::Unknown);
+    assert_eq!(action("CALENDAR"), Action::Calendar { hours: 24 });
+    assert_eq!(action("CALENDAR: 48"), Action::Calendar { hours: 48 });
+    assert_eq!(action("CALENDAR looks busy"), Action::Unknown);
+    assert_eq!(action("CHECK_EMAIL"), Action::CheckEmail { limit: 10 });
+    assert_eq!(action("CHECK_EMAIL: 3"), Action::CheckEmail { limit: 3 });
+}
+
+#[test]
+fn bare_actions() {
+    assert_eq!(action("LOOK"), Action::Look);
+    assert_eq!(action("LOOK:"), Action::Look);
+    assert_eq!(action("APPS"), Action::Apps);
+    assert_eq!(action("UNDO_LAST"), Action::UndoLast);
+    assert!(invalid("LOOK around").contains("LOOK takes no argument"));
+}
+
+// ---------- 引数 ----------
+
+#[test]
+fn required_arguments() {
+    assert_eq!(action("EXEC: notepad"), Action::Exec { app: s("notepad") });
+    assert_eq!(
+        action("CLICK: 100,200 right"),
+        Action::Click {
+            target: s("100,200 right")
+        }
+    );
+    assert_eq!(action("PRESS: ctrl+s"), Action::Press { key: s("ctrl+s") });
+    assert_eq!(
+        action("PLAN: set up a rust project"),
+        Action::Plan {
+            goal: s("set up a rust project")
+        }
+    );
+    assert_eq!(
+        action("MACRO: morning city=\"New York\""),
+        Action::Macro {
+            name: s("morning"),
+            params: BTreeMap::from([(s("city"), s("New York"))])
+        }
+    );
+    assert_eq!(
+        action("MACRO: weekly report"),
+        Action::Macro {
+            name: s("weekly report"),
+            params: BTreeMap::new()
+        }
+    );
+    assert_eq!(
+        action("BACKGROUND: RUN: cargo test @ repo"),
+        Action::Background {
+            kind: s("run"),
+            argument: s("cargo test"),
+            cwd: Some(s("repo"))
+        }
+    );
+    assert_eq!(
+        action("BACKGROUND: summarize my inbox"),
+        Action::Background {
+            kind: s("ask"),
+            argument: s("summarize my inbox"),
+            cwd: None
+        }
+    );
+    assert_eq!(
+        action("GIT: Status axis"),
+        Action::Git {
+            op: s("status"),
+            repo: s("axis")
+        }
+    );
+    assert_eq!(
+        action("GIT: repos"),
+        Action::Git {
+            op: s("repos"),
+            repo: s("")
+        }
+    );
+    assert_eq!(
+        action("COMMIT: axis ||| fix typo"),
+        Action::Commit {
+            repo: s("axis"),
+            message: s("fix typo")
+        }
+    );
+    assert_eq!(
+        action("HA: turn_on light.living brightness_pct=40"),
+        Action::HomeAssistant {
+            service: s("turn_on"),
+            entity_id: s("light.living"),
+            data: BTreeMap::from([(s("brightness_pct"), s("40"))])
+        }
+    );
+    assert_eq!(
+        action("HA: states"),
+        Action::HomeAssistant {
+            service: s("states"),
+            entity_id: s(""),
+            data: BTreeMap::new()
+        }
+    );
+    assert_eq!(
+        action("MEDIA: vol 30%"),
+        Action::Media {
+            action: s("volume"),
+            volume: Some(30)
+        }
+    );
+    assert_eq!(
+        action("MEDIA: pause"),
+        Action::Media {
+            action: s("pause"),
+            volume: None
+        }
+    );
+    assert_eq!(action("WAIT: 500"), Action::Wait { ms: 500 });
+    assert_eq!(
+        action("WAITFOR: window Notepad 5s"),
+        Action::WaitFor {
+            kind: s("window"),
+            target: s("Notepad"),
+            timeout_secs: 5
+        }
+    );
+    assert_eq!(
+        action(r#"WAITFOR: text "Build succeeded" 30s"#),
+        Action::WaitFor {
+            kind: s("text"),
+            target: s("Build succeeded"),
+            timeout_secs: 30
+        }
+    );
+    assert_eq!(
+        action("WAITFOR: Window Untitled - Notepad"),
+        Action::WaitFor {
+            kind: s("window"),
+            target: s("Untitled - Notepad"),
+            timeout_secs: command::WAITFOR_DEFAULT_SECS
+        }
+    );
+    // 秒数は "s" 付きだけ。数字で終わる名前はそのまま待つ先に
+    assert_eq!(
+        action("WAITFOR: window Windows 11"),
+        Action::WaitFor {
+            kind: s("window"),
+            target: s("Windows 11"),
+            timeout_secs: command::WAITFOR_DEFAULT_SECS
+        }
+    );
+    assert_eq!(
+        action("FETCH: https://example.com/a:b"),
+        Action::Fetch {
+            url: s("https://example.com/a:b")
+        }
+    );
+}
+
+#[test]
+fn colons_in_arguments_are_kept() {
+    assert_eq!(
+        action("SEARCH: rust: the book"),
+        Action::Search {
+            query: s("rust: the book")
+        }
+    );
+    assert_eq!(
+        action(r"EXEC: C:\Tools\app.exe"),
+        Action::Exec {
+            app: s(r"C:\Tools\app.exe")
+        }
+    );
+}
+
+#[test]
+fn quoted_arguments() {
+    assert_eq!(
+        action(r#"EXEC: "C:\Program Files\App\app.exe""#),
+        Action::Exec {
+            app: s(r"C:\Program Files\App\app.exe")
+        }
+    );
+    assert_eq!(
+        action(r#"SEARCH: "say \"hi\" \\ bye""#),
+        Action::Search {
+            query: s(r#"say "hi" \ bye"#)
+        }
+    );
+    // 一部だけ囲んだものはそのまま
+    assert_eq!(
+        action(r#"SEARCH: "rust" book"#),
+        Action::Search {
+            query: s(r#""rust" book"#)
+        }
+    );
+    assert_eq!(
+        action(r#"SAVE: "my notes.md" ||| a "quoted" word"#),
+        Action::Save {
+            path: s("my notes.md"),
+            mode: Mode::Create,
+            content: s(r#"a "quoted" word"#)
+        }
+    );
+}
+
+#[test]
+fn type_splits_target() {
+    assert_eq!(
+        action("TYPE: hello @ Notepad"),
+        Action::Type {
+            text: s("hello"),
+            target: Some(s("Notepad"))
+        }
+    );
+    assert_eq!(
+        action("TYPE: hello"),
+        Action::Type {
+            text: s("hello"),
+            target: None
+        }
+    );
+    // メールアドレスの @ では切らない
+    assert_eq!(
+        action("TYPE: me@example.com @ Outlook"),
+        Action::Type {
+            text: s("me@example.com"),
+            target: Some(s("Outlook"))
+        }
+    );
+    assert_eq!(
+        action("TYPE: me@example.com"),
+        Action::Type {
+            text: s("me@example.com"),
+            target: None
+        }
+    );
+    assert_eq!(
+        action(r#"TYPE: "a @ b" @ current"#),
+        Action::Type {
+            text: s("a @ b"),
+            target: Some(s("current"))
+        }
+    );
+}
+
+#[test]
+fn templates() {
+    assert_eq!(
+        action("GENERATE_FROM_TEMPLATE: invoice > acme.pdf ||| {\"to\": \"A && B\"}"),
+        Action::Template {
+            name: s("invoice > acme.pdf"),
+            fields: s("{\"to\": \"A && B\"}")
+        }
+    );
+}
+
+#[test]
+fn confirmable_actions_keep_their_name() {
+    let cmd = Command::parse("KILL: chrome.exe");
+    assert_eq!(
+        cmd.action,
+        Action::Kill {
+            target: s("chrome.exe")
+        }
+    );
+    assert_eq!(cmd.name(), "KILL");
+    assert_eq!(cmd.argument(), "chrome.exe");
+    assert_eq!(
+        action(r#"OPEN_FILE: "report (2).md""#),
+        Action::OpenFile {
+            path: s("report (2).md")
+        }
+    );
+}
+
+// ---------- 書式の誤り ----------
+
+#[test]
+fn validation_errors_show_the_format() {
+    assert_eq!(
+        invalid("SAVE: notes.md"),
+        "SAVE is missing '|||' (use 'SAVE: <filename> [mode] ||| <content>')"
+    );
+    assert!(invalid("GENERATE_FROM_TEMPLATE: invoice").contains("'|||'"));
+    assert!(invalid("SAVE:  ||| hi").contains("missing the name"));
+    assert!(invalid("SCHEDULE: 17:00 stand up").contains("'|||'"));
+    assert!(invalid("COMMIT: fix typo").contains("'|||'"));
+    assert!(invalid("COMMIT: axis |||").contains("needs a message"));
+    assert!(invalid("SAVE: ../x.txt ||| hi").contains("outside the Desktop"));
+    assert!(invalid("SAVE: a.txt [merge] ||| hi").contains("unknown SAVE mode"));
+    assert!(invalid("MACRO: morning city=Tokyo late").contains("not key=value"));
+    assert!(invalid("GIT: push axis").contains("unknown GIT command"));
+    assert!(invalid("HA: turn_on").contains("<service> <entity_id>"));
+    assert!(invalid("MEDIA: louder").contains("unknown media command"));
+    assert!(invalid(r#"BACKGROUND: RUN: """#).contains("RUN needs a command"));
+    assert_eq!(
+        invalid("EXEC notepad"),
+        "EXEC needs ':' (use 'EXEC: <app>')"
+    );
+    assert!(invalid("EXEC:").contains("EXEC needs an argument"));
+    assert!(invalid("SEARCH: \"\"").contains("needs an argument"));
+    assert!(invalid("TYPE: @ Notepad").contains("TYPE needs some text"));
+    assert!(invalid("WAIT: soon").contains("WAIT takes milliseconds"));
+    assert!(invalid("WAIT: 3600000").contains("at most 60000 ms"));
+    assert!(invalid("CALENDAR: tomorrow").contains("number of hours"));
+    assert!(invalid("CHECK_EMAIL: all").contains("number of messages"));
+    assert!(invalid("FETCH: example.com").contains("not an http(s) URL"));
+    assert!(invalid("PRESS: ctrl+hyper").contains("unknown key 'hyper'"));
+    assert!(invalid("WAITFOR: pixel 10,10").contains("window or text"));
+    assert!(invalid("WAITFOR: window").contains("needs something to wait for"));
+    assert!(invalid("WAITFOR: text Done 600s").contains("1-120 seconds"));
+}
+
+#[test]
+fn unterminated_quotes_are_invalid() {
+    assert_eq!(
+        invalid(r#"EXEC: "notepad"#),
+        r#"unterminated quote in "notepad"#
+    );
+    // 閉じていない引用は後ろの && も飲み込む
+    let cmds = command::parse_response(r#"SEARCH: "rust && LOOK"#);
+    assert_eq!(cmds.len(), 1);
+    assert!(matches!(cmds[0].action, Action::Invalid { .. }));
+}
+
+#[test]
+fn untrusted_sources() {
+    assert_eq!(
+        Command::parse("SEARCH: x").untrusted_source(),
+        Some("search")
+    );
+    assert_eq!(
+        Command::parse("FETCH: https://example.com").untrusted_source(),
+        Some("web_page")
+    );
+    assert_eq!(
+        Command::parse("CHECK_EMAIL").untrusted_source(),
+        Some("email")
+    );
+    assert_eq!(
+        Command::parse("BROWSER_TAB").untrusted_source(),
+        Some("browser_tab")
+    );
+    assert_eq!(Command::parse("LOOK").untrusted_source(), Some("screen"));
+    assert_eq!(
+        Command::parse("WAITFOR: text Done 5s").untrusted_source(),
+        Some("screen_text")
+    );
+    assert_eq!(
+        Command::parse("WAITFOR: window Notepad").untrusted_source(),
+        None
+    );
+    assert_eq!(Command::parse("EXEC: notepad").untrusted_source(), None);
+}
+
+#[test]
+fn long_labels_are_clipped() {
+    let cmd = Command::parse(&format!("TYPE: {}", "a".repeat(200)));
+    assert_eq!(cmd.label().chars().count(), 121);
+    assert!(cmd.label().ends_with('…'));
+}
+
+// ---------- Action ----------
+
+#[test]
+fn action_name_matches_the_grammar() {
+    for cmd in [
+        "LOOK",
+        "PROCESSES",
+        "CHECK_EMAIL: 3",
+        "SAVE: a.txt ||| x",
+        "GENERATE_FROM_TEMPLATE: invoice ||| {}",
+        "KILL: chrome.exe",
+        "REVEAL_IN_EXPLORER: a.txt",
+        "HA: light.turn_on light.living",
+        "UNDO_LAST",
+        "WAIT: 500",
+    ] {
+        let cmd = Command::parse(cmd);
+        assert_eq!(cmd.action.name(), cmd.name(), "{}", cmd.raw);
+    }
+}
+
+#[test]
+fn action_argument_drops_content() {
+    assert_eq!(action("SAVE: a.md ||| secret").argument(), "a.md");
+    assert_eq!(
+        action("SAVE: a.md [overwrite] ||| secret").argument(),
+        "a.md [overwrite]"
+    );
+    assert_eq!(
+        action("RUN: cargo test @ repo").argument(),
+        "cargo test @ repo"
+    );
+    assert_eq!(
+        action("HA: turn_on light.living brightness_pct=40").argument(),
+        "turn_on light.living brightness_pct=40"
+    );
+    assert_eq!(
+        action("TYPE: me@example.com @ Outlook").argument(),
+        "me@example.com @ Outlook"
+    );
+    assert_eq!(action("CALENDAR").argument(), "24");
+    assert_eq!(action("LOOK").argument(), "");
+}
+
+#[test]
+fn actions_serialize_with_their_name() {
+    let json = serde_json::to_value(action("KILL: chrome.exe")).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"type": "KILL", "target": "chrome.exe"})
+    );
+    let json = serde_json::to_value(action("TYPE: hi @ Notepad")).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"type": "TYPE", "text": "hi", "target": "Notepad"})
+    );
+    assert_eq!(serde_json::to_value(Action::Look).unwrap()["type"], "LOOK");
+
+    for cmd in [
+        "GENERATE_FROM_TEMPLATE: invoice ||| {}",
+        "REVEAL_IN_EXPLORER: a.txt",
+        "HA: light.turn_on light.living",
+        "SAVE: a.txt [append] ||| x",
+        "MACRO: morning city=Tokyo",
+        "RUN: cargo test @ repo",
+        "ACTIVITY",
+    ] {
+        let a = action(cmd);
+        let json = serde_json::to_value(&a).unwrap();
+        assert_eq!(json["type"], a.name());
+        assert_eq!(serde_json::from_value::<Action>(json).unwrap(), a);
+    }
+}
+
+// ---------- プラグイン ----------
+
+#[test]
+fn plugin_actions_parse_once_registered() {
+    assert_eq!(invalid("JIRA: create x"), "unknown action 'JIRA'");
+    command::set_plugin_actions(vec![s("JIRA"), s("NOTION")]);
+    assert!(command::has_actions("JIRA: create Fix login"));
+    assert!(!command::is_builtin("JIRA"));
+    assert_eq!(
+        actions(r#"JIRA: "create Fix && login" && NOTION: append Notes && LOOK"#),
+        [
+            Action::Plugin {
+                name: s("JIRA"),
+                argument: s("create Fix && login")
+            },
+            Action::Plugin {
+                name: s("NOTION"),
+                argument: s("append Notes")
+            },
+            Action::Look
+        ]
+    );
+    // content の中でも次のアクションとして切る
+    assert_eq!(actions("SAVE: a.md ||| x && JIRA: create y").len(), 2);
+    let cmd = Command::parse("EXECUTE JIRA: create y");
+    assert_eq!(cmd.action.name(), "JIRA");
+    assert_eq!(cmd.untrusted_source(), Some("plugin"));
+    assert!(invalid("JIRA create").contains("JIRA needs ':'"));
+    assert_eq!(
+        serde_json::to_value(&cmd.action).unwrap(),
+        serde_json::json!({"type": "PLUGIN", "name": "JIRA", "argument": "create y"})
+    );
+}
+
+#[test]
+fn image_gen_takes_an_optional_aspect() {
+    assert_eq!(
+        action("IMAGE_GEN: a cozy desk at night, flat illustration"),
+        Action::ImageGen {
+            prompt: s("a cozy desk at night, flat illustration"),
+            aspect: None
+        }
+    );
+    assert_eq!(
+        action("IMAGE_GEN: thumbnail for a Rust post ||| 16:9"),
+        Action::ImageGen {
+            prompt: s("thumbnail for a Rust post"),
+            aspect: Some(s("16:9"))
+        }
+    );
+    assert!(invalid("IMAGE_GEN: a cat ||| wide").contains("not an aspect ratio"));
+    assert!(invalid("IMAGE_GEN: ||| 1:1").contains("needs a prompt"));
+}
+
+#[test]
+fn record_screen_defaults_to_ten_seconds() {
+    assert_eq!(
+        action("RECORD_SCREEN"),
+        Action::RecordScreen { seconds: 10 }
+    );
+    assert_eq!(
+        action("RECORD_SCREEN: 20"),
+        Action::RecordScreen { seconds: 20 }
+    );
+    assert!(invalid("RECORD_SCREEN: a while").contains("number of seconds"));
+    assert!(invalid("RECORD_SCREEN: 0").contains("number of seconds"));
+}
+
+#[test]
+fn learn_alias_splits_on_equals() {
+    assert_eq!(
+        action("LEARN_ALIAS: Browser = chrome.exe"),
+        Action::LearnAlias {
+            name: s("browser"),
+            target: s("chrome.exe"),
+        }
+    );
+    assert_eq!(
+        action(r#"LEARN_ALIAS: "a = b" = "C:\Tools\ab.exe""#),
+        Action::LearnAlias {
+            name: s("a = b"),
+            target: s(r"C:\Tools\ab.exe"),
+        }
+    );
+    assert!(invalid("LEARN_ALIAS: browser chrome").contains("'<name> = <app>'"));
+    assert!(invalid("LEARN_ALIAS: browser =").contains("needs an app"));
+    assert!(invalid("LEARN_ALIAS: code = Code").contains("alias of itself"));
+}
@@ -0,0 +1,43 @@
+// Worker / Commander に渡す文脈 (context.rs)
+
+use axis_core::context::{self, Turn};
+
+fn turn(n: usize) -> Turn {
+    Turn {
+        user: format!("q{}", n),
+        axis: format!("a{}", n),
+    }
+}
+
+#[test]
+fn empty_history_is_none() {
+    assert_eq!(context::history_text(&[]), "None");
+}
+
+#[test]
+fn keeps_the_last_turns_in_order() {
+    let turns: Vec<Turn> = (1..=7).map(turn).collect();
+    let text = context::history_text(&turns);
+    assert!(!text.contains("q2"));
+    assert!(text.starts_with("User: q3\nAxis: a3"));
+    assert!(text.ends_with("User: q7\nAxis: a7"));
+    assert_eq!(text.matches("\n---\n").count(), context::HISTORY_TURNS - 1);
+}
+
+#[test]
+fn recall_skips_what_is_already_in_history() {
+    let history = context::history_text(&[turn(1)]);
+    assert_eq!(context::recall_section(&["q1".to_string()], &history), "");
+    let recall = context::recall_section(&["x".repeat(400), "q1".to_string()], &history);
+    assert!(recall.starts_with("[Recall: related past messages]\n- "));
+    assert_eq!(recall.lines().count(), 2);
+    assert_eq!(recall.lines().nth(1).unwrap().chars().count(), 302);
+}
+
+#[test]
+fn task_input_layout() {
+    assert_eq!(
+        context::task_input("None", "mem", "", "hello"),
+        "Context:\nNone\nmem\n\n\nUser Request: hello"
+    );
+}
@@ -0,0 +1,93 @@
+// Commander の振り分け結果の読み出しと振り替え (dispatch.rs)
+
+use axis_core::dispatch::{self, Availability, RoutingDecision};
+
+struct Mock {
+    offline: &'static [&'static str],
+    unhealthy: &'static [&'static str],
+}
+
+impl Availability for Mock {
+    fn reachable(&self, provider: &str) -> bool {
+        !self.offline.contains(&provider)
+    }
+
+    fn unavailable(&self, provider: &str) -> Option<(&'static str, String)> {
+        self.unhealthy
+            .contains(&provider)
+            .then(|| ("health", format!("{} keeps timing out", provider)))
+    }
+}
+
+fn decision(target: &str) -> RoutingDecision {
+    RoutingDecision {
+        target: target.to_string(),
+        strategy: dispatch::default_strategy(),
+        reason: String::new(),
+        task_type: "chat".to_string(),
+    }
+}
+
+#[test]
+fn reads_json_inside_prose() {
+    let d = dispatch::parse_decision(
+        "Sure! {\"target\": \"gemini\", \"task_type\": \"research\"} hope that helps",
+    );
+    assert_eq!(d.target, "gemini");
+    assert_eq!(d.strategy, "general");
+    assert_eq!(d.reason, "Default decision");
+    assert_eq!(d.task_type, "research");
+}
+
+#[test]
+fn falls_back_to_gpt() {
+    let d = dispatch::parse_decision("I think grok would be best");
+    assert_eq!(d.target, "gpt");
+    assert_eq!(d.strategy, "fallback");
+    assert_eq!(d.reason, "JSON Parse Failed");
+    assert_eq!(d.task_type, "unknown");
+}
+
+#[test]
+fn unreachable_goes_local() {
+    let mut d = decision("grok");
+    let mock = Mock {
+        offline: &["grok"],
+        unhealthy: &[],
+    };
+    dispatch::reroute(&mut d, &mock, false);
+    assert_eq!(d.target, "local");
+    assert_eq!(d.strategy, "offline");
+}
+
+#[test]
+fn unhealthy_goes_to_another_cloud() {
+    let mut d = decision("gpt");
+    let mock = Mock {
+        offline: &["gemini"],
+        unhealthy: &["gpt"],
+    };
+    dispatch::reroute(&mut d, &mock, false);
+    assert_eq!(d.target, "grok");
+    assert_eq!(d.strategy, "health");
+
+    let mut d = decision("gpt");
+    let mock = Mock {
+        offline: &[],
+        unhealthy: &["gpt", "gemini", "grok"],
+    };
+    dispatch::reroute(&mut d, &mock, false);
+    assert_eq!(d.target, "local");
+}
+
+#[test]
+fn requested_provider_is_kept() {
+    let mut d = decision("gpt");
+    let mock = Mock {
+        offline: &[],
+        unhealthy: &["gpt"],
+    };
+    dispatch::reroute(&mut d, &mock, true);
+    assert_eq!(d.target, "gpt");
+    assert_eq!(d.strategy, "general");
+}
@@ -0,0 +1,88 @@
+// SAVE で書く中身 (files.rs)。MemoryFs の上で
+
+use axis_core::files::{self, Mode};
+use axis_core::fs::MemoryFs;
+use std::path::{Path, PathBuf};
+
+fn text(content: &str) -> Result<Vec<u8>, String> {
+    Ok(content.as_bytes().to_vec())
+}
+
+fn plan(fs: &MemoryFs, name: &str, mode: Mode, content: &str) -> Result<(PathBuf, String), String> {
+    let path = Path::new("/out").join(name);
+    files::plan_write(fs, &path, name, mode, content, text)
+        .map(|(path, bytes)| (path, String::from_utf8(bytes).unwrap()))
+}
+
+#[test]
+fn parses_target_and_mode() {
+    assert_eq!(
+        files::parse_target(" report.md [append] ").unwrap(),
+        ("report.md".to_string(), Mode::Append)
+    );
+    assert_eq!(
+        files::parse_target("report.md").unwrap(),
+        ("report.md".to_string(), Mode::Create)
+    );
+    assert!(files::parse_target("report.md [shred]").is_err());
+    assert!(files::parse_target(" [new]").is_err());
+    assert!(files::parse_target("notes/today.md").is_ok());
+    assert!(files::parse_target("../proj/package.json").is_err());
+    assert!(files::parse_target("/etc/profile").is_err());
+    assert!(files::parse_target(r"C:\proj\run.bat [overwrite]").is_err());
+}
+
+#[test]
+fn create_refuses_existing_file() {
+    let fs = MemoryFs::new();
+    fs.insert("/out/memo.txt", "old");
+    let err = plan(&fs, "memo.txt", Mode::Create, "new").unwrap_err();
+    assert!(err.contains("already exists (3 bytes)"));
+    assert!(err.contains("SAVE: memo.txt [overwrite|append|new|patch]"));
+
+    let (path, content) = plan(&fs, "memo.txt", Mode::Overwrite, "new").unwrap();
+    assert_eq!(path, Path::new("/out/memo.txt"));
+    assert_eq!(content, "new");
+}
+
+#[test]
+fn append_adds_missing_newline() {
+    let fs = MemoryFs::new();
+    fs.insert("/out/log.txt", "first");
+    let (_, content) = plan(&fs, "log.txt", Mode::Append, "second").unwrap();
+    assert_eq!(content, "first\nsecond\n");
+
+    let (_, content) = plan(&fs, "fresh.txt", Mode::Append, "only").unwrap();
+    assert_eq!(content, "only\n");
+}
+
+#[test]
+fn new_picks_an_unused_name() {
+    let fs = MemoryFs::new();
+    fs.insert("/out/memo.txt", "1");
+    fs.insert("/out/memo (2).txt", "2");
+    let (path, _) = plan(&fs, "memo.txt", Mode::New, "3").unwrap();
+    assert_eq!(path, Path::new("/out/memo (3).txt"));
+
+    let (path, _) = plan(&fs, "other.txt", Mode::New, "x").unwrap();
+    assert_eq!(path, Path::new("/out/other.txt"));
+}
+
+#[test]
+fn patch_applies_unified_diff() {
+    let fs = MemoryFs::new();
+    fs.insert("/out/a.rs", "fn main() {\n    old();\n}\n");
+    let patch =
+        "--- a/a.rs\n+++ b/a.rs\n@@ -1,3 +1,3 @@\n fn main() {\n-    old();\n+    new();\n }\n";
+    let (_, content) = plan(&fs, "a.rs", Mode::Patch, patch).unwrap();
+    assert_eq!(content, "fn main() {\n    new();\n}\n");
+
+    assert!(plan(&fs, "missing.rs", Mode::Patch, patch).is_err());
+    assert!(plan(&fs, "a.rs", Mode::Patch, "@@ -1 +1 @@\n-nothing\n+x\n").is_err());
+}
+
+#[test]
+fn patch_keeps_crlf() {
+    let patched = files::apply_patch("a\r\nb\r\n", "@@ -2 +2 @@\n-b\n+c\n").unwrap();
+    assert_eq!(patched, "a\r\nc\r\n");
+}
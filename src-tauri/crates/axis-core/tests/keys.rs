@@ -0,0 +1,80 @@
+// PRESS: のキーの書式 (keys.rs)
+
+use axis_core::keys::{self, Key, Modifier, Press};
+
+fn press(modifiers: &[Modifier], key: Key, times: u32) -> Press {
+    Press {
+        modifiers: modifiers.to_vec(),
+        key,
+        times,
+    }
+}
+
+#[test]
+fn single_keys_and_chords() {
+    assert_eq!(keys::parse("Enter"), Ok(vec![press(&[], Key::Enter, 1)]));
+    assert_eq!(
+        keys::parse("ctrl+shift+s"),
+        Ok(vec![press(
+            &[Modifier::Ctrl, Modifier::Shift],
+            Key::Char('s'),
+            1
+        )])
+    );
+    assert_eq!(
+        keys::parse("Alt + Left"),
+        Ok(vec![press(&[Modifier::Alt], Key::Left, 1)])
+    );
+    assert_eq!(keys::parse("F5"), Ok(vec![press(&[], Key::F(5), 1)]));
+    assert_eq!(
+        keys::parse("ctrl+plus"),
+        Ok(vec![press(&[Modifier::Ctrl], Key::Char('+'), 1)])
+    );
+}
+
+#[test]
+fn modifiers_alone_are_pressed_as_keys() {
+    assert_eq!(
+        keys::parse("windows"),
+        Ok(vec![press(&[], Key::Modifier(Modifier::Meta), 1)])
+    );
+    assert_eq!(
+        keys::parse("alt+shift"),
+        Ok(vec![press(
+            &[Modifier::Alt],
+            Key::Modifier(Modifier::Shift),
+            1
+        )])
+    );
+}
+
+#[test]
+fn repeats_and_sequences() {
+    assert_eq!(keys::parse("tab x3"), Ok(vec![press(&[], Key::Tab, 3)]));
+    assert_eq!(
+        keys::parse("ctrl+k, ctrl+c"),
+        Ok(vec![
+            press(&[Modifier::Ctrl], Key::Char('k'), 1),
+            press(&[Modifier::Ctrl], Key::Char('c'), 1)
+        ])
+    );
+    // "x" というキー
+    assert_eq!(
+        keys::parse("shift+x"),
+        Ok(vec![press(&[Modifier::Shift], Key::Char('x'), 1)])
+    );
+}
+
+#[test]
+fn bad_specs_say_why() {
+    assert!(keys::parse("ctrl+a+b")
+        .unwrap_err()
+        .contains("more than one key"));
+    assert!(keys::parse("hyper+q")
+        .unwrap_err()
+        .contains("unknown key 'hyper'"));
+    assert!(keys::parse("f25").unwrap_err().contains("unknown key"));
+    assert!(keys::parse("tab x0").unwrap_err().contains("x1〜x50"));
+    assert!(keys::parse("ctrl++").unwrap_err().contains("'plus'"));
+    assert!(keys::parse("enter,").unwrap_err().contains("empty step"));
+}
@@ -0,0 +1,137 @@
+// 記録からの再生 (replay.rs)。振り分け → アクション → レポートを API なしで
+
+use axis_core::dispatch::RoutingDecision;
+use axis_core::replay::{self, RecordedAction, Recording};
+use axis_core::untrusted;
+
+fn routing(target: &str, strategy: &str) -> Option<RoutingDecision> {
+    Some(RoutingDecision {
+        target: target.to_string(),
+        strategy: strategy.to_string(),
+        reason: String::new(),
+        task_type: "research".to_string(),
+    })
+}
+
+fn recorded(command: &str, result: &str) -> RecordedAction {
+    RecordedAction {
+        command: command.to_string(),
+        result: result.to_string(),
+    }
+}
+
+fn calendar_recording() -> Recording {
+    let calendar = untrusted::wrap("calendar", "- 10:00 Rust 1.80 release party\n");
+    Recording {
+        routing_raw: "{\"target\": \"gemini\", \"task_type\": \"research\"}".to_string(),
+        routing: routing("gemini", "general"),
+        worker_output: "CALENDAR && EXEC: notepad".to_string(),
+        actions: vec![
+            recorded("CALENDAR", calendar.trim()),
+            recorded(
+                "EXEC: notepad",
+                "[Policy] EXEC notepad was proposed in a session that has read untrusted web / document content, so it requires user confirmation (pending id=1). Ask the user to approve it in the confirmation dialog.",
+            ),
+        ],
+        report: "The release party is at 10:00. I need your OK to open Notepad.".to_string(),
+        tainted: true,
+    }
+}
+
+#[tokio::test]
+async fn replays_the_whole_pipeline() {
+    let recording = calendar_recording();
+    let replayed = replay::replay(&recording, true).await;
+    assert_eq!(replayed.decision.target, "gemini");
+    assert_eq!(replayed.actions, ["CALENDAR"]);
+    assert_eq!(replayed.held, ["EXEC: notepad"]);
+    assert_eq!(replayed.answer, recording.report);
+    assert!(replayed.mismatches.is_empty(), "{:?}", replayed.mismatches);
+}
+
+#[tokio::test]
+async fn reports_policy_changes() {
+    // 汚れたセッションでも確認しない設定だと、記録では保留した EXEC が実行される
+    let replayed = replay::replay(&calendar_recording(), false).await;
+    assert_eq!(replayed.actions, ["CALENDAR", "EXEC: notepad"]);
+    assert!(replayed.held.is_empty());
+    assert!(replayed.mismatches.is_empty());
+
+    // 逆に、記録では実行したものが今は保留される
+    let mut recording = calendar_recording();
+    recording.actions[1].result = "Launched: notepad".to_string();
+    let replayed = replay::replay(&recording, true).await;
+    assert_eq!(
+        replayed.mismatches,
+        ["action 2: 'EXEC: notepad' was held but ran when recorded"]
+    );
+}
+
+#[tokio::test]
+async fn reports_routing_and_action_drift() {
+    let mut recording = calendar_recording();
+    recording.routing = routing("gpt", "general");
+    recording
+        .actions
+        .push(recorded("LOOK", "[System] Analyzed screen."));
+    let replayed = replay::replay(&recording, true).await;
+    assert_eq!(
+        replayed.mismatches,
+        [
+            "routing: gemini but gpt was recorded",
+            "recorded action 'LOOK' was not run"
+        ]
+    );
+
+    let mut recording = calendar_recording();
+    recording.worker_output = "CALENDAR: 48".to_string();
+    let replayed = replay::replay(&recording, true).await;
+    assert_eq!(
+        replayed.mismatches,
+        [
+            "action 1: ran 'CALENDAR: 48' but 'CALENDAR' was recorded",
+            "recorded action 'EXEC: notepad' was not run"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn keeps_environment_reroutes() {
+    let mut recording = calendar_recording();
+    recording.routing = routing("local", "offline");
+    let replayed = replay::replay(&recording, true).await;
+    assert_eq!(replayed.decision.target, "local");
+    assert!(replayed.mismatches.is_empty());
+}
+
+#[tokio::test]
+async fn plain_answers_skip_the_report() {
+    let recording = Recording {
+        routing_raw: "not json".to_string(),
+        routing: routing("gpt", "fallback"),
+        worker_output: "Hello! How can I help?".to_string(),
+        ..Default::default()
+    };
+    let replayed = replay::replay(&recording, true).await;
+    assert_eq!(replayed.decision.strategy, "fallback");
+    assert_eq!(replayed.answer, "Hello! How can I help?");
+    assert!(replayed.actions.is_empty());
+    assert!(replayed.mismatches.is_empty());
+}
+
+#[tokio::test]
+async fn missing_report_falls_back_to_done() {
+    let mut recording = calendar_recording();
+    recording.report.clear();
+    assert_eq!(replay::replay(&recording, true).await.answer, "Done.");
+}
+
+#[test]
+fn unwrap_returns_the_sealed_body() {
+    let wrapped = untrusted::wrap("news", "headline one\nheadline two\n");
+    assert_eq!(untrusted::unwrap(&wrapped), "headline one\nheadline two");
+    assert_eq!(untrusted::unwrap("[System] ok"), "[System] ok");
+    // trace で切られて終わりの印が無いもの
+    let clipped = &wrapped[..wrapped.find("<<<END").unwrap()];
+    assert_eq!(untrusted::unwrap(clipped), "headline one\nheadline two");
+}
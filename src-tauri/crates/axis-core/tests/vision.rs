@@ -0,0 +1,169 @@
+// 画像を読むモデルの選び方とフォールバック (vision.rs)
+
+use axis_core::vision::{self, UiNode, VisionModel, VisionScore};
+use std::sync::Mutex;
+
+struct Mock {
+    failing: Vec<&'static str>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl Mock {
+    fn new(failing: &[&'static str]) -> Self {
+        Self {
+            failing: failing.to_vec(),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl VisionModel for Mock {
+    async fn describe(&self, name: &str, _prompt: &str, _png: &str) -> Result<String, String> {
+        self.calls.lock().unwrap().push(name.to_string());
+        if self.failing.contains(&name) {
+            Err(format!("{} is down", name))
+        } else {
+            Ok(format!("seen by {}", name))
+        }
+    }
+}
+
+fn score(quality: f32, speed: f32, cost: f32) -> VisionScore {
+    VisionScore {
+        quality,
+        speed,
+        cost,
+    }
+}
+
+fn candidates() -> Vec<(String, VisionScore)> {
+    vec![
+        ("local".to_string(), score(0.6, 0.6, 1.0)),
+        ("gemini".to_string(), score(0.88, 0.92, 0.85)),
+        ("gpt".to_string(), score(0.9, 0.75, 0.6)),
+    ]
+}
+
+fn names(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn auto_orders_by_weighted_score() {
+    assert_eq!(
+        vision::order("auto", &candidates()),
+        names(&["gemini", "gpt", "local"])
+    );
+}
+
+#[test]
+fn preferred_model_goes_first() {
+    assert_eq!(
+        vision::order("local", &candidates()),
+        names(&["local", "gemini", "gpt"])
+    );
+}
+
+#[test]
+fn unknown_preference_falls_back_to_scores() {
+    assert_eq!(
+        vision::order("claude", &candidates()),
+        names(&["gemini", "gpt", "local"])
+    );
+}
+
+#[tokio::test]
+async fn falls_back_when_one_errors() {
+    let mock = Mock::new(&["gemini"]);
+    let run = vision::describe(&mock, &names(&["gemini", "gpt"]), "Describe.", "png").await;
+    assert_eq!(run.result, Ok("seen by gpt".to_string()));
+    assert_eq!(run.used.as_deref(), Some("gpt"));
+    assert_eq!(run.failures, names(&["gemini: gemini is down"]));
+    assert_eq!(*mock.calls.lock().unwrap(), names(&["gemini", "gpt"]));
+}
+
+#[tokio::test]
+async fn stops_at_the_first_success() {
+    let mock = Mock::new(&[]);
+    let run = vision::describe(&mock, &names(&["gpt", "local"]), "Describe.", "png").await;
+    assert_eq!(run.used.as_deref(), Some("gpt"));
+    assert_eq!(*mock.calls.lock().unwrap(), names(&["gpt"]));
+}
+
+#[tokio::test]
+async fn reports_every_failure_when_all_fail() {
+    let mock = Mock::new(&["gpt", "local"]);
+    let run = vision::describe(&mock, &names(&["gpt", "local"]), "Describe.", "png").await;
+    assert!(run.used.is_none());
+    let err = run.result.unwrap_err();
+    assert!(err.contains("gpt: gpt is down") && err.contains("local: local is down"));
+}
+
+#[tokio::test]
+async fn empty_order_is_an_error() {
+    let mock = Mock::new(&[]);
+    let run = vision::describe(&mock, &[], "Describe.", "png").await;
+    assert_eq!(run.result, Err("no vision model is available".to_string()));
+}
+
+#[test]
+fn keyframes_keep_first_last_and_biggest_changes() {
+    let changes = [0.0, 0.01, 0.30, 0.05, 0.0, 0.20, 0.01];
+    assert_eq!(vision::keyframes(&changes, 4), vec![0, 2, 5, 6]);
+    assert_eq!(vision::keyframes(&changes, 10), vec![0, 2, 3, 5, 6]);
+}
+
+#[test]
+fn keyframes_of_a_still_screen_are_the_ends() {
+    assert_eq!(vision::keyframes(&[0.0, 0.0, 0.001, 0.0], 6), vec![0, 3]);
+    assert_eq!(vision::keyframes(&[0.0], 6), vec![0]);
+    assert!(vision::keyframes(&[], 6).is_empty());
+    assert_eq!(vision::keyframes(&[0.0, 0.5, 0.0], 1), vec![0]);
+}
+
+fn node(role: &str, name: &str, value: &str, children: Vec<UiNode>) -> UiNode {
+    UiNode {
+        role: role.to_string(),
+        name: name.to_string(),
+        value: value.to_string(),
+        children,
+    }
+}
+
+#[test]
+fn outline_indents_named_controls_and_flattens_empty_panes() {
+    let tree = node(
+        "window",
+        "Untitled - Notepad",
+        "",
+        vec![node(
+            "pane",
+            "",
+            "",
+            vec![
+                node("document", "Text Editor", "hello\nworld", vec![]),
+                node(
+                    "button",
+                    "Close",
+                    "",
+                    vec![node("text", "Close", "", vec![])],
+                ),
+            ],
+        )],
+    );
+    assert_eq!(
+        vision::outline(&tree, 1000),
+        "- window \"Untitled - Notepad\"\n  - document \"Text Editor\": hello\n    world\n  - button \"Close\"\n"
+    );
+}
+
+#[test]
+fn outline_stops_at_the_limit() {
+    let items = (0..50)
+        .map(|i| node("list item", &format!("item {}", i), "", vec![]))
+        .collect();
+    let text = vision::outline(&node("list", "", "", items), 100);
+    assert!(text.starts_with("- list item \"item 0\"\n"));
+    assert!(text.ends_with("[... truncated]\n"));
+    assert!(text.len() <= 100 + "[... truncated]\n".len());
+}
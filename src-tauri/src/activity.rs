@@ -0,0 +1,169 @@
+// src-tauri/src/activity.rs
+//
+// Observer が記録するアクティビティ（どのウィンドウに何分いたか）
+// - 記録: observer の監視ループから ActivityRecorder 経由で activity テーブルへ
+// - 参照: get_activity_timeline コマンド / ACTIVITY アクション
+
+use crate::db::AxisDatabase;
+use chrono::{Duration as ChronoDuration, Local, NaiveDate, TimeZone};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::AppHandle;
+use tracing::warn;
+
+// 離席区間に付ける app 名
+pub const IDLE_APP: &str = "idle";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ActivitySpan {
+    pub id: i64,
+    pub title: String,
+    pub app: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub duration_ms: i64,
+}
+
+// 監視ループ専用（スレッド内で DB 接続を持ち回す）
+pub struct ActivityRecorder {
+    db: Option<AxisDatabase>,
+    current: Option<i64>,
+}
+
+impl ActivityRecorder {
+    pub fn new(app: &AppHandle) -> Self {
+        let db = match AxisDatabase::open(app) {
+            Ok(db) => Some(db),
+            Err(e) => {
+                warn!("⚠️ [Activity] DB unavailable, timeline disabled: {}", e);
+                None
+            }
+        };
+        Self { db, current: None }
+    }
+
+    // フォーカスが変わった: 新しい区間を開始
+    pub fn focus(&mut self, title: &str, app: &str) {
+        let Some(db) = &self.db else { return };
+        let now = Local::now().timestamp_millis();
+        if let Some(id) = self.current {
+            let _ = db.touch_activity(id, now);
+        }
+        self.current = db.insert_activity(title, app, now).ok();
+    }
+
+    // 離席: 直前の区間を最終入力時刻で閉じ、空白区間 (app="idle") を開始
+    pub fn away(&mut self, since_ms: i64) {
+        let Some(db) = &self.db else { return };
+        if let Some(id) = self.current {
+            let _ = db.touch_activity(id, since_ms);
+        }
+        self.current = db.insert_activity("Away", IDLE_APP, since_ms).ok();
+    }
+
+    // 復帰: 空白区間を閉じる（次のフォーカスで新しい区間が始まる）
+    pub fn back(&mut self) {
+        self.touch();
+        self.current = None;
+    }
+
+    // 同じウィンドウに滞在中: 終了時刻を延ばす
+    pub fn touch(&mut self) {
+        let (Some(db), Some(id)) = (&self.db, self.current) else {
+            return;
+        };
+        let _ = db.touch_activity(id, Local::now().timestamp_millis());
+    }
+}
+
+// "today" / "yesterday" / "YYYY-MM-DD"（None は今日）
+pub fn parse_day(day: Option<&str>) -> Result<NaiveDate, String> {
+    let today = Local::now().date_naive();
+    match day.map(|d| d.trim().to_lowercase()) {
+        None => Ok(today),
+        Some(d) if d.is_empty() || d == "today" || d == "今日" => Ok(today),
+        Some(d) if d == "yesterday" || d == "昨日" => Ok(today - ChronoDuration::days(1)),
+        Some(d) => NaiveDate::parse_from_str(&d, "%Y-%m-%d")
+            .map_err(|_| format!("invalid day '{}': expected YYYY-MM-DD", d)),
+    }
+}
+
+pub fn day_bounds(date: NaiveDate) -> Result<(i64, i64), String> {
+    let start = date
+        .and_hms_opt(0, 0, 0)
+        .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+        .ok_or_else(|| format!("invalid local date {}", date))?;
+    let end = start + ChronoDuration::days(1);
+    Ok((start.timestamp_millis(), end.timestamp_millis()))
+}
+
+// 指定日の区間一覧（日付境界でクリップ済み）
+pub fn timeline(app: &AppHandle, day: Option<&str>) -> Result<Vec<ActivitySpan>, String> {
+    let (from, to) = day_bounds(parse_day(day)?)?;
+    let db = AxisDatabase::open(app)?;
+    let spans = db.activity_between(from, to).map_err(|e| e.to_string())?;
+
+    Ok(spans
+        .into_iter()
+        .map(|mut s| {
+            s.started_at = s.started_at.max(from);
+            s.ended_at = s.ended_at.min(to);
+            s.duration_ms = (s.ended_at - s.started_at).max(0);
+            s
+        })
+        .collect())
+}
+
+fn fmt_minutes(ms: i64) -> String {
+    let m = ms / 60_000;
+    if m >= 60 {
+        format!("{}h{:02}m", m / 60, m % 60)
+    } else {
+        format!("{}m", m)
+    }
+}
+
+fn fmt_clock(ms: i64) -> String {
+    Local
+        .timestamp_millis_opt(ms)
+        .single()
+        .map(|d| d.format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+// LLM に渡す要約（時系列 + アプリ別合計）
+pub fn summarize(spans: &[ActivitySpan]) -> String {
+    if spans.is_empty() {
+        return "No activity recorded.".to_string();
+    }
+
+    let mut out = String::from("[Timeline] (app \"idle\" = user was away)\n");
+    // 1 分未満の寄り道は省く
+    for s in spans.iter().filter(|s| s.duration_ms >= 60_000).take(40) {
+        out.push_str(&format!(
+            "- {}-{} {} ({}) {}\n",
+            fmt_clock(s.started_at),
+            fmt_clock(s.ended_at),
+            if s.app.is_empty() { "?" } else { &s.app },
+            fmt_minutes(s.duration_ms),
+            s.title
+        ));
+    }
+
+    let mut per_app: HashMap<&str, i64> = HashMap::new();
+    for s in spans.iter().filter(|s| s.app != IDLE_APP) {
+        *per_app.entry(s.app.as_str()).or_default() += s.duration_ms;
+    }
+    let mut totals: Vec<(&str, i64)> = per_app.into_iter().collect();
+    totals.sort_by_key(|t| std::cmp::Reverse(t.1));
+
+    out.push_str("[Totals]\n");
+    for (app, ms) in totals.into_iter().take(10) {
+        out.push_str(&format!(
+            "- {}: {}\n",
+            if app.is_empty() { "?" } else { app },
+            fmt_minutes(ms)
+        ));
+    }
+    out
+}
@@ -0,0 +1,1105 @@
+// src-tauri/src/adapter.rs
+//
+// axis-core の trait の Tauri 側の実装（run_axis から使う）
+// - Worker: provider::ModelProvider。振り分け先ごとに ai.rs / ensemble.rs / Llama を呼ぶ
+//   ensemble の候補と判定は ResponseMeta 用に取っておく
+// - Actions: engine::ActionExecutor。アクション 1 つずつの実際の操作
+//   添付 / 書き出したファイル / 出典を集め、監査記録 (audit.rs) に残す
+// - RecordedActions: 答え直し (regenerate_response) 用。何も実行せず、元の依頼の trace の結果を返す
+//   アクション毎の trace は orchestrator が executor を包んで残す
+// - Session: orchestrator::Host。依頼 1 回分のログ / 設定 / メモリ / プロンプトと、答えの保存
+//   (ログ / trace / 実績 / 記憶。critic の見直しと ensemble の候補も ResponseMeta に)
+//   dispatch::Availability も（system::check_network の到達性と health / budget）
+
+use crate::ai;
+use crate::db::AxisDatabase;
+use crate::filegen::Saved;
+use crate::settings::Settings;
+use crate::storage::{AxisToken, InteractionLog};
+use crate::system::{self, NetworkStatus};
+use crate::AiMessage;
+use crate::{
+    activity, app_catalog, audit, connectors, critic, ensemble, filegen, graph, imagegen,
+    incognito, macros, media, memory, model_profiles, notify, objects, outcomes, plans, plugins,
+    policy, prompts, providers, routing, scheduler, search, settings, shell, storage, tagging,
+    tasks, templates, trace, uia, undo, vision, web,
+};
+use axis_core::command::{Action, Command};
+use axis_core::context;
+use axis_core::dispatch::{Availability, RoutingDecision};
+use axis_core::engine::ActionExecutor;
+use axis_core::orchestrator::{Answered, Host, PastTurn};
+use axis_core::policy::Hold;
+use axis_core::provider::ModelProvider;
+use axis_core::untrusted;
+use base64::Engine as _;
+use chrono::Local;
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+// これより短ければ（文字を出さないアプリ / ゲーム / 画像）LOOK はスクリーンショットで
+const LOOK_MIN_CHARS: usize = 200;
+
+pub struct Worker<'a> {
+    pub cfg: &'a Settings,
+    pub network: &'a NetworkStatus,
+    pub input: &'a str, // Llama はそのまま依頼文だけで答える
+    pub ensemble: Mutex<Option<ensemble::EnsembleResult>>,
+}
+
+impl ModelProvider for Worker<'_> {
+    async fn complete(&self, target: &str, system: &str, user: &str) -> Result<String, String> {
+        let models = &self.cfg.models;
+        match target {
+            "gpt" => {
+                info!("🔧 [Worker] GPT ({}) executing...", models.gpt);
+                ai::call_openai(&models.gpt, system, user).await
+            }
+            "gemini" => {
+                info!("🧠 [Worker] Gemini ({}) executing...", models.gemini);
+                ai::call_google(&models.gemini, system, user).await
+            }
+            "grok" => {
+                info!("🦉 [Worker] Grok ({}) executing...", models.grok);
+                ai::call_grok(&models.grok, system, user).await
+            }
+            "local" => {
+                info!("🏠 [Worker] Local LLM ({}) executing...", models.local);
+                ai::call_local(&models.local, system, user).await
+            }
+            "ensemble" => {
+                // 届かないプロバイダは外す
+                let members: Vec<String> = self
+                    .cfg
+                    .ensemble_members
+                    .iter()
+                    .filter(|m| {
+                        self.network.reachable(m) && crate::provider_unavailable(m).is_none()
+                    })
+                    .cloned()
+                    .collect();
+                let r =
+                    ensemble::run(&members, &self.cfg.ensemble_judge, models, system, user).await?;
+                let answer = r.answer.clone();
+                if let Ok(mut slot) = self.ensemble.lock() {
+                    *slot = Some(r);
+                }
+                Ok(answer)
+            }
+            other if providers::is_custom(other) => {
+                info!("🔌 [Worker] {} ({}) executing...", other, models.for_alias(other));
+                ai::call_provider(other, system, user).await
+            }
+            _ => {
+                info!("👑 [Worker] Llama handling locally...");
+                crate::send_llm_request(
+                    &models.core,
+                    vec![
+                        AiMessage {
+                            role: "system".to_string(),
+                            content: json!(system),
+                        },
+                        AiMessage {
+                            role: "user".to_string(),
+                            content: json!(self.input),
+                        },
+                    ],
+                    0.7,
+                )
+                .await
+            }
+        }
+    }
+
+    fn is_timeout(&self, error: &str) -> bool {
+        ai::is_timeout_error(error)
+    }
+}
+
+pub struct Actions<'a> {
+    pub app: &'a AppHandle,
+    pub session_id: &'a str,
+    pub cfg: &'a Settings,
+    pub incognito: bool,
+    // メモリの応答側に付ける添付 (objects.rs)
+    pub attachments: Vec<memory::AttachmentRef>,
+    // SAVE で書き出したファイル (ResponseMeta.files)
+    pub saved_files: Vec<storage::SavedFile>,
+    // 書き出し (export.rs) 用の出典
+    pub sources: Vec<storage::Source>,
+    // バックグラウンドタスクの入れ子の深さ（tasks.rs。0 = 普通の会話）
+    pub depth: u32,
+}
+
+impl<'a> Actions<'a> {
+    pub fn new(
+        app: &'a AppHandle,
+        session_id: &'a str,
+        cfg: &'a Settings,
+        incognito: bool,
+    ) -> Self {
+        Self {
+            app,
+            session_id,
+            cfg,
+            incognito,
+            attachments: Vec::new(),
+            saved_files: Vec::new(),
+            sources: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    fn saved(&mut self, result: Result<Saved, String>, context: &mut String) {
+        match result {
+            Ok(saved) => {
+                context.push_str(&saved.report());
+                self.saved_files.push(storage::SavedFile {
+                    path: saved.path.to_string_lossy().to_string(),
+                    name: saved.name.clone(),
+                    size: saved.content.len() as u64,
+                    preview: crate::file_preview(&saved.text),
+                });
+                // 生成したファイルもメモリの添付として残す
+                if let Ok(att) = objects::attach(
+                    self.app,
+                    &saved.content,
+                    crate::mime_for(&saved.name),
+                    &saved.name,
+                ) {
+                    self.attachments.push(att);
+                }
+            }
+            Err(e) => context.push_str(&format!("[System] File Save Error: {}\n", e)),
+        }
+    }
+
+    async fn search(&mut self, q: &str, context: &mut String) {
+        // settings.search_providers の順に試す (search.rs)
+        let (provider, search_res) = match search::search(q).await {
+            Ok(found) => found,
+            Err(e) => {
+                context.push_str(&format!("Search Error: {}\n", e));
+                (String::new(), Vec::new())
+            }
+        };
+        if search_res.is_empty() {
+            context.push_str("No search results found from any provider.\n");
+            return;
+        }
+        context.push_str(&format!("[Search Results: {}]\n", provider));
+
+        // 上位ページの本文を読んで要約（リンクだけだとモデルは中身を知らない）
+        // 百科事典は要約と infobox が既にあるのでページは取りに行かない
+        let fetch_n = if provider == "wikipedia" {
+            0
+        } else {
+            self.cfg.web_fetch_pages
+        };
+        if provider == "wikipedia" {
+            context.push_str("[Encyclopedia]\n");
+            for (i, r) in search_res.iter().enumerate() {
+                context.push_str(&format!(
+                    "[{}] {} ({})\n{}\n",
+                    i + 1,
+                    r.title,
+                    r.link,
+                    r.snippet
+                ));
+                for (k, v) in &r.facts {
+                    context.push_str(&format!("  • {}: {}\n", k, v));
+                }
+            }
+            context.push_str("[All Results]\n");
+        }
+        if fetch_n > 0 {
+            let pages = web::fetch_top(&search_res, fetch_n).await;
+            if !pages.is_empty() {
+                let alias = self.cfg.web_summarizer.clone();
+                let model = self.cfg.models.for_alias(&alias);
+                let digest = web::digest(self.app, q, &pages, &alias, &model).await;
+                context.push_str("[Web Digest]\n");
+                context.push_str(&digest);
+                context.push_str("\n[Pages Read]\n");
+                for (i, p) in pages.iter().enumerate() {
+                    let title = if p.title.is_empty() { &p.url } else { &p.title };
+                    context.push_str(&format!("[{}] {} ({})\n", i + 1, title, p.url));
+                }
+                context.push_str("[All Results]\n");
+            }
+        }
+
+        for r in search_res {
+            context.push_str(&format!("- {} ({})\n", r.title, r.link));
+            self.sources.push(storage::Source {
+                title: r.title,
+                url: r.link,
+            });
+        }
+    }
+}
+
+impl ActionExecutor for Actions<'_> {
+    async fn execute(&mut self, cmd: &Command, context: &mut String) {
+        macros::record(self.app, &cmd.raw);
+        let (app, session_id) = (self.app, self.session_id);
+
+        match &cmd.action {
+            Action::Look => {
+                // 前面ウィンドウを文字で読めればそれで済ませる（読めない / 少ない時だけスクリーンショット）
+                let mut read = false;
+                if settings::current().look_source == "auto" {
+                    match uia::read_active_window().await {
+                        Ok(win) if win.outline.chars().count() >= LOOK_MIN_CHARS => {
+                            context.push_str(&format!(
+                                "[System] Read the active window via UI Automation ({} elements).\n",
+                                win.nodes
+                            ));
+                            // ページやメールの文面もそのまま入る（LOOK の出力ごと engine が外の文面として囲む）
+                            context.push_str(&format!("Window: {}\n{}\n", win.title, win.outline));
+                            read = true;
+                        }
+                        Ok(win) => info!(title = %win.title, "🪟 [UIA] little text, taking a screenshot"),
+                        Err(e) => info!("🪟 [UIA] {}, taking a screenshot", e),
+                    }
+                }
+                if !read {
+                    if let Ok(b64) = vision::take_screenshot() {
+                        // 見た画面はメモリの添付として残す（シークレットセッションでは残さない）
+                        if !self.incognito {
+                            let png = base64::engine::general_purpose::STANDARD.decode(&b64);
+                            match png.map_err(|e| e.to_string()).and_then(|bytes| {
+                                objects::attach(app, &bytes, "image/png", "screenshot.png")
+                            }) {
+                                Ok(att) => self.attachments.push(att),
+                                Err(e) => warn!("⚠️ [Objects] failed to keep screenshot: {}", e),
+                            }
+                        }
+                        context.push_str("[System] Analyzed screen.\n");
+                        let vision_prompt = prompts::render(app, "vision", &[]);
+                        let vision_report = crate::consult_vision_agent(&b64, &vision_prompt).await;
+                        context.push_str(&format!("\n[Vision Report]\n{}\n", vision_report));
+                    }
+                }
+            }
+            Action::RecordScreen { seconds } => match vision::record_screen(app, *seconds).await {
+                Ok(rec) => {
+                    // LOOK と同じく、並べたコマをメモリの添付に
+                    if !self.incognito {
+                        let png = base64::engine::general_purpose::STANDARD.decode(&rec.sheet_base64);
+                        match png.map_err(|e| e.to_string()).and_then(|bytes| {
+                            objects::attach(app, &bytes, "image/png", "screen-recording.png")
+                        }) {
+                            Ok(att) => self.attachments.push(att),
+                            Err(e) => warn!("⚠️ [Objects] failed to keep screen recording: {}", e),
+                        }
+                    }
+                    context.push_str(&format!(
+                        "[System] Recorded the screen for {}s ({} frames, {} keyframes).\n\n[Screen Recording Report]\n{}\n",
+                        rec.seconds,
+                        rec.frames,
+                        rec.keyframes_ms.len(),
+                        rec.description
+                    ));
+                }
+                Err(e) => context.push_str(&format!("[System] Screen Recording Error: {}\n", e)),
+            },
+            Action::Apps => {
+                let apps = system::get_running_apps();
+                context.push_str("[System] Running Apps:\n");
+                for (i, app_name) in apps.iter().take(10).enumerate() {
+                    context.push_str(&format!("{}. {}\n", i + 1, app_name));
+                }
+            }
+            Action::Processes { sort_by } => match system::get_top_processes(10, sort_by) {
+                Ok(procs) => context.push_str(&format!(
+                    "[System] Top processes by {}:\n{}",
+                    sort_by,
+                    system::format_processes(&procs)
+                )),
+                Err(e) => context.push_str(&format!("[System] Process Error: {}\n", e)),
+            },
+            Action::Disk { path } => {
+                context.push_str(&format!(
+                    "[System] Drives:\n{}",
+                    system::format_disks(&system::get_system_stats().disks)
+                ));
+                if !path.is_empty() {
+                    let path = path.clone();
+                    match tauri::async_runtime::spawn_blocking(move || {
+                        system::analyze_disk_usage(&path, 10)
+                    })
+                    .await
+                    {
+                        Ok(Ok(report)) => context.push_str(&format!(
+                            "[System] Largest folders:\n{}",
+                            system::format_disk_usage(&report)
+                        )),
+                        Ok(Err(e)) => context.push_str(&format!("[System] Disk Error: {}\n", e)),
+                        Err(e) => context.push_str(&format!("[System] Disk Error: {}\n", e)),
+                    }
+                }
+            }
+            Action::Activity { day } => match activity::timeline(app, day.as_deref()) {
+                Ok(spans) => context.push_str(&format!(
+                    "[System] Activity ({}):\n{}",
+                    day.as_deref().unwrap_or("today"),
+                    activity::summarize(&spans)
+                )),
+                Err(e) => context.push_str(&format!("[System] Activity Error: {}\n", e)),
+            },
+            Action::Search { query } => self.search(query, context).await,
+
+            // 天気 / ニュースは専用コネクタ (connectors/)
+            Action::Weather { place } => match connectors::weather::forecast(place).await {
+                Ok(report) => {
+                    context.push_str(&format!(
+                        "[Weather: Open-Meteo]\n{}",
+                        connectors::weather::summarize(&report)
+                    ));
+                    self.sources.push(storage::Source {
+                        title: format!("Open-Meteo ({})", report.place),
+                        url: "https://open-meteo.com/".to_string(),
+                    });
+                }
+                Err(e) => context.push_str(&format!("[System] Weather Error: {}\n", e)),
+            },
+            Action::News { topic } => match connectors::news::headlines(topic).await {
+                Ok(items) if !items.is_empty() => {
+                    context.push_str(&format!(
+                        "[News]\n{}\n",
+                        connectors::news::summarize(&items)
+                    ));
+                    for item in items {
+                        self.sources.push(storage::Source {
+                            title: item.title,
+                            url: item.link,
+                        });
+                    }
+                }
+                Ok(_) => context.push_str("[System] No news found.\n"),
+                Err(e) => context.push_str(&format!("[System] News Error: {}\n", e)),
+            },
+            Action::Calendar { hours } => match connectors::calendar::upcoming(*hours).await {
+                Ok(events) => context.push_str(&format!(
+                    "[Calendar: next {}h]\n{}",
+                    hours,
+                    connectors::calendar::summarize(&events)
+                )),
+                Err(e) => context.push_str(&format!("[System] Calendar Error: {}\n", e)),
+            },
+            Action::CheckEmail { limit } => match connectors::email::unread(app, *limit).await {
+                Ok(messages) => context.push_str(&format!(
+                    "[Unread Email]\n{}",
+                    connectors::email::summarize(&messages)
+                )),
+                Err(e) => context.push_str(&format!("[System] Email Error: {}\n", e)),
+            },
+
+            // URL を読む (FETCH: <url>)
+            Action::BrowserTab => match connectors::browser::read(self.cfg).await {
+                Ok(page) => {
+                    context.push_str(&format!(
+                        "[Browser Tab] {} ({})\n{}\n",
+                        page.title, page.url, page.text
+                    ));
+                    self.sources.push(storage::Source {
+                        title: page.title,
+                        url: page.url,
+                    });
+                }
+                Err(e) => context.push_str(&format!("[System] Browser Tab Error: {}\n", e)),
+            },
+            Action::Fetch { url } => match web::fetch_url(
+                url,
+                &self.cfg.fetch_allow_domains,
+                &self.cfg.fetch_deny_domains,
+                self.cfg.fetch_max_chars,
+            )
+            .await
+            {
+                Ok(page) => {
+                    let title = if page.title.is_empty() {
+                        page.url.clone()
+                    } else {
+                        page.title.clone()
+                    };
+                    context.push_str(&format!(
+                        "[Fetched Page] {} ({})\n{}\n",
+                        title, page.url, page.text
+                    ));
+                    self.sources.push(storage::Source {
+                        title,
+                        url: page.url,
+                    });
+                }
+                Err(e) => context.push_str(&format!("[System] Fetch Error: {}\n", e)),
+            },
+
+            // テンプレートからの生成も SAVE と同じ扱い (templates.rs)
+            Action::Save {
+                path,
+                mode,
+                content,
+            } => {
+                let sources = self.sources.clone();
+                let result =
+                    filegen::save(app, session_id, path, *mode, content.trim(), &sources);
+                self.saved(result, context);
+            }
+            Action::Template { name, fields } => {
+                let result = templates::generate(app, session_id, name, fields);
+                self.saved(result, context);
+            }
+            Action::ImageGen { prompt, aspect } => {
+                let result = imagegen::generate(app, session_id, prompt, aspect.as_deref()).await;
+                self.saved(result, context);
+            }
+            Action::Schedule { when, message } => match scheduler::create_from_action(
+                app, when, message, session_id,
+            ) {
+                Ok(task) => context.push_str(&format!(
+                    "[System] Scheduled '{}' ({}) at {}\n",
+                    task.title,
+                    if task.repeat_spec.is_empty() {
+                        "once"
+                    } else {
+                        &task.repeat_spec
+                    },
+                    chrono::DateTime::from_timestamp_millis(task.next_run_at)
+                        .map(|d| d.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default()
+                )),
+                Err(e) => context.push_str(&format!("[System] Schedule Error: {}\n", e)),
+            },
+            // 覚えた別名は後の EXEC で毎回使われるので、必ず確認してから保存 (policy.rs)
+            Action::LearnAlias { name, target } => match settings::check_learned_alias(name, target) {
+                Ok(_) => {
+                    let res = policy::run_or_queue(app, cmd.action.clone(), session_id);
+                    context.push_str(&format!("{}\n", res));
+                }
+                Err(e) => context.push_str(&format!("Failed: could not save the alias: {}\n", e)),
+            },
+            // settings.confirm_actions (["EXEC"] / ["*"] ...) に入っていれば確認待ちに
+            Action::Exec { .. }
+            | Action::Type { .. }
+            | Action::Press { .. }
+            | Action::Click { .. }
+            | Action::Kill { .. }
+            | Action::Focus { .. }
+            | Action::Minimize { .. }
+            | Action::OpenFile { .. }
+            | Action::Reveal { .. }
+            | Action::OpenUrl { .. } => {
+                let res = policy::run_or_queue(app, cmd.action.clone(), session_id);
+                context.push_str(&format!("{}\n", res));
+            }
+            Action::UndoLast => match undo::undo_last(app, Some(session_id)) {
+                Ok(msg) => context.push_str(&format!("[Undo] {}\n", msg)),
+                Err(e) => context.push_str(&format!("[System] Undo Error: {}\n", e)),
+            },
+            Action::Plan { goal } => {
+                context.push_str(&plans::run_command(app, goal, session_id).await);
+            }
+            Action::Macro { name, params } => {
+                context.push_str(&macros::run_command(app, name, params, session_id).await);
+            }
+            Action::Background {
+                kind,
+                argument,
+                cwd,
+            } => {
+                // BACKGROUND: RUN: <command> か BACKGROUND: <prompt>。ID だけ返して待たない
+                let started =
+                    tasks::start(app, kind, argument, cwd.as_deref(), session_id, self.depth);
+                match started {
+                    Ok(task) => context.push_str(&format!(
+                        "[Background] Started task {} ({}). The user will be notified when it finishes.\n",
+                        task.id, task.label
+                    )),
+                    Err(e) => context.push_str(&format!("[System] Background Error: {}\n", e)),
+                }
+            }
+            Action::Run { command, cwd } => {
+                let allowed = policy::run_allowed(command, cwd.as_deref());
+                let res = if allowed && !policy::requires_confirmation("RUN") {
+                    let (command, cwd) = (command.clone(), cwd.clone());
+                    tauri::async_runtime::spawn_blocking(move || {
+                        shell::run_shell(&command, cwd.as_deref())
+                    })
+                    .await
+                    .unwrap_or_else(|e| format!("Error: {}", e))
+                } else {
+                    let pending = policy::enqueue(app, cmd.action.clone(), session_id);
+                    format!(
+                        "[Policy] RUN {} requires user confirmation (pending id={}). Ask the user to approve it in the confirmation dialog.",
+                        pending.argument, pending.id
+                    )
+                };
+                context.push_str(&format!("[Command Output]\n{}\n", res));
+            }
+            Action::Git { op, repo } => context.push_str(&connectors::git::run_command(op, repo)),
+            Action::Commit { repo, message } => {
+                // リポジトリを決めてから確認待ちへ（ダイアログにパスが出る）
+                match connectors::git::prepare_commit(repo, message) {
+                    Ok((repo, message)) => {
+                        let res =
+                            policy::run_or_queue(app, Action::Commit { repo, message }, session_id);
+                        context.push_str(&format!("{}\n", res));
+                    }
+                    Err(e) => context.push_str(&format!("[System] Git Error: {}\n", e)),
+                }
+            }
+            Action::HomeAssistant {
+                service,
+                entity_id,
+                data,
+            } => {
+                context.push_str(
+                    &connectors::home_assistant::run_command(service, entity_id, data).await,
+                );
+            }
+            Action::Media { action, volume } => {
+                let res = media::control(action, *volume).await;
+                context.push_str(&format!("[Media] {}\n", res));
+            }
+            Action::Plugin { .. } => {
+                // サブプロセスを待つのでスレッドで (plugins.rs)
+                let (app, action, session_id) =
+                    (app.clone(), cmd.action.clone(), session_id.to_string());
+                let res = tauri::async_runtime::spawn_blocking(move || {
+                    policy::run_or_queue(&app, action, &session_id)
+                })
+                .await
+                .unwrap_or_else(|e| format!("Error: {}", e));
+                context.push_str(&format!("{}\n", res));
+            }
+            Action::Wait { ms } => tokio::time::sleep(Duration::from_millis(*ms)).await,
+            Action::WaitFor {
+                kind,
+                target,
+                timeout_secs,
+            } => {
+                let (kind, target, secs) = (kind.clone(), target.clone(), *timeout_secs);
+                let res = tokio::task::spawn_blocking(move || uia::wait_for(&kind, &target, secs))
+                    .await
+                    .unwrap_or_else(|e| format!("Error: {}", e));
+                context.push_str(&format!("{}\n", res));
+            }
+            // 書式の誤りはモデルに返して直させる (command.rs)
+            Action::Invalid { message } => context.push_str(&format!(
+                "[System] Invalid action '{}': {}\n",
+                cmd.label(),
+                message
+            )),
+            Action::Unknown => {}
+        }
+    }
+
+    fn hold(&mut self, cmd: &Command, hold: Hold) -> String {
+        policy::hold_after_untrusted(self.app, cmd, hold, self.session_id)
+    }
+
+    fn finished(&mut self, cmd: &Command, output: &str, executed: bool) {
+        if executed {
+            audit::record(self.app, "agent", &cmd.raw, output, self.session_id);
+        }
+    }
+}
+
+// 答え直しの枝: 操作は繰り返さず、元の依頼で同じアクションが返した結果をそのまま使う
+pub struct RecordedActions {
+    recorded: Vec<trace::ActionTrace>,
+}
+
+impl RecordedActions {
+    pub fn new(recorded: Vec<trace::ActionTrace>) -> Self {
+        Self { recorded }
+    }
+}
+
+impl ActionExecutor for RecordedActions {
+    async fn execute(&mut self, cmd: &Command, context: &mut String) {
+        let label = cmd.label();
+        match self.recorded.iter().position(|r| r.command == label) {
+            Some(i) => {
+                let r = self.recorded.remove(i);
+                context.push_str(untrusted::unwrap(&r.result));
+                context.push('\n');
+            }
+            None => context.push_str(&format!(
+                "[System] {} was not run: regenerating an answer does not repeat actions. Ask the user to request it directly if it is needed.\n",
+                label
+            )),
+        }
+    }
+
+    fn hold(&mut self, cmd: &Command, _hold: Hold) -> String {
+        format!(
+            "[System] {} was not run: regenerating an answer does not repeat actions.\n",
+            cmd.label()
+        )
+    }
+}
+
+// 依頼 1 回分の executor（答え直しの枝は RecordedActions）
+pub enum Executor<'a> {
+    Live(Actions<'a>),
+    Recorded(RecordedActions),
+}
+
+impl ActionExecutor for Executor<'_> {
+    async fn execute(&mut self, cmd: &Command, context: &mut String) {
+        match self {
+            Executor::Live(a) => a.execute(cmd, context).await,
+            Executor::Recorded(r) => r.execute(cmd, context).await,
+        }
+    }
+
+    fn hold(&mut self, cmd: &Command, hold: Hold) -> String {
+        match self {
+            Executor::Live(a) => a.hold(cmd, hold),
+            Executor::Recorded(r) => r.hold(cmd, hold),
+        }
+    }
+
+    fn finished(&mut self, cmd: &Command, output: &str, executed: bool) {
+        match self {
+            Executor::Live(a) => a.finished(cmd, output, executed),
+            Executor::Recorded(r) => r.finished(cmd, output, executed),
+        }
+    }
+}
+
+fn user_text(log: &InteractionLog) -> String {
+    log.user_tokens
+        .iter()
+        .map(|t| t.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub struct Session<'a> {
+    pub app: &'a AppHandle,
+    pub cfg: &'a Settings,
+    pub network: &'a NetworkStatus,
+    pub session_id: &'a str,
+    pub incognito: bool,
+    pub branch_of: Option<String>, // 答え直しの枝: 元のログ id
+    pub worker: Worker<'a>,
+    started: Instant,
+    // 答え直しの枝を除いたログ（履歴 / fast path / 言い直しの判定）
+    logs: Vec<InteractionLog>,
+    pinned: Option<String>,
+    persona: Option<String>, // set_persona で選ばれた口調（worker / report プロンプトに重ねる）
+    critic: Mutex<Option<critic::CriticReport>>,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(
+        app: &'a AppHandle,
+        cfg: &'a Settings,
+        network: &'a NetworkStatus,
+        session_id: &'a str,
+        input: &'a str,
+        branch_of: Option<String>,
+    ) -> Self {
+        let logs = storage::get_all_logs(app)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|l| l.branch_of.is_none())
+            .collect();
+        let db = AxisDatabase::open(app).ok();
+        Self {
+            app,
+            cfg,
+            network,
+            session_id,
+            incognito: incognito::is_active(session_id),
+            branch_of,
+            worker: Worker {
+                cfg,
+                network,
+                input,
+                ensemble: Default::default(),
+            },
+            started: Instant::now(),
+            logs,
+            pinned: db
+                .as_ref()
+                .and_then(|db| db.session_provider(session_id).ok().flatten()),
+            persona: db
+                .as_ref()
+                .and_then(|db| db.session_persona(session_id).ok().flatten()),
+            critic: Mutex::new(None),
+        }
+    }
+
+    // 同じセッションの直前のやり取り
+    fn previous(&self) -> Option<&InteractionLog> {
+        self.logs
+            .iter()
+            .rev()
+            .find(|l| l.session_id == self.session_id)
+    }
+
+    fn with_persona(&self, prompt: String) -> String {
+        let overlay = self
+            .persona
+            .as_deref()
+            .and_then(|p| prompts::persona_overlay(self.app, p));
+        prompts::with_persona(prompt, overlay.as_deref())
+    }
+}
+
+impl Availability for Session<'_> {
+    fn reachable(&self, provider: &str) -> bool {
+        self.network.reachable(provider)
+    }
+
+    fn unavailable(&self, provider: &str) -> Option<(&'static str, String)> {
+        crate::provider_unavailable(provider)
+    }
+}
+
+impl<'a> Host for Session<'a> {
+    type Models = Worker<'a>;
+    type Executor = Executor<'a>;
+    type Saved = InteractionLog;
+
+    fn models(&self) -> &Worker<'a> {
+        &self.worker
+    }
+
+    fn history(&self) -> Vec<PastTurn> {
+        // シークレットセッションは保存していないので incognito.rs の分だけ
+        if self.incognito {
+            return incognito::history(self.session_id)
+                .into_iter()
+                .map(|(user, axis)| PastTurn {
+                    user,
+                    axis,
+                    untrusted: false,
+                })
+                .collect();
+        }
+        let mut turns: Vec<PastTurn> = self
+            .logs
+            .iter()
+            .rev()
+            .filter(|log| log.session_id == self.session_id)
+            .take(context::HISTORY_TURNS)
+            .map(|log| PastTurn {
+                user: user_text(log),
+                axis: log.ai_response.clone(),
+                untrusted: log.meta.as_ref().is_some_and(|m| m.untrusted),
+            })
+            .collect();
+        turns.reverse();
+        turns
+    }
+
+    async fn memory_context(&self, input: &str) -> String {
+        // シークレットセッションではメモリを引かない
+        if self.incognito {
+            return String::new();
+        }
+        let (app, query) = (self.app.clone(), input.to_string());
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut context = memory::build_memory_context(&app, &query, 3).unwrap_or_default();
+            // 会話から覚えた事実 (graph.rs)
+            context.push_str(&graph::build_context(&app, &query));
+            context
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    async fn recall(&self, input: &str) -> Vec<String> {
+        if self.incognito {
+            return Vec::new();
+        }
+        let (app, query) = (self.app.clone(), input.to_string());
+        tauri::async_runtime::spawn_blocking(move || {
+            AxisDatabase::open(&app)
+                .and_then(|db| db.search_similar_logs(&query).map_err(|e| e.to_string()))
+                .unwrap_or_default()
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    fn pinned_provider(&self) -> Option<String> {
+        self.pinned.clone()
+    }
+
+    fn fast_route(&self, input: &str) -> Option<RoutingDecision> {
+        if !self.cfg.fast_path_routing {
+            return None;
+        }
+        let previous = self.previous().and_then(|l| l.meta.as_ref());
+        let route = routing::fast_path(input, previous)
+            .or_else(|| routing::cached(self.session_id, input))?;
+        Some(RoutingDecision {
+            target: route.target,
+            strategy: route.strategy.to_string(),
+            reason: route.reason,
+            task_type: route.task_type,
+        })
+    }
+
+    fn remember_route(&self, input: &str, decision: &RoutingDecision) {
+        routing::remember(
+            self.session_id,
+            input,
+            &decision.target,
+            &decision.task_type,
+        );
+    }
+
+    fn commander_unavailable(&self) -> Option<(&'static str, String)> {
+        crate::provider_unavailable("llama")
+    }
+
+    async fn commander(&self, system: &str, input: &str) -> Result<String, String> {
+        let messages = vec![
+            AiMessage {
+                role: "system".to_string(),
+                content: json!(system),
+            },
+            AiMessage {
+                role: "user".to_string(),
+                content: json!(input),
+            },
+        ];
+        crate::send_llm_request(&self.cfg.models.core, messages, 0.1).await
+    }
+
+    fn dispatch_prompt(&self, history_text: &str) -> String {
+        prompts::render(
+            self.app,
+            "commander",
+            &[
+                ("profiles", &model_profiles::build_profiles_prompt()),
+                ("history", history_text),
+                ("providers", &providers::prompt_list()),
+            ],
+        )
+    }
+
+    fn worker_prompt(&self) -> String {
+        self.with_persona(prompts::render(
+            self.app,
+            "worker",
+            &[
+                ("macros", &macros::prompt_list(self.app)),
+                ("templates", &templates::prompt_list(self.app)),
+                ("plugins", &plugins::prompt_list()),
+                ("apps", &app_catalog::prompt_list()),
+            ],
+        ))
+    }
+
+    fn report_prompt(&self, target: &str) -> String {
+        let name = if target == "grok" {
+            "report_witty"
+        } else {
+            "report"
+        };
+        self.with_persona(prompts::render(self.app, name, &[]))
+    }
+
+    fn prompt_hash(&self, prompt: &str) -> String {
+        trace::hash_prompt(prompt)
+    }
+
+    async fn review(
+        &self,
+        target: &str,
+        task_type: &str,
+        input: &str,
+        output: &str,
+    ) -> Option<String> {
+        if !critic::applies(self.cfg, task_type, output) {
+            return None;
+        }
+        let (checked, report) = critic::review(self.cfg, target, task_type, input, output).await;
+        if let Ok(mut slot) = self.critic.lock() {
+            *slot = Some(report);
+        }
+        Some(checked)
+    }
+
+    fn persist(
+        &self,
+        answered: Answered,
+        executor: Executor<'a>,
+    ) -> Result<InteractionLog, String> {
+        let (attachments, saved_files, sources) = match executor {
+            Executor::Live(a) => (a.attachments, a.saved_files, a.sources),
+            Executor::Recorded(_) => Default::default(),
+        };
+        let ensemble = self.worker.ensemble.lock().ok().and_then(|mut r| r.take());
+        let stored_answer = answered.stored_answer();
+        let answered_target = answered.answered_target().to_string();
+        let Answered {
+            decision,
+            pinned,
+            worker_ok,
+            worker_latency_ms,
+            failover,
+            answer,
+            actions,
+            read_untrusted,
+            mut trace,
+        } = answered;
+        let (session_id, input, now_ts) = (self.session_id, trace.input.clone(), trace.created_at);
+
+        let answered_model = match (decision.target.as_str(), &ensemble) {
+            ("ensemble", Some(r)) => r
+                .candidates
+                .iter()
+                .map(|c| c.model.as_str())
+                .collect::<Vec<_>>()
+                .join(" + "),
+            (target, _) => self.cfg.models.for_alias(target),
+        };
+        let response_meta = storage::ResponseMeta {
+            target: answered_target.clone(),
+            model: if failover {
+                self.cfg.models.local.clone()
+            } else {
+                answered_model.clone()
+            },
+            task_type: decision.task_type.clone(),
+            strategy: decision.strategy.clone(),
+            reason: decision.reason.clone(),
+            session_override: pinned,
+            failover,
+            latency_ms: self.started.elapsed().as_millis() as i64,
+            candidates: ensemble
+                .as_ref()
+                .map(|r| r.candidates.clone())
+                .unwrap_or_default(),
+            ensemble_choice: ensemble
+                .as_ref()
+                .map(|r| format!("{} ({})", r.choice, r.method)),
+            critic: self.critic.lock().ok().and_then(|mut r| r.take()),
+            persona: self.persona.clone(),
+            actions,
+            sources,
+            files: saved_files,
+            untrusted: read_untrusted,
+        };
+        trace.worker_model = response_meta.model.clone();
+        let log = InteractionLog {
+            id: trace.log_id.clone(),
+            session_id: session_id.to_string(),
+            timestamp: now_ts,
+            user_tokens: input
+                .split_whitespace()
+                .enumerate()
+                .map(|(i, t)| AxisToken {
+                    id: format!("{}-{}", now_ts, i),
+                    text: t.to_string(),
+                    timestamp: now_ts,
+                    tags: vec![],
+                })
+                .collect(),
+            ai_response: answer,
+            provider_used: format!("Llama -> {}", decision.target),
+            feedback: None,
+            meta: Some(response_meta),
+            branch_of: self.branch_of.clone(),
+        };
+
+        // シークレットセッション: どこにも保存せず、続きの会話用にプロセス内にだけ持つ
+        if self.incognito {
+            incognito::push(session_id, &input, &stored_answer);
+            info!("🕶️ [Incognito] answered without saving ({})", session_id);
+            return Ok(log);
+        }
+
+        storage::save_log(self.app, &log)?;
+        trace::save(self.app, &trace);
+
+        // ルーティング補正用の実績（ensemble は複数モデルなので数えない）
+        if decision.target != "ensemble" {
+            outcomes::record(
+                self.app,
+                &outcomes::ModelOutcome {
+                    log_id: log.id.clone(),
+                    session_id: session_id.to_string(),
+                    model: answered_model,
+                    task_type: decision.task_type.clone(),
+                    success: worker_ok,
+                    latency_ms: worker_latency_ms,
+                    reasked: false,
+                    rating: None,
+                    created_at: now_ts,
+                },
+            );
+        }
+        // 答え直しの枝は同じ質問なので、ここから先（言い直し判定 / 記憶への保存）は省く
+        if self.branch_of.is_some() {
+            return Ok(log);
+        }
+
+        // 同じセッションの直前の質問の言い直しなら、前の回答を減点
+        if let Some(prev) = self.previous() {
+            outcomes::note_reask(
+                self.app,
+                &prev.id,
+                &user_text(prev),
+                prev.timestamp,
+                &input,
+                now_ts,
+            );
+        }
+
+        if let Ok(db) = AxisDatabase::open(self.app) {
+            let _ = db.save_interaction(session_id, "user", &input);
+            let _ = db.save_interaction(session_id, "assistant", &stored_answer);
+        }
+
+        // Axis メモリ (json+meta) にも保存。保存できたら分類とタグを裏で付ける
+        let saved = memory::save_interaction_with_task(
+            self.app,
+            session_id,
+            &input,
+            &stored_answer,
+            "llm",
+            &decision.target,
+            vec![format!("log:{}", log.id)],
+            if decision.task_type.is_empty() {
+                None
+            } else {
+                Some(decision.task_type.clone())
+            },
+            attachments,
+        );
+        if let Ok(memory_id) = saved {
+            tagging::spawn_auto_tag(self.app.clone(), memory_id);
+        }
+        graph::spawn_extract(
+            self.app.clone(),
+            log.id.clone(),
+            input,
+            answered_target == "local",
+        );
+
+        // 時間が掛かった応答は、別アプリを見ている間に終わった可能性が高いので OS 通知
+        if self.started.elapsed() >= Duration::from_secs(self.cfg.notify_long_task_secs) {
+            notify::notify(
+                self.app,
+                "Axis finished",
+                &log.ai_response,
+                Some(notify::DeepLink::session(session_id)),
+            );
+        }
+        Ok(log)
+    }
+}
@@ -1,20 +1,409 @@
 // src-tauri/src/ai.rs
 
+use crate::budget;
+use crate::health;
+use crate::providers;
+use crate::redact;
+use crate::secrets;
+use crate::settings;
+use base64::Engine as _;
+use serde::Serialize;
 use serde_json::json;
+use std::collections::{HashMap, VecDeque};
 use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use reqwest::Client;
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, warn};
+
+// --- タイムアウト設定 ---
+// settings.timeouts["<provider>"] (provider = llama / gpt / gemini / grok / local / web)
+// 未指定なら settings.timeouts["default"]、それも無ければ既定値
+// env の <PROVIDER>_CONNECT_TIMEOUT_SECS / <PROVIDER>_TIMEOUT_SECS / LLM_* も従来どおり効く (settings.rs)
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_TIMEOUT_SECS: u64 = 90;
+// ローカルモデルは初回ロードが遅いので長め
+const DEFAULT_LOCAL_TIMEOUT_SECS: u64 = 180;
+
+// タイムアウト時のエラーはこの接頭辞で始まる（フェイルオーバー判定用）
+pub const TIMEOUT_ERROR_PREFIX: &str = "Timeout";
+
+#[derive(Debug, Clone, Copy)]
+pub struct Timeouts {
+    pub connect: Duration,
+    pub request: Duration,
+}
+
+pub fn timeouts_for(provider: &str) -> Timeouts {
+    let p = provider.to_lowercase();
+    let cfg = settings::current();
+    let pick = |f: fn(&settings::TimeoutSettings) -> Option<u64>| {
+        [p.as_str(), "default"]
+            .iter()
+            .filter_map(|k| cfg.timeouts.get(*k))
+            .find_map(|t| f(t).filter(|s| *s > 0))
+    };
+    let default_request = if p == "local" { DEFAULT_LOCAL_TIMEOUT_SECS } else { DEFAULT_TIMEOUT_SECS };
+    let connect = pick(|t| t.connect_secs).unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS);
+    let request = pick(|t| t.request_secs).unwrap_or(default_request);
+    Timeouts {
+        connect: Duration::from_secs(connect),
+        request: Duration::from_secs(request),
+    }
+}
+
+// --- AppHandle ---
+// ここの関数は AppHandle を受け取らないので、イベント発火 / db 書き込み (budget.rs) 用に setup で預かる
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+pub fn app_handle() -> Option<&'static AppHandle> {
+    APP.get()
+}
+
+// --- 送信待ち (プロバイダ毎のトークンバケット) ---
+// settings.rate_limits["<provider>"] → ["default"] → DEFAULT_RATE_LIMITS の順。requests_per_minute = 0 で無制限
+// 先着順に並ばせ、待っている間は axis-llm-queue で順番を知らせる（順番が変わった時だけ）
+// 429 が返ってきたらバケットを空にして、次の補充まで待たせる
+const QUEUE_EVENT: &str = "axis-llm-queue";
+const QUEUE_POLL: Duration = Duration::from_millis(200);
+
+// (provider, requests_per_minute, burst)
+const DEFAULT_RATE_LIMITS: &[(&str, u32, u32)] = &[
+    ("llama", 40, 4), // NVIDIA の無料枠は 40 回 / 分
+    ("gpt", 60, 6),
+    ("gemini", 15, 3),
+    ("grok", 60, 6),
+    ("local", 0, 0),
+];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct QueueStatus {
+    pub provider: String,
+    pub ticket: u64,
+    pub position: usize, // 0 = 送信した
+    pub waiting: usize,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+    queue: VecDeque<u64>,
+}
+
+static BUCKETS: OnceLock<Mutex<HashMap<String, Bucket>>> = OnceLock::new();
+static NEXT_TICKET: AtomicU64 = AtomicU64::new(1);
+
+fn rate_limit_for(provider: &str) -> (u32, u32) {
+    let cfg = settings::current();
+    let default = DEFAULT_RATE_LIMITS
+        .iter()
+        .find(|(p, _, _)| *p == provider)
+        .map(|(_, rpm, burst)| (*rpm, *burst))
+        .unwrap_or((60, 6));
+    let pick = |f: fn(&settings::RateLimitSettings) -> Option<u32>| {
+        [provider, "default"]
+            .iter()
+            .filter_map(|k| cfg.rate_limits.get(*k))
+            .find_map(f)
+    };
+    let rpm = pick(|r| r.requests_per_minute).unwrap_or(default.0);
+    let burst = pick(|r| r.burst).unwrap_or(default.1).max(1);
+    (rpm, burst)
+}
+
+// 待っている途中で呼び出し側が捨てられても列に残らないように
+struct Ticket<'a> {
+    provider: &'a str,
+    id: u64,
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        if let Some(mut all) = BUCKETS.get().and_then(|b| b.lock().ok()) {
+            if let Some(bucket) = all.get_mut(self.provider) {
+                bucket.queue.retain(|t| *t != self.id);
+            }
+        }
+    }
+}
+
+fn emit_queue(status: QueueStatus) {
+    if let Some(app) = app_handle() {
+        let _ = app.emit(QUEUE_EVENT, status);
+    }
+}
+
+// 送信してよくなるまで待つ
+pub async fn acquire(provider: &str) {
+    let (rpm, burst) = rate_limit_for(provider);
+    if rpm == 0 {
+        return;
+    }
+    let per_sec = rpm as f64 / 60.0;
+    let ticket = Ticket {
+        provider,
+        id: NEXT_TICKET.fetch_add(1, Ordering::Relaxed),
+    };
+    let buckets = BUCKETS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut last_position = 0;
+    loop {
+        let (position, waiting) = {
+            let Ok(mut all) = buckets.lock() else {
+                return;
+            };
+            let bucket = all.entry(provider.to_string()).or_insert_with(|| Bucket {
+                tokens: burst as f64,
+                refilled_at: Instant::now(),
+                queue: VecDeque::new(),
+            });
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * per_sec).min(burst as f64);
+            bucket.refilled_at = now;
+            if !bucket.queue.contains(&ticket.id) {
+                bucket.queue.push_back(ticket.id);
+            }
+            if bucket.queue.front() == Some(&ticket.id) && bucket.tokens >= 1.0 {
+                bucket.tokens -= 1.0;
+                bucket.queue.pop_front();
+                (0, bucket.queue.len())
+            } else {
+                let position = bucket.queue.iter().position(|t| *t == ticket.id).unwrap_or(0) + 1;
+                (position, bucket.queue.len())
+            }
+        };
+        if position != last_position {
+            // すぐ送れた時は何も知らせない
+            if position > 0 || last_position > 0 {
+                emit_queue(QueueStatus {
+                    provider: provider.to_string(),
+                    ticket: ticket.id,
+                    position,
+                    waiting,
+                });
+            }
+            last_position = position;
+        }
+        if position == 0 {
+            return;
+        }
+        tokio::time::sleep(QUEUE_POLL).await;
+    }
+}
+
+// 429 を受けたら、補充されるまで次を送らせない
+pub fn throttled(provider: &str) {
+    if let Some(mut all) = BUCKETS.get().and_then(|b| b.lock().ok()) {
+        if let Some(bucket) = all.get_mut(provider) {
+            bucket.tokens = 0.0;
+            bucket.refilled_at = Instant::now();
+        }
+    }
+}
+
+// --- 共有クライアントプール ---
+// Client は内部で接続プールを持つ（clone は Arc の複製）ので、プロバイダ毎に 1 つを使い回して
+// Keep-Alive / TLS セッションを再利用する。タイムアウト設定が変わった時だけ作り直す
+static CLIENTS: OnceLock<Mutex<HashMap<String, (Timeouts, Client)>>> = OnceLock::new();
+
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+fn build_client(t: Timeouts) -> Result<Client, String> {
+    Client::builder()
+        .connect_timeout(t.connect)
+        .timeout(t.request)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(Duration::from_secs(60))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+// プロバイダ毎のタイムアウト付きクライアント（初回呼び出し時に生成）
+// ローカル専用モードでは "local" 以外を作らない（外への HTTP は全部ここを通る / local_only.rs）
+pub fn client_for(provider: &str) -> Result<Client, String> {
+    crate::local_only::check(provider)?;
+    let t = timeouts_for(provider);
+    let pool = CLIENTS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut pool = pool.lock().map_err(|e| e.to_string())?;
+
+    if let Some((cached, client)) = pool.get(provider) {
+        if cached.connect == t.connect && cached.request == t.request {
+            return Ok(client.clone());
+        }
+    }
+    let client = build_client(t)?;
+    pool.insert(provider.to_string(), (t, client.clone()));
+    Ok(client)
+}
+
+// reqwest のエラーを「タイムアウト」「接続不可」「その他」に分けて文字列化
+pub fn describe_request_error(provider: &str, e: &reqwest::Error) -> String {
+    let t = timeouts_for(provider);
+    if e.is_timeout() {
+        format!(
+            "{}: {} did not respond within {}s (connect {}s)",
+            TIMEOUT_ERROR_PREFIX,
+            provider,
+            t.request.as_secs(),
+            t.connect.as_secs()
+        )
+    } else if e.is_connect() {
+        format!("Network Error: cannot connect to {}: {}", provider, e)
+    } else {
+        format!("Network Error: {}: {}", provider, e)
+    }
+}
+
+pub fn is_timeout_error(err: &str) -> bool {
+    err.starts_with(TIMEOUT_ERROR_PREFIX)
+}
+
+// multipart/form-data の本文を組む（reqwest の multipart 機能は入れていない）
+// file: (フィールド名, ファイル名, MIME, 中身)。戻り値は (Content-Type, 本文)
+pub fn multipart(fields: &[(&str, &str)], file: Option<(&str, &str, &str, &[u8])>) -> (String, Vec<u8>) {
+    let boundary = format!("axis-{:x}", chrono::Local::now().timestamp_nanos_opt().unwrap_or_default());
+    let mut body = Vec::new();
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n", boundary, name, value)
+                .as_bytes(),
+        );
+    }
+    if let Some((name, filename, mime, bytes)) = file {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                boundary,
+                name,
+                filename.replace('"', "'"),
+                mime
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(bytes);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    (format!("multipart/form-data; boundary={}", boundary), body)
+}
+
+// --- 呼び出しの記録 (health.rs / budget.rs) ---
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenUsage {
+    pub input: u64,
+    pub output: u64,
+}
+
+// usage を返さない API は文字数 / 4 で見積もる
+pub fn record_call(
+    provider: &str,
+    model: &str,
+    started: Instant,
+    prompt_chars: usize,
+    result: &Result<String, String>,
+    usage: Option<TokenUsage>,
+) {
+    health::record(provider, model, started, result, usage.map(|u| u.output));
+    if let Ok(text) = result {
+        let usage = usage.unwrap_or(TokenUsage {
+            input: prompt_chars as u64 / 4,
+            output: text.chars().count() as u64 / 4,
+        });
+        budget::record(provider, model, usage);
+    }
+}
+
+// (本文, usage) → record_call に渡す形に
+pub fn split_usage<U>(result: Result<(String, Option<U>), String>) -> (Result<String, String>, Option<U>) {
+    match result {
+        Ok((text, usage)) => (Ok(text), usage),
+        Err(e) => (Err(e), None),
+    }
+}
+
+fn openai_usage(json: &serde_json::Value) -> Option<TokenUsage> {
+    let usage = json.get("usage")?;
+    Some(TokenUsage {
+        input: usage["prompt_tokens"].as_u64().unwrap_or(0),
+        output: usage["completion_tokens"].as_u64()?,
+    })
+}
 
 // --- 共通: OpenAI互換 API呼び出し (汎用) ---
 pub async fn call_openai_compatible(
+    provider: &str,
     url: &str,
-    api_key_env: &str,
     model_name: &str,
     system_prompt: &str,
     user_input: &str
 ) -> Result<String, String> {
-    let api_key = env::var(api_key_env).map_err(|_| format!("{} missing", api_key_env))?;
-    
-    let client = Client::new();
+    // keyring → env の順 (secrets.rs)
+    let api_key = secrets::require_api_key(provider)?;
+    // 個人情報を伏せて送り、答えの中で戻す (redact.rs。call_local はここを通らない)
+    let redaction = redact::prepare(provider, &[system_prompt, user_input]).await;
+    let system_prompt = &redaction.apply(system_prompt);
+    let user_input = &redaction.apply(user_input);
+    let headers = [bearer(&api_key)];
+    post_chat_completion(provider, url, &headers, model_name, system_prompt, user_input)
+        .await
+        .map(|text| redaction.restore(&text))
+}
+
+// 追加プロバイダ (providers.rs の Azure OpenAI / OpenRouter など)。URL / モデル / 認証ヘッダーは設定から
+pub async fn call_provider(alias: &str, system_prompt: &str, user_input: &str) -> Result<String, String> {
+    let endpoint = providers::endpoint(alias)?;
+    let api_key = secrets::require_api_key(&endpoint.key_name)?;
+    let redaction = redact::prepare(alias, &[system_prompt, user_input]).await;
+    let system_prompt = &redaction.apply(system_prompt);
+    let user_input = &redaction.apply(user_input);
+    let mut headers = endpoint.headers;
+    headers.push(match endpoint.auth {
+        providers::Auth::Bearer => bearer(&api_key),
+        providers::Auth::ApiKey => ("api-key".to_string(), api_key),
+    });
+    post_chat_completion(alias, &endpoint.url, &headers, &endpoint.model, system_prompt, user_input)
+        .await
+        .map(|text| redaction.restore(&text))
+}
+
+fn bearer(key: &str) -> (String, String) {
+    ("Authorization".to_string(), format!("Bearer {}", key))
+}
+
+// OpenAI互換 chat/completions の本体（headers に認証も入れて渡す。空なら何も付けない）
+async fn post_chat_completion(
+    provider: &str,
+    url: &str,
+    headers: &[(String, String)],
+    model_name: &str,
+    system_prompt: &str,
+    user_input: &str
+) -> Result<String, String> {
+    acquire(provider).await;
+    let started = Instant::now();
+    let (result, usage) = split_usage(
+        request_chat_completion(provider, url, headers, model_name, system_prompt, user_input).await,
+    );
+    let prompt_chars = system_prompt.chars().count() + user_input.chars().count();
+    record_call(provider, model_name, started, prompt_chars, &result, usage);
+    result
+}
+
+async fn request_chat_completion(
+    provider: &str,
+    url: &str,
+    headers: &[(String, String)],
+    model_name: &str,
+    system_prompt: &str,
+    user_input: &str
+) -> Result<(String, Option<TokenUsage>), String> {
+    let client = client_for(provider)?;
     
     // ★修正: temperatureパラメータを削除しました。
     // o1系(gpt-5-nano等)はtemperature指定不可、他モデルもデフォルト(1.0等)で動作します。
@@ -27,15 +416,22 @@ pub async fn call_openai_compatible(
         // "temperature": 0.3  <-- 削除！これが犯人でした
     });
 
-    let res = client.post(url)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .send().await.map_err(|e| e.to_string())?;
+    let mut req = client.post(url).header("Content-Type", "application/json");
+    for (name, value) in headers {
+        req = req.header(name.as_str(), value.as_str());
+    }
+    // 外に出る分だけ送った中身を残す (sharing.rs)
+    if provider != "local" {
+        crate::sharing::record(provider, model_name, system_prompt, user_input, (0, 0));
+    }
+    let res = req.json(&body).send().await.map_err(|e| describe_request_error(provider, &e))?;
 
     let status = res.status();
-    let text = res.text().await.unwrap_or_default();
+    let text = res.text().await.map_err(|e| describe_request_error(provider, &e))?;
 
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        throttled(provider);
+    }
     if !status.is_success() {
         return Err(format!("API Error [{}]: {}", status, text));
     }
@@ -49,25 +445,106 @@ pub async fn call_openai_compatible(
     json["choices"][0]["message"]["content"]
         .as_str()
         .ok_or_else(|| format!("No content in response: {}", text))
-        .map(|s| s.to_string())
+        .map(|s| (s.to_string(), openai_usage(&json)))
 }
 
 // --- Google Gemini 呼び出し (汎用) ---
 pub async fn call_google(model_name: &str, system_prompt: &str, user_input: &str) -> Result<String, String> {
-    let api_key = env::var("GEMINI_API_KEY").map_err(|_| "GEMINI_API_KEY missing".to_string())?;
-    let url = format!("https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}", model_name, api_key);
+    call_google_with(model_name, system_prompt, user_input, &[]).await
+}
 
-    let body = json!({
-        "system_instruction": { "parts": [{ "text": system_prompt }] },
-        "contents": [{ "parts": [{ "text": user_input }] }]
-    });
+// Gemini に文字以外も渡す（画像 / PDF 等）。小さいものは inline_data、大きいものは Files API に上げて file_data で
+pub struct Attachment {
+    pub mime: String,
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+// 1 回の依頼は全体で 20MB まで。余裕を見てこれを超えたら Files API へ
+const GEMINI_INLINE_MAX_BYTES: usize = 15 * 1024 * 1024;
+const GEMINI_API: &str = "https://generativelanguage.googleapis.com";
+// 動画 / 大きな PDF は処理が終わるまで使えない (state=PROCESSING)
+const GEMINI_FILE_POLLS: usize = 30;
+const GEMINI_FILE_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
-    let client = Client::new();
-    let res = client.post(&url).json(&body).send().await.map_err(|e| e.to_string())?;
+pub async fn call_google_with(
+    model_name: &str,
+    system_prompt: &str,
+    user_input: &str,
+    files: &[Attachment]
+) -> Result<String, String> {
+    let api_key = secrets::require_api_key("gemini")?;
+    // 伏せるのは文字だけ（画像 / ファイルの中身はそのまま）
+    let redaction = redact::prepare("gemini", &[system_prompt, user_input]).await;
+    let system_prompt = &redaction.apply(system_prompt);
+    let user_input = &redaction.apply(user_input);
+    acquire("gemini").await;
+    let started = Instant::now();
+    let (result, usage) = split_usage(request_google(&api_key, model_name, system_prompt, user_input, files).await);
+    let prompt_chars = system_prompt.chars().count() + user_input.chars().count();
+    record_call("gemini", model_name, started, prompt_chars, &result, usage);
+    result.map(|text| redaction.restore(&text))
+}
+
+// Files API に上げた分は答えが返ったら（失敗でも）消す。48 時間残しておかない
+async fn request_google(
+    api_key: &str,
+    model_name: &str,
+    system_prompt: &str,
+    user_input: &str,
+    files: &[Attachment]
+) -> Result<(String, Option<TokenUsage>), String> {
+    let client = client_for("gemini")?;
+    let mut uploaded: Vec<String> = Vec::new();
+    let result = generate_google(&client, api_key, model_name, system_prompt, user_input, files, &mut uploaded).await;
+    for name in &uploaded {
+        delete_gemini_file(&client, api_key, name).await;
+    }
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn generate_google(
+    client: &Client,
+    api_key: &str,
+    model_name: &str,
+    system_prompt: &str,
+    user_input: &str,
+    files: &[Attachment],
+    uploaded: &mut Vec<String>,
+) -> Result<(String, Option<TokenUsage>), String> {
+    let url = format!("{}/v1beta/models/{}:generateContent?key={}", GEMINI_API, model_name, api_key);
+
+    let mut parts = Vec::new();
+    for file in files {
+        if file.bytes.len() <= GEMINI_INLINE_MAX_BYTES {
+            parts.push(json!({ "inline_data": {
+                "mime_type": file.mime,
+                "data": base64::engine::general_purpose::STANDARD.encode(&file.bytes),
+            }}));
+        } else {
+            let uri = upload_gemini_file(client, api_key, file, uploaded).await?;
+            parts.push(json!({ "file_data": { "mime_type": file.mime, "file_uri": uri } }));
+        }
+    }
+    parts.push(json!({ "text": user_input }));
+
+    let mut body = json!({ "contents": [{ "parts": parts }] });
+    // 空の system_instruction は弾かれる
+    if !system_prompt.is_empty() {
+        body["system_instruction"] = json!({ "parts": [{ "text": system_prompt }] });
+    }
+
+    let file_bytes = files.iter().map(|f| f.bytes.len()).sum();
+    crate::sharing::record("gemini", model_name, system_prompt, user_input, (files.len(), file_bytes));
+    let res = client.post(&url).json(&body).send().await.map_err(|e| describe_request_error("gemini", &e))?;
     
     let status = res.status();
-    let text = res.text().await.unwrap_or_default();
+    let text = res.text().await.map_err(|e| describe_request_error("gemini", &e))?;
 
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        throttled("gemini");
+    }
     if !status.is_success() {
         return Err(format!("Gemini Error [{}]: {}", status, text));
     }
@@ -82,14 +559,273 @@ pub async fn call_google(model_name: &str, system_prompt: &str, user_input: &str
     json["candidates"][0]["content"]["parts"][0]["text"]
         .as_str()
         .ok_or_else(|| format!("No content in Gemini response: {}", text))
+        .map(|s| {
+            let usage = json.get("usageMetadata").and_then(|u| {
+                Some(TokenUsage {
+                    input: u["promptTokenCount"].as_u64().unwrap_or(0),
+                    output: u["candidatesTokenCount"].as_u64()?,
+                })
+            });
+            (s.to_string(), usage)
+        })
+}
+
+// Files API (resumable upload) に上げて file_uri を返す。上げたファイルの name は uploaded に（後で消す用）
+async fn upload_gemini_file(
+    client: &Client,
+    api_key: &str,
+    file: &Attachment,
+    uploaded_names: &mut Vec<String>,
+) -> Result<String, String> {
+    let start = client
+        .post(format!("{}/upload/v1beta/files?key={}", GEMINI_API, api_key))
+        .header("X-Goog-Upload-Protocol", "resumable")
+        .header("X-Goog-Upload-Command", "start")
+        .header("X-Goog-Upload-Header-Content-Length", file.bytes.len().to_string())
+        .header("X-Goog-Upload-Header-Content-Type", file.mime.as_str())
+        .json(&json!({ "file": { "display_name": file.name } }))
+        .send()
+        .await
+        .map_err(|e| describe_request_error("gemini", &e))?;
+    let status = start.status();
+    let upload_url = start
+        .headers()
+        .get("x-goog-upload-url")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string());
+    let Some(upload_url) = upload_url.filter(|_| status.is_success()) else {
+        let text = start.text().await.unwrap_or_default();
+        return Err(format!("Gemini Files API Error [{}]: {}", status, text));
+    };
+
+    let res = client
+        .post(&upload_url)
+        .header("X-Goog-Upload-Offset", "0")
+        .header("X-Goog-Upload-Command", "upload, finalize")
+        .body(file.bytes.clone())
+        .send()
+        .await
+        .map_err(|e| describe_request_error("gemini", &e))?;
+    let status = res.status();
+    let text = res.text().await.map_err(|e| describe_request_error("gemini", &e))?;
+    if !status.is_success() {
+        return Err(format!("Gemini Files API Error [{}]: {}", status, text));
+    }
+    let mut uploaded: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| format!("JSON Parse Error: {}", e))?;
+    let name = uploaded["file"]["name"].as_str().unwrap_or_default().to_string();
+    info!("📎 [Gemini] uploaded {} ({} bytes) as {}", file.name, file.bytes.len(), name);
+    if !name.is_empty() {
+        uploaded_names.push(name.clone());
+    }
+
+    for _ in 0..GEMINI_FILE_POLLS {
+        if uploaded["file"]["state"].as_str() != Some("PROCESSING") {
+            break;
+        }
+        tokio::time::sleep(GEMINI_FILE_POLL_INTERVAL).await;
+        let res = client
+            .get(format!("{}/v1beta/{}?key={}", GEMINI_API, name, api_key))
+            .send()
+            .await
+            .map_err(|e| describe_request_error("gemini", &e))?;
+        let status = res.status();
+        let text = res.text().await.map_err(|e| describe_request_error("gemini", &e))?;
+        if !status.is_success() {
+            return Err(format!("Gemini Files API Error [{}]: {}", status, text));
+        }
+        let file_json: serde_json::Value =
+            serde_json::from_str(&text).map_err(|e| format!("JSON Parse Error: {}", e))?;
+        uploaded = json!({ "file": file_json });
+    }
+    match uploaded["file"]["state"].as_str() {
+        Some("PROCESSING") => return Err(format!("Gemini is still processing {}", file.name)),
+        Some("FAILED") => return Err(format!("Gemini could not process {}", file.name)),
+        _ => {}
+    }
+    uploaded["file"]["uri"]
+        .as_str()
         .map(|s| s.to_string())
+        .ok_or_else(|| format!("No file uri in Gemini upload response: {}", text))
+}
+
+// 消せなくても Gemini 側で 48 時間後には消えるので、警告だけ
+async fn delete_gemini_file(client: &Client, api_key: &str, name: &str) {
+    let result = client
+        .delete(format!("{}/v1beta/{}?key={}", GEMINI_API, name, api_key))
+        .send()
+        .await;
+    match result {
+        Ok(res) if res.status().is_success() => debug!("📎 [Gemini] deleted {}", name),
+        Ok(res) => warn!("⚠️ [Gemini] failed to delete {}: {}", name, res.status()),
+        Err(e) => warn!("⚠️ [Gemini] failed to delete {}: {}", name, describe_request_error("gemini", &e)),
+    }
 }
 
 // --- ショートカット関数 ---
 pub async fn call_openai(model: &str, sys: &str, user: &str) -> Result<String, String> {
-    call_openai_compatible("https://api.openai.com/v1/chat/completions", "OPENAI_API_KEY", model, sys, user).await
+    call_openai_compatible("gpt", "https://api.openai.com/v1/chat/completions", model, sys, user).await
 }
 
 pub async fn call_grok(model: &str, sys: &str, user: &str) -> Result<String, String> {
-    call_openai_compatible("https://api.x.ai/v1/chat/completions", "XAI_API_KEY", model, sys, user).await
-}
\ No newline at end of file
+    call_openai_compatible("grok", "https://api.x.ai/v1/chat/completions", model, sys, user).await
+}
+
+// Commander のエイリアスで呼ぶ (gpt / gemini / grok / local / llama / settings.providers)
+// ローカル専用モードなら何を指定されてもローカルモデルへ
+pub async fn call_alias(alias: &str, model: &str, sys: &str, user: &str) -> Result<String, String> {
+    if alias != "local" && crate::local_only::enabled() {
+        return call_local(&settings::current().models.local, sys, user).await;
+    }
+    match alias {
+        "gpt" => call_openai(model, sys, user).await,
+        "gemini" => call_google(model, sys, user).await,
+        "grok" => call_grok(model, sys, user).await,
+        "local" => call_local(model, sys, user).await,
+        "llama" => {
+            call_openai_compatible("llama", "https://integrate.api.nvidia.com/v1/chat/completions", model, sys, user)
+                .await
+        }
+        other => call_provider(other, sys, user).await,
+    }
+}
+
+// ローカル LLM (Ollama の OpenAI互換エンドポイント)。オフライン時の退避先
+pub async fn call_local(model: &str, sys: &str, user: &str) -> Result<String, String> {
+    let url = settings::current().local_llm_url;
+    // 任意: LM Studio 等でキーが要る場合だけ付ける
+    let headers: Vec<_> = env::var("LOCAL_LLM_API_KEY")
+        .ok()
+        .filter(|k| !k.is_empty())
+        .map(|k| bearer(&k))
+        .into_iter()
+        .collect();
+    post_chat_completion("local", &url, &headers, model, sys, user)
+        .await
+        .map_err(|e| format!("Local LLM ({}) unavailable: {}", url, e))
+}
+
+// ローカルの視覚モデル（Ollama の OpenAI互換エンドポイントに image_url で渡す）。local_only の LOOK 用
+pub async fn call_local_vision(model: &str, prompt: &str, base64_png: &str) -> Result<String, String> {
+    let url = settings::current().local_llm_url;
+    let headers: Vec<_> = env::var("LOCAL_LLM_API_KEY")
+        .ok()
+        .filter(|k| !k.is_empty())
+        .map(|k| bearer(&k))
+        .into_iter()
+        .collect();
+    post_vision("local", &url, &headers, model, prompt, base64_png)
+        .await
+        .map_err(|e| format!("Local vision model ({}) unavailable: {}", model, e))
+}
+
+// OpenAI の視覚モデル (gpt-4o 等)。vision.rs のフォールバック候補
+pub async fn call_openai_vision(model: &str, prompt: &str, base64_png: &str) -> Result<String, String> {
+    let api_key = secrets::require_api_key("gpt")?;
+    crate::sharing::record("gpt", model, "", prompt, (1, base64_png.len()));
+    let headers = [bearer(&api_key)];
+    post_vision("gpt", "https://api.openai.com/v1/chat/completions", &headers, model, prompt, base64_png).await
+}
+
+// OpenAI互換の chat/completions に image_url で画像を渡す (local / gpt)
+async fn post_vision(
+    provider: &str,
+    url: &str,
+    headers: &[(String, String)],
+    model: &str,
+    prompt: &str,
+    base64_png: &str
+) -> Result<String, String> {
+    let body = json!({
+        "model": model,
+        "messages": [{
+            "role": "user",
+            "content": [
+                { "type": "text", "text": prompt },
+                { "type": "image_url", "image_url": { "url": format!("data:image/png;base64,{}", base64_png) } }
+            ]
+        }]
+    });
+    acquire(provider).await;
+    let started = Instant::now();
+    let result = async {
+        let mut req = client_for(provider)?.post(url).header("Content-Type", "application/json");
+        for (name, value) in headers {
+            req = req.header(name.as_str(), value.as_str());
+        }
+        let res = req.json(&body).send().await.map_err(|e| describe_request_error(provider, &e))?;
+        let status = res.status();
+        let text = res.text().await.map_err(|e| describe_request_error(provider, &e))?;
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            throttled(provider);
+        }
+        if !status.is_success() {
+            return Err(format!("API Error [{}]: {}", status, text));
+        }
+        let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| format!("JSON Parse Error: {}", e))?;
+        json["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| format!("No content in response: {}", text))
+            .map(|s| (s.to_string(), openai_usage(&json)))
+    }
+    .await;
+    let (result, usage) = split_usage(result);
+    record_call(provider, model, started, prompt.chars().count(), &result, usage);
+    result
+}
+
+// Anthropic Claude の視覚 (Messages API)。キーは secrets "anthropic"
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+pub async fn call_claude_vision(model: &str, prompt: &str, base64_png: &str) -> Result<String, String> {
+    let api_key = secrets::require_api_key("claude")?;
+    let body = json!({
+        "model": model,
+        "max_tokens": 1024,
+        "messages": [{
+            "role": "user",
+            "content": [
+                { "type": "image", "source": { "type": "base64", "media_type": "image/png", "data": base64_png } },
+                { "type": "text", "text": prompt }
+            ]
+        }]
+    });
+    acquire("claude").await;
+    let started = Instant::now();
+    crate::sharing::record("claude", model, "", prompt, (1, base64_png.len()));
+    let result = async {
+        let res = client_for("claude")?
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", &api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| describe_request_error("claude", &e))?;
+        let status = res.status();
+        let text = res.text().await.map_err(|e| describe_request_error("claude", &e))?;
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            throttled("claude");
+        }
+        if !status.is_success() {
+            return Err(format!("Claude Error [{}]: {}", status, text));
+        }
+        let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| format!("JSON Parse Error: {}", e))?;
+        json["content"][0]["text"]
+            .as_str()
+            .ok_or_else(|| format!("No content in Claude response: {}", text))
+            .map(|s| {
+                let usage = json.get("usage").and_then(|u| {
+                    Some(TokenUsage {
+                        input: u["input_tokens"].as_u64().unwrap_or(0),
+                        output: u["output_tokens"].as_u64()?,
+                    })
+                });
+                (s.to_string(), usage)
+            })
+    }
+    .await;
+    let (result, usage) = split_usage(result);
+    record_call("claude", model, started, prompt.chars().count(), &result, usage);
+    result
+}
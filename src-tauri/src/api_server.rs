@@ -0,0 +1,620 @@
+// src-tauri/src/api_server.rs
+//
+// ローカルの HTTP API / MCP サーバー（スクリプト / エディタ / 他のエージェントから Axis を動かす用）
+// - settings.api_server_enabled で 127.0.0.1:<api_server_port> だけに開く（env: AXIS_API_SERVER / AXIS_API_PORT）
+//   update_settings の度に sync して、止める / ポートを変えて開き直す
+// - 全リクエストに Authorization: Bearer <token>。トークンは OS 資格情報ストア (secrets.rs の token "api_server")
+//   get_api_server_status で見る / rotate_api_token で作り直す
+//   ブラウザ拡張には別のトークン (token "browser_extension")。/v1/browser/ の下にしか使えない
+//   （拡張に入れたトークンで /v1/ask や /v1/actions を叩かせない）。rotate_browser_token で作り直す
+//   Host が 127.0.0.1 / localhost でないものは断る（ブラウザからの DNS rebinding 対策）
+// - HTTP (JSON):
+//     POST /v1/ask            {"input": "...", "session_id": "..."}  → ask_axis と同じ InteractionLog
+//     POST /v1/memory/search  {"query": "...", "limit": 5}
+//     POST /v1/actions        {"command": "APPS && EXEC: notepad", "session_id": "..."}
+//     GET  /v1/pending        確認待ちの一覧（承認はアプリの確認ダイアログからだけ）
+//     POST /v1/browser/tab    {"url", "title", "browser"}  ブラウザ拡張からの今のタブ (connectors/browser.rs。browser_bridge_enabled の時だけ)
+//     GET  /v1/browser/wait   拡張の待ち受け。BROWSER_TAB が本文を頼んだら {"want_text": true}（BROWSER_WAIT で false）
+//     POST /v1/browser/text   {"url", "text"}  頼まれた時だけ拡張が送る今のタブの本文
+//     POST /mcp               MCP (JSON-RPC 2.0。initialize / tools/list / tools/call)。ツールは上と同じ 4 つ
+// - /v1/actions と /v1/ask の答えの中の操作は、外から来た文面の後と同じ扱い (axis_core::policy::after_untrusted)
+//   読むだけのものはすぐ実行、EXEC / TYPE / RUN / KILL / SAVE / HA / MACRO 等は確認待ちに、
+//   PLAN / BACKGROUND（別のセッションでモデルを動かし直すもの）は断る
+//   実行したものは監査記録に initiator "api" で
+// - WebSocket は無し（1 回の依頼は 1 回の応答で返る。途中経過は返さない）
+
+use crate::adapter;
+use crate::connectors::browser;
+use crate::{audit, incognito, memory, policy, secrets, settings};
+use axis_core::command::{self, Action};
+use axis_core::engine::ActionExecutor;
+use axis_core::policy::Hold;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::{self, JoinHandle};
+use tauri::AppHandle;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+const TOKEN_NAME: &str = "api_server";
+const BROWSER_TOKEN_NAME: &str = "browser_extension";
+// 拡張の待ち受け 1 回の長さ（拡張はすぐ次を投げる）
+const BROWSER_WAIT: Duration = Duration::from_secs(25);
+const DEFAULT_SESSION: &str = "api";
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+const MCP_PROTOCOL_VERSION: &str = "2025-03-26";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ApiServerStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub url: String,
+    pub token: String,
+    pub browser_token: String, // ブラウザ拡張用（/v1/browser/ の下だけ）
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ActionResult {
+    command: String,
+    status: &'static str, // ran / pending / refused / invalid
+    output: String,
+}
+
+struct Running {
+    port: u16,
+    handle: JoinHandle<()>,
+}
+
+static SERVER: Mutex<Option<Running>> = Mutex::new(None);
+static LISTENING: AtomicBool = AtomicBool::new(false);
+// 資格情報ストアが使えない時はこの起動の間だけのトークン
+static TOKEN: Mutex<Option<String>> = Mutex::new(None);
+static BROWSER_TOKEN: Mutex<Option<String>> = Mutex::new(None);
+
+fn new_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn cached_token(name: &str, cache: &Mutex<Option<String>>) -> String {
+    let mut cached = cache.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(t) = cached.as_ref() {
+        return t.clone();
+    }
+    let t = secrets::token(name).unwrap_or_else(|| {
+        let t = new_token();
+        if let Err(e) = secrets::set_token(name, &t) {
+            warn!("⚠️ [ApiServer] {} token is kept for this run only: {}", name, e);
+        }
+        t
+    });
+    *cached = Some(t.clone());
+    t
+}
+
+fn token() -> String {
+    cached_token(TOKEN_NAME, &TOKEN)
+}
+
+fn browser_token() -> String {
+    cached_token(BROWSER_TOKEN_NAME, &BROWSER_TOKEN)
+}
+
+pub fn status() -> ApiServerStatus {
+    let cfg = settings::current();
+    ApiServerStatus {
+        enabled: cfg.api_server_enabled,
+        running: LISTENING.load(Ordering::Relaxed),
+        url: format!("http://127.0.0.1:{}", cfg.api_server_port),
+        token: token(),
+        browser_token: browser_token(),
+    }
+}
+
+pub fn rotate_token() -> Result<ApiServerStatus, String> {
+    let t = new_token();
+    secrets::set_token(TOKEN_NAME, &t)?;
+    *TOKEN.lock().unwrap_or_else(|e| e.into_inner()) = Some(t);
+    info!("🔌 [ApiServer] token rotated");
+    Ok(status())
+}
+
+pub fn rotate_browser_token() -> Result<ApiServerStatus, String> {
+    let t = new_token();
+    secrets::set_token(BROWSER_TOKEN_NAME, &t)?;
+    *BROWSER_TOKEN.lock().unwrap_or_else(|e| e.into_inner()) = Some(t);
+    info!("🔌 [ApiServer] browser extension token rotated");
+    Ok(status())
+}
+
+// 設定に合わせて開く / 止める
+pub fn sync(app: &AppHandle) {
+    let cfg = settings::current();
+    let want = cfg.api_server_enabled.then_some(cfg.api_server_port);
+    let mut server = SERVER.lock().unwrap_or_else(|e| e.into_inner());
+    if server.as_ref().map(|r| r.port) == want {
+        return;
+    }
+    if let Some(running) = server.take() {
+        running.handle.abort();
+        LISTENING.store(false, Ordering::Relaxed);
+        info!("🔌 [ApiServer] stopped");
+    }
+    if let Some(port) = want {
+        let handle = async_runtime::spawn(serve(app.clone(), port));
+        *server = Some(Running { port, handle });
+    }
+}
+
+async fn serve(app: AppHandle, port: u16) {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await {
+        Ok(l) => l,
+        Err(e) => {
+            warn!("⚠️ [ApiServer] failed to listen on port {}: {}", port, e);
+            return;
+        }
+    };
+    LISTENING.store(true, Ordering::Relaxed);
+    info!("🔌 [ApiServer] listening on http://127.0.0.1:{}", port);
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("⚠️ [ApiServer] accept failed: {}", e);
+                continue;
+            }
+        };
+        if !peer.ip().is_loopback() {
+            continue;
+        }
+        let app = app.clone();
+        async_runtime::spawn(async move {
+            if let Err(e) = handle(&app, stream, port).await {
+                warn!("⚠️ [ApiServer] {}", e);
+            }
+        });
+    }
+}
+
+// ---------- HTTP ----------
+
+struct Request {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn header_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|w| w == b"\r\n\r\n")
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, (u16, String)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let end = loop {
+        if let Some(end) = header_end(&buf) {
+            break end;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return Err((413, "headers too large".to_string()));
+        }
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| (400, e.to_string()))?;
+        if n == 0 {
+            return Err((400, "connection closed".to_string()));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+
+    let length: usize = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.parse().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err((413, "body too large".to_string()));
+    }
+    let mut body = buf[end + 4..].to_vec();
+    while body.len() < length {
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| (400, e.to_string()))?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(length);
+    Ok(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+async fn write_response(stream: &mut TcpStream, status: u16, body: Option<&Value>) {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    };
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+fn error(message: &str) -> Value {
+    json!({ "error": message })
+}
+
+// 長さで漏れないように全部比べる
+fn same_token(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn local_host(host: &str, port: u16) -> bool {
+    let port = port.to_string();
+    let (name, p) = host.rsplit_once(':').unwrap_or((host, &port));
+    p == port && matches!(name, "127.0.0.1" | "localhost")
+}
+
+async fn handle(app: &AppHandle, mut stream: TcpStream, port: u16) -> Result<(), String> {
+    let request = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(r)) => r,
+        Ok(Err((status, message))) => {
+            write_response(&mut stream, status, Some(&error(&message))).await;
+            return Err(message);
+        }
+        Err(_) => return Err("request timed out".to_string()),
+    };
+
+    if !request.header("host").is_some_and(|h| local_host(h, port)) {
+        write_response(&mut stream, 403, Some(&error("host not allowed"))).await;
+        return Err(format!(
+            "rejected host {:?}",
+            request.header("host").unwrap_or_default()
+        ));
+    }
+    let path = request.path.split('?').next().unwrap_or_default();
+    let given = request
+        .header("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    // 拡張のトークンは /v1/browser/ の下だけ
+    let authorized = given.is_some_and(|t| {
+        same_token(t, &token())
+            || (path.starts_with("/v1/browser/") && same_token(t, &browser_token()))
+    });
+    if !authorized {
+        write_response(&mut stream, 401, Some(&error("missing or wrong token"))).await;
+        return Err(format!("unauthorized {} {}", request.method, request.path));
+    }
+
+    let body: Value = if request.body.is_empty() {
+        json!({})
+    } else {
+        match serde_json::from_slice(&request.body) {
+            Ok(v) => v,
+            Err(e) => {
+                write_response(
+                    &mut stream,
+                    400,
+                    Some(&error(&format!("invalid JSON: {}", e))),
+                )
+                .await;
+                return Ok(());
+            }
+        }
+    };
+    info!("🔌 [ApiServer] {} {}", request.method, request.path);
+
+    let (status, response) = match (request.method.as_str(), path) {
+        ("POST", "/mcp") => match mcp(app, &body).await {
+            Some(v) => (200, Some(v)),
+            None => (202, None),
+        },
+        ("POST", "/v1/ask") => result(ask(app, &body).await),
+        ("POST", "/v1/memory/search") => result(search_memory(app, &body).await),
+        ("POST", "/v1/actions") => result(run_actions(app, &body).await),
+        ("GET", "/v1/pending") => (200, Some(json!(policy::list_pending(app)))),
+        ("POST", "/v1/browser/tab") => result(browser_tab(&body)),
+        ("GET", "/v1/browser/wait") => result(browser_wait().await),
+        ("POST", "/v1/browser/text") => result(browser_text(&body)),
+        (
+            _,
+            "/mcp" | "/v1/ask" | "/v1/memory/search" | "/v1/actions" | "/v1/pending"
+            | "/v1/browser/tab" | "/v1/browser/wait" | "/v1/browser/text",
+        ) => {
+            (405, Some(error("method not allowed")))
+        }
+        _ => (404, Some(error("not found"))),
+    };
+    write_response(&mut stream, status, response.as_ref()).await;
+    Ok(())
+}
+
+fn result(r: Result<Value, String>) -> (u16, Option<Value>) {
+    match r {
+        Ok(v) => (200, Some(v)),
+        Err(e) => (400, Some(error(&e))),
+    }
+}
+
+// ---------- 操作 ----------
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn session(args: &Value) -> String {
+    str_arg(args, "session_id")
+        .unwrap_or(DEFAULT_SESSION)
+        .to_string()
+}
+
+async fn ask(app: &AppHandle, args: &Value) -> Result<Value, String> {
+    let input = str_arg(args, "input").ok_or("input is required")?;
+    let log = crate::run_axis(
+        app.clone(),
+        input.to_string(),
+        session(args),
+        crate::AskOptions {
+            untrusted_input: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+    serde_json::to_value(log).map_err(|e| e.to_string())
+}
+
+async fn search_memory(app: &AppHandle, args: &Value) -> Result<Value, String> {
+    let query = str_arg(args, "query")
+        .ok_or("query is required")?
+        .to_string();
+    let limit = args
+        .get("limit")
+        .and_then(Value::as_u64)
+        .unwrap_or(5)
+        .clamp(1, 50) as usize;
+    let app = app.clone();
+    let hits = async_runtime::spawn_blocking(move || memory::search_top_k(&app, &query, limit))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(json!(hits
+        .into_iter()
+        .map(|h| json!({ "id": h.id, "score": h.score, "entry": h.entry }))
+        .collect::<Vec<_>>()))
+}
+
+fn browser_tab(args: &Value) -> Result<Value, String> {
+    if !settings::current().browser_bridge_enabled {
+        return Err("the browser bridge is disabled (browser_bridge_enabled)".to_string());
+    }
+    let url = str_arg(args, "url").ok_or("url is required")?;
+    let tab = browser::update(
+        url,
+        str_arg(args, "title").unwrap_or_default(),
+        str_arg(args, "browser").unwrap_or_default(),
+    )?;
+    serde_json::to_value(tab).map_err(|e| e.to_string())
+}
+
+async fn browser_wait() -> Result<Value, String> {
+    if !settings::current().browser_bridge_enabled {
+        return Err("the browser bridge is disabled (browser_bridge_enabled)".to_string());
+    }
+    Ok(json!({ "want_text": browser::wait_for_request(BROWSER_WAIT).await }))
+}
+
+fn browser_text(args: &Value) -> Result<Value, String> {
+    if !settings::current().browser_bridge_enabled {
+        return Err("the browser bridge is disabled (browser_bridge_enabled)".to_string());
+    }
+    let url = str_arg(args, "url").ok_or("url is required")?;
+    let used = browser::deliver_text(url, str_arg(args, "text").unwrap_or_default())?;
+    Ok(json!({ "used": used }))
+}
+
+async fn run_actions(app: &AppHandle, args: &Value) -> Result<Value, String> {
+    let text = str_arg(args, "command").ok_or("command is required")?;
+    let session_id = session(args);
+    let cfg = settings::current();
+    let mut actions =
+        adapter::Actions::new(app, &session_id, &cfg, incognito::is_active(&session_id));
+
+    let mut results = Vec::new();
+    for cmd in command::parse_response(text) {
+        let (status, output) = match (&cmd.action, axis_core::policy::after_untrusted(&cmd)) {
+            (Action::Unknown, _) => ("invalid", format!("'{}' is not an action", cmd.label())),
+            (Action::Invalid { message }, _) => ("invalid", message.clone()),
+            (_, Some(Hold::Confirm)) => {
+                let pending = policy::enqueue(app, cmd.action.clone(), &session_id);
+                (
+                    "pending",
+                    format!(
+                        "{} {} needs the user's approval in Axis (pending id={})",
+                        pending.action, pending.argument, pending.id
+                    ),
+                )
+            }
+            (_, Some(Hold::Refuse)) => (
+                "refused",
+                format!("{} is not available over the API", cmd.action.name()),
+            ),
+            (_, None) => {
+                let mut output = String::new();
+                actions.execute(&cmd, &mut output).await;
+                audit::record(app, "api", &cmd.raw, &output, &session_id);
+                ("ran", output.trim().to_string())
+            }
+        };
+        results.push(ActionResult {
+            command: cmd.label(),
+            status,
+            output,
+        });
+    }
+    if results.is_empty() {
+        return Err("no actions in command".to_string());
+    }
+    Ok(json!(results))
+}
+
+// ---------- MCP ----------
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "ask_axis",
+            "description": "Ask Axis (routes to the best model, may run desktop actions) and return its answer",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "input": { "type": "string" },
+                    "session_id": { "type": "string" }
+                },
+                "required": ["input"]
+            }
+        },
+        {
+            "name": "search_memory",
+            "description": "Search Axis's long-term memory of past conversations",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": 50 }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "run_action",
+            "description": "Run Axis actions such as 'APPS', 'PROCESSES: cpu' or 'EXEC: notepad' (chain with ' && '). Actions that change the desktop wait for the user's approval in Axis",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "command": { "type": "string" },
+                    "session_id": { "type": "string" }
+                },
+                "required": ["command"]
+            }
+        },
+        {
+            "name": "list_pending_actions",
+            "description": "List actions waiting for the user's approval",
+            "inputSchema": { "type": "object", "properties": {} }
+        }
+    ])
+}
+
+async fn call_tool(app: &AppHandle, params: &Value) -> Value {
+    let args = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    let result = match params
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+    {
+        "ask_axis" => ask(app, &args)
+            .await
+            .map(|log| log["ai_response"].as_str().unwrap_or_default().to_string()),
+        "search_memory" => search_memory(app, &args)
+            .await
+            .map(|v| serde_json::to_string_pretty(&v).unwrap_or_default()),
+        "run_action" => run_actions(app, &args)
+            .await
+            .map(|v| serde_json::to_string_pretty(&v).unwrap_or_default()),
+        "list_pending_actions" => {
+            Ok(serde_json::to_string_pretty(&policy::list_pending(app)).unwrap_or_default())
+        }
+        other => Err(format!("unknown tool '{}'", other)),
+    };
+    let (text, is_error) = match result {
+        Ok(text) => (text, false),
+        Err(e) => (e, true),
+    };
+    json!({ "content": [{ "type": "text", "text": text }], "isError": is_error })
+}
+
+// JSON-RPC 1 件。通知 (id 無し) には何も返さない
+async fn mcp(app: &AppHandle, message: &Value) -> Option<Value> {
+    let id = message.get("id")?.clone();
+    let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+    let result = match message
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+    {
+        "initialize" => Ok(json!({
+            "protocolVersion": params
+                .get("protocolVersion")
+                .and_then(Value::as_str)
+                .unwrap_or(MCP_PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "axis-os", "version": app.package_info().version.to_string() }
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => Ok(call_tool(app, &params).await),
+        other => Err(json!({ "code": -32601, "message": format!("method not found: {}", other) })),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    })
+}
@@ -0,0 +1,169 @@
+// src-tauri/src/app_catalog.rs
+//
+// インストール済みアプリの一覧（EXEC を PowerShell 無しで解決する / Worker にあるアプリを見せる）
+// - 裏のループが settings.app_catalog_refresh_hours おきにスタートメニューのアプリ (win32.rs。だめなら Get-StartApps) を読み、memory.db の app_catalog に入れる
+//   起動時はまず memory.db の前回の一覧を使う（取り込みは裏で）
+// - EXEC: 別名 → 組み込みの名前の次に find（axis_core::apps のあいまい検索）。見つかれば AppID で起動して回数を記録
+//   一覧に無い時だけ従来どおり PowerShell で探す (shell.rs)
+// - worker プロンプトの {{apps}}: 最近使った順に PROMPT_APPS 個まで
+// - Windows 以外は一覧が空のまま（EXEC は従来どおり）
+
+use crate::db::AxisDatabase;
+use crate::settings;
+use axis_core::apps::{self, AppEntry};
+use chrono::Local;
+use serde::Deserialize;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+const PROMPT_APPS: usize = 40;
+
+static CATALOG: RwLock<Vec<AppEntry>> = RwLock::new(Vec::new());
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+#[derive(Deserialize)]
+struct StartApp {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "AppID")]
+    app_id: String,
+}
+
+fn load(db: &AxisDatabase) -> Result<usize, String> {
+    let list = db.list_app_catalog().map_err(|e| e.to_string())?;
+    let n = list.len();
+    *CATALOG.write().unwrap_or_else(|e| e.into_inner()) = list;
+    Ok(n)
+}
+
+// スタートメニューの (AppID, 名前)。AppsFolder を直接読み (win32.rs)、だめなら Get-StartApps
+#[cfg(target_os = "windows")]
+fn start_apps() -> Result<Vec<(String, String)>, String> {
+    match crate::win32::start_apps() {
+        Ok(apps) if !apps.is_empty() => return Ok(apps),
+        Ok(_) => warn!("⚠️ [Apps] AppsFolder is empty, using Get-StartApps"),
+        Err(e) => warn!("⚠️ [Apps] {}, using Get-StartApps", e),
+    }
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-WindowStyle",
+            "Hidden",
+            "-Command",
+            "[Console]::OutputEncoding = [Text.Encoding]::UTF8; Get-StartApps | Select-Object Name, AppID | ConvertTo-Json -Compress",
+        ])
+        .creation_flags(0x08000000)
+        .output()
+        .map_err(|e| format!("Get-StartApps failed: {}", e))?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Get-StartApps returned nothing".to_string());
+    }
+    // 1 件だけの時は配列にならない
+    let list: Vec<StartApp> = if text.starts_with('[') {
+        serde_json::from_str(text)
+    } else {
+        serde_json::from_str(text).map(|a| vec![a])
+    }
+    .map_err(|e| format!("Get-StartApps output: {}", e))?;
+    Ok(list
+        .into_iter()
+        .filter(|a| !a.app_id.trim().is_empty() && !a.name.trim().is_empty())
+        .map(|a| (a.app_id.trim().to_string(), a.name.trim().to_string()))
+        .collect())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn start_apps() -> Result<Vec<(String, String)>, String> {
+    Err("the app catalog is only available on Windows".to_string())
+}
+
+// 読み直して memory.db と手元の一覧を入れ替える。戻り値は件数
+pub fn refresh(app: &AppHandle) -> Result<usize, String> {
+    let found = start_apps()?;
+    let db = AxisDatabase::open(app)?;
+    db.replace_app_catalog(&found).map_err(|e| e.to_string())?;
+    let n = load(&db)?;
+    info!("🗂️ [Apps] catalog refreshed: {} apps", n);
+    Ok(n)
+}
+
+pub fn spawn_refresher(app: AppHandle) {
+    let _ = APP.set(app.clone());
+    match AxisDatabase::open(&app).and_then(|db| load(&db)) {
+        Ok(n) => info!("🗂️ [Apps] {} apps from the last refresh", n),
+        Err(e) => warn!("⚠️ [Apps] could not load the catalog: {}", e),
+    }
+    if !cfg!(target_os = "windows") {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let hours = settings::current().app_catalog_refresh_hours;
+            if hours > 0 {
+                let handle = app.clone();
+                match tauri::async_runtime::spawn_blocking(move || refresh(&handle)).await {
+                    Ok(Err(e)) => warn!("⚠️ [Apps] refresh failed: {}", e),
+                    Err(e) => warn!("⚠️ [Apps] refresh failed: {}", e),
+                    Ok(Ok(_)) => {}
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(hours.max(1) * 3600)).await;
+        }
+    });
+}
+
+pub fn list() -> Vec<AppEntry> {
+    CATALOG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn find(query: &str) -> Option<AppEntry> {
+    let catalog = CATALOG.read().unwrap_or_else(|e| e.into_inner());
+    apps::find(&catalog, query).cloned()
+}
+
+// EXEC で起動した時
+pub fn record_launch(app_id: &str) {
+    let now = Local::now().timestamp_millis();
+    if let Some(a) = CATALOG
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter_mut()
+        .find(|a| a.id == app_id)
+    {
+        a.launches += 1;
+        a.last_launched = Some(now);
+    }
+    let Some(app) = APP.get() else {
+        return;
+    };
+    if let Err(e) = AxisDatabase::open(app)
+        .and_then(|db| db.mark_app_launched(app_id, now).map_err(|e| e.to_string()))
+    {
+        warn!("⚠️ [Apps] could not record the launch: {}", e);
+    }
+}
+
+// worker プロンプトの {{apps}}
+pub fn prompt_list() -> String {
+    let catalog = CATALOG.read().unwrap_or_else(|e| e.into_inner());
+    if catalog.is_empty() {
+        return "(not loaded yet; EXEC still searches the Start menu)".to_string();
+    }
+    let names: Vec<&str> = apps::shortlist(&catalog, PROMPT_APPS)
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect();
+    let more = catalog.len().saturating_sub(names.len());
+    if more > 0 {
+        format!("{} (+{} more)", names.join(", "), more)
+    } else {
+        names.join(", ")
+    }
+}
@@ -0,0 +1,302 @@
+// src-tauri/src/audio.rs
+//
+// 録音の取り込み (ingest_audio)。会議の録音やボイスメモを文字起こしして要約する
+// - 対応: mp3 / wav / m4a（ほか ogg / flac / webm も送れるものは送る）
+// - 文字起こしは settings.transcription_provider:
+//   "openai"  /v1/audio/transcriptions（25MB まで。whisper-1 は長さから料金を budget.rs に）
+//   "gemini"  音声をそのまま渡す（大きいものは Files API / ai.rs）
+//   "local"   OpenAI 互換の Whisper サーバー (local_whisper_url)。ローカル専用モードは必ずこれ
+// - 文字起こしはオブジェクトストアに .txt で、要約 + 全文は長期のメモリに（同じファイルは同じ id で書き直す）
+// - 要約は settings.audio_summarizer のモデルで（失敗しても文字起こしは残す）
+
+use crate::{ai, budget, health, local_only, memory, objects, prompts, settings, sharing, shell};
+use chrono::Local;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+const OPENAI_MAX_BYTES: u64 = 25 * 1024 * 1024;
+const MAX_FILE_BYTES: u64 = 200 * 1024 * 1024;
+// 要約に渡す文字数の上限（長い会議は頭から）
+const SUMMARY_MAX_CHARS: usize = 60_000;
+// USD / 分
+const PRICES_PER_MINUTE: &[(&str, f64)] = &[
+    ("gpt-4o-mini-transcribe", 0.003),
+    ("gpt-4o-transcribe", 0.006),
+    ("whisper", 0.006),
+];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AudioIngest {
+    pub memory_id: String,
+    pub object_id: String, // 文字起こしの .txt (objects.rs)
+    pub name: String,
+    pub provider: String,
+    pub duration_secs: Option<f64>,
+    pub transcript_chars: usize,
+    pub summary: String,
+}
+
+fn mime_for(ext: &str) -> Option<&'static str> {
+    match ext {
+        "mp3" => Some("audio/mpeg"),
+        "wav" => Some("audio/wav"),
+        "m4a" | "mp4" => Some("audio/mp4"),
+        "ogg" | "oga" => Some("audio/ogg"),
+        "flac" => Some("audio/flac"),
+        "webm" => Some("audio/webm"),
+        _ => None,
+    }
+}
+
+pub async fn ingest(app: &AppHandle, path: &str) -> Result<AudioIngest, String> {
+    let path = shell::resolve_user_path(path.trim());
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mime =
+        mime_for(&ext).ok_or_else(|| format!("{}: not an audio file (mp3 / wav / m4a)", name))?;
+    let size = fs::metadata(&path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?
+        .len();
+    if size > MAX_FILE_BYTES {
+        return Err(format!(
+            "{} is too large ({} MB)",
+            name,
+            size / (1024 * 1024)
+        ));
+    }
+    let bytes = fs::read(&path).map_err(|e| format!("{}: {}", name, e))?;
+
+    let cfg = settings::current();
+    let provider = if local_only::enabled() {
+        "local"
+    } else {
+        cfg.transcription_provider.as_str()
+    };
+    info!(
+        "🎙️ [Audio] transcribing {} ({} bytes) with {}",
+        name, size, provider
+    );
+    let (transcript, duration_secs) = match provider {
+        "openai" if size > OPENAI_MAX_BYTES => {
+            return Err(format!(
+                "{} is over 25 MB, the OpenAI limit: use transcription_provider \"gemini\" or \"local\"",
+                name
+            ))
+        }
+        "openai" => transcribe_openai(&cfg.transcription_model, &name, mime, &bytes).await?,
+        "gemini" => (transcribe_gemini(&cfg.models.gemini, &name, mime, &bytes).await?, None),
+        "local" => transcribe_local(&cfg, &name, mime, &bytes).await?,
+        other => {
+            return Err(format!(
+                "unknown transcription_provider '{}': use openai / gemini / local",
+                other
+            ))
+        }
+    };
+    let transcript = transcript.trim().to_string();
+    if transcript.is_empty() {
+        return Err(format!("no speech found in {}", name));
+    }
+
+    let stem = Path::new(&name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let object = objects::save(
+        app,
+        transcript.as_bytes(),
+        "text/plain",
+        &format!("{}.transcript.txt", stem),
+    )?;
+
+    let summary = match summarize(app, &cfg, &transcript).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            warn!("⚠️ [Audio] summary failed for {}: {}", name, e);
+            format!("(summary failed: {})", e)
+        }
+    };
+
+    let memory_id = format!("audio-{}", &objects::hash(&bytes)[..16]);
+    let recorded_at = fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .map(|t| chrono::DateTime::<Local>::from(t).timestamp_millis())
+        .unwrap_or_else(|_| Local::now().timestamp_millis());
+    memory::save_long_term(
+        app,
+        &memory_id,
+        "audio",
+        recorded_at,
+        &format!("[Audio] {}", name),
+        &format!("{}\n\n[Transcript]\n{}", summary, transcript),
+        "audio",
+        vec!["audio".to_string(), "transcript".to_string()],
+    )?;
+    info!(
+        "🎙️ [Audio] {}: {} chars stored as {}",
+        name,
+        transcript.chars().count(),
+        memory_id
+    );
+
+    Ok(AudioIngest {
+        memory_id,
+        object_id: object.id,
+        name,
+        provider: provider.to_string(),
+        duration_secs,
+        transcript_chars: transcript.chars().count(),
+        summary,
+    })
+}
+
+// whisper-1 は verbose_json で長さも返す（料金の計算に使う）。gpt-4o-*-transcribe は json だけ
+async fn transcribe_openai(
+    model: &str,
+    name: &str,
+    mime: &str,
+    bytes: &[u8],
+) -> Result<(String, Option<f64>), String> {
+    let key = crate::secrets::require_api_key("gpt")?;
+    let format = if model.starts_with("whisper") {
+        "verbose_json"
+    } else {
+        "json"
+    };
+    sharing::record(
+        "gpt",
+        model,
+        "",
+        &format!("[audio] {}", name),
+        (1, bytes.len()),
+    );
+    let (content_type, body) = ai::multipart(
+        &[("model", model), ("response_format", format)],
+        Some(("file", name, mime, bytes)),
+    );
+    let req = ai::client_for("gpt")?
+        .post("https://api.openai.com/v1/audio/transcriptions")
+        .bearer_auth(&key)
+        .header("Content-Type", content_type)
+        .body(body);
+    let result = post("gpt", model, req).await?;
+    if let Some(secs) = result.1 {
+        let per_minute = PRICES_PER_MINUTE
+            .iter()
+            .find(|(prefix, _)| model.starts_with(prefix))
+            .map(|(_, usd)| *usd)
+            .unwrap_or(0.0);
+        budget::record_usd("gpt", model, secs / 60.0 * per_minute);
+    }
+    Ok(result)
+}
+
+async fn transcribe_local(
+    cfg: &settings::Settings,
+    name: &str,
+    mime: &str,
+    bytes: &[u8],
+) -> Result<(String, Option<f64>), String> {
+    let (content_type, body) = ai::multipart(
+        &[
+            ("model", &cfg.transcription_model),
+            ("response_format", "json"),
+        ],
+        Some(("file", name, mime, bytes)),
+    );
+    let req = ai::client_for("local")?
+        .post(&cfg.local_whisper_url)
+        .header("Content-Type", content_type)
+        .body(body);
+    post("local", &cfg.transcription_model, req)
+        .await
+        .map_err(|e| format!("local Whisper server ({}): {}", cfg.local_whisper_url, e))
+}
+
+async fn post(
+    provider: &str,
+    model: &str,
+    req: reqwest::RequestBuilder,
+) -> Result<(String, Option<f64>), String> {
+    ai::acquire(provider).await;
+    let started = Instant::now();
+    let result = async {
+        let res = req
+            .send()
+            .await
+            .map_err(|e| ai::describe_request_error(provider, &e))?;
+        let status = res.status();
+        let text = res
+            .text()
+            .await
+            .map_err(|e| ai::describe_request_error(provider, &e))?;
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            ai::throttled(provider);
+        }
+        if !status.is_success() {
+            return Err(format!("Transcription Error [{}]: {}", status, text));
+        }
+        let json: serde_json::Value =
+            serde_json::from_str(&text).map_err(|e| format!("JSON Parse Error: {}", e))?;
+        let transcript = json["text"]
+            .as_str()
+            .ok_or_else(|| format!("No text in transcription response: {}", text))?;
+        Ok((transcript.to_string(), json["duration"].as_f64()))
+    }
+    .await;
+    health::record(
+        provider,
+        model,
+        started,
+        &result
+            .as_ref()
+            .map(|(t, _)| t.clone())
+            .map_err(|e| e.clone()),
+        None,
+    );
+    result
+}
+
+async fn transcribe_gemini(
+    model: &str,
+    name: &str,
+    mime: &str,
+    bytes: &[u8],
+) -> Result<String, String> {
+    let audio = ai::Attachment {
+        mime: mime.to_string(),
+        name: name.to_string(),
+        bytes: bytes.to_vec(),
+    };
+    ai::call_google_with(
+        model,
+        "Transcribe the attached recording verbatim in its original language. Output only the transcript, with a new paragraph when the speaker changes.",
+        "Transcribe this recording.",
+        &[audio],
+    )
+    .await
+}
+
+async fn summarize(
+    app: &AppHandle,
+    cfg: &settings::Settings,
+    transcript: &str,
+) -> Result<String, String> {
+    let sys = prompts::render(app, "audio_summary", &[]);
+    let mut input: String = transcript.chars().take(SUMMARY_MAX_CHARS).collect();
+    if transcript.chars().count() > SUMMARY_MAX_CHARS {
+        input.push_str("\n[... transcript truncated]");
+    }
+    let alias = &cfg.audio_summarizer;
+    ai::call_alias(alias, &cfg.models.for_alias(alias), &sys, &input).await
+}
@@ -0,0 +1,384 @@
+// src-tauri/src/audit.rs
+//
+// 実行したアクションの監査記録（Axis がこのマシンで実際に何をしたか。memory.db の action_audit）
+// - run_axis のアクション (initiator "agent") / 確認ダイアログで承認したもの ("user") / マクロの各ステップ
+//   （run_macro から "user"、MACRO: から "agent"）を実行した後に 1 行ずつ
+// - hash = HMAC-SHA256(鍵, 1 つ前の hash + この行の中身)。鍵はキーリング (token:audit_key) にあり DB には無い
+//   途中の行を書き換える / 消すと鎖が切れ、鍵が無いので作り直せない
+// - 鎖の先頭 (最後の行の id と hash) もキーリング (token:audit_head) に。末尾を消す / 丸ごと入れ替えると合わない
+// - get_action_audit は前回確かめた所から後だけを確かめる（確かめ済みの行の hash が変わっていないかも）
+//   切れていた最初の行 (broken_at) を返す
+// - キーリングが使えない環境では鍵無しの sha256 で鎖だけ（keyed = false）。使えるようになったら鍵付きに繋ぎ直す
+// - シークレットセッション (incognito.rs) は引数と結果を残さず、何をしたかだけ
+//   セッションの分からない記録（UI からのマクロ）はシークレットのセッションが 1 つでもあれば同じく残さない
+// - purge.rs で消すときは、消す前に鎖が繋がっていた場合だけ残りを繋ぎ直す（切れていたらそのまま）
+
+use crate::activity;
+use crate::db::AxisDatabase;
+use crate::incognito;
+use crate::objects;
+use crate::secrets;
+use crate::sharing;
+use chrono::Local;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use tauri::AppHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+const KEY_TOKEN: &str = "audit_key";
+const HEAD_TOKEN: &str = "audit_head";
+
+// SAVE の本文や検索結果をまるごと残さない
+const MAX_ARGUMENT_CHARS: usize = 2000;
+const MAX_RESULT_CHARS: usize = 1000;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub action: String, // "EXEC" / "SAVE" / "RUN" ...
+    pub argument: String,
+    pub result: String,
+    pub initiator: String, // "agent" / "user"
+    pub session_id: Option<String>,
+    pub log_id: Option<String>, // 依頼 1 回分 (InteractionLog.id)
+    pub omitted: bool,          // シークレットセッションなので引数と結果は残していない
+    pub created_at: i64,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct AuditReport {
+    pub entries: Vec<AuditEntry>, // 新しい順
+    pub verified: bool,           // 鎖全体が繋がっているか
+    pub checked: usize,           // 確かめ済みの行の数
+    pub broken_at: Option<i64>,   // 最初に合わなかった行の id
+    pub keyed: bool,              // キーリングの鍵で HMAC しているか
+}
+
+// 確かめ済みの所まで（get_action_audit が毎回頭から読まないように）
+struct Verified {
+    id: i64,
+    hash: String,
+    count: usize,
+}
+
+fn verified() -> &'static Mutex<Option<Verified>> {
+    static VERIFIED: OnceLock<Mutex<Option<Verified>>> = OnceLock::new();
+    VERIFIED.get_or_init(|| Mutex::new(None))
+}
+
+// 前の hash を読んでから書くまでを 1 本に
+fn append_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+// purge.rs: 消して繋ぎ直すまでの間に record させない
+pub fn lock() -> MutexGuard<'static, ()> {
+    append_lock().lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn clip(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.trim().to_string();
+    }
+    text.chars()
+        .take(max)
+        .collect::<String>()
+        .trim()
+        .to_string()
+        + "…"
+}
+
+fn fields(e: &AuditEntry) -> String {
+    [
+        e.prev_hash.as_str(),
+        &e.created_at.to_string(),
+        &e.initiator,
+        &e.action,
+        &e.argument,
+        &e.result,
+        e.session_id.as_deref().unwrap_or(""),
+        e.log_id.as_deref().unwrap_or(""),
+        if e.omitted { "1" } else { "0" },
+    ]
+    .join("\u{1f}")
+}
+
+fn hmac(key: &[u8], msg: &[u8]) -> String {
+    const BLOCK: usize = 64;
+    let mut k = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        k[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        k[..key.len()].copy_from_slice(key);
+    }
+    let inner = Sha256::new()
+        .chain_update(k.map(|b| b ^ 0x36))
+        .chain_update(msg)
+        .finalize();
+    Sha256::new()
+        .chain_update(k.map(|b| b ^ 0x5c))
+        .chain_update(inner)
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// 鍵が無い（キーリングが使えない）間は sha256 だけ
+fn digest(key: Option<&[u8]>, e: &AuditEntry) -> String {
+    match key {
+        Some(key) => hmac(key, fields(e).as_bytes()),
+        None => objects::hash(fields(e).as_bytes()),
+    }
+}
+
+// キーリングの鍵。無ければ作り、それまでの鍵無しの鎖が繋がっていれば鍵付きで繋ぎ直す
+// 呼ぶのは lock() の中から
+fn key(db: &AxisDatabase) -> Option<&'static [u8]> {
+    static KEY: OnceLock<Vec<u8>> = OnceLock::new();
+    if let Some(key) = KEY.get() {
+        return Some(key.as_slice());
+    }
+    let key = match secrets::token(KEY_TOKEN) {
+        Some(key) => key,
+        None => {
+            let key = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+            if let Err(e) = secrets::set_token(KEY_TOKEN, &key) {
+                warn!("⚠️ [Audit] no keyring, hash chain is unkeyed: {}", e);
+                return None;
+            }
+            let key = KEY.get_or_init(|| key.into_bytes()).as_slice();
+            upgrade(db, key);
+            return Some(key);
+        }
+    };
+    Some(KEY.get_or_init(|| key.into_bytes()).as_slice())
+}
+
+fn upgrade(db: &AxisDatabase, key: &[u8]) {
+    let chain = match db.audit_chain() {
+        Ok(chain) => chain,
+        Err(e) => {
+            warn!("⚠️ [Audit] failed to read the chain: {}", e);
+            return;
+        }
+    };
+    if let Some(id) = verify(None, &chain, "") {
+        warn!("⚠️ [Audit] unkeyed chain broken at entry {}, not re-keying", id);
+        return;
+    }
+    let keyed = |e: &AuditEntry| digest(Some(key), e);
+    match db.rechain_audit(&keyed) {
+        Ok(n) => {
+            info!("🔏 [Audit] re-keyed {} entries", n);
+            save_head(db, Some(key));
+        }
+        Err(e) => warn!("⚠️ [Audit] failed to re-key the chain: {}", e),
+    }
+}
+
+// キーリングの token:audit_head は "<id>:<hash>"
+fn head() -> Option<(i64, String)> {
+    let head = secrets::token(HEAD_TOKEN)?;
+    let (id, hash) = head.split_once(':')?;
+    Some((id.parse().ok()?, hash.to_string()))
+}
+
+fn set_head(entry: Option<(i64, &str)>) {
+    let value = entry
+        .map(|(id, hash)| format!("{}:{}", id, hash))
+        .unwrap_or_default();
+    if let Err(e) = secrets::set_token(HEAD_TOKEN, &value) {
+        warn!("⚠️ [Audit] failed to save the chain head: {}", e);
+    }
+}
+
+fn save_head(db: &AxisDatabase, key: Option<&[u8]>) {
+    if key.is_none() {
+        return;
+    }
+    match db.audit_chain() {
+        Ok(chain) => set_head(chain.last().map(|e| (e.id, e.hash.as_str()))),
+        Err(e) => warn!("⚠️ [Audit] failed to read the chain: {}", e),
+    }
+}
+
+// cmd は "EXEC: notepad" のようなアクション 1 つ分
+pub fn record(app: &AppHandle, initiator: &str, cmd: &str, result: &str, session_id: &str) {
+    let cmd = cmd.trim();
+    let action: String = cmd
+        .chars()
+        .take_while(|c| c.is_ascii_uppercase() || *c == '_')
+        .collect();
+    let (action, argument) = if action.is_empty() {
+        (clip(cmd, 40), "")
+    } else {
+        let argument = cmd[action.len()..].trim_start_matches(':');
+        (action, argument)
+    };
+    let omitted = incognito::is_active(session_id)
+        || (session_id.is_empty() && incognito::any_active());
+    let mut entry = AuditEntry {
+        action,
+        argument: if omitted {
+            String::new()
+        } else {
+            clip(argument, MAX_ARGUMENT_CHARS)
+        },
+        result: if omitted {
+            String::new()
+        } else {
+            clip(result, MAX_RESULT_CHARS)
+        },
+        initiator: initiator.to_string(),
+        session_id: Some(session_id.to_string()).filter(|s| !s.is_empty()),
+        log_id: sharing::current().map(|i| i.log_id),
+        omitted,
+        created_at: Local::now().timestamp_millis(),
+        ..Default::default()
+    };
+
+    let _guard = lock();
+    let result = AxisDatabase::open(app).and_then(|db| {
+        let key = key(&db);
+        entry.prev_hash = db
+            .last_audit_hash()
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default();
+        entry.hash = digest(key, &entry);
+        let id = db.add_audit(&entry).map_err(|e| e.to_string())?;
+        if key.is_some() {
+            set_head(Some((id, &entry.hash)));
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!("⚠️ [Audit] failed to record {}: {}", entry.action, e);
+    }
+}
+
+// prev から続く鎖を確かめる。None なら全部繋がっている
+fn verify(key: Option<&[u8]>, chain: &[AuditEntry], prev: &str) -> Option<i64> {
+    let mut prev = prev.to_string();
+    for e in chain {
+        if e.prev_hash != prev || digest(key, e) != e.hash {
+            return Some(e.id);
+        }
+        prev = e.hash.clone();
+    }
+    None
+}
+
+// 最後の行がキーリングの head と合うか（鍵無しなら head も無いので見ない）
+fn head_matches(key: Option<&[u8]>, last: Option<(i64, &str)>) -> bool {
+    if key.is_none() {
+        return true;
+    }
+    match (head(), last) {
+        (None, None) => true,
+        (Some((id, hash)), Some((last_id, last_hash))) => id == last_id && hash == last_hash,
+        _ => false,
+    }
+}
+
+// purge.rs 用: 消す前に鎖が繋がっているか（こちらは頭から全部）
+pub fn chain_intact(db: &AxisDatabase) -> Result<bool, String> {
+    let key = key(db);
+    let chain = db.audit_chain().map_err(|e| e.to_string())?;
+    Ok(verify(key, &chain, "").is_none()
+        && head_matches(key, chain.last().map(|e| (e.id, e.hash.as_str()))))
+}
+
+// purge.rs 用: 行を消した後（commit 前）に残りを繋ぎ直す
+// head は commit 前に書いてしまうが、commit に失敗しても次の get_action_audit で合わないと分かるだけ
+pub fn rechain(db: &AxisDatabase) -> Result<usize, String> {
+    let key = key(db);
+    let chained = |e: &AuditEntry| digest(key, e);
+    let n = db.rechain_audit(&chained).map_err(|e| e.to_string())?;
+    save_head(db, key);
+    if let Ok(mut v) = verified().lock() {
+        *v = None;
+    }
+    Ok(n)
+}
+
+// 前回確かめた所から後を確かめる。戻り値は (broken_at, 確かめ済みの行の数)
+fn verify_new(db: &AxisDatabase, key: Option<&[u8]>) -> Result<(Option<i64>, usize), String> {
+    let mut v = verified().lock().map_err(|e| e.to_string())?;
+    // 確かめ済みの行が書き換えられていたら頭からやり直す
+    if let Some(done) = v.as_ref() {
+        let hash = db.audit_hash(done.id).map_err(|e| e.to_string())?;
+        if hash.as_deref() != Some(done.hash.as_str()) {
+            *v = None;
+        }
+    }
+    let (from, prev, count) = v
+        .as_ref()
+        .map(|d| (d.id, d.hash.clone(), d.count))
+        .unwrap_or((0, String::new(), 0));
+    let rows = db.audit_after(from).map_err(|e| e.to_string())?;
+    if let Some(id) = verify(key, &rows, &prev) {
+        return Ok((Some(id), count));
+    }
+    let last = rows
+        .last()
+        .map(|e| (e.id, e.hash.as_str()))
+        .or(v.as_ref().map(|d| (d.id, d.hash.as_str())));
+    if !head_matches(key, last) {
+        return Ok((Some(last.map(|(id, _)| id).unwrap_or(0)), count));
+    }
+    if let Some(e) = rows.last() {
+        *v = Some(Verified {
+            id: e.id,
+            hash: e.hash.clone(),
+            count: count + rows.len(),
+        });
+    }
+    Ok((None, count + rows.len()))
+}
+
+// from_date / to_date: "today" / "yesterday" / "YYYY-MM-DD"（両端を含む。無ければ制限なし）
+pub fn list(
+    app: &AppHandle,
+    from_date: Option<&str>,
+    to_date: Option<&str>,
+    limit: usize,
+) -> Result<AuditReport, String> {
+    let bound = |day: Option<&str>, end: bool| -> Result<Option<i64>, String> {
+        match day.map(str::trim).filter(|d| !d.is_empty()) {
+            Some(d) => {
+                let (start, next) = activity::day_bounds(activity::parse_day(Some(d))?)?;
+                Ok(Some(if end { next } else { start }))
+            }
+            None => Ok(None),
+        }
+    };
+    let from = bound(from_date, false)?.unwrap_or(i64::MIN);
+    let to = bound(to_date, true)?.unwrap_or(i64::MAX);
+
+    let db = AxisDatabase::open(app)?;
+    let (broken_at, checked, keyed) = {
+        let _guard = lock();
+        let key = key(&db);
+        let (broken_at, checked) = verify_new(&db, key)?;
+        (broken_at, checked, key.is_some())
+    };
+    if let Some(id) = broken_at {
+        warn!("⚠️ [Audit] hash chain broken at entry {}", id);
+    }
+    let entries = db
+        .audit_between(from, to, limit)
+        .map_err(|e| e.to_string())?;
+    Ok(AuditReport {
+        entries,
+        verified: broken_at.is_none(),
+        checked,
+        broken_at,
+        keyed,
+    })
+}
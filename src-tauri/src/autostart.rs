@@ -0,0 +1,141 @@
+// src-tauri/src/autostart.rs
+//
+// ログイン時の自動起動（settings.start_on_login）
+// - 登録するコマンドは "<exe> --background"。--background で起動した時はメインウィンドウを出さずにトレイに常駐
+//   observer / scheduler / ホットキーは普段どおり setup で動き出す（呼び出しはホットキーかトレイから）
+// - Windows: HKCU\...\CurrentVersion\Run（reg.exe）
+// - macOS: ~/Library/LaunchAgents/<identifier>.plist
+// - Linux: ~/.config/autostart/<identifier>.desktop
+// - 起動時と設定の保存時に sync する（exe の場所が変わっていても登録し直す）
+
+use crate::settings;
+use std::env;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+pub const BACKGROUND_ARG: &str = "--background";
+
+pub fn started_in_background() -> bool {
+    env::args().any(|a| a == BACKGROUND_ARG)
+}
+
+fn exe_path() -> Result<PathBuf, String> {
+    tauri::utils::platform::current_exe().map_err(|e| e.to_string())
+}
+
+// settings.start_on_login に合わせて登録 / 解除する
+pub fn sync(app: &AppHandle) {
+    let enabled = settings::current().start_on_login;
+    let result = if enabled {
+        platform::register(app)
+    } else if platform::is_registered(app) {
+        platform::unregister(app)
+    } else {
+        Ok(())
+    };
+    match result {
+        Ok(()) if enabled => info!("🚀 [Autostart] registered to start on login"),
+        Ok(()) => {}
+        Err(e) => warn!("⚠️ [Autostart] failed to update login item: {}", e),
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    const VALUE_NAME: &str = "AxisOS";
+
+    fn reg(args: &[&str]) -> Result<(), String> {
+        let out = Command::new("reg")
+            .args(args)
+            .creation_flags(0x08000000)
+            .output()
+            .map_err(|e| e.to_string())?;
+        if out.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&out.stderr).trim().to_string())
+        }
+    }
+
+    pub fn register(_app: &AppHandle) -> Result<(), String> {
+        let command = format!("\"{}\" {}", exe_path()?.display(), BACKGROUND_ARG);
+        reg(&[
+            "add", RUN_KEY, "/v", VALUE_NAME, "/t", "REG_SZ", "/d", &command, "/f",
+        ])
+    }
+
+    pub fn unregister(_app: &AppHandle) -> Result<(), String> {
+        reg(&["delete", RUN_KEY, "/v", VALUE_NAME, "/f"])
+    }
+
+    pub fn is_registered(_app: &AppHandle) -> bool {
+        reg(&["query", RUN_KEY, "/v", VALUE_NAME]).is_ok()
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::*;
+    use std::fs;
+    use tauri::Manager;
+
+    fn entry_path(app: &AppHandle) -> Result<PathBuf, String> {
+        let home = app.path().home_dir().map_err(|e| e.to_string())?;
+        let id = &app.config().identifier;
+        Ok(if cfg!(target_os = "macos") {
+            home.join("Library/LaunchAgents")
+                .join(format!("{}.plist", id))
+        } else {
+            home.join(".config/autostart")
+                .join(format!("{}.desktop", id))
+        })
+    }
+
+    fn entry(app: &AppHandle) -> Result<String, String> {
+        let exe = exe_path()?.display().to_string();
+        Ok(if cfg!(target_os = "macos") {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key><string>{}</string>
+  <key>ProgramArguments</key><array><string>{}</string><string>{}</string></array>
+  <key>RunAtLoad</key><true/>
+</dict>
+</plist>
+"#,
+                app.config().identifier,
+                exe,
+                BACKGROUND_ARG
+            )
+        } else {
+            format!(
+                "[Desktop Entry]\nType=Application\nName=AxisOS\nExec=\"{}\" {}\nX-GNOME-Autostart-enabled=true\n",
+                exe, BACKGROUND_ARG
+            )
+        })
+    }
+
+    pub fn register(app: &AppHandle) -> Result<(), String> {
+        let path = entry_path(app)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, entry(app)?).map_err(|e| e.to_string())
+    }
+
+    pub fn unregister(app: &AppHandle) -> Result<(), String> {
+        fs::remove_file(entry_path(app)?).map_err(|e| e.to_string())
+    }
+
+    pub fn is_registered(app: &AppHandle) -> bool {
+        entry_path(app).is_ok_and(|p| p.exists())
+    }
+}
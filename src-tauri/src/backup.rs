@@ -0,0 +1,268 @@
+// src-tauri/src/backup.rs
+//
+// メモリのバックアップ / 復元（1 つの zip 書庫にまとめる）
+// 書庫の中身:
+//   manifest.json           : 形式とバージョン、件数
+//   history.json            : 会話履歴 (storage.rs)
+//   memory/<id>.json        : メモリ entry（MEMORY_BACKEND に関係なく論理単位で出す）
+//   memory/<id>.meta.json   : メモリ meta
+//   memory.db               : SQLite のスナップショット（セッション / FTS / スケジュール / アクティビティ等）
+//   config/<name>.json      : app_data_dir 直下の設定 JSON（observer_rules.json など）
+//
+// 復元は「足りない物だけ足す」マージ。既存のデータや設定は上書きしない
+
+use crate::budget;
+use crate::db::AxisDatabase;
+use crate::memory::{self, MemoryEntry, MemoryMeta};
+use crate::storage::{self, InteractionLog};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const BACKUP_FORMAT: &str = "axis-backup";
+const BACKUP_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupManifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: i64,
+    pub app_version: String,
+    pub history_count: usize,
+    pub memory_count: usize,
+    pub has_database: bool,
+    pub config_files: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ImportReport {
+    pub version: u32,
+    pub history_added: usize,
+    pub memory_imported: usize,
+    pub memory_skipped: usize, // 既にある / 壊れている
+    pub db_rows: usize,
+    pub config_restored: Vec<String>,
+}
+
+fn app_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path().app_data_dir().map_err(|e| e.to_string())
+}
+
+// app_data_dir 直下の設定 JSON（履歴は別扱い）
+fn config_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(rd) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    rd.filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| p.extension().is_some_and(|x| x == "json"))
+        .filter(|p| p.file_name().is_some_and(|n| n != "history.json"))
+        .collect()
+}
+
+fn zip_err(e: zip::result::ZipError) -> String {
+    format!("Archive Error: {}", e)
+}
+
+pub fn export_memory(app: &AppHandle, path: &str) -> Result<BackupManifest, String> {
+    let dir = app_dir(app)?;
+    let dest = PathBuf::from(path.trim());
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let history = storage::get_all_logs(app)?;
+    let memories = memory::export_all(app)?;
+    let configs = config_files(&dir);
+
+    // SQLite は一旦スナップショットを作ってから書庫に入れる
+    let snapshot = dir.join(format!("backup-{}.db.tmp", Local::now().timestamp_millis()));
+    let has_database = AxisDatabase::open(app)?.snapshot_to(&snapshot).is_ok();
+
+    let manifest = BackupManifest {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        created_at: Local::now().timestamp_millis(),
+        app_version: app.package_info().version.to_string(),
+        history_count: history.len(),
+        memory_count: memories.len(),
+        has_database,
+        config_files: configs
+            .iter()
+            .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .collect(),
+    };
+
+    let result = (|| -> Result<(), String> {
+        let file = File::create(&dest).map_err(|e| e.to_string())?;
+        let mut zip = ZipWriter::new(file);
+        let opts = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        let mut put = |name: &str, bytes: &[u8]| -> Result<(), String> {
+            zip.start_file(name, opts).map_err(zip_err)?;
+            zip.write_all(bytes).map_err(|e| e.to_string())
+        };
+
+        put(
+            "manifest.json",
+            &serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?,
+        )?;
+        put(
+            "history.json",
+            &serde_json::to_vec_pretty(&history).map_err(|e| e.to_string())?,
+        )?;
+        for (entry, meta) in &memories {
+            put(
+                &format!("memory/{}.json", entry.id),
+                &serde_json::to_vec_pretty(entry).map_err(|e| e.to_string())?,
+            )?;
+            put(
+                &format!("memory/{}.meta.json", meta.id),
+                &serde_json::to_vec_pretty(meta).map_err(|e| e.to_string())?,
+            )?;
+        }
+        if has_database {
+            put(
+                "memory.db",
+                &fs::read(&snapshot).map_err(|e| e.to_string())?,
+            )?;
+        }
+        for p in &configs {
+            if let (Some(name), Ok(bytes)) = (p.file_name(), fs::read(p)) {
+                put(&format!("config/{}", name.to_string_lossy()), &bytes)?;
+            }
+        }
+        zip.finish().map_err(zip_err)?;
+        Ok(())
+    })();
+
+    let _ = fs::remove_file(&snapshot);
+    result?;
+
+    info!(
+        "💾 [Backup] exported {} logs / {} memories to {}",
+        manifest.history_count,
+        manifest.memory_count,
+        dest.display()
+    );
+    Ok(manifest)
+}
+
+fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> Result<Vec<u8>, String> {
+    let mut f = archive.by_name(name).map_err(zip_err)?;
+    let mut buf = Vec::new();
+    f.read_to_end(&mut buf).map_err(|e| e.to_string())?;
+    Ok(buf)
+}
+
+pub fn import_memory(app: &AppHandle, path: &str) -> Result<ImportReport, String> {
+    let dir = app_dir(app)?;
+    let file = File::open(path.trim()).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(zip_err)?;
+
+    let manifest: BackupManifest =
+        serde_json::from_slice(&read_entry(&mut archive, "manifest.json")?)
+            .map_err(|e| format!("invalid manifest: {}", e))?;
+    if manifest.format != BACKUP_FORMAT {
+        return Err(format!("not an Axis backup (format '{}')", manifest.format));
+    }
+    if manifest.version > BACKUP_VERSION {
+        return Err(format!(
+            "backup version {} is newer than supported ({})",
+            manifest.version, BACKUP_VERSION
+        ));
+    }
+
+    let mut report = ImportReport {
+        version: manifest.version,
+        ..Default::default()
+    };
+
+    // 1) 履歴
+    if let Ok(bytes) = read_entry(&mut archive, "history.json") {
+        let logs: Vec<InteractionLog> = serde_json::from_slice(&bytes).unwrap_or_default();
+        report.history_added = storage::merge_logs(app, logs)?;
+    }
+
+    // 2) メモリ / 設定 を名前で振り分け
+    let mut entries: HashMap<String, MemoryEntry> = HashMap::new();
+    let mut metas: Vec<MemoryMeta> = Vec::new();
+    for i in 0..archive.len() {
+        let mut f = archive.by_index(i).map_err(zip_err)?;
+        let Some(name) = f.enclosed_name() else {
+            continue; // ../ などは無視
+        };
+        let name = name.to_string_lossy().replace('\\', "/");
+        let mut buf = Vec::new();
+        if f.read_to_end(&mut buf).is_err() {
+            continue;
+        }
+
+        if let Some(rest) = name.strip_prefix("memory/") {
+            if rest.ends_with(".meta.json") {
+                match serde_json::from_slice::<MemoryMeta>(&buf) {
+                    Ok(m) => metas.push(m),
+                    Err(_) => report.memory_skipped += 1,
+                }
+            } else if let Ok(e) = serde_json::from_slice::<MemoryEntry>(&buf) {
+                entries.insert(e.id.clone(), e);
+            }
+        } else if let Some(file_name) = name.strip_prefix("config/") {
+            // サブディレクトリは作らない / 既存の設定は上書きしない
+            if file_name.contains('/') {
+                continue;
+            }
+            let target = dir.join(file_name);
+            if !target.exists() && fs::write(&target, &buf).is_ok() {
+                report.config_restored.push(file_name.to_string());
+            }
+        }
+    }
+
+    for meta in metas {
+        let Some(entry) = entries.remove(&meta.id) else {
+            report.memory_skipped += 1;
+            continue;
+        };
+        if memory::exists(app, &meta.id) {
+            report.memory_skipped += 1;
+            continue;
+        }
+        match memory::save_entry_and_meta(app, &entry, &meta) {
+            Ok(()) => report.memory_imported += 1,
+            Err(e) => {
+                warn!("⚠️ [Backup] memory {} not imported: {}", meta.id, e);
+                report.memory_skipped += 1;
+            }
+        }
+    }
+
+    // 3) SQLite（一時ファイルに展開して ATTACH で取り込む）
+    if manifest.has_database {
+        let tmp = dir.join(format!("import-{}.db.tmp", Local::now().timestamp_millis()));
+        let merged = read_entry(&mut archive, "memory.db")
+            .and_then(|bytes| fs::write(&tmp, bytes).map_err(|e| e.to_string()))
+            .and_then(|_| {
+                AxisDatabase::open(app)?
+                    .merge_from(&tmp)
+                    .map_err(|e| e.to_string())
+            });
+        let _ = fs::remove_file(&tmp);
+        report.db_rows = merged?;
+        budget::forget_cached();
+    }
+
+    info!(
+        "💾 [Backup] imported: {} logs / {} memories / {} db rows",
+        report.history_added, report.memory_imported, report.db_rows
+    );
+    Ok(report)
+}
@@ -0,0 +1,289 @@
+// src-tauri/src/budget.rs
+//
+// プロバイダ毎の利用額の上限（settings.budgets: { "gpt": { daily_usd, monthly_usd } }）
+// - ai.rs が 1 回呼ぶ毎に record: トークン数 × 単価で見積もって memory.db の spend に日毎 / モデル毎で足す
+//   単価は settings.model_prices（モデル名の前方一致、長い方を優先）→ DEFAULT_PRICES の順。local は数えない
+// - 上限の budget_warn_ratio を超えた時 / 上限に達した時に axis-budget-warning を発火 + OS 通知
+//   （同じ日 / 月の同じ段階は 1 回だけ）
+// - 上限に達したプロバイダは lib.rs のルーティングで避ける（他の予算内のプロバイダか local へ）
+//   ルーティングの度に DB を開かないよう、(今日, 今月) の額はプロバイダ毎に覚えておく
+//   （record で更新、日付が変われば / 履歴の削除やバックアップの取り込みの後は読み直し）
+// - ai.rs からは AppHandle が渡らないので ai::app_handle（setup で ai::init に渡したもの）を使う
+
+use crate::ai::{self, TokenUsage};
+use crate::db::AxisDatabase;
+use crate::notify;
+use crate::settings;
+use chrono::Local;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+const WARNING_EVENT: &str = "axis-budget-warning";
+
+// (モデル名の前方一致, 入力, 出力) USD / 100 万トークン。長い方を先に
+const DEFAULT_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-5-nano", 0.05, 0.40),
+    ("gpt-5-mini", 0.25, 2.00),
+    ("gpt-5", 1.25, 10.00),
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("gemini-2.5-flash", 0.30, 2.50),
+    ("gemini-2.5-pro", 1.25, 10.00),
+    ("gemini", 0.30, 2.50),
+    ("grok-4-1-fast", 0.20, 0.50),
+    ("grok-4", 3.00, 15.00),
+    ("grok", 3.00, 15.00),
+    ("claude-haiku-4", 1.00, 5.00),
+    ("claude-opus-4", 15.00, 75.00),
+    ("claude", 3.00, 15.00),
+    // llama (NVIDIA)
+    ("meta/llama-3.1-405b", 3.50, 3.50),
+    ("meta/llama-3.1-8b", 0.18, 0.18),
+    ("meta/llama-3.2-11b", 0.18, 0.18),
+    ("meta/llama-3.2-90b", 1.20, 1.20),
+    ("meta/llama", 0.88, 0.88),
+];
+
+// 通知済みの段階 ("gpt|daily|2025-01-31|exceeded")
+static ALERTED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+// プロバイダ → 覚えている利用額
+static SPENT: OnceLock<Mutex<HashMap<String, SpentCache>>> = OnceLock::new();
+
+// day に読んだ (今日, 今月) の額
+struct SpentCache {
+    day: String,
+    daily: f64,
+    monthly: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BudgetAlert {
+    pub provider: String,
+    pub period: String, // daily / monthly
+    pub spent_usd: f64,
+    pub limit_usd: f64,
+    pub exceeded: bool, // false = 警告の割合を超えただけ
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BudgetStatus {
+    pub provider: String,
+    pub daily_spent_usd: f64,
+    pub daily_limit_usd: Option<f64>,
+    pub monthly_spent_usd: f64,
+    pub monthly_limit_usd: Option<f64>,
+    pub exceeded: bool,
+}
+
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn month_start() -> String {
+    Local::now().format("%Y-%m-01").to_string()
+}
+
+// USD / 100 万トークン (入力, 出力)。分からないモデルは 0（数えない）
+fn price_for(model: &str) -> (f64, f64) {
+    let cfg = settings::current();
+    let custom = cfg
+        .model_prices
+        .iter()
+        .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, p)| (p.input_per_mtok, p.output_per_mtok));
+    custom
+        .or_else(|| {
+            DEFAULT_PRICES
+                .iter()
+                .find(|(prefix, _, _)| model.starts_with(prefix))
+                .map(|(_, i, o)| (*i, *o))
+        })
+        .unwrap_or((0.0, 0.0))
+}
+
+pub fn cost_usd(model: &str, usage: TokenUsage) -> f64 {
+    let (input, output) = price_for(model);
+    (usage.input as f64 * input + usage.output as f64 * output) / 1_000_000.0
+}
+
+// (今日, 今月) の利用額
+fn spent(db: &AxisDatabase, provider: &str) -> Result<(f64, f64), String> {
+    let daily = db
+        .spend_since(provider, &today())
+        .map_err(|e| e.to_string())?;
+    let monthly = db
+        .spend_since(provider, &month_start())
+        .map_err(|e| e.to_string())?;
+    Ok((daily, monthly))
+}
+
+fn remember(provider: &str, daily: f64, monthly: f64) {
+    if let Ok(mut cache) = SPENT.get_or_init(|| Mutex::new(HashMap::new())).lock() {
+        cache.insert(
+            provider.to_string(),
+            SpentCache {
+                day: today(),
+                daily,
+                monthly,
+            },
+        );
+    }
+}
+
+// 履歴の削除 (purge.rs) / バックアップの取り込み (backup.rs) の後。次のルーティングで DB から読み直す
+pub fn forget_cached() {
+    if let Ok(mut cache) = SPENT.get_or_init(|| Mutex::new(HashMap::new())).lock() {
+        cache.clear();
+    }
+}
+
+// 覚えている額（今日の分だけ）。無ければ DB から読んで覚える
+fn cached_spent(provider: &str) -> Option<(f64, f64)> {
+    let day = today();
+    let cached = SPENT
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .ok()
+        .and_then(|cache| {
+            cache
+                .get(provider)
+                .filter(|c| c.day == day)
+                .map(|c| (c.daily, c.monthly))
+        });
+    if cached.is_some() {
+        return cached;
+    }
+    let db = AxisDatabase::open(ai::app_handle()?).ok()?;
+    let (daily, monthly) = spent(&db, provider).ok()?;
+    remember(provider, daily, monthly);
+    Some((daily, monthly))
+}
+
+pub fn record(provider: &str, model: &str, usage: TokenUsage) {
+    add(provider, model, usage, cost_usd(model, usage));
+}
+
+// トークンで数えないもの（画像の生成など / imagegen.rs）は額をそのまま
+pub fn record_usd(provider: &str, model: &str, usd: f64) {
+    add(provider, model, TokenUsage::default(), usd);
+}
+
+fn add(provider: &str, model: &str, usage: TokenUsage, cost: f64) {
+    if provider == "local" {
+        return;
+    }
+    let Some(app) = ai::app_handle() else {
+        return;
+    };
+    let result = AxisDatabase::open(app).and_then(|db| {
+        db.add_spend(&today(), provider, model, usage.input, usage.output, cost)
+            .map_err(|e| e.to_string())?;
+        spent(&db, provider)
+    });
+    match result {
+        Ok((daily, monthly)) => {
+            remember(provider, daily, monthly);
+            check_limits(app, provider, daily, monthly)
+        }
+        Err(e) => warn!("⚠️ [Budget] failed to record spend: {}", e),
+    }
+}
+
+fn check_limits(app: &AppHandle, provider: &str, daily: f64, monthly: f64) {
+    let cfg = settings::current();
+    let Some(limit) = cfg.budgets.get(provider) else {
+        return;
+    };
+    for (period, key, spent, limit) in [
+        ("daily", today(), daily, limit.daily_usd),
+        ("monthly", month_start(), monthly, limit.monthly_usd),
+    ] {
+        let Some(limit) = limit.filter(|l| *l > 0.0) else {
+            continue;
+        };
+        let exceeded = spent >= limit;
+        if !exceeded && spent < limit * cfg.budget_warn_ratio as f64 {
+            continue;
+        }
+        let stage = if exceeded { "exceeded" } else { "warning" };
+        let alerted = ALERTED.get_or_init(|| Mutex::new(HashSet::new()));
+        let first = alerted
+            .lock()
+            .map(|mut a| a.insert(format!("{}|{}|{}|{}", provider, period, key, stage)))
+            .unwrap_or(false);
+        if !first {
+            continue;
+        }
+        info!(
+            "💸 [Budget] {} {} spend ${:.2} / ${:.2} ({})",
+            provider, period, spent, limit, stage
+        );
+        let alert = BudgetAlert {
+            provider: provider.to_string(),
+            period: period.to_string(),
+            spent_usd: spent,
+            limit_usd: limit,
+            exceeded,
+        };
+        let _ = app.emit(WARNING_EVENT, &alert);
+        let body = if exceeded {
+            format!(
+                "{} reached its {} limit (${:.2}). Axis will use cheaper or local models.",
+                provider, period, limit
+            )
+        } else {
+            format!(
+                "{} has used ${:.2} of its {} limit (${:.2}).",
+                provider, spent, period, limit
+            )
+        };
+        notify::notify(app, "Axis budget", &body, None);
+    }
+}
+
+// ルーティング用: 今日か今月の上限に達していれば理由
+pub fn exceeded(provider: &str) -> Option<String> {
+    let cfg = settings::current();
+    let limit = cfg.budgets.get(provider)?;
+    let (daily, monthly) = cached_spent(provider)?;
+    let over = |spent: f64, limit: Option<f64>| limit.is_some_and(|l| l > 0.0 && spent >= l);
+    if over(daily, limit.daily_usd) {
+        Some(format!("daily budget reached (${:.2})", daily))
+    } else if over(monthly, limit.monthly_usd) {
+        Some(format!("monthly budget reached (${:.2})", monthly))
+    } else {
+        None
+    }
+}
+
+// get_budget_status: 上限を決めたプロバイダ + 今月使ったプロバイダ
+pub fn status(app: &AppHandle) -> Result<Vec<BudgetStatus>, String> {
+    let cfg = settings::current();
+    let db = AxisDatabase::open(app)?;
+    let mut providers: BTreeSet<String> = cfg.budgets.keys().cloned().collect();
+    providers.extend(
+        db.spend_providers_since(&month_start())
+            .map_err(|e| e.to_string())?,
+    );
+    providers
+        .into_iter()
+        .map(|provider| {
+            let (daily, monthly) = spent(&db, &provider)?;
+            let limit = cfg.budgets.get(&provider).cloned().unwrap_or_default();
+            Ok(BudgetStatus {
+                exceeded: exceeded(&provider).is_some(),
+                provider,
+                daily_spent_usd: daily,
+                daily_limit_usd: limit.daily_usd,
+                monthly_spent_usd: monthly,
+                monthly_limit_usd: limit.monthly_usd,
+            })
+        })
+        .collect()
+}
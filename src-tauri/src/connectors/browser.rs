@@ -0,0 +1,148 @@
+// src-tauri/src/connectors/browser.rs
+//
+// ブラウザの今のタブ（拡張機能 browser-extension/ からの橋渡し）
+// - 拡張がタブを切り替えた / 読み込み終えた時に、API サーバーの POST /v1/browser/tab へ {url, title, browser} だけを送る
+//   settings.browser_bridge_enabled と api_server_enabled の両方が要る。トークンは拡張用のもの
+//   （api_server.rs の "browser_extension"。/v1/browser/ の下にしか使えない）
+// - 持つのは最後に届いたタブ 1 つだけ（メモリの中だけ。ディスクには書かない）。TAB_TTL_MS より古いものは使わない
+// - 本文は BROWSER_TAB を実行した時だけ頼む: request_text が拡張の GET /v1/browser/wait（待ち受け）を起こし、
+//   拡張が今のタブの本文を POST /v1/browser/text で返す（ログインしたページも見えているとおり）
+//   TEXT_WAIT のうちに届かなければ URL を FETCH と同じく取りに行く
+// - quick-ask の文脈に、前面がブラウザならタブの URL も (quick.rs)
+// - シークレットウィンドウのタブ / http(s) 以外のページは拡張側で送らない
+
+use crate::settings::Settings;
+use crate::web;
+use chrono::Local;
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::{oneshot, Notify};
+use tracing::{debug, info};
+
+const TAB_TTL_MS: i64 = 30 * 60 * 1000;
+// 拡張は 200k 文字で切って送る（API サーバーの本文は 1MB まで）
+const MAX_TEXT_CHARS: usize = 200_000;
+// BROWSER_TAB が拡張からの本文を待つ時間
+const TEXT_WAIT: Duration = Duration::from_secs(5);
+const BROWSERS: &[&str] = &[
+    "chrome", "msedge", "firefox", "brave", "opera", "vivaldi", "arc",
+];
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct BrowserTab {
+    pub url: String,
+    pub title: String,
+    pub browser: String,
+    pub received_at: i64,
+}
+
+static TAB: Mutex<Option<BrowserTab>> = Mutex::new(None);
+// 本文を頼む合図（拡張が待ち受けていなくても次の待ち受けで拾えるよう notify_one）と、届いた本文の受け口
+static WANT_TEXT: OnceLock<Notify> = OnceLock::new();
+static TEXT_REPLY: Mutex<Option<oneshot::Sender<(String, String)>>> = Mutex::new(None);
+
+fn want_text() -> &'static Notify {
+    WANT_TEXT.get_or_init(Notify::new)
+}
+
+fn parse_tab_url(url: &str) -> Result<String, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("invalid url: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("only http(s) tabs are accepted".to_string());
+    }
+    Ok(parsed.to_string())
+}
+
+pub fn update(url: &str, title: &str, browser: &str) -> Result<BrowserTab, String> {
+    let tab = BrowserTab {
+        url: parse_tab_url(url)?,
+        title: title.trim().to_string(),
+        browser: browser.trim().to_string(),
+        received_at: Local::now().timestamp_millis(),
+    };
+    debug!(url = %tab.url, "🌐 [Browser] tab from {}", tab.browser);
+    *TAB.lock().unwrap_or_else(|e| e.into_inner()) = Some(tab.clone());
+    Ok(tab)
+}
+
+// 拡張の GET /v1/browser/wait。max_wait のうちに本文を頼まれたら true
+pub async fn wait_for_request(max_wait: Duration) -> bool {
+    tokio::time::timeout(max_wait, want_text().notified())
+        .await
+        .is_ok()
+}
+
+// 拡張の POST /v1/browser/text。頼んでいなければ捨てる
+pub fn deliver_text(url: &str, text: &str) -> Result<bool, String> {
+    let url = parse_tab_url(url)?;
+    let text: String = text.trim().chars().take(MAX_TEXT_CHARS).collect();
+    let Some(reply) = TEXT_REPLY.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return Ok(false);
+    };
+    info!("🌐 [Browser] page text received ({} chars)", text.chars().count());
+    Ok(reply.send((url, text)).is_ok())
+}
+
+// 拡張に今のタブの本文を頼んで、TEXT_WAIT まで待つ（別のタブの本文なら使わない）
+async fn request_text(url: &str) -> Option<String> {
+    let (tx, rx) = oneshot::channel();
+    *TEXT_REPLY.lock().unwrap_or_else(|e| e.into_inner()) = Some(tx);
+    want_text().notify_one();
+    let reply = tokio::time::timeout(TEXT_WAIT, rx).await.ok()?.ok();
+    TEXT_REPLY.lock().unwrap_or_else(|e| e.into_inner()).take();
+    reply
+        .filter(|(got, text)| got == url && !text.is_empty())
+        .map(|(_, text)| text)
+}
+
+pub fn current() -> Option<BrowserTab> {
+    let now = Local::now().timestamp_millis();
+    TAB.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .filter(|t| now - t.received_at < TAB_TTL_MS)
+}
+
+// プロセス名 ("chrome" / "msedge.exe" ...) がブラウザか
+pub fn is_browser(app: &str) -> bool {
+    let app = app.to_lowercase();
+    let app = app.trim_end_matches(".exe");
+    BROWSERS.contains(&app)
+}
+
+// BROWSER_TAB の結果。本文は fetch_max_chars まで
+pub async fn read(cfg: &Settings) -> Result<web::PageExtract, String> {
+    if !cfg.browser_bridge_enabled || !cfg.api_server_enabled {
+        return Err(
+            "the browser bridge is off: turn on browser_bridge_enabled and api_server_enabled, then install browser-extension/"
+                .to_string(),
+        );
+    }
+    let tab = current()
+        .ok_or("no tab from the browser extension yet (is it installed and connected?)")?;
+    let Some(text) = request_text(&tab.url).await else {
+        let page = web::fetch_url(
+            &tab.url,
+            &cfg.fetch_allow_domains,
+            &cfg.fetch_deny_domains,
+            cfg.fetch_max_chars,
+        )
+        .await?;
+        let title = if tab.title.is_empty() {
+            page.title
+        } else {
+            tab.title
+        };
+        return Ok(web::PageExtract {
+            url: tab.url,
+            title,
+            text: page.text,
+        });
+    };
+    Ok(web::PageExtract {
+        url: tab.url,
+        title: tab.title,
+        text: text.chars().take(cfg.fetch_max_chars).collect(),
+    })
+}
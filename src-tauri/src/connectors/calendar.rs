@@ -0,0 +1,563 @@
+// src-tauri/src/connectors/calendar.rs
+//
+// カレンダー（ローカル / 購読 .ics + Google Calendar）
+// - settings.calendar_ics: .ics のパスか URL (http / https / webcal)
+// - Google は connect_google_calendar で OAuth 接続した時だけ (google_calendar.rs)
+// - upcoming(hours): これから hours 時間の予定をまとめて開始順に返す（get_upcoming_events / CALENDAR:）
+// - 会議の警告: 1 分おきに見て、calendar_warn_minutes 前になったら observer と同じ形で通知し、
+//   会議リンクがあれば OPEN_URL を確認待ちに積む（承認すると開く）
+//
+// ICS の対応範囲: VEVENT の DTSTART / DTEND / DURATION / RRULE (FREQ, INTERVAL, COUNT, UNTIL, BYDAY) / EXDATE
+// TZID 付きの時刻はローカル時刻として扱う（tz データベースを持たないため）
+
+use super::google_calendar;
+use crate::notify::{self, DeepLink};
+use crate::{policy, settings};
+use axis_core::command::Action;
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, Months, NaiveDate, NaiveDateTime,
+    TimeZone, Utc, Weekday,
+};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(60);
+// 警告用に予定を読み直す間隔（毎分 ICS / Google を叩かない）
+const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+// 1 つの繰り返しで [from, to) の辺りから何回分まで見るか（COUNT 付きは頭から数えるので MAX_COUNT_STEPS まで）
+const MAX_OCCURRENCES: usize = 1000;
+const MAX_COUNT_STEPS: usize = 100_000;
+// ICS / 取り込むファイルの上限
+const MAX_ICS_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CalendarEvent {
+    pub id: String,
+    pub title: String,
+    pub start: i64, // UNIX ミリ秒
+    pub end: i64,
+    pub all_day: bool,
+    pub location: String,
+    pub meeting_url: Option<String>, // Meet / Zoom / Teams 等
+    pub source: String,              // "ics:<path>" / "google"
+}
+
+// ---------- ICS ----------
+
+#[derive(Debug, Clone, Default)]
+struct IcsProp {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+// 折り返し行 (先頭が空白 / タブ) を前の行に繋げる
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        if let Some(rest) = raw.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(rest);
+                continue;
+            }
+        }
+        lines.push(raw.trim_end_matches('\r').to_string());
+    }
+    lines
+}
+
+fn parse_prop(line: &str) -> Option<IcsProp> {
+    let (head, value) = line.split_once(':')?;
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.trim().to_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Some(IcsProp {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("\\n", "\n")
+        .replace("\\N", "\n")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+// (ミリ秒, 終日か)
+fn parse_ics_time(prop: &IcsProp) -> Option<(i64, bool)> {
+    let v = prop.value.trim();
+    let is_date = prop
+        .params
+        .iter()
+        .any(|(k, val)| k == "VALUE" && val == "DATE")
+        || v.len() == 8;
+    if is_date {
+        let d = NaiveDate::parse_from_str(v, "%Y%m%d").ok()?;
+        let ms = Local
+            .from_local_datetime(&d.and_hms_opt(0, 0, 0)?)
+            .earliest()?
+            .timestamp_millis();
+        return Some((ms, true));
+    }
+    if let Some(utc) = v.strip_suffix('Z') {
+        let dt = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((Utc.from_utc_datetime(&dt).timestamp_millis(), false));
+    }
+    // TZID 付き / floating はローカル時刻
+    let dt = NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S").ok()?;
+    Some((
+        Local
+            .from_local_datetime(&dt)
+            .earliest()?
+            .timestamp_millis(),
+        false,
+    ))
+}
+
+// DURATION: P1D / PT1H30M / P1W
+fn parse_duration(s: &str) -> Option<i64> {
+    let s = s.trim().trim_start_matches('+');
+    let body = s.strip_prefix('P')?;
+    let mut total = 0i64;
+    let mut num = String::new();
+    let mut in_time = false;
+    for c in body.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => num.push(c),
+            unit => {
+                let n: i64 = num.parse().ok()?;
+                num.clear();
+                total += n * match (unit, in_time) {
+                    ('W', _) => 7 * 86_400_000,
+                    ('D', _) => 86_400_000,
+                    ('H', true) => 3_600_000,
+                    ('M', true) => 60_000,
+                    ('S', true) => 1000,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(total)
+}
+
+fn meeting_link(texts: &[&str]) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r#"https://(meet\.google\.com/[a-z0-9\-]+|[a-z0-9\-]*\.?zoom\.us/j/[^\s"<>]+|teams\.microsoft\.com/l/meetup-join/[^\s"<>]+|[a-z0-9\-]+\.webex\.com/[^\s"<>]+)"#)
+            .expect("meeting link regex")
+    });
+    texts
+        .iter()
+        .find_map(|t| re.find(t).map(|m| m.as_str().to_string()))
+}
+
+#[derive(Debug, Clone, Default)]
+struct Rule {
+    freq: String,
+    interval: i64,
+    count: Option<usize>,
+    until: Option<i64>,
+    by_day: Vec<Weekday>,
+}
+
+fn weekday(code: &str) -> Option<Weekday> {
+    // "MO" / "-1FR" など。序数は無視
+    let code = code.trim_start_matches(|c: char| c == '-' || c == '+' || c.is_ascii_digit());
+    match code {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_rule(s: &str) -> Rule {
+    let mut rule = Rule {
+        interval: 1,
+        ..Default::default()
+    };
+    for part in s.split(';') {
+        let Some((k, v)) = part.split_once('=') else {
+            continue;
+        };
+        match k.to_uppercase().as_str() {
+            "FREQ" => rule.freq = v.to_uppercase(),
+            "INTERVAL" => rule.interval = v.parse().unwrap_or(1).max(1),
+            "COUNT" => rule.count = v.parse().ok(),
+            "UNTIL" => {
+                rule.until = parse_ics_time(&IcsProp {
+                    value: v.to_string(),
+                    ..Default::default()
+                })
+                .map(|(ms, _)| ms)
+            }
+            "BYDAY" => rule.by_day = v.split(',').filter_map(weekday).collect(),
+            _ => {}
+        }
+    }
+    rule
+}
+
+// 繰り返しを [from, to) の範囲だけ展開して開始時刻を返す
+fn expand(start: i64, rule: &Rule, exdates: &HashSet<i64>, from: i64, to: i64) -> Vec<i64> {
+    let Some(first) = Local.timestamp_millis_opt(start).single() else {
+        return vec![];
+    };
+    let mut out = Vec::new();
+    let mut produced = 0usize;
+    let mut emit = |t: DateTime<Local>, out: &mut Vec<i64>| -> bool {
+        let ms = t.timestamp_millis();
+        if rule.until.is_some_and(|u| ms > u) || ms >= to {
+            return false;
+        }
+        produced += 1;
+        if rule.count.is_some_and(|c| produced > c) {
+            return false;
+        }
+        if ms >= from && !exdates.contains(&ms) {
+            out.push(ms);
+        }
+        true
+    };
+
+    // COUNT が無ければ from の少し手前の回まで飛ばす（何年も前に始まった毎日の予定でも途中で切れない）
+    let (skip, steps) = match rule.count {
+        Some(count) => (0, count.saturating_add(1).min(MAX_COUNT_STEPS)),
+        None => (first_step(&first, rule, from), MAX_OCCURRENCES),
+    };
+    for step in skip..skip + steps as i64 {
+        let n = step * rule.interval;
+        let base = match rule.freq.as_str() {
+            "DAILY" => Some(first + ChronoDuration::days(n)),
+            "WEEKLY" => Some(first + ChronoDuration::weeks(n)),
+            "MONTHLY" => first.checked_add_months(Months::new(n as u32)),
+            "YEARLY" => first.checked_add_months(Months::new(12 * n as u32)),
+            _ => None,
+        };
+        let Some(base) = base else { break };
+
+        if rule.freq == "WEEKLY" && !rule.by_day.is_empty() {
+            // その週の BYDAY の曜日を順に
+            let monday = base - ChronoDuration::days(base.weekday().num_days_from_monday() as i64);
+            let mut days: Vec<DateTime<Local>> = rule
+                .by_day
+                .iter()
+                .map(|d| monday + ChronoDuration::days(d.num_days_from_monday() as i64))
+                .filter(|t| *t >= first)
+                .collect();
+            days.sort();
+            if !days.into_iter().all(|t| emit(t, &mut out)) {
+                break;
+            }
+        } else if !emit(base, &mut out) {
+            break;
+        }
+    }
+    out
+}
+
+// from を含む回の 1 つ前（週の BYDAY や月末の丸めで取りこぼさないように）
+fn first_step(first: &DateTime<Local>, rule: &Rule, from: i64) -> i64 {
+    let Some(from) = Local.timestamp_millis_opt(from).single() else {
+        return 0;
+    };
+    if from <= *first {
+        return 0;
+    }
+    let months = (from.year() - first.year()) as i64 * 12 + from.month() as i64
+        - first.month() as i64;
+    let periods = match rule.freq.as_str() {
+        "DAILY" => (from - *first).num_days(),
+        "WEEKLY" => (from - *first).num_weeks(),
+        "MONTHLY" => months,
+        "YEARLY" => months / 12,
+        _ => 0,
+    };
+    (periods / rule.interval.max(1) - 1).max(0)
+}
+
+pub fn parse_ics(text: &str, source: &str, from: i64, to: i64) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    let mut props: Option<Vec<IcsProp>> = None;
+
+    for line in unfold(text) {
+        let upper = line.trim().to_uppercase();
+        if upper == "BEGIN:VEVENT" {
+            props = Some(Vec::new());
+            continue;
+        }
+        if upper == "END:VEVENT" {
+            if let Some(list) = props.take() {
+                events.extend(build_events(&list, source, from, to));
+            }
+            continue;
+        }
+        if let (Some(list), Some(p)) = (props.as_mut(), parse_prop(&line)) {
+            list.push(p);
+        }
+    }
+    events
+}
+
+fn build_events(props: &[IcsProp], source: &str, from: i64, to: i64) -> Vec<CalendarEvent> {
+    let get = |name: &str| props.iter().find(|p| p.name == name);
+    let text = |name: &str| get(name).map(|p| unescape(&p.value)).unwrap_or_default();
+
+    if text("STATUS").eq_ignore_ascii_case("CANCELLED") {
+        return vec![];
+    }
+    let Some((start, all_day)) = get("DTSTART").and_then(parse_ics_time) else {
+        return vec![];
+    };
+    let length = get("DTEND")
+        .and_then(parse_ics_time)
+        .map(|(end, _)| end - start)
+        .or_else(|| get("DURATION").and_then(|p| parse_duration(&p.value)))
+        .unwrap_or(if all_day { 86_400_000 } else { 3_600_000 });
+
+    let summary = text("SUMMARY");
+    let location = text("LOCATION");
+    let description = text("DESCRIPTION");
+    let url = text("URL");
+    let meeting_url = meeting_link(&[&url, &location, &description]).or_else(|| {
+        // 既知の会議サービスが無ければ URL プロパティ (https) をそのまま使う
+        Some(url.clone()).filter(|u| u.starts_with("https://"))
+    });
+    let uid = text("UID");
+
+    let starts = match get("RRULE") {
+        Some(r) => {
+            let exdates: HashSet<i64> = props
+                .iter()
+                .filter(|p| p.name == "EXDATE")
+                .flat_map(|p| {
+                    p.value
+                        .split(',')
+                        .filter_map(|v| {
+                            parse_ics_time(&IcsProp {
+                                value: v.to_string(),
+                                ..p.clone()
+                            })
+                        })
+                        .map(|(ms, _)| ms)
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            expand(start, &parse_rule(&r.value), &exdates, from - length, to)
+        }
+        None => vec![start],
+    };
+
+    starts
+        .into_iter()
+        .filter(|s| *s + length > from && *s < to)
+        .map(|s| CalendarEvent {
+            id: format!("{}@{}", uid, s),
+            title: if summary.is_empty() {
+                "(no title)".to_string()
+            } else {
+                summary.clone()
+            },
+            start: s,
+            end: s + length,
+            all_day,
+            location: location.clone(),
+            meeting_url: meeting_url.clone(),
+            source: format!("ics:{}", source),
+        })
+        .collect()
+}
+
+async fn read_ics(source: &str) -> Result<String, String> {
+    let s = source.trim();
+    if let Some(rest) = s.strip_prefix("webcal://") {
+        return read_url(&format!("https://{}", rest)).await;
+    }
+    if s.starts_with("http://") || s.starts_with("https://") {
+        return read_url(s).await;
+    }
+    let size = fs::metadata(s).map_err(|e| format!("{}: {}", s, e))?.len();
+    if size > MAX_ICS_BYTES as u64 {
+        return Err(format!("{}: calendar is too large ({} bytes)", s, size));
+    }
+    fs::read_to_string(s).map_err(|e| format!("{}: {}", s, e))
+}
+
+async fn read_url(url: &str) -> Result<String, String> {
+    let mut res = crate::ai::client_for("web")?
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error("web", &e))?;
+    if !res.status().is_success() {
+        return Err(format!("HTTP {} from {}", res.status(), url));
+    }
+    let mut body: Vec<u8> = Vec::new();
+    while let Some(chunk) = res.chunk().await.map_err(|e| e.to_string())? {
+        if body.len() + chunk.len() > MAX_ICS_BYTES {
+            return Err(format!("calendar too large (over {} bytes): {}", MAX_ICS_BYTES, url));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
+// ---------- まとめ ----------
+
+pub async fn upcoming(hours: i64) -> Result<Vec<CalendarEvent>, String> {
+    let cfg = settings::current();
+    let now = Local::now().timestamp_millis();
+    let to = now + hours.max(1) * 3_600_000;
+
+    let mut events = Vec::new();
+    let mut errors = Vec::new();
+    for source in &cfg.calendar_ics {
+        match read_ics(source).await {
+            Ok(text) => events.extend(parse_ics(&text, source, now, to)),
+            Err(e) => errors.push(e),
+        }
+    }
+    if google_calendar::connected() {
+        match google_calendar::events(now, to).await {
+            Ok(list) => events.extend(list),
+            Err(e) => errors.push(format!("google: {}", e)),
+        }
+    }
+    for e in &errors {
+        warn!("⚠️ [Calendar] {}", e);
+    }
+    if events.is_empty() && !errors.is_empty() {
+        return Err(errors.join(" / "));
+    }
+    events.sort_by_key(|e| e.start);
+    Ok(events)
+}
+
+fn format_time(ms: i64) -> String {
+    Local
+        .timestamp_millis_opt(ms)
+        .single()
+        .map(|t| t.format("%m/%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+// system_context 用
+pub fn summarize(events: &[CalendarEvent]) -> String {
+    if events.is_empty() {
+        return "No upcoming events.\n".to_string();
+    }
+    events
+        .iter()
+        .map(|e| {
+            let when = if e.all_day {
+                format!("{} (all day)", format_time(e.start))
+            } else {
+                format!("{} - {}", format_time(e.start), format_time(e.end))
+            };
+            let mut line = format!("- {} {}", when, e.title);
+            if !e.location.is_empty() {
+                line.push_str(&format!(" @ {}", e.location));
+            }
+            if let Some(url) = &e.meeting_url {
+                line.push_str(&format!(" [meeting: {}]", url));
+            }
+            line + "\n"
+        })
+        .collect()
+}
+
+// ---------- 会議前の警告 ----------
+
+struct Cache {
+    fetched: Instant,
+    events: Vec<CalendarEvent>,
+}
+
+async fn cached_upcoming() -> Vec<CalendarEvent> {
+    static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+    if let Ok(c) = CACHE.lock() {
+        if let Some(c) = c.as_ref().filter(|c| c.fetched.elapsed() < CACHE_TTL) {
+            return c.events.clone();
+        }
+    }
+    let events = upcoming(3).await.unwrap_or_default();
+    if let Ok(mut c) = CACHE.lock() {
+        *c = Some(Cache {
+            fetched: Instant::now(),
+            events: events.clone(),
+        });
+    }
+    events
+}
+
+fn warn(app: &AppHandle, event: &CalendarEvent, minutes_left: i64) {
+    let start = Local
+        .timestamp_millis_opt(event.start)
+        .single()
+        .map(|t| t.format("%H:%M").to_string())
+        .unwrap_or_default();
+    let mut message = format!(
+        "{} の「{}」まであと {} 分です。",
+        start, event.title, minutes_left
+    );
+    if let Some(url) = &event.meeting_url {
+        message.push_str(" 会議リンクを開きますか？");
+        // 承認されたら開く（確認ダイアログは policy の保留キュー）
+        policy::enqueue(app, Action::OpenUrl { url: url.clone() }, "calendar");
+    }
+    info!(text = %message, "📅 [Calendar] warning");
+    let _ = app.emit("axis-observer-event", format!("[Calendar] {}", message));
+    notify::notify(
+        app,
+        "📅 Upcoming meeting",
+        &message,
+        Some(DeepLink::new("meeting", &event.id)),
+    );
+}
+
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut warned: HashSet<String> = HashSet::new();
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            let cfg = settings::current();
+            let lead = cfg.calendar_warn_minutes as i64;
+            let configured = !cfg.calendar_ics.is_empty() || google_calendar::connected();
+            if lead <= 0 || !configured {
+                continue;
+            }
+            let now = Local::now().timestamp_millis();
+            for event in cached_upcoming().await {
+                let left_ms = event.start - now;
+                if event.all_day || left_ms <= 0 || left_ms > lead * 60_000 {
+                    continue;
+                }
+                if warned.insert(event.id.clone()) {
+                    warn(&app, &event, (left_ms + 59_999) / 60_000);
+                }
+            }
+            // 警告済みの記録は溜まりすぎたら捨てる
+            if warned.len() > 500 {
+                warned.clear();
+            }
+        }
+    });
+}
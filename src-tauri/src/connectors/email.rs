@@ -0,0 +1,495 @@
+// src-tauri/src/connectors/email.rs
+//
+// メール (IMAP / 読み取り専用)
+// - 接続先: settings.email_imap_host / email_imap_port (TLS) / email_user / email_mailbox
+//   パスワード（アプリパスワード）は secrets "imap"
+// - EXAMINE（読み取り専用で開く）+ BODY.PEEK なので既読フラグは変えない
+// - unread(limit): 未読の新しい順。本文は先頭だけ取り、MIME を最低限ほどいてテキストにする
+// - 読んだメールは "email" タグ付きで記憶に残す（「今日大事なメール来てた？」に記憶からも答えられるように）
+//   差出人 / 件名 / 本文は外の文面なので untrusted の印で囲んだまま残す（文脈に戻った時に指示として読ませない）
+
+use crate::memory;
+use axis_core::untrusted;
+use crate::{secrets, settings};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_native_tls::TlsStream;
+use tracing::{info, warn};
+
+pub const EMAIL_SESSION: &str = "email";
+const MAX_MESSAGES: usize = 20;
+// 1 通あたり取ってくる先頭バイト数（添付まで落とさない）
+const PEEK_BYTES: usize = 64 * 1024;
+const SNIPPET_CHARS: usize = 1200;
+// サーバーが送ってくるリテラル {n} の上限（本文は PEEK_BYTES までしか頼まないので、これを超えるものは読まない）
+const MAX_LITERAL_BYTES: usize = PEEK_BYTES + 64 * 1024;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct EmailMessage {
+    pub uid: u32,
+    pub from: String,
+    pub subject: String,
+    pub date: Option<i64>, // UNIX ミリ秒
+    pub message_id: String,
+    pub snippet: String,
+}
+
+// ---------- IMAP ----------
+
+// 応答 1 行（リテラル {n} は本文ごと literals に入れ、text には前後の文字だけ残す）
+#[derive(Debug, Default)]
+struct Line {
+    text: String,
+    literals: Vec<(String, Vec<u8>)>, // (直前の text, 中身)
+}
+
+struct Imap {
+    stream: BufReader<TlsStream<TcpStream>>,
+    next_tag: u32,
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Imap {
+    async fn connect(host: &str, port: u16) -> Result<Self, String> {
+        crate::local_only::check("email")?;
+        let tcp = TcpStream::connect((host, port))
+            .await
+            .map_err(|e| format!("IMAP connect {}:{}: {}", host, port, e))?;
+        let tls = native_tls::TlsConnector::new().map_err(|e| e.to_string())?;
+        let stream = tokio_native_tls::TlsConnector::from(tls)
+            .connect(host, tcp)
+            .await
+            .map_err(|e| format!("IMAP TLS: {}", e))?;
+        let mut imap = Self {
+            stream: BufReader::new(stream),
+            next_tag: 1,
+        };
+        let greeting = imap.read_line().await?;
+        if !greeting.text.starts_with("* OK") && !greeting.text.starts_with("* PREAUTH") {
+            return Err(format!("IMAP greeting: {}", greeting.text.trim()));
+        }
+        Ok(imap)
+    }
+
+    async fn read_line(&mut self) -> Result<Line, String> {
+        let mut line = Line::default();
+        loop {
+            let mut raw = Vec::new();
+            let n = self
+                .stream
+                .read_until(b'\n', &mut raw)
+                .await
+                .map_err(|e| e.to_string())?;
+            if n == 0 {
+                return Err("IMAP connection closed".to_string());
+            }
+            let chunk = String::from_utf8_lossy(&raw).to_string();
+            let trimmed = chunk.trim_end_matches(['\r', '\n']);
+            // 行末が {n} ならリテラルが続く
+            let literal = trimmed
+                .strip_suffix('}')
+                .and_then(|s| s.rsplit_once('{'))
+                .and_then(|(head, len)| len.parse::<usize>().ok().map(|n| (head, n)));
+            match literal {
+                Some((_, len)) if len > MAX_LITERAL_BYTES => {
+                    return Err(format!(
+                        "IMAP literal of {} bytes exceeds the {} byte limit",
+                        len, MAX_LITERAL_BYTES
+                    ));
+                }
+                Some((head, len)) => {
+                    line.text.push_str(head);
+                    let mut buf = vec![0u8; len];
+                    self.stream
+                        .read_exact(&mut buf)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    line.literals.push((line.text.clone(), buf));
+                }
+                None => {
+                    line.text.push_str(trimmed);
+                    return Ok(line);
+                }
+            }
+        }
+    }
+
+    // タグ付きで送って、同じタグの完了行まで読む（untagged 応答を返す）
+    async fn command(&mut self, cmd: &str) -> Result<Vec<Line>, String> {
+        let tag = format!("A{:03}", self.next_tag);
+        self.next_tag += 1;
+        self.stream
+            .get_mut()
+            .write_all(format!("{} {}\r\n", tag, cmd).as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut lines = Vec::new();
+        loop {
+            let line = self.read_line().await?;
+            if let Some(status) = line.text.strip_prefix(&format!("{} ", tag)) {
+                if status.starts_with("OK") {
+                    return Ok(lines);
+                }
+                // LOGIN の失敗でパスワードを出さないようにコマンド名だけ
+                let verb = cmd.split_whitespace().next().unwrap_or("");
+                return Err(format!("IMAP {} failed: {}", verb, status));
+            }
+            lines.push(line);
+        }
+    }
+
+    async fn logout(mut self) {
+        let _ = self.command("LOGOUT").await;
+    }
+}
+
+async fn open() -> Result<Imap, String> {
+    let cfg = settings::current();
+    if cfg.email_imap_host.trim().is_empty() || cfg.email_user.trim().is_empty() {
+        return Err("email_imap_host / email_user are not set".to_string());
+    }
+    let password = secrets::require_api_key("imap")?;
+    let mut imap = Imap::connect(cfg.email_imap_host.trim(), cfg.email_imap_port).await?;
+    imap.command(&format!(
+        "LOGIN {} {}",
+        quote(cfg.email_user.trim()),
+        quote(&password)
+    ))
+    .await?;
+    imap.command(&format!("EXAMINE {}", quote(&cfg.email_mailbox)))
+        .await?;
+    Ok(imap)
+}
+
+// secrets の test_api_key("imap") 用
+pub async fn test_login() -> Result<String, String> {
+    let imap = open().await?;
+    imap.logout().await;
+    Ok("imap login OK".to_string())
+}
+
+// ---------- MIME ----------
+
+// ヘッダ部と本文に分けて、折り返しをつないだヘッダを返す
+fn split_message(raw: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let (head, body) = match raw.windows(4).position(|w| w == b"\r\n\r\n") {
+        Some(i) => (&raw[..i], &raw[i + 4..]),
+        None => match raw.windows(2).position(|w| w == b"\n\n") {
+            Some(i) => (&raw[..i], &raw[i + 2..]),
+            None => (raw, &raw[raw.len()..]),
+        },
+    };
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in String::from_utf8_lossy(head).lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, v)) = headers.last_mut() {
+                v.push(' ');
+                v.push_str(line.trim());
+            }
+        } else if let Some((k, v)) = line.split_once(':') {
+            headers.push((k.trim().to_lowercase(), v.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> &'a str {
+    headers
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+        .unwrap_or("")
+}
+
+// Content-Type の引数 (charset / boundary)
+fn param(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|p| {
+        let (k, v) = p.split_once('=')?;
+        (k.trim().eq_ignore_ascii_case(name)).then(|| v.trim().trim_matches('"').to_string())
+    })
+}
+
+fn decode_charset(bytes: &[u8], charset: &str) -> String {
+    match encoding_rs::Encoding::for_label(charset.trim().as_bytes()) {
+        Some(enc) => enc.decode(bytes).0.to_string(),
+        None => String::from_utf8_lossy(bytes).to_string(),
+    }
+}
+
+fn quoted_printable(bytes: &[u8], header_mode: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' if bytes.get(i + 1) == Some(&b'\r') && bytes.get(i + 2) == Some(&b'\n') => i += 3,
+            b'=' if bytes.get(i + 1) == Some(&b'\n') => i += 2,
+            b'=' if i + 2 < bytes.len() => {
+                match u8::from_str_radix(&String::from_utf8_lossy(&bytes[i + 1..i + 3]), 16) {
+                    Ok(b) => {
+                        out.push(b);
+                        i += 3;
+                    }
+                    Err(_) => {
+                        out.push(b'=');
+                        i += 1;
+                    }
+                }
+            }
+            b'_' if header_mode => {
+                out.push(b' ');
+                i += 1;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+// RFC 2047: =?charset?B|Q?...?=
+fn decode_words(value: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"=\?([^?]+)\?([BbQq])\?([^?]*)\?=").expect("encoded-word regex")
+    });
+    // 隣り合う encoded-word の間の空白は捨てる
+    static GAP: OnceLock<Regex> = OnceLock::new();
+    let gap = GAP.get_or_init(|| Regex::new(r"\?=\s+=\?").expect("encoded-word gap regex"));
+    let joined = gap.replace_all(value, "?==?");
+    re.replace_all(&joined, |c: &regex::Captures| {
+        let bytes = if c[2].eq_ignore_ascii_case("B") {
+            STANDARD.decode(c[3].trim()).unwrap_or_default()
+        } else {
+            quoted_printable(c[3].as_bytes(), true)
+        };
+        decode_charset(&bytes, &c[1])
+    })
+    .to_string()
+}
+
+fn plain_html(html: &str) -> String {
+    let text: String = scraper::Html::parse_document(html)
+        .root_element()
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// 本文はバイトのまま区切る（Shift_JIS などを壊さないように）
+fn split_bytes<'a>(hay: &'a [u8], needle: &[u8]) -> Vec<&'a [u8]> {
+    let mut parts = Vec::new();
+    let mut rest = hay;
+    while let Some(i) = rest.windows(needle.len()).position(|w| w == needle) {
+        parts.push(&rest[..i]);
+        rest = &rest[i + needle.len()..];
+    }
+    parts.push(rest);
+    parts
+}
+
+// 最初の text/plain（無ければ text/html を文字だけ）を取り出す
+fn body_text(headers: &[(String, String)], body: &[u8]) -> Option<String> {
+    let ctype = header(headers, "content-type");
+    let lower = ctype.to_lowercase();
+    if lower.starts_with("multipart/") {
+        let boundary = format!("--{}", param(ctype, "boundary")?);
+        let mut html = None;
+        for part in split_bytes(body, boundary.as_bytes()).into_iter().skip(1) {
+            if part.starts_with(b"--") {
+                break;
+            }
+            let start = part
+                .iter()
+                .position(|b| *b != b'\r' && *b != b'\n')
+                .unwrap_or(part.len());
+            let (h, b) = split_message(&part[start..]);
+            let sub = header(&h, "content-type").to_lowercase();
+            if sub.starts_with("text/html") && html.is_none() {
+                html = body_text(&h, b);
+            } else if sub.is_empty()
+                || sub.starts_with("text/plain")
+                || sub.starts_with("multipart/")
+            {
+                if let Some(t) = body_text(&h, b).filter(|t| !t.trim().is_empty()) {
+                    return Some(t);
+                }
+            }
+        }
+        return html;
+    }
+    if !(lower.is_empty() || lower.starts_with("text/")) {
+        return None;
+    }
+    let decoded = match header(headers, "content-transfer-encoding")
+        .to_lowercase()
+        .as_str()
+    {
+        "base64" => {
+            let compact: Vec<u8> = body
+                .iter()
+                .copied()
+                .filter(|b| !b.is_ascii_whitespace())
+                .collect();
+            // 途中で切った本文も読めるところまで
+            let usable = compact.len() / 4 * 4;
+            STANDARD.decode(&compact[..usable]).unwrap_or_default()
+        }
+        "quoted-printable" => quoted_printable(body, false),
+        _ => body.to_vec(),
+    };
+    let text = decode_charset(&decoded, &param(ctype, "charset").unwrap_or_default());
+    Some(if lower.starts_with("text/html") {
+        plain_html(&text)
+    } else {
+        text
+    })
+}
+
+fn parse_message(uid: u32, raw: &[u8]) -> EmailMessage {
+    let (headers, body) = split_message(raw);
+    let snippet = body_text(&headers, body)
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(SNIPPET_CHARS)
+        .collect();
+    EmailMessage {
+        uid,
+        from: decode_words(header(&headers, "from")),
+        subject: decode_words(header(&headers, "subject")),
+        date: DateTime::parse_from_rfc2822(header(&headers, "date").trim())
+            .ok()
+            .map(|t| t.timestamp_millis()),
+        message_id: header(&headers, "message-id").to_string(),
+        snippet,
+    }
+}
+
+// ---------- まとめ ----------
+
+pub async fn unread(app: &AppHandle, limit: usize) -> Result<Vec<EmailMessage>, String> {
+    let mut imap = open().await?;
+    let found = imap.command("UID SEARCH UNSEEN").await?;
+    let mut uids: Vec<u32> = found
+        .iter()
+        .filter_map(|l| l.text.strip_prefix("* SEARCH"))
+        .flat_map(|s| s.split_whitespace().filter_map(|n| n.parse().ok()))
+        .collect();
+    uids.sort_unstable();
+    let newest: Vec<String> = uids
+        .iter()
+        .rev()
+        .take(limit.clamp(1, MAX_MESSAGES))
+        .map(|u| u.to_string())
+        .collect();
+    info!(
+        "📧 [Email] {} unread ({} fetched)",
+        uids.len(),
+        newest.len()
+    );
+    if newest.is_empty() {
+        imap.logout().await;
+        return Ok(vec![]);
+    }
+
+    let fetched = imap
+        .command(&format!(
+            "UID FETCH {} (UID BODY.PEEK[]<0.{}>)",
+            newest.join(","),
+            PEEK_BYTES
+        ))
+        .await?;
+    imap.logout().await;
+
+    static UID_RE: OnceLock<Regex> = OnceLock::new();
+    let uid_re = UID_RE.get_or_init(|| Regex::new(r"UID (\d+)").expect("uid regex"));
+    let mut messages: Vec<EmailMessage> = fetched
+        .iter()
+        .filter(|l| l.text.contains("FETCH"))
+        .filter_map(|l| {
+            let uid = uid_re.captures(&l.text)?[1].parse().ok()?;
+            let (_, raw) = l
+                .literals
+                .iter()
+                .find(|(before, _)| before.contains("BODY["))?;
+            Some(parse_message(uid, raw))
+        })
+        .collect();
+    messages.sort_by_key(|m| std::cmp::Reverse(m.date.unwrap_or(0)));
+    remember(app, &messages);
+    Ok(messages)
+}
+
+// 読んだメールを記憶に残す（同じメールは 1 回だけ）
+fn remember(app: &AppHandle, messages: &[EmailMessage]) {
+    let user = settings::current().email_user;
+    for m in messages {
+        let key = if m.message_id.is_empty() {
+            format!("{}:{}", user, m.uid)
+        } else {
+            m.message_id.clone()
+        };
+        let id = format!("email-{}", &crate::objects::hash(key.as_bytes())[..16]);
+        let timestamp = m.date.unwrap_or_else(|| Local::now().timestamp_millis());
+        let input = untrusted::wrap(
+            "email",
+            &format!("[Email] From: {}\nSubject: {}", m.from, m.subject),
+        );
+        if let Err(e) = memory::save_external(
+            app,
+            &id,
+            EMAIL_SESSION,
+            timestamp,
+            &input,
+            &untrusted::wrap("email", &m.snippet),
+            "imap",
+            "email",
+            vec!["email".to_string()],
+        ) {
+            warn!("⚠️ [Email] failed to remember {}: {}", m.uid, e);
+        }
+    }
+}
+
+fn format_time(ms: i64) -> String {
+    DateTime::from_timestamp_millis(ms)
+        .map(|t| t.with_timezone(&Local).format("%m/%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+// system_context 用
+pub fn summarize(messages: &[EmailMessage]) -> String {
+    if messages.is_empty() {
+        return "No unread email.\n".to_string();
+    }
+    messages
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let snippet: String = m.snippet.chars().take(300).collect();
+            format!(
+                "[{}] {} | {} | {}\n    {}\n",
+                i + 1,
+                m.date.map(format_time).unwrap_or_default(),
+                m.from,
+                m.subject,
+                snippet
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,317 @@
+// src-tauri/src/connectors/git.rs
+//
+// Git リポジトリ
+// - settings.project_folders の下（3 階層まで）から .git のあるフォルダを探す
+// - status / diff の要約 / 最近のコミットは git コマンドの出力をそのまま整形
+// - COMMIT: <repo> ||| <message>（repo は省略可）は必ず確認してから（policy の ALWAYS_CONFIRM）
+//   確認待ちに積む前にリポジトリを決めておき、承認ダイアログにパスが出るようにする
+//   コミットするのは追跡中のファイルの変更だけ（未追跡の .env などを勝手に入れない）
+// - リポジトリの指定はフォルダ名かフルパス（どちらも list_repos に出るものだけ）。省略時は変更のあるリポジトリが 1 つだけならそれ
+// - git は毎回 core.fsmonitor / core.hooksPath を空にして呼ぶ（リポジトリ側の設定でコマンドを動かさせない）
+
+use crate::settings;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+const MAX_DEPTH: usize = 3;
+const DIFF_MAX_CHARS: usize = 4000;
+// リポジトリの .git/config やフックに書かれたコマンドを動かさない
+const SAFE_CONFIG: &[&str] = &["-c", "core.fsmonitor=", "-c", "core.hooksPath="];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Repo {
+    pub name: String,
+    pub path: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct RepoStatus {
+    pub path: String,
+    pub branch: String,
+    pub ahead: u32,
+    pub behind: u32,
+    pub staged: Vec<String>,
+    pub modified: Vec<String>,
+    pub untracked: Vec<String>,
+}
+
+impl RepoStatus {
+    pub fn clean(&self) -> bool {
+        self.staged.is_empty() && self.modified.is_empty() && self.untracked.is_empty()
+    }
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    cmd.args(SAFE_CONFIG).arg("-C").arg(repo).args(args);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(0x08000000);
+    let out = cmd
+        .output()
+        .map_err(|e| format!("failed to run git (is it installed?): {}", e))?;
+    if !out.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+fn walk(dir: &Path, depth: usize, out: &mut Vec<Repo>) {
+    if dir.join(".git").exists() {
+        out.push(Repo {
+            name: dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: dir.to_string_lossy().to_string(),
+        });
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        let skip = matches!(
+            entry.file_name().to_string_lossy().as_ref(),
+            "node_modules" | "target" | "dist" | "build"
+        );
+        if path.is_dir() && !hidden && !skip {
+            walk(&path, depth - 1, out);
+        }
+    }
+}
+
+pub fn list_repos() -> Vec<Repo> {
+    let mut repos = Vec::new();
+    for folder in settings::current().project_folders {
+        walk(
+            &crate::shell::resolve_user_path(&folder),
+            MAX_DEPTH,
+            &mut repos,
+        );
+    }
+    repos.sort_by_key(|r| r.name.to_lowercase());
+    repos.dedup_by(|a, b| a.path == b.path);
+    repos
+}
+
+// 名前かパスでリポジトリを決める（空なら変更のあるものが 1 つだけの時それ）
+pub fn resolve_repo(name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    let repos = list_repos();
+    if name.is_empty() {
+        let dirty: Vec<Repo> = repos
+            .iter()
+            .filter(|r| status(&r.path).map(|s| !s.clean()).unwrap_or(false))
+            .cloned()
+            .collect();
+        return match (dirty.as_slice(), repos.as_slice()) {
+            ([one], _) | ([], [one]) => Ok(PathBuf::from(&one.path)),
+            _ => Err(format!(
+                "which repository? ({})",
+                repos
+                    .iter()
+                    .map(|r| r.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        };
+    }
+    let as_path = Path::new(name);
+    if as_path.is_absolute() {
+        let wanted = as_path.canonicalize().ok();
+        return repos
+            .iter()
+            .find(|r| wanted.is_some() && Path::new(&r.path).canonicalize().ok() == wanted)
+            .map(|r| PathBuf::from(&r.path))
+            .ok_or_else(|| format!("'{}' is not a repository under project_folders", name));
+    }
+    repos
+        .iter()
+        .find(|r| r.name.eq_ignore_ascii_case(name))
+        .map(|r| PathBuf::from(&r.path))
+        .ok_or_else(|| format!("repository '{}' not found under project_folders", name))
+}
+
+pub fn status(repo: &str) -> Result<RepoStatus, String> {
+    let path = Path::new(repo);
+    let out = git(path, &["status", "--porcelain=v1", "--branch"])?;
+    let mut s = RepoStatus {
+        path: repo.to_string(),
+        ..Default::default()
+    };
+    for line in out.lines() {
+        if let Some(head) = line.strip_prefix("## ") {
+            // "main...origin/main [ahead 1, behind 2]"
+            let (branch, rest) = head.split_once("...").unwrap_or((head, ""));
+            s.branch = branch.trim().to_string();
+            for part in rest
+                .split(['[', ']', ','])
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+            {
+                if let Some(n) = part.strip_prefix("ahead ") {
+                    s.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix("behind ") {
+                    s.behind = n.parse().unwrap_or(0);
+                }
+            }
+            continue;
+        }
+        if line.len() < 4 {
+            continue;
+        }
+        let (x, y, file) = (&line[..1], &line[1..2], line[3..].to_string());
+        if x == "?" {
+            s.untracked.push(file);
+            continue;
+        }
+        if x != " " {
+            s.staged.push(file.clone());
+        }
+        if y != " " {
+            s.modified.push(file);
+        }
+    }
+    Ok(s)
+}
+
+pub fn diff_summary(repo: &str) -> Result<String, String> {
+    let path = Path::new(repo);
+    let stat = git(path, &["diff", "HEAD", "--stat"])?;
+    if stat.trim().is_empty() {
+        return Ok("No changes against HEAD.".to_string());
+    }
+    // 変更の中身も先頭だけ（モデルが何を変えたか説明できるように）
+    let patch: String = git(path, &["diff", "HEAD", "--unified=1"])?
+        .chars()
+        .take(DIFF_MAX_CHARS)
+        .collect();
+    Ok(format!("{}\n{}", stat.trim_end(), patch))
+}
+
+pub fn log_recent(repo: &str, n: usize) -> Result<String, String> {
+    let count = format!("-{}", n.clamp(1, 50));
+    git(
+        Path::new(repo),
+        &[
+            "log",
+            &count,
+            "--date=relative",
+            "--pretty=format:%h %ad %an: %s",
+        ],
+    )
+}
+
+fn format_status(s: &RepoStatus) -> String {
+    let mut out = format!("{} [{}", s.path, s.branch);
+    if s.ahead > 0 || s.behind > 0 {
+        out.push_str(&format!(" +{} -{}", s.ahead, s.behind));
+    }
+    out.push_str("]\n");
+    if s.clean() {
+        out.push_str("  clean\n");
+    }
+    for (label, files) in [
+        ("staged", &s.staged),
+        ("modified", &s.modified),
+        ("untracked", &s.untracked),
+    ] {
+        if !files.is_empty() {
+            let shown: Vec<&str> = files.iter().take(20).map(|f| f.as_str()).collect();
+            out.push_str(&format!("  {}: {}", label, shown.join(", ")));
+            if files.len() > shown.len() {
+                out.push_str(&format!(" (+{} more)", files.len() - shown.len()));
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+// Worker の "GIT: <status|diff|log|repos> [repo]" を system_context 用の文字列に
+pub fn run_command(verb: &str, repo: &str) -> String {
+    if verb == "repos" {
+        let repos = list_repos();
+        if repos.is_empty() {
+            return "[Git] No repositories found (set project_folders in settings).\n".to_string();
+        }
+        let lines: String = repos
+            .iter()
+            .map(|r| match status(&r.path) {
+                Ok(s) => format!(
+                    "- {} [{}] {}\n",
+                    r.name,
+                    s.branch,
+                    if s.clean() { "clean" } else { "changed" }
+                ),
+                Err(_) => format!("- {} ({})\n", r.name, r.path),
+            })
+            .collect();
+        return format!("[Git Repositories]\n{}", lines);
+    }
+    let repo = match resolve_repo(repo) {
+        Ok(p) => p.to_string_lossy().to_string(),
+        Err(e) => return format!("[System] Git Error: {}\n", e),
+    };
+    let result = match verb {
+        "status" => status(&repo).map(|s| format_status(&s)),
+        "diff" => diff_summary(&repo),
+        "log" => log_recent(&repo, 10),
+        other => Err(format!(
+            "unknown GIT command '{}': use status / diff / log / repos",
+            other
+        )),
+    };
+    match result {
+        Ok(text) => format!("[Git {}: {}]\n{}\n", verb, repo, text.trim_end()),
+        Err(e) => format!("[System] Git Error: {}\n", e),
+    }
+}
+
+// COMMIT: のリポジトリ（空なら変更のある 1 つ）をパスに解決して確認待ちに積む。(パス, message)
+pub fn prepare_commit(repo: &str, message: &str) -> Result<(String, String), String> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err("COMMIT needs a message".to_string());
+    }
+    let path = resolve_repo(repo)?;
+    let s = status(&path.to_string_lossy())?;
+    if s.staged.is_empty() && s.modified.is_empty() {
+        let note = if s.untracked.is_empty() {
+            String::new()
+        } else {
+            " (untracked files are never committed; add them yourself first)".to_string()
+        };
+        return Err(format!("nothing to commit in {}{}", path.display(), note));
+    }
+    Ok((path.display().to_string(), message.to_string()))
+}
+
+// 承認後 (policy::execute)。追跡中のファイルの変更だけをコミット（git commit -a。未追跡は入れない）
+pub fn commit(repo: &str, message: &str) -> String {
+    let path = match resolve_repo(repo) {
+        Ok(p) => p,
+        Err(e) => return format!("Error: {}", e),
+    };
+    let path = path.as_path();
+    let result = git(path, &["commit", "-a", "--no-verify", "-m", message.trim()])
+        .and_then(|_| git(path, &["log", "-1", "--pretty=format:%h %s"]));
+    match result {
+        Ok(head) => format!("Success: Committed {} in {}.", head, path.display()),
+        Err(e) => format!("Error: {}", e),
+    }
+}
@@ -0,0 +1,213 @@
+// src-tauri/src/connectors/google_calendar.rs
+//
+// Google Calendar (OAuth 2.0 / デスクトップアプリ用クライアント)
+// - client_id は settings.google_calendar_client_id、シークレットは secrets "google"
+// - connect(): ブラウザで同意画面 (oauth.rs のループバック + PKCE) → code をトークンに交換
+//   リフレッシュトークンは keyring ("token:google_calendar") に保存
+// - events(from, to): primary カレンダーの予定（繰り返しは展開済みで受け取る）
+// - アクセストークンはメモリだけに持ち、期限が来たらリフレッシュトークンで取り直す
+
+use super::calendar::CalendarEvent;
+use super::oauth::{self, TokenCache};
+use crate::{secrets, settings};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use serde_json::Value;
+use tracing::info;
+
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const EVENTS_URL: &str = "https://www.googleapis.com/calendar/v3/calendars/primary/events";
+const SCOPE: &str = "https://www.googleapis.com/auth/calendar.readonly";
+const TOKEN_NAME: &str = "google_calendar";
+const LABEL: &str = "Google Calendar";
+
+static ACCESS: TokenCache = TokenCache::new();
+
+pub fn connected() -> bool {
+    secrets::token(TOKEN_NAME).is_some()
+}
+
+fn client() -> Result<(String, String), String> {
+    let id = settings::current().google_calendar_client_id;
+    if id.trim().is_empty() {
+        return Err("google_calendar_client_id is not set".to_string());
+    }
+    let secret = secrets::require_api_key("google")?;
+    Ok((id.trim().to_string(), secret))
+}
+
+async fn access_token() -> Result<String, String> {
+    if let Some(token) = ACCESS.get() {
+        return Ok(token);
+    }
+    let refresh = secrets::token(TOKEN_NAME).ok_or("Google Calendar is not connected")?;
+    let (id, secret) = client()?;
+    let v = oauth::post_token(
+        TOKEN_URL,
+        &[
+            ("client_id", id.as_str()),
+            ("client_secret", secret.as_str()),
+            ("refresh_token", refresh.as_str()),
+            ("grant_type", "refresh_token"),
+        ],
+        LABEL,
+    )
+    .await?;
+    ACCESS.remember(&v, LABEL)
+}
+
+pub async fn connect() -> Result<String, String> {
+    let (id, secret) = client()?;
+    // Google のデスクトップ用クライアントはループバックのポートを問わない
+    let auth = oauth::authorize(
+        AUTH_URL,
+        &[
+            ("client_id", id.as_str()),
+            ("scope", SCOPE),
+            ("access_type", "offline"),
+            ("prompt", "consent"),
+        ],
+        0,
+        LABEL,
+    )
+    .await?;
+    let v = oauth::post_token(
+        TOKEN_URL,
+        &[
+            ("client_id", id.as_str()),
+            ("client_secret", secret.as_str()),
+            ("code", auth.code.as_str()),
+            ("code_verifier", auth.verifier.as_str()),
+            ("redirect_uri", auth.redirect_uri.as_str()),
+            ("grant_type", "authorization_code"),
+        ],
+        LABEL,
+    )
+    .await?;
+    let refresh = v
+        .get("refresh_token")
+        .and_then(|t| t.as_str())
+        .ok_or("Google did not return a refresh token")?;
+    secrets::set_token(TOKEN_NAME, refresh)?;
+    ACCESS.remember(&v, LABEL)?;
+    info!("📅 [Google Calendar] connected");
+    Ok("Google Calendar connected.".to_string())
+}
+
+pub fn disconnect() -> Result<String, String> {
+    secrets::set_token(TOKEN_NAME, "")?;
+    ACCESS.clear();
+    info!("📅 [Google Calendar] disconnected");
+    Ok("Google Calendar disconnected.".to_string())
+}
+
+// start / end: {"dateTime": RFC3339} か {"date": "YYYY-MM-DD"}（終日）
+fn parse_when(v: &Value) -> Option<(i64, bool)> {
+    if let Some(dt) = v.get("dateTime").and_then(|d| d.as_str()) {
+        return DateTime::parse_from_rfc3339(dt)
+            .ok()
+            .map(|t| (t.timestamp_millis(), false));
+    }
+    let d = NaiveDate::parse_from_str(v.get("date")?.as_str()?, "%Y-%m-%d").ok()?;
+    let ms = Local
+        .from_local_datetime(&d.and_hms_opt(0, 0, 0)?)
+        .earliest()?
+        .timestamp_millis();
+    Some((ms, true))
+}
+
+fn meeting_url(item: &Value) -> Option<String> {
+    if let Some(link) = item.get("hangoutLink").and_then(|l| l.as_str()) {
+        return Some(link.to_string());
+    }
+    item.pointer("/conferenceData/entryPoints")
+        .and_then(|e| e.as_array())?
+        .iter()
+        .find(|e| e.get("entryPointType").and_then(|t| t.as_str()) == Some("video"))
+        .and_then(|e| e.get("uri").and_then(|u| u.as_str()))
+        .map(str::to_string)
+}
+
+pub async fn events(from: i64, to: i64) -> Result<Vec<CalendarEvent>, String> {
+    let token = access_token().await?;
+    let rfc3339 = |ms: i64| {
+        Utc.timestamp_millis_opt(ms)
+            .single()
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default()
+    };
+    let url = reqwest::Url::parse_with_params(
+        EVENTS_URL,
+        &[
+            ("timeMin", rfc3339(from).as_str()),
+            ("timeMax", rfc3339(to).as_str()),
+            ("singleEvents", "true"),
+            ("orderBy", "startTime"),
+            ("maxResults", "50"),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let res = crate::ai::client_for("web")?
+        .get(url)
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error("web", &e))?;
+    let code = res.status();
+    let v: Value = res
+        .json()
+        .await
+        .map_err(|e| format!("Google Calendar JSON Error: {}", e))?;
+    if !code.is_success() {
+        if code == reqwest::StatusCode::UNAUTHORIZED {
+            ACCESS.clear();
+        }
+        return Err(format!(
+            "Google Calendar [{}]: {}",
+            code,
+            v.pointer("/error/message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("")
+        ));
+    }
+
+    let text = |item: &Value, key: &str| {
+        item.get(key)
+            .and_then(|x| x.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+    Ok(v.get("items")
+        .and_then(|i| i.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter(|i| text(i, "status") != "cancelled")
+                .filter_map(|i| {
+                    let (start, all_day) = parse_when(i.get("start")?)?;
+                    let end = i
+                        .get("end")
+                        .and_then(parse_when)
+                        .map(|(e, _)| e)
+                        .unwrap_or(start);
+                    let title = text(i, "summary");
+                    Some(CalendarEvent {
+                        id: format!("google:{}", text(i, "id")),
+                        title: if title.is_empty() {
+                            "(no title)".to_string()
+                        } else {
+                            title
+                        },
+                        start,
+                        end,
+                        all_day,
+                        location: text(i, "location"),
+                        meeting_url: meeting_url(i),
+                        source: "google".to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
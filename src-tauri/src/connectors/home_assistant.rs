@@ -0,0 +1,191 @@
+// src-tauri/src/connectors/home_assistant.rs
+//
+// Home Assistant (REST API)
+// - 接続先は settings.home_assistant_url、長期アクセストークンは secrets "homeassistant"
+// - HA: <service> <entity> [key=value ...]
+//   例: HA: turn_on light.living brightness_pct=40 / HA: climate.set_temperature climate.bedroom temperature=22
+//   service にドメインが無ければ entity のドメインを使う
+// - HA: states で操作できるエンティティの今の状態
+// - 操作できるのは policy::ha_entity_allowed を通ったものだけ（settings.ha_allowed_entities。空なら何もできない）
+
+use crate::{policy, secrets, settings, sharing};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use tracing::info;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EntityState {
+    pub entity_id: String,
+    pub name: String,
+    pub state: String,
+}
+
+fn base_url() -> Result<String, String> {
+    let url = settings::current().home_assistant_url;
+    let url = url.trim().trim_end_matches('/');
+    if url.is_empty() {
+        return Err("home_assistant_url is not set".to_string());
+    }
+    Ok(url.to_string())
+}
+
+async fn request(
+    method: reqwest::Method,
+    path: &str,
+    body: Option<Value>,
+) -> Result<Value, String> {
+    let token = secrets::require_api_key("homeassistant")?;
+    let mut req = crate::ai::client_for("web")?
+        .request(method, format!("{}{}", base_url()?, path))
+        .bearer_auth(token);
+    if let Some(body) = body {
+        req = req.json(&body);
+    }
+    let res = req
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error("web", &e))?;
+    let code = res.status();
+    if !code.is_success() {
+        let text: String = res
+            .text()
+            .await
+            .unwrap_or_default()
+            .chars()
+            .take(200)
+            .collect();
+        return Err(format!("Home Assistant [{}]: {}", code, text));
+    }
+    res.json()
+        .await
+        .map_err(|e| format!("Home Assistant JSON Error: {}", e))
+}
+
+// secrets の test_api_key("homeassistant") 用
+pub async fn test_connection() -> Result<String, String> {
+    let v = request(reqwest::Method::GET, "/api/", None).await?;
+    Ok(format!(
+        "homeassistant OK ({})",
+        v.get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("connected")
+    ))
+}
+
+// "40" → 数値, "true" → bool, それ以外は文字列
+fn value_of(raw: &str) -> Value {
+    if let Ok(n) = raw.parse::<i64>() {
+        return json!(n);
+    }
+    if let Ok(f) = raw.parse::<f64>() {
+        return json!(f);
+    }
+    match raw {
+        "true" => json!(true),
+        "false" => json!(false),
+        _ => json!(raw),
+    }
+}
+
+// ドメイン / サービス名は [a-z0-9_]+ だけ（URL のパスに入れるので "../" や "?" を通さない）
+fn is_slug(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+pub async fn call_service(
+    service: &str,
+    entity: &str,
+    data: &[(String, String)],
+) -> Result<String, String> {
+    let entity = entity.trim().to_lowercase();
+    let Some((entity_domain, _)) = entity.split_once('.') else {
+        return Err(format!("'{}' is not an entity id (domain.name)", entity));
+    };
+    if !policy::ha_entity_allowed(&entity) {
+        return Err(format!(
+            "{} is not in ha_allowed_entities (add it in settings to let Axis control it)",
+            entity
+        ));
+    }
+    let service = service.trim().to_lowercase();
+    let (domain, service) = match service.split_once('.') {
+        Some((d, s)) => (d.to_string(), s.to_string()),
+        None => (entity_domain.to_string(), service),
+    };
+    if !is_slug(&domain) || !is_slug(&service) {
+        return Err(format!("invalid service name '{}.{}'", domain, service));
+    }
+    // homeassistant.turn_off 等の汎用ドメインも使えるが、それ以外でエンティティと違うドメインは止める
+    if domain != entity_domain && domain != "homeassistant" {
+        return Err(format!(
+            "service {}.{} does not match entity {}",
+            domain, service, entity
+        ));
+    }
+
+    let mut body = Map::new();
+    body.insert("entity_id".to_string(), json!(entity));
+    for (k, v) in data {
+        body.insert(k.clone(), value_of(v));
+    }
+    info!("🏠 [HomeAssistant] {}.{} {}", domain, service, entity);
+    let sent = Value::Object(body.clone()).to_string();
+    sharing::record("home_assistant", &format!("{}.{}", domain, service), "", &sent, (0, 0));
+    request(
+        reqwest::Method::POST,
+        &format!("/api/services/{}/{}", domain, service),
+        Some(Value::Object(body)),
+    )
+    .await?;
+    Ok(format!("Success: {}.{} {}", domain, service, entity))
+}
+
+pub async fn states() -> Result<Vec<EntityState>, String> {
+    let v = request(reqwest::Method::GET, "/api/states", None).await?;
+    let text = |s: &Value, ptr: &str| {
+        s.pointer(ptr)
+            .and_then(|x| x.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+    Ok(v.as_array()
+        .map(|list| {
+            list.iter()
+                .map(|s| EntityState {
+                    entity_id: text(s, "/entity_id"),
+                    name: text(s, "/attributes/friendly_name"),
+                    state: text(s, "/state"),
+                })
+                .filter(|s| policy::ha_entity_allowed(&s.entity_id))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+// Worker の "HA: ..." を実行して system_context 用の文字列を返す（service "states" は一覧）
+pub async fn run_command(service: &str, entity: &str, data: &BTreeMap<String, String>) -> String {
+    if service.is_empty() || service.eq_ignore_ascii_case("states") {
+        return match states().await {
+            Ok(list) if list.is_empty() => "[Home] No controllable entities.\n".to_string(),
+            Ok(list) => {
+                let lines: String = list
+                    .iter()
+                    .map(|s| format!("- {} ({}): {}\n", s.entity_id, s.name, s.state))
+                    .collect();
+                format!("[Home]\n{}", lines)
+            }
+            Err(e) => format!("[System] Home Assistant Error: {}\n", e),
+        };
+    }
+    if entity.is_empty() {
+        return "[System] Home Assistant Error: HA needs <service> <entity>\n".to_string();
+    }
+    let data: Vec<(String, String)> = data.clone().into_iter().collect();
+    match call_service(service, entity, &data).await {
+        Ok(msg) => format!("[Home] {}\n", msg),
+        Err(e) => format!("[System] Home Assistant Error: {}\n", e),
+    }
+}
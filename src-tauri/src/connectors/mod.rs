@@ -0,0 +1,24 @@
+// src-tauri/src/connectors/mod.rs
+//
+// 外部データの専用コネクタ（HTML を削らずに構造化データを取る）
+// - browser: ブラウザ拡張から届く今のタブの URL / タイトル / 本文 (BROWSER_TAB)
+// - weather: Open-Meteo（キー不要。地名 → 緯度経度 → 現在 + 3 日予報）
+// - news: RSS / Atom フィード、または NewsAPI（settings.news_source）
+// - calendar: ローカル / 購読 .ics と Google Calendar の予定、会議前の警告
+// - email: IMAP の受信箱を読み取り専用で（未読の一覧と本文の先頭）
+// - git: project_folders の下のリポジトリ（status / diff / log、COMMIT: は必ず確認）
+// - home_assistant: Home Assistant の REST API（HA: / 操作できるのは許可リストのエンティティだけ）
+// - spotify: 再生操作 (MEDIA: の Spotify 側。media.rs から)
+// - oauth: Google / Spotify 共通の OAuth (ループバック + PKCE)
+// - Worker の BROWSER_TAB / WEATHER: / NEWS: / CALENDAR: / CHECK_EMAIL / HA: / GIT: / COMMIT: コマンドから呼ばれる
+
+pub mod browser;
+pub mod calendar;
+pub mod email;
+pub mod git;
+pub mod google_calendar;
+pub mod home_assistant;
+pub mod news;
+pub mod oauth;
+pub mod spotify;
+pub mod weather;
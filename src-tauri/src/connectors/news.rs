@@ -0,0 +1,313 @@
+// src-tauri/src/connectors/news.rs
+//
+// ニュース
+// - settings.news_source = "rss": settings.news_feeds の RSS / Atom を読む（topic があれば見出しで絞る）
+// - settings.news_source = "newsapi": NewsAPI (キーは secrets "newsapi")。topic なしならトップニュース
+// - parse_feed は RSS 2.0 / Atom どちらも読める（フィード購読でも使う）
+
+use crate::secrets;
+use crate::settings;
+use chrono::DateTime;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::info;
+
+const MAX_ITEMS: usize = 8;
+const FEED_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct FeedItem {
+    pub title: String,
+    pub link: String,
+    pub summary: String,
+    pub published: Option<i64>, // UNIX ミリ秒
+    pub source: String,         // フィード (チャンネル) 名
+    pub guid: String,           // 無ければ link
+}
+
+fn parse_date(s: &str) -> Option<i64> {
+    let s = s.trim();
+    DateTime::parse_from_rfc2822(s)
+        .or_else(|_| DateTime::parse_from_rfc3339(s))
+        .ok()
+        .map(|t| t.timestamp_millis())
+}
+
+// description に HTML が入っているフィードが多いので文字だけにする
+fn plain(s: &str) -> String {
+    let text: String = scraper::Html::parse_fragment(s)
+        .root_element()
+        .text()
+        .collect();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// RSS 2.0 (<channel><item>) と Atom (<feed><entry>) を読む
+pub fn parse_feed(xml: &str) -> Result<Vec<FeedItem>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut items = Vec::new();
+    let mut source = String::new();
+    let mut current: Option<FeedItem> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase();
+                if name == "item" || name == "entry" {
+                    current = Some(FeedItem {
+                        source: source.clone(),
+                        ..Default::default()
+                    });
+                }
+                if name == "link" {
+                    // Atom: <link href="..." rel="alternate">...</link>
+                    if let (Some(item), Ok(Some(href))) =
+                        (current.as_mut(), e.try_get_attribute("href"))
+                    {
+                        if item.link.is_empty() {
+                            item.link = href.unescape_value().unwrap_or_default().to_string();
+                        }
+                    }
+                }
+                text.clear();
+            }
+            Ok(Event::Empty(e)) => {
+                // Atom の <link href="..."/>
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase();
+                if name != "link" {
+                    continue;
+                }
+                let rel = e
+                    .try_get_attribute("rel")
+                    .ok()
+                    .flatten()
+                    .and_then(|a| a.unescape_value().ok().map(|v| v.to_string()))
+                    .unwrap_or_else(|| "alternate".to_string());
+                if let (Some(item), Ok(Some(href))) =
+                    (current.as_mut(), e.try_get_attribute("href"))
+                {
+                    if item.link.is_empty() && rel == "alternate" {
+                        item.link = href.unescape_value().unwrap_or_default().to_string();
+                    }
+                }
+            }
+            Ok(Event::Text(t)) => text.push_str(&t.decode().unwrap_or_default()),
+            Ok(Event::CData(c)) => text.push_str(&c.decode().unwrap_or_default()),
+            Ok(Event::GeneralRef(r)) => {
+                if let Ok(Some(ch)) = r.resolve_char_ref() {
+                    text.push(ch);
+                } else if let Ok(name) = r.decode() {
+                    match resolve_predefined_entity(&name) {
+                        Some(s) => text.push_str(s),
+                        None => text.push_str(&format!("&{};", name)),
+                    }
+                }
+            }
+            Ok(Event::End(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase();
+                let value = text.trim().to_string();
+                match current.as_mut() {
+                    Some(item) => match name.as_str() {
+                        "title" => item.title = plain(&value),
+                        "link" if item.link.is_empty() => item.link = value,
+                        "description" | "summary" | "content" | "encoded"
+                            if item.summary.is_empty() =>
+                        {
+                            item.summary = plain(&value)
+                        }
+                        "pubdate" | "published" | "updated" | "date"
+                            if item.published.is_none() =>
+                        {
+                            item.published = parse_date(&value)
+                        }
+                        "guid" | "id" => item.guid = value,
+                        "item" | "entry" => {
+                            let mut done = current.take().unwrap_or_default();
+                            if done.guid.is_empty() {
+                                done.guid = done.link.clone();
+                            }
+                            if !done.title.is_empty() {
+                                items.push(done);
+                            }
+                        }
+                        _ => {}
+                    },
+                    // チャンネル / フィードのタイトル
+                    None if name == "title" && source.is_empty() => source = plain(&value),
+                    None => {}
+                }
+                text.clear();
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(format!(
+                    "feed parse error at {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+            _ => {}
+        }
+    }
+    Ok(items)
+}
+
+pub async fn fetch_feed(url: &str) -> Result<Vec<FeedItem>, String> {
+    let mut res = crate::ai::client_for("web")?
+        .get(url)
+        .header(
+            reqwest::header::ACCEPT,
+            "application/rss+xml, application/atom+xml, application/xml, text/xml",
+        )
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error("web", &e))?;
+    if !res.status().is_success() {
+        return Err(format!("HTTP {} from {}", res.status(), url));
+    }
+    // 読みながら上限を見る（巨大なフィードを全部受け取ってから弾くのでは遅い）
+    let mut body: Vec<u8> = Vec::new();
+    while let Some(chunk) = res.chunk().await.map_err(|e| e.to_string())? {
+        if body.len() + chunk.len() > FEED_MAX_BYTES {
+            return Err(format!("feed too large (over {} bytes): {}", FEED_MAX_BYTES, url));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    parse_feed(&String::from_utf8_lossy(&body))
+}
+
+async fn from_rss(feeds: &[String], topic: &str) -> Result<Vec<FeedItem>, String> {
+    if feeds.is_empty() {
+        return Err("news_feeds is empty".to_string());
+    }
+    let mut all = Vec::new();
+    let mut errors = Vec::new();
+    for url in feeds {
+        match fetch_feed(url).await {
+            Ok(items) => all.extend(items),
+            Err(e) => errors.push(e),
+        }
+    }
+    if all.is_empty() && !errors.is_empty() {
+        return Err(errors.join(" / "));
+    }
+
+    // topic の語がどれか入っている記事だけ（1 件も無ければ全部）
+    let words: Vec<String> = topic.split_whitespace().map(|w| w.to_lowercase()).collect();
+    if !words.is_empty() {
+        let hits: Vec<FeedItem> = all
+            .iter()
+            .filter(|i| {
+                let hay = format!("{} {}", i.title, i.summary).to_lowercase();
+                words.iter().any(|w| hay.contains(w))
+            })
+            .cloned()
+            .collect();
+        if !hits.is_empty() {
+            all = hits;
+        }
+    }
+    all.sort_by_key(|i| std::cmp::Reverse(i.published.unwrap_or(0)));
+    all.truncate(MAX_ITEMS);
+    Ok(all)
+}
+
+async fn from_newsapi(topic: &str, country: &str) -> Result<Vec<FeedItem>, String> {
+    let key = secrets::require_api_key("newsapi")?;
+    let page_size = MAX_ITEMS.to_string();
+    let url = if topic.trim().is_empty() {
+        reqwest::Url::parse_with_params(
+            "https://newsapi.org/v2/top-headlines",
+            &[("country", country), ("pageSize", page_size.as_str())],
+        )
+    } else {
+        reqwest::Url::parse_with_params(
+            "https://newsapi.org/v2/everything",
+            &[
+                ("q", topic.trim()),
+                ("sortBy", "publishedAt"),
+                ("pageSize", page_size.as_str()),
+            ],
+        )
+    }
+    .map_err(|e| e.to_string())?;
+
+    let res = crate::ai::client_for("web")?
+        .get(url)
+        .header("X-Api-Key", key)
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error("web", &e))?;
+    let code = res.status();
+    let v: Value = res
+        .json()
+        .await
+        .map_err(|e| format!("NewsAPI JSON Error: {}", e))?;
+    if !code.is_success() {
+        return Err(format!(
+            "NewsAPI [{}]: {}",
+            code,
+            v.get("message").and_then(|m| m.as_str()).unwrap_or("")
+        ));
+    }
+
+    let text = |a: &Value, ptr: &str| {
+        a.pointer(ptr)
+            .and_then(|x| x.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+    Ok(v.get("articles")
+        .and_then(|a| a.as_array())
+        .map(|list| {
+            list.iter()
+                .map(|a| FeedItem {
+                    title: text(a, "/title"),
+                    link: text(a, "/url"),
+                    summary: text(a, "/description"),
+                    published: parse_date(&text(a, "/publishedAt")),
+                    source: text(a, "/source/name"),
+                    guid: text(a, "/url"),
+                })
+                .filter(|i| !i.title.is_empty())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+pub async fn headlines(topic: &str) -> Result<Vec<FeedItem>, String> {
+    let cfg = settings::current();
+    info!(query = %topic.trim(), "📰 [News] {}", cfg.news_source);
+    match cfg.news_source.trim().to_lowercase().as_str() {
+        "newsapi" => {
+            // RSS はフィードを取ってきて手元で絞るので、話題が外に出るのは NewsAPI だけ
+            crate::sharing::record("newsapi", "news", "", topic.trim(), (0, 0));
+            from_newsapi(topic, &cfg.news_country).await
+        }
+        _ => from_rss(&cfg.news_feeds, topic).await,
+    }
+}
+
+// system_context 用
+pub fn summarize(items: &[FeedItem]) -> String {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let summary: String = item.summary.chars().take(160).collect();
+            format!(
+                "[{}] {} — {} ({})\n    {}",
+                i + 1,
+                item.title,
+                item.source,
+                item.link,
+                summary
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,204 @@
+// src-tauri/src/connectors/oauth.rs
+//
+// OAuth 2.0 (認可コード + PKCE / ループバック) の共通部分
+// - authorize(): 127.0.0.1 で待ち受け → ブラウザで同意画面 → リダイレクトで code を受け取る
+//   port = 0 なら空きポート（Spotify のように登録済みの URI しか通らない所は固定する）
+// - post_token(): トークンエンドポイントへの form POST
+// - TokenCache: アクセストークンをメモリだけに持ち、期限の 1 分前に切れたことにする
+// - リフレッシュトークンの保存は secrets::set_token（呼び出し側）
+
+use crate::shell;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::info;
+use uuid::Uuid;
+
+// ブラウザで同意するまで待つ時間
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(300);
+pub const REDIRECT_PATH: &str = "/callback";
+
+pub struct Authorization {
+    pub code: String,
+    pub redirect_uri: String,
+    pub verifier: String, // code_verifier としてトークン交換に渡す
+}
+
+struct Cached {
+    value: String,
+    expires: Instant,
+}
+
+pub struct TokenCache(Mutex<Option<Cached>>);
+
+impl TokenCache {
+    pub const fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    pub fn get(&self) -> Option<String> {
+        self.0
+            .lock()
+            .ok()?
+            .as_ref()
+            .filter(|c| c.expires > Instant::now())
+            .map(|c| c.value.clone())
+    }
+
+    // トークン応答 {"access_token", "expires_in"} を覚えてアクセストークンを返す
+    pub fn remember(&self, v: &Value, label: &str) -> Result<String, String> {
+        let value = v
+            .get("access_token")
+            .and_then(|t| t.as_str())
+            .ok_or_else(|| format!("{} token: no access_token", label))?
+            .to_string();
+        let secs = v.get("expires_in").and_then(|t| t.as_u64()).unwrap_or(3600);
+        if let Ok(mut c) = self.0.lock() {
+            *c = Some(Cached {
+                value: value.clone(),
+                // 期限ぎりぎりで失敗しないよう 1 分早めに取り直す
+                expires: Instant::now() + Duration::from_secs(secs.saturating_sub(60)),
+            });
+        }
+        Ok(value)
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut c) = self.0.lock() {
+            *c = None;
+        }
+    }
+}
+
+pub async fn post_token(url: &str, form: &[(&str, &str)], label: &str) -> Result<Value, String> {
+    let res = crate::ai::client_for("web")?
+        .post(url)
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error("web", &e))?;
+    let code = res.status();
+    let v: Value = res
+        .json()
+        .await
+        .map_err(|e| format!("{} token JSON Error: {}", label, e))?;
+    if !code.is_success() {
+        return Err(format!(
+            "{} token [{}]: {}",
+            label,
+            code,
+            v.get("error_description")
+                .or_else(|| v.get("error"))
+                .and_then(|m| m.as_str())
+                .unwrap_or("")
+        ));
+    }
+    Ok(v)
+}
+
+// リダイレクトの GET /callback?code=...&state=... を 1 回だけ受ける
+async fn wait_for_code(listener: TcpListener, state: &str, label: &str) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener.accept().await.map_err(|e| e.to_string())?;
+        let mut buf = vec![0u8; 8192];
+        let n = stream.read(&mut buf).await.map_err(|e| e.to_string())?;
+        let request = String::from_utf8_lossy(&buf[..n]);
+        let Some(path) = request
+            .lines()
+            .next()
+            .and_then(|l| l.split_whitespace().nth(1))
+        else {
+            continue;
+        };
+        let Ok(url) = reqwest::Url::parse(&format!("http://127.0.0.1{}", path)) else {
+            continue;
+        };
+        let param = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.to_string())
+        };
+        // favicon 等は無視して待ち続ける
+        if param("code").is_none() && param("error").is_none() {
+            let _ = stream
+                .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+                .await;
+            continue;
+        }
+
+        let result = match (param("state"), param("code"), param("error")) {
+            (_, _, Some(err)) => Err(format!("authorization denied: {}", err)),
+            (Some(s), Some(code), None) if s == state => Ok(code),
+            _ => Err("state mismatch".to_string()),
+        };
+        let body = match &result {
+            Ok(_) => format!("AXIS OS: {} is connected. You can close this tab.", label),
+            Err(_) => format!(
+                "AXIS OS: {} connection failed. You can close this tab.",
+                label
+            ),
+        };
+        let _ = stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .as_bytes(),
+            )
+            .await;
+        return result;
+    }
+}
+
+// params: client_id / scope などプロバイダ固有のもの（redirect_uri / state / PKCE はここで足す）
+pub async fn authorize(
+    auth_url: &str,
+    params: &[(&str, &str)],
+    port: u16,
+    label: &str,
+) -> Result<Authorization, String> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("failed to listen for OAuth redirect: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, REDIRECT_PATH);
+
+    // PKCE (S256)
+    let verifier = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let state = Uuid::new_v4().simple().to_string();
+
+    let mut query: Vec<(&str, &str)> = params.to_vec();
+    query.extend([
+        ("redirect_uri", redirect_uri.as_str()),
+        ("response_type", "code"),
+        ("code_challenge", challenge.as_str()),
+        ("code_challenge_method", "S256"),
+        ("state", state.as_str()),
+    ]);
+    let url = reqwest::Url::parse_with_params(auth_url, &query).map_err(|e| e.to_string())?;
+    info!(
+        "🔐 [OAuth] {}: waiting for consent on {}",
+        label, redirect_uri
+    );
+    let opened = shell::open_url(url.as_str());
+    if !opened.starts_with("Success") {
+        return Err(opened);
+    }
+
+    let code = tokio::time::timeout(CONNECT_TIMEOUT, wait_for_code(listener, &state, label))
+        .await
+        .map_err(|_| format!("timed out waiting for {} consent", label))??;
+    Ok(Authorization {
+        code,
+        redirect_uri,
+        verifier,
+    })
+}
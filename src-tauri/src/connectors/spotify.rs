@@ -0,0 +1,138 @@
+// src-tauri/src/connectors/spotify.rs
+//
+// Spotify Web API（再生の操作だけ）
+// - client_id は settings.spotify_client_id（PKCE なのでシークレット不要）
+// - Spotify はリダイレクト URI を登録したものしか通さないので、ポートは settings.spotify_redirect_port に固定
+//   ダッシュボードに http://127.0.0.1:<port>/callback を登録しておく
+// - リフレッシュトークンは keyring ("token:spotify")
+// - Premium でないと再生操作の API は 403 になる（その時は media.rs がメディアキーに戻す）
+
+use super::oauth::{self, TokenCache};
+use crate::{secrets, settings};
+use serde_json::Value;
+use tracing::info;
+
+const AUTH_URL: &str = "https://accounts.spotify.com/authorize";
+const TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
+const PLAYER_URL: &str = "https://api.spotify.com/v1/me/player";
+const SCOPE: &str = "user-modify-playback-state user-read-playback-state";
+const TOKEN_NAME: &str = "spotify";
+const LABEL: &str = "Spotify";
+
+static ACCESS: TokenCache = TokenCache::new();
+
+pub fn connected() -> bool {
+    secrets::token(TOKEN_NAME).is_some()
+}
+
+fn client_id() -> Result<String, String> {
+    let id = settings::current().spotify_client_id;
+    if id.trim().is_empty() {
+        return Err("spotify_client_id is not set".to_string());
+    }
+    Ok(id.trim().to_string())
+}
+
+async fn access_token() -> Result<String, String> {
+    if let Some(token) = ACCESS.get() {
+        return Ok(token);
+    }
+    let refresh = secrets::token(TOKEN_NAME).ok_or("Spotify is not connected")?;
+    let id = client_id()?;
+    let v = oauth::post_token(
+        TOKEN_URL,
+        &[
+            ("client_id", id.as_str()),
+            ("refresh_token", refresh.as_str()),
+            ("grant_type", "refresh_token"),
+        ],
+        LABEL,
+    )
+    .await?;
+    // Spotify はリフレッシュの度に新しいリフレッシュトークンを返すことがある
+    if let Some(r) = v.get("refresh_token").and_then(|t| t.as_str()) {
+        secrets::set_token(TOKEN_NAME, r)?;
+    }
+    ACCESS.remember(&v, LABEL)
+}
+
+pub async fn connect() -> Result<String, String> {
+    let id = client_id()?;
+    let port = settings::current().spotify_redirect_port;
+    let auth = oauth::authorize(
+        AUTH_URL,
+        &[("client_id", id.as_str()), ("scope", SCOPE)],
+        port,
+        LABEL,
+    )
+    .await?;
+    let v = oauth::post_token(
+        TOKEN_URL,
+        &[
+            ("client_id", id.as_str()),
+            ("code", auth.code.as_str()),
+            ("code_verifier", auth.verifier.as_str()),
+            ("redirect_uri", auth.redirect_uri.as_str()),
+            ("grant_type", "authorization_code"),
+        ],
+        LABEL,
+    )
+    .await?;
+    let refresh = v
+        .get("refresh_token")
+        .and_then(|t| t.as_str())
+        .ok_or("Spotify did not return a refresh token")?;
+    secrets::set_token(TOKEN_NAME, refresh)?;
+    ACCESS.remember(&v, LABEL)?;
+    info!("🎵 [Spotify] connected");
+    Ok("Spotify connected.".to_string())
+}
+
+pub fn disconnect() -> Result<String, String> {
+    secrets::set_token(TOKEN_NAME, "")?;
+    ACCESS.clear();
+    info!("🎵 [Spotify] disconnected");
+    Ok("Spotify disconnected.".to_string())
+}
+
+// action: play / pause / next / prev / volume（volume は 0..=100）
+pub async fn control(action: &str, volume: Option<u32>) -> Result<String, String> {
+    let token = access_token().await?;
+    let http = crate::ai::client_for("web")?;
+    let sent = match (action, volume) {
+        ("volume", v) => format!("volume {}", v.unwrap_or(50).min(100)),
+        (a, _) => a.to_string(),
+    };
+    crate::sharing::record("spotify", "player", "", &sent, (0, 0));
+    let req = match action {
+        "play" => http.put(format!("{}/play", PLAYER_URL)),
+        "pause" => http.put(format!("{}/pause", PLAYER_URL)),
+        "next" => http.post(format!("{}/next", PLAYER_URL)),
+        "prev" => http.post(format!("{}/previous", PLAYER_URL)),
+        "volume" => http
+            .put(format!("{}/volume", PLAYER_URL))
+            .query(&[("volume_percent", volume.unwrap_or(50).min(100))]),
+        other => return Err(format!("unknown media action: {}", other)),
+    };
+    let res = req
+        .bearer_auth(token)
+        .header(reqwest::header::CONTENT_LENGTH, "0")
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error("web", &e))?;
+    let code = res.status();
+    if code.is_success() {
+        return Ok(format!("Spotify: {}", action));
+    }
+    if code == reqwest::StatusCode::UNAUTHORIZED {
+        ACCESS.clear();
+    }
+    let v: Value = res.json().await.unwrap_or_default();
+    Err(format!(
+        "Spotify [{}]: {}",
+        code,
+        v.pointer("/error/message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("")
+    ))
+}
@@ -0,0 +1,195 @@
+// src-tauri/src/connectors/weather.rs
+//
+// 天気 (Open-Meteo)
+// - 地名はジオコーディング API で緯度経度にする（空なら settings.weather_location）
+// - 現在の気温 / 体感 / 湿度 / 降水 / 風 と、今日から 3 日分の最高・最低・降水確率
+
+use serde::Serialize;
+use serde_json::Value;
+use tracing::info;
+
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+const FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const FORECAST_DAYS: &str = "3";
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct CurrentWeather {
+    pub temperature: f64,
+    pub apparent_temperature: f64,
+    pub humidity: f64,
+    pub precipitation: f64,
+    pub wind_speed: f64,
+    pub description: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DailyForecast {
+    pub date: String,
+    pub max: f64,
+    pub min: f64,
+    pub precipitation_probability: f64,
+    pub description: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct WeatherReport {
+    pub place: String,
+    pub country: String,
+    pub timezone: String,
+    pub current: CurrentWeather,
+    pub daily: Vec<DailyForecast>,
+}
+
+// WMO weather code → 日本語
+fn describe(code: i64) -> &'static str {
+    match code {
+        0 => "快晴",
+        1 => "晴れ",
+        2 => "晴れ時々くもり",
+        3 => "くもり",
+        45 | 48 => "霧",
+        51 | 53 | 55 => "霧雨",
+        56 | 57 => "着氷性の霧雨",
+        61 => "小雨",
+        63 => "雨",
+        65 => "大雨",
+        66 | 67 => "着氷性の雨",
+        71 => "小雪",
+        73 => "雪",
+        75 => "大雪",
+        77 => "霧雪",
+        80..=82 => "にわか雨",
+        85 | 86 => "にわか雪",
+        95 => "雷雨",
+        96 | 99 => "ひょうを伴う雷雨",
+        _ => "不明",
+    }
+}
+
+async fn get_json(url: reqwest::Url) -> Result<Value, String> {
+    let res = crate::ai::client_for("web")?
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error("web", &e))?;
+    if !res.status().is_success() {
+        return Err(format!("Open-Meteo HTTP {}", res.status()));
+    }
+    res.json()
+        .await
+        .map_err(|e| format!("Open-Meteo JSON Error: {}", e))
+}
+
+fn num(v: &Value, ptr: &str) -> f64 {
+    v.pointer(ptr).and_then(|x| x.as_f64()).unwrap_or_default()
+}
+
+pub async fn forecast(place: &str) -> Result<WeatherReport, String> {
+    let place = if place.trim().is_empty() {
+        crate::settings::current().weather_location
+    } else {
+        place.trim().to_string()
+    };
+    info!(query = %place, "🌤️ [Weather]");
+    crate::sharing::record("open-meteo", "weather", "", &place, (0, 0));
+
+    let geo_url = reqwest::Url::parse_with_params(
+        GEOCODING_URL,
+        &[("name", place.as_str()), ("count", "1"), ("language", "ja")],
+    )
+    .map_err(|e| e.to_string())?;
+    let geo = get_json(geo_url).await?;
+    let hit = geo
+        .pointer("/results/0")
+        .ok_or_else(|| format!("place not found: {}", place))?;
+    let lat = num(hit, "/latitude");
+    let lon = num(hit, "/longitude");
+    let text = |v: &Value, key: &str| {
+        v.get(key)
+            .and_then(|x| x.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+
+    let url = reqwest::Url::parse_with_params(
+        FORECAST_URL,
+        &[
+            ("latitude", lat.to_string().as_str()),
+            ("longitude", lon.to_string().as_str()),
+            (
+                "current",
+                "temperature_2m,apparent_temperature,relative_humidity_2m,precipitation,weather_code,wind_speed_10m",
+            ),
+            (
+                "daily",
+                "weather_code,temperature_2m_max,temperature_2m_min,precipitation_probability_max",
+            ),
+            ("timezone", "auto"),
+            ("forecast_days", FORECAST_DAYS),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    let v = get_json(url).await?;
+
+    let dates: Vec<String> = v
+        .pointer("/daily/time")
+        .and_then(|t| t.as_array())
+        .map(|t| {
+            t.iter()
+                .filter_map(|d| d.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let daily = dates
+        .into_iter()
+        .enumerate()
+        .map(|(i, date)| DailyForecast {
+            date,
+            max: num(&v, &format!("/daily/temperature_2m_max/{}", i)),
+            min: num(&v, &format!("/daily/temperature_2m_min/{}", i)),
+            precipitation_probability: num(
+                &v,
+                &format!("/daily/precipitation_probability_max/{}", i),
+            ),
+            description: describe(num(&v, &format!("/daily/weather_code/{}", i)) as i64)
+                .to_string(),
+        })
+        .collect();
+
+    Ok(WeatherReport {
+        place: text(hit, "name"),
+        country: text(hit, "country"),
+        timezone: text(&v, "timezone"),
+        current: CurrentWeather {
+            temperature: num(&v, "/current/temperature_2m"),
+            apparent_temperature: num(&v, "/current/apparent_temperature"),
+            humidity: num(&v, "/current/relative_humidity_2m"),
+            precipitation: num(&v, "/current/precipitation"),
+            wind_speed: num(&v, "/current/wind_speed_10m"),
+            description: describe(num(&v, "/current/weather_code") as i64).to_string(),
+        },
+        daily,
+    })
+}
+
+// system_context 用
+pub fn summarize(r: &WeatherReport) -> String {
+    let mut out = format!(
+        "{} ({}) 現在: {} {:.1}°C (体感 {:.1}°C) 湿度 {:.0}% 降水 {:.1}mm 風 {:.1}km/h\n",
+        r.place,
+        r.country,
+        r.current.description,
+        r.current.temperature,
+        r.current.apparent_temperature,
+        r.current.humidity,
+        r.current.precipitation,
+        r.current.wind_speed
+    );
+    for d in &r.daily {
+        out.push_str(&format!(
+            "- {}: {} 最高 {:.1}°C / 最低 {:.1}°C 降水確率 {:.0}%\n",
+            d.date, d.description, d.max, d.min, d.precipitation_probability
+        ));
+    }
+    out
+}
@@ -0,0 +1,353 @@
+// src-tauri/src/conversations.rs
+//
+// 全セッションを横断した会話検索 (search_conversations)
+// - memory.db の message_index (FTS5 trigram) + Axis メモリ (memory::search_top_k) の両方を引いて混ぜる
+//   それぞれのスコアを最大値で 0..1 に揃えて足す。両方に出た同じやり取りは 1 件にまとめて加点
+// - FTS 側のプロバイダ / task_type は、同じセッションでそのメッセージより前の最後の InteractionLog から
+// - 絞り込み: 期間 (from_ms / to_ms) / provider (gpt / gemini ...) / task_type / session_id
+//   tags（全部付いているもの）/ sticky（L / M / S のどれかが一致）。やり取りのメモリとセッションの分を合わせて見る
+// - 返すのはスニペット（一致箇所を [ ] で囲む）と DeepLink（kind "session"）
+
+use crate::db::AxisDatabase;
+use crate::memory::{self, Stickies};
+use crate::notify::DeepLink;
+use crate::storage::{self, InteractionLog};
+use crate::tagging;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+const DEFAULT_LIMIT: usize = 20;
+// 混ぜる前にそれぞれから取る件数（絞り込みで落ちる分を見込んで多め）
+const CANDIDATES: usize = 100;
+const SNIPPET_CHARS: usize = 160;
+// FTS とメモリの両方に出たやり取りの加点
+const BOTH_BONUS: f32 = 0.5;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SearchFilters {
+    pub from_ms: Option<i64>,
+    pub to_ms: Option<i64>,
+    pub provider: Option<String>,
+    pub task_type: Option<String>,
+    pub session_id: Option<String>,
+    pub tags: Vec<String>,
+    pub sticky: Option<String>,
+    pub limit: Option<usize>,
+}
+
+// db.rs の search_messages の 1 行
+#[derive(Debug, Clone)]
+pub struct MessageHit {
+    pub session_id: String,
+    pub session_title: Option<String>,
+    pub role: String,
+    pub created_at: i64,
+    pub snippet: String,
+    pub bm25: f64, // 小さいほど良い
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ConversationHit {
+    pub session_id: String,
+    pub session_title: Option<String>,
+    pub log_id: Option<String>,
+    pub role: String, // user / assistant / memory
+    pub snippet: String,
+    pub timestamp: i64,
+    pub provider: Option<String>,
+    pub task_type: Option<String>,
+    pub score: f32,
+    pub sources: Vec<String>, // "messages" / "memory"
+    pub tags: Vec<String>,
+    pub stickies: Option<Stickies>,
+    pub link: DeepLink,
+}
+
+// trigram は 3 文字未満の語では引けないので、それ以上の語だけを AND で
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|t| t.replace(['"', '*', ':'], ""))
+        .filter(|t| t.chars().count() >= 3)
+        .map(|t| format!("\"{}\"", t))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+// 大文字小文字を無視して term を探す。(元の text のバイト位置, 一致した長さ)
+// 小文字にすると長さが変わる文字があるので、小文字にした方の位置は使わない
+fn find_ignore_case(text: &str, term: &str) -> Option<(usize, usize)> {
+    let needle: Vec<char> = term.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return None;
+    }
+    text.char_indices().find_map(|(start, _)| {
+        let mut want = needle.iter();
+        let mut end = start;
+        for c in text[start..].chars() {
+            if !c.to_lowercase().all(|l| want.next() == Some(&l)) {
+                return None;
+            }
+            end += c.len_utf8();
+            if want.len() == 0 {
+                return Some((start, end - start));
+            }
+        }
+        None
+    })
+}
+
+// 一致した所の前後を切り出して [ ] で囲む
+fn snippet_around(text: &str, query: &str) -> String {
+    let hit = query
+        .split_whitespace()
+        .filter_map(|t| find_ignore_case(text, t))
+        .min_by_key(|(i, _)| *i);
+    let Some((start, len)) = hit else {
+        return text.chars().take(SNIPPET_CHARS).collect();
+    };
+    let before: String = text[..start]
+        .chars()
+        .rev()
+        .take(SNIPPET_CHARS / 3)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let after: String = text[start + len..]
+        .chars()
+        .take(SNIPPET_CHARS / 2)
+        .collect();
+    format!(
+        "{}{}[{}]{}{}",
+        if before.len() < start { "…" } else { "" },
+        before,
+        &text[start..start + len],
+        after,
+        if start + len + after.len() < text.len() {
+            "…"
+        } else {
+            ""
+        }
+    )
+}
+
+// セッション毎に時刻順の InteractionLog
+fn logs_by_session(app: &AppHandle) -> HashMap<String, Vec<InteractionLog>> {
+    let mut by_session: HashMap<String, Vec<InteractionLog>> = HashMap::new();
+    for log in storage::get_all_logs(app).unwrap_or_default() {
+        by_session
+            .entry(log.session_id.clone())
+            .or_default()
+            .push(log);
+    }
+    for logs in by_session.values_mut() {
+        logs.sort_by_key(|l| l.timestamp);
+    }
+    by_session
+}
+
+fn matches(filters: &SearchFilters, hit: &ConversationHit) -> bool {
+    let eq = |want: &Option<String>, got: &Option<String>| match want
+        .as_deref()
+        .filter(|w| !w.is_empty())
+    {
+        Some(w) => got.as_deref().is_some_and(|g| g.eq_ignore_ascii_case(w)),
+        None => true,
+    };
+    filters.from_ms.is_none_or(|from| hit.timestamp >= from)
+        && filters.to_ms.is_none_or(|to| hit.timestamp <= to)
+        && filters
+            .session_id
+            .as_deref()
+            .is_none_or(|s| s.is_empty() || hit.session_id == s)
+        && eq(&filters.provider, &hit.provider)
+        && eq(&filters.task_type, &hit.task_type)
+        && tagging::normalize_tags(filters.tags.clone())
+            .iter()
+            .all(|t| hit.tags.contains(t))
+        && filters
+            .sticky
+            .as_deref()
+            .map(str::trim)
+            .is_none_or(|s| s.is_empty() || tagging::sticky_matches(hit.stickies.as_ref(), s))
+}
+
+// メモリ側の分にセッションのタグ / 分類を足す（分類はメモリの方を優先）
+fn add_session_labels(
+    hits: &mut [ConversationHit],
+    sessions: &HashMap<String, tagging::SessionLabels>,
+) {
+    for hit in hits.iter_mut() {
+        let Some(labels) = sessions.get(&hit.session_id) else {
+            continue;
+        };
+        for tag in &labels.tags {
+            if !hit.tags.contains(tag) {
+                hit.tags.push(tag.clone());
+            }
+        }
+        if hit.stickies.is_none() {
+            hit.stickies = labels.stickies.clone();
+        }
+    }
+}
+
+fn normalize(hits: &mut [ConversationHit]) {
+    let max = hits.iter().map(|h| h.score).fold(0.0f32, f32::max);
+    if max > 0.0 {
+        for h in hits.iter_mut() {
+            h.score /= max;
+        }
+    }
+}
+
+fn message_hits(
+    app: &AppHandle,
+    query: &str,
+    filters: &SearchFilters,
+    logs: &HashMap<String, Vec<InteractionLog>>,
+) -> Result<Vec<ConversationHit>, String> {
+    let labels = tagging::log_labels(app)?;
+    let Some(fts) = fts_query(query) else {
+        return Ok(Vec::new());
+    };
+    let rows = AxisDatabase::open(app)?
+        .search_messages(
+            &fts,
+            filters.session_id.as_deref().filter(|s| !s.is_empty()),
+            filters.from_ms,
+            filters.to_ms,
+            CANDIDATES,
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            // user / assistant は run_axis の最後に保存されるので、それより前の最後のログ
+            let log = logs
+                .get(&row.session_id)
+                .and_then(|l| l.iter().rev().find(|l| l.timestamp <= row.created_at));
+            let meta = log.and_then(|l| l.meta.as_ref());
+            let (tags, stickies) = log
+                .and_then(|l| labels.get(&l.id).cloned())
+                .unwrap_or_default();
+            ConversationHit {
+                link: DeepLink::session(&row.session_id),
+                session_id: row.session_id,
+                session_title: row.session_title,
+                log_id: log.map(|l| l.id.clone()),
+                role: row.role,
+                snippet: row.snippet,
+                timestamp: row.created_at,
+                provider: meta.map(|m| m.target.clone()),
+                task_type: meta.map(|m| m.task_type.clone()).filter(|t| !t.is_empty()),
+                score: -row.bm25 as f32,
+                sources: vec!["messages".to_string()],
+                tags,
+                stickies,
+            }
+        })
+        .collect())
+}
+
+fn memory_hits(app: &AppHandle, query: &str) -> Result<Vec<ConversationHit>, String> {
+    let hits = memory::search_top_k(app, query, CANDIDATES)?;
+    // meta は 1 件ずつ開かずにまとめて
+    let ids: Vec<String> = hits.iter().map(|h| h.id.clone()).collect();
+    let mut metas = memory::load_metas(app, &ids)?;
+    Ok(hits
+        .into_iter()
+        .map(|hit| {
+            let meta = metas.remove(&hit.id);
+            let log_id = meta.as_ref().and_then(|m| {
+                m.references
+                    .iter()
+                    .find_map(|r| r.strip_prefix("log:").map(str::to_string))
+            });
+            let text = format!("{}\n{}", hit.entry.input.text, hit.entry.output.text);
+            ConversationHit {
+                link: DeepLink::session(&hit.entry.session_id),
+                session_id: hit.entry.session_id.clone(),
+                session_title: None,
+                log_id,
+                role: "memory".to_string(),
+                snippet: snippet_around(&text, query),
+                timestamp: hit.entry.timestamp_ms,
+                provider: meta.as_ref().and_then(|m| m.provider.clone()),
+                task_type: meta.as_ref().and_then(|m| m.task_type.clone()),
+                score: hit.score,
+                sources: vec!["memory".to_string()],
+                tags: meta.as_ref().map(|m| m.tags.clone()).unwrap_or_default(),
+                stickies: meta.and_then(|m| m.stickies),
+            }
+        })
+        .collect())
+}
+
+pub fn search(
+    app: &AppHandle,
+    query: &str,
+    filters: &SearchFilters,
+) -> Result<Vec<ConversationHit>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let logs = logs_by_session(app);
+    let mut from_messages = message_hits(app, query, filters, &logs)?;
+    let mut from_memory = memory_hits(app, query)?;
+    let sessions = tagging::session_labels_map(app)?;
+    add_session_labels(&mut from_messages, &sessions);
+    add_session_labels(&mut from_memory, &sessions);
+    from_messages.retain(|h| matches(filters, h));
+    from_memory.retain(|h| matches(filters, h));
+    normalize(&mut from_messages);
+    normalize(&mut from_memory);
+
+    // 同じやり取り (log_id) は 1 件に。FTS 側はユーザー / 応答の両方が出るので良い方を残す
+    let mut merged: Vec<ConversationHit> = Vec::new();
+    for hit in from_messages.into_iter().chain(from_memory) {
+        let same = hit
+            .log_id
+            .as_ref()
+            .and_then(|id| merged.iter_mut().find(|m| m.log_id.as_ref() == Some(id)));
+        match same {
+            Some(existing) => {
+                let both = !existing.sources.iter().any(|s| hit.sources.contains(s));
+                if hit.score > existing.score {
+                    let sources = existing.sources.clone();
+                    *existing = ConversationHit {
+                        session_title: hit.session_title.clone().or(existing.session_title.take()),
+                        ..hit
+                    };
+                    for s in sources {
+                        if !existing.sources.contains(&s) {
+                            existing.sources.push(s);
+                        }
+                    }
+                } else {
+                    for s in hit.sources {
+                        if !existing.sources.contains(&s) {
+                            existing.sources.push(s);
+                        }
+                    }
+                }
+                if both {
+                    existing.score += BOTH_BONUS;
+                }
+            }
+            None => merged.push(hit),
+        }
+    }
+    merged.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.timestamp.cmp(&a.timestamp))
+    });
+    merged.truncate(filters.limit.unwrap_or(DEFAULT_LIMIT));
+    Ok(merged)
+}
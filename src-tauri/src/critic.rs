@@ -0,0 +1,154 @@
+// src-tauri/src/critic.rs
+//
+// 検証フェーズ（コード / 数学の回答を別モデルが見直す）
+// - 有効化: settings.critic_enabled、対象は settings.critic_task_types（既定 code_edit / math_solve）
+// - 見直し役: settings.critic_reviewer（回答したモデルと同じなら別のモデルに替える）
+// - 間違いがあれば修正版に差し替え、指摘内容は ResponseMeta.critic に残す
+// - コマンド列（EXEC: など）の回答は対象外
+
+use crate::ai;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use std::time::Instant;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CriticReport {
+    pub reviewer: String,
+    pub model: String,
+    pub verdict: String, // "ok" / "fix" / "error"
+    #[serde(default)]
+    pub critique: String,
+    #[serde(default)]
+    pub corrected: bool, // 回答を差し替えた
+    #[serde(default)]
+    pub latency_ms: i64,
+}
+
+#[derive(Deserialize)]
+struct Review {
+    verdict: String,
+    #[serde(default)]
+    critique: String,
+    #[serde(default)]
+    corrected_answer: String,
+}
+
+// ask_axis の Phase 3 がコマンドとして扱う印
+const COMMAND_MARKERS: &[&str] = &[
+    "EXEC:",
+    "TYPE:",
+    "SEARCH:",
+    "FETCH:",
+    "WEATHER",
+    "NEWS",
+    "CALENDAR",
+    "CHECK_EMAIL",
+    "MEDIA:",
+    "HA:",
+    "RUN:",
+    "BACKGROUND:",
+    "MACRO:",
+    "PLAN:",
+    "CLICK:",
+    "GIT:",
+    "COMMIT:",
+    "SAVE:",
+    "GENERATE_FROM_TEMPLATE:",
+    "UNDO_LAST",
+    "SCHEDULE:",
+    "KILL:",
+    "FOCUS:",
+    "MINIMIZE:",
+    "OPEN_FILE:",
+    "REVEAL_IN_EXPLORER:",
+];
+
+pub fn applies(cfg: &Settings, task_type: &str, answer: &str) -> bool {
+    cfg.critic_enabled
+        && cfg
+            .critic_task_types
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(task_type.trim()))
+        && !answer.starts_with("Error:")
+        && !COMMAND_MARKERS.iter().any(|m| answer.contains(m))
+}
+
+fn reviewer_for(cfg: &Settings, worker: &str) -> String {
+    let preferred = cfg.critic_reviewer.trim().to_lowercase();
+    if preferred != worker {
+        return preferred;
+    }
+    // 自分の回答を自分で見直しても意味が薄い
+    if worker == "gpt" { "grok" } else { "gpt" }.to_string()
+}
+
+// 見直して、(使う回答, レポート) を返す。見直し自体が失敗したら元の回答のまま
+pub async fn review(
+    cfg: &Settings,
+    worker: &str,
+    task_type: &str,
+    request: &str,
+    answer: &str,
+) -> (String, CriticReport) {
+    let reviewer = reviewer_for(cfg, worker);
+    let model = cfg.models.for_alias(&reviewer);
+    let started = Instant::now();
+    info!(
+        "🧐 [Critic] {} ({}) reviewing {} answer...",
+        reviewer, model, task_type
+    );
+
+    let sys = r#"You are a strict reviewer of code and math answers.
+    Check the answer for bugs, wrong calculations, wrong logic, or missing edge cases.
+    - If it is correct, return verdict "ok" and leave corrected_answer empty.
+    - If it has errors, return verdict "fix", explain briefly in critique (Japanese),
+      and give the FULL corrected answer in corrected_answer (same language and format as the original).
+    Return STRICT JSON only:
+    {"verdict": "ok|fix", "critique": "<short>", "corrected_answer": "<full answer or empty>"}"#;
+    let prompt = format!(
+        "[Task type]\n{}\n\n[User Request]\n{}\n\n[Answer to review]\n{}",
+        task_type, request, answer
+    );
+
+    let result = ai::call_alias(&reviewer, &model, sys, &prompt)
+        .await
+        .and_then(|raw| {
+            let start = raw.find('{').ok_or("reviewer returned no JSON")?;
+            let end = raw.rfind('}').ok_or("reviewer returned no JSON")?;
+            serde_json::from_str::<Review>(&raw[start..=end])
+                .map_err(|e| format!("reviewer JSON: {}", e))
+        });
+
+    let mut report = CriticReport {
+        reviewer,
+        model,
+        latency_ms: started.elapsed().as_millis() as i64,
+        ..Default::default()
+    };
+
+    match result {
+        Ok(r) => {
+            let fix =
+                r.verdict.eq_ignore_ascii_case("fix") && !r.corrected_answer.trim().is_empty();
+            report.verdict = if fix { "fix" } else { "ok" }.to_string();
+            report.critique = r.critique;
+            report.corrected = fix;
+            info!(
+                text = %report.critique,
+                "🧐 [Critic] verdict: {}",
+                report.verdict
+            );
+            if fix {
+                return (r.corrected_answer.trim().to_string(), report);
+            }
+            (answer.to_string(), report)
+        }
+        Err(e) => {
+            warn!("⚠️ [Critic] review failed: {}", e);
+            report.verdict = "error".to_string();
+            report.critique = e;
+            (answer.to_string(), report)
+        }
+    }
+}
@@ -0,0 +1,122 @@
+// src-tauri/src/crypto.rs
+//
+// 保存データの暗号化（at rest）
+// - 有効化: settings.encrypt_at_rest（env AXIS_ENCRYPT_AT_REST=1 でも可）
+// - 鍵: OS の資格情報ストア（Windows 資格情報マネージャー / macOS キーチェーン）に置いた
+//       ランダムな秘密から HKDF-SHA256 で導出。初回に自動生成する
+// - 形式: "AXENC1" + nonce(12) + AES-256-GCM 暗号文
+// - 読み込みは形式を見て判断するので、平文の既存ファイルもそのまま読める（次の保存で暗号化される）
+// - SQLite は `sqlcipher` feature でビルドした時だけ PRAGMA key で暗号化（db.rs）
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose, Engine as _};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use tracing::info;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const MAGIC: &[u8] = b"AXENC1";
+const NONCE_LEN: usize = 12;
+
+const KEYRING_SERVICE: &str = "axis-os";
+const KEYRING_SECRET_USER: &str = "data-encryption-secret";
+
+// 用途毎に鍵を分ける
+const FILE_KEY_INFO: &[u8] = b"axis-os/at-rest/files/v1";
+const DB_KEY_INFO: &[u8] = b"axis-os/at-rest/sqlite/v1";
+
+static SECRET: OnceLock<Result<Vec<u8>, String>> = OnceLock::new();
+
+pub fn enabled() -> bool {
+    crate::settings::current().encrypt_at_rest
+}
+
+// 資格情報ストアの秘密（無ければ作って保存）
+fn load_or_create_secret() -> Result<Vec<u8>, String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_SECRET_USER)
+        .map_err(|e| format!("keyring unavailable: {}", e))?;
+
+    match entry.get_password() {
+        Ok(b64) => general_purpose::STANDARD
+            .decode(b64.trim())
+            .map_err(|e| format!("stored secret is corrupt: {}", e)),
+        Err(keyring::Error::NoEntry) => {
+            let secret = Aes256Gcm::generate_key(OsRng).to_vec();
+            entry
+                .set_password(&general_purpose::STANDARD.encode(&secret))
+                .map_err(|e| format!("failed to store secret in keyring: {}", e))?;
+            info!("🔐 [Crypto] generated a new data encryption secret");
+            Ok(secret)
+        }
+        Err(e) => Err(format!("keyring read failed: {}", e)),
+    }
+}
+
+fn derive_key(info: &[u8]) -> Result<[u8; 32], String> {
+    let secret = SECRET.get_or_init(load_or_create_secret).clone()?;
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, &secret)
+        .expand(info, &mut key)
+        .map_err(|e| e.to_string())?;
+    Ok(key)
+}
+
+// SQLCipher 用の生鍵（"x'…'" 形式の 16 進）
+#[cfg_attr(not(feature = "sqlcipher"), allow(dead_code))]
+pub fn db_key_hex() -> Result<String, String> {
+    let key = derive_key(DB_KEY_INFO)?;
+    Ok(key.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+// 暗号化（無効なら素通し）
+pub fn seal(plain: &[u8]) -> Result<Vec<u8>, String> {
+    if !enabled() {
+        return Ok(plain.to_vec());
+    }
+    let key = derive_key(FILE_KEY_INFO)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let body = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|_| "encryption failed".to_string())?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + body.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+// 復号（平文ならそのまま返す。無効化後も暗号化済みファイルは読める）
+pub fn open(data: &[u8]) -> Result<Vec<u8>, String> {
+    if !is_encrypted(data) {
+        return Ok(data.to_vec());
+    }
+    if data.len() < MAGIC.len() + NONCE_LEN {
+        return Err("encrypted file is truncated".to_string());
+    }
+    let (nonce, body) = data[MAGIC.len()..].split_at(NONCE_LEN);
+    let key = derive_key(FILE_KEY_INFO)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), body)
+        .map_err(|_| "decryption failed (wrong key or corrupted file)".to_string())
+}
+
+// fs::read_to_string / fs::write の置き換え
+pub fn read_to_string<P: AsRef<Path>>(path: P) -> Result<String, String> {
+    let raw = fs::read(path).map_err(|e| e.to_string())?;
+    String::from_utf8(open(&raw)?).map_err(|e| e.to_string())
+}
+
+pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<(), String> {
+    let sealed = seal(contents.as_ref())?;
+    fs::write(path, sealed).map_err(|e| e.to_string())
+}
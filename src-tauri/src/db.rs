@@ -1,7 +1,27 @@
 // src-tauri/src/db.rs
+use crate::activity::ActivitySpan;
+use crate::audit::AuditEntry;
+use crate::conversations::MessageHit;
+use crate::deferred::DeferredRequest;
+use crate::feeds::{Feed, StoredFeedItem};
+use crate::graph::{Entity, Fact};
+use crate::macros::Macro;
+use crate::memory::{MemoryEntry, MemoryMeta};
+use crate::outcomes::ModelOutcome;
+use crate::plans::Plan;
+use crate::purge::PurgeRows;
+use crate::scheduler::ScheduledTask;
+use crate::sharing::SharedPayload;
+use crate::tagging::SessionLabels;
+use crate::tasks::TaskInfo;
+use crate::trace::Trace;
+use crate::undo::JournalEntry;
+use axis_core::apps::AppEntry;
 use chrono::Utc;
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, Result, Transaction};
 use std::{fs, path::Path};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
 
 pub struct AxisDatabase {
     conn: Connection,
@@ -13,7 +33,7 @@ impl AxisDatabase {
             let _ = fs::create_dir_all(parent);
         }
 
-        let conn = Connection::open(path)?;
+        let conn = Self::open_connection(path.as_ref())?;
         conn.execute_batch(
             r#"
             PRAGMA foreign_keys = ON;
@@ -76,12 +96,347 @@ impl AxisDatabase {
                 category_s TEXT,
                 FOREIGN KEY(doc_id) REFERENCES documents(id) ON DELETE CASCADE
             );
+
+            -- 8) スケジュール（リマインダー / 定期プロンプト）
+            CREATE TABLE IF NOT EXISTS scheduled_tasks (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                action TEXT NOT NULL,        -- notify / prompt
+                payload TEXT NOT NULL,
+                session_id TEXT,
+                repeat_spec TEXT NOT NULL DEFAULT '',  -- 空文字 = 単発
+                next_run_at INTEGER NOT NULL,
+                last_run_at INTEGER,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_scheduled_tasks_next
+                ON scheduled_tasks(enabled, next_run_at);
+
+            -- 9) アクティビティ（フォーカス中ウィンドウの滞在区間）
+            CREATE TABLE IF NOT EXISTS activity (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                title TEXT NOT NULL,
+                app TEXT NOT NULL DEFAULT '',
+                started_at INTEGER NOT NULL,
+                ended_at INTEGER NOT NULL     -- 監視ループが tick ごとに更新
+            );
+            CREATE INDEX IF NOT EXISTS idx_activity_started
+                ON activity(started_at);
+
+            -- 10) Axis メモリ（MEMORY_BACKEND=sqlite の時の保存先。json 版の entry/meta を丸ごと持つ）
+            --     memory_fts は search_text の全文索引（SQL から直接 recall する用）
+            CREATE TABLE IF NOT EXISTS memory_entries (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                timestamp_ms INTEGER NOT NULL,
+                updated_at_ms INTEGER NOT NULL,
+                entry_json TEXT NOT NULL,
+                meta_json TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_memory_entries_updated
+                ON memory_entries(updated_at_ms);
+            CREATE VIRTUAL TABLE IF NOT EXISTS memory_fts
+            USING fts5(search_text, id UNINDEXED, tokenize='trigram');
+
+            -- 11) モデル毎の実績（ルーティングスコアの補正用 / outcomes.rs）
+            CREATE TABLE IF NOT EXISTS model_outcomes (
+                log_id TEXT PRIMARY KEY,     -- storage の InteractionLog.id
+                session_id TEXT NOT NULL,
+                model TEXT NOT NULL,
+                task_type TEXT NOT NULL DEFAULT '',
+                success INTEGER NOT NULL,
+                latency_ms INTEGER NOT NULL,
+                reasked INTEGER NOT NULL DEFAULT 0,
+                rating INTEGER,              -- +1 / -1 / NULL
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_model_outcomes_created
+                ON model_outcomes(created_at);
+
+            -- 12) セッション毎のプロバイダ固定（Commander を通さない / set_session_provider）
+            CREATE TABLE IF NOT EXISTS session_providers (
+                session_id TEXT PRIMARY KEY,
+                provider TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
+            -- 13) セッション毎のペルソナ（set_persona）
+            CREATE TABLE IF NOT EXISTS session_personas (
+                session_id TEXT PRIMARY KEY,
+                persona TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
+            -- 14) RSS / Atom の購読と取り込んだ記事（feeds.rs / 朝のブリーフィング）
+            CREATE TABLE IF NOT EXISTS feeds (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL UNIQUE,
+                title TEXT NOT NULL,
+                topic TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                last_polled_at INTEGER,
+                last_error TEXT
+            );
+            CREATE TABLE IF NOT EXISTS feed_items (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                feed_id TEXT NOT NULL,
+                guid TEXT NOT NULL,
+                title TEXT NOT NULL,
+                link TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                published_at INTEGER,
+                fetched_at INTEGER NOT NULL,
+                read INTEGER NOT NULL DEFAULT 0,
+                UNIQUE(feed_id, guid)
+            );
+            CREATE INDEX IF NOT EXISTS idx_feed_items_unread
+                ON feed_items(read, fetched_at);
+
+            -- 15) バックグラウンドタスク（tasks.rs）
+            CREATE TABLE IF NOT EXISTS tasks (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                label TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                status TEXT NOT NULL,
+                progress REAL NOT NULL DEFAULT 0,
+                message TEXT NOT NULL DEFAULT '',
+                result TEXT,
+                error TEXT,
+                created_at INTEGER NOT NULL,
+                started_at INTEGER,
+                finished_at INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS idx_tasks_created ON tasks(created_at);
+
+            -- 16) マクロ（macros.rs。steps / triggers は JSON 配列）
+            CREATE TABLE IF NOT EXISTS macros (
+                name TEXT PRIMARY KEY,
+                description TEXT NOT NULL DEFAULT '',
+                triggers TEXT NOT NULL DEFAULT '[]',
+                steps TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
+            -- 17) プラン（plans.rs。steps は PlanStep の JSON 配列）
+            CREATE TABLE IF NOT EXISTS plans (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                goal TEXT NOT NULL,
+                status TEXT NOT NULL,
+                steps TEXT NOT NULL,
+                message TEXT NOT NULL DEFAULT '',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_plans_created ON plans(created_at);
+
+            -- 18) エージェントが書いたファイルの取り消し用ジャーナル（undo.rs）
+            --     backup_id: 書く前の中身（objects/）。NULL なら新規作成だった
+            CREATE TABLE IF NOT EXISTS file_journal (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                action TEXT NOT NULL,
+                path TEXT NOT NULL,
+                backup_id TEXT,
+                written_hash TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                undone_at INTEGER
+            );
+
+            -- 19) ask_axis 1 回分の処理の記録（trace.rs。trace は Trace の JSON）
+            --     input は依頼文（前後の空白を除いたもの。replay_mode で引く。索引は migrate_traces）
+            CREATE TABLE IF NOT EXISTS traces (
+                log_id TEXT PRIMARY KEY,     -- storage の InteractionLog.id
+                session_id TEXT NOT NULL,
+                trace TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                input TEXT NOT NULL DEFAULT ''
+            );
+            CREATE INDEX IF NOT EXISTS idx_traces_created ON traces(created_at);
+
+            -- 20) LLM の利用額の見積もり（budget.rs。日毎 / モデル毎に足していく）
+            CREATE TABLE IF NOT EXISTS spend (
+                day TEXT NOT NULL,           -- YYYY-MM-DD（ローカル時刻）
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                cost_usd REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY(day, provider, model)
+            );
+
+            -- 21) 繋がったら聞く依頼（deferred.rs）
+            CREATE TABLE IF NOT EXISTS deferred_requests (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                input TEXT NOT NULL,
+                status TEXT NOT NULL,
+                answer TEXT,
+                error TEXT,
+                created_at INTEGER NOT NULL,
+                finished_at INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS idx_deferred_status
+                ON deferred_requests(status, created_at);
+
+            -- 22) セッションのタグ / 分類（tagging.rs。メモリの分は MemoryMeta に）
+            CREATE TABLE IF NOT EXISTS session_labels (
+                session_id TEXT PRIMARY KEY,
+                tags TEXT NOT NULL,
+                stickies TEXT,
+                updated_at INTEGER NOT NULL
+            );
+
+            -- 23) 会話から抜き出した事実のグラフ（graph.rs）
+            --     エンティティは name_key（名前の小文字）で同一視
+            CREATE TABLE IF NOT EXISTS kg_entities (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                name_key TEXT NOT NULL UNIQUE,
+                kind TEXT NOT NULL DEFAULT '',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS kg_relations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                subject_id INTEGER NOT NULL,
+                predicate TEXT NOT NULL,
+                object_id INTEGER NOT NULL,
+                mentions INTEGER NOT NULL DEFAULT 1,
+                source_log TEXT,              -- 最後に出てきた InteractionLog.id
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                UNIQUE(subject_id, predicate, object_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_kg_relations_object
+                ON kg_relations(object_id);
+
+            -- 24) 外部プロバイダに送った中身（sharing.rs。payload は SharedPayload の JSON）
+            CREATE TABLE IF NOT EXISTS data_sharing (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                log_id TEXT,                 -- 依頼 1 回分 (InteractionLog.id)。裏の呼び出しは NULL
+                session_id TEXT,
+                payload TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_data_sharing_log ON data_sharing(log_id);
+            CREATE INDEX IF NOT EXISTS idx_data_sharing_created ON data_sharing(created_at);
+
+            -- 25) 実行したアクションの監査記録（audit.rs。hash は prev_hash を含めた鎖）
+            CREATE TABLE IF NOT EXISTS action_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                action TEXT NOT NULL,
+                argument TEXT NOT NULL,
+                result TEXT NOT NULL,
+                initiator TEXT NOT NULL,     -- agent / user
+                session_id TEXT,
+                log_id TEXT,
+                omitted INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                prev_hash TEXT NOT NULL,
+                hash TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_action_audit_created ON action_audit(created_at);
+
+            -- 26) インストール済みアプリの一覧（app_catalog.rs。Get-StartApps を定期的に取り込む。起動の記録は EXEC から）
+            CREATE TABLE IF NOT EXISTS app_catalog (
+                app_id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                seen_at INTEGER NOT NULL,    -- 最後の取り込みで見えた時刻（見えなくなったものは消す）
+                launches INTEGER NOT NULL DEFAULT 0,
+                last_launched INTEGER
+            );
             "#,
         )?;
+        Self::migrate_traces(&conn)?;
 
         Ok(Self { conn })
     }
 
+    // input 列より前に作った traces には列を足し、JSON の依頼文から埋める
+    fn migrate_traces(conn: &Connection) -> Result<()> {
+        let has_input: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM pragma_table_info('traces') WHERE name = 'input')",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_input {
+            conn.execute_batch(
+                "ALTER TABLE traces ADD COLUMN input TEXT NOT NULL DEFAULT '';
+                 UPDATE traces SET input = trim(
+                     coalesce(json_extract(trace, '$.input'), ''),
+                     ' ' || char(9) || char(10) || char(13)
+                 );",
+            )?;
+        }
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_traces_input ON traces(input, created_at);",
+        )
+    }
+
+    // 暗号化あり: AXIS_ENCRYPT_AT_REST=1 かつ sqlcipher feature でビルドした時だけ
+    #[cfg(feature = "sqlcipher")]
+    fn open_connection(path: &Path) -> Result<Connection> {
+        let conn = Connection::open(path)?;
+        if !crate::crypto::enabled() {
+            return Ok(conn);
+        }
+        let key = crate::crypto::db_key_hex()
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+        let pragma = format!("PRAGMA key = \"x'{}'\";", key);
+        conn.execute_batch(&pragma)?;
+        if conn
+            .query_row("SELECT count(*) FROM sqlite_master", [], |r| {
+                r.get::<_, i64>(0)
+            })
+            .is_ok()
+        {
+            return Ok(conn);
+        }
+
+        // 平文の既存 db: 暗号化したコピーを作って差し替える
+        drop(conn);
+        tracing::info!("🔐 [DB] encrypting existing database {:?}", path);
+        let tmp = path.with_extension("db.enc.tmp");
+        let _ = fs::remove_file(&tmp);
+        let plain = Connection::open(path)?;
+        plain.execute_batch(&format!(
+            "ATTACH DATABASE '{}' AS encrypted KEY \"x'{}'\";",
+            tmp.to_string_lossy().replace('\'', "''"),
+            key
+        ))?;
+        plain.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+        plain.execute_batch("DETACH DATABASE encrypted;")?;
+        drop(plain);
+        fs::rename(&tmp, path).map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+
+        let conn = Connection::open(path)?;
+        conn.execute_batch(&pragma)?;
+        Ok(conn)
+    }
+
+    #[cfg(not(feature = "sqlcipher"))]
+    fn open_connection(path: &Path) -> Result<Connection> {
+        static WARN: std::sync::Once = std::sync::Once::new();
+        if crate::crypto::enabled() {
+            WARN.call_once(|| {
+                warn!("⚠️ [DB] AXIS_ENCRYPT_AT_REST is on but this build has no SQLCipher (feature \"sqlcipher\"): memory.db stays unencrypted");
+            });
+        }
+        Connection::open(path)
+    }
+
+    // app_data_dir/memory.db を開く（lib.rs 以外のモジュール用）
+    pub fn open(app: &AppHandle) -> std::result::Result<Self, String> {
+        let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+        Self::init(app_dir.join("memory.db")).map_err(|e| e.to_string())
+    }
+
     fn now_ms() -> i64 {
         Utc::now().timestamp_millis()
     }
@@ -123,8 +478,120 @@ impl AxisDatabase {
         Ok(())
     }
 
+    // journal.rs 用: 期間内にユーザーが話したセッション (session_id, 発言数, 最初の発言)。古い順
+    pub fn sessions_between(&self, from: i64, to: i64) -> Result<Vec<(String, i64, String)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT m.session_id, COUNT(*),
+                   (SELECT f.content FROM messages f
+                     WHERE f.session_id = m.session_id AND f.role = 'user'
+                       AND f.created_at >= ?1 AND f.created_at < ?2
+                     ORDER BY f.created_at LIMIT 1)
+            FROM messages m
+            WHERE m.role = 'user' AND m.created_at >= ?1 AND m.created_at < ?2
+            GROUP BY m.session_id
+            ORDER BY MIN(m.created_at)
+            "#,
+        )?;
+        let rows = stmt.query_map(params![from, to], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+        rows.collect()
+    }
+
+    // search_conversations 用: FTS で引いて messages / sessions と突き合わせる
+    pub fn search_messages(
+        &self,
+        fts_query: &str,
+        session_id: Option<&str>,
+        from_ms: Option<i64>,
+        to_ms: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<MessageHit>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT i.session_id, s.title, m.role, m.created_at,
+                    snippet(message_index, 0, '[', ']', '…', 16), bm25(message_index)
+             FROM message_index i
+             JOIN messages m ON m.session_id = i.session_id AND m.content = i.content
+             LEFT JOIN sessions s ON s.session_id = i.session_id
+             WHERE message_index MATCH ?1
+               AND (?2 IS NULL OR i.session_id = ?2)
+               AND (?3 IS NULL OR m.created_at >= ?3)
+               AND (?4 IS NULL OR m.created_at <= ?4)
+             ORDER BY bm25(message_index)
+             LIMIT ?5",
+        )?;
+        let rows = stmt.query_map(
+            params![fts_query, session_id, from_ms, to_ms, limit as i64],
+            |row| {
+                Ok(MessageHit {
+                    session_id: row.get(0)?,
+                    session_title: row.get(1)?,
+                    role: row.get(2)?,
+                    created_at: row.get(3)?,
+                    snippet: row.get(4)?,
+                    bm25: row.get(5)?,
+                })
+            },
+        )?;
+        rows.collect()
+    }
+
+    // 他のアシスタントから取り込んだ会話 (importer.rs)。既にあるセッションなら false
+    pub fn import_conversation(
+        &self,
+        session_id: &str,
+        title: &str,
+        messages: &[(&str, &str, i64)], // (role, content, created_at)
+    ) -> Result<bool> {
+        let tx = self.conn.unchecked_transaction()?;
+        let added = Self::insert_conversation(&tx, session_id, title, messages)?;
+        tx.commit()?;
+        Ok(added)
+    }
+
+    // importer.rs: 会話とメモリの行を 1 つのトランザクションで入れる
+    // commit は呼び出し側（history.json / メモリのファイルと揃えるため）
+    pub fn begin_import(&self) -> Result<Transaction<'_>> {
+        self.conn.unchecked_transaction()
+    }
+
+    // 既にあるセッションなら false（何も書かない）
+    pub fn insert_conversation(
+        conn: &Connection,
+        session_id: &str,
+        title: &str,
+        messages: &[(&str, &str, i64)],
+    ) -> Result<bool> {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sessions WHERE session_id = ?1)",
+            [session_id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            return Ok(false);
+        }
+        let first = messages.first().map(|m| m.2).unwrap_or_else(Self::now_ms);
+        let last = messages.last().map(|m| m.2).unwrap_or(first);
+
+        conn.execute(
+            "INSERT INTO sessions(session_id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![session_id, title, first, last],
+        )?;
+        for (role, content, at) in messages {
+            conn.execute(
+                "INSERT INTO messages(session_id, role, content, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![session_id, role, content, at],
+            )?;
+            conn.execute(
+                "INSERT INTO message_index(content, session_id) VALUES (?1, ?2)",
+                params![content, session_id],
+            )?;
+        }
+        Ok(true)
+    }
+
     // lib.rs が呼んでるやつ（赤線の根）
-    #[allow(dead_code)]
     pub fn search_similar_logs(&self, query: &str) -> Result<Vec<String>> {
         // FTS5のクエリ構文で事故りやすい文字を軽く潰す（最低限）
         let cleaned: String = query
@@ -157,4 +624,1569 @@ impl AxisDatabase {
         }
         Ok(results)
     }
+
+    // ---------- スケジュール ----------
+
+    fn row_to_task(row: &rusqlite::Row) -> Result<ScheduledTask> {
+        Ok(ScheduledTask {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            action: row.get(2)?,
+            payload: row.get(3)?,
+            session_id: row.get(4)?,
+            repeat_spec: row.get(5)?,
+            next_run_at: row.get(6)?,
+            last_run_at: row.get(7)?,
+            enabled: row.get::<_, i64>(8)? != 0,
+            created_at: row.get(9)?,
+        })
+    }
+
+    pub fn insert_scheduled_task(&self, task: &ScheduledTask) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO scheduled_tasks(
+                id, title, action, payload, session_id, repeat_spec,
+                next_run_at, last_run_at, enabled, created_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+            params![
+                task.id,
+                task.title,
+                task.action,
+                task.payload,
+                task.session_id,
+                task.repeat_spec,
+                task.next_run_at,
+                task.last_run_at,
+                task.enabled as i64,
+                task.created_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn list_scheduled_tasks(&self) -> Result<Vec<ScheduledTask>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, action, payload, session_id, repeat_spec,
+                    next_run_at, last_run_at, enabled, created_at
+             FROM scheduled_tasks
+             ORDER BY enabled DESC, next_run_at ASC",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_task)?;
+        rows.collect()
+    }
+
+    // 実行時刻を過ぎた有効タスク
+    pub fn due_scheduled_tasks(&self, now_ms: i64) -> Result<Vec<ScheduledTask>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, action, payload, session_id, repeat_spec,
+                    next_run_at, last_run_at, enabled, created_at
+             FROM scheduled_tasks
+             WHERE enabled = 1 AND next_run_at <= ?1
+             ORDER BY next_run_at ASC",
+        )?;
+        let rows = stmt.query_map([now_ms], Self::row_to_task)?;
+        rows.collect()
+    }
+
+    // 実行後の更新: next_run_at が None なら単発として無効化
+    pub fn mark_scheduled_task_run(
+        &self,
+        id: &str,
+        ran_at: i64,
+        next_run_at: Option<i64>,
+    ) -> Result<()> {
+        match next_run_at {
+            Some(next) => self.conn.execute(
+                "UPDATE scheduled_tasks SET last_run_at = ?2, next_run_at = ?3 WHERE id = ?1",
+                params![id, ran_at, next],
+            )?,
+            None => self.conn.execute(
+                "UPDATE scheduled_tasks SET last_run_at = ?2, enabled = 0 WHERE id = ?1",
+                params![id, ran_at],
+            )?,
+        };
+        Ok(())
+    }
+
+    pub fn delete_scheduled_task(&self, id: &str) -> Result<bool> {
+        let n = self
+            .conn
+            .execute("DELETE FROM scheduled_tasks WHERE id = ?1", params![id])?;
+        Ok(n > 0)
+    }
+
+    // ---------- フィード ----------
+
+    fn row_to_feed(row: &rusqlite::Row) -> Result<Feed> {
+        Ok(Feed {
+            id: row.get(0)?,
+            url: row.get(1)?,
+            title: row.get(2)?,
+            topic: row.get(3)?,
+            created_at: row.get(4)?,
+            last_polled_at: row.get(5)?,
+            last_error: row.get(6)?,
+            unread: row.get(7)?,
+        })
+    }
+
+    pub fn insert_feed(&self, feed: &Feed) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO feeds(id, url, title, topic, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![feed.id, feed.url, feed.title, feed.topic, feed.created_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn list_feeds(&self) -> Result<Vec<Feed>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT f.id, f.url, f.title, f.topic, f.created_at, f.last_polled_at, f.last_error,
+                    (SELECT COUNT(*) FROM feed_items i WHERE i.feed_id = f.id AND i.read = 0)
+             FROM feeds f
+             ORDER BY f.topic, f.title",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_feed)?;
+        rows.collect()
+    }
+
+    pub fn delete_feed(&self, id: &str) -> Result<bool> {
+        self.conn
+            .execute("DELETE FROM feed_items WHERE feed_id = ?1", params![id])?;
+        let n = self
+            .conn
+            .execute("DELETE FROM feeds WHERE id = ?1", params![id])?;
+        Ok(n > 0)
+    }
+
+    pub fn mark_feed_polled(&self, id: &str, at: i64, error: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE feeds SET last_polled_at = ?2, last_error = ?3 WHERE id = ?1",
+            params![id, at, error],
+        )?;
+        Ok(())
+    }
+
+    // 新しい記事なら true（guid が既にあれば入れない）
+    pub fn insert_feed_item(&self, feed_id: &str, item: &StoredFeedItem) -> Result<bool> {
+        let n = self.conn.execute(
+            "INSERT OR IGNORE INTO feed_items(
+                 feed_id, guid, title, link, summary, published_at, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                feed_id,
+                item.guid,
+                item.title,
+                item.link,
+                item.summary,
+                item.published_at,
+                item.fetched_at
+            ],
+        )?;
+        Ok(n > 0)
+    }
+
+    pub fn unread_feed_items(&self, limit: usize) -> Result<Vec<StoredFeedItem>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT i.id, i.feed_id, f.title, f.topic, i.guid, i.title, i.link, i.summary,
+                    i.published_at, i.fetched_at
+             FROM feed_items i JOIN feeds f ON f.id = i.feed_id
+             WHERE i.read = 0
+             ORDER BY COALESCE(i.published_at, i.fetched_at) DESC
+             LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], |row| {
+            Ok(StoredFeedItem {
+                id: row.get(0)?,
+                feed_id: row.get(1)?,
+                feed_title: row.get(2)?,
+                topic: row.get(3)?,
+                guid: row.get(4)?,
+                title: row.get(5)?,
+                link: row.get(6)?,
+                summary: row.get(7)?,
+                published_at: row.get(8)?,
+                fetched_at: row.get(9)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    pub fn mark_feed_items_read(&self, ids: &[i64]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut n = 0;
+        for id in ids {
+            n += tx.execute("UPDATE feed_items SET read = 1 WHERE id = ?1", params![id])?;
+        }
+        tx.commit()?;
+        Ok(n)
+    }
+
+    // ---------- バックグラウンドタスク ----------
+
+    fn row_to_task_info(row: &rusqlite::Row) -> Result<TaskInfo> {
+        Ok(TaskInfo {
+            id: row.get(0)?,
+            kind: row.get(1)?,
+            label: row.get(2)?,
+            session_id: row.get(3)?,
+            status: row.get(4)?,
+            progress: row.get::<_, f64>(5)? as f32,
+            message: row.get(6)?,
+            result: row.get(7)?,
+            error: row.get(8)?,
+            created_at: row.get(9)?,
+            started_at: row.get(10)?,
+            finished_at: row.get(11)?,
+        })
+    }
+
+    pub fn save_task(&self, t: &TaskInfo) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO tasks(
+                 id, kind, label, session_id, status, progress, message,
+                 result, error, created_at, started_at, finished_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                t.id,
+                t.kind,
+                t.label,
+                t.session_id,
+                t.status,
+                t.progress as f64,
+                t.message,
+                t.result,
+                t.error,
+                t.created_at,
+                t.started_at,
+                t.finished_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_task(&self, id: &str) -> Result<Option<TaskInfo>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, kind, label, session_id, status, progress, message,
+                    result, error, created_at, started_at, finished_at
+             FROM tasks WHERE id = ?1",
+        )?;
+        let mut rows = stmt.query_map(params![id], Self::row_to_task_info)?;
+        rows.next().transpose()
+    }
+
+    pub fn list_tasks(&self, limit: usize) -> Result<Vec<TaskInfo>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, kind, label, session_id, status, progress, message,
+                    result, error, created_at, started_at, finished_at
+             FROM tasks ORDER BY created_at DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], Self::row_to_task_info)?;
+        rows.collect()
+    }
+
+    // 起動時: 前回の queued / running を failed に
+    pub fn fail_unfinished_tasks(&self, now_ms: i64) -> Result<usize> {
+        self.conn.execute(
+            "UPDATE tasks SET status = 'failed', error = 'interrupted (app closed)',
+                    finished_at = ?1
+             WHERE status IN ('queued', 'running')",
+            params![now_ms],
+        )
+    }
+
+    // ---------- マクロ ----------
+
+    fn row_to_macro(row: &rusqlite::Row) -> Result<Macro> {
+        let triggers: String = row.get(2)?;
+        let steps: String = row.get(3)?;
+        Ok(Macro::new(
+            row.get(0)?,
+            row.get(1)?,
+            serde_json::from_str(&triggers).unwrap_or_default(),
+            serde_json::from_str(&steps).unwrap_or_default(),
+            row.get(4)?,
+            row.get(5)?,
+        ))
+    }
+
+    pub fn save_macro(&self, m: &Macro) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO macros(name, description, triggers, steps, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(name) DO UPDATE SET
+                 description = excluded.description,
+                 triggers = excluded.triggers,
+                 steps = excluded.steps,
+                 updated_at = excluded.updated_at",
+            params![
+                m.name,
+                m.description,
+                serde_json::to_string(&m.triggers).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&m.steps).unwrap_or_else(|_| "[]".to_string()),
+                m.created_at,
+                m.updated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_macro(&self, name: &str) -> Result<Option<Macro>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, description, triggers, steps, created_at, updated_at
+             FROM macros WHERE name = ?1 COLLATE NOCASE",
+        )?;
+        let mut rows = stmt.query_map(params![name], Self::row_to_macro)?;
+        rows.next().transpose()
+    }
+
+    pub fn list_macros(&self) -> Result<Vec<Macro>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, description, triggers, steps, created_at, updated_at
+             FROM macros ORDER BY name COLLATE NOCASE",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_macro)?;
+        rows.collect()
+    }
+
+    pub fn delete_macro(&self, name: &str) -> Result<bool> {
+        let n = self.conn.execute(
+            "DELETE FROM macros WHERE name = ?1 COLLATE NOCASE",
+            params![name],
+        )?;
+        Ok(n > 0)
+    }
+
+    // ---------- プラン ----------
+
+    fn row_to_plan(row: &rusqlite::Row) -> Result<Plan> {
+        let steps: String = row.get(4)?;
+        Ok(Plan {
+            id: row.get(0)?,
+            session_id: row.get(1)?,
+            goal: row.get(2)?,
+            status: row.get(3)?,
+            steps: serde_json::from_str(&steps).unwrap_or_default(),
+            message: row.get(5)?,
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+        })
+    }
+
+    pub fn save_plan(&self, p: &Plan) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO plans(
+                 id, session_id, goal, status, steps, message, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                p.id,
+                p.session_id,
+                p.goal,
+                p.status,
+                serde_json::to_string(&p.steps).unwrap_or_else(|_| "[]".to_string()),
+                p.message,
+                p.created_at,
+                p.updated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_plan(&self, id: &str) -> Result<Option<Plan>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, goal, status, steps, message, created_at, updated_at
+             FROM plans WHERE id = ?1",
+        )?;
+        let mut rows = stmt.query_map(params![id], Self::row_to_plan)?;
+        rows.next().transpose()
+    }
+
+    pub fn list_plans(&self, limit: usize) -> Result<Vec<Plan>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, goal, status, steps, message, created_at, updated_at
+             FROM plans ORDER BY created_at DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], Self::row_to_plan)?;
+        rows.collect()
+    }
+
+    // ---------- ファイル操作のジャーナル ----------
+
+    fn row_to_journal_entry(row: &rusqlite::Row) -> Result<JournalEntry> {
+        Ok(JournalEntry {
+            id: row.get(0)?,
+            session_id: row.get(1)?,
+            action: row.get(2)?,
+            path: row.get(3)?,
+            backup_id: row.get(4)?,
+            written_hash: row.get(5)?,
+            created_at: row.get(6)?,
+            undone_at: row.get(7)?,
+        })
+    }
+
+    pub fn insert_journal_entry(&self, e: &JournalEntry) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO file_journal(session_id, action, path, backup_id, written_hash, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                e.session_id,
+                e.action,
+                e.path,
+                e.backup_id,
+                e.written_hash,
+                e.created_at
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    // まだ取り消していない最新のもの（session_id が None なら全セッションから）
+    pub fn last_journal_entry(&self, session_id: Option<&str>) -> Result<Option<JournalEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, action, path, backup_id, written_hash, created_at, undone_at
+             FROM file_journal
+             WHERE undone_at IS NULL AND (?1 IS NULL OR session_id = ?1)
+             ORDER BY id DESC LIMIT 1",
+        )?;
+        let mut rows = stmt.query_map(params![session_id], Self::row_to_journal_entry)?;
+        rows.next().transpose()
+    }
+
+    pub fn list_journal(&self, limit: usize) -> Result<Vec<JournalEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, action, path, backup_id, written_hash, created_at, undone_at
+             FROM file_journal ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], Self::row_to_journal_entry)?;
+        rows.collect()
+    }
+
+    pub fn mark_journal_undone(&self, id: i64, now_ms: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE file_journal SET undone_at = ?2 WHERE id = ?1",
+            params![id, now_ms],
+        )?;
+        Ok(())
+    }
+
+    // objects の gc で消さないもの（新しい方から limit 件分）
+    pub fn journal_backup_ids(&self, limit: usize) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT backup_id FROM (
+                 SELECT backup_id FROM file_journal ORDER BY id DESC LIMIT ?1)
+             WHERE backup_id IS NOT NULL",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    // ---------- アクティビティ ----------
+
+    pub fn insert_activity(&self, title: &str, app: &str, now_ms: i64) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO activity(title, app, started_at, ended_at) VALUES (?1, ?2, ?3, ?3)",
+            params![title, app, now_ms],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn touch_activity(&self, id: i64, now_ms: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE activity SET ended_at = ?2 WHERE id = ?1",
+            params![id, now_ms],
+        )?;
+        Ok(())
+    }
+
+    // [from, to) に掛かる区間
+    pub fn activity_between(&self, from_ms: i64, to_ms: i64) -> Result<Vec<ActivitySpan>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, app, started_at, ended_at
+             FROM activity
+             WHERE started_at < ?2 AND ended_at >= ?1
+             ORDER BY started_at ASC",
+        )?;
+        let rows = stmt.query_map(params![from_ms, to_ms], |row| {
+            let started_at: i64 = row.get(3)?;
+            let ended_at: i64 = row.get(4)?;
+            Ok(ActivitySpan {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                app: row.get(2)?,
+                started_at,
+                ended_at,
+                duration_ms: (ended_at - started_at).max(0),
+            })
+        })?;
+        rows.collect()
+    }
+
+    // ---------- セッションのプロバイダ固定 ----------
+
+    pub fn session_provider(&self, session_id: &str) -> Result<Option<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT provider FROM session_providers WHERE session_id = ?1")?;
+        let mut rows = stmt.query_map([session_id], |row| row.get::<_, String>(0))?;
+        rows.next().transpose()
+    }
+
+    // provider=None で解除
+    pub fn set_session_provider(&self, session_id: &str, provider: Option<&str>) -> Result<()> {
+        match provider {
+            Some(p) => self.conn.execute(
+                r#"
+                INSERT INTO session_providers(session_id, provider, updated_at)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(session_id) DO UPDATE
+                    SET provider = excluded.provider, updated_at = excluded.updated_at
+                "#,
+                params![session_id, p, Self::now_ms()],
+            )?,
+            None => self.conn.execute(
+                "DELETE FROM session_providers WHERE session_id = ?1",
+                params![session_id],
+            )?,
+        };
+        Ok(())
+    }
+
+    // ---------- セッションのペルソナ ----------
+
+    pub fn session_persona(&self, session_id: &str) -> Result<Option<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT persona FROM session_personas WHERE session_id = ?1")?;
+        let mut rows = stmt.query_map([session_id], |row| row.get::<_, String>(0))?;
+        rows.next().transpose()
+    }
+
+    // persona=None で既定に戻す
+    pub fn set_session_persona(&self, session_id: &str, persona: Option<&str>) -> Result<()> {
+        match persona {
+            Some(p) => self.conn.execute(
+                r#"
+                INSERT INTO session_personas(session_id, persona, updated_at)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(session_id) DO UPDATE
+                    SET persona = excluded.persona, updated_at = excluded.updated_at
+                "#,
+                params![session_id, p, Self::now_ms()],
+            )?,
+            None => self.conn.execute(
+                "DELETE FROM session_personas WHERE session_id = ?1",
+                params![session_id],
+            )?,
+        };
+        Ok(())
+    }
+
+    // ---------- モデル実績 ----------
+
+    pub fn insert_model_outcome(&self, o: &ModelOutcome) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO model_outcomes(
+                log_id, session_id, model, task_type, success, latency_ms,
+                reasked, rating, created_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+            params![
+                o.log_id,
+                o.session_id,
+                o.model,
+                o.task_type,
+                o.success as i64,
+                o.latency_ms,
+                o.reasked as i64,
+                o.rating,
+                o.created_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn mark_model_outcome_reasked(&self, log_id: &str) -> Result<bool> {
+        let n = self.conn.execute(
+            "UPDATE model_outcomes SET reasked = 1 WHERE log_id = ?1",
+            params![log_id],
+        )?;
+        Ok(n > 0)
+    }
+
+    // 評価の付け直し / 取り消し（rating=None）
+    pub fn set_model_outcome_rating(&self, log_id: &str, rating: Option<i64>) -> Result<bool> {
+        let n = self.conn.execute(
+            "UPDATE model_outcomes SET rating = ?2 WHERE log_id = ?1",
+            params![log_id, rating],
+        )?;
+        Ok(n > 0)
+    }
+
+    pub fn model_outcomes_since(&self, since_ms: i64) -> Result<Vec<ModelOutcome>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT log_id, session_id, model, task_type, success, latency_ms,
+                    reasked, rating, created_at
+             FROM model_outcomes
+             WHERE created_at >= ?1",
+        )?;
+        let rows = stmt.query_map([since_ms], |row| {
+            Ok(ModelOutcome {
+                log_id: row.get(0)?,
+                session_id: row.get(1)?,
+                model: row.get(2)?,
+                task_type: row.get(3)?,
+                success: row.get::<_, i64>(4)? != 0,
+                latency_ms: row.get(5)?,
+                reasked: row.get::<_, i64>(6)? != 0,
+                rating: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    // ---------- 処理の記録 ----------
+
+    pub fn insert_trace(&self, t: &Trace) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO traces(log_id, session_id, trace, created_at, input)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                t.log_id,
+                t.session_id,
+                Self::to_json(t)?,
+                t.created_at,
+                t.input.trim()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn trace(&self, log_id: &str) -> Result<Option<Trace>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT trace FROM traces WHERE log_id = ?1")?;
+        let mut rows = stmt.query_map(params![log_id], |row| {
+            Self::from_json::<Trace>(&row.get::<_, String>(0)?)
+        })?;
+        rows.next().transpose()
+    }
+
+    pub fn prune_traces(&self, before_ms: i64) -> Result<usize> {
+        self.conn.execute(
+            "DELETE FROM traces WHERE created_at < ?1",
+            params![before_ms],
+        )
+    }
+
+    // 同じ依頼文の一番新しい記録（replay.rs。前後の空白は無視）
+    pub fn latest_trace_for_input(&self, input: &str) -> Result<Option<Trace>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT trace FROM traces WHERE input = ?1 ORDER BY created_at DESC LIMIT 1",
+        )?;
+        let mut rows = stmt.query_map(params![input.trim()], |row| {
+            Self::from_json::<Trace>(&row.get::<_, String>(0)?)
+        })?;
+        rows.next().transpose()
+    }
+
+    // ---------- 利用額 ----------
+
+    pub fn add_spend(
+        &self,
+        day: &str,
+        provider: &str,
+        model: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+        cost_usd: f64,
+    ) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO spend(day, provider, model, input_tokens, output_tokens, cost_usd)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(day, provider, model) DO UPDATE SET
+                input_tokens = input_tokens + excluded.input_tokens,
+                output_tokens = output_tokens + excluded.output_tokens,
+                cost_usd = cost_usd + excluded.cost_usd
+            "#,
+            params![
+                day,
+                provider,
+                model,
+                input_tokens as i64,
+                output_tokens as i64,
+                cost_usd
+            ],
+        )?;
+        Ok(())
+    }
+
+    // since_day (YYYY-MM-DD) 以降の合計
+    pub fn spend_since(&self, provider: &str, since_day: &str) -> Result<f64> {
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(cost_usd), 0) FROM spend WHERE provider = ?1 AND day >= ?2",
+            params![provider, since_day],
+            |r| r.get(0),
+        )
+    }
+
+    pub fn spend_providers_since(&self, since_day: &str) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT provider FROM spend WHERE day >= ?1")?;
+        let rows = stmt.query_map(params![since_day], |r| r.get(0))?;
+        rows.collect()
+    }
+
+    // ---------- 繋がったら聞く依頼 ----------
+
+    fn row_to_deferred(row: &rusqlite::Row) -> Result<DeferredRequest> {
+        Ok(DeferredRequest {
+            id: row.get(0)?,
+            session_id: row.get(1)?,
+            input: row.get(2)?,
+            status: row.get(3)?,
+            answer: row.get(4)?,
+            error: row.get(5)?,
+            created_at: row.get(6)?,
+            finished_at: row.get(7)?,
+        })
+    }
+
+    pub fn save_deferred(&self, r: &DeferredRequest) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO deferred_requests(
+                 id, session_id, input, status, answer, error, created_at, finished_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                r.id,
+                r.session_id,
+                r.input,
+                r.status,
+                r.answer,
+                r.error,
+                r.created_at,
+                r.finished_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_deferred(&self, id: &str) -> Result<Option<DeferredRequest>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, input, status, answer, error, created_at, finished_at
+             FROM deferred_requests WHERE id = ?1",
+        )?;
+        let mut rows = stmt.query_map(params![id], Self::row_to_deferred)?;
+        rows.next().transpose()
+    }
+
+    pub fn list_deferred(&self, limit: usize) -> Result<Vec<DeferredRequest>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, input, status, answer, error, created_at, finished_at
+             FROM deferred_requests ORDER BY created_at DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], Self::row_to_deferred)?;
+        rows.collect()
+    }
+
+    // 古い順
+    pub fn queued_deferred(&self) -> Result<Vec<DeferredRequest>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, input, status, answer, error, created_at, finished_at
+             FROM deferred_requests WHERE status = 'queued' ORDER BY created_at",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_deferred)?;
+        rows.collect()
+    }
+
+    pub fn requeue_running_deferred(&self) -> Result<usize> {
+        self.conn.execute(
+            "UPDATE deferred_requests SET status = 'queued' WHERE status = 'running'",
+            [],
+        )
+    }
+
+    // ---------- セッションのタグ ----------
+
+    fn row_to_session_labels(row: &rusqlite::Row) -> Result<SessionLabels> {
+        let tags: String = row.get(1)?;
+        let stickies: Option<String> = row.get(2)?;
+        Ok(SessionLabels {
+            session_id: row.get(0)?,
+            tags: Self::from_json(&tags)?,
+            stickies: stickies.map(|s| Self::from_json(&s)).transpose()?,
+            updated_at: row.get(3)?,
+        })
+    }
+
+    pub fn session_labels(&self, session_id: &str) -> Result<Option<SessionLabels>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT session_id, tags, stickies, updated_at
+             FROM session_labels WHERE session_id = ?1",
+        )?;
+        let mut rows = stmt.query_map([session_id], Self::row_to_session_labels)?;
+        rows.next().transpose()
+    }
+
+    pub fn all_session_labels(&self) -> Result<Vec<SessionLabels>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT session_id, tags, stickies, updated_at
+             FROM session_labels ORDER BY updated_at DESC",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_session_labels)?;
+        rows.collect()
+    }
+
+    // タグも分類も空なら消す
+    pub fn set_session_labels(&self, labels: &SessionLabels) -> Result<()> {
+        if labels.tags.is_empty() && labels.stickies.is_none() {
+            self.conn.execute(
+                "DELETE FROM session_labels WHERE session_id = ?1",
+                params![labels.session_id],
+            )?;
+            return Ok(());
+        }
+        self.conn.execute(
+            "INSERT OR REPLACE INTO session_labels(session_id, tags, stickies, updated_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                labels.session_id,
+                Self::to_json(&labels.tags)?,
+                labels.stickies.as_ref().map(Self::to_json).transpose()?,
+                labels.updated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    // ---------- 知識グラフ ----------
+
+    // 種別は最初に分かったものを残す
+    pub fn upsert_kg_entity(&self, name: &str, kind: &str) -> Result<i64> {
+        let now = Self::now_ms();
+        let key = name.to_lowercase();
+        self.conn.execute(
+            r#"
+            INSERT INTO kg_entities(name, name_key, kind, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?4)
+            ON CONFLICT(name_key) DO UPDATE SET
+                updated_at = excluded.updated_at,
+                kind = CASE WHEN kind = '' THEN excluded.kind ELSE kind END
+            "#,
+            params![name, key, kind, now],
+        )?;
+        self.conn.query_row(
+            "SELECT id FROM kg_entities WHERE name_key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+    }
+
+    // 同じ関係がまた出てきたら mentions を足す
+    pub fn add_kg_relation(
+        &self,
+        subject_id: i64,
+        predicate: &str,
+        object_id: i64,
+        source_log: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO kg_relations(subject_id, predicate, object_id, source_log, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+            ON CONFLICT(subject_id, predicate, object_id) DO UPDATE SET
+                mentions = mentions + 1,
+                source_log = COALESCE(excluded.source_log, source_log),
+                updated_at = excluded.updated_at
+            "#,
+            params![subject_id, predicate, object_id, source_log, Self::now_ms()],
+        )?;
+        Ok(())
+    }
+
+    pub fn kg_entities(&self, limit: usize) -> Result<Vec<Entity>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, kind, created_at, updated_at
+             FROM kg_entities ORDER BY updated_at DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], |row| {
+            Ok(Entity {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                kind: row.get(2)?,
+                created_at: row.get(3)?,
+                updated_at: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    fn row_to_fact(row: &rusqlite::Row) -> Result<Fact> {
+        Ok(Fact {
+            id: row.get(0)?,
+            subject: row.get(1)?,
+            subject_kind: row.get(2)?,
+            predicate: row.get(3)?,
+            object: row.get(4)?,
+            object_kind: row.get(5)?,
+            mentions: row.get(6)?,
+            source_log: row.get(7)?,
+            created_at: row.get(8)?,
+            updated_at: row.get(9)?,
+        })
+    }
+
+    // entity_id=None で全体から
+    pub fn kg_facts(&self, entity_id: Option<i64>, limit: usize) -> Result<Vec<Fact>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT r.id, s.name, s.kind, r.predicate, o.name, o.kind,
+                    r.mentions, r.source_log, r.created_at, r.updated_at
+             FROM kg_relations r
+             JOIN kg_entities s ON s.id = r.subject_id
+             JOIN kg_entities o ON o.id = r.object_id
+             WHERE ?1 IS NULL OR r.subject_id = ?1 OR r.object_id = ?1
+             ORDER BY r.mentions DESC, r.updated_at DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![entity_id, limit as i64], Self::row_to_fact)?;
+        rows.collect()
+    }
+
+    // 関係が無くなったエンティティも消す
+    pub fn delete_kg_relation(&self, id: i64) -> Result<bool> {
+        let n = self
+            .conn
+            .execute("DELETE FROM kg_relations WHERE id = ?1", params![id])?;
+        self.conn.execute(
+            "DELETE FROM kg_entities WHERE id NOT IN (SELECT subject_id FROM kg_relations)
+                 AND id NOT IN (SELECT object_id FROM kg_relations)",
+            [],
+        )?;
+        Ok(n > 0)
+    }
+
+    // ---------- 外部に送った中身 ----------
+
+    pub fn add_data_share(&self, p: &SharedPayload) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO data_sharing(provider, model, log_id, session_id, payload, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                p.provider,
+                p.model,
+                p.log_id,
+                p.session_id,
+                Self::to_json(p)?,
+                p.created_at
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    // 新しい順
+    pub fn data_shares(
+        &self,
+        log_id: Option<&str>,
+        provider: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SharedPayload>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, payload FROM data_sharing
+             WHERE (?1 IS NULL OR log_id = ?1) AND (?2 IS NULL OR provider = ?2)
+             ORDER BY created_at DESC, id DESC LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![log_id, provider, limit as i64], |row| {
+            let mut p = Self::from_json::<SharedPayload>(&row.get::<_, String>(1)?)?;
+            p.id = row.get(0)?;
+            Ok(p)
+        })?;
+        rows.collect()
+    }
+
+    pub fn prune_data_shares(&self, before_ms: i64) -> Result<usize> {
+        self.conn.execute(
+            "DELETE FROM data_sharing WHERE created_at < ?1",
+            params![before_ms],
+        )
+    }
+
+    // ---------- 操作の監査記録 ----------
+
+    fn row_to_audit_entry(row: &rusqlite::Row) -> Result<AuditEntry> {
+        Ok(AuditEntry {
+            id: row.get(0)?,
+            action: row.get(1)?,
+            argument: row.get(2)?,
+            result: row.get(3)?,
+            initiator: row.get(4)?,
+            session_id: row.get(5)?,
+            log_id: row.get(6)?,
+            omitted: row.get::<_, i64>(7)? != 0,
+            created_at: row.get(8)?,
+            prev_hash: row.get(9)?,
+            hash: row.get(10)?,
+        })
+    }
+
+    pub fn last_audit_hash(&self) -> Result<Option<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT hash FROM action_audit ORDER BY id DESC LIMIT 1")?;
+        let mut rows = stmt.query_map([], |row| row.get(0))?;
+        rows.next().transpose()
+    }
+
+    pub fn add_audit(&self, e: &AuditEntry) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO action_audit(action, argument, result, initiator, session_id, log_id,
+                                      omitted, created_at, prev_hash, hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                e.action,
+                e.argument,
+                e.result,
+                e.initiator,
+                e.session_id,
+                e.log_id,
+                e.omitted as i64,
+                e.created_at,
+                e.prev_hash,
+                e.hash
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    // [from, to) の新しい順
+    pub fn audit_between(&self, from: i64, to: i64, limit: usize) -> Result<Vec<AuditEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, action, argument, result, initiator, session_id, log_id, omitted,
+                    created_at, prev_hash, hash
+             FROM action_audit WHERE created_at >= ?1 AND created_at < ?2
+             ORDER BY id DESC LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![from, to, limit as i64], Self::row_to_audit_entry)?;
+        rows.collect()
+    }
+
+    // 確かめ済みの行より後だけ、古い順
+    pub fn audit_after(&self, id: i64) -> Result<Vec<AuditEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, action, argument, result, initiator, session_id, log_id, omitted,
+                    created_at, prev_hash, hash
+             FROM action_audit WHERE id > ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![id], Self::row_to_audit_entry)?;
+        rows.collect()
+    }
+
+    pub fn audit_hash(&self, id: i64) -> Result<Option<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT hash FROM action_audit WHERE id = ?1")?;
+        let mut rows = stmt.query_map(params![id], |row| row.get(0))?;
+        rows.next().transpose()
+    }
+
+    // 鎖の確認用に全部、古い順
+    pub fn audit_chain(&self) -> Result<Vec<AuditEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, action, argument, result, initiator, session_id, log_id, omitted,
+                    created_at, prev_hash, hash
+             FROM action_audit ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_audit_entry)?;
+        rows.collect()
+    }
+
+    // 行を消した後に残りの鎖を繋ぎ直す（purge.rs。begin_purge のトランザクションの中で）
+    pub fn rechain_audit(&self, digest: &dyn Fn(&AuditEntry) -> String) -> Result<usize> {
+        let mut prev = String::new();
+        let mut changed = 0;
+        for mut e in self.audit_chain()? {
+            e.prev_hash = prev;
+            let hash = digest(&e);
+            if hash != e.hash {
+                self.conn.execute(
+                    "UPDATE action_audit SET prev_hash = ?1, hash = ?2 WHERE id = ?3",
+                    params![e.prev_hash, hash, e.id],
+                )?;
+                changed += 1;
+            }
+            prev = hash;
+        }
+        Ok(changed)
+    }
+
+    // ---------- インストール済みアプリ (app_catalog.rs) ----------
+
+    // (AppID, 名前) で入れ替える。起動の記録は残し、今回無かったものは消す。戻り値は件数
+    pub fn replace_app_catalog(&self, apps: &[(String, String)]) -> Result<usize> {
+        let now = Self::now_ms();
+        let tx = self.conn.unchecked_transaction()?;
+        for (id, name) in apps {
+            tx.execute(
+                "INSERT INTO app_catalog(app_id, name, seen_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(app_id) DO UPDATE SET name = excluded.name, seen_at = excluded.seen_at",
+                params![id, name, now],
+            )?;
+        }
+        tx.execute("DELETE FROM app_catalog WHERE seen_at < ?1", params![now])?;
+        tx.commit()?;
+        Ok(apps.len())
+    }
+
+    pub fn list_app_catalog(&self) -> Result<Vec<AppEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT app_id, name, launches, last_launched FROM app_catalog
+             ORDER BY name COLLATE NOCASE",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(AppEntry {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                launches: row.get(2)?,
+                last_launched: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    pub fn mark_app_launched(&self, app_id: &str, at: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE app_catalog SET launches = launches + 1, last_launched = ?2 WHERE app_id = ?1",
+            params![app_id, at],
+        )?;
+        Ok(())
+    }
+
+    // ---------- データの削除 (purge.rs) ----------
+
+    // 消す行を 1 つのトランザクションで消して、テーブル毎の件数と一緒に返す
+    // commit は呼び出し側（history.json を書き終えてから）。dry_run は commit せずに捨てれば数えるだけになる
+    pub fn begin_purge(&self, rows: &PurgeRows) -> Result<(Transaction<'_>, Vec<(String, usize)>)> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        let mut add = |table: &str, n: usize| {
+            if n == 0 {
+                return;
+            }
+            match counts.iter_mut().find(|(t, _)| t == table) {
+                Some((_, c)) => *c += n,
+                None => counts.push((table.to_string(), n)),
+            }
+        };
+
+        if let Some(before) = rows.before {
+            for (table, column) in [
+                ("messages", "created_at"),
+                ("traces", "created_at"),
+                ("model_outcomes", "created_at"),
+                ("session_providers", "updated_at"),
+                ("session_personas", "updated_at"),
+                ("session_labels", "updated_at"),
+                ("plans", "updated_at"),
+                ("tasks", "created_at"),
+                ("deferred_requests", "created_at"),
+                ("activity", "ended_at"),
+                ("kg_relations", "updated_at"),
+                ("data_sharing", "created_at"),
+                ("goals", "created_at"),
+                ("beliefs", "updated_at"),
+                ("documents", "created_at"),
+                ("feed_items", "fetched_at"),
+                ("scheduled_tasks", "created_at"),
+                ("file_journal", "created_at"),
+                ("action_audit", "created_at"),
+            ] {
+                let n = tx.execute(
+                    &format!("DELETE FROM {} WHERE {} < ?1", table, column),
+                    params![before],
+                )?;
+                add(table, n);
+            }
+            let n = tx.execute(
+                r#"
+                DELETE FROM sessions WHERE updated_at < ?1
+                  AND NOT EXISTS (SELECT 1 FROM messages m WHERE m.session_id = sessions.session_id)
+                "#,
+                params![before],
+            )?;
+            add("sessions", n);
+        }
+
+        for log_id in &rows.log_ids {
+            for table in ["traces", "model_outcomes", "data_sharing", "action_audit"] {
+                let n = tx.execute(
+                    &format!("DELETE FROM {} WHERE log_id = ?1", table),
+                    params![log_id],
+                )?;
+                add(table, n);
+            }
+            let n = tx.execute(
+                "DELETE FROM kg_relations WHERE source_log = ?1",
+                params![log_id],
+            )?;
+            add("kg_relations", n);
+        }
+
+        if let Some(provider) = &rows.provider {
+            let n = tx.execute(
+                "DELETE FROM data_sharing WHERE provider = ?1",
+                params![provider],
+            )?;
+            add("data_sharing", n);
+        }
+
+        // messages にはログの id が無いので、回答の本文で探してその直前の質問と一緒に
+        for (session_id, answer) in &rows.answers {
+            let n = tx.execute(
+                r#"
+                DELETE FROM messages WHERE id IN (
+                    SELECT (SELECT MAX(u.id) FROM messages u
+                             WHERE u.session_id = a.session_id AND u.role = 'user' AND u.id < a.id)
+                    FROM messages a
+                    WHERE a.session_id = ?1 AND a.role = 'assistant' AND a.content = ?2
+                )
+                "#,
+                params![session_id, answer],
+            )?;
+            add("messages", n);
+            let n = tx.execute(
+                "DELETE FROM messages WHERE session_id = ?1 AND role = 'assistant' AND content = ?2",
+                params![session_id, answer],
+            )?;
+            add("messages", n);
+        }
+
+        // 履歴が無くなったセッションの設定 / ラベル
+        for session_id in &rows.sessions {
+            for table in [
+                "session_providers",
+                "session_personas",
+                "session_labels",
+                "scheduled_tasks",
+                "file_journal",
+                "action_audit",
+                "messages",
+                "sessions",
+            ] {
+                let n = tx.execute(
+                    &format!("DELETE FROM {} WHERE session_id = ?1", table),
+                    params![session_id],
+                )?;
+                add(table, n);
+            }
+        }
+
+        for id in &rows.memory_ids {
+            let n = tx.execute("DELETE FROM memory_entries WHERE id = ?1", params![id])?;
+            add("memory_entries", n);
+            tx.execute("DELETE FROM memory_fts WHERE id = ?1", params![id])?;
+        }
+
+        // 本体が無くなった索引 / どの関係にも出てこないエンティティ
+        let n = tx.execute(
+            r#"
+            DELETE FROM message_index WHERE NOT EXISTS (
+                SELECT 1 FROM messages m
+                WHERE m.session_id = message_index.session_id AND m.content = message_index.content
+            )
+            "#,
+            [],
+        )?;
+        add("message_index", n);
+        let n = tx.execute(
+            r#"
+            DELETE FROM kg_entities WHERE NOT EXISTS (
+                SELECT 1 FROM kg_relations r
+                WHERE r.subject_id = kg_entities.id OR r.object_id = kg_entities.id
+            )
+            "#,
+            [],
+        )?;
+        add("kg_entities", n);
+        let n = tx.execute(
+            r#"
+            DELETE FROM tags WHERE doc_id IS NOT NULL AND NOT EXISTS (
+                SELECT 1 FROM documents d WHERE d.id = tags.doc_id
+            )
+            "#,
+            [],
+        )?;
+        add("tags", n);
+
+        Ok((tx, counts))
+    }
+
+    // ---------- Axis メモリ (sqlite バックエンド) ----------
+
+    fn to_json<T: serde::Serialize>(v: &T) -> Result<String> {
+        serde_json::to_string(v).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+    }
+
+    fn from_json<T: serde::de::DeserializeOwned>(s: &str) -> Result<T> {
+        serde_json::from_str(s).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })
+    }
+
+    pub fn upsert_memory(&self, entry: &MemoryEntry, meta: &MemoryMeta) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        Self::write_memory(&tx, entry, meta)?;
+        tx.commit()
+    }
+
+    // begin_import 等のトランザクションの中から
+    pub fn write_memory(conn: &Connection, entry: &MemoryEntry, meta: &MemoryMeta) -> Result<()> {
+        conn.execute(
+            r#"
+            INSERT INTO memory_entries(id, session_id, timestamp_ms, updated_at_ms, entry_json, meta_json)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(id) DO UPDATE SET
+                updated_at_ms = excluded.updated_at_ms,
+                entry_json = excluded.entry_json,
+                meta_json = excluded.meta_json
+            "#,
+            params![
+                entry.id,
+                entry.session_id,
+                entry.timestamp_ms,
+                meta.updated_at_ms,
+                Self::to_json(entry)?,
+                Self::to_json(meta)?
+            ],
+        )?;
+        conn.execute("DELETE FROM memory_fts WHERE id = ?1", params![entry.id])?;
+        conn.execute(
+            "INSERT INTO memory_fts(search_text, id) VALUES (?1, ?2)",
+            params![meta.search_text, entry.id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_memory(&self, id: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM memory_entries WHERE id = ?1", params![id])?;
+        tx.execute("DELETE FROM memory_fts WHERE id = ?1", params![id])?;
+        tx.commit()
+    }
+
+    pub fn memory_exists(&self, id: &str) -> Result<bool> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM memory_entries WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        Ok(n > 0)
+    }
+
+    pub fn load_memory_entry(&self, id: &str) -> Result<MemoryEntry> {
+        let json: String = self.conn.query_row(
+            "SELECT entry_json FROM memory_entries WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        Self::from_json(&json)
+    }
+
+    pub fn load_memory_meta(&self, id: &str) -> Result<MemoryMeta> {
+        let json: String = self.conn.query_row(
+            "SELECT meta_json FROM memory_entries WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        Self::from_json(&json)
+    }
+
+    // 検索結果の分をまとめて（無い / 壊れた行は入れない）
+    pub fn load_memory_metas(&self, ids: &[String]) -> Result<Vec<MemoryMeta>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT meta_json FROM memory_entries WHERE id = ?1")?;
+        let mut metas = Vec::new();
+        for id in ids {
+            let mut rows = stmt.query_map(params![id], |row| row.get::<_, String>(0))?;
+            if let Some(meta) = rows
+                .next()
+                .transpose()?
+                .and_then(|j| Self::from_json(&j).ok())
+            {
+                metas.push(meta);
+            }
+        }
+        Ok(metas)
+    }
+
+    // 新しいもの順（壊れた行は読み飛ばす）
+    pub fn list_memory_meta(&self) -> Result<Vec<MemoryMeta>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT meta_json FROM memory_entries ORDER BY updated_at_ms DESC")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows
+            .filter_map(|r| r.ok())
+            .filter_map(|json| Self::from_json(&json).ok())
+            .collect())
+    }
+
+    // ---------- バックアップ ----------
+
+    // 使用中でも整合の取れたコピーを書き出す
+    pub fn snapshot_to<P: AsRef<Path>>(&self, dest: P) -> Result<()> {
+        let dest = dest.as_ref().to_string_lossy().to_string();
+        self.conn.execute("VACUUM INTO ?1", params![dest])?;
+        Ok(())
+    }
+
+    // バックアップの db から行を取り込む（重複は入れない）。取り込んだ行数を返す
+    // memory_entries は memory.rs 側で entry/meta 単位に取り込むのでここでは扱わない
+    pub fn merge_from<P: AsRef<Path>>(&self, src: P) -> Result<usize> {
+        let src = src.as_ref().to_string_lossy().to_string();
+        self.conn
+            .execute("ATTACH DATABASE ?1 AS backup", params![src])?;
+        let result = self.merge_attached();
+        let _ = self.conn.execute("DETACH DATABASE backup", []);
+        result
+    }
+
+    fn backup_has_table(&self, table: &str) -> Result<bool> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM backup.sqlite_master WHERE type IN ('table', 'view') AND name = ?1",
+            params![table],
+            |row| row.get(0),
+        )?;
+        Ok(n > 0)
+    }
+
+    fn merge_attached(&self) -> Result<usize> {
+        // (必要なテーブル, SQL)。古いバックアップに無いテーブルは飛ばす
+        const STEPS: &[(&str, &str)] = &[
+            (
+                "sessions",
+                "INSERT OR IGNORE INTO main.sessions(session_id, title, created_at, updated_at)
+                 SELECT session_id, title, created_at, updated_at FROM backup.sessions",
+            ),
+            // FTS は messages より先に（「まだ無いメッセージ」の判定に main.messages を使うため）
+            (
+                "messages",
+                "INSERT INTO main.message_index(content, session_id)
+                 SELECT b.content, b.session_id FROM backup.messages b
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM main.messages m
+                     WHERE m.session_id = b.session_id AND m.created_at = b.created_at
+                       AND m.role = b.role AND m.content = b.content)",
+            ),
+            (
+                "messages",
+                "INSERT INTO main.messages(session_id, role, content, created_at)
+                 SELECT b.session_id, b.role, b.content, b.created_at FROM backup.messages b
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM main.messages m
+                     WHERE m.session_id = b.session_id AND m.created_at = b.created_at
+                       AND m.role = b.role AND m.content = b.content)",
+            ),
+            (
+                "beliefs",
+                "INSERT OR IGNORE INTO main.beliefs(key, value, updated_at)
+                 SELECT key, value, updated_at FROM backup.beliefs",
+            ),
+            (
+                "goals",
+                "INSERT INTO main.goals(title, status, priority, due_at, created_at)
+                 SELECT b.title, b.status, b.priority, b.due_at, b.created_at FROM backup.goals b
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM main.goals g WHERE g.title = b.title AND g.created_at = b.created_at)",
+            ),
+            (
+                "documents",
+                "INSERT OR IGNORE INTO main.documents(file_path, summary, content_text, created_at)
+                 SELECT file_path, summary, content_text, created_at FROM backup.documents",
+            ),
+            // 付箋は doc_id が変わるので file_path で付け替える
+            (
+                "tags",
+                "INSERT INTO main.tags(doc_id, category_l, category_m, category_s)
+                 SELECT d.id, t.category_l, t.category_m, t.category_s
+                 FROM backup.tags t
+                 JOIN backup.documents bd ON bd.id = t.doc_id
+                 JOIN main.documents d ON d.file_path = bd.file_path
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM main.tags x
+                     WHERE x.doc_id = d.id AND x.category_l IS t.category_l
+                       AND x.category_m IS t.category_m AND x.category_s IS t.category_s)",
+            ),
+            (
+                "scheduled_tasks",
+                "INSERT OR IGNORE INTO main.scheduled_tasks(
+                     id, title, action, payload, session_id, repeat_spec,
+                     next_run_at, last_run_at, enabled, created_at)
+                 SELECT id, title, action, payload, session_id, repeat_spec,
+                        next_run_at, last_run_at, enabled, created_at
+                 FROM backup.scheduled_tasks",
+            ),
+            (
+                "activity",
+                "INSERT INTO main.activity(title, app, started_at, ended_at)
+                 SELECT b.title, b.app, b.started_at, b.ended_at FROM backup.activity b
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM main.activity a
+                     WHERE a.started_at = b.started_at AND a.title = b.title)",
+            ),
+            (
+                "session_providers",
+                "INSERT OR IGNORE INTO main.session_providers(session_id, provider, updated_at)
+                 SELECT session_id, provider, updated_at FROM backup.session_providers",
+            ),
+            (
+                "session_personas",
+                "INSERT OR IGNORE INTO main.session_personas(session_id, persona, updated_at)
+                 SELECT session_id, persona, updated_at FROM backup.session_personas",
+            ),
+            (
+                "feeds",
+                "INSERT OR IGNORE INTO main.feeds(
+                     id, url, title, topic, created_at, last_polled_at, last_error)
+                 SELECT id, url, title, topic, created_at, last_polled_at, last_error
+                 FROM backup.feeds",
+            ),
+            (
+                "feed_items",
+                "INSERT OR IGNORE INTO main.feed_items(
+                     feed_id, guid, title, link, summary, published_at, fetched_at, read)
+                 SELECT feed_id, guid, title, link, summary, published_at, fetched_at, read
+                 FROM backup.feed_items",
+            ),
+            (
+                "model_outcomes",
+                "INSERT OR IGNORE INTO main.model_outcomes(
+                     log_id, session_id, model, task_type, success, latency_ms,
+                     reasked, rating, created_at)
+                 SELECT log_id, session_id, model, task_type, success, latency_ms,
+                        reasked, rating, created_at
+                 FROM backup.model_outcomes",
+            ),
+        ];
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut total = 0;
+        for (table, sql) in STEPS {
+            if !self.backup_has_table(table)? {
+                continue;
+            }
+            // message_index への投入は行数に数えない
+            let n = tx.execute(sql, [])?;
+            if !sql.contains("message_index") {
+                total += n;
+            }
+        }
+        tx.commit()?;
+        Ok(total)
+    }
 }
@@ -0,0 +1,155 @@
+// src-tauri/src/deferred.rs
+//
+// オフラインの間に「繋がったら聞いて」と積んでおく依頼
+// - queue_when_online で memory.db の deferred_requests に積む（queued → running → done / failed / cancelled）
+// - 見張り: WATCH_INTERVAL 毎に queued があれば接続を測り直し、繋がっていれば古い順に ask_axis
+//   （ローカル LLM だけで答えてしまわないよう、どのクラウドにも届かない間は待つ）
+// - 答えは OS 通知（DeepLink でそのセッションへ）+ axis-deferred-update（DeferredRequest そのまま）
+// - アプリを落とした時に running だったものは次の起動で queued に戻す
+
+use crate::db::AxisDatabase;
+use crate::notify::{self, DeepLink};
+use crate::system;
+use chrono::Local;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+const UPDATE_EVENT: &str = "axis-deferred-update";
+const WATCH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DeferredRequest {
+    pub id: String,
+    pub session_id: String,
+    pub input: String,
+    pub status: String, // queued / running / done / failed / cancelled
+    pub answer: Option<String>,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+fn save(app: &AppHandle, req: &DeferredRequest) {
+    if let Err(e) =
+        AxisDatabase::open(app).and_then(|db| db.save_deferred(req).map_err(|e| e.to_string()))
+    {
+        warn!("⚠️ [Deferred] failed to save {}: {}", req.id, e);
+    }
+    let _ = app.emit(UPDATE_EVENT, req);
+}
+
+pub fn enqueue(app: &AppHandle, input: &str, session_id: &str) -> Result<DeferredRequest, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("nothing to ask".to_string());
+    }
+    let req = DeferredRequest {
+        id: Uuid::new_v4().to_string(),
+        session_id: session_id.to_string(),
+        input: input.to_string(),
+        status: "queued".to_string(),
+        created_at: Local::now().timestamp_millis(),
+        ..Default::default()
+    };
+    save(app, &req);
+    info!(input = %req.input, "📥 [Deferred] queued until online");
+    Ok(req)
+}
+
+pub fn list(app: &AppHandle, limit: usize) -> Result<Vec<DeferredRequest>, String> {
+    AxisDatabase::open(app)?
+        .list_deferred(limit)
+        .map_err(|e| e.to_string())
+}
+
+pub fn cancel(app: &AppHandle, id: &str) -> Result<DeferredRequest, String> {
+    let mut req = AxisDatabase::open(app)?
+        .get_deferred(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("deferred request '{}' not found", id))?;
+    if req.status != "queued" {
+        return Err(format!("request is already {}", req.status));
+    }
+    req.status = "cancelled".to_string();
+    req.finished_at = Some(Local::now().timestamp_millis());
+    save(app, &req);
+    Ok(req)
+}
+
+// クラウドのどれかに届くか（ローカルだけでは答えない）
+async fn online() -> bool {
+    tauri::async_runtime::spawn_blocking(|| system::check_network(true).online)
+        .await
+        .unwrap_or(false)
+}
+
+async fn drain(app: &AppHandle) -> Result<(), String> {
+    let queued = AxisDatabase::open(app)?
+        .queued_deferred()
+        .map_err(|e| e.to_string())?;
+    if queued.is_empty() || !online().await {
+        return Ok(());
+    }
+    info!(
+        "📤 [Deferred] back online, sending {} request(s)",
+        queued.len()
+    );
+    for mut req in queued {
+        // 送る前に取り消されていないか
+        let still_queued = AxisDatabase::open(app)?
+            .get_deferred(&req.id)
+            .map_err(|e| e.to_string())?
+            .is_some_and(|r| r.status == "queued");
+        if !still_queued {
+            continue;
+        }
+        req.status = "running".to_string();
+        save(app, &req);
+
+        let result = crate::ask_axis(app.clone(), req.input.clone(), req.session_id.clone()).await;
+        req.finished_at = Some(Local::now().timestamp_millis());
+        let body = match result {
+            Ok(answer) => {
+                req.status = "done".to_string();
+                req.answer = Some(answer.clone());
+                answer
+            }
+            Err(e) => {
+                req.status = "failed".to_string();
+                req.error = Some(e.clone());
+                format!("Failed: {}", e)
+            }
+        };
+        save(app, &req);
+        let title: String = req.input.chars().take(60).collect();
+        notify::notify(
+            app,
+            &format!("📬 {}", title),
+            &body,
+            Some(DeepLink::session(&req.session_id)),
+        );
+    }
+    Ok(())
+}
+
+// setup で呼ぶ
+pub fn spawn_watcher(app: AppHandle) {
+    match AxisDatabase::open(&app)
+        .and_then(|db| db.requeue_running_deferred().map_err(|e| e.to_string()))
+    {
+        Ok(n) if n > 0 => info!("📥 [Deferred] re-queued {} interrupted request(s)", n),
+        Ok(_) => {}
+        Err(e) => warn!("⚠️ [Deferred] failed to re-queue: {}", e),
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = drain(&app).await {
+                warn!("⚠️ [Deferred] drain failed: {}", e);
+            }
+            tokio::time::sleep(WATCH_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,79 @@
+// src-tauri/src/documents.rs
+//
+// 文書への質問 (ask_document)
+// - settings.document_provider が "gemini" ならファイルをそのまま渡す（PDF / 画像 / テキスト。大きいものは Files API 経由 / ai.rs）
+// - ほかのエイリアスは文字のファイルだけ。中身を MAX_TEXT_CHARS まで質問に添える（PDF / 画像は gemini で）
+// - ローカル専用モードではファイルを外に出さない（文字のファイルだけローカルモデルで読む）
+// - 相対パスは SAVE と同じくデスクトップ基準
+
+use crate::{ai, local_only, prompts, settings, shell};
+use std::fs;
+use tauri::AppHandle;
+use tracing::info;
+
+// Files API の上限より十分小さく（全部メモリに読むので）
+const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+const MAX_TEXT_CHARS: usize = 60_000;
+
+pub async fn ask(app: &AppHandle, path: &str, question: &str) -> Result<String, String> {
+    let question = question.trim();
+    if question.is_empty() {
+        return Err("question is empty".to_string());
+    }
+    let path = shell::resolve_user_path(path.trim());
+    let size = fs::metadata(&path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?
+        .len();
+    if size > MAX_FILE_BYTES {
+        return Err(format!(
+            "{} is too large ({} MB, max {} MB)",
+            path.display(),
+            size / (1024 * 1024),
+            MAX_FILE_BYTES / (1024 * 1024)
+        ));
+    }
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mime = crate::mime_for(&name);
+    let cfg = settings::current();
+    let sys = prompts::render(app, "document", &[]);
+
+    if cfg.document_provider == "gemini" && !local_only::enabled() {
+        info!("📄 [Documents] {} ({}) -> gemini", name, mime);
+        let file = ai::Attachment {
+            mime: mime.to_string(),
+            name,
+            bytes: fs::read(&path).map_err(|e| e.to_string())?,
+        };
+        return ai::call_google_with(&cfg.models.gemini, &sys, question, &[file]).await;
+    }
+
+    if !is_text(mime) {
+        return Err(format!(
+            "{} ({}) can only be read by gemini: set document_provider to \"gemini\"",
+            name, mime
+        ));
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("{}: {}", name, e))?;
+    let total = content.chars().count();
+    let mut excerpt: String = content.chars().take(MAX_TEXT_CHARS).collect();
+    if total > MAX_TEXT_CHARS {
+        excerpt.push_str(&format!(
+            "\n[... truncated: {} of {} chars]",
+            MAX_TEXT_CHARS, total
+        ));
+    }
+    let alias = &cfg.document_provider;
+    info!("📄 [Documents] {} ({} chars) -> {}", name, total, alias);
+    let user = format!(
+        "[Document: {}]\n{}\n[End of document]\n\n{}",
+        name, excerpt, question
+    );
+    ai::call_alias(alias, &cfg.models.for_alias(alias), &sys, &user).await
+}
+
+fn is_text(mime: &str) -> bool {
+    mime.starts_with("text/") || mime == "application/json" || mime == "application/xml"
+}
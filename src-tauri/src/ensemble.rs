@@ -0,0 +1,215 @@
+// src-tauri/src/ensemble.rs
+//
+// アンサンブル: 複数モデルに同時に聞いて、審判モデルが 1 つ選ぶ / 統合する
+// - 参加モデル: settings.ensemble_members（既定 gpt / gemini / grok）
+// - 審判: settings.ensemble_judge（既定 llama）。失敗したら多数決（他の候補と一番似ている回答）
+// - 各候補は ResponseMeta.candidates に残す
+
+use crate::ai;
+use crate::routing;
+use crate::settings::ModelSettings;
+use crate::sharing;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use std::time::Instant;
+
+// 審判に渡す候補 1 つ分の上限
+const MAX_CANDIDATE_CHARS: usize = 4000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EnsembleCandidate {
+    pub provider: String,
+    pub model: String,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub latency_ms: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnsembleResult {
+    pub answer: String,
+    pub choice: String, // 選ばれた provider / "merged"
+    pub method: String, // "judge:<alias>" / "vote" / "single"
+    pub candidates: Vec<EnsembleCandidate>,
+}
+
+#[derive(Deserialize)]
+struct JudgeVerdict {
+    choice: String,
+    #[serde(default)]
+    answer: String,
+}
+
+async fn ask_all(
+    members: &[String],
+    models: &ModelSettings,
+    sys: &str,
+    user: &str,
+) -> Vec<EnsembleCandidate> {
+    // join_all の代わりに 1 モデル 1 タスクで同時に走らせる
+    let handles: Vec<_> = members
+        .iter()
+        .map(|alias| {
+            let (alias, model) = (alias.clone(), models.for_alias(alias));
+            let (sys, user) = (sys.to_string(), user.to_string());
+            // 送信記録 (sharing.rs) を元の依頼に紐付けたまま
+            let interaction = sharing::current();
+            tauri::async_runtime::spawn(sharing::inherit(interaction, async move {
+                let t = Instant::now();
+                let result = ai::call_alias(&alias, &model, &sys, &user).await;
+                let latency_ms = t.elapsed().as_millis() as i64;
+                match result {
+                    Ok(text) => EnsembleCandidate {
+                        provider: alias,
+                        model,
+                        text,
+                        error: None,
+                        latency_ms,
+                    },
+                    Err(e) => EnsembleCandidate {
+                        provider: alias,
+                        model,
+                        text: String::new(),
+                        error: Some(e),
+                        latency_ms,
+                    },
+                }
+            }))
+        })
+        .collect();
+
+    let mut out = Vec::new();
+    for (alias, h) in members.iter().zip(handles) {
+        match h.await {
+            Ok(c) => out.push(c),
+            Err(e) => out.push(EnsembleCandidate {
+                provider: alias.clone(),
+                error: Some(e.to_string()),
+                ..Default::default()
+            }),
+        }
+    }
+    out
+}
+
+// 他の候補と一番似ている回答（= 多数派）を選ぶ
+fn vote(ok: &[&EnsembleCandidate]) -> usize {
+    (0..ok.len())
+        .max_by(|&a, &b| {
+            let score = |i: usize| -> f32 {
+                ok.iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, c)| routing::similarity(&ok[i].text, &c.text))
+                    .sum()
+            };
+            score(a).total_cmp(&score(b))
+        })
+        .unwrap_or(0)
+}
+
+async fn judge(
+    judge_alias: &str,
+    models: &ModelSettings,
+    user: &str,
+    ok: &[&EnsembleCandidate],
+) -> Result<JudgeVerdict, String> {
+    let listing: String = ok
+        .iter()
+        .map(|c| {
+            format!(
+                "### candidate: {}\n{}\n",
+                c.provider,
+                c.text.chars().take(MAX_CANDIDATE_CHARS).collect::<String>()
+            )
+        })
+        .collect();
+    let sys = r#"You are the judge of an AI ensemble.
+    Compare the candidate answers to the user request. Pick the most correct and helpful one,
+    or merge them when each has complementary correct parts.
+    - If the candidates are command chains (EXEC:, TYPE:, SAVE: ...), choose one; do NOT merge commands.
+    - Keep the language of the candidates (usually Japanese).
+    Return STRICT JSON only:
+    {"choice": "<candidate name>|merged", "answer": "<merged answer, only when choice is merged>"}"#;
+    let prompt = format!("[User Request]\n{}\n\n[Candidates]\n{}", user, listing);
+
+    let raw = ai::call_alias(judge_alias, &models.for_alias(judge_alias), sys, &prompt).await?;
+    let start = raw.find('{').ok_or("judge returned no JSON")?;
+    let end = raw.rfind('}').ok_or("judge returned no JSON")?;
+    serde_json::from_str(&raw[start..=end]).map_err(|e| format!("judge JSON: {}", e))
+}
+
+pub async fn run(
+    members: &[String],
+    judge_alias: &str,
+    models: &ModelSettings,
+    sys: &str,
+    user: &str,
+) -> Result<EnsembleResult, String> {
+    if members.is_empty() {
+        return Err("ensemble has no available members".to_string());
+    }
+    info!("🤝 [Ensemble] asking {}...", members.join(", "));
+    let candidates = ask_all(members, models, sys, user).await;
+    let ok: Vec<&EnsembleCandidate> = candidates.iter().filter(|c| c.error.is_none()).collect();
+
+    let (answer, choice, method) = match ok.len() {
+        0 => {
+            let errors: Vec<String> = candidates
+                .iter()
+                .map(|c| format!("{}: {}", c.provider, c.error.clone().unwrap_or_default()))
+                .collect();
+            return Err(format!(
+                "all ensemble members failed ({})",
+                errors.join(" / ")
+            ));
+        }
+        1 => (
+            ok[0].text.clone(),
+            ok[0].provider.clone(),
+            "single".to_string(),
+        ),
+        _ => match judge(judge_alias, models, user, &ok).await {
+            Ok(v) if v.choice == "merged" && !v.answer.trim().is_empty() => (
+                v.answer,
+                "merged".to_string(),
+                format!("judge:{}", judge_alias),
+            ),
+            verdict => {
+                let picked = match &verdict {
+                    Ok(v) => ok.iter().find(|c| c.provider == v.choice),
+                    Err(e) => {
+                        warn!("⚠️ [Ensemble] judge failed, voting instead: {}", e);
+                        None
+                    }
+                };
+                match picked {
+                    Some(c) => (
+                        c.text.clone(),
+                        c.provider.clone(),
+                        format!("judge:{}", judge_alias),
+                    ),
+                    None => {
+                        let i = vote(&ok);
+                        (
+                            ok[i].text.clone(),
+                            ok[i].provider.clone(),
+                            "vote".to_string(),
+                        )
+                    }
+                }
+            }
+        },
+    };
+
+    info!("🤝 [Ensemble] chose {} ({})", choice, method);
+    Ok(EnsembleResult {
+        answer,
+        choice,
+        method,
+        candidates,
+    })
+}
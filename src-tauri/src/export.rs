@@ -0,0 +1,508 @@
+// src-tauri/src/export.rs
+//
+// セッションの書き出し（共有・保管用）
+// - export_session(session_id, format, path): format = "markdown" / "html" / "pdf"
+// - 中身: 依頼 / Axis の回答 / 実行したアクション (ResponseMeta.actions) / 出典 (ResponseMeta.sources)
+// - PDF は HTML を書いてから Edge のヘッドレス印刷で変換する（日本語フォントもそのまま使える）
+// - 答え直しの枝 (branch_of) は含めない
+// - render_report: SAVE: <name>.pdf 用。モデルが書いた markdown を見出し / 表 / 箇条書き付きの HTML に
+//   （簡易な markdown。セッションの出典は末尾に Sources として付ける）
+
+use crate::storage::{self, InteractionLog, Source};
+use chrono::{Local, TimeZone};
+use regex::Regex;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+use tracing::info;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ExportReport {
+    pub path: String,
+    pub format: String,
+    pub turns: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim().to_lowercase().as_str() {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "html" | "htm" => Ok(Self::Html),
+            "pdf" => Ok(Self::Pdf),
+            other => Err(format!(
+                "unknown export format '{}': use markdown / html / pdf",
+                other
+            )),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Html => "html",
+            Self::Pdf => "pdf",
+        }
+    }
+}
+
+fn user_text(log: &InteractionLog) -> String {
+    log.user_tokens
+        .iter()
+        .map(|t| t.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn format_time(ms: i64) -> String {
+    Local
+        .timestamp_millis_opt(ms)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn session_logs(app: &AppHandle, session_id: &str) -> Result<Vec<InteractionLog>, String> {
+    let mut logs: Vec<InteractionLog> = storage::get_all_logs(app)?
+        .into_iter()
+        .filter(|l| l.session_id == session_id && l.branch_of.is_none())
+        .collect();
+    if logs.is_empty() {
+        return Err(format!("session '{}' has no messages", session_id));
+    }
+    logs.sort_by_key(|l| l.timestamp);
+    Ok(logs)
+}
+
+pub fn render_markdown(session_id: &str, logs: &[InteractionLog]) -> String {
+    let mut out = format!("# Axis session {}\n\n", session_id);
+    out.push_str(&format!(
+        "_Exported {} / {} turns_\n\n",
+        Local::now().format("%Y-%m-%d %H:%M"),
+        logs.len()
+    ));
+
+    for log in logs {
+        out.push_str("---\n\n");
+        let user = user_text(log);
+        if !user.is_empty() {
+            out.push_str(&format!(
+                "### 🧑 You — {}\n\n{}\n\n",
+                format_time(log.timestamp),
+                user
+            ));
+        }
+        let model = log
+            .meta
+            .as_ref()
+            .map(|m| format!(" ({})", m.model))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "### 🤖 Axis — {}{}\n\n{}\n\n",
+            log.provider_used, model, log.ai_response
+        ));
+
+        let Some(meta) = &log.meta else { continue };
+        if !meta.actions.is_empty() {
+            out.push_str("**Actions**\n\n");
+            for a in &meta.actions {
+                out.push_str(&format!("- `{}`\n", a.replace('`', "'")));
+            }
+            out.push('\n');
+        }
+        if !meta.sources.is_empty() {
+            out.push_str("**Sources**\n\n");
+            for (i, s) in meta.sources.iter().enumerate() {
+                out.push_str(&format!("{}. [{}]({})\n", i + 1, s.title, s.url));
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn render_html(session_id: &str, logs: &[InteractionLog]) -> String {
+    let mut body = String::new();
+    for log in logs {
+        body.push_str("<section class=\"turn\">\n");
+        let user = user_text(log);
+        if !user.is_empty() {
+            body.push_str(&format!(
+                "<div class=\"msg user\"><div class=\"who\">You · {}</div><div class=\"text\">{}</div></div>\n",
+                format_time(log.timestamp),
+                escape_html(&user)
+            ));
+        }
+        let model = log
+            .meta
+            .as_ref()
+            .map(|m| format!(" ({})", escape_html(&m.model)))
+            .unwrap_or_default();
+        body.push_str(&format!(
+            "<div class=\"msg ai\"><div class=\"who\">Axis · {}{}</div><div class=\"text\">{}</div></div>\n",
+            escape_html(&log.provider_used),
+            model,
+            escape_html(&log.ai_response)
+        ));
+
+        if let Some(meta) = &log.meta {
+            if !meta.actions.is_empty() {
+                body.push_str("<div class=\"extra\"><b>Actions</b><ul>");
+                for a in &meta.actions {
+                    body.push_str(&format!("<li><code>{}</code></li>", escape_html(a)));
+                }
+                body.push_str("</ul></div>\n");
+            }
+            if !meta.sources.is_empty() {
+                body.push_str("<div class=\"extra\"><b>Sources</b><ol>");
+                for s in &meta.sources {
+                    body.push_str(&format!(
+                        "<li><a href=\"{}\">{}</a></li>",
+                        escape_html(&s.url),
+                        escape_html(&s.title)
+                    ));
+                }
+                body.push_str("</ol></div>\n");
+            }
+        }
+        body.push_str("</section>\n");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ja">
+<head>
+<meta charset="utf-8">
+<title>Axis session {id}</title>
+<style>
+  body {{ font-family: "Segoe UI", "Yu Gothic UI", "Hiragino Sans", sans-serif; max-width: 820px; margin: 32px auto; padding: 0 16px; color: #1f2937; }}
+  h1 {{ font-size: 20px; margin-bottom: 4px; }}
+  .sub {{ color: #6b7280; font-size: 12px; margin-bottom: 24px; }}
+  .turn {{ border-top: 1px solid #e5e7eb; padding: 12px 0; page-break-inside: avoid; }}
+  .msg {{ margin: 8px 0; }}
+  .who {{ font-size: 11px; color: #6b7280; font-family: Consolas, monospace; }}
+  .text {{ white-space: pre-wrap; line-height: 1.6; padding: 8px 12px; border-radius: 8px; }}
+  .user .text {{ background: #ecfdf5; }}
+  .ai .text {{ background: #f3f4f6; }}
+  .extra {{ font-size: 12px; margin: 4px 12px; }}
+  code {{ font-family: Consolas, monospace; font-size: 11px; }}
+</style>
+</head>
+<body>
+<h1>Axis session {id}</h1>
+<div class="sub">Exported {at} · {n} turns</div>
+{body}</body>
+</html>
+"#,
+        id = escape_html(session_id),
+        at = Local::now().format("%Y-%m-%d %H:%M"),
+        n = logs.len(),
+        body = body
+    )
+}
+
+// セッション中に出てきた出典（URL で重複を除く、古い順）
+pub fn session_sources(app: &AppHandle, session_id: &str) -> Vec<Source> {
+    let mut out: Vec<Source> = Vec::new();
+    for log in session_logs(app, session_id).unwrap_or_default() {
+        for s in log.meta.map(|m| m.sources).unwrap_or_default() {
+            if !out.iter().any(|o| o.url == s.url) {
+                out.push(s);
+            }
+        }
+    }
+    out
+}
+
+// **bold** / *italic* / `code` / [text](url)（escape 済みの文字列に）
+fn inline_markdown(text: &str) -> String {
+    static RULES: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    let rules = RULES.get_or_init(|| {
+        vec![
+            (Regex::new(r"`([^`]+)`").unwrap(), "<code>$1</code>"),
+            (Regex::new(r"\*\*([^*]+)\*\*").unwrap(), "<b>$1</b>"),
+            (Regex::new(r"\*([^*]+)\*").unwrap(), "<i>$1</i>"),
+            (
+                Regex::new(r"\[([^\]]+)\]\((https?://[^)\s]+)\)").unwrap(),
+                "<a href=\"$2\">$1</a>",
+            ),
+        ]
+    });
+    let mut out = escape_html(text);
+    for (re, rep) in rules {
+        out = re.replace_all(&out, *rep).to_string();
+    }
+    out
+}
+
+fn table_cells(line: &str) -> Vec<String> {
+    line.trim()
+        .trim_matches('|')
+        .split('|')
+        .map(|c| c.trim().to_string())
+        .collect()
+}
+
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut para: Vec<String> = Vec::new();
+    let mut list: Option<&str> = None; // "ul" / "ol"
+    let mut in_code = false;
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut i = 0;
+
+    let flush = |out: &mut String, para: &mut Vec<String>, list: &mut Option<&str>| {
+        if !para.is_empty() {
+            out.push_str(&format!("<p>{}</p>\n", para.join("<br>")));
+            para.clear();
+        }
+        if let Some(tag) = list.take() {
+            out.push_str(&format!("</{}>\n", tag));
+        }
+    };
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        i += 1;
+        if trimmed.starts_with("```") {
+            if in_code {
+                out.push_str("</pre>\n");
+            } else {
+                flush(&mut out, &mut para, &mut list);
+                out.push_str("<pre>");
+            }
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            out.push_str(&escape_html(line));
+            out.push('\n');
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush(&mut out, &mut para, &mut list);
+            continue;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=4).contains(&level) && trimmed[level..].starts_with(' ') {
+            flush(&mut out, &mut para, &mut list);
+            out.push_str(&format!(
+                "<h{0}>{1}</h{0}>\n",
+                level,
+                inline_markdown(trimmed[level..].trim())
+            ));
+            continue;
+        }
+        // | a | b | の次が |---|---| なら表
+        if trimmed.starts_with('|')
+            && lines
+                .get(i)
+                .is_some_and(|next| next.trim().starts_with('|') && next.contains("--"))
+        {
+            flush(&mut out, &mut para, &mut list);
+            out.push_str("<table><thead><tr>");
+            for c in table_cells(trimmed) {
+                out.push_str(&format!("<th>{}</th>", inline_markdown(&c)));
+            }
+            out.push_str("</tr></thead><tbody>\n");
+            i += 1;
+            while let Some(row) = lines.get(i).filter(|l| l.trim().starts_with('|')) {
+                out.push_str("<tr>");
+                for c in table_cells(row) {
+                    out.push_str(&format!("<td>{}</td>", inline_markdown(&c)));
+                }
+                out.push_str("</tr>\n");
+                i += 1;
+            }
+            out.push_str("</tbody></table>\n");
+            continue;
+        }
+        let bullet = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+            .map(|t| ("ul", t));
+        let numbered = trimmed
+            .split_once(". ")
+            .filter(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+            .map(|(_, t)| ("ol", t));
+        if let Some((tag, text)) = bullet.or(numbered) {
+            if list != Some(tag) {
+                flush(&mut out, &mut para, &mut list);
+                out.push_str(&format!("<{}>\n", tag));
+                list = Some(tag);
+            }
+            out.push_str(&format!("<li>{}</li>\n", inline_markdown(text)));
+            continue;
+        }
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            flush(&mut out, &mut para, &mut list);
+            out.push_str(&format!(
+                "<blockquote>{}</blockquote>\n",
+                inline_markdown(quote.trim())
+            ));
+            continue;
+        }
+        if list.is_some() {
+            flush(&mut out, &mut para, &mut list);
+        }
+        para.push(inline_markdown(trimmed));
+    }
+    if in_code {
+        out.push_str("</pre>\n");
+    }
+    flush(&mut out, &mut para, &mut list);
+    out
+}
+
+// 先頭の "# " 見出しがあればそれをタイトルに（無ければ fallback_title）
+pub fn render_report(fallback_title: &str, markdown: &str, sources: &[Source]) -> String {
+    let markdown = markdown.trim();
+    let (title, rest) = match markdown.strip_prefix("# ") {
+        Some(r) => {
+            let (t, rest) = r.split_once('\n').unwrap_or((r, ""));
+            (t.trim().to_string(), rest)
+        }
+        None => (fallback_title.to_string(), markdown),
+    };
+    let mut body = markdown_to_html(rest);
+    if !sources.is_empty() {
+        body.push_str("<h2>Sources</h2>\n<ol class=\"sources\">\n");
+        for s in sources {
+            body.push_str(&format!(
+                "<li>{} — <a href=\"{}\">{}</a></li>\n",
+                escape_html(&s.title),
+                escape_html(&s.url),
+                escape_html(&s.url)
+            ));
+        }
+        body.push_str("</ol>\n");
+    }
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ja">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+  @page {{ margin: 18mm 16mm; }}
+  body {{ font-family: "Segoe UI", "Yu Gothic UI", "Hiragino Sans", sans-serif; color: #1f2937; line-height: 1.7; font-size: 13px; }}
+  h1 {{ font-size: 24px; margin: 0 0 4px; }}
+  .sub {{ color: #6b7280; font-size: 11px; margin-bottom: 20px; border-bottom: 2px solid #1f2937; padding-bottom: 8px; }}
+  h2 {{ font-size: 17px; margin-top: 22px; border-bottom: 1px solid #e5e7eb; padding-bottom: 2px; page-break-after: avoid; }}
+  h3, h4 {{ font-size: 14px; margin-top: 16px; page-break-after: avoid; }}
+  table {{ border-collapse: collapse; margin: 8px 0; width: 100%; page-break-inside: avoid; }}
+  th, td {{ border: 1px solid #d1d5db; padding: 4px 8px; text-align: left; vertical-align: top; }}
+  th {{ background: #f3f4f6; }}
+  pre {{ background: #f3f4f6; padding: 8px 12px; border-radius: 6px; white-space: pre-wrap; font-size: 11px; }}
+  code {{ font-family: Consolas, monospace; font-size: 11px; }}
+  blockquote {{ border-left: 3px solid #d1d5db; margin: 8px 0; padding-left: 12px; color: #4b5563; }}
+  .sources {{ font-size: 11px; color: #4b5563; }}
+  a {{ color: #2563eb; text-decoration: none; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<div class="sub">AXIS OS · {at}</div>
+{body}</body>
+</html>
+"#,
+        title = escape_html(&title),
+        at = Local::now().format("%Y-%m-%d %H:%M"),
+        body = body
+    )
+}
+
+// Edge（無ければ Chrome）のヘッドレス印刷で HTML → PDF
+fn find_browser() -> Option<PathBuf> {
+    let candidates = [
+        r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+        r"C:\Program Files\Microsoft\Edge\Application\msedge.exe",
+        r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+        r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+    ];
+    candidates.iter().map(PathBuf::from).find(|p| p.exists())
+}
+
+pub fn html_to_pdf(html: &str, dest: &Path) -> Result<(), String> {
+    let browser = find_browser().ok_or("PDF export needs Microsoft Edge or Google Chrome")?;
+    let tmp = std::env::temp_dir().join(format!(
+        "axis-export-{}.html",
+        Local::now().timestamp_millis()
+    ));
+    fs::write(&tmp, html).map_err(|e| e.to_string())?;
+
+    let mut cmd = Command::new(browser);
+    cmd.args(["--headless", "--disable-gpu", "--no-pdf-header-footer"])
+        .arg(format!("--print-to-pdf={}", dest.display()))
+        .arg(&tmp);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(0x08000000);
+    let output = cmd.output();
+    let _ = fs::remove_file(&tmp);
+
+    let output = output.map_err(|e| format!("failed to start browser: {}", e))?;
+    if !dest.exists() {
+        return Err(format!(
+            "PDF was not created: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+pub fn export_session(
+    app: &AppHandle,
+    session_id: &str,
+    format: &str,
+    path: &str,
+) -> Result<ExportReport, String> {
+    let format = ExportFormat::parse(format)?;
+    let logs = session_logs(app, session_id)?;
+
+    let dest = PathBuf::from(path.trim());
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    match format {
+        ExportFormat::Markdown => {
+            fs::write(&dest, render_markdown(session_id, &logs)).map_err(|e| e.to_string())?
+        }
+        ExportFormat::Html => {
+            fs::write(&dest, render_html(session_id, &logs)).map_err(|e| e.to_string())?
+        }
+        ExportFormat::Pdf => html_to_pdf(&render_html(session_id, &logs), &dest)?,
+    }
+
+    info!(
+        "📤 [Export] session {} → {} ({})",
+        session_id,
+        dest.display(),
+        format.name()
+    );
+    Ok(ExportReport {
+        path: dest.to_string_lossy().to_string(),
+        format: format.name().to_string(),
+        turns: logs.len(),
+    })
+}
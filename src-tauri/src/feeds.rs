@@ -0,0 +1,300 @@
+// src-tauri/src/feeds.rs
+//
+// RSS / Atom の購読と朝のブリーフィング
+// - subscribe_feed(url, topic): 一度読んでタイトルを確かめてから memory.db の feeds に登録
+// - 裏のループが settings.feed_poll_minutes おきに全フィードを読み、新着を feed_items に入れる
+// - ブリーフィング: 未読をトピック毎にまとめ、settings.briefing_writer に briefing プロンプトで要約させる
+//   記事は untrusted の印で囲んで渡し、答えのアクションは実行しない（run_axis を通さない素の呼び出し）
+//   → "briefing" セッションに囲んだまま残し、observer と同じ形で通知。使った記事は既読にする
+// - 定時実行はスケジューラの "briefing" アクション（SCHEDULE: daily 07:30 ||| BRIEFING）
+
+use crate::connectors::news;
+use crate::db::AxisDatabase;
+use crate::notify::{self, DeepLink};
+use crate::{ai, memory, prompts, settings};
+use axis_core::untrusted;
+use chrono::Local;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub const BRIEFING_SESSION: &str = "briefing";
+// 1 回のブリーフィングに入れる未読記事の上限
+const BRIEFING_MAX_ITEMS: usize = 60;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Feed {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub topic: String,
+    pub created_at: i64,
+    pub last_polled_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub unread: i64,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct StoredFeedItem {
+    pub id: i64,
+    pub feed_id: String,
+    pub feed_title: String,
+    pub topic: String,
+    pub guid: String,
+    pub title: String,
+    pub link: String,
+    pub summary: String,
+    pub published_at: Option<i64>,
+    pub fetched_at: i64,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PollReport {
+    pub feeds: usize,
+    pub new_items: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Briefing {
+    pub session_id: String,
+    pub items: usize,
+    pub topics: Vec<String>,
+    pub text: String,
+}
+
+fn store_items(
+    db: &AxisDatabase,
+    feed_id: &str,
+    items: Vec<news::FeedItem>,
+) -> Result<usize, String> {
+    let now = Local::now().timestamp_millis();
+    let mut added = 0;
+    for item in items {
+        let stored = StoredFeedItem {
+            guid: if item.guid.is_empty() {
+                item.title.clone()
+            } else {
+                item.guid
+            },
+            title: item.title,
+            link: item.link,
+            summary: item.summary.chars().take(1000).collect(),
+            published_at: item.published,
+            fetched_at: now,
+            ..Default::default()
+        };
+        if db
+            .insert_feed_item(feed_id, &stored)
+            .map_err(|e| e.to_string())?
+        {
+            added += 1;
+        }
+    }
+    Ok(added)
+}
+
+pub async fn subscribe(app: &AppHandle, url: &str, topic: &str) -> Result<Feed, String> {
+    let url = url.trim();
+    let cfg = settings::current();
+    crate::web::check_fetch_url(url, &cfg.fetch_allow_domains, &cfg.fetch_deny_domains)?;
+    let items = news::fetch_feed(url).await?;
+    let title = items
+        .first()
+        .map(|i| i.source.clone())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| url.to_string());
+    let topic = if topic.trim().is_empty() {
+        "general".to_string()
+    } else {
+        topic.trim().to_string()
+    };
+
+    let feed = Feed {
+        id: Uuid::new_v4().to_string(),
+        url: url.to_string(),
+        title,
+        topic,
+        created_at: Local::now().timestamp_millis(),
+        last_polled_at: None,
+        last_error: None,
+        unread: 0,
+    };
+    let db = AxisDatabase::open(app)?;
+    db.insert_feed(&feed)
+        .map_err(|e| format!("failed to subscribe (already subscribed?): {}", e))?;
+    // 登録時点の記事も取り込んでおく（最初のブリーフィングが空にならないように）
+    let added = store_items(&db, &feed.id, items)?;
+    db.mark_feed_polled(&feed.id, Local::now().timestamp_millis(), None)
+        .map_err(|e| e.to_string())?;
+
+    info!(
+        "📡 [Feeds] subscribed '{}' [{}] ({} items)",
+        feed.title, feed.topic, added
+    );
+    Ok(Feed {
+        unread: added as i64,
+        ..feed
+    })
+}
+
+pub fn unsubscribe(app: &AppHandle, id: &str) -> Result<bool, String> {
+    AxisDatabase::open(app)?
+        .delete_feed(id)
+        .map_err(|e| e.to_string())
+}
+
+pub fn list(app: &AppHandle) -> Result<Vec<Feed>, String> {
+    AxisDatabase::open(app)?
+        .list_feeds()
+        .map_err(|e| e.to_string())
+}
+
+pub fn unread(app: &AppHandle, limit: usize) -> Result<Vec<StoredFeedItem>, String> {
+    AxisDatabase::open(app)?
+        .unread_feed_items(limit)
+        .map_err(|e| e.to_string())
+}
+
+pub async fn poll_all(app: &AppHandle) -> Result<PollReport, String> {
+    let feeds = list(app)?;
+    let mut report = PollReport {
+        feeds: feeds.len(),
+        ..Default::default()
+    };
+    for feed in feeds {
+        let fetched = news::fetch_feed(&feed.url).await;
+        let db = AxisDatabase::open(app)?;
+        let now = Local::now().timestamp_millis();
+        match fetched {
+            Ok(items) => {
+                report.new_items += store_items(&db, &feed.id, items)?;
+                db.mark_feed_polled(&feed.id, now, None)
+                    .map_err(|e| e.to_string())?;
+            }
+            Err(e) => {
+                db.mark_feed_polled(&feed.id, now, Some(&e))
+                    .map_err(|e| e.to_string())?;
+                report.errors.push(format!("{}: {}", feed.title, e));
+            }
+        }
+    }
+    if report.new_items > 0 || !report.errors.is_empty() {
+        info!(
+            "📡 [Feeds] polled {} feeds: {} new, {} errors",
+            report.feeds,
+            report.new_items,
+            report.errors.len()
+        );
+    }
+    Ok(report)
+}
+
+pub fn spawn_poller(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let minutes = settings::current().feed_poll_minutes;
+            if minutes > 0 {
+                if let Err(e) = poll_all(&app).await {
+                    warn!("⚠️ [Feeds] poll failed: {}", e);
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(minutes.max(1) * 60)).await;
+        }
+    });
+}
+
+// トピック → 記事の一覧をプロンプト用の文字列に
+fn format_items(items: &[StoredFeedItem]) -> (Vec<String>, String) {
+    let mut by_topic: BTreeMap<&str, Vec<&StoredFeedItem>> = BTreeMap::new();
+    for item in items {
+        by_topic.entry(item.topic.as_str()).or_default().push(item);
+    }
+    let mut out = String::new();
+    for (topic, list) in &by_topic {
+        out.push_str(&format!("## {}\n", topic));
+        for item in list {
+            let summary: String = item.summary.chars().take(300).collect();
+            out.push_str(&format!(
+                "- {} [{}] ({})\n  {}\n",
+                item.title, item.feed_title, item.link, summary
+            ));
+        }
+        out.push('\n');
+    }
+    (by_topic.keys().map(|t| t.to_string()).collect(), out)
+}
+
+pub async fn run_briefing(app: &AppHandle) -> Result<Briefing, String> {
+    // 直前に新着を取り込んでから
+    if let Err(e) = poll_all(app).await {
+        warn!("⚠️ [Feeds] poll before briefing failed: {}", e);
+    }
+    let items = unread(app, BRIEFING_MAX_ITEMS)?;
+    if items.is_empty() {
+        return Ok(Briefing {
+            session_id: BRIEFING_SESSION.to_string(),
+            items: 0,
+            topics: vec![],
+            text: "未読の記事はありません。".to_string(),
+        });
+    }
+
+    let (topics, listing) = format_items(&items);
+    let date = Local::now().format("%Y-%m-%d (%a)").to_string();
+    let items_text = untrusted::wrap("feed", &listing);
+    let sys = prompts::render(app, "briefing", &[("date", &date), ("items", &items_text)]);
+    let cfg = settings::current();
+    let alias = cfg.briefing_writer.trim().to_lowercase();
+    let request = format!("Write the morning briefing for {}.", date);
+    let text = ai::call_alias(&alias, &cfg.models.for_alias(&alias), &sys, &request).await?;
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("briefing writer returned nothing".to_string());
+    }
+    // 記事から書いたものなので、セッションには囲んだまま残す（後で文脈に戻っても指示として読ませない）
+    let stored = untrusted::wrap("briefing", &text);
+    let db = AxisDatabase::open(app)?;
+    let _ = db.save_interaction(BRIEFING_SESSION, "user", &request);
+    let _ = db.save_interaction(BRIEFING_SESSION, "assistant", &stored);
+    if let Err(e) = memory::save_interaction(
+        app,
+        BRIEFING_SESSION,
+        &request,
+        &stored,
+        "briefing",
+        &alias,
+        vec![],
+    ) {
+        warn!("⚠️ [Feeds] failed to remember the briefing: {}", e);
+    }
+
+    let ids: Vec<i64> = items.iter().map(|i| i.id).collect();
+    db.mark_feed_items_read(&ids).map_err(|e| e.to_string())?;
+
+    // observer の提案と同じ経路で知らせる
+    let _ = app.emit(
+        "axis-observer-event",
+        format!("[Briefing] {}", text.chars().take(400).collect::<String>()),
+    );
+    notify::notify(
+        app,
+        "☀️ Morning briefing",
+        &text,
+        Some(DeepLink::session(BRIEFING_SESSION)),
+    );
+    info!(
+        "☀️ [Feeds] briefing: {} items / {} topics",
+        items.len(),
+        topics.len()
+    );
+    Ok(Briefing {
+        session_id: BRIEFING_SESSION.to_string(),
+        items: items.len(),
+        topics,
+        text,
+    })
+}
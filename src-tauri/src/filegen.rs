@@ -0,0 +1,134 @@
+// src-tauri/src/filegen.rs
+//
+// SAVE: <filename> [mode] ||| <content> の書き込み
+// - mode なし: 新規作成のみ。既にあれば書かずにエラーを返し、モデル（→ ユーザー）にどうするか聞かせる
+// - [overwrite]: 上書き / [append]: 末尾に追記 / [new]: "name (2).ext" のように別名で作る
+// - [patch]: content を unified diff として既存ファイルに当てる（@@ の行番号はずれていても文脈で探す）
+//   どの mode で何を書くかは axis-core の files.rs（ここは xlsx / pdf の組み立てと書き込み）
+// - .xlsx は content を表として xlsx.rs で組み立てる（append / patch は不可）
+// - .pdf は content を markdown として export::render_report → Edge の印刷。セッションの出典を末尾に付ける
+// - 書き込みは undo::write_file を通す（UNDO_LAST で戻せる）
+
+use crate::storage::Source;
+use crate::{export, shell, undo, xlsx};
+use axis_core::files;
+use axis_core::fs::StdFs;
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+pub use axis_core::files::Mode;
+
+pub struct Saved {
+    pub path: PathBuf,
+    pub name: String,
+    pub content: Vec<u8>, // 書いた後のファイルの中身（添付用）
+    pub text: String,     // プレビュー用の文字列（xlsx は表をタブ区切りで）
+    pub mode: Mode,
+    pub replaced: bool, // 既存の中身を置き換えた（UNDO_LAST で戻せる）
+}
+
+// "memo.txt" → "memo (2).txt"（空いている番号まで）
+pub fn unused_name(path: &Path) -> PathBuf {
+    files::unused_name(&StdFs, path)
+}
+
+fn pdf_report(
+    app: &AppHandle,
+    session_id: &str,
+    name: &str,
+    markdown: &str,
+    current: &[Source],
+) -> Result<Vec<u8>, String> {
+    let mut sources = export::session_sources(app, session_id);
+    for s in current {
+        if !sources.iter().any(|o| o.url == s.url) {
+            sources.push(s.clone());
+        }
+    }
+    let title = Path::new(name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let html = export::render_report(&title, markdown, &sources);
+    // undo のジャーナルに中身が要るので一旦 temp に印刷して読む
+    let tmp = std::env::temp_dir().join(format!(
+        "axis-report-{}.pdf",
+        Local::now().timestamp_millis()
+    ));
+    export::html_to_pdf(&html, &tmp)?;
+    let bytes = fs::read(&tmp).map_err(|e| e.to_string());
+    let _ = fs::remove_file(&tmp);
+    bytes
+}
+
+// sources: この応答で集めた出典（前の応答の分はセッションの履歴から）
+pub fn save(
+    app: &AppHandle,
+    session_id: &str,
+    name: &str,
+    mode: Mode,
+    content: &str,
+    sources: &[Source],
+) -> Result<Saved, String> {
+    files::check_name(name)?;
+    let name = name.to_string();
+    let path = shell::desktop_dir().join(&name);
+
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let generated = matches!(ext.as_str(), "xlsx" | "pdf");
+    if generated && matches!(mode, Mode::Append | Mode::Patch) {
+        return Err(format!(
+            "{} files can only be created, overwritten or saved as a new copy",
+            ext
+        ));
+    }
+    let sheets = if ext == "xlsx" {
+        Some(xlsx::parse_sheets(content)?)
+    } else {
+        None
+    };
+    let new_content = |content: &str| match (&sheets, ext.as_str()) {
+        (Some(sheets), _) => xlsx::build(sheets),
+        (None, "pdf") => pdf_report(app, session_id, &name, content, sources),
+        _ => Ok(content.as_bytes().to_vec()),
+    };
+
+    let (path, bytes) = files::plan_write(&StdFs, &path, &name, mode, content, new_content)?;
+
+    let entry = undo::write_file(app, session_id, "SAVE", &path, &bytes)?;
+    Ok(Saved {
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or(name),
+        path,
+        text: match &sheets {
+            Some(sheets) => xlsx::preview(sheets),
+            None if ext == "pdf" => content.to_string(),
+            None => String::from_utf8_lossy(&bytes).to_string(),
+        },
+        content: bytes,
+        mode,
+        replaced: entry.restorable(),
+    })
+}
+
+impl Saved {
+    // system_context に入れる 1-2 行
+    pub fn report(&self) -> String {
+        let mut out = format!(
+            "[System] File saved successfully ({}): {:?}\n",
+            self.mode.label(),
+            self.path
+        );
+        if self.replaced {
+            out.push_str("[System] The previous version can be restored with UNDO_LAST.\n");
+        }
+        out
+    }
+}
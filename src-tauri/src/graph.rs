@@ -0,0 +1,225 @@
+// src-tauri/src/graph.rs
+//
+// 会話から抜き出した事実の小さな知識グラフ（エンティティ + 関係。memory.db の kg_entities / kg_relations）
+// - 抽出: run_axis の保存後に settings.graph_extractor（既定はローカル）が prompts/graph.md で
+//   {"facts": [{"subject", "subject_type", "predicate", "object", "object_type"}]} を返す
+//   読むのは利用者の依頼文だけ（答えには検索結果やメールの文面が混ざるので、覚えた後に素の事実として戻さない）
+//   ローカルで答えた依頼はクラウドの抽出モデルに送らない（settings.graph_enabled は既定で off）
+//   エンティティは名前の小文字で同一視。同じ (主語, 述語, 目的語) がまた出たら mentions を足す
+// - 文脈: 依頼文に名前が語として出てくるエンティティ + "user" の関係を [Known Facts] として Worker に渡す
+// - query_graph / delete_graph_fact: 画面から見る / 間違って覚えたものを消す
+
+use crate::ai;
+use crate::db::AxisDatabase;
+use crate::prompts;
+use crate::settings;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+const MAX_FACTS: usize = 8; // 1 回の抽出で受け取る上限
+const MIN_INPUT_CHARS: usize = 8; // 挨拶などは抜き出さない
+const PROMPT_TEXT_CHARS: usize = 2000;
+const CONTEXT_FACTS: usize = 12;
+const CONTEXT_ENTITIES: usize = 2000; // 依頼文と突き合わせる数（新しい順）
+const USER: &str = "user";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Entity {
+    pub id: i64,
+    pub name: String,
+    pub kind: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Fact {
+    pub id: i64,
+    pub subject: String,
+    pub subject_kind: String,
+    pub predicate: String,
+    pub object: String,
+    pub object_kind: String,
+    pub mentions: i64,
+    pub source_log: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct ExtractedFact {
+    subject: String,
+    subject_type: String,
+    predicate: String,
+    object: String,
+    object_type: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct ExtractReply {
+    facts: Vec<ExtractedFact>,
+}
+
+// "Works At" / "works-at" → "works_at"
+fn normalize_predicate(p: &str) -> String {
+    p.trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn clip(text: &str) -> String {
+    text.chars().take(PROMPT_TEXT_CHARS).collect()
+}
+
+// 覚えた事実の数を返す
+pub async fn extract(app: &AppHandle, log_id: &str, input: &str) -> Result<usize, String> {
+    let cfg = settings::current();
+    let alias = cfg.graph_extractor.trim().to_lowercase();
+    let input = clip(input);
+    let sys = prompts::render(
+        app,
+        "graph",
+        &[("max_facts", &MAX_FACTS.to_string())],
+    );
+    let raw = ai::call_alias(&alias, &cfg.models.for_alias(&alias), &sys, &input).await?;
+    let start = raw.find('{').ok_or("extractor returned no JSON")?;
+    let end = raw.rfind('}').ok_or("extractor returned no JSON")?;
+    let reply: ExtractReply =
+        serde_json::from_str(&raw[start..=end]).map_err(|e| format!("extractor JSON: {}", e))?;
+
+    let db = AxisDatabase::open(app)?;
+    let mut saved = 0;
+    for fact in reply.facts.into_iter().take(MAX_FACTS) {
+        let subject = fact.subject.trim();
+        let object = fact.object.trim();
+        let predicate = normalize_predicate(&fact.predicate);
+        if subject.is_empty() || object.is_empty() || predicate.is_empty() {
+            continue;
+        }
+        let result = db
+            .upsert_kg_entity(subject, fact.subject_type.trim())
+            .and_then(|s| Ok((s, db.upsert_kg_entity(object, fact.object_type.trim())?)))
+            .and_then(|(s, o)| db.add_kg_relation(s, &predicate, o, Some(log_id)));
+        match result {
+            Ok(()) => saved += 1,
+            Err(e) => warn!("⚠️ [Graph] failed to store fact: {}", e),
+        }
+    }
+    Ok(saved)
+}
+
+// run_axis の保存後に呼ぶ（応答は待たせない）。answered_locally: ローカルモデルで答えた依頼
+pub fn spawn_extract(app: AppHandle, log_id: String, input: String, answered_locally: bool) {
+    let cfg = settings::current();
+    if !cfg.graph_enabled || input.trim().chars().count() < MIN_INPUT_CHARS {
+        return;
+    }
+    // ローカルで済ませた依頼をクラウドの抽出モデルに送らない
+    if answered_locally && cfg.graph_extractor.trim().to_lowercase() != "local" {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        match extract(&app, &log_id, &input).await {
+            Ok(n) if n > 0 => info!("🕸️ [Graph] stored {} fact(s) from {}", n, log_id),
+            Ok(_) => {}
+            Err(e) => warn!("⚠️ [Graph] extraction failed for {}: {}", log_id, e),
+        }
+    });
+}
+
+// name が query に語として出てくるか（大文字小文字は区別しない）
+// 英数字どうしが続く所は別の語とみなす（"go" は "google" に当たらない）。日本語は区切りが無いのでそのまま
+fn mentions(query: &str, name: &str) -> bool {
+    if name.chars().count() < 2 {
+        return false;
+    }
+    let joins = |a: Option<char>, b: Option<char>| {
+        matches!((a, b), (Some(a), Some(b)) if a.is_ascii_alphanumeric() && b.is_ascii_alphanumeric())
+    };
+    query.match_indices(name).any(|(at, _)| {
+        let before = query[..at].chars().next_back();
+        let after = query[at + name.len()..].chars().next();
+        !joins(before, name.chars().next()) && !joins(name.chars().next_back(), after)
+    })
+}
+
+// Worker に渡す [Known Facts]。関係のあるものが無ければ空文字
+pub fn build_context(app: &AppHandle, query: &str) -> String {
+    let Ok(db) = AxisDatabase::open(app) else {
+        return String::new();
+    };
+    let query = query.to_lowercase();
+    let mut entities: Vec<Entity> = db
+        .kg_entities(CONTEXT_ENTITIES)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|e| {
+            let name = e.name.to_lowercase();
+            name == USER || mentions(&query, &name)
+        })
+        .collect();
+    // 依頼文に出てきたものを先に（"user" は最後）
+    entities.sort_by_key(|e| e.name.eq_ignore_ascii_case(USER));
+
+    let mut facts: Vec<Fact> = Vec::new();
+    for entity in entities {
+        for fact in db
+            .kg_facts(Some(entity.id), CONTEXT_FACTS)
+            .unwrap_or_default()
+        {
+            if facts.len() >= CONTEXT_FACTS {
+                break;
+            }
+            if !facts.iter().any(|f| f.id == fact.id) {
+                facts.push(fact);
+            }
+        }
+    }
+    if facts.is_empty() {
+        return String::new();
+    }
+    let lines: Vec<String> = facts
+        .iter()
+        .map(|f| format!("- {} {} {}", f.subject, f.predicate, f.object))
+        .collect();
+    format!("\n[Known Facts]\n{}", lines.join("\n"))
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct GraphView {
+    pub entities: Vec<Entity>,
+    pub facts: Vec<Fact>,
+}
+
+// entity を指定したらその名前（大文字小文字は区別しない）の関係だけ
+pub fn query(app: &AppHandle, entity: Option<&str>, limit: usize) -> Result<GraphView, String> {
+    let db = AxisDatabase::open(app)?;
+    let mut entities = db
+        .kg_entities(CONTEXT_ENTITIES)
+        .map_err(|e| e.to_string())?;
+    let facts = match entity.map(str::trim).filter(|e| !e.is_empty()) {
+        Some(name) => {
+            let key = name.to_lowercase();
+            entities.retain(|e| e.name.to_lowercase() == key);
+            match entities.first() {
+                Some(e) => db.kg_facts(Some(e.id), limit),
+                None => Ok(Vec::new()),
+            }
+        }
+        None => db.kg_facts(None, limit),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(GraphView { entities, facts })
+}
+
+pub fn delete_fact(app: &AppHandle, id: i64) -> Result<bool, String> {
+    AxisDatabase::open(app)?
+        .delete_kg_relation(id)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,188 @@
+// src-tauri/src/health.rs
+//
+// プロバイダ / モデル毎の応答の具合（メモリ上の直近分だけ。再起動で消える）
+// - ai.rs / send_llm_request が 1 回呼ぶ毎に record (レイテンシ / 成否 / タイムアウトか / 出力トークン数)
+// - get_provider_health: 直近 WINDOW_MS の p50 / p95 レイテンシ、エラー率、出力トークン/秒
+// - ルーティング: unhealthy なプロバイダは避ける（lib.rs で別のプロバイダかローカルへ）
+//   直近 TIMEOUT_STREAK 回続けてタイムアウト、または DEGRADED_MIN_SAMPLES 件以上でエラー率が DEGRADED_ERROR_RATE 以上
+//   最後の失敗から RETRY_AFTER_MS 経ったら一度通して様子を見る
+
+use chrono::Local;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+const WINDOW_MS: i64 = 60 * 60 * 1000;
+const MAX_SAMPLES: usize = 200; // (プロバイダ, モデル) 毎
+const TIMEOUT_STREAK: usize = 2;
+const DEGRADED_MIN_SAMPLES: usize = 4;
+const DEGRADED_ERROR_RATE: f32 = 0.5;
+const RETRY_AFTER_MS: i64 = 5 * 60 * 1000;
+
+#[derive(Debug, Clone)]
+struct Sample {
+    at: i64,
+    latency_ms: i64,
+    ok: bool,
+    timeout: bool,
+    output_tokens: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProviderHealth {
+    pub provider: String,
+    pub model: String,
+    pub status: String, // ok / degraded / idle（直近の呼び出しなし）
+    pub samples: usize,
+    pub p50_ms: Option<i64>,
+    pub p95_ms: Option<i64>,
+    pub error_rate: f32,
+    pub timeout_rate: f32,
+    pub tokens_per_sec: Option<f32>, // 出力トークンを返すプロバイダだけ
+    pub last_error: Option<String>,
+    pub last_called_at: Option<i64>,
+}
+
+#[derive(Default)]
+struct Stats {
+    samples: VecDeque<Sample>,
+    last_error: Option<String>,
+}
+
+fn stats() -> &'static Mutex<HashMap<(String, String), Stats>> {
+    static STATS: OnceLock<Mutex<HashMap<(String, String), Stats>>> = OnceLock::new();
+    STATS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn record(
+    provider: &str,
+    model: &str,
+    started: Instant,
+    result: &Result<String, String>,
+    output_tokens: Option<u64>,
+) {
+    let Ok(mut all) = stats().lock() else {
+        return;
+    };
+    let entry = all
+        .entry((provider.to_string(), model.to_string()))
+        .or_default();
+    if entry.samples.len() >= MAX_SAMPLES {
+        entry.samples.pop_front();
+    }
+    entry.samples.push_back(Sample {
+        at: Local::now().timestamp_millis(),
+        latency_ms: started.elapsed().as_millis() as i64,
+        ok: result.is_ok(),
+        timeout: matches!(result, Err(e) if crate::ai::is_timeout_error(e)),
+        output_tokens: if result.is_ok() { output_tokens } else { None },
+    });
+    if let Err(e) = result {
+        entry.last_error = Some(e.chars().take(300).collect());
+    }
+}
+
+fn percentile(sorted: &[i64], p: f32) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((sorted.len() as f32 - 1.0) * p).round() as usize;
+    sorted.get(rank).copied()
+}
+
+fn recent(samples: &VecDeque<Sample>, now: i64) -> Vec<&Sample> {
+    samples.iter().filter(|s| now - s.at <= WINDOW_MS).collect()
+}
+
+// 新しい順に見て、避けるべき理由があれば返す
+fn degraded_reason(samples: &[&Sample], now: i64) -> Option<String> {
+    let last_failure = samples.iter().filter(|s| !s.ok).map(|s| s.at).max()?;
+    if now - last_failure > RETRY_AFTER_MS {
+        return None;
+    }
+    let streak = samples.iter().rev().take_while(|s| s.timeout).count();
+    if streak >= TIMEOUT_STREAK {
+        return Some(format!("{} timeouts in a row", streak));
+    }
+    let errors = samples.iter().filter(|s| !s.ok).count();
+    let rate = errors as f32 / samples.len() as f32;
+    if samples.len() >= DEGRADED_MIN_SAMPLES && rate >= DEGRADED_ERROR_RATE {
+        return Some(format!(
+            "{:.0}% errors in the last {} calls",
+            rate * 100.0,
+            samples.len()
+        ));
+    }
+    None
+}
+
+fn summarize(provider: &str, model: &str, stats: &Stats, now: i64) -> ProviderHealth {
+    let samples = recent(&stats.samples, now);
+    let n = samples.len();
+    let mut latencies: Vec<i64> = samples
+        .iter()
+        .filter(|s| s.ok)
+        .map(|s| s.latency_ms)
+        .collect();
+    latencies.sort_unstable();
+    let rate = |f: fn(&Sample) -> bool| {
+        if n == 0 {
+            0.0
+        } else {
+            samples.iter().filter(|s| f(s)).count() as f32 / n as f32
+        }
+    };
+    // トークン/秒は出力トークン数が分かった呼び出しだけで計算
+    let (tokens, ms) = samples
+        .iter()
+        .filter_map(|s| s.output_tokens.map(|t| (t, s.latency_ms)))
+        .fold((0u64, 0i64), |(t, m), (st, sm)| (t + st, m + sm));
+    ProviderHealth {
+        provider: provider.to_string(),
+        model: model.to_string(),
+        status: if n == 0 {
+            "idle"
+        } else if degraded_reason(&samples, now).is_some() {
+            "degraded"
+        } else {
+            "ok"
+        }
+        .to_string(),
+        samples: n,
+        p50_ms: percentile(&latencies, 0.5),
+        p95_ms: percentile(&latencies, 0.95),
+        error_rate: rate(|s| !s.ok),
+        timeout_rate: rate(|s| s.timeout),
+        tokens_per_sec: (ms > 0).then(|| tokens as f32 * 1000.0 / ms as f32),
+        last_error: stats.last_error.clone(),
+        last_called_at: stats.samples.back().map(|s| s.at),
+    }
+}
+
+// get_provider_health: 呼んだことのある (プロバイダ, モデル) の一覧
+pub fn snapshot() -> Vec<ProviderHealth> {
+    let now = Local::now().timestamp_millis();
+    let Ok(all) = stats().lock() else {
+        return Vec::new();
+    };
+    let mut out: Vec<ProviderHealth> = all
+        .iter()
+        .map(|((provider, model), s)| summarize(provider, model, s, now))
+        .collect();
+    out.sort_by(|a, b| (&a.provider, &a.model).cmp(&(&b.provider, &b.model)));
+    out
+}
+
+// ルーティング用: プロバイダ単位（モデルをまたいで時刻順に並べて）で判定
+pub fn unhealthy(provider: &str) -> Option<String> {
+    let now = Local::now().timestamp_millis();
+    let all = stats().lock().ok()?;
+    let mut samples: Vec<&Sample> = all
+        .iter()
+        .filter(|((p, _), _)| p == provider)
+        .flat_map(|(_, s)| recent(&s.samples, now))
+        .collect();
+    samples.sort_by_key(|s| s.at);
+    degraded_reason(&samples, now)
+}
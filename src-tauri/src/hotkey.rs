@@ -0,0 +1,197 @@
+// src-tauri/src/hotkey.rs
+//
+// グローバルホットキー（どのアプリを使っていても Axis を呼び出す）
+// - キー: settings.hotkey（既定 "Ctrl+Alt+Space"、空文字で無効）。設定が変わったら登録し直す
+// - 登録と押下の受け取りは tauri-plugin-global-shortcut（Windows / macOS / Linux の X11）
+// - 押されたら quick-ask ウィンドウ (label "quick") を出して前面に。入力は quick_ask (quick.rs) へ
+// - 押した時に前面だったウィンドウは、quick-ask を出す前に quick.rs が文脈として控える（Windows のみ）
+// - ウェイクワード（音声）は未対応
+
+use crate::quick;
+use crate::settings;
+use std::sync::Mutex;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Listener, Manager, WebviewUrl, WebviewWindowBuilder, Wry};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+use tracing::{info, warn};
+
+pub const QUICK_WINDOW: &str = "quick";
+const QUICK_TITLE: &str = "Axis Quick Ask";
+
+// 登録中の settings.hotkey（変わった時だけ登録し直す）
+static REGISTERED: Mutex<Option<String>> = Mutex::new(None);
+
+// "Ctrl+Alt+Space" → Shortcut
+pub fn parse_hotkey(spec: &str) -> Result<Shortcut, String> {
+    let mut mods = Modifiers::empty();
+    let mut key = None;
+    for part in spec.split('+').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let lower = part.to_lowercase();
+        match lower.as_str() {
+            "ctrl" | "control" => mods |= Modifiers::CONTROL,
+            "alt" | "option" => mods |= Modifiers::ALT,
+            "shift" => mods |= Modifiers::SHIFT,
+            "win" | "super" | "meta" | "cmd" => mods |= Modifiers::SUPER,
+            _ if key.is_some() => return Err(format!("hotkey '{}' has more than one key", spec)),
+            _ => key = Some(key_code(&lower).ok_or(format!("unknown key '{}'", part))?),
+        }
+    }
+    let key = key.ok_or(format!("hotkey '{}' has no key", spec))?;
+    if mods.is_empty() {
+        return Err(format!(
+            "hotkey '{}' needs a modifier (Ctrl / Alt / Shift / Win)",
+            spec
+        ));
+    }
+    Ok(Shortcut::new(Some(mods), key))
+}
+
+fn key_code(name: &str) -> Option<Code> {
+    let code = match name {
+        "space" => Code::Space,
+        "enter" | "return" => Code::Enter,
+        "tab" => Code::Tab,
+        "esc" | "escape" => Code::Escape,
+        "backspace" => Code::Backspace,
+        _ => {
+            let mut chars = name.chars();
+            let code = match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphabetic() => {
+                    format!("Key{}", c.to_ascii_uppercase())
+                }
+                (Some(c), None) if c.is_ascii_digit() => format!("Digit{}", c),
+                (Some('f'), Some(_)) => {
+                    let n: u32 = name[1..].parse().ok()?;
+                    if !(1..=24).contains(&n) {
+                        return None;
+                    }
+                    format!("F{}", n)
+                }
+                _ => return None,
+            };
+            return code.parse().ok();
+        }
+    };
+    Some(code)
+}
+
+// quick-ask ウィンドウを出す（無ければ作る）
+pub fn show_quick_window(app: &AppHandle) {
+    if let Some(win) = app.get_webview_window(QUICK_WINDOW) {
+        let _ = win.show();
+        let _ = win.unminimize();
+        let _ = win.set_focus();
+        return;
+    }
+    let built = WebviewWindowBuilder::new(
+        app,
+        QUICK_WINDOW,
+        WebviewUrl::App("index.html?quick=1".into()),
+    )
+    .title(QUICK_TITLE)
+    .inner_size(640.0, 160.0)
+    .decorations(false)
+    .transparent(true)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .resizable(false)
+    .center()
+    .focused(true)
+    .build();
+    if let Err(e) = built {
+        warn!("⚠️ [Hotkey] failed to open quick-ask window: {}", e);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId,
+    };
+
+    // 前面ウィンドウの (タイトル, プロセス名)。observer の PowerShell 版より速いのでこちらを使う
+    pub fn foreground_window() -> Option<(String, String)> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        let mut buf = [0u16; 512];
+        let len = unsafe { GetWindowTextW(hwnd, &mut buf) }.max(0) as usize;
+        let title = String::from_utf16_lossy(&buf[..len]);
+
+        let mut pid = 0u32;
+        unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+        let mut sys = sysinfo::System::new();
+        let pid = sysinfo::Pid::from_u32(pid);
+        sys.refresh_process(pid);
+        let app = sys
+            .process(pid)
+            .map(|p| p.name().trim_end_matches(".exe").to_string())
+            .unwrap_or_default();
+        Some((title, app))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    // 前面ウィンドウは控えない（quick-ask は文脈なしで開く）
+    pub fn foreground_window() -> Option<(String, String)> {
+        None
+    }
+}
+
+fn on_pressed(app: &AppHandle) {
+    if let Some((title, app_name)) = platform::foreground_window() {
+        // quick-ask 自身が前面の時（2 回押した時）は文脈を上書きしない
+        if title != QUICK_TITLE {
+            quick::capture(app, title, app_name);
+        }
+    }
+    show_quick_window(app);
+}
+
+fn register(app: &AppHandle, spec: &str) {
+    let Ok(mut registered) = REGISTERED.lock() else {
+        return;
+    };
+    if registered.as_deref() == Some(spec) {
+        return;
+    }
+    let shortcuts = app.global_shortcut();
+    // Axis が登録するのはこの 1 つだけ
+    let _ = shortcuts.unregister_all();
+    *registered = Some(spec.to_string());
+    if spec.trim().is_empty() {
+        info!("⌨️ [Hotkey] disabled");
+        return;
+    }
+    let shortcut = match parse_hotkey(spec) {
+        Ok(s) => s,
+        Err(e) => {
+            warn!("⚠️ [Hotkey] {}", e);
+            return;
+        }
+    };
+    match shortcuts.register(shortcut) {
+        Ok(()) => info!("⌨️ [Hotkey] registered {}", spec),
+        // 他のアプリが同じキーを使っている
+        Err(e) => warn!("⚠️ [Hotkey] failed to register {}: {}", spec, e),
+    }
+}
+
+// tauri::Builder に足す。押された（離した時ではなく）ら quick-ask
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                on_pressed(app);
+            }
+        })
+        .build()
+}
+
+// 起動時（setup）に呼ぶ
+pub fn init(app: &AppHandle) {
+    register(app, &settings::current().hotkey);
+    let handle = app.clone();
+    app.listen("axis-settings-changed", move |_| {
+        register(&handle, &settings::current().hotkey);
+    });
+}
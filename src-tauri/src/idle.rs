@@ -0,0 +1,80 @@
+// src-tauri/src/idle.rs
+//
+// 無操作時間（最後のキー / マウス入力からのミリ秒）。observer.rs の離席判定
+// - Windows: GetLastInputInfo (win32.rs)
+// - macOS: ioreg の IOHIDSystem / HIDIdleTime（ナノ秒）
+// - Linux: GNOME（Wayland でも）は Mutter の IdleMonitor を gdbus で。それ以外 (X11) は xprintidle
+// - どれも取れない時は None → observer は「ずっと在席」として扱う
+//   （離席で提案を止めない / タイムラインに空白区間を入れない / 復帰の挨拶をしない）。最初の 1 回だけログに残す
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::warn;
+
+static WARNED: AtomicBool = AtomicBool::new(false);
+
+pub fn idle_ms() -> Option<u64> {
+    let ms = crate::win32::idle_ms().or_else(platform_idle_ms);
+    if ms.is_none() && !WARNED.swap(true, Ordering::Relaxed) {
+        warn!("⚠️ [Idle] idle time is unavailable on this system; away detection is off");
+    }
+    ms
+}
+
+#[cfg(target_os = "windows")]
+fn platform_idle_ms() -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn platform_idle_ms() -> Option<u64> {
+    // "HIDIdleTime" = 1234567890
+    let out = std::process::Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    let ns = text
+        .lines()
+        .find(|l| l.contains("\"HIDIdleTime\""))
+        .and_then(|l| l.rsplit('=').next())
+        .and_then(|v| v.trim().parse::<u64>().ok())?;
+    Some(ns / 1_000_000)
+}
+
+#[cfg(target_os = "linux")]
+fn platform_idle_ms() -> Option<u64> {
+    let run = |cmd: &str, args: &[&str]| {
+        std::process::Command::new(cmd)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    };
+    // "(uint64 12345,)"
+    let mutter = run(
+        "gdbus",
+        &[
+            "call",
+            "--session",
+            "--dest",
+            "org.gnome.Mutter.IdleMonitor",
+            "--object-path",
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            "--method",
+            "org.gnome.Mutter.IdleMonitor.GetIdletime",
+        ],
+    )
+    .and_then(|s| {
+        s.trim_start_matches("(uint64 ")
+            .trim_end_matches(",)")
+            .parse()
+            .ok()
+    });
+    mutter.or_else(|| run("xprintidle", &[]).and_then(|s| s.parse().ok()))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn platform_idle_ms() -> Option<u64> {
+    None
+}
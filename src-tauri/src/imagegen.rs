@@ -0,0 +1,225 @@
+// src-tauri/src/imagegen.rs
+//
+// 画像の生成 (IMAGE_GEN: <prompt> [||| <縦横比>])
+// - settings.image_provider で選ぶ: "openai"（DALL·E / gpt-image）/ "imagen"（Google。キーは gemini）/ "stability"
+// - 出来た PNG は settings.image_folder に書き出し（undo::write_file 経由。UNDO_LAST で消せる）、オブジェクトストアにも（adapter の saved）
+// - 料金は 1 枚あたりの単価で budget.rs に。利用額 / 具合はそれぞれ gpt / gemini / stability のものとして数える
+// - 送った prompt は sharing.rs に残る。ローカル専用モードでは使えない
+
+use crate::filegen::{self, Mode, Saved};
+use crate::{ai, budget, health, secrets, settings, sharing, shell, undo};
+use base64::Engine as _;
+use chrono::Local;
+use serde_json::json;
+use std::fs;
+use std::time::Instant;
+use tauri::AppHandle;
+use tracing::info;
+
+// (モデル名の前方一致, USD / 枚)。標準画質 1024px 前後の値
+const PRICES: &[(&str, f64)] = &[
+    ("dall-e-3", 0.04),
+    ("dall-e-2", 0.02),
+    ("gpt-image-1", 0.04),
+    ("imagen-4.0-ultra", 0.06),
+    ("imagen-4.0-fast", 0.02),
+    ("imagen", 0.04),
+    ("ultra", 0.08),
+    ("sd3", 0.065),
+    ("core", 0.03),
+];
+
+pub async fn generate(
+    app: &AppHandle,
+    session_id: &str,
+    prompt: &str,
+    aspect: Option<&str>,
+) -> Result<Saved, String> {
+    if crate::local_only::enabled() {
+        return Err("local-only mode: IMAGE_GEN is unavailable".to_string());
+    }
+    let cfg = settings::current();
+    let aspect = aspect.unwrap_or(&cfg.image_aspect);
+    let (budget_name, model) = match cfg.image_provider.as_str() {
+        "openai" => ("gpt", cfg.image_models.openai.clone()),
+        "imagen" => ("gemini", cfg.image_models.imagen.clone()),
+        "stability" => ("stability", cfg.image_models.stability.clone()),
+        other => {
+            return Err(format!(
+                "unknown image_provider '{}': use openai / imagen / stability",
+                other
+            ))
+        }
+    };
+    if let Some((_, why)) = crate::provider_unavailable(budget_name) {
+        return Err(why);
+    }
+
+    info!(
+        prompt = %prompt,
+        "🎨 [ImageGen] {} ({}) {}",
+        cfg.image_provider, model, aspect
+    );
+    sharing::record(budget_name, &model, "", prompt, (0, 0));
+    let started = Instant::now();
+    let result = match cfg.image_provider.as_str() {
+        "openai" => openai(&model, prompt, aspect).await,
+        "imagen" => imagen(&model, prompt, aspect).await,
+        _ => stability(&model, prompt, aspect).await,
+    };
+    health::record(
+        budget_name,
+        &model,
+        started,
+        &result
+            .as_ref()
+            .map(|_| String::new())
+            .map_err(|e| e.clone()),
+        None,
+    );
+    let png = result?;
+    budget::record_usd(budget_name, &model, price(&model));
+
+    let folder = match cfg.image_folder.trim() {
+        "" => shell::desktop_dir(),
+        f => shell::resolve_user_path(f),
+    };
+    fs::create_dir_all(&folder).map_err(|e| format!("{}: {}", folder.display(), e))?;
+    let path = filegen::unused_name(&folder.join(file_name(prompt)));
+    undo::write_file(app, session_id, "IMAGE_GEN", &path, &png)?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(Saved {
+        path,
+        name,
+        text: format!("[image {}] {}", aspect, prompt),
+        content: png,
+        mode: Mode::Create,
+        replaced: false,
+    })
+}
+
+fn price(model: &str) -> f64 {
+    PRICES
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map(|(_, usd)| *usd)
+        .unwrap_or(0.0)
+}
+
+// "Thumbnail for my Rust post" → "thumbnail-for-my-rust-post-20261014-153000.png"
+fn file_name(prompt: &str) -> String {
+    let slug: Vec<String> = prompt
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(6)
+        .map(|w| w.to_lowercase())
+        .collect();
+    let stem = if slug.is_empty() {
+        "image".to_string()
+    } else {
+        slug.join("-")
+    };
+    format!("{}-{}.png", stem, Local::now().format("%Y%m%d-%H%M%S"))
+}
+
+fn decode(b64: Option<&str>, body: &str) -> Result<Vec<u8>, String> {
+    let b64 = b64.ok_or_else(|| format!("No image in response: {}", truncate(body)))?;
+    base64::engine::general_purpose::STANDARD
+        .decode(b64)
+        .map_err(|e| format!("Image decode error: {}", e))
+}
+
+fn truncate(body: &str) -> String {
+    body.chars().take(300).collect()
+}
+
+async fn send(provider: &str, req: reqwest::RequestBuilder) -> Result<serde_json::Value, String> {
+    ai::acquire(provider).await;
+    let res = req
+        .send()
+        .await
+        .map_err(|e| ai::describe_request_error(provider, &e))?;
+    let status = res.status();
+    let text = res
+        .text()
+        .await
+        .map_err(|e| ai::describe_request_error(provider, &e))?;
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        ai::throttled(provider);
+    }
+    if !status.is_success() {
+        return Err(format!("Image API Error [{}]: {}", status, truncate(&text)));
+    }
+    serde_json::from_str(&text).map_err(|e| format!("JSON Parse Error: {}", e))
+}
+
+// DALL·E 3 と gpt-image では横長 / 縦長の大きさが違う
+async fn openai(model: &str, prompt: &str, aspect: &str) -> Result<Vec<u8>, String> {
+    let key = secrets::require_api_key("gpt")?;
+    let gpt_image = model.starts_with("gpt-image");
+    let size = match (aspect, gpt_image) {
+        ("16:9", true) => "1536x1024",
+        ("9:16", true) => "1024x1536",
+        ("16:9", false) => "1792x1024",
+        ("9:16", false) => "1024x1792",
+        _ => "1024x1024",
+    };
+    let mut body = json!({ "model": model, "prompt": prompt, "n": 1, "size": size });
+    // gpt-image は常に base64 で返す（response_format を付けると弾かれる）
+    if !gpt_image {
+        body["response_format"] = json!("b64_json");
+    }
+    let req = ai::client_for("gpt")?
+        .post("https://api.openai.com/v1/images/generations")
+        .bearer_auth(&key)
+        .json(&body);
+    let json = send("gpt", req).await?;
+    decode(json["data"][0]["b64_json"].as_str(), &json.to_string())
+}
+
+async fn imagen(model: &str, prompt: &str, aspect: &str) -> Result<Vec<u8>, String> {
+    let key = secrets::require_api_key("gemini")?;
+    let body = json!({
+        "instances": [{ "prompt": prompt }],
+        "parameters": { "sampleCount": 1, "aspectRatio": aspect }
+    });
+    let req = ai::client_for("gemini")?
+        .post(format!(
+            "https://generativelanguage.googleapis.com/v1beta/models/{}:predict",
+            model
+        ))
+        .header("x-goog-api-key", &key)
+        .json(&body);
+    let json = send("gemini", req).await?;
+    decode(
+        json["predictions"][0]["bytesBase64Encoded"].as_str(),
+        &json.to_string(),
+    )
+}
+
+// v2beta は multipart/form-data だけを受け付ける
+async fn stability(model: &str, prompt: &str, aspect: &str) -> Result<Vec<u8>, String> {
+    let key = secrets::require_api_key("stability")?;
+    let (content_type, body) = ai::multipart(
+        &[
+            ("prompt", prompt),
+            ("aspect_ratio", aspect),
+            ("output_format", "png"),
+        ],
+        None,
+    );
+    let req = ai::client_for("stability")?
+        .post(format!(
+            "https://api.stability.ai/v2beta/stable-image/generate/{}",
+            model
+        ))
+        .bearer_auth(&key)
+        .header("Accept", "application/json")
+        .header("Content-Type", content_type)
+        .body(body);
+    let json = send("stability", req).await?;
+    decode(json["image"].as_str(), &json.to_string())
+}
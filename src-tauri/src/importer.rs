@@ -0,0 +1,463 @@
+// src-tauri/src/importer.rs
+//
+// 他のアシスタントの会話履歴を取り込む
+// - 対応: ChatGPT のエクスポート (conversations.json / それを含む zip)
+//         Claude のエクスポート (conversations.json: chat_messages 形式)
+//         汎用形式 [{ "title", "messages": [{ "role", "content", "timestamp"? }] }]
+// - 1 会話 = 1 セッション (session_id = "import-<source>-<会話 id>")
+// - 依頼 → 回答を 1 往復として history.json / memory.db (messages + FTS) / Axis メモリに入れる
+// - ID は元の会話 id から作るので、同じファイルをもう一度取り込んでも重複しない
+//   id はファイル名 / セッション id になるので、英数字と - _ 以外が入っていたらハッシュにする
+// - memory.db (会話 + sqlite のメモリ) は 1 トランザクション。メモリのファイル (json) と history.json を書いてから commit
+//   どこかで失敗したら書いたファイルを消し、history.json を戻す（途中まで入った状態を残さない）
+// - 読むのは .json / .zip だけ、MAX_EXPORT_BYTES まで
+
+use crate::db::AxisDatabase;
+use crate::memory::{self, MemoryMeta};
+use crate::objects;
+use crate::storage::{self, AxisToken, InteractionLog};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tracing::{info, warn};
+use zip::ZipArchive;
+
+const MAX_EXPORT_BYTES: u64 = 512 * 1024 * 1024;
+const MAX_ID_CHARS: usize = 64;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ConversationImportReport {
+    pub source: String, // "chatgpt" / "claude" / "generic"
+    pub conversations: usize,
+    pub turns: usize,
+    pub skipped: usize, // 取り込み済みの会話
+}
+
+#[derive(Debug, Clone, Default)]
+struct Turn {
+    user: String,
+    assistant: String,
+    at: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Conversation {
+    id: String,
+    title: String,
+    turns: Vec<Turn>,
+}
+
+// ---------- 読み込み ----------
+
+fn read_export(path: &Path) -> Result<String, String> {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if ext != "zip" && ext != "json" {
+        return Err(format!("{}: expected a .json or .zip export", path.display()));
+    }
+    let meta = fs::metadata(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if !meta.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+    let too_large = |bytes: u64| {
+        format!(
+            "{}: export is too large ({} MB, max {} MB)",
+            path.display(),
+            bytes / 1024 / 1024,
+            MAX_EXPORT_BYTES / 1024 / 1024
+        )
+    };
+    let mut s = String::new();
+    if ext == "json" {
+        if meta.len() > MAX_EXPORT_BYTES {
+            return Err(too_large(meta.len()));
+        }
+        File::open(path)
+            .and_then(|f| f.take(MAX_EXPORT_BYTES).read_to_string(&mut s))
+            .map_err(|e| e.to_string())?;
+        return Ok(s);
+    }
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("Archive Error: {}", e))?;
+    let entry = zip
+        .by_name("conversations.json")
+        .map_err(|_| "conversations.json not found in the archive".to_string())?;
+    if entry.size() > MAX_EXPORT_BYTES {
+        return Err(too_large(entry.size()));
+    }
+    entry
+        .take(MAX_EXPORT_BYTES)
+        .read_to_string(&mut s)
+        .map_err(|e| e.to_string())?;
+    Ok(s)
+}
+
+// 元の会話 id はメモリのファイル名やセッション id になる
+fn safe_id(raw: &str) -> String {
+    let ok = !raw.is_empty()
+        && raw.len() <= MAX_ID_CHARS
+        && raw
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if ok {
+        raw.to_string()
+    } else {
+        objects::hash(raw.as_bytes())[..32].to_string()
+    }
+}
+
+fn millis(v: Option<&Value>) -> Option<i64> {
+    match v? {
+        // ChatGPT: UNIX 秒 (小数)
+        Value::Number(n) => n.as_f64().map(|s| (s * 1000.0) as i64),
+        // Claude: ISO8601
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|t| t.timestamp_millis()),
+        _ => None,
+    }
+}
+
+fn text_of(v: Option<&Value>) -> String {
+    match v {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|p| match p {
+                Value::String(s) => Some(s.clone()),
+                Value::Object(_) => p.get("text").and_then(|t| t.as_str()).map(str::to_string),
+                _ => None,
+            })
+            .filter(|s| !s.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+// (role, text, at) の並びを 1 往復ずつにまとめる
+fn to_turns(messages: Vec<(String, String, Option<i64>)>, fallback_at: i64) -> Vec<Turn> {
+    let mut turns: Vec<Turn> = Vec::new();
+    let mut last_at = fallback_at;
+    for (role, text, at) in messages {
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+        let at = at.unwrap_or(last_at);
+        last_at = at;
+        match role.as_str() {
+            "user" | "human" => turns.push(Turn {
+                user: text,
+                assistant: String::new(),
+                at,
+            }),
+            "assistant" | "model" => match turns.last_mut() {
+                Some(t) if t.assistant.is_empty() => t.assistant = text,
+                Some(t) => t.assistant = format!("{}\n\n{}", t.assistant, text),
+                None => turns.push(Turn {
+                    user: String::new(),
+                    assistant: text,
+                    at,
+                }),
+            },
+            _ => {} // system / tool は取り込まない
+        }
+    }
+    turns
+}
+
+// ChatGPT: mapping は木構造。current_node から親を辿った枝が画面に出ていた会話
+fn parse_chatgpt(conv: &Value) -> Option<Conversation> {
+    let mapping = conv.get("mapping")?.as_object()?;
+    let mut node_id = conv
+        .get("current_node")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let mut chain = Vec::new();
+    while let Some(id) = node_id {
+        let node = mapping.get(&id)?;
+        if let Some(msg) = node.get("message").filter(|m| !m.is_null()) {
+            let role = msg
+                .pointer("/author/role")
+                .and_then(|r| r.as_str())
+                .unwrap_or("")
+                .to_string();
+            let content = msg.get("content");
+            let mut text = text_of(content.and_then(|c| c.get("parts")));
+            if text.is_empty() {
+                text = text_of(content.and_then(|c| c.get("text")));
+            }
+            chain.push((role, text, millis(msg.get("create_time"))));
+        }
+        node_id = node
+            .get("parent")
+            .and_then(|p| p.as_str())
+            .map(str::to_string);
+    }
+    chain.reverse();
+
+    let created =
+        millis(conv.get("create_time")).unwrap_or_else(|| Local::now().timestamp_millis());
+    Some(Conversation {
+        id: conv
+            .get("id")
+            .or_else(|| conv.get("conversation_id"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| created.to_string()),
+        title: conv
+            .get("title")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        turns: to_turns(chain, created),
+    })
+}
+
+// Claude: chat_messages に sender = human / assistant
+fn parse_claude(conv: &Value) -> Option<Conversation> {
+    let messages = conv.get("chat_messages")?.as_array()?;
+    let created = millis(conv.get("created_at")).unwrap_or_else(|| Local::now().timestamp_millis());
+    let list = messages
+        .iter()
+        .map(|m| {
+            let role = m
+                .get("sender")
+                .and_then(|s| s.as_str())
+                .unwrap_or("")
+                .to_string();
+            let mut text = text_of(m.get("text"));
+            if text.is_empty() {
+                text = text_of(m.get("content"));
+            }
+            (role, text, millis(m.get("created_at")))
+        })
+        .collect();
+    Some(Conversation {
+        id: conv
+            .get("uuid")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| created.to_string()),
+        title: conv
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        turns: to_turns(list, created),
+    })
+}
+
+// 汎用: messages に role / content
+fn parse_generic(conv: &Value, index: usize) -> Option<Conversation> {
+    let messages = conv.get("messages")?.as_array()?;
+    let created = millis(conv.get("created_at").or_else(|| conv.get("create_time")))
+        .unwrap_or_else(|| Local::now().timestamp_millis());
+    let list = messages
+        .iter()
+        .map(|m| {
+            (
+                m.get("role")
+                    .and_then(|s| s.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                text_of(m.get("content")),
+                millis(m.get("timestamp").or_else(|| m.get("created_at"))),
+            )
+        })
+        .collect();
+    Some(Conversation {
+        id: conv
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}-{}", created, index)),
+        title: conv
+            .get("title")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        turns: to_turns(list, created),
+    })
+}
+
+fn parse(raw: &str) -> Result<(&'static str, Vec<Conversation>), String> {
+    let v: Value = serde_json::from_str(raw).map_err(|e| format!("invalid export JSON: {}", e))?;
+    let list = match v {
+        Value::Array(a) => a,
+        obj @ Value::Object(_) => vec![obj],
+        _ => return Err("export must be a JSON array of conversations".to_string()),
+    };
+    let Some(first) = list.first() else {
+        return Ok(("generic", vec![]));
+    };
+
+    let (source, parsed): (&'static str, Vec<Option<Conversation>>) =
+        if first.get("mapping").is_some() {
+            ("chatgpt", list.iter().map(parse_chatgpt).collect())
+        } else if first.get("chat_messages").is_some() {
+            ("claude", list.iter().map(parse_claude).collect())
+        } else if first.get("messages").is_some() {
+            (
+                "generic",
+                list.iter()
+                    .enumerate()
+                    .map(|(i, c)| parse_generic(c, i))
+                    .collect(),
+            )
+        } else {
+            return Err(
+                "unrecognized export format (expected ChatGPT or Claude conversations.json)"
+                    .to_string(),
+            );
+        };
+    Ok((
+        source,
+        parsed
+            .into_iter()
+            .flatten()
+            .filter(|c| !c.turns.is_empty())
+            .collect(),
+    ))
+}
+
+// ---------- 取り込み ----------
+
+fn provider_label(source: &str) -> &'static str {
+    match source {
+        "chatgpt" => "ChatGPT (import)",
+        "claude" => "Claude (import)",
+        _ => "Import",
+    }
+}
+
+// resume.rs も使う
+pub fn tokens(text: &str, at: i64) -> Vec<AxisToken> {
+    text.split_whitespace()
+        .enumerate()
+        .map(|(i, t)| AxisToken {
+            id: format!("{}-{}", at, i),
+            text: t.to_string(),
+            timestamp: at,
+            tags: vec![],
+        })
+        .collect()
+}
+
+pub fn import_conversations(
+    app: &AppHandle,
+    path: &str,
+) -> Result<ConversationImportReport, String> {
+    let raw = read_export(Path::new(path.trim()))?;
+    let (source, conversations) = parse(&raw)?;
+    let provider = provider_label(source);
+    let db = AxisDatabase::open(app)?;
+    let tx = db.begin_import().map_err(|e| e.to_string())?;
+
+    let mut report = ConversationImportReport {
+        source: source.to_string(),
+        ..Default::default()
+    };
+    let mut logs = Vec::new();
+    let mut metas: Vec<MemoryMeta> = Vec::new();
+    let mut written = Vec::new();
+    let remove_written = |written: &[PathBuf]| {
+        for p in written {
+            if let Err(e) = fs::remove_file(p) {
+                warn!("⚠️ [Import] failed to remove {}: {}", p.display(), e);
+            }
+        }
+    };
+
+    let mut add = |conv: &Conversation,
+                   report: &mut ConversationImportReport,
+                   written: &mut Vec<PathBuf>|
+     -> Result<(), String> {
+        let session_id = format!("import-{}-{}", source, safe_id(&conv.id));
+        let messages: Vec<(&str, &str, i64)> = conv
+            .turns
+            .iter()
+            .flat_map(|t| {
+                [
+                    ("user", t.user.as_str(), t.at),
+                    ("assistant", t.assistant.as_str(), t.at),
+                ]
+            })
+            .filter(|(_, text, _)| !text.is_empty())
+            .collect();
+        let title = if conv.title.trim().is_empty() {
+            format!("{} import", provider)
+        } else {
+            conv.title.clone()
+        };
+        // 取り込み済みのセッションは飛ばす
+        if !AxisDatabase::insert_conversation(&tx, &session_id, &title, &messages)
+            .map_err(|e| e.to_string())?
+        {
+            report.skipped += 1;
+            return Ok(());
+        }
+
+        for (i, turn) in conv.turns.iter().enumerate() {
+            let log_id = format!("{}-{}", session_id, i);
+            logs.push(InteractionLog {
+                id: log_id.clone(),
+                session_id: session_id.clone(),
+                timestamp: turn.at,
+                user_tokens: tokens(&turn.user, turn.at),
+                ai_response: turn.assistant.clone(),
+                provider_used: provider.to_string(),
+                feedback: None,
+                meta: None,
+                branch_of: None,
+            });
+            let (entry, meta) = memory::import_entry(
+                &log_id,
+                &session_id,
+                turn.at,
+                &turn.user,
+                &turn.assistant,
+                provider,
+            );
+            memory::write_in(app, &tx, &entry, &meta, written)?;
+            metas.push(meta);
+            report.turns += 1;
+        }
+        report.conversations += 1;
+        Ok(())
+    };
+    for conv in &conversations {
+        if let Err(e) = add(conv, &mut report, &mut written) {
+            remove_written(&written);
+            return Err(format!("import failed, nothing was imported: {}", e));
+        }
+    }
+
+    // history.json → commit（失敗したら history.json とメモリのファイルを戻す）
+    let before = storage::get_all_logs(app)?;
+    if let Err(e) = storage::merge_logs(app, logs) {
+        remove_written(&written);
+        return Err(format!("import failed, nothing was imported: {}", e));
+    }
+    if let Err(e) = tx.commit() {
+        if let Err(restore) = storage::replace_all_logs(app, &before) {
+            warn!("⚠️ [Import] failed to restore history.json: {}", restore);
+        }
+        remove_written(&written);
+        return Err(format!("import failed, nothing was imported: {}", e));
+    }
+    memory::index_entries(app, &metas);
+
+    info!(
+        "📥 [Import] {}: {} conversations, {} turns ({} already imported)",
+        source, report.conversations, report.turns, report.skipped
+    );
+    Ok(report)
+}
@@ -0,0 +1,80 @@
+// src-tauri/src/incognito.rs
+//
+// シークレットセッション（人に見られたくない一回きりの質問用）
+// - start_incognito_session で新しく始めるか、set_session_incognito で今のセッションを切り替える
+// - run_axis は history.json / memory.db / Axis メモリのどれにも書かず、メモリ・過去ログの recall もしない
+//   （trace / 実績 / 自動タグ / 知識グラフも同じく省く）
+// - セッション内の続きの会話のために、やり取りはこのプロセスのメモリにだけ持つ
+//   set_session_incognito で戻す / アプリ終了で消える
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tracing::info;
+use uuid::Uuid;
+
+// 文脈に使う直近の往復（run_axis の履歴と同じ）
+const MAX_TURNS: usize = 5;
+
+// (User, Axis) の往復
+type Turns = Vec<(String, String)>;
+
+// session_id → Turns
+fn sessions() -> &'static Mutex<HashMap<String, Turns>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<String, Turns>>> = OnceLock::new();
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn is_active(session_id: &str) -> bool {
+    sessions()
+        .lock()
+        .map(|s| s.contains_key(session_id))
+        .unwrap_or(false)
+}
+
+pub fn start() -> String {
+    let session_id = Uuid::new_v4().to_string();
+    set(&session_id, true);
+    session_id
+}
+
+// 戻したらそれまでのやり取りは捨てる（後から保存はしない）
+pub fn set(session_id: &str, enabled: bool) {
+    let Ok(mut sessions) = sessions().lock() else {
+        return;
+    };
+    if enabled {
+        sessions.entry(session_id.to_string()).or_default();
+    } else {
+        sessions.remove(session_id);
+    }
+    info!(
+        "🕶️ [Incognito] session {} {}",
+        session_id,
+        if enabled { "on" } else { "off" }
+    );
+}
+
+// 古い順
+pub fn history(session_id: &str) -> Turns {
+    sessions()
+        .lock()
+        .ok()
+        .and_then(|s| s.get(session_id).cloned())
+        .unwrap_or_default()
+}
+
+pub fn push(session_id: &str, input: &str, output: &str) {
+    if let Ok(mut sessions) = sessions().lock() {
+        if let Some(turns) = sessions.get_mut(session_id) {
+            turns.push((input.to_string(), output.to_string()));
+            if turns.len() > MAX_TURNS {
+                turns.remove(0);
+            }
+        }
+    }
+}
+
+// セッションの分からない呼び出し（UI から直接のマクロ等）用: どれか 1 つでもシークレットなら
+pub fn any_active() -> bool {
+    sessions().lock().map(|s| !s.is_empty()).unwrap_or(false)
+}
@@ -0,0 +1,313 @@
+// src-tauri/src/journal.rs
+//
+// 日記の自動作成（毎日 / 毎週）
+// - 材料: アクティビティのタイムライン / その日話したセッション / 動いたプラン / その日できた importance の高いメモリ
+//   週のまとめはその週の日記も材料に入れる
+// - settings.journal_writer のモデルが prompts/journal.md で Markdown に
+// - LongTerm メモリ "journal-<daily|weekly>-<日付>" としてタグ "journal" で保存（同じ日に書き直したら上書き）
+// - settings.journal_folder があれば <日付>-<daily|weekly>.md の写しも
+// - 裏のループ: settings.journal_time を過ぎてその日の日記が無ければ書く。journal_weekly_day の日は週の分も
+//   journal_time は既定で空（書かない）。起動していなかった日は CATCH_UP_DAYS 日前まで遡って書く（話した日だけ）
+//   generate_journal コマンドで手動でも（過去の日付を指定して書き直しも）
+
+use crate::activity::{self, ActivitySpan};
+use crate::ai;
+use crate::db::AxisDatabase;
+use crate::memory::{self, MemoryKind};
+use crate::plans;
+use crate::settings;
+use crate::shell;
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveTime, Weekday};
+use serde::Serialize;
+use std::fs;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+pub const JOURNAL_SESSION: &str = "journal";
+const TICK: Duration = Duration::from_secs(60);
+// 失敗した時に次に試すまで
+const RETRY_AFTER: Duration = Duration::from_secs(30 * 60);
+const NOTABLE_MEMORIES: usize = 8;
+const MAX_SESSIONS: usize = 30;
+// 起動していなかった間の分を何日前まで書くか
+const CATCH_UP_DAYS: i64 = 7;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Journal {
+    pub id: String,     // メモリの id
+    pub period: String, // daily / weekly
+    pub date: String,   // 最後の日 (YYYY-MM-DD)
+    pub text: String,
+    pub markdown_path: Option<String>,
+}
+
+fn journal_id(period: &str, date: NaiveDate) -> String {
+    format!("journal-{}-{}", period, date.format("%Y-%m-%d"))
+}
+
+// (最初の日, 最後の日)
+fn range(period: &str, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    if period == "weekly" {
+        (date - ChronoDuration::days(6), date)
+    } else {
+        (date, date)
+    }
+}
+
+fn activity_text(app: &AppHandle, first: NaiveDate, last: NaiveDate) -> String {
+    let mut spans: Vec<ActivitySpan> = Vec::new();
+    let mut day = first;
+    while day <= last {
+        spans.extend(
+            activity::timeline(app, Some(&day.format("%Y-%m-%d").to_string())).unwrap_or_default(),
+        );
+        day += ChronoDuration::days(1);
+    }
+    activity::summarize(&spans)
+}
+
+fn sessions_text(db: &AxisDatabase, from: i64, to: i64) -> String {
+    let sessions = db.sessions_between(from, to).unwrap_or_default();
+    if sessions.is_empty() {
+        return "(none)".to_string();
+    }
+    sessions
+        .iter()
+        .take(MAX_SESSIONS)
+        .map(|(_, n, first)| {
+            let first: String = first.chars().take(120).collect();
+            format!("- {} ({} messages)", first.replace('\n', " "), n)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn plans_text(app: &AppHandle, from: i64, to: i64) -> String {
+    let lines: Vec<String> = plans::list(app, 100)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| p.updated_at >= from && p.created_at < to)
+        .map(|p| {
+            let done = p.steps.iter().filter(|s| s.status == "done").count();
+            format!(
+                "- {} [{}] {}/{} steps",
+                p.goal,
+                p.status,
+                done,
+                p.steps.len()
+            )
+        })
+        .collect();
+    if lines.is_empty() {
+        "(none)".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+// 期間内にできたメモリのうち importance の高いもの（日記自身は除く）。週のまとめにはその週の日記を
+fn memories_text(app: &AppHandle, period: &str, from: i64, to: i64) -> String {
+    let mut picked: Vec<(f32, String)> = Vec::new();
+    let mut journals: Vec<(i64, String)> = Vec::new();
+    let _ = memory::visit_meta(app, |meta| {
+        if meta.created_at_ms < from
+            || meta.created_at_ms >= to
+            || matches!(meta.kind, MemoryKind::Sealed)
+        {
+            return;
+        }
+        if meta.source == "journal" {
+            if period == "weekly" && meta.id.starts_with("journal-daily-") {
+                journals.push((meta.created_at_ms, meta.id.clone()));
+            }
+            return;
+        }
+        picked.push((meta.importance, meta.id.clone()));
+    });
+    picked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    journals.sort();
+
+    let mut lines: Vec<String> = journals
+        .iter()
+        .filter_map(|(_, id)| memory::load_entry(app, id).ok())
+        .map(|e| format!("### {}\n{}", e.input.text, e.output.text))
+        .collect();
+    lines.extend(
+        picked
+            .iter()
+            .take(NOTABLE_MEMORIES)
+            .filter_map(|(_, id)| memory::load_entry(app, id).ok())
+            .map(|e| {
+                let q: String = e.input.text.chars().take(100).collect();
+                let a: String = e.output.text.chars().take(200).collect();
+                format!(
+                    "- Q: {} / A: {}",
+                    q.replace('\n', " "),
+                    a.replace('\n', " ")
+                )
+            }),
+    );
+    if lines.is_empty() {
+        "(none)".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+fn write_markdown(title: &str, file_name: &str, text: &str) -> Result<Option<String>, String> {
+    let folder = settings::current().journal_folder;
+    if folder.trim().is_empty() {
+        return Ok(None);
+    }
+    let dir = shell::resolve_user_path(&folder);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(file_name);
+    fs::write(&path, format!("# {}\n\n{}\n", title, text.trim())).map_err(|e| e.to_string())?;
+    Ok(Some(path.display().to_string()))
+}
+
+pub async fn generate(app: &AppHandle, period: &str, day: Option<&str>) -> Result<Journal, String> {
+    if !matches!(period, "daily" | "weekly") {
+        return Err(format!("unknown period '{}': use daily or weekly", period));
+    }
+    let date = activity::parse_day(day)?;
+    let (first, last) = range(period, date);
+    let (from, _) = activity::day_bounds(first)?;
+    let (_, to) = activity::day_bounds(last)?;
+    let label = if first == last {
+        last.format("%Y-%m-%d (%a)").to_string()
+    } else {
+        format!("{} - {}", first.format("%Y-%m-%d"), last.format("%Y-%m-%d"))
+    };
+
+    let db = AxisDatabase::open(app)?;
+    let activity = activity_text(app, first, last);
+    let sessions = sessions_text(&db, from, to);
+    let plans = plans_text(app, from, to);
+    let memories = memories_text(app, period, from, to);
+
+    let cfg = settings::current();
+    let alias = cfg.journal_writer.trim().to_lowercase();
+    let sys = crate::prompts::render(
+        app,
+        "journal",
+        &[
+            ("period", period),
+            ("date", &label),
+            ("activity", &activity),
+            ("sessions", &sessions),
+            ("plans", &plans),
+            ("memories", &memories),
+        ],
+    );
+    let request = format!("Write the {} journal for {}.", period, label);
+    let text = ai::call_alias(&alias, &cfg.models.for_alias(&alias), &sys, &request).await?;
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("journal writer returned nothing".to_string());
+    }
+
+    let id = journal_id(period, last);
+    let title = format!("{} journal {}", period, label);
+    memory::save_long_term(
+        app,
+        &id,
+        JOURNAL_SESSION,
+        to - 1,
+        &title,
+        &text,
+        "journal",
+        vec!["journal".to_string(), period.to_string()],
+    )?;
+    let markdown_path = match write_markdown(
+        &title,
+        &format!("{}-{}.md", last.format("%Y-%m-%d"), period),
+        &text,
+    ) {
+        Ok(p) => p,
+        Err(e) => {
+            warn!("⚠️ [Journal] failed to write markdown copy: {}", e);
+            None
+        }
+    };
+    info!("📔 [Journal] wrote {}", id);
+
+    let journal = Journal {
+        id,
+        period: period.to_string(),
+        date: last.format("%Y-%m-%d").to_string(),
+        text,
+        markdown_path,
+    };
+    let _ = app.emit("axis-journal", &journal);
+    Ok(journal)
+}
+
+// その日に話したセッションがあるか（無い日の日記は遡って書かない）
+fn had_sessions(db: &AxisDatabase, day: NaiveDate) -> bool {
+    activity::day_bounds(day)
+        .ok()
+        .and_then(|(from, to)| db.sessions_between(from, to).ok())
+        .is_some_and(|s| !s.is_empty())
+}
+
+// 今書くべき分 (period, 日付)。設定の時刻を過ぎた今日の分と、書きそびれた CATCH_UP_DAYS 日前までの分（古い順）
+fn due(app: &AppHandle) -> Vec<(&'static str, NaiveDate)> {
+    let cfg = settings::current();
+    let Ok(at) = NaiveTime::parse_from_str(cfg.journal_time.trim(), "%H:%M") else {
+        return Vec::new();
+    };
+    let Ok(db) = AxisDatabase::open(app) else {
+        return Vec::new();
+    };
+    let now = Local::now();
+    let today = now.date_naive();
+    let weekly_day = cfg.journal_weekly_day.trim().parse::<Weekday>().ok();
+    let mut days: Vec<NaiveDate> = (1..=CATCH_UP_DAYS)
+        .rev()
+        .map(|n| today - ChronoDuration::days(n))
+        .collect();
+    if now.time() >= at {
+        days.push(today);
+    }
+    let mut out = Vec::new();
+    // 日記を先に（週のまとめがその日の分も読めるように）
+    for period in ["daily", "weekly"] {
+        for day in &days {
+            if period == "weekly" && weekly_day != Some(day.weekday()) {
+                continue;
+            }
+            if memory::exists(app, &journal_id(period, *day)) {
+                continue;
+            }
+            if *day != today && !had_sessions(&db, *day) {
+                continue;
+            }
+            out.push((period, *day));
+        }
+    }
+    out
+}
+
+// setup で呼ぶ
+pub fn spawn_writer(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut failed_at: Option<Instant> = None;
+        loop {
+            tokio::time::sleep(TICK).await;
+            if failed_at.is_some_and(|t| t.elapsed() < RETRY_AFTER) {
+                continue;
+            }
+            for (period, day) in due(&app) {
+                let day = day.format("%Y-%m-%d").to_string();
+                if let Err(e) = generate(&app, period, Some(&day)).await {
+                    warn!("⚠️ [Journal] {} journal for {} failed: {}", period, day, e);
+                    failed_at = Some(Instant::now());
+                    break;
+                }
+                failed_at = None;
+            }
+        }
+    });
+}
@@ -0,0 +1,111 @@
+// src-tauri/src/local_only.rs
+//
+// ローカル専用モード (settings.local_only / env AXIS_LOCAL_ONLY)。エアギャップ環境や人に出せない作業用
+// - 外への通信は ai::client_for と IMAP の接続でまとめて止める（"local" の Ollama だけ通す）
+// - system::check_network は測らずにオフライン扱い → run_axis は Commander を通さずローカルへ
+//   ai::call_alias も settings.models.local に振り替える（Critic / 自動タグ / 知識グラフ / 日記 / 続きから）
+// - LOOK / RECORD_SCREEN は視覚モデルを使わず、端末内の OCR で画面の文字を読む (ocr.rs)
+// - set_local_only で切り替え、"axis-local-only" で画面に何が使えなくなるか (degraded) を知らせる
+
+use crate::{ocr, settings};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::info;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DegradedFeature {
+    pub feature: &'static str,
+    pub effect: &'static str,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct LocalOnlyStatus {
+    pub enabled: bool,
+    pub local_llm_url: String,
+    pub local_model: String,
+    pub ocr_engine: &'static str,
+    pub degraded: Vec<DegradedFeature>,
+}
+
+const DEGRADED: &[DegradedFeature] = &[
+    DegradedFeature {
+        feature: "cloud_models",
+        effect: "GPT / Gemini / Grok / NVIDIA Llama are never called. Routing, critic, ensemble, auto-tagging, knowledge graph, journals and resume all use the local model",
+    },
+    DegradedFeature {
+        feature: "routing",
+        effect: "Commander routing is skipped; every answer comes from the local model",
+    },
+    DegradedFeature {
+        feature: "vision",
+        effect: "LOOK and RECORD_SCREEN only read the text on screen with local OCR (Windows.Media.Ocr, or tesseract elsewhere); images and layout are not described",
+    },
+    DegradedFeature {
+        feature: "web",
+        effect: "SEARCH / FETCH / RESEARCH are unavailable",
+    },
+    DegradedFeature {
+        feature: "connectors",
+        effect: "Weather, news, RSS feeds and the morning briefing, remote calendars, Google Calendar, email, Spotify and Home Assistant are unavailable",
+    },
+    DegradedFeature {
+        feature: "image_generation",
+        effect: "IMAGE_GEN is unavailable (every image provider is a cloud API)",
+    },
+    DegradedFeature {
+        feature: "plugins",
+        effect: "Plugin actions are not offered to the model and do not run (plugins may reach the network)",
+    },
+    DegradedFeature {
+        feature: "api_keys",
+        effect: "API keys can be saved but are not verified",
+    },
+];
+
+pub fn enabled() -> bool {
+    settings::current().local_only
+}
+
+// 外に出る前に呼ぶ。provider はタイムアウト設定と同じ名前 (llama / gpt / gemini / grok / web / email ...)
+pub fn check(provider: &str) -> Result<(), String> {
+    if provider != "local" && enabled() {
+        return Err(format!("{} is blocked: local-only mode is on", provider));
+    }
+    Ok(())
+}
+
+pub fn status() -> LocalOnlyStatus {
+    let cfg = settings::current();
+    LocalOnlyStatus {
+        enabled: cfg.local_only,
+        local_llm_url: cfg.local_llm_url,
+        local_model: cfg.models.local,
+        ocr_engine: ocr::ENGINE,
+        degraded: if cfg.local_only {
+            DEGRADED.to_vec()
+        } else {
+            Vec::new()
+        },
+    }
+}
+
+pub fn set(app: &AppHandle, enabled: bool) -> Result<LocalOnlyStatus, String> {
+    let mut s = settings::view().settings;
+    s.local_only = enabled;
+    settings::update(app, s)?;
+    let status = status();
+    if status.enabled != enabled {
+        // env AXIS_LOCAL_ONLY の方が優先される
+        return Err("local_only is pinned by AXIS_LOCAL_ONLY".to_string());
+    }
+    info!(
+        "🔒 [LocalOnly] {}",
+        if enabled {
+            "on: network providers blocked"
+        } else {
+            "off"
+        }
+    );
+    let _ = app.emit("axis-local-only", &status);
+    Ok(status)
+}
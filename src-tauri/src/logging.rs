@@ -0,0 +1,400 @@
+// src-tauri/src/logging.rs
+//
+// ログ（tracing の購読側。tracing-subscriber は入れずに必要な分だけここで持つ）
+// - 各モジュールは tracing::{debug!, info!, warn!, error!} で出す（target = axis_os_lib::<module>）
+// - レベル: settings.log_level（env AXIS_LOG でも可）。"info,web=debug,critic=trace" のようにモジュール毎にも
+//   Axis 以外のクレート (hyper / reqwest など) は "hyper=debug" のように書かなければ warn 以上だけ
+// - 出力: 標準出力 + app_data_dir/logs/axis-YYYY-MM-DD.log（日付が変わったら次のファイル。KEEP_DAYS 日分だけ残す）
+//   ファイルを開く前（setup より前）のログも直近分から書き出す
+// - 直近 RECENT_CAPACITY 件はメモリにも持っておき get_recent_logs で返す
+// - 伏せ字: API キーらしい文字列はどのレベルでも伏せる
+//   ユーザーの中身を入れるフィールド (CONTENT_FIELDS: info!(query = %q, "...")) は info 以上では文字数だけ
+
+use crate::activity;
+use chrono::{Local, NaiveDate};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use tauri::{AppHandle, Manager};
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+
+const CRATE_TARGET: &str = "axis_os_lib";
+const RECENT_CAPACITY: usize = 2000;
+const KEEP_DAYS: usize = 7;
+// 中身を info 以上では出さないフィールド
+const CONTENT_FIELDS: &[&str] = &[
+    "query", "prompt", "input", "output", "content", "text", "title",
+];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct LogRecord {
+    pub timestamp: i64,
+    pub level: String,  // ERROR / WARN / INFO / DEBUG / TRACE
+    pub module: String, // "web" / "connectors::email"（Axis 以外はクレートのパス）
+    pub message: String,
+}
+
+// "info,web=debug" を読んだもの
+struct Filter {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    fn parse(spec: &str) -> Self {
+        let mut filter = Filter {
+            default: LevelFilter::INFO,
+            modules: Vec::new(),
+        };
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let parsed = match part.split_once('=') {
+                Some((module, level)) => LevelFilter::from_str(level.trim())
+                    .map(|l| filter.modules.push((module.trim().to_string(), l))),
+                None => LevelFilter::from_str(part).map(|l| filter.default = l),
+            };
+            if parsed.is_err() {
+                // ここで tracing を呼ぶと自分に返ってくるので標準出力に直接
+                println!("⚠️ [Logging] ignored bad log level '{}'", part);
+            }
+        }
+        // 長い（細かい）指定を優先
+        filter
+            .modules
+            .sort_by_key(|(m, _)| std::cmp::Reverse(m.len()));
+        filter
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        let (module, ours) = module_of(target);
+        let hit = self.modules.iter().find(|(m, _)| {
+            let under = |path: &str| path == m || path.starts_with(&format!("{}::", m));
+            under(module) || under(target)
+        });
+        match hit {
+            Some((_, level)) => *level,
+            None if ours => self.default,
+            None => self.default.min(LevelFilter::WARN),
+        }
+    }
+}
+
+// "axis_os_lib::connectors::email" → ("connectors::email", true)
+fn module_of(target: &str) -> (&str, bool) {
+    match target.strip_prefix(CRATE_TARGET) {
+        Some("") => ("lib", true),
+        Some(rest) => match rest.strip_prefix("::") {
+            Some(module) => (module, true),
+            None => (target, false),
+        },
+        None => (target, false),
+    }
+}
+
+struct FileSink {
+    dir: PathBuf,
+    date: String,
+    file: Option<File>,
+}
+
+impl FileSink {
+    fn write_line(&mut self, line: &str) {
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        if self.file.is_none() || self.date != today {
+            self.date = today;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.dir.join(format!("axis-{}.log", self.date)))
+                .ok();
+            self.prune();
+        }
+        if let Some(f) = self.file.as_mut() {
+            let _ = writeln!(f, "{}", line);
+        }
+    }
+
+    fn prune(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut logs: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.file_name()
+                    .map(|n| n.to_string_lossy())
+                    .is_some_and(|n| n.starts_with("axis-") && n.ends_with(".log"))
+            })
+            .collect();
+        logs.sort();
+        let old = logs.len().saturating_sub(KEEP_DAYS);
+        for path in &logs[..old] {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+struct State {
+    filter: RwLock<Filter>,
+    recent: Mutex<VecDeque<LogRecord>>,
+    file: Mutex<Option<FileSink>>,
+}
+
+fn state() -> &'static State {
+    static STATE: OnceLock<State> = OnceLock::new();
+    STATE.get_or_init(|| State {
+        filter: RwLock::new(Filter::parse("info")),
+        recent: Mutex::new(VecDeque::new()),
+        file: Mutex::new(None),
+    })
+}
+
+fn secret_res() -> &'static [Regex] {
+    static RES: OnceLock<Vec<Regex>> = OnceLock::new();
+    RES.get_or_init(|| {
+        [
+            r"sk-[A-Za-z0-9_\-]{16,}",    // OpenAI
+            r"AIza[0-9A-Za-z_\-]{30,}",   // Google
+            r"xai-[A-Za-z0-9]{20,}",      // xAI
+            r"nvapi-[A-Za-z0-9_\-]{20,}", // NVIDIA
+            r"(?i)bearer\s+[A-Za-z0-9._\-]{16,}",
+            r#"(?i)\b(?:api[_-]?key|key|access_token|token|secret|password)\s*[:=]\s*"?[^\s"'&,]+"#,
+        ]
+        .iter()
+        .map(|p| Regex::new(p).unwrap())
+        .collect()
+    })
+}
+
+pub fn redact(text: &str) -> String {
+    let mut out = text.to_string();
+    for re in secret_res() {
+        out = re
+            .replace_all(&out, |c: &regex::Captures| {
+                // "key=..." は名前を残す
+                let m = &c[0];
+                match m.find(['=', ':']) {
+                    Some(i) => format!("{}***", &m[..=i]),
+                    _ => "***".to_string(),
+                }
+            })
+            .into_owned();
+    }
+    out
+}
+
+struct Fields {
+    message: String,
+    extra: String,
+    hide_content: bool,
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        let value = format!("{:?}", value);
+        if field.name() == "message" {
+            self.message = value;
+        } else if self.hide_content && CONTENT_FIELDS.contains(&field.name()) {
+            self.extra.push_str(&format!(
+                " {}=[{} chars]",
+                field.name(),
+                value.chars().count()
+            ));
+        } else {
+            self.extra.push_str(&format!(" {}={}", field.name(), value));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        // &str はそのまま（Debug だと引用符が付く）
+        self.record_debug(field, &format_args!("{}", value));
+    }
+}
+
+struct AxisSubscriber {
+    next_span: AtomicU64,
+}
+
+impl Subscriber for AxisSubscriber {
+    // 設定が変わった時に呼び出し元のキャッシュを捨てなくて済むよう、毎回 enabled で見る
+    fn register_callsite(&self, _: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, meta: &Metadata<'_>) -> bool {
+        state()
+            .filter
+            .read()
+            .map(|f| *meta.level() <= f.level_for(meta.target()))
+            .unwrap_or(true)
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let meta = event.metadata();
+        let mut fields = Fields {
+            message: String::new(),
+            extra: String::new(),
+            hide_content: *meta.level() <= Level::INFO,
+        };
+        event.record(&mut fields);
+        let record = LogRecord {
+            timestamp: Local::now().timestamp_millis(),
+            level: meta.level().to_string(),
+            module: module_of(meta.target()).0.to_string(),
+            message: redact(&format!("{}{}", fields.message, fields.extra)),
+        };
+        let line = format_line(&record);
+        println!("{}", line);
+
+        let state = state();
+        if let Ok(mut file) = state.file.lock() {
+            if let Some(sink) = file.as_mut() {
+                sink.write_line(&line);
+            }
+        }
+        if let Ok(mut recent) = state.recent.lock() {
+            if recent.len() >= RECENT_CAPACITY {
+                recent.pop_front();
+            }
+            recent.push_back(record);
+        }
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+fn format_line(r: &LogRecord) -> String {
+    let time = chrono::DateTime::from_timestamp_millis(r.timestamp)
+        .map(|t| {
+            t.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S%.3f")
+                .to_string()
+        })
+        .unwrap_or_default();
+    format!("{} {:<5} {}: {}", time, r.level, r.module, r.message)
+}
+
+// run() の最初に呼ぶ（この時点ではまだ標準出力とメモリだけ）
+pub fn init() {
+    let subscriber = AxisSubscriber {
+        next_span: AtomicU64::new(1),
+    };
+    if tracing::subscriber::set_global_default(subscriber).is_err() {
+        println!("⚠️ [Logging] a tracing subscriber is already installed");
+    }
+}
+
+// setup で呼ぶ: app_data_dir/logs に書き始める（それまでの分も書き出す）
+pub fn open_log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("logs");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let mut sink = FileSink {
+        dir: dir.clone(),
+        date: String::new(),
+        file: None,
+    };
+    let state = state();
+    if let Ok(recent) = state.recent.lock() {
+        for record in recent.iter() {
+            sink.write_line(&format_line(record));
+        }
+    }
+    if let Ok(mut file) = state.file.lock() {
+        *file = Some(sink);
+    }
+    Ok(dir)
+}
+
+// purge.rs 用: before より前に終わった日のログファイル（書き込み中の今日の分は clear_current で）
+pub fn files_before(before: i64) -> Vec<PathBuf> {
+    let today = Local::now().date_naive();
+    let Some(dir) = state()
+        .file
+        .lock()
+        .ok()
+        .and_then(|f| f.as_ref().map(|s| s.dir.clone()))
+    else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            let Some(day) = p
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .and_then(|n| {
+                    n.strip_prefix("axis-")
+                        .and_then(|n| n.strip_suffix(".log"))
+                        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                })
+            else {
+                return false;
+            };
+            day < today && activity::day_bounds(day).is_ok_and(|(_, next)| next <= before)
+        })
+        .collect()
+}
+
+// purge.rs 用: 今日のログファイルと get_recent_logs の分を空にする
+pub fn clear_current() {
+    let state = state();
+    if let Ok(mut recent) = state.recent.lock() {
+        recent.clear();
+    }
+    if let Ok(mut file) = state.file.lock() {
+        if let Some(f) = file.as_mut().and_then(|s| s.file.as_mut()) {
+            let _ = f.set_len(0);
+        }
+    }
+}
+
+// settings の読み込み / 変更時に呼ばれる
+pub fn configure(spec: &str) {
+    let filter = Filter::parse(spec);
+    if let Ok(mut f) = state().filter.write() {
+        *f = filter;
+    }
+}
+
+// get_recent_logs: level 以上（既定 info）を新しい方から n 件
+pub fn recent(level: Option<&str>, n: usize) -> Result<Vec<LogRecord>, String> {
+    let min = match level {
+        Some(l) => Level::from_str(l.trim()).map_err(|_| format!("unknown log level '{}'", l))?,
+        None => Level::INFO,
+    };
+    let recent = state().recent.lock().map_err(|e| e.to_string())?;
+    Ok(recent
+        .iter()
+        .rev()
+        .filter(|r| Level::from_str(&r.level).is_ok_and(|l| l <= min))
+        .take(n)
+        .cloned()
+        .collect())
+}
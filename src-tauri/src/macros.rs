@@ -0,0 +1,325 @@
+// src-tauri/src/macros.rs
+//
+// マクロ（エージェントの操作の記録と再生）
+// - start_recording(name) 〜 stop_recording() の間に実行された EXEC / TYPE / CLICK / PRESS / WAIT / WAITFOR を 1 行ずつ記録
+// - 保存先は memory.db の macros。手で直す時は save() に steps をそのまま渡す
+// - steps の {{name}} がパラメータ。run(name, params) で置き換えてから上から順に実行
+// - 各行は Command::parse で Action にし、エージェントの操作と同じく policy::run_or_queue を通す
+// - 自然文での呼び出し ("do my morning setup") は Worker が MACRO: <name> [key=value ...] を出す
+//   （worker プロンプトの {{macros}} に名前 / トリガー / パラメータを並べる）
+
+use crate::audit;
+use crate::db::AxisDatabase;
+use crate::{policy, uia};
+use axis_core::command::{Action, Command};
+use chrono::Local;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+// 記録 / 再生できる操作（確認の要る操作や問い合わせ系は入れない）
+const RECORDABLE: &[&str] = &["EXEC:", "TYPE:", "CLICK:", "PRESS:", "WAIT:", "WAITFOR:"];
+const MAX_STEPS: usize = 100;
+// 再生時、手で書いた WAIT が無くてもアプリの起動を少し待つ
+const STEP_GAP: Duration = Duration::from_millis(300);
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Macro {
+    pub name: String,
+    pub description: String,
+    pub triggers: Vec<String>, // "do my morning setup" など、呼び出しに使う言い回し
+    pub steps: Vec<String>,
+    pub params: Vec<String>, // steps の {{...}} から
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Macro {
+    pub fn new(
+        name: String,
+        description: String,
+        triggers: Vec<String>,
+        steps: Vec<String>,
+        created_at: i64,
+        updated_at: i64,
+    ) -> Self {
+        let mut params: Vec<String> = Vec::new();
+        for step in &steps {
+            for cap in param_re().captures_iter(step) {
+                let p = cap[1].to_string();
+                if !params.contains(&p) {
+                    params.push(p);
+                }
+            }
+        }
+        Self {
+            name,
+            description,
+            triggers,
+            steps,
+            params,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+struct Recording {
+    name: String,
+    description: String,
+    steps: Vec<String>,
+}
+
+// 記録中のマクロ (Tauri managed state)
+#[derive(Default)]
+pub struct MacroRecorder(Mutex<Option<Recording>>);
+
+fn param_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap())
+}
+
+fn recordable(step: &str) -> bool {
+    RECORDABLE.iter().any(|p| step.starts_with(p))
+}
+
+fn clean_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > 60 {
+        return Err("macro name must be 1-60 characters".to_string());
+    }
+    Ok(name.to_string())
+}
+
+pub fn start_recording(app: &AppHandle, name: &str, description: &str) -> Result<(), String> {
+    let name = clean_name(name)?;
+    let state = app
+        .try_state::<MacroRecorder>()
+        .ok_or("macro recorder is not available")?;
+    let mut rec = state.0.lock().map_err(|e| e.to_string())?;
+    if let Some(current) = rec.as_ref() {
+        return Err(format!("already recording '{}'", current.name));
+    }
+    info!("⏺️ [Macros] recording '{}'", name);
+    *rec = Some(Recording {
+        name,
+        description: description.trim().to_string(),
+        steps: Vec::new(),
+    });
+    Ok(())
+}
+
+// コマンドループから実行した操作ごとに呼ばれる（記録中でなければ何もしない）
+pub fn record(app: &AppHandle, step: &str) {
+    let step = step.trim();
+    if !recordable(step) {
+        return;
+    }
+    let Some(state) = app.try_state::<MacroRecorder>() else {
+        return;
+    };
+    let Ok(mut rec) = state.0.lock() else {
+        return;
+    };
+    if let Some(r) = rec.as_mut().filter(|r| r.steps.len() < MAX_STEPS) {
+        r.steps.push(step.to_string());
+    }
+}
+
+// 記録を止めて保存（何も記録していなければ保存しない）
+pub fn stop_recording(app: &AppHandle) -> Result<Macro, String> {
+    let state = app
+        .try_state::<MacroRecorder>()
+        .ok_or("macro recorder is not available")?;
+    let rec = state
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .ok_or("not recording a macro")?;
+    if rec.steps.is_empty() {
+        return Err(format!("'{}' recorded no actions; nothing saved", rec.name));
+    }
+    info!(
+        "⏹️ [Macros] recorded '{}' ({} steps)",
+        rec.name,
+        rec.steps.len()
+    );
+    save(app, &rec.name, &rec.description, Vec::new(), rec.steps)
+}
+
+pub fn save(
+    app: &AppHandle,
+    name: &str,
+    description: &str,
+    triggers: Vec<String>,
+    steps: Vec<String>,
+) -> Result<Macro, String> {
+    let name = clean_name(name)?;
+    let steps: Vec<String> = steps
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if steps.is_empty() || steps.len() > MAX_STEPS {
+        return Err(format!("a macro needs 1-{} steps", MAX_STEPS));
+    }
+    if let Some(bad) = steps.iter().find(|s| !recordable(s)) {
+        return Err(format!(
+            "'{}' is not a macro step: use EXEC / TYPE / CLICK / PRESS / WAIT / WAITFOR",
+            bad
+        ));
+    }
+    let db = AxisDatabase::open(app)?;
+    let now = Local::now().timestamp_millis();
+    let created_at = db
+        .get_macro(&name)
+        .map_err(|e| e.to_string())?
+        .map(|m| m.created_at)
+        .unwrap_or(now);
+    let triggers = triggers
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    let m = Macro::new(
+        name,
+        description.trim().to_string(),
+        triggers,
+        steps,
+        created_at,
+        now,
+    );
+    db.save_macro(&m).map_err(|e| e.to_string())?;
+    Ok(m)
+}
+
+pub fn list(app: &AppHandle) -> Result<Vec<Macro>, String> {
+    AxisDatabase::open(app)?
+        .list_macros()
+        .map_err(|e| e.to_string())
+}
+
+pub fn delete(app: &AppHandle, name: &str) -> Result<bool, String> {
+    AxisDatabase::open(app)?
+        .delete_macro(name)
+        .map_err(|e| e.to_string())
+}
+
+fn substitute(step: &str, params: &HashMap<String, String>) -> Result<String, String> {
+    let mut missing = None;
+    let out = param_re().replace_all(step, |cap: &regex::Captures| match params.get(&cap[1]) {
+        Some(v) => v.clone(),
+        None => {
+            missing.get_or_insert_with(|| cap[1].to_string());
+            String::new()
+        }
+    });
+    match missing {
+        Some(p) => Err(format!("missing parameter '{}'", p)),
+        None => Ok(out.to_string()),
+    }
+}
+
+// 1 行を Action にして、エージェントの操作と同じく policy を通して実行する（確認の要るものは保留に積む）
+fn execute_step(app: &AppHandle, step: &str, session_id: &str) -> String {
+    match Command::parse(step).action {
+        Action::Wait { ms } => {
+            thread::sleep(Duration::from_millis(ms.min(60_000)));
+            format!("Waited {} ms", ms)
+        }
+        Action::WaitFor {
+            kind,
+            target,
+            timeout_secs,
+        } => uia::wait_for(&kind, &target, timeout_secs),
+        action @ (Action::Exec { .. }
+        | Action::Type { .. }
+        | Action::Click { .. }
+        | Action::Press { .. }) => policy::run_or_queue(app, action, session_id),
+        Action::Invalid { message } => format!("Error: {}", message),
+        _ => format!("Error: '{}' is not a macro step", step),
+    }
+}
+
+// 全部置き換えられるのを確かめてから実行する（途中で止まらないように）
+// initiator: 監査記録 (audit.rs) 用。run_macro から "user"、MACRO: から "agent"
+// session_id: MACRO: を出した依頼のセッション（保留 / 監査記録をそこに付ける）
+pub async fn run(
+    app: &AppHandle,
+    name: &str,
+    params: HashMap<String, String>,
+    initiator: &str,
+    session_id: &str,
+) -> Result<String, String> {
+    let m = AxisDatabase::open(app)?
+        .get_macro(name.trim())
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("macro '{}' not found", name.trim()))?;
+    let steps = m
+        .steps
+        .iter()
+        .map(|s| substitute(s, &params))
+        .collect::<Result<Vec<_>, _>>()?;
+    info!("▶️ [Macros] running '{}' ({} steps)", m.name, steps.len());
+    let (app, initiator, session_id) = (app.clone(), initiator.to_string(), session_id.to_string());
+    let log = tauri::async_runtime::spawn_blocking(move || {
+        let mut log = String::new();
+        for (i, step) in steps.iter().enumerate() {
+            let res = execute_step(&app, step, &session_id);
+            audit::record(&app, &initiator, step, &res, &session_id);
+            log.push_str(&format!("{}. {} -> {}\n", i + 1, step, res));
+            if !step.starts_with("WAIT:") {
+                thread::sleep(STEP_GAP);
+            }
+        }
+        log
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(format!("Macro '{}':\n{}", m.name, log))
+}
+
+// Worker の "MACRO: <name> [key=value ...]"（名前と引数は axis-core の command.rs で分けてある）
+pub async fn run_command(
+    app: &AppHandle,
+    name: &str,
+    params: &BTreeMap<String, String>,
+    session_id: &str,
+) -> String {
+    let params: HashMap<String, String> = params.clone().into_iter().collect();
+    match run(app, name, params, "agent", session_id).await {
+        Ok(log) => format!("[Macro] {}", log),
+        Err(e) => format!("[System] Macro Error: {}\n", e),
+    }
+}
+
+// worker プロンプトの {{macros}}
+pub fn prompt_list(app: &AppHandle) -> String {
+    let macros = list(app).unwrap_or_default();
+    if macros.is_empty() {
+        return "(none)".to_string();
+    }
+    macros
+        .iter()
+        .map(|m| {
+            let mut line = format!("- {}", m.name);
+            if !m.description.is_empty() {
+                line.push_str(&format!(": {}", m.description));
+            }
+            if !m.triggers.is_empty() {
+                line.push_str(&format!(" (say: \"{}\")", m.triggers.join("\", \"")));
+            }
+            if !m.params.is_empty() {
+                line.push_str(&format!(" [params: {}]", m.params.join(", ")));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
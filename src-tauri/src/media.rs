@@ -0,0 +1,87 @@
+// src-tauri/src/media.rs
+//
+// メディア操作 (MEDIA: play | pause | next | prev | volume <n>)
+// - settings.media_backend = "keys": OS のメディアキーを送る（どのプレーヤーにも効く）
+// - settings.media_backend = "spotify": Spotify Web API（未接続 / 失敗したらメディアキーに戻す）
+// - "pause the music and open my editor" のように他のコマンドと並べて使う
+// - play / pause はトグルのキーではなく、その状態にする API で（止めたつもりで鳴り出さないように）
+//   Windows: GlobalSystemMediaTransportControls (win32.rs) / Linux: playerctl (MPRIS) / macOS: Music と Spotify に AppleScript
+
+use crate::connectors::spotify;
+use crate::{settings, shell};
+use tracing::warn;
+
+// action / volume は axis-core の command::parse_media で読んだもの
+pub async fn control(action: &str, volume: Option<u32>) -> String {
+    // toggle / mute は Web API に無いのでキーで
+    let api_action = matches!(action, "play" | "pause" | "next" | "prev" | "volume");
+    let backend = settings::current().media_backend.trim().to_lowercase();
+    if backend == "spotify" && api_action && spotify::connected() {
+        match spotify::control(action, volume).await {
+            Ok(msg) => return format!("Success: {}", msg),
+            Err(e) => warn!("⚠️ [Media] Spotify failed, using media keys: {}", e),
+        }
+    }
+    // メディアキーの play / pause は同じトグルなので使わない
+    if matches!(action, "play" | "pause") {
+        let play = action == "play";
+        return tauri::async_runtime::spawn_blocking(move || match set_playing(play) {
+            Ok(msg) => format!("Success: {}", msg),
+            Err(e) => format!("Error: {}", e),
+        })
+        .await
+        .unwrap_or_else(|e| format!("Error: {}", e));
+    }
+    let action = action.to_string();
+    tauri::async_runtime::spawn_blocking(move || shell::media_key(&action, volume))
+        .await
+        .unwrap_or_else(|e| format!("Error: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn set_playing(play: bool) -> Result<String, String> {
+    match crate::win32::media_session(play)? {
+        true => Ok(format!("Media {}.", if play { "playing" } else { "paused" })),
+        false => Err("no media session to control".to_string()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_playing(play: bool) -> Result<String, String> {
+    let out = std::process::Command::new("playerctl")
+        .arg(if play { "play" } else { "pause" })
+        .output()
+        .map_err(|e| format!("playerctl is not available: {}", e))?;
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).trim().to_string());
+    }
+    Ok(format!("Media {}.", if play { "playing" } else { "paused" }))
+}
+
+#[cfg(target_os = "macos")]
+fn set_playing(play: bool) -> Result<String, String> {
+    let verb = if play { "play" } else { "pause" };
+    let script = ["Music", "Spotify"]
+        .iter()
+        .map(|app| {
+            format!(
+                "if application \"{0}\" is running then tell application \"{0}\" to {1}",
+                app, verb
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let out = std::process::Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).trim().to_string());
+    }
+    Ok(format!("Media {}.", if play { "playing" } else { "paused" }))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn set_playing(_play: bool) -> Result<String, String> {
+    Err("play / pause is not supported on this platform".to_string())
+}
@@ -4,14 +4,32 @@
 // - entry: input/output 分離
 // - meta : kind / importance / tags / stickies / search_text
 //
-// 検索はフルスキャン + 簡易スコアリング（MVP）
-
+// 保存先は settings.memory_backend（env MEMORY_BACKEND）で選択
+// - json  (既定): axis_memory/entries/<id>.json + <id>.meta.json
+// - sqlite      : memory.db の memory_entries / memory_fts（初回起動時に json から一度だけ移行）
+//
+// 検索はメモリ上の meta インデックス (MemoryIndex) + 簡易スコアリング
+// - 起動時に一括ロード、保存時に差分更新
+// - json バックエンドは entries ディレクトリを監視 (notify クレート)。外で書き換え / 追加 / 削除された
+//   meta だけ読み直す。監視が落ちたら次の検索で全件読み直す
+//
+// ShortTerm の importance は最後に使われてからの日数で半減していく（effective_importance）
+// ほぼ同じやり取りは保存時に既存の 1 件へまとめる（access_count と importance を上げるだけ）
+// 古くて低いものの整理は retention.rs
+
+use crate::crypto;
+use crate::db::AxisDatabase;
+use crate::storage::ResponseFeedback;
+use axis_core::untrusted;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct AttachmentRef {
@@ -93,6 +111,14 @@ pub struct MemoryMeta {
     pub updated_at_ms: i64,
     #[serde(default)]
     pub search_text: String, // input+output+添付テキストなどを詰めた検索面
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback: Option<ResponseFeedback>,
+    // 最後に文脈として使われた / 評価された時刻（importance の減衰はここから。無ければ created_at_ms）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_accessed_ms: Option<i64>,
+    // 文脈として使われた + ほぼ同じやり取りがまとめられた回数
+    #[serde(default)]
+    pub access_count: u32,
 }
 
 
@@ -103,6 +129,21 @@ pub struct MemoryHit {
     pub entry: MemoryEntry,
 }
 
+// ---------- バックエンド選択 ----------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryBackend {
+    Json,
+    Sqlite,
+}
+
+pub fn backend() -> MemoryBackend {
+    match crate::settings::current().memory_backend.as_str() {
+        "sqlite" | "db" => MemoryBackend::Sqlite,
+        _ => MemoryBackend::Json,
+    }
+}
+
 // ---------- パス関連 ----------
 
 // src-tauri/src/memory.rs
@@ -115,7 +156,7 @@ fn memory_root(app: &AppHandle) -> Result<PathBuf, String> {
     }
 
     // ★ ここで保存先を1回はログに出す（起動直後の確認用）
-    println!("[memory] root dir = {:?}", &root);
+    info!("[memory] root dir = {:?}", &root);
 
     Ok(root)
 }
@@ -158,31 +199,103 @@ pub fn save_entry_and_meta(
 ) -> Result<(), String> {
     validate_meta(meta)?;
 
+    match backend() {
+        MemoryBackend::Sqlite => AxisDatabase::open(app)?
+            .upsert_memory(entry, meta)
+            .map_err(|e| e.to_string())?,
+        MemoryBackend::Json => json_save(app, entry, meta)?,
+    }
+    index_upsert(app, meta);
+    Ok(())
+}
+
+fn json_save(app: &AppHandle, entry: &MemoryEntry, meta: &MemoryMeta) -> Result<(), String> {
     let ep = entry_path(app, &entry.id)?;
     let mp = meta_path(app, &entry.id)?;
 
     let entry_json = serde_json::to_string_pretty(entry).map_err(|e| e.to_string())?;
     let meta_json = serde_json::to_string_pretty(meta).map_err(|e| e.to_string())?;
 
-    fs::write(ep, entry_json).map_err(|e| e.to_string())?;
-    fs::write(mp, meta_json).map_err(|e| e.to_string())?;
+    crypto::write(ep, entry_json)?;
+    crypto::write(mp, meta_json)?;
     Ok(())
 }
 
 pub fn load_entry(app: &AppHandle, id: &str) -> Result<MemoryEntry, String> {
+    match backend() {
+        MemoryBackend::Sqlite => AxisDatabase::open(app)?
+            .load_memory_entry(id)
+            .map_err(|e| e.to_string()),
+        MemoryBackend::Json => json_load_entry(app, id),
+    }
+}
+
+pub fn delete_entry(app: &AppHandle, id: &str) -> Result<(), String> {
+    match backend() {
+        MemoryBackend::Sqlite => AxisDatabase::open(app)?
+            .delete_memory(id)
+            .map_err(|e| e.to_string())?,
+        MemoryBackend::Json => {
+            for p in [entry_path(app, id)?, meta_path(app, id)?] {
+                if p.exists() {
+                    fs::remove_file(&p).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+    }
+    index_remove(app, id);
+    Ok(())
+}
+
+// purge.rs: json バックエンドのファイル（sqlite なら memory.db の行なので無し）
+pub fn entry_files(app: &AppHandle, id: &str) -> Result<Vec<PathBuf>, String> {
+    match backend() {
+        MemoryBackend::Sqlite => Ok(Vec::new()),
+        MemoryBackend::Json => Ok(vec![entry_path(app, id)?, meta_path(app, id)?]),
+    }
+}
+
+fn json_load_entry(app: &AppHandle, id: &str) -> Result<MemoryEntry, String> {
     let ep = entry_path(app, id)?;
-    let s = fs::read_to_string(ep).map_err(|e| e.to_string())?;
+    let s = crypto::read_to_string(ep)?;
     serde_json::from_str(&s).map_err(|e| e.to_string())
 }
 
-#[allow(dead_code)]
 pub fn load_meta(app: &AppHandle, id: &str) -> Result<MemoryMeta, String> {
+    if backend() == MemoryBackend::Sqlite {
+        return AxisDatabase::open(app)?
+            .load_memory_meta(id)
+            .map_err(|e| e.to_string());
+    }
     let mp = meta_path(app, id)?;
-    let s = fs::read_to_string(mp).map_err(|e| e.to_string())?;
+    let s = crypto::read_to_string(mp)?;
     serde_json::from_str(&s).map_err(|e| e.to_string())
 }
 
+// 検索結果の分をまとめて読む（id → meta。読めないものは入らない）
+pub fn load_metas(app: &AppHandle, ids: &[String]) -> Result<HashMap<String, MemoryMeta>, String> {
+    let metas = if backend() == MemoryBackend::Sqlite {
+        AxisDatabase::open(app)?
+            .load_memory_metas(ids)
+            .map_err(|e| e.to_string())?
+    } else {
+        ids.iter()
+            .filter_map(|id| load_meta(app, id).ok())
+            .collect()
+    };
+    Ok(metas.into_iter().map(|m| (m.id.clone(), m)).collect())
+}
+
 fn list_meta(app: &AppHandle) -> Result<Vec<MemoryMeta>, String> {
+    match backend() {
+        MemoryBackend::Sqlite => AxisDatabase::open(app)?
+            .list_memory_meta()
+            .map_err(|e| e.to_string()),
+        MemoryBackend::Json => json_list_meta(app),
+    }
+}
+
+fn json_list_meta(app: &AppHandle) -> Result<Vec<MemoryMeta>, String> {
     let dir = entries_dir(app)?;
     let mut out = Vec::new();
 
@@ -193,7 +306,7 @@ fn list_meta(app: &AppHandle) -> Result<Vec<MemoryMeta>, String> {
             if p.is_file() {
                 if let Some(name) = p.file_name().and_then(|n| n.to_str()) {
                     if name.ends_with(".meta.json") {
-                        if let Ok(s) = fs::read_to_string(&p) {
+                        if let Ok(s) = crypto::read_to_string(&p) {
                             if let Ok(m) = serde_json::from_str::<MemoryMeta>(&s) {
                                 out.push(m);
                             }
@@ -209,6 +322,273 @@ fn list_meta(app: &AppHandle) -> Result<Vec<MemoryMeta>, String> {
     Ok(out)
 }
 
+// ---------- meta インデックス ----------
+
+struct IndexedMeta {
+    meta: MemoryMeta,
+    tokens: HashSet<String>, // search_text のトークン（検索毎に作らない）
+}
+
+impl IndexedMeta {
+    fn new(meta: MemoryMeta) -> Self {
+        let tokens = tokenize(&meta.search_text).into_iter().collect();
+        Self { meta, tokens }
+    }
+}
+
+#[derive(Default)]
+struct IndexInner {
+    loaded: bool,
+    metas: HashMap<String, IndexedMeta>,
+    watcher: Option<RecommendedWatcher>, // 落とすと監視が止まるので持っておく
+}
+
+// Tauri managed state（lib.rs の builder で manage）
+#[derive(Default)]
+pub struct MemoryIndex(Mutex<IndexInner>);
+
+fn rebuild(app: &AppHandle, inner: &mut IndexInner) -> Result<(), String> {
+    let metas = list_meta(app)?;
+    inner.metas = metas
+        .into_iter()
+        .map(|m| (m.id.clone(), IndexedMeta::new(m)))
+        .collect();
+    inner.loaded = true;
+    info!("[memory] index loaded: {} entries", inner.metas.len());
+    Ok(())
+}
+
+// 起動時に呼ぶ（初回検索の待ちを無くす）
+pub fn warm_index(app: &AppHandle) {
+    // sqlite に切り替えた直後の起動なら json から移行しておく
+    if backend() == MemoryBackend::Sqlite && !migration_done(app) {
+        match migrate_json_to_sqlite(app) {
+            Ok(r) => info!(
+                "[memory] migrated json -> sqlite: {} migrated, {} skipped, {} failed",
+                r.migrated, r.skipped, r.failed
+            ),
+            Err(e) => info!("[memory] json -> sqlite migration failed: {}", e),
+        }
+    }
+
+    if let Some(index) = app.try_state::<MemoryIndex>() {
+        if let Ok(mut inner) = index.0.lock() {
+            // 監視を先に始める（読み込み中の変更も取りこぼさない）
+            if backend() == MemoryBackend::Json {
+                match watch(app) {
+                    Ok(w) => inner.watcher = Some(w),
+                    Err(e) => warn!(
+                        "⚠️ [memory] cannot watch entries, outside edits show up after a restart: {}",
+                        e
+                    ),
+                }
+            }
+            if let Err(e) = rebuild(app, &mut inner) {
+                info!("[memory] index load failed: {}", e);
+            }
+        }
+    }
+}
+
+fn watch(app: &AppHandle) -> Result<RecommendedWatcher, String> {
+    let handle = app.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) => on_entries_changed(&handle, &event),
+        Err(e) => {
+            warn!("⚠️ [memory] entries watcher: {}", e);
+            invalidate_index(&handle);
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    watcher
+        .watch(&entries_dir(app)?, RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())?;
+    Ok(watcher)
+}
+
+// 変わった <id>.meta.json だけ読み直す（自分の保存でも来るが 1 件読むだけ）
+fn on_entries_changed(app: &AppHandle, event: &Event) {
+    if event.kind.is_access() {
+        return;
+    }
+    for path in &event.paths {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let Some(id) = name.strip_suffix(".meta.json") else {
+            continue;
+        };
+        match load_meta(app, id) {
+            Ok(meta) => index_upsert(app, &meta),
+            Err(_) if !path.exists() => index_remove(app, id),
+            // 書きかけ。書き終わりでもう一度来る
+            Err(_) => {}
+        }
+    }
+}
+
+// 次の検索で全件読み直させる
+fn invalidate_index(app: &AppHandle) {
+    if let Some(index) = app.try_state::<MemoryIndex>() {
+        if let Ok(mut inner) = index.0.lock() {
+            inner.loaded = false;
+        }
+    }
+}
+
+fn index_upsert(app: &AppHandle, meta: &MemoryMeta) {
+    let Some(index) = app.try_state::<MemoryIndex>() else { return };
+    let Ok(mut inner) = index.0.lock() else { return };
+    if !inner.loaded {
+        return; // 次の検索で全件ロードされる
+    }
+    inner
+        .metas
+        .insert(meta.id.clone(), IndexedMeta::new(meta.clone()));
+}
+
+fn index_remove(app: &AppHandle, id: &str) {
+    let Some(index) = app.try_state::<MemoryIndex>() else { return };
+    let Ok(mut inner) = index.0.lock() else { return };
+    inner.metas.remove(id);
+}
+
+// インデックスを使って meta を走査（state が無ければディスクを直接読む）
+fn with_index<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut dyn Iterator<Item = (&MemoryMeta, &HashSet<String>)>) -> T,
+) -> Result<T, String> {
+    let Some(index) = app.try_state::<MemoryIndex>() else {
+        let metas: Vec<IndexedMeta> = list_meta(app)?.into_iter().map(IndexedMeta::new).collect();
+        return Ok(f(&mut metas.iter().map(|m| (&m.meta, &m.tokens))));
+    };
+    let mut inner = index.0.lock().map_err(|e| e.to_string())?;
+    if !inner.loaded {
+        rebuild(app, &mut inner)?;
+    }
+    Ok(f(&mut inner.metas.values().map(|m| (&m.meta, &m.tokens))))
+}
+
+// タグ / 分類の集計や絞り込み用 (tagging.rs)。インデックス経由で全 meta を 1 回ずつ
+pub fn visit_meta(app: &AppHandle, mut f: impl FnMut(&MemoryMeta)) -> Result<(), String> {
+    with_index(app, |metas| metas.for_each(|(meta, _)| f(meta)))
+}
+
+// ---------- 評価 (rate_response) ----------
+
+// 👍/👎 1 つ分の importance の増減
+const FEEDBACK_IMPORTANCE_STEP: f32 = 0.2;
+
+// ログに対応するメモリ: references の "log:<id>"。古いログは同じセッションで直後に作られたものを探す
+fn find_memory_for_log(
+    app: &AppHandle,
+    log_id: &str,
+    session_id: &str,
+    log_ts: i64,
+) -> Result<Option<String>, String> {
+    let reference = format!("log:{}", log_id);
+    with_index(app, |metas| {
+        let mut fallback: Option<(i64, String)> = None;
+        for (meta, _) in metas {
+            if meta.references.contains(&reference) {
+                return Some(meta.id.clone());
+            }
+            let delay = meta.created_at_ms - log_ts;
+            if meta.id.starts_with(session_id)
+                && (0..10 * 60 * 1000).contains(&delay)
+                && !matches!(&fallback, Some((d, _)) if *d <= delay)
+            {
+                fallback = Some((delay, meta.id.clone()));
+            }
+        }
+        fallback.map(|(_, id)| id)
+    })
+}
+
+// 評価を meta に付けて importance を上下させる（評価の付け直しは差分だけ動かす）。更新した id を返す
+pub fn apply_feedback(
+    app: &AppHandle,
+    log_id: &str,
+    session_id: &str,
+    log_ts: i64,
+    previous: Option<i64>,
+    feedback: Option<ResponseFeedback>,
+) -> Result<Option<String>, String> {
+    let Some(id) = find_memory_for_log(app, log_id, session_id, log_ts)? else {
+        return Ok(None);
+    };
+    let entry = load_entry(app, &id)?;
+    let mut meta = load_meta(app, &id)?;
+
+    let new_rating = feedback.as_ref().map(|f| f.rating).unwrap_or(0);
+    let delta = (new_rating - previous.unwrap_or(0)) as f32 * FEEDBACK_IMPORTANCE_STEP;
+    meta.importance = (meta.importance + delta).clamp(0.0, 1.0);
+    meta.feedback = feedback;
+    meta.updated_at_ms = Utc::now().timestamp_millis();
+    meta.last_accessed_ms = Some(meta.updated_at_ms);
+
+    save_entry_and_meta(app, &entry, &meta)?;
+    Ok(Some(id))
+}
+
+// ---------- バックアップ用 ----------
+
+// 全 entry/meta（backend に関係なく）
+pub fn export_all(app: &AppHandle) -> Result<Vec<(MemoryEntry, MemoryMeta)>, String> {
+    Ok(list_meta(app)?
+        .into_iter()
+        .filter_map(|meta| load_entry(app, &meta.id).ok().map(|entry| (entry, meta)))
+        .collect())
+}
+
+pub fn exists(app: &AppHandle, id: &str) -> bool {
+    load_meta(app, id).is_ok()
+}
+
+// ---------- json -> sqlite 移行 ----------
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct MigrationReport {
+    pub migrated: usize,
+    pub skipped: usize, // 既に sqlite にある
+    pub failed: usize,  // entry が読めない等
+}
+
+const MIGRATION_MARKER: &str = ".sqlite_migrated";
+
+fn migration_done(app: &AppHandle) -> bool {
+    memory_root(app)
+        .map(|root| root.join(MIGRATION_MARKER).exists())
+        .unwrap_or(false)
+}
+
+// json ファイルはそのまま残す（戻したくなったら MEMORY_BACKEND=json に戻すだけ）
+pub fn migrate_json_to_sqlite(app: &AppHandle) -> Result<MigrationReport, String> {
+    let db = AxisDatabase::open(app)?;
+    let mut report = MigrationReport::default();
+
+    for meta in json_list_meta(app)? {
+        if db.memory_exists(&meta.id).map_err(|e| e.to_string())? {
+            report.skipped += 1;
+            continue;
+        }
+        let Ok(entry) = json_load_entry(app, &meta.id) else {
+            report.failed += 1;
+            continue;
+        };
+        match db.upsert_memory(&entry, &meta) {
+            Ok(()) => report.migrated += 1,
+            Err(e) => {
+                info!("[memory] migrate {} failed: {}", meta.id, e);
+                report.failed += 1;
+            }
+        }
+    }
+
+    let marker = memory_root(app)?.join(MIGRATION_MARKER);
+    fs::write(marker, Utc::now().timestamp_millis().to_string()).map_err(|e| e.to_string())?;
+    invalidate_index(app);
+    Ok(report)
+}
+
 // ---------- 検索ロジック(MVP) ----------
 
 fn normalize_text(s: &str) -> String {
@@ -287,6 +667,17 @@ fn tag_overlap(tags: &[String], query_tokens: &[String]) -> i32 {
     n
 }
 
+// ShortTerm だけ減衰（LongTerm / Meta / Sealed は付けた値のまま）
+pub fn effective_importance(meta: &MemoryMeta, now_ms: i64) -> f32 {
+    let half_life = crate::settings::current().memory_decay_half_life_days;
+    if !matches!(meta.kind, MemoryKind::ShortTerm) || half_life <= 0.0 {
+        return meta.importance;
+    }
+    let since = meta.last_accessed_ms.unwrap_or(meta.created_at_ms);
+    let age_days = (now_ms - since).max(0) as f32 / (24.0 * 60.0 * 60.0 * 1000.0);
+    meta.importance * 0.5f32.powf(age_days / half_life)
+}
+
 fn recency_boost(updated_at_ms: i64) -> f32 {
     let now = Utc::now().timestamp_millis();
     let age_ms = (now - updated_at_ms).max(0) as f32;
@@ -305,60 +696,58 @@ pub fn search_top_k(app: &AppHandle, query: &str, limit: usize) -> Result<Vec<Me
     let q_tokens = tokenize(&q);
     let q_set: HashSet<String> = q_tokens.iter().cloned().collect();
 
-    let metas = list_meta(app)?;
-    let mut hits: Vec<MemoryHit> = Vec::new();
-
-    for meta in metas {
-        if matches!(meta.kind, MemoryKind::Sealed) {
-            continue;
-        }
+    let now = Utc::now().timestamp_millis();
+    // (id, score) だけ先に出して、entry 本体は上位 limit 件だけ読む
+    let mut scored: Vec<(String, f32)> = with_index(app, |metas| {
+        let mut scored = Vec::new();
+        for (meta, t_set) in metas {
+            if matches!(meta.kind, MemoryKind::Sealed) {
+                continue;
+            }
 
-        if meta.search_text.is_empty() {
-            continue;
-        }
+            if meta.search_text.is_empty() {
+                continue;
+            }
 
-        // ざっくりフィルタ
-        if !q_tokens.iter().any(|t| meta.search_text.contains(t))
-            && tag_overlap(&meta.tags, &q_tokens) == 0
-        {
-            continue;
-        }
+            // ざっくりフィルタ
+            if !q_tokens.iter().any(|t| meta.search_text.contains(t))
+                && tag_overlap(&meta.tags, &q_tokens) == 0
+            {
+                continue;
+            }
 
-        let t_tokens = tokenize(&meta.search_text);
-        let t_set: HashSet<String> = t_tokens.into_iter().collect();
+            let jac = jaccard(&q_set, t_set);
+            let ov = tag_overlap(&meta.tags, &q_tokens) as f32;
 
-        let jac = jaccard(&q_set, &t_set);
-        let ov = tag_overlap(&meta.tags, &q_tokens) as f32;
+            let mut score = 0.0;
+            score += jac * 5.0;
+            score += ov * 1.5;
+            score += effective_importance(meta, now).clamp(0.0, 1.0) * 2.0;
+            score += recency_boost(meta.updated_at_ms) * 1.0;
 
-        let mut score = 0.0;
-        score += jac * 5.0;
-        score += ov * 1.5;
-        score += meta.importance.clamp(0.0, 1.0) * 2.0;
-        score += recency_boost(meta.updated_at_ms) * 1.0;
+            if meta.search_text.contains(&q) {
+                score += 2.0;
+            }
 
-        if meta.search_text.contains(&q) {
-            score += 2.0;
+            if score <= 0.0 {
+                continue;
+            }
+            scored.push((meta.id.clone(), score));
         }
+        scored
+    })?;
 
-        if score <= 0.0 {
-            continue;
-        }
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
-        if let Ok(entry) = load_entry(app, &meta.id) {
-            hits.push(MemoryHit {
-                id: meta.id.clone(),
-                score,
-                entry,
-            });
+    let mut hits: Vec<MemoryHit> = Vec::new();
+    for (id, score) in scored {
+        if hits.len() >= limit {
+            break;
+        }
+        if let Ok(entry) = load_entry(app, &id) {
+            hits.push(MemoryHit { id, score, entry });
         }
     }
-
-    hits.sort_by(|a, b| {
-        b.score
-            .partial_cmp(&a.score)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    hits.truncate(limit);
     Ok(hits)
 }
 
@@ -366,17 +755,34 @@ pub fn search_best_for_query(app: &AppHandle, query: &str) -> Result<Option<Memo
     Ok(search_top_k(app, query, 1)?.into_iter().next())
 }
 
+fn touch(app: &AppHandle, hits: &[MemoryHit]) {
+    let now = Utc::now().timestamp_millis();
+    for hit in hits {
+        let Ok(mut meta) = load_meta(app, &hit.id) else {
+            continue;
+        };
+        meta.last_accessed_ms = Some(now);
+        meta.access_count += 1;
+        if let Err(e) = save_entry_and_meta(app, &hit.entry, &meta) {
+            info!("[memory] failed to mark {} as accessed: {}", hit.id, e);
+        }
+    }
+}
+
 // LLM 用の [Relevant Memories] セクション文字列
 pub fn build_memory_context(app: &AppHandle, query: &str, limit: usize) -> Result<String, String> {
     let hits = search_top_k(app, query, limit)?;
     if hits.is_empty() {
         return Ok(String::new());
     }
+    // 文脈として使った分は減衰をやり直す
+    touch(app, &hits);
 
     let mut lines: Vec<String> = Vec::new();
     for h in hits {
         let q_snip: String = h.entry.input.text.chars().take(80).collect();
-        let a_snip: String = h.entry.output.text.chars().take(120).collect();
+        // 外の文面を読んだ答えは囲んだまま切る
+        let a_snip = untrusted::clip(&h.entry.output.text, 120);
         lines.push(format!(
             "- (score={:.2}) Q: {} / A: {}",
             h.score, q_snip, a_snip
@@ -386,6 +792,98 @@ pub fn build_memory_context(app: &AppHandle, query: &str, limit: usize) -> Resul
     Ok(format!("\n[Relevant Memories]\n{}", lines.join("\n")))
 }
 
+// ---------- 重複の統合 ----------
+
+// まとめる毎の importance の上げ幅
+const DUPLICATE_IMPORTANCE_STEP: f32 = 0.05;
+// 文字 3-gram で比べる前のふるい（tokenize のトークンの Jaccard）
+const DUPLICATE_PREFILTER: f32 = 0.3;
+
+// 文字 3-gram（空白は 1 つに詰める）。日本語は tokenize だと文の塊になるので
+fn shingles(text: &str) -> HashSet<String> {
+    let chars: Vec<char> = normalize_text(text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect();
+    if chars.len() < 3 {
+        let whole: String = chars.iter().collect();
+        return [whole].into_iter().filter(|w| !w.is_empty()).collect();
+    }
+    chars.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+// 保存しようとしている search_text とほぼ同じ ShortTerm（一番近いもの）
+// 同じセッション・同じ source の中だけ（別の会話 / 外から来たものとはまとめない）。id は "<session>-<時刻>"
+fn find_near_duplicate(
+    app: &AppHandle,
+    session_id: &str,
+    source: &str,
+    search_text: &str,
+) -> Option<String> {
+    let threshold = crate::settings::current().memory_dedup_threshold;
+    if threshold <= 0.0 || search_text.is_empty() {
+        return None;
+    }
+    let tokens: HashSet<String> = tokenize(search_text).into_iter().collect();
+    let new_shingles = shingles(search_text);
+    let prefix = format!("{}-", session_id);
+    with_index(app, |metas| {
+        metas
+            .filter(|(meta, _)| matches!(meta.kind, MemoryKind::ShortTerm))
+            .filter(|(meta, _)| meta.source == source && meta.id.starts_with(&prefix))
+            .filter(|(_, t)| jaccard(&tokens, t) >= DUPLICATE_PREFILTER)
+            .map(|(meta, _)| {
+                let sim = jaccard(&new_shingles, &shingles(&meta.search_text));
+                (meta.id.clone(), sim)
+            })
+            .filter(|(_, sim)| *sim >= threshold)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(id, _)| id)
+    })
+    .ok()
+    .flatten()
+}
+
+// 既存の方に参照（log:<id>）を足して数え上げる。中身は新しい方（最新の答え / セッション）にする
+#[allow(clippy::too_many_arguments)]
+fn merge_duplicate(
+    app: &AppHandle,
+    id: &str,
+    session_id: &str,
+    input_text: &str,
+    output_text: &str,
+    provider: &str,
+    search_text: String,
+    references: Vec<String>,
+    now: i64,
+) -> Result<String, String> {
+    let mut entry = load_entry(app, id)?;
+    let mut meta = load_meta(app, id)?;
+    entry.session_id = session_id.to_string();
+    entry.timestamp_ms = now;
+    entry.input.text = input_text.to_string();
+    entry.output.text = output_text.to_string();
+    meta.provider = Some(provider.to_string());
+    meta.search_text = search_text;
+    meta.access_count += 1;
+    meta.importance = (meta.importance + DUPLICATE_IMPORTANCE_STEP).clamp(0.0, 1.0);
+    meta.last_accessed_ms = Some(now);
+    meta.updated_at_ms = now;
+    for r in references {
+        if !meta.references.contains(&r) {
+            meta.references.push(r);
+        }
+    }
+    save_entry_and_meta(app, &entry, &meta)?;
+    info!(
+        "[memory] merged near-duplicate into id={} (count={})",
+        id, meta.access_count
+    );
+    Ok(meta.id)
+}
+
 // ask_axis から使う「1対話の保存」ヘルパ（従来版）
 pub fn save_interaction(
     app: &AppHandle,
@@ -405,10 +903,14 @@ pub fn save_interaction(
         provider,
         references,
         None,
+        vec![],
     )
+    .map(|_| ())
 }
 
-// ★ Commander の task_type も一緒に保存する版
+// ★ Commander の task_type も一緒に保存する版。保存した id を返す（自動タグ付け用）
+// attachments: 応答側に付ける添付（LOOK のスクリーンショットなど。objects.rs に保存済みのもの）
+#[allow(clippy::too_many_arguments)]
 pub fn save_interaction_with_task(
     app: &AppHandle,
     session_id: &str,
@@ -418,7 +920,8 @@ pub fn save_interaction_with_task(
     provider: &str,
     references: Vec<String>,
     task_type: Option<String>,
-) -> Result<(), String> {
+    attachments: Vec<AttachmentRef>,
+) -> Result<String, String> {
     inner_save_interaction(
         app,
         session_id,
@@ -428,10 +931,178 @@ pub fn save_interaction_with_task(
         provider,
         references,
         task_type,
+        attachments,
     )
 }
 
+// 他のアシスタントから取り込んだ 1 往復 (importer.rs)。id / 時刻は元の会話のものを使う
+pub fn import_entry(
+    id: &str,
+    session_id: &str,
+    timestamp_ms: i64,
+    input_text: &str,
+    output_text: &str,
+    provider: &str,
+) -> (MemoryEntry, MemoryMeta) {
+    external_entry(
+        id,
+        session_id,
+        timestamp_ms,
+        input_text,
+        output_text,
+        provider,
+        "import",
+        vec!["import".to_string()],
+    )
+}
+
+// importer.rs: begin_import のトランザクションの中で書く
+// sqlite なら同じトランザクションの行、json なら書いたファイルを written に（commit できなければ呼び出し側が消す）
+// 索引は commit の後に index_entries で
+pub fn write_in(
+    app: &AppHandle,
+    conn: &rusqlite::Connection,
+    entry: &MemoryEntry,
+    meta: &MemoryMeta,
+    written: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    validate_meta(meta)?;
+    match backend() {
+        MemoryBackend::Sqlite => {
+            AxisDatabase::write_memory(conn, entry, meta).map_err(|e| e.to_string())
+        }
+        MemoryBackend::Json => {
+            if entry_path(app, &entry.id)?.exists() {
+                return Ok(());
+            }
+            written.extend([entry_path(app, &entry.id)?, meta_path(app, &entry.id)?]);
+            json_save(app, entry, meta)
+        }
+    }
+}
+
+pub fn index_entries(app: &AppHandle, metas: &[MemoryMeta]) {
+    for meta in metas {
+        index_upsert(app, meta);
+    }
+}
+
+// 会話以外から入ってくる記憶（取り込み / メールなど）。同じ id は 2 回書かない
+#[allow(clippy::too_many_arguments)]
+pub fn save_external(
+    app: &AppHandle,
+    id: &str,
+    session_id: &str,
+    timestamp_ms: i64,
+    input_text: &str,
+    output_text: &str,
+    provider: &str,
+    source: &str,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    if exists(app, id) {
+        return Ok(());
+    }
+    let (entry, meta) = external_entry(
+        id,
+        session_id,
+        timestamp_ms,
+        input_text,
+        output_text,
+        provider,
+        source,
+        tags,
+    );
+    save_entry_and_meta(app, &entry, &meta)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn external_entry(
+    id: &str,
+    session_id: &str,
+    timestamp_ms: i64,
+    input_text: &str,
+    output_text: &str,
+    provider: &str,
+    source: &str,
+    tags: Vec<String>,
+) -> (MemoryEntry, MemoryMeta) {
+    let entry = MemoryEntry {
+        id: id.to_string(),
+        session_id: session_id.to_string(),
+        timestamp_ms,
+        input: IoBlock {
+            text: input_text.to_string(),
+            attachments: vec![],
+        },
+        output: IoBlock {
+            text: output_text.to_string(),
+            attachments: vec![],
+        },
+    };
+    let meta = MemoryMeta {
+        id: id.to_string(),
+        kind: MemoryKind::ShortTerm,
+        importance: 0.4,
+        tags,
+        source: source.to_string(),
+        provider: Some(provider.to_string()),
+        created_at_ms: timestamp_ms,
+        updated_at_ms: timestamp_ms,
+        // 囲んだ外の文面 (untrusted.rs) は印を除いた中身で探す
+        search_text: normalize_text(&format!(
+            "{}\n{}\n",
+            untrusted::unwrap(input_text.trim_start()),
+            untrusted::unwrap(output_text.trim_start())
+        )),
+        ..Default::default()
+    };
+    (entry, meta)
+}
+
+// Axis 自身がまとめた長期の記憶（journal.rs の日記など）。同じ id は書き直す
+// created_at_ms は書いた時刻ではなく timestamp_ms（何の日の分か。週のまとめが日記を拾う時に使う）
+#[allow(clippy::too_many_arguments)]
+pub fn save_long_term(
+    app: &AppHandle,
+    id: &str,
+    session_id: &str,
+    timestamp_ms: i64,
+    input_text: &str,
+    output_text: &str,
+    source: &str,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let now = Utc::now().timestamp_millis();
+    let entry = MemoryEntry {
+        id: id.to_string(),
+        session_id: session_id.to_string(),
+        timestamp_ms,
+        input: IoBlock {
+            text: input_text.to_string(),
+            attachments: vec![],
+        },
+        output: IoBlock {
+            text: output_text.to_string(),
+            attachments: vec![],
+        },
+    };
+    let meta = MemoryMeta {
+        id: id.to_string(),
+        kind: MemoryKind::LongTerm,
+        importance: 0.7,
+        tags,
+        source: source.to_string(),
+        created_at_ms: timestamp_ms,
+        updated_at_ms: now,
+        search_text: normalize_text(&format!("{}\n{}\n", input_text, output_text)),
+        ..Default::default()
+    };
+    save_entry_and_meta(app, &entry, &meta)
+}
+
 // 実処理本体
+#[allow(clippy::too_many_arguments)]
 fn inner_save_interaction(
     app: &AppHandle,
     session_id: &str,
@@ -441,10 +1112,35 @@ fn inner_save_interaction(
     provider: &str,
     references: Vec<String>,
     task_type: Option<String>,
-) -> Result<(), String> {
+    attachments: Vec<AttachmentRef>,
+) -> Result<String, String> {
     use chrono::Utc;
 
     let now = Utc::now().timestamp_millis();
+    // 囲んだ答え (untrusted.rs) は印を除いた中身で探す
+    let search_text: String = normalize_text(&format!(
+        "{}\n{}\n",
+        input_text,
+        untrusted::unwrap(output_text.trim_start())
+    ));
+
+    // 添付のあるものはまとめると添付が消えるのでそのまま保存
+    if attachments.is_empty() {
+        if let Some(existing) = find_near_duplicate(app, session_id, source, &search_text) {
+            return merge_duplicate(
+                app,
+                &existing,
+                session_id,
+                input_text,
+                output_text,
+                provider,
+                search_text,
+                references,
+                now,
+            );
+        }
+    }
+
     let id = format!("{}-{}", session_id, now);
 
     let entry = MemoryEntry {
@@ -457,12 +1153,10 @@ fn inner_save_interaction(
         },
         output: IoBlock {
             text: output_text.to_string(),
-            attachments: vec![],
+            attachments,
         },
     };
 
-    let search_text: String = normalize_text(&format!("{}\n{}\n", input_text, output_text));
-
     let meta: MemoryMeta = MemoryMeta {
         id,
         kind: MemoryKind::ShortTerm,
@@ -477,18 +1171,19 @@ fn inner_save_interaction(
         created_at_ms: now,
         updated_at_ms: now,
         search_text,
+        feedback: None,
+        last_accessed_ms: None,
+        access_count: 0,
     };
 
     // ★ ここで self:: を付けて「同じモジュール内の関数」を明示
-    let res = self::save_entry_and_meta(app, &entry, &meta);
+    self::save_entry_and_meta(app, &entry, &meta)?;
 
-    if let Ok(()) = res {
-        // ★ 保存されたことをログ
-        println!(
-            "[memory] saved id={} session={} source={} provider={}",
-            meta.id, session_id, source, provider
-        );
-    }
+    // ★ 保存されたことをログ
+    info!(
+        "[memory] saved id={} session={} source={} provider={}",
+        meta.id, session_id, source, provider
+    );
 
-    res
+    Ok(meta.id)
 }  
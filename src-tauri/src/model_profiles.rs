@@ -1,7 +1,16 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
 
-#[derive(Debug, Deserialize, Clone)]
+use crate::outcomes::Adjustments;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModelScore {
     pub code: f32,
     pub reasoning: f32,
@@ -13,18 +22,157 @@ pub struct ModelScore {
     pub cost: f32,
 }
 
+impl ModelScore {
+    fn dimension_mut(&mut self, dim: &str) -> Option<&mut f32> {
+        match dim {
+            "code" => Some(&mut self.code),
+            "reasoning" => Some(&mut self.reasoning),
+            "math" => Some(&mut self.math),
+            "general_qa" => Some(&mut self.general_qa),
+            "planning" => Some(&mut self.planning),
+            "multimodal" => Some(&mut self.multimodal),
+            "speed" => Some(&mut self.speed),
+            "cost" => Some(&mut self.cost),
+            _ => None,
+        }
+    }
+}
+
 pub type ModelProfiles = HashMap<String, ModelScore>;
 
-fn load_profiles() -> ModelProfiles {
-    // ビルド時に同ディレクトリのJSONを埋め込む
-    const RAW: &str = include_str!("model_profiles.json");
+// ビルド時に同ディレクトリのJSONを埋め込む（app_data_dir に無い / 壊れている時の既定値）
+const DEFAULT_RAW: &str = include_str!("model_profiles.json");
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(3);
+
+struct Loaded {
+    mtime: Option<SystemTime>,
+    profiles: ModelProfiles,
+}
+
+static PROFILES: RwLock<Option<Loaded>> = RwLock::new(None);
+
+// 実績からの補正値（outcomes.rs が定期的に入れ替える）。基準値に足して使う
+static ADJUSTMENTS: RwLock<Option<Adjustments>> = RwLock::new(None);
 
-    serde_json::from_str(RAW).unwrap_or_else(|e| {
-        println!("[model_profiles] JSON parse error: {e}");
+fn default_profiles() -> ModelProfiles {
+    serde_json::from_str(DEFAULT_RAW).unwrap_or_else(|e| {
+        info!("[model_profiles] JSON parse error: {e}");
         HashMap::new()
     })
 }
 
+fn profiles_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|d| d.join("model_profiles.json"))
+}
+
+fn mtime(path: &Option<PathBuf>) -> Option<SystemTime> {
+    path.as_ref()?.metadata().ok()?.modified().ok()
+}
+
+// app_data_dir/model_profiles.json → 無ければ / 読めなければ埋め込みの既定値
+fn read_profiles(path: &Option<PathBuf>) -> ModelProfiles {
+    let Some(p) = path.as_ref().filter(|p| p.exists()) else {
+        return default_profiles();
+    };
+    match fs::read_to_string(p)
+        .map_err(|e| e.to_string())
+        .and_then(|t| serde_json::from_str::<ModelProfiles>(&t).map_err(|e| e.to_string()))
+    {
+        Ok(p) if !p.is_empty() => p,
+        Ok(_) => default_profiles(),
+        Err(e) => {
+            info!(
+                "[model_profiles] {} unreadable, using embedded default: {e}",
+                p.display()
+            );
+            default_profiles()
+        }
+    }
+}
+
+fn store(path: Option<PathBuf>) -> ModelProfiles {
+    let profiles = read_profiles(&path);
+    let loaded = Loaded {
+        mtime: mtime(&path),
+        profiles: profiles.clone(),
+    };
+    if let Ok(mut p) = PROFILES.write() {
+        *p = Some(loaded);
+    }
+    profiles
+}
+
+fn base_profiles() -> ModelProfiles {
+    if let Some(p) = PROFILES
+        .read()
+        .ok()
+        .and_then(|p| p.as_ref().map(|l| l.profiles.clone()))
+    {
+        return p;
+    }
+    default_profiles()
+}
+
+pub fn set_adjustments(adjustments: Adjustments) {
+    if let Ok(mut a) = ADJUSTMENTS.write() {
+        *a = Some(adjustments);
+    }
+}
+
+// 基準値 + 実績補正（0.0〜1.0 に収め、表示用に小数 2 桁へ丸める）
+pub fn load_profiles() -> ModelProfiles {
+    let mut profiles = base_profiles();
+    let guard = ADJUSTMENTS.read().ok();
+    let Some(adjustments) = guard.as_ref().and_then(|a| a.as_ref()) else {
+        return profiles;
+    };
+    for (model, score) in profiles.iter_mut() {
+        let Some(deltas) = adjustments.get(model) else {
+            continue;
+        };
+        for (dim, delta) in deltas {
+            if let Some(v) = score.dimension_mut(dim) {
+                *v = ((*v + delta).clamp(0.0, 1.0) * 100.0).round() / 100.0;
+            }
+        }
+    }
+    profiles
+}
+
+// 起動時（setup）に呼ぶ: 読み込み + 変更監視
+pub fn init(app: &AppHandle) {
+    let path = profiles_path(app);
+    store(path.clone());
+
+    let app = app.clone();
+    thread::spawn(move || loop {
+        thread::sleep(WATCH_INTERVAL);
+        let known = PROFILES
+            .read()
+            .ok()
+            .and_then(|p| p.as_ref().and_then(|l| l.mtime));
+        if mtime(&path) != known {
+            info!("📊 [ModelProfiles] model_profiles.json changed, reloading");
+            let profiles = store(path.clone());
+            let _ = app.emit("axis-model-profiles-changed", &profiles);
+        }
+    });
+}
+
+// reload_model_profiles コマンド用（補正込みの値を返す）
+pub fn reload(app: &AppHandle) -> ModelProfiles {
+    let path = profiles_path(app);
+    let count = store(path).len();
+    info!("📊 [ModelProfiles] reloaded {} profiles", count);
+    let profiles = load_profiles();
+    let _ = app.emit("axis-model-profiles-changed", &profiles);
+    profiles
+}
+
 /// Commander にそのまま渡せるテキストブロックを生成
 pub fn build_profiles_prompt() -> String {
     let profiles = load_profiles();
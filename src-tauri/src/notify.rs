@@ -0,0 +1,175 @@
+// src-tauri/src/notify.rs
+//
+// OS ネイティブ通知（Windows トースト / macOS 通知センター）
+// - observer の提案 / スケジュール実行結果 / 時間のかかった ask_axis の完了を通知
+// - デスクトップ版プラグインはクリックイベントを返さないので、
+//   「通知を出した後にメインウィンドウがフォーカスされたら直近のリンクを開く」方式で
+//   クリックスルーを実現する（フロントは axis-deep-link イベントを受けて画面遷移）
+// - policy の確認待ち: Windows は Approve / Deny ボタン付きトースト（tauri-winrt-notification）を出し、
+//   押された結果をそのまま policy::resolve に渡す（メインウィンドウを開かなくていい）
+//   引数がトーストに収まらない時はボタンを出さず、アプリ内の確認ダイアログで全部見てから決めてもらう
+//   他の OS は普通の通知 + "confirm" のディープリンク
+
+use crate::policy::{self, PendingAction};
+use chrono::Local;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tracing::{info, warn};
+
+// 通知からこの時間が経ったリンクは捨てる
+const LINK_TTL_MS: i64 = 10 * 60 * 1000;
+// 確認トーストに出す引数の長さ（これより長ければ承認ボタンを出さない）
+#[cfg(windows)]
+const TOAST_BODY_CHARS: usize = 240;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DeepLink {
+    pub kind: String, // "session" / "task" / "observer"
+    pub target: String,
+    pub created_at: i64,
+}
+
+impl DeepLink {
+    pub fn new(kind: &str, target: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            target: target.to_string(),
+            created_at: Local::now().timestamp_millis(),
+        }
+    }
+
+    pub fn session(session_id: &str) -> Self {
+        Self::new("session", session_id)
+    }
+}
+
+#[derive(Default)]
+pub struct PendingLink(pub Mutex<Option<DeepLink>>);
+
+fn main_window_focused(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .and_then(|w| w.is_focused().ok())
+        .unwrap_or(false)
+}
+
+fn set_pending(app: &AppHandle, link: Option<DeepLink>) {
+    if let Some(state) = app.try_state::<PendingLink>() {
+        if let Ok(mut p) = state.0.lock() {
+            *p = link;
+        }
+    }
+}
+
+// OS 通知を出す。アプリを見ている最中なら（アプリ内表示で足りるので）出さない
+pub fn notify(app: &AppHandle, title: &str, body: &str, link: Option<DeepLink>) {
+    if main_window_focused(app) {
+        return;
+    }
+
+    let body: String = body.chars().take(240).collect();
+    match app.notification().builder().title(title).body(&body).show() {
+        Ok(()) => set_pending(app, link),
+        Err(e) => warn!("⚠️ [Notify] failed to show notification: {}", e),
+    }
+}
+
+// 直近の通知リンクを取り出す（期限切れは破棄）
+pub fn take_pending(app: &AppHandle) -> Option<DeepLink> {
+    let state = app.try_state::<PendingLink>()?;
+    let link = state.0.lock().ok()?.take()?;
+    if Local::now().timestamp_millis() - link.created_at > LINK_TTL_MS {
+        return None;
+    }
+    Some(link)
+}
+
+// メインウィンドウがフォーカスされた時に呼ぶ
+pub fn on_focus(app: &AppHandle) {
+    if let Some(link) = take_pending(app) {
+        info!("🔗 [Notify] deep link -> {}:{}", link.kind, link.target);
+        let _ = app.emit("axis-deep-link", link);
+    }
+}
+
+// 確認待ちのアクションを通知する。アプリを見ている最中ならアプリ内の確認ダイアログに任せる
+pub fn confirm_request(app: &AppHandle, pending: &PendingAction) {
+    if main_window_focused(app) {
+        return;
+    }
+    let title = format!("Axis wants to run {}", pending.action);
+    #[cfg(windows)]
+    {
+        match confirm_toast(app, &title, pending) {
+            Ok(()) => return,
+            Err(e) => warn!("⚠️ [Notify] failed to show confirmation toast: {}", e),
+        }
+    }
+    notify(
+        app,
+        &title,
+        &pending.argument,
+        Some(DeepLink::new("confirm", &pending.id)),
+    );
+}
+
+#[cfg(windows)]
+fn confirm_toast(app: &AppHandle, title: &str, pending: &PendingAction) -> Result<(), String> {
+    use tauri_winrt_notification::{Scenario, Toast};
+
+    // 通知プラグインと同じく、インストールしていない（target/ から動かしている）時は PowerShell の ID で出す
+    let exe_dir = tauri::utils::platform::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|d| d.to_path_buf()))
+        .unwrap_or_default();
+    let app_id = if exe_dir.ends_with("target/debug") || exe_dir.ends_with("target/release") {
+        Toast::POWERSHELL_APP_ID.to_string()
+    } else {
+        app.config().identifier.clone()
+    };
+
+    // 切った引数のまま承認させない（見えていない所に何があるか分からない）
+    let truncated = pending.argument.chars().count() > TOAST_BODY_CHARS;
+    let body: String = pending.argument.chars().take(TOAST_BODY_CHARS).collect();
+    let handle = app.clone();
+    let id = pending.id.clone();
+    let mut toast = Toast::new(&app_id)
+        .title(title)
+        .text1(&body)
+        .scenario(Scenario::Reminder);
+    toast = if truncated {
+        toast
+            .text2("Too long to show here: review it in Axis.")
+            .add_button("Review in Axis", "review")
+    } else {
+        toast.add_button("Approve", "approve").add_button("Deny", "deny")
+    };
+    toast
+        .on_activated(move |action| {
+            let approve = match action.as_deref() {
+                Some("approve") => true,
+                Some("deny") => false,
+                // 本文のクリック / Review はアプリを開いて確認ダイアログで
+                _ => {
+                    set_pending(&handle, Some(DeepLink::new("confirm", &id)));
+                    if let Some(w) = handle.get_webview_window("main") {
+                        let _ = w.show();
+                        let _ = w.set_focus();
+                    }
+                    return Ok(());
+                }
+            };
+            // トーストのコールバックのスレッドで RUN などを待たない
+            let handle = handle.clone();
+            let id = id.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = policy::resolve(&handle, &id, approve).await {
+                    warn!("⚠️ [Notify] {}", e);
+                }
+            });
+            Ok(())
+        })
+        .show()
+        .map_err(|e| e.to_string())
+}
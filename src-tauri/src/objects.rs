@@ -0,0 +1,201 @@
+// src-tauri/src/objects.rs
+//
+// 添付オブジェクトの保存先（スクリーンショット / 生成ファイル / アップロード画像）
+// - ID: 中身の SHA-256（16 進）。同じ中身は 1 つにまとまる
+// - 保存先: axis_memory/objects/<id の先頭 2 文字>/<id> + <id>.meta.json（mime / size / name）
+// - 本体は crypto::seal を通す（encrypt_at_rest が有効なら暗号化）。ID は平文のハッシュ
+// - メモリの AttachmentRef.object_id から参照する。gc でどこからも参照されていないものを消す
+//   （undo.rs のバックアップも参照扱い）
+
+use crate::crypto;
+use crate::memory;
+use crate::undo;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+// 保存直後でまだメモリに紐付いていないものは gc で消さない
+const GC_GRACE_MS: i64 = 60 * 60 * 1000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ObjectMeta {
+    pub id: String,
+    #[serde(default)]
+    pub mime: String,
+    pub size: u64,
+    #[serde(default)]
+    pub name: String,
+    pub created_at: i64,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct GcReport {
+    pub kept: usize,
+    pub removed: usize,
+    pub freed_bytes: u64,
+}
+
+fn objects_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("axis_memory")
+        .join("objects");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn valid_id(id: &str) -> bool {
+    id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn object_paths(app: &AppHandle, id: &str) -> Result<(PathBuf, PathBuf), String> {
+    if !valid_id(id) {
+        return Err(format!("invalid object id: {}", id));
+    }
+    let dir = objects_dir(app)?.join(&id[..2]);
+    Ok((dir.join(id), dir.join(format!("{}.meta.json", id))))
+}
+
+pub fn hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// 保存して meta を返す（既にあれば書かずにそのまま）
+pub fn save(app: &AppHandle, bytes: &[u8], mime: &str, name: &str) -> Result<ObjectMeta, String> {
+    let id = hash(bytes);
+    if let Ok(meta) = load_meta(app, &id) {
+        return Ok(meta);
+    }
+    let (data_path, meta_path) = object_paths(app, &id)?;
+    if let Some(dir) = data_path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+
+    let meta = ObjectMeta {
+        id,
+        mime: mime.to_string(),
+        size: bytes.len() as u64,
+        name: name.to_string(),
+        created_at: Local::now().timestamp_millis(),
+    };
+    crypto::write(&data_path, bytes)?;
+    crypto::write(
+        &meta_path,
+        serde_json::to_string_pretty(&meta).map_err(|e| e.to_string())?,
+    )?;
+    info!(
+        "📦 [Objects] saved {} ({}, {} bytes)",
+        meta.id, meta.mime, meta.size
+    );
+    Ok(meta)
+}
+
+pub fn load_meta(app: &AppHandle, id: &str) -> Result<ObjectMeta, String> {
+    let (_, meta_path) = object_paths(app, id)?;
+    let s = crypto::read_to_string(meta_path)?;
+    serde_json::from_str(&s).map_err(|e| e.to_string())
+}
+
+pub fn load(app: &AppHandle, id: &str) -> Result<(Vec<u8>, ObjectMeta), String> {
+    let meta = load_meta(app, id)?;
+    let (data_path, _) = object_paths(app, id)?;
+    let raw = fs::read(data_path).map_err(|e| e.to_string())?;
+    let bytes = crypto::open(&raw)?;
+    if hash(&bytes) != meta.id {
+        return Err(format!("object {} is corrupted (hash mismatch)", id));
+    }
+    Ok((bytes, meta))
+}
+
+pub fn list(app: &AppHandle) -> Result<Vec<ObjectMeta>, String> {
+    let mut out = Vec::new();
+    for shard in fs::read_dir(objects_dir(app)?).map_err(|e| e.to_string())? {
+        let Ok(shard) = shard else { continue };
+        let Ok(files) = fs::read_dir(shard.path()) else {
+            continue;
+        };
+        for f in files.flatten() {
+            let name = f.file_name().to_string_lossy().to_string();
+            let Some(id) = name.strip_suffix(".meta.json") else {
+                continue;
+            };
+            if let Ok(meta) = load_meta(app, id) {
+                out.push(meta);
+            }
+        }
+    }
+    out.sort_by_key(|m| m.created_at);
+    Ok(out)
+}
+
+// 本体と meta を消して、消した大きさを返す
+// purge.rs: 本体と meta のファイル
+pub fn files(app: &AppHandle, id: &str) -> Result<Vec<PathBuf>, String> {
+    let (data_path, meta_path) = object_paths(app, id)?;
+    Ok(vec![data_path, meta_path])
+}
+
+pub fn remove(app: &AppHandle, id: &str) -> Result<u64, String> {
+    let size = load_meta(app, id).map(|m| m.size).unwrap_or(0);
+    let (data_path, meta_path) = object_paths(app, id)?;
+    let _ = fs::remove_file(&data_path);
+    fs::remove_file(&meta_path).map_err(|e| e.to_string())?;
+    Ok(size)
+}
+
+// どのメモリエントリからも参照されていないオブジェクトを消す
+pub fn gc(app: &AppHandle) -> Result<GcReport, String> {
+    let referenced: HashSet<String> = memory::export_all(app)?
+        .iter()
+        .flat_map(|(entry, _)| {
+            entry
+                .input
+                .attachments
+                .iter()
+                .chain(entry.output.attachments.iter())
+                .map(|a| a.object_id.clone())
+        })
+        .chain(undo::backup_ids(app)?)
+        .collect();
+
+    let now = Local::now().timestamp_millis();
+    let mut report = GcReport::default();
+    for meta in list(app)? {
+        if referenced.contains(&meta.id) || now - meta.created_at < GC_GRACE_MS {
+            report.kept += 1;
+            continue;
+        }
+        report.freed_bytes += remove(app, &meta.id)?;
+        report.removed += 1;
+    }
+    info!(
+        "📦 [Objects] gc: kept {}, removed {} ({} bytes)",
+        report.kept, report.removed, report.freed_bytes
+    );
+    Ok(report)
+}
+
+// 保存してメモリに付ける参照を返す
+pub fn attach(
+    app: &AppHandle,
+    bytes: &[u8],
+    mime: &str,
+    name: &str,
+) -> Result<memory::AttachmentRef, String> {
+    let meta = save(app, bytes, mime, name)?;
+    Ok(memory::AttachmentRef {
+        object_id: meta.id,
+        name: meta.name,
+        mime: meta.mime,
+    })
+}
@@ -1,61 +1,296 @@
 // src-tauri/src/observer.rs
-use tauri::{AppHandle, Emitter};
+use crate::activity::ActivityRecorder;
+use crate::notify::{self, DeepLink};
+use crate::observer_rules::{
+    self, ObserverRule, Observation, RuleAction, RuleEngine, ThrottleConfig,
+};
+use crate::system;
+use chrono::Local;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use crate::settings;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, info, warn};
 use std::process::Command;
 use std::thread;
 use std::time::Duration;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
+const TICK_SECS: u64 = 5;
+
+// 在席状態（get_presence コマンドから参照）
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct Presence {
+    pub away: bool,
+    pub idle_secs: u64,
+    pub away_since: Option<i64>,
+    pub last_return_at: Option<i64>,
+    // おやすみモード（この時刻まで notify / prompt を止める）
+    pub dnd_until: Option<i64>,
+    // 監視そのものを止めている（トレイの Pause Observer。活動記録もしない）
+    pub paused: bool,
+}
+
+#[derive(Default)]
+pub struct PresenceState(pub Mutex<Presence>);
+
+struct WindowSnapshot {
+    title: String,
+    app: String,
+    idle_ms: u64,
+}
+
+// この秒数だけ入力が無ければ「離席」とみなす（settings.observer_idle_secs / env: OBSERVER_IDLE_SECS）
+fn idle_threshold_secs() -> u64 {
+    settings::current().observer_idle_secs.max(TICK_SECS)
+}
+
+fn update_presence(app: &AppHandle, f: impl FnOnce(&mut Presence)) {
+    if let Some(state) = app.try_state::<PresenceState>() {
+        if let Ok(mut p) = state.0.lock() {
+            f(&mut p);
+        }
+    }
+}
+
+fn fmt_away(ms: i64) -> String {
+    let m = ms / 60_000;
+    if m >= 60 {
+        format!("{}h{:02}m", m / 60, m % 60)
+    } else {
+        format!("{}m", m.max(1))
+    }
+}
+
+// おやすみモード / 静音時間帯なら理由を返す
+fn muted(app: &AppHandle, rules: &RuleEngine) -> Option<&'static str> {
+    let dnd_until = current_presence(app).dnd_until;
+    if dnd_until.is_some_and(|until| Local::now().timestamp_millis() < until) {
+        return Some("do-not-disturb");
+    }
+    if rules.in_quiet_hours(Local::now().time()) {
+        return Some("quiet hours");
+    }
+    None
+}
+
+// 提案の出しすぎ防止（ルール毎クールダウン + 1時間の総数上限 + 静音時間帯 + おやすみモード）
+#[derive(Default)]
+struct Throttle {
+    last_fired: HashMap<String, Instant>,
+    recent: VecDeque<Instant>,
+}
+
+impl Throttle {
+    fn allow(&mut self, app: &AppHandle, rule: &ObserverRule, rules: &RuleEngine) -> Result<(), String> {
+        // log はユーザーに届かないので抑制しない
+        if matches!(rule.action, RuleAction::Log { .. }) {
+            return Ok(());
+        }
+
+        let config: &ThrottleConfig = &rules.throttle;
+        let now = Instant::now();
+
+        if let Some(reason) = muted(app, rules) {
+            return Err(reason.to_string());
+        }
+
+        let cooldown = Duration::from_secs(rule.cooldown_secs.unwrap_or(config.default_cooldown_secs));
+        if let Some(last) = self.last_fired.get(&rule.name) {
+            if now.duration_since(*last) < cooldown {
+                return Err(format!("cooldown ({}s)", cooldown.as_secs()));
+            }
+        }
+
+        while self
+            .recent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= Duration::from_secs(3600))
+        {
+            self.recent.pop_front();
+        }
+        if config.max_events_per_hour > 0 && self.recent.len() >= config.max_events_per_hour as usize {
+            return Err(format!("rate limit ({}/h)", config.max_events_per_hour));
+        }
+
+        self.last_fired.insert(rule.name.clone(), now);
+        self.recent.push_back(now);
+        Ok(())
+    }
+}
+
 // 監視ループの開始
 pub fn spawn_observer(app: AppHandle) {
+    app.manage(PresenceState::default());
+
     thread::spawn(move || {
         let mut last_window_title = String::new();
-        let mut same_window_count = 0; // 滞在時間の計測用
+        let mut same_window_count: u64 = 0; // 滞在時間の計測用
+        let mut rules = RuleEngine::load(&app);
+        let mut activity = ActivityRecorder::new(&app);
+        let mut away_since: Option<i64> = None;
+        let mut throttle = Throttle::default();
 
         loop {
             // 5秒おきにチェック
-            thread::sleep(Duration::from_secs(5));
+            thread::sleep(Duration::from_secs(TICK_SECS));
+            if current_presence(&app).paused {
+                continue;
+            }
+            rules.reload_if_changed(&app);
 
-            let current_title = get_active_window_title();
+            let snapshot = get_active_window();
+            let idle_secs = snapshot.idle_ms / 1000;
+            update_presence(&app, |p| p.idle_secs = idle_secs);
+
+            // ---- 離席中: 提案は止めて、タイムラインには空白区間を記録 ----
+            if idle_secs >= idle_threshold_secs() {
+                if away_since.is_none() {
+                    let since = Local::now().timestamp_millis() - snapshot.idle_ms as i64;
+                    info!("💤 [Observer] User away (idle {}s)", idle_secs);
+                    activity.away(since);
+                    away_since = Some(since);
+                    update_presence(&app, |p| {
+                        p.away = true;
+                        p.away_since = Some(since);
+                    });
+                } else {
+                    activity.touch();
+                }
+                continue;
+            }
+
+            // ---- 復帰: 挨拶して状態をリセット ----
+            if let Some(since) = away_since.take() {
+                let now = Local::now().timestamp_millis();
+                let away_ms = now - since;
+                info!("👋 [Observer] User returned after {}", fmt_away(away_ms));
+                activity.back();
+                update_presence(&app, |p| {
+                    p.away = false;
+                    p.away_since = None;
+                    p.last_return_at = Some(now);
+                });
+                let _ = app.emit(
+                    "axis-presence-event",
+                    serde_json::json!({ "state": "returned", "away_ms": away_ms }),
+                );
+                if muted(&app, &rules).is_none() {
+                    send_event(
+                        &app,
+                        "Welcome Back",
+                        &format!("Welcome back! You were away for {}.", fmt_away(away_ms)),
+                    );
+                }
+                // 戻ってきたウィンドウを新しい区間として数え直す
+                last_window_title.clear();
+                rules.reset_focus();
+            }
+
+            let WindowSnapshot {
+                title: current_title,
+                app: current_app,
+                ..
+            } = snapshot;
             
             // ウィンドウが変わった場合
             if current_title != last_window_title && !current_title.is_empty() {
-                println!("👀 [Observer] Focus changed to: {}", current_title);
-                
-                // 特定のキーワードに反応する「空気を読む」ロジック
-                if current_title.contains("Error") || current_title.contains("エラー") {
-                    send_event(&app, "Error Detected", &format!("Looks like an error occurred in '{}'. Need help?", current_title));
-                } else if current_title.contains("Visual Studio Code") || current_title.contains("VSCode") {
-                     // 頻繁に出るとうざいので、たまに言うなどの制御が必要だが、一旦テスト用に
-                     // send_event(&app, "Coding Mode", "System optimization for coding... ready.");
-                }
-
+                info!(title = %current_title, "👀 [Observer] Focus changed");
+                activity.focus(&current_title, &current_app);
                 last_window_title = current_title.clone();
                 same_window_count = 0;
+                rules.reset_focus();
             } else {
                 // 同じウィンドウを見続けている場合
                 same_window_count += 1;
-                
-                // 5秒 * 12回 = 60秒 (1分) 経過
-                if same_window_count == 12 {
-                    // YouTubeなどをダラダラ見ている時にチクリと言う
-                    if current_title.contains("YouTube") || current_title.contains("Netflix") {
-                         send_event(&app, "Suggestion", "You've been watching content for a while. focus_mode check?");
-                    }
+                activity.touch();
+            }
+
+            if last_window_title.is_empty() {
+                continue;
+            }
+
+            // CPU を使うルールがある時だけ計測（100ms 待ちが入るため）
+            let cpu = if rules.needs_cpu() {
+                Some(system::get_system_stats().cpu_usage as f32)
+            } else {
+                None
+            };
+
+            let obs = Observation {
+                title: &last_window_title,
+                dwell_secs: same_window_count * TICK_SECS,
+                cpu,
+            };
+
+            for rule in rules.evaluate(&obs) {
+                match throttle.allow(&app, &rule, &rules) {
+                    Ok(()) => fire(&app, &rule, &obs),
+                    Err(reason) => info!("🔕 [Observer] rule '{}' suppressed: {}", rule.name, reason),
                 }
             }
         }
     });
 }
 
+// ルールのアクションを実行
+fn fire(app: &AppHandle, rule: &ObserverRule, obs: &Observation) {
+    info!("👀 [Observer] rule fired: {}", rule.name);
+    match &rule.action {
+        RuleAction::Notify { topic, message } => {
+            send_event(app, topic, &observer_rules::render(message, obs));
+        }
+        RuleAction::Prompt { prompt, session_id } => {
+            let app = app.clone();
+            let prompt = observer_rules::render(prompt, obs);
+            let session_id = session_id
+                .clone()
+                .unwrap_or_else(|| format!("observer-{}", rule.name));
+            let topic = rule.name.clone();
+            tauri::async_runtime::spawn(async move {
+                match crate::ask_axis(app.clone(), prompt, session_id).await {
+                    Ok(answer) => send_event(&app, &topic, &answer),
+                    Err(e) => warn!("⚠️ [Observer] prompt rule failed: {}", e),
+                }
+            });
+        }
+        RuleAction::Log { label } => {
+            observer_rules::append_activity_log(app, &rule.name, label, obs);
+        }
+    }
+}
+
 // フロントエンドに通知を送る
 fn send_event(app: &AppHandle, topic: &str, message: &str) {
     // "axis-observer-event" というイベント名で発信
     let _ = app.emit("axis-observer-event", format!("[{}] {}", topic, message));
+    notify::notify(app, topic, message, Some(DeepLink::new("observer", topic)));
 }
 
-// PowerShellを使ってアクティブウィンドウのタイトルを取得
-fn get_active_window_title() -> String {
+// アクティブウィンドウの (タイトル, プロセス名, 無操作時間) を取得
+// win32.rs で直接（数秒おきに呼ぶので PowerShell を立ち上げない）。使えなかった時だけ下の PowerShell 版
+// 無操作時間は idle.rs（macOS / Linux も）。Windows 以外は前面ウィンドウを読まず、離席の判定だけ
+fn get_active_window() -> WindowSnapshot {
+    match crate::win32::active_window() {
+        Ok((title, app)) => {
+            return WindowSnapshot {
+                title,
+                app,
+                idle_ms: crate::idle::idle_ms().unwrap_or(0),
+            }
+        }
+        Err(_) if !cfg!(target_os = "windows") => {
+            return WindowSnapshot {
+                title: String::new(),
+                app: String::new(),
+                idle_ms: crate::idle::idle_ms().unwrap_or(0),
+            }
+        }
+        Err(e) => debug!("👀 [Observer] native window lookup failed, using PowerShell: {}", e),
+    }
     // C#のWin32APIラッパーをインライン定義して叩く（最速・確実）
     let ps_script = r#"
       Add-Type @"
@@ -64,12 +299,26 @@ fn get_active_window_title() -> String {
         public class Win32 {
           [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
           [DllImport("user32.dll")] public static extern int GetWindowText(IntPtr hWnd, System.Text.StringBuilder text, int count);
+          [DllImport("user32.dll")] public static extern uint GetWindowThreadProcessId(IntPtr hWnd, out uint processId);
+          [StructLayout(LayoutKind.Sequential)] public struct LASTINPUTINFO { public uint cbSize; public uint dwTime; }
+          [DllImport("user32.dll")] public static extern bool GetLastInputInfo(ref LASTINPUTINFO plii);
+          public static uint IdleMillis() {
+            LASTINPUTINFO lii = new LASTINPUTINFO();
+            lii.cbSize = (uint)Marshal.SizeOf(lii);
+            if (!GetLastInputInfo(ref lii)) { return 0; }
+            return (uint)Environment.TickCount - lii.dwTime;
+          }
         }
 "@
       $hwnd = [Win32]::GetForegroundWindow()
       $sb = New-Object System.Text.StringBuilder 256
       [Win32]::GetWindowText($hwnd, $sb, 256) > $null
+      $procId = 0
+      [Win32]::GetWindowThreadProcessId($hwnd, [ref]$procId) > $null
+      $proc = Get-Process -Id $procId -ErrorAction SilentlyContinue
       $sb.ToString()
+      if ($proc) { $proc.ProcessName } else { "" }
+      [Win32]::IdleMillis()
     "#;
 
     let output = Command::new("powershell")
@@ -78,7 +327,42 @@ fn get_active_window_title() -> String {
         .output();
 
     match output {
-        Ok(o) => String::from_utf8_lossy(&o.stdout).trim().to_string(),
-        Err(_) => String::new(),
+        Ok(o) => {
+            // 1行目: タイトル / 2行目: プロセス名 / 3行目: 無操作ミリ秒
+            let text = String::from_utf8_lossy(&o.stdout).to_string();
+            let mut lines = text.lines().map(|l| l.trim());
+            let title = lines.next().unwrap_or("").to_string();
+            let app = lines.next().unwrap_or("").to_string();
+            let idle_ms = lines.next().and_then(|l| l.parse::<u64>().ok()).unwrap_or(0);
+            WindowSnapshot { title, app, idle_ms }
+        }
+        Err(_) => WindowSnapshot {
+            title: String::new(),
+            app: String::new(),
+            idle_ms: 0,
+        },
     }
-}
\ No newline at end of file
+}
+
+// 在席状態の取得（コマンド用）
+pub fn current_presence(app: &AppHandle) -> Presence {
+    app.try_state::<PresenceState>()
+        .and_then(|s| s.0.lock().ok().map(|p| p.clone()))
+        .unwrap_or_default()
+}
+
+// おやすみモードの設定: minutes=None/0 で解除
+pub fn set_do_not_disturb(app: &AppHandle, minutes: Option<u64>) -> Presence {
+    let until = minutes
+        .filter(|m| *m > 0)
+        .map(|m| Local::now().timestamp_millis() + (m as i64) * 60_000);
+    update_presence(app, |p| p.dnd_until = until);
+    current_presence(app)
+}
+
+// 監視の一時停止 / 再開
+pub fn set_paused(app: &AppHandle, paused: bool) -> Presence {
+    update_presence(app, |p| p.paused = paused);
+    info!("👁️ [Observer] {}", if paused { "paused" } else { "resumed" });
+    current_presence(app)
+}
@@ -0,0 +1,400 @@
+// src-tauri/src/observer_rules.rs
+//
+// Observer のルールエンジン
+// - 設定: app_data_dir/observer_rules.json（無ければ埋め込みデフォルトを書き出す）
+// - 条件: ウィンドウタイトル正規表現 / 滞在秒数 / 時間帯 / CPU 負荷（指定したもの全ての AND）
+// - 動作: notify / prompt / log
+// - 抑制: ルール毎のクールダウン / 1時間あたりの上限 / 静音時間帯（判定は observer.rs 側）
+//
+// 発火は「条件が false → true になった瞬間」だけ。フォーカスが変わると状態をリセットする。
+
+use chrono::{Local, NaiveTime};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+// ビルド時に同ディレクトリのJSONを埋め込む
+const DEFAULT_RULES: &str = include_str!("observer_rules.json");
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RuleTrigger {
+    #[serde(default)]
+    pub title_regex: Option<String>,
+    #[serde(default)]
+    pub min_dwell_secs: Option<u64>,
+    // "09:00-18:00"（日跨ぎ "22:00-06:00" も可）
+    #[serde(default)]
+    pub time_range: Option<String>,
+    #[serde(default)]
+    pub min_cpu: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    Notify {
+        topic: String,
+        message: String,
+    },
+    Prompt {
+        prompt: String,
+        #[serde(default)]
+        session_id: Option<String>,
+    },
+    Log {
+        #[serde(default)]
+        label: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ObserverRule {
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    // 未指定なら throttle.default_cooldown_secs
+    #[serde(default)]
+    pub cooldown_secs: Option<u64>,
+    #[serde(default)]
+    pub trigger: RuleTrigger,
+    pub action: RuleAction,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ThrottleConfig {
+    #[serde(default = "default_cooldown_secs")]
+    pub default_cooldown_secs: u64,
+    // 0 = 無制限
+    #[serde(default = "default_max_events_per_hour")]
+    pub max_events_per_hour: u32,
+    // "23:00-07:00" のような時間帯のリスト。この間は notify / prompt を出さない
+    #[serde(default)]
+    pub quiet_hours: Vec<String>,
+}
+
+fn default_cooldown_secs() -> u64 {
+    300
+}
+fn default_max_events_per_hour() -> u32 {
+    6
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            default_cooldown_secs: default_cooldown_secs(),
+            max_events_per_hour: default_max_events_per_hour(),
+            quiet_hours: vec![],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ObserverConfig {
+    #[serde(default)]
+    pub throttle: ThrottleConfig,
+    #[serde(default)]
+    pub rules: Vec<ObserverRule>,
+}
+
+// 旧形式（ルール配列だけ）も読めるようにする
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ConfigFile {
+    Full(ObserverConfig),
+    Legacy(Vec<ObserverRule>),
+}
+
+impl From<ConfigFile> for ObserverConfig {
+    fn from(f: ConfigFile) -> Self {
+        match f {
+            ConfigFile::Full(c) => c,
+            ConfigFile::Legacy(rules) => ObserverConfig {
+                throttle: ThrottleConfig::default(),
+                rules,
+            },
+        }
+    }
+}
+
+// 1 tick 分の観測値
+pub struct Observation<'a> {
+    pub title: &'a str,
+    pub dwell_secs: u64,
+    pub cpu: Option<f32>,
+}
+
+struct CompiledRule {
+    rule: ObserverRule,
+    title_re: Option<Regex>,
+    time_range: Option<(NaiveTime, NaiveTime)>,
+}
+
+pub struct RuleEngine {
+    rules: Vec<CompiledRule>,
+    pub throttle: ThrottleConfig,
+    quiet_hours: Vec<(NaiveTime, NaiveTime)>,
+    // ルール名 → 直前の tick で条件を満たしていたか
+    active: HashMap<String, bool>,
+    source: Option<PathBuf>,
+    loaded_at: Option<SystemTime>,
+}
+
+// ---------- 読み込み ----------
+
+fn rules_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    if !app_dir.exists() {
+        fs::create_dir_all(&app_dir).map_err(|e| e.to_string())?;
+    }
+    Ok(app_dir.join("observer_rules.json"))
+}
+
+fn parse_time_range(s: &str) -> Result<(NaiveTime, NaiveTime), String> {
+    let (a, b) = s
+        .split_once('-')
+        .ok_or_else(|| format!("invalid time_range '{}': expected HH:MM-HH:MM", s))?;
+    let from = NaiveTime::parse_from_str(a.trim(), "%H:%M").map_err(|e| e.to_string())?;
+    let to = NaiveTime::parse_from_str(b.trim(), "%H:%M").map_err(|e| e.to_string())?;
+    Ok((from, to))
+}
+
+fn in_range(now: NaiveTime, (from, to): (NaiveTime, NaiveTime)) -> bool {
+    if from <= to {
+        now >= from && now < to
+    } else {
+        // 日跨ぎ
+        now >= from || now < to
+    }
+}
+
+fn compile(rules: Vec<ObserverRule>) -> Vec<CompiledRule> {
+    let mut out = Vec::new();
+    for rule in rules {
+        let title_re = match rule.trigger.title_regex.as_deref() {
+            Some(pat) => match Regex::new(pat) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    warn!(
+                        "⚠️ [Observer] rule '{}' skipped: bad regex: {}",
+                        rule.name, e
+                    );
+                    continue;
+                }
+            },
+            None => None,
+        };
+        let time_range = match rule.trigger.time_range.as_deref() {
+            Some(r) => match parse_time_range(r) {
+                Ok(tr) => Some(tr),
+                Err(e) => {
+                    warn!("⚠️ [Observer] rule '{}' skipped: {}", rule.name, e);
+                    continue;
+                }
+            },
+            None => None,
+        };
+        out.push(CompiledRule {
+            rule,
+            title_re,
+            time_range,
+        });
+    }
+    out
+}
+
+fn default_config() -> ObserverConfig {
+    serde_json::from_str::<ConfigFile>(DEFAULT_RULES)
+        .map(ObserverConfig::from)
+        .unwrap_or_default()
+}
+
+pub fn load_config(app: &AppHandle) -> Result<ObserverConfig, String> {
+    let path = rules_path(app)?;
+    if !path.exists() {
+        fs::write(&path, DEFAULT_RULES).map_err(|e| e.to_string())?;
+    }
+    let s = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str::<ConfigFile>(&s)
+        .map(ObserverConfig::from)
+        .map_err(|e| format!("observer_rules.json parse error: {}", e))
+}
+
+fn save_config(app: &AppHandle, config: &ObserverConfig) -> Result<(), String> {
+    // 保存前に正規表現等を検証
+    for r in &config.rules {
+        if let Some(pat) = r.trigger.title_regex.as_deref() {
+            Regex::new(pat).map_err(|e| format!("rule '{}': bad regex: {}", r.name, e))?;
+        }
+        if let Some(tr) = r.trigger.time_range.as_deref() {
+            parse_time_range(tr).map_err(|e| format!("rule '{}': {}", r.name, e))?;
+        }
+    }
+    for q in &config.throttle.quiet_hours {
+        parse_time_range(q).map_err(|e| format!("quiet_hours: {}", e))?;
+    }
+    let path = rules_path(app)?;
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+pub fn load_rules(app: &AppHandle) -> Result<Vec<ObserverRule>, String> {
+    Ok(load_config(app)?.rules)
+}
+
+pub fn save_rules(app: &AppHandle, rules: &[ObserverRule]) -> Result<(), String> {
+    let mut config = load_config(app).unwrap_or_else(|_| default_config());
+    config.rules = rules.to_vec();
+    save_config(app, &config)
+}
+
+pub fn save_throttle(app: &AppHandle, throttle: &ThrottleConfig) -> Result<(), String> {
+    let mut config = load_config(app).unwrap_or_else(|_| default_config());
+    config.throttle = throttle.clone();
+    save_config(app, &config)
+}
+
+fn modified_at(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl RuleEngine {
+    pub fn load(app: &AppHandle) -> Self {
+        let config = load_config(app).unwrap_or_else(|e| {
+            warn!("⚠️ [Observer] {} (using built-in rules)", e);
+            default_config()
+        });
+        let source = rules_path(app).ok();
+        let loaded_at = source.as_ref().and_then(modified_at);
+        info!("👀 [Observer] {} rule(s) loaded", config.rules.len());
+
+        let quiet_hours = config
+            .throttle
+            .quiet_hours
+            .iter()
+            .filter_map(|q| match parse_time_range(q) {
+                Ok(tr) => Some(tr),
+                Err(e) => {
+                    warn!("⚠️ [Observer] quiet_hours '{}' ignored: {}", q, e);
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            rules: compile(config.rules),
+            throttle: config.throttle,
+            quiet_hours,
+            active: HashMap::new(),
+            source,
+            loaded_at,
+        }
+    }
+
+    // 設定ファイルが更新されていたら読み直す
+    pub fn reload_if_changed(&mut self, app: &AppHandle) {
+        let current = self.source.as_ref().and_then(modified_at);
+        if current.is_some() && current != self.loaded_at {
+            *self = Self::load(app);
+        }
+    }
+
+    pub fn needs_cpu(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|c| c.rule.enabled && c.rule.trigger.min_cpu.is_some())
+    }
+
+    pub fn in_quiet_hours(&self, now: NaiveTime) -> bool {
+        self.quiet_hours.iter().any(|tr| in_range(now, *tr))
+    }
+
+    pub fn reset_focus(&mut self) {
+        self.active.clear();
+    }
+
+    fn matches(c: &CompiledRule, obs: &Observation) -> bool {
+        let t = &c.rule.trigger;
+        if let Some(re) = &c.title_re {
+            if !re.is_match(obs.title) {
+                return false;
+            }
+        }
+        if let Some(min) = t.min_dwell_secs {
+            if obs.dwell_secs < min {
+                return false;
+            }
+        }
+        if let Some(tr) = c.time_range {
+            if !in_range(Local::now().time(), tr) {
+                return false;
+            }
+        }
+        if let Some(min) = t.min_cpu {
+            match obs.cpu {
+                Some(cpu) if cpu >= min => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    // 今回新たに発火したルールを返す
+    pub fn evaluate(&mut self, obs: &Observation) -> Vec<ObserverRule> {
+        let mut fired = Vec::new();
+        for c in &self.rules {
+            if !c.rule.enabled {
+                continue;
+            }
+            let now_match = Self::matches(c, obs);
+            let was_match = self.active.insert(c.rule.name.clone(), now_match);
+            if now_match && !was_match.unwrap_or(false) {
+                fired.push(c.rule.clone());
+            }
+        }
+        fired
+    }
+}
+
+// "{title}" / "{cpu}" / "{dwell}" を埋める
+pub fn render(template: &str, obs: &Observation) -> String {
+    template
+        .replace("{title}", obs.title)
+        .replace("{dwell}", &obs.dwell_secs.to_string())
+        .replace(
+            "{cpu}",
+            &obs.cpu.map(|c| format!("{:.0}", c)).unwrap_or_default(),
+        )
+}
+
+// log アクション: app_data_dir/observer_activity.jsonl に追記
+pub fn append_activity_log(app: &AppHandle, rule: &str, label: &str, obs: &Observation) {
+    let Ok(app_dir) = app.path().app_data_dir() else {
+        return;
+    };
+    let line = serde_json::json!({
+        "timestamp": Local::now().timestamp_millis(),
+        "rule": rule,
+        "label": label,
+        "title": obs.title,
+        "dwell_secs": obs.dwell_secs,
+        "cpu": obs.cpu,
+    });
+    if let Ok(mut f) = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(app_dir.join("observer_activity.jsonl"))
+    {
+        let _ = writeln!(f, "{}", line);
+    }
+}
@@ -0,0 +1,126 @@
+// src-tauri/src/ocr.rs
+//
+// 画面の文字をその場で読む OCR（ローカル専用モードの LOOK / RECORD_SCREEN。local_only.rs）
+// - Windows は OS 標準の Windows.Media.Ocr（ユーザーの言語設定の言語パックで読む）
+// - それ以外は tesseract コマンド（PATH に無ければ使えない）
+// - どちらも端末の外には出ない。読めるのは文字だけで、画像やレイアウトの意味は分からない
+
+use base64::{engine::general_purpose, Engine as _};
+use tracing::info;
+
+pub const ENGINE: &str = platform::ENGINE;
+
+// PNG (base64) の文字を行ごとに
+pub async fn read(png_base64: &str) -> Result<String, String> {
+    let png = general_purpose::STANDARD
+        .decode(png_base64)
+        .map_err(|e| e.to_string())?;
+    let text = tokio::task::spawn_blocking(move || platform::read(&png))
+        .await
+        .map_err(|e| e.to_string())??;
+    let text = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    info!("🔤 [OCR] {}: {} chars", ENGINE, text.len());
+    if text.is_empty() {
+        return Err(format!("{} found no text on the screen", ENGINE));
+    }
+    Ok(text)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use image::imageops::FilterType;
+    use image::ImageOutputFormat;
+    use std::io::Cursor;
+    use windows::Graphics::Imaging::BitmapDecoder;
+    use windows::Media::Ocr::OcrEngine;
+    use windows::Storage::Streams::{DataWriter, InMemoryRandomAccessStream};
+    use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_MULTITHREADED};
+
+    pub const ENGINE: &str = "Windows.Media.Ocr";
+
+    // OcrEngine は MaxImageDimension を超える画像を読まないので縮める
+    fn fit(png: &[u8], max: u32) -> Result<Vec<u8>, String> {
+        let image = image::load_from_memory(png).map_err(|e| e.to_string())?;
+        if image.width().max(image.height()) <= max {
+            return Ok(png.to_vec());
+        }
+        let mut out = Vec::new();
+        image
+            .resize(max, max, FilterType::Triangle)
+            .write_to(&mut Cursor::new(&mut out), ImageOutputFormat::Png)
+            .map_err(|e| e.to_string())?;
+        Ok(out)
+    }
+
+    fn recognize(png: &[u8]) -> windows::core::Result<String> {
+        let stream = InMemoryRandomAccessStream::new()?;
+        let writer = DataWriter::CreateDataWriter(&stream)?;
+        writer.WriteBytes(png)?;
+        writer.StoreAsync()?.get()?;
+        writer.DetachStream()?;
+        stream.Seek(0)?;
+        let bitmap = BitmapDecoder::CreateAsync(&stream)?
+            .get()?
+            .GetSoftwareBitmapAsync()?
+            .get()?;
+        let engine = OcrEngine::TryCreateFromUserProfileLanguages()?;
+        let lines = engine.RecognizeAsync(&bitmap)?.get()?.Lines()?;
+        let mut text = Vec::new();
+        for i in 0..lines.Size()? {
+            text.push(lines.GetAt(i)?.Text()?.to_string());
+        }
+        Ok(text.join("\n"))
+    }
+
+    pub fn read(png: &[u8]) -> Result<String, String> {
+        let max = OcrEngine::MaxImageDimension().unwrap_or(2600);
+        let png = fit(png, max)?;
+        unsafe {
+            let init = CoInitializeEx(None, COINIT_MULTITHREADED);
+            // 言語パックが 1 つも無いと TryCreateFromUserProfileLanguages が空を返す
+            let result = recognize(&png).map_err(|e| format!("{} is unavailable: {}", ENGINE, e));
+            if init.is_ok() {
+                CoUninitialize();
+            }
+            result
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    pub const ENGINE: &str = "tesseract";
+
+    // tesseract stdin stdout: PNG を標準入力で渡し、文字を標準出力で受け取る
+    pub fn read(png: &[u8]) -> Result<String, String> {
+        let mut child = Command::new("tesseract")
+            .args(["stdin", "stdout"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("tesseract is not installed: {}", e))?;
+        child
+            .stdin
+            .take()
+            .ok_or("tesseract: no stdin")?
+            .write_all(png)
+            .map_err(|e| e.to_string())?;
+        let out = child.wait_with_output().map_err(|e| e.to_string())?;
+        if !out.status.success() {
+            return Err(format!(
+                "tesseract failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+}
@@ -0,0 +1,296 @@
+// src-tauri/src/outcomes.rs
+//
+// モデル毎の実績から ModelScore を補正する（学習するルーティング）
+// - 記録: ask_axis の 1 往復毎に memory.db の model_outcomes へ
+//         (モデル, task_type, 成否, レイテンシ, 言い直し, 👍/👎)
+// - 言い直し: 同じセッションで直後に似た質問が来たら前の回答は「不満」扱い
+// - 評価: rate_response で 👍/👎 を InteractionLog / メモリ meta / model_outcomes に記録
+// - 補正: 10 分おきに直近 30 日分を集計し、task_type に対応する項目と speed を最大 ±MAX_SHIFT ずらす
+//         （model_profiles.rs の基準値はそのまま。件数が少ないうちは補正も小さい）
+// - usage_today: 今日の件数 / モデル別 / 平均レイテンシ（トレイの Show Usage Today）
+
+use crate::db::AxisDatabase;
+use crate::memory;
+use crate::model_profiles;
+use crate::routing;
+use crate::storage::{self, InteractionLog, ResponseFeedback};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+const RECOMPUTE_INTERVAL: Duration = Duration::from_secs(600);
+const WINDOW_MS: i64 = 30 * 24 * 60 * 60 * 1000;
+
+// 基準値からずらす最大幅 (スコアは 0.0〜1.0)
+const MAX_SHIFT: f32 = 0.1;
+// この件数で補正が満額になる
+const FULL_CONFIDENCE_SAMPLES: f32 = 20.0;
+// 何も言われずに成功した回答の品質（👍 = 1.0 / 👎・エラー = 0.0）
+const NEUTRAL_QUALITY: f32 = 0.7;
+const REASKED_QUALITY: f32 = 0.3;
+
+// 言い直し判定
+const REASK_WINDOW_MS: i64 = 10 * 60 * 1000;
+const REASK_SIMILARITY: f32 = 0.6;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModelOutcome {
+    pub log_id: String,
+    pub session_id: String,
+    pub model: String,
+    pub task_type: String,
+    pub success: bool,
+    pub latency_ms: i64,
+    pub reasked: bool,
+    pub rating: Option<i64>, // +1 / -1（未評価は None）
+    pub created_at: i64,
+}
+
+// Commander の task_type ("code_edit" など自由記述) → ModelScore の項目
+pub fn dimension_for(task_type: &str) -> &'static str {
+    let t = task_type.to_lowercase();
+    if t.contains("code") || t.contains("program") || t.contains("debug") {
+        "code"
+    } else if t.contains("math") || t.contains("calc") {
+        "math"
+    } else if t.contains("plan") || t.contains("roadmap") || t.contains("design") {
+        "planning"
+    } else if t.contains("image") || t.contains("vision") || t.contains("multimodal") {
+        "multimodal"
+    } else if t.contains("reason") || t.contains("analy") || t.contains("logic") {
+        "reasoning"
+    } else {
+        "general_qa"
+    }
+}
+
+pub fn record(app: &AppHandle, outcome: &ModelOutcome) {
+    let result = AxisDatabase::open(app)
+        .and_then(|db| db.insert_model_outcome(outcome).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("⚠️ [Outcomes] failed to record outcome: {}", e);
+    }
+}
+
+// 直前の質問とほぼ同じなら、前の回答を「言い直された」として記録
+pub fn note_reask(
+    app: &AppHandle,
+    prev_log_id: &str,
+    prev_input: &str,
+    prev_ts: i64,
+    input: &str,
+    now_ms: i64,
+) {
+    if now_ms - prev_ts > REASK_WINDOW_MS
+        || routing::similarity(prev_input, input) < REASK_SIMILARITY
+    {
+        return;
+    }
+    info!(
+        "🔁 [Outcomes] re-ask detected (previous answer {})",
+        prev_log_id
+    );
+    if let Ok(db) = AxisDatabase::open(app) {
+        let _ = db.mark_model_outcome_reasked(prev_log_id);
+    }
+}
+
+// rate_response コマンド本体。rating は +1 / -1（0 で取り消し）
+pub fn rate_response(
+    app: &AppHandle,
+    log_id: &str,
+    rating: i64,
+    comment: Option<String>,
+) -> Result<InteractionLog, String> {
+    if !(-1..=1).contains(&rating) {
+        return Err("rating must be 1 (up), -1 (down) or 0 (clear)".to_string());
+    }
+    let feedback = (rating != 0).then(|| ResponseFeedback {
+        rating,
+        comment: comment
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty()),
+        rated_at: Local::now().timestamp_millis(),
+    });
+
+    let (previous, log) = storage::set_feedback(app, log_id, feedback.clone())?;
+    let previous = previous.map(|f| f.rating);
+
+    // 答え直しの枝は記憶に保存していない
+    if log.branch_of.is_none() {
+        match memory::apply_feedback(
+            app,
+            log_id,
+            &log.session_id,
+            log.timestamp,
+            previous,
+            feedback,
+        ) {
+            Ok(Some(id)) => info!("👍 [Outcomes] feedback {} applied to memory {}", rating, id),
+            Ok(None) => warn!("⚠️ [Outcomes] no memory entry found for log {}", log_id),
+            Err(e) => warn!("⚠️ [Outcomes] memory feedback failed: {}", e),
+        }
+    }
+
+    // ルーティング統計にもすぐ反映
+    let rating = (rating != 0).then_some(rating);
+    AxisDatabase::open(app)?
+        .set_model_outcome_rating(log_id, rating)
+        .map_err(|e| e.to_string())?;
+    recompute(app)?;
+
+    Ok(log)
+}
+
+fn quality(o: &ModelOutcome) -> f32 {
+    if !o.success {
+        return 0.0;
+    }
+    match o.rating {
+        Some(r) if r > 0 => 1.0,
+        Some(r) if r < 0 => 0.0,
+        _ if o.reasked => REASKED_QUALITY,
+        _ => NEUTRAL_QUALITY,
+    }
+}
+
+fn confidence(n: usize) -> f32 {
+    (n as f32 / FULL_CONFIDENCE_SAMPLES).min(1.0)
+}
+
+// モデル → (項目 → 補正値)
+pub type Adjustments = HashMap<String, HashMap<String, f32>>;
+
+pub fn compute_adjustments(outcomes: &[ModelOutcome]) -> Adjustments {
+    let mut out: Adjustments = HashMap::new();
+
+    // 品質: (モデル, 項目) 毎の平均と NEUTRAL_QUALITY の差
+    let mut quality_sums: HashMap<(String, &'static str), (f32, usize)> = HashMap::new();
+    for o in outcomes {
+        let e = quality_sums
+            .entry((o.model.clone(), dimension_for(&o.task_type)))
+            .or_default();
+        e.0 += quality(o);
+        e.1 += 1;
+    }
+    for ((model, dim), (sum, n)) in quality_sums {
+        let mean = sum / n as f32;
+        let shift = ((mean - NEUTRAL_QUALITY) / NEUTRAL_QUALITY).clamp(-1.0, 1.0);
+        out.entry(model)
+            .or_default()
+            .insert(dim.to_string(), shift * MAX_SHIFT * confidence(n));
+    }
+
+    // 速度: 成功した応答の平均レイテンシをモデル間の平均と比べる
+    let mut latency: HashMap<String, (f64, usize)> = HashMap::new();
+    for o in outcomes.iter().filter(|o| o.success && o.latency_ms > 0) {
+        let e = latency.entry(o.model.clone()).or_default();
+        e.0 += o.latency_ms as f64;
+        e.1 += 1;
+    }
+    let averages: Vec<(String, f64, usize)> = latency
+        .into_iter()
+        .map(|(m, (sum, n))| (m, sum / n as f64, n))
+        .collect();
+    if averages.len() >= 2 {
+        let reference = averages.iter().map(|(_, a, _)| a).sum::<f64>() / averages.len() as f64;
+        for (model, avg, n) in averages {
+            let shift = ((reference - avg) / reference).clamp(-1.0, 1.0) as f32;
+            out.entry(model)
+                .or_default()
+                .insert("speed".to_string(), shift * MAX_SHIFT * confidence(n));
+        }
+    }
+
+    out
+}
+
+pub fn recompute(app: &AppHandle) -> Result<usize, String> {
+    let since = Local::now().timestamp_millis() - WINDOW_MS;
+    let outcomes = AxisDatabase::open(app)?
+        .model_outcomes_since(since)
+        .map_err(|e| e.to_string())?;
+    let adjustments = compute_adjustments(&outcomes);
+    model_profiles::set_adjustments(adjustments);
+    Ok(outcomes.len())
+}
+
+// 今日（0 時から）の利用状況（トレイの Show Usage Today / get_usage_today）
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct UsageSummary {
+    pub requests: usize,
+    pub failures: usize,
+    pub avg_latency_ms: i64,
+    pub by_model: BTreeMap<String, usize>,
+}
+
+pub fn usage_today(app: &AppHandle) -> Result<UsageSummary, String> {
+    let midnight = Local::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .and_then(|t| t.and_local_timezone(Local).earliest())
+        .map(|t| t.timestamp_millis())
+        .unwrap_or(0);
+    let outcomes = AxisDatabase::open(app)?
+        .model_outcomes_since(midnight)
+        .map_err(|e| e.to_string())?;
+    let mut usage = UsageSummary {
+        requests: outcomes.len(),
+        ..Default::default()
+    };
+    for o in &outcomes {
+        *usage.by_model.entry(o.model.clone()).or_default() += 1;
+        if !o.success {
+            usage.failures += 1;
+        }
+    }
+    if !outcomes.is_empty() {
+        usage.avg_latency_ms =
+            outcomes.iter().map(|o| o.latency_ms).sum::<i64>() / outcomes.len() as i64;
+    }
+    Ok(usage)
+}
+
+impl UsageSummary {
+    // "12 requests (gpt 8, gemini 4), avg 2.3s"
+    pub fn summarize(&self) -> String {
+        if self.requests == 0 {
+            return "No requests yet today.".to_string();
+        }
+        let models: Vec<String> = self
+            .by_model
+            .iter()
+            .map(|(m, n)| format!("{} {}", m, n))
+            .collect();
+        let mut out = format!(
+            "{} requests ({}), avg {:.1}s",
+            self.requests,
+            models.join(", "),
+            self.avg_latency_ms as f64 / 1000.0
+        );
+        if self.failures > 0 {
+            out.push_str(&format!(", {} failed", self.failures));
+        }
+        out
+    }
+}
+
+// 起動時（setup）に呼ぶ: 起動直後 + RECOMPUTE_INTERVAL 毎に補正を計算し直す
+pub fn spawn_learner(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let handle = app.clone();
+            match tauri::async_runtime::spawn_blocking(move || recompute(&handle)).await {
+                Ok(Ok(n)) if n > 0 => {
+                    info!("📈 [Outcomes] model scores adjusted from {} outcomes", n)
+                }
+                Ok(Err(e)) => warn!("⚠️ [Outcomes] recompute failed: {}", e),
+                _ => {}
+            }
+            tokio::time::sleep(RECOMPUTE_INTERVAL).await;
+        }
+    });
+}
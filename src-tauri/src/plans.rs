@@ -0,0 +1,312 @@
+// src-tauri/src/plans.rs
+//
+// 複数ステップの計画と実行 (PLAN: <goal>)
+// - settings.planner のモデルが手順の一覧を JSON で返す（prompts/planner.md）→ memory.db の plans に保存
+// - 手順は 1 つずつ ask_axis に投げる（元のセッションで。各ステップの会話も履歴に残る）
+// - ステップが終わる度に状態を保存して axis-plan-progress イベント（Plan そのまま）= チェックポイント
+// - pause / resume / skip / cancel はステップの切れ目で効く。失敗したステップで一時停止して判断を待つ
+// - settings.plan_auto_start が false（既定）なら作っただけで止めておき、start_plan を待つ
+// - 前のステップの結果は次のステップに渡す。外の文面を読んだステップの結果は囲んで (untrusted::wrap)
+
+use crate::db::AxisDatabase;
+use crate::{ai, prompts, settings};
+use axis_core::untrusted;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
+use uuid::Uuid;
+
+const PROGRESS_EVENT: &str = "axis-plan-progress";
+// 次のステップに渡す前のステップの結果
+const CARRY_CHARS: usize = 600;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PlanStep {
+    pub description: String,
+    pub status: String, // pending / running / done / failed / skipped
+    #[serde(default)]
+    pub result: String,
+    #[serde(default)]
+    pub untrusted: bool, // 外の文面を読んだ答え（ResponseMeta.untrusted）
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Plan {
+    pub id: String,
+    pub session_id: String,
+    pub goal: String,
+    pub status: String, // planned / running / paused / done / cancelled
+    pub steps: Vec<PlanStep>,
+    pub message: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// 実行ループが回っているプラン (Tauri managed state)。同じプランを 2 本で回さない
+#[derive(Default)]
+pub struct PlanRunners(Mutex<HashSet<String>>);
+
+#[derive(Deserialize)]
+struct PlannerReply {
+    steps: Vec<String>,
+}
+
+fn save(app: &AppHandle, plan: &mut Plan) -> Result<(), String> {
+    plan.updated_at = Local::now().timestamp_millis();
+    AxisDatabase::open(app)?
+        .save_plan(plan)
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit(PROGRESS_EVENT, &*plan);
+    Ok(())
+}
+
+pub fn get(app: &AppHandle, id: &str) -> Result<Plan, String> {
+    AxisDatabase::open(app)?
+        .get_plan(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("plan '{}' not found", id))
+}
+
+pub fn list(app: &AppHandle, limit: usize) -> Result<Vec<Plan>, String> {
+    AxisDatabase::open(app)?
+        .list_plans(limit)
+        .map_err(|e| e.to_string())
+}
+
+async fn make_steps(app: &AppHandle, goal: &str) -> Result<Vec<String>, String> {
+    let cfg = settings::current();
+    let max = cfg.plan_max_steps.max(1);
+    let alias = cfg.planner.trim().to_lowercase();
+    let sys = prompts::render(
+        app,
+        "planner",
+        &[("goal", goal), ("max_steps", &max.to_string())],
+    );
+    info!(prompt = %goal, "🗺️ [Plans] {} planning", alias);
+    let raw = ai::call_alias(&alias, &cfg.models.for_alias(&alias), &sys, goal).await?;
+    let start = raw.find('{').ok_or("planner returned no JSON")?;
+    let end = raw.rfind('}').ok_or("planner returned no JSON")?;
+    let reply: PlannerReply =
+        serde_json::from_str(&raw[start..=end]).map_err(|e| format!("planner JSON: {}", e))?;
+    let steps: Vec<String> = reply
+        .steps
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .take(max)
+        .collect();
+    if steps.is_empty() {
+        return Err("planner returned no steps".to_string());
+    }
+    Ok(steps)
+}
+
+pub async fn create(app: &AppHandle, goal: &str, session_id: &str) -> Result<Plan, String> {
+    let goal = goal.trim();
+    if goal.is_empty() {
+        return Err("PLAN needs a goal".to_string());
+    }
+    // 実行中のステップから PLAN: がまた出ても入れ子にしない
+    let busy = list(app, 20)?
+        .into_iter()
+        .any(|p| p.session_id == session_id && p.status == "running");
+    if busy {
+        return Err("a plan is already running in this session".to_string());
+    }
+    let steps = make_steps(app, goal).await?;
+    let now = Local::now().timestamp_millis();
+    let mut plan = Plan {
+        id: Uuid::new_v4().to_string(),
+        session_id: session_id.to_string(),
+        goal: goal.to_string(),
+        status: "planned".to_string(),
+        steps: steps
+            .into_iter()
+            .map(|description| PlanStep {
+                description,
+                status: "pending".to_string(),
+                ..Default::default()
+            })
+            .collect(),
+        message: String::new(),
+        created_at: now,
+        updated_at: now,
+    };
+    save(app, &mut plan)?;
+    if settings::current().plan_auto_start {
+        return start(app, &plan.id);
+    }
+    Ok(plan)
+}
+
+fn step_prompt(plan: &Plan, index: usize) -> String {
+    let mut prompt = format!(
+        "[Plan step {}/{}] {}\n(Overall goal: {})\n",
+        index + 1,
+        plan.steps.len(),
+        plan.steps[index].description,
+        plan.goal
+    );
+    if let Some(prev) = plan.steps[..index]
+        .iter()
+        .rev()
+        .find(|s| s.status == "done")
+    {
+        // 外の文面を読んだステップの結果は資料として囲む（この依頼の操作は確認に回る）
+        let carry: String = if prev.untrusted {
+            untrusted::clip(&untrusted::wrap("plan_step", &prev.result), CARRY_CHARS)
+        } else {
+            prev.result.chars().take(CARRY_CHARS).collect()
+        };
+        prompt.push_str(&format!("Previous step result: {}\n", carry));
+    }
+    prompt.push_str("Do ONLY this step. Do not output PLAN:.");
+    prompt
+}
+
+async fn run_loop(app: AppHandle, id: String) {
+    while let Ok(mut plan) = get(&app, &id) {
+        if plan.status != "running" {
+            break;
+        }
+        let Some(index) = plan.steps.iter().position(|s| s.status == "pending") else {
+            plan.status = "done".to_string();
+            plan.message = "all steps finished".to_string();
+            let _ = save(&app, &mut plan);
+            info!(text = %plan.goal, "🗺️ [Plans] done");
+            break;
+        };
+        plan.steps[index].status = "running".to_string();
+        plan.message = format!("step {}/{}", index + 1, plan.steps.len());
+        let _ = save(&app, &mut plan);
+
+        let prompt = step_prompt(&plan, index);
+        let outcome = crate::ask_step(app.clone(), prompt, plan.session_id.clone()).await;
+
+        // 実行中に pause / cancel / skip されていても、このステップの結果は残す
+        let Ok(mut plan) = get(&app, &id) else {
+            break;
+        };
+        match outcome {
+            Ok((answer, read_untrusted)) => {
+                plan.steps[index].status = "done".to_string();
+                plan.steps[index].result = answer;
+                plan.steps[index].untrusted = read_untrusted;
+            }
+            Err(e) => {
+                plan.steps[index].status = "failed".to_string();
+                plan.steps[index].result = e.clone();
+                if plan.status == "running" {
+                    plan.status = "paused".to_string();
+                    plan.message = format!("step {} failed: {}", index + 1, e);
+                }
+            }
+        }
+        let _ = save(&app, &mut plan);
+    }
+    if let Some(state) = app.try_state::<PlanRunners>() {
+        if let Ok(mut set) = state.0.lock() {
+            set.remove(&id);
+        }
+    }
+}
+
+// 開始 / 再開（失敗したステップはやり直す）
+pub fn start(app: &AppHandle, id: &str) -> Result<Plan, String> {
+    let mut plan = get(app, id)?;
+    if matches!(plan.status.as_str(), "done" | "cancelled") {
+        return Err(format!("plan is already {}", plan.status));
+    }
+    for step in plan.steps.iter_mut().filter(|s| s.status == "failed") {
+        step.status = "pending".to_string();
+    }
+    plan.status = "running".to_string();
+    plan.message = String::new();
+    save(app, &mut plan)?;
+
+    let state = app
+        .try_state::<PlanRunners>()
+        .ok_or("plan runner is not available")?;
+    // ループが回っていれば status を見て続きをやる
+    if state
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(plan.id.clone())
+    {
+        tauri::async_runtime::spawn(run_loop(app.clone(), plan.id.clone()));
+    }
+    Ok(plan)
+}
+
+pub fn pause(app: &AppHandle, id: &str) -> Result<Plan, String> {
+    let mut plan = get(app, id)?;
+    if plan.status != "running" {
+        return Err(format!("plan is {}, not running", plan.status));
+    }
+    plan.status = "paused".to_string();
+    plan.message = "paused after the current step".to_string();
+    save(app, &mut plan)?;
+    Ok(plan)
+}
+
+pub fn skip(app: &AppHandle, id: &str, index: usize) -> Result<Plan, String> {
+    let mut plan = get(app, id)?;
+    let step = plan
+        .steps
+        .get_mut(index)
+        .ok_or_else(|| format!("plan has no step {}", index + 1))?;
+    if !matches!(step.status.as_str(), "pending" | "failed") {
+        return Err(format!("step {} is {}", index + 1, step.status));
+    }
+    step.status = "skipped".to_string();
+    save(app, &mut plan)?;
+    Ok(plan)
+}
+
+pub fn cancel(app: &AppHandle, id: &str) -> Result<Plan, String> {
+    let mut plan = get(app, id)?;
+    plan.status = "cancelled".to_string();
+    plan.message = "cancelled".to_string();
+    save(app, &mut plan)?;
+    Ok(plan)
+}
+
+// 起動時: 前回途中だったプランは一時停止に（勝手に続きをやらない）
+pub fn recover(app: &AppHandle) {
+    let Ok(plans) = list(app, 50) else {
+        return;
+    };
+    for mut plan in plans.into_iter().filter(|p| p.status == "running") {
+        for step in plan.steps.iter_mut().filter(|s| s.status == "running") {
+            step.status = "pending".to_string();
+        }
+        plan.status = "paused".to_string();
+        plan.message = "interrupted (app closed)".to_string();
+        let _ = save(app, &mut plan);
+    }
+}
+
+// Worker の "PLAN: <goal>"。手順を system_context に並べる
+pub async fn run_command(app: &AppHandle, goal: &str, session_id: &str) -> String {
+    match create(app, goal, session_id).await {
+        Ok(plan) => {
+            let steps: String = plan
+                .steps
+                .iter()
+                .enumerate()
+                .map(|(i, s)| format!("{}. {}\n", i + 1, s.description))
+                .collect();
+            let next = if plan.status == "running" {
+                "Running the steps one at a time now."
+            } else {
+                "Waiting for the user to start it."
+            };
+            format!("[Plan {}] {}\n{}{}\n", plan.id, plan.goal, steps, next)
+        }
+        Err(e) => format!("[System] Plan Error: {}\n", e),
+    }
+}
@@ -0,0 +1,390 @@
+// src-tauri/src/plugins.rs
+//
+// プラグイン（サードパーティのアクション / コネクタ）。マニフェスト + サブプロセスのプロトコル
+// - app_data_dir/plugins/<folder>/plugin.json を起動時と reload_plugins で読む
+//     { "name": "jira", "description": "Jira Cloud", "command": ["python", "jira.py"], "timeout_secs": 30,
+//       "actions": [{ "name": "JIRA", "usage": "JIRA: create <summary>", "description": "Create a ticket", "confirm": true }] }
+//   actions の NAME は大文字と _ だけ。組み込みのアクション / 先に読んだプラグインと重なるものは読まない
+// - NAME は axis_core::command に登録する（Worker の出力の "JIRA: ..." が Action::Plugin に）
+//   worker プロンプトの {{plugins}} に usage と説明を並べる
+// - 実行: plugin.json のあるフォルダで command を 1 回ごとに起動し、stdin に 1 行の JSON
+//     {"action": "JIRA", "argument": "create Fix login", "session_id": "..."}
+//   stdout に {"ok": true, "output": "..."} か {"ok": false, "error": "..."}（JSON でなければ stdout をそのまま）
+//   timeout_secs (既定 30) を過ぎたら止める。環境変数は PLUGIN_ENV だけ渡す（API キーなどを継がせない）
+//   stdout / stderr は MAX_READ_BYTES まで読み、残りは捨てる
+// - ポリシー: confirm: true のものと settings.confirm_actions に入れた NAME は確認待ちに (policy::run_or_queue)
+//   結果は外の文面として囲む (untrusted_source "plugin")。外の文面の後のプラグインは確認に回す
+//   渡した引数は外に出したものとして記録する (sharing.rs。provider "plugin:<name>")
+// - settings.plugins_enabled = false / ローカル専用モードでは、一覧には出るがプロンプトに出さず実行もしない
+
+use crate::{local_only, settings, sharing};
+use axis_core::command;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+const MANIFEST: &str = "plugin.json";
+const MAX_OUTPUT_CHARS: usize = 8000;
+// パイプから取っておく上限（JSON の応答はこれに収まる前提。超えた分は読み捨てる）
+const MAX_READ_BYTES: usize = 1024 * 1024;
+// プラグインに引き継ぐ環境変数（プログラムを探す / 一時ファイル / 文字コードに要るものだけ）
+const PLUGIN_ENV: &[&str] = &[
+    "PATH", "PATHEXT", "SYSTEMROOT", "WINDIR", "COMSPEC", "TEMP", "TMP", "HOME", "USERPROFILE",
+    "LANG", "LC_ALL",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PluginAction {
+    pub name: String, // "JIRA"
+    #[serde(default)]
+    pub usage: String, // 空なら "JIRA: <argument>"
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub confirm: bool, // 毎回ユーザーに確認する
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Manifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub version: String,
+    pub command: Vec<String>, // プログラムと引数。プログラムがフォルダの中にあればそれを使う
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    pub actions: Vec<PluginAction>,
+}
+
+fn default_timeout() -> u64 {
+    30
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PluginInfo {
+    pub dir: String,
+    #[serde(flatten)]
+    pub manifest: Manifest,
+    pub error: Option<String>, // 読めなかった理由（この時は使えない）
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    action: &'a str,
+    argument: &'a str,
+    session_id: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    ok: bool,
+    #[serde(default)]
+    output: String,
+    #[serde(default)]
+    error: String,
+}
+
+static PLUGINS: Mutex<Vec<PluginInfo>> = Mutex::new(Vec::new());
+
+fn plugins_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("plugins");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn available() -> bool {
+    settings::current().plugins_enabled && !local_only::enabled()
+}
+
+fn read_manifest(dir: &Path, taken: &mut HashSet<String>) -> Result<Manifest, String> {
+    let text = fs::read_to_string(dir.join(MANIFEST)).map_err(|e| e.to_string())?;
+    let mut manifest: Manifest =
+        serde_json::from_str(&text).map_err(|e| format!("invalid {}: {}", MANIFEST, e))?;
+    manifest.name = manifest.name.trim().to_string();
+    if manifest.name.is_empty() {
+        return Err("name is empty".to_string());
+    }
+    if manifest.command.first().is_none_or(|c| c.trim().is_empty()) {
+        return Err("command is empty".to_string());
+    }
+    if manifest.actions.is_empty() {
+        return Err("no actions".to_string());
+    }
+    let mut names = HashSet::new();
+    for action in &mut manifest.actions {
+        action.name = action.name.trim().to_string();
+        let name = &action.name;
+        if name.len() < 2 || !name.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+            return Err(format!(
+                "action name '{}' must be upper-case letters and '_'",
+                name
+            ));
+        }
+        if command::is_builtin(name) {
+            return Err(format!("action {} is a built-in action", name));
+        }
+        if taken.contains(name) || !names.insert(name.clone()) {
+            return Err(format!("action {} is already provided by a plugin", name));
+        }
+        if action.usage.trim().is_empty() {
+            action.usage = format!("{}: <argument>", name);
+        }
+    }
+    taken.extend(names);
+    Ok(manifest)
+}
+
+// plugins/ を読み直して、アクションの NAME をパーサに登録する
+pub fn load(app: &AppHandle) -> Vec<PluginInfo> {
+    let mut dirs: Vec<PathBuf> = match plugins_dir(app) {
+        Ok(dir) => fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.join(MANIFEST).is_file())
+            .collect(),
+        Err(e) => {
+            warn!("⚠️ [Plugins] no plugins folder: {}", e);
+            Vec::new()
+        }
+    };
+    dirs.sort();
+
+    let mut taken = HashSet::new();
+    let plugins: Vec<PluginInfo> = dirs
+        .into_iter()
+        .map(|dir| {
+            let (manifest, error) = match read_manifest(&dir, &mut taken) {
+                Ok(m) => (m, None),
+                Err(e) => {
+                    warn!("⚠️ [Plugins] skipped {}: {}", dir.display(), e);
+                    let name = dir
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    (
+                        Manifest {
+                            name,
+                            ..Default::default()
+                        },
+                        Some(e),
+                    )
+                }
+            };
+            PluginInfo {
+                dir: dir.to_string_lossy().to_string(),
+                manifest,
+                error,
+            }
+        })
+        .collect();
+
+    command::set_plugin_actions(
+        plugins
+            .iter()
+            .filter(|p| p.error.is_none())
+            .flat_map(|p| p.manifest.actions.iter().map(|a| a.name.clone()))
+            .collect(),
+    );
+    info!(
+        "🧩 [Plugins] loaded {} plugin(s) ({} failed)",
+        plugins.iter().filter(|p| p.error.is_none()).count(),
+        plugins.iter().filter(|p| p.error.is_some()).count()
+    );
+    if let Ok(mut list) = PLUGINS.lock() {
+        *list = plugins.clone();
+    }
+    plugins
+}
+
+pub fn list() -> Vec<PluginInfo> {
+    PLUGINS.lock().map(|l| l.clone()).unwrap_or_default()
+}
+
+fn find(action: &str) -> Option<(PluginInfo, PluginAction)> {
+    list()
+        .into_iter()
+        .filter(|p| p.error.is_none())
+        .find_map(|p| {
+            let a = p
+                .manifest
+                .actions
+                .iter()
+                .find(|a| a.name == action)?
+                .clone();
+            Some((p, a))
+        })
+}
+
+// マニフェストで confirm: true にしたもの (policy::requires_confirmation)
+pub fn requires_confirmation(action: &str) -> bool {
+    find(action).is_some_and(|(_, a)| a.confirm)
+}
+
+// worker プロンプトの {{plugins}}
+pub fn prompt_list() -> String {
+    let lines: Vec<String> = if available() {
+        list()
+            .iter()
+            .filter(|p| p.error.is_none())
+            .flat_map(|p| p.manifest.actions.iter())
+            .map(|a| {
+                let mut line = format!("           - {}", a.usage);
+                if !a.description.is_empty() {
+                    line.push_str(&format!("   ({})", a.description));
+                }
+                if a.confirm {
+                    line.push_str("   (user is asked to confirm)");
+                }
+                line
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if lines.is_empty() {
+        return "           (none)".to_string();
+    }
+    lines.join("\n")
+}
+
+fn clip(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() <= MAX_OUTPUT_CHARS {
+        return text.to_string();
+    }
+    text.chars().take(MAX_OUTPUT_CHARS).collect::<String>() + "…"
+}
+
+// max_bytes まで取っておき、残りは子プロセスが詰まらないよう読み捨てる
+fn read_limited(mut pipe: impl Read, max_bytes: usize) -> Vec<u8> {
+    let mut kept = Vec::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = match pipe.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        let room = max_bytes.saturating_sub(kept.len());
+        kept.extend_from_slice(&buf[..n.min(room)]);
+    }
+    kept
+}
+
+fn call(plugin: &PluginInfo, request: &str) -> Result<String, String> {
+    let dir = Path::new(&plugin.dir);
+    let program = &plugin.manifest.command[0];
+    let local = dir.join(program);
+    let mut cmd = Command::new(if local.is_file() {
+        local
+    } else {
+        PathBuf::from(program)
+    });
+    cmd.args(&plugin.manifest.command[1..])
+        .current_dir(dir)
+        .env_clear()
+        .envs(PLUGIN_ENV.iter().filter_map(|k| std::env::var_os(k).map(|v| (k, v))))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(0x08000000);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("failed to start '{}': {}", program, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(format!("{}\n", request).as_bytes());
+    }
+    // パイプが詰まらないよう別スレッドで読み続ける
+    let out_pipe = child.stdout.take().ok_or("no stdout")?;
+    let err_pipe = child.stderr.take().ok_or("no stderr")?;
+    let out_reader = thread::spawn(move || read_limited(out_pipe, MAX_READ_BYTES));
+    let err_reader = thread::spawn(move || read_limited(err_pipe, MAX_READ_BYTES));
+
+    let timeout = Duration::from_secs(plugin.manifest.timeout_secs.max(1));
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break status,
+            None if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {}s", timeout.as_secs()));
+            }
+            None => thread::sleep(Duration::from_millis(50)),
+        }
+    };
+    let stdout = String::from_utf8_lossy(&out_reader.join().unwrap_or_default()).to_string();
+    let stderr = String::from_utf8_lossy(&err_reader.join().unwrap_or_default()).to_string();
+
+    match serde_json::from_str::<Response>(stdout.trim()) {
+        Ok(r) if r.ok => Ok(clip(&r.output)),
+        Ok(r) => Err(clip(&r.error)),
+        Err(_) if status.success() => Ok(clip(&stdout)),
+        Err(_) => Err(format!(
+            "exited with {}: {}",
+            status.code().unwrap_or(-1),
+            clip(if stderr.trim().is_empty() {
+                &stdout
+            } else {
+                &stderr
+            })
+        )),
+    }
+}
+
+// Action::Plugin の実行（確認が要るかどうかは policy が先に見る）。待つのでスレッドから呼ぶ
+pub fn run(action: &str, argument: &str, session_id: &str) -> String {
+    if !settings::current().plugins_enabled {
+        return format!("[Plugin] {} is unavailable: plugins are disabled.", action);
+    }
+    if let Err(e) = local_only::check("plugin") {
+        return format!("[Plugin] {}", e);
+    }
+    let Some((plugin, _)) = find(action) else {
+        return format!("[Plugin] No plugin provides {}.", action);
+    };
+    let request = serde_json::to_string(&Request {
+        action,
+        argument,
+        session_id,
+    })
+    .unwrap_or_default();
+    sharing::record(
+        &format!("plugin:{}", plugin.manifest.name),
+        action,
+        "",
+        &request,
+        (0, 0),
+    );
+    info!(
+        input = %argument,
+        "🧩 [Plugins] {} -> {}",
+        action, plugin.manifest.name
+    );
+    match call(&plugin, &request) {
+        Ok(output) => format!("[Plugin {}] {}", plugin.manifest.name, output),
+        Err(e) => {
+            warn!("⚠️ [Plugins] {} failed: {}", action, e);
+            format!("[Plugin {}] Error: {}", plugin.manifest.name, e)
+        }
+    }
+}
@@ -0,0 +1,334 @@
+// src-tauri/src/policy.rs
+//
+// アクション実行ポリシー（確認が必要な操作の保留キュー）
+// - 確認対象: settings.confirm_actions（env AXIS_CONFIRM_ACTIONS でも可。既定 "KILL"）
+// - 確認対象のアクションは実行せずに保留し、axis-confirm-request イベントで UI に通知
+//   保留するのはパーサの Action そのもの (axis_core::command)。承認されたらそれを実行し、UI には command として渡す
+// - UI は confirm_action(id, approve) で承認 / 却下する
+//   ウィンドウを見ていない時は OS 通知でも聞く（Windows はトーストの Approve / Deny。notify.rs）
+// - Home Assistant の操作対象の許可リストもここ（ha_entity_allowed）
+// - RUN: は許可リスト (run_allow_commands) に当たらなければ確認に回す
+// - 外の文面 (untrusted.rs) が文脈に入ったセッションの操作と外への送信は、確認に回すか止める (hold_after_untrusted)
+//   承認されたものは execute_confirmed で実行（SAVE / SEARCH などもここで）
+// - プラグインのアクションはマニフェストで confirm: true にしたものも確認に回す (plugins.rs)
+
+use crate::settings;
+use axis_core::command::{Action, Command};
+use axis_core::policy::Hold;
+use crate::{
+    audit, connectors, filegen, imagegen, macros, media, notify, plugins, scheduler, search, shell,
+    templates, undo, web,
+};
+use chrono::Local;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
+use uuid::Uuid;
+
+// 放置された保留はこの時間で破棄
+const PENDING_TTL_MS: i64 = 10 * 60 * 1000;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PendingAction {
+    pub id: String,
+    pub action: String, // "KILL" など (Action::name)
+    pub argument: String, // 表示用 (Action::argument)
+    pub command: Action,  // 承認されたらこれを実行
+    pub session_id: String,
+    pub created_at: i64,
+}
+
+#[derive(Default)]
+pub struct PendingActions(pub Mutex<Vec<PendingAction>>);
+
+// settings に関係なく毎回確認するもの（LEARN_ALIAS は後の EXEC をずっと変えるので）
+const ALWAYS_CONFIRM: &[&str] = &["COMMIT", "LEARN_ALIAS"];
+
+pub fn requires_confirmation(action: &str) -> bool {
+    if ALWAYS_CONFIRM.contains(&action.to_uppercase().as_str()) || plugins::requires_confirmation(action) {
+        return true;
+    }
+    settings::current()
+        .confirm_actions
+        .iter()
+        .map(|a| a.trim().to_uppercase())
+        .any(|a| a == action.to_uppercase() || a == "*")
+}
+
+// Home Assistant で操作してよいエンティティ (settings.ha_allowed_entities)
+// "light.living" のような完全一致か "light.*" のドメイン単位。空ならどれも不可
+pub fn ha_entity_allowed(entity: &str) -> bool {
+    let entity = entity.trim().to_lowercase();
+    settings::current()
+        .ha_allowed_entities
+        .iter()
+        .map(|p| p.trim().to_lowercase())
+        .any(|p| match p.strip_suffix(".*") {
+            Some(domain) => entity.split_once('.').is_some_and(|(d, _)| d == domain),
+            None => p == entity || p == "*",
+        })
+}
+
+// RUN: をそのまま実行してよいか
+// - コマンドは settings.run_allow_commands と引数まで一致するものだけ（"*" は - / で始まらない引数 1 つ）
+//   後ろに足してよいのは settings.run_allow_flags のフラグだけ（--config や --output=... は確認に回す）
+// - "@ <folder>" は project_folders の下で、SAVE の書き込み先（デスクトップ）の外にあること
+// && / | / > などで繋いだものは許可リストに当たっても確認に回す
+pub fn run_allowed(command: &str, cwd: Option<&str>) -> bool {
+    let command = command.trim().to_lowercase();
+    if command.contains(['&', '|', '>', '<', ';', '^', '%', '`', '"', '\'', '\n']) {
+        return false;
+    }
+    if let Some(dir) = cwd.map(str::trim).filter(|d| !d.is_empty()) {
+        if !shell::run_cwd_trusted(&shell::resolve_run_cwd(dir)) {
+            return false;
+        }
+    }
+    let cfg = settings::current();
+    let flags: Vec<String> = cfg
+        .run_allow_flags
+        .iter()
+        .map(|f| f.trim().to_lowercase())
+        .collect();
+    let argv: Vec<&str> = command.split_whitespace().collect();
+    cfg.run_allow_commands
+        .iter()
+        .map(|a| a.trim().to_lowercase())
+        .filter(|a| !a.is_empty())
+        .any(|allowed| {
+            let pattern: Vec<&str> = allowed.split_whitespace().collect();
+            argv.len() >= pattern.len()
+                && pattern.iter().zip(&argv).all(|(p, a)| {
+                    if *p == "*" {
+                        !a.starts_with(['-', '/']) && !a.contains('=')
+                    } else {
+                        p == a
+                    }
+                })
+                && argv[pattern.len()..]
+                    .iter()
+                    .all(|a| flags.iter().any(|f| f == a))
+        })
+}
+
+// 検索結果やメールに書かれた指示が履歴 / メモリ越しにモデルを動かしていても、ユーザーが見るまでは実行しない
+// どれを保留 / 停止するかは axis_core::policy::after_untrusted。止めたものは新しいセッションで頼み直してもらう
+pub fn hold_after_untrusted(
+    app: &AppHandle,
+    cmd: &Command,
+    hold: Hold,
+    session_id: &str,
+) -> String {
+    let action = cmd.action.name();
+    if hold == Hold::Confirm {
+        let pending = enqueue(app, cmd.action.clone(), session_id);
+        return format!(
+            "[Policy] {} {} was proposed in a session that has read untrusted web / document content, so it requires user confirmation (pending id={}). Ask the user to approve it in the confirmation dialog.\n",
+            pending.action, pending.argument, pending.id
+        );
+    }
+    info!("🛡️ [Policy] {} held back in a session with untrusted content", action);
+    format!(
+        "[Policy] {} was not run because this session has read untrusted web / document content. Ask the user to request it again in a new session.\n",
+        action
+    )
+}
+
+fn prune(list: &mut Vec<PendingAction>) {
+    let now = Local::now().timestamp_millis();
+    list.retain(|p| now - p.created_at < PENDING_TTL_MS);
+}
+
+// 保留キューに積んで UI に確認を依頼
+pub fn enqueue(app: &AppHandle, command: Action, session_id: &str) -> PendingAction {
+    let pending = PendingAction {
+        id: Uuid::new_v4().to_string(),
+        action: command.name().to_string(),
+        argument: command.argument().trim().to_string(),
+        command,
+        session_id: session_id.to_string(),
+        created_at: Local::now().timestamp_millis(),
+    };
+
+    if let Some(state) = app.try_state::<PendingActions>() {
+        if let Ok(mut list) = state.0.lock() {
+            prune(&mut list);
+            list.push(pending.clone());
+        }
+    }
+    info!(
+        input = %pending.argument,
+        "🛡️ [Policy] {} awaiting confirmation (id={})",
+        pending.action,
+        pending.id
+    );
+    let _ = app.emit("axis-confirm-request", &pending);
+    notify::confirm_request(app, &pending);
+    pending
+}
+
+pub fn list_pending(app: &AppHandle) -> Vec<PendingAction> {
+    app.try_state::<PendingActions>()
+        .and_then(|state| {
+            state.0.lock().ok().map(|mut list| {
+                prune(&mut list);
+                list.clone()
+            })
+        })
+        .unwrap_or_default()
+}
+
+fn take_pending(app: &AppHandle, id: &str) -> Option<PendingAction> {
+    let state = app.try_state::<PendingActions>()?;
+    let mut list = state.0.lock().ok()?;
+    prune(&mut list);
+    let pos = list.iter().position(|p| p.id == id)?;
+    Some(list.remove(pos))
+}
+
+// 承認済みアクションの実際の実行
+fn execute(action: &Action, session_id: &str) -> String {
+    match action {
+        Action::Kill { target } => shell::kill_process(target),
+        Action::Focus { window } => shell::focus_window(window),
+        Action::Minimize { window } => shell::minimize_window(window),
+        Action::OpenFile { path } => shell::open_file(path),
+        Action::Reveal { path } => shell::reveal_in_explorer(path),
+        Action::OpenUrl { url } => shell::open_url(url),
+        Action::Commit { repo, message } => crate::connectors::git::commit(repo, message),
+        Action::Run { command, cwd } => shell::run_shell(command, cwd.as_deref()),
+        Action::Exec { app } => shell::execute_command(app),
+        Action::Type { text, target } => shell::type_text(text, target.as_deref()),
+        Action::Click { target } => shell::click(target),
+        Action::Press { key } => shell::press_key(key),
+        Action::Plugin { name, argument } => plugins::run(name, argument, session_id),
+        other => format!("Error: action '{}' cannot be confirmed.", other.name()),
+    }
+}
+
+// 承認されたものの実行。汚れたセッションで保留したもの (hold_after_untrusted) もここで
+async fn execute_confirmed(app: &AppHandle, action: Action, session_id: &str) -> String {
+    let saved = |result: Result<filegen::Saved, String>| match result {
+        Ok(saved) => saved.report(),
+        Err(e) => format!("Error: {}", e),
+    };
+    match &action {
+        Action::Search { query } => match search::search(query).await {
+            Ok((provider, results)) => {
+                let mut out = format!("[Search Results: {}]\n", provider);
+                for r in results {
+                    out.push_str(&format!("- {} ({})\n", r.title, r.link));
+                }
+                out
+            }
+            Err(e) => format!("Error: {}", e),
+        },
+        Action::Fetch { url } => {
+            let cfg = settings::current();
+            match web::fetch_url(
+                url,
+                &cfg.fetch_allow_domains,
+                &cfg.fetch_deny_domains,
+                cfg.fetch_max_chars,
+            )
+            .await
+            {
+                Ok(page) => format!("[Fetched Page] {} ({})\n{}", page.title, page.url, page.text),
+                Err(e) => format!("Error: {}", e),
+            }
+        }
+        Action::Weather { place } => match connectors::weather::forecast(place).await {
+            Ok(report) => connectors::weather::summarize(&report),
+            Err(e) => format!("Error: {}", e),
+        },
+        Action::News { topic } => match connectors::news::headlines(topic).await {
+            Ok(items) => connectors::news::summarize(&items),
+            Err(e) => format!("Error: {}", e),
+        },
+        Action::ImageGen { prompt, aspect } => {
+            saved(imagegen::generate(app, session_id, prompt, aspect.as_deref()).await)
+        }
+        Action::Save {
+            path,
+            mode,
+            content,
+        } => saved(filegen::save(app, session_id, path, *mode, content.trim(), &[])),
+        Action::Template { name, fields } => {
+            saved(templates::generate(app, session_id, name, fields))
+        }
+        Action::Schedule { when, message } => {
+            match scheduler::create_from_action(app, when, message, session_id) {
+                Ok(task) => format!("Success: Scheduled '{}'.", task.title),
+                Err(e) => format!("Error: {}", e),
+            }
+        }
+        Action::LearnAlias { name, target } => match settings::learn_alias(app, name, target) {
+            Ok((name, target)) => format!("Success: EXEC: {} now opens '{}'.", name, target),
+            Err(e) => format!("Error: {}", e),
+        },
+        Action::UndoLast => undo::undo_last(app, Some(session_id)).unwrap_or_else(|e| format!("Error: {}", e)),
+        Action::Macro { name, params } => macros::run_command(app, name, params, session_id).await,
+        Action::HomeAssistant {
+            service,
+            entity_id,
+            data,
+        } => connectors::home_assistant::run_command(service, entity_id, data).await,
+        Action::Media { action, volume } => media::control(action, *volume).await,
+        _ => {
+            // サブプロセス / キー操作を待つのでスレッドで
+            let session_id = session_id.to_string();
+            tauri::async_runtime::spawn_blocking(move || execute(&action, &session_id))
+                .await
+                .unwrap_or_else(|e| format!("Error: {}", e))
+        }
+    }
+}
+
+// UI からの承認 / 却下
+pub async fn resolve(app: &AppHandle, id: &str, approve: bool) -> Result<String, String> {
+    let pending = take_pending(app, id)
+        .ok_or_else(|| format!("pending action '{}' not found or expired", id))?;
+
+    let result = if approve {
+        info!(input = %pending.argument, "🛡️ [Policy] approved {}", pending.action);
+        let result = execute_confirmed(app, pending.command.clone(), &pending.session_id).await;
+        audit::record(
+            app,
+            "user",
+            &format!("{}: {}", pending.action, pending.argument),
+            &result,
+            &pending.session_id,
+        );
+        result
+    } else {
+        info!(input = %pending.argument, "🛡️ [Policy] denied {}", pending.action);
+        format!("Denied: {} {}", pending.action, pending.argument)
+    };
+
+    let _ = app.emit(
+        "axis-confirm-result",
+        serde_json::json!({
+            "id": pending.id,
+            "action": pending.action,
+            "argument": pending.argument,
+            "command": pending.command,
+            "session_id": pending.session_id,
+            "approved": approve,
+            "result": result,
+        }),
+    );
+    Ok(result)
+}
+
+// ask_axis から: 確認不要なら即実行、必要なら保留してメッセージを返す
+pub fn run_or_queue(app: &AppHandle, action: Action, session_id: &str) -> String {
+    if requires_confirmation(action.name()) {
+        let pending = enqueue(app, action, session_id);
+        return format!(
+            "[Policy] {} {} requires user confirmation (pending id={}). Ask the user to approve it in the confirmation dialog.",
+            pending.action, pending.argument, pending.id
+        );
+    }
+    execute(&action, session_id)
+}
@@ -0,0 +1,365 @@
+// src-tauri/src/prompts.rs
+//
+// システムプロンプトの管理
+// - 既定値: src-tauri/prompts/*.md（ビルド時に埋め込み）
+// - 上書き: app_data_dir/prompts/<name>.md があればそちらを使う（毎回読むので再起動不要）
+// - テンプレート変数: {{name}} を render の vars で置き換える
+// - list_prompts / update_prompt コマンドから編集（空文字で既定値に戻す）
+//
+// ペルソナ（口調の上書き）
+// - 既定: src-tauri/prompts/personas/*.md。app_data_dir/prompts/personas/<name>.md で上書き・追加
+// - set_persona でセッション毎に選び、worker / report プロンプトの末尾に [PERSONA] として足す
+
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+struct PromptDef {
+    name: &'static str,
+    description: &'static str,
+    variables: &'static [&'static str],
+    default: &'static str,
+}
+
+const PROMPTS: &[PromptDef] = &[
+    PromptDef {
+        name: "commander",
+        description: "Commander (routing) prompt: picks the model and task_type as JSON",
+        variables: &["profiles", "history", "providers"],
+        default: include_str!("../prompts/commander.md"),
+    },
+    PromptDef {
+        name: "worker",
+        description: "Worker system prompt: intent classification and the action command DSL",
+        variables: &["macros", "templates", "plugins", "apps"],
+        default: include_str!("../prompts/worker.md"),
+    },
+    PromptDef {
+        name: "report",
+        description: "System prompt for the final report after actions ran",
+        variables: &[],
+        default: include_str!("../prompts/report.md"),
+    },
+    PromptDef {
+        name: "report_witty",
+        description: "Report prompt used when Grok handled the request",
+        variables: &[],
+        default: include_str!("../prompts/report_witty.md"),
+    },
+    PromptDef {
+        name: "vision",
+        description: "Instruction sent with a screenshot for LOOK",
+        variables: &[],
+        default: include_str!("../prompts/vision.md"),
+    },
+    PromptDef {
+        name: "vision_clip",
+        description: "Instruction sent with the keyframes of a screen recording for RECORD_SCREEN (vision.rs)",
+        variables: &["count", "seconds", "times"],
+        default: include_str!("../prompts/vision_clip.md"),
+    },
+    PromptDef {
+        name: "document",
+        description: "System prompt for ask_document (documents.rs)",
+        variables: &[],
+        default: include_str!("../prompts/document.md"),
+    },
+    PromptDef {
+        name: "audio_summary",
+        description: "Summary of a transcribed recording for ingest_audio (audio.rs)",
+        variables: &[],
+        default: include_str!("../prompts/audio_summary.md"),
+    },
+    PromptDef {
+        name: "briefing",
+        description: "Morning briefing request built from unread feed items (feeds.rs)",
+        variables: &["date", "items"],
+        default: include_str!("../prompts/briefing.md"),
+    },
+    PromptDef {
+        name: "planner",
+        description: "Step list for PLAN: (plans.rs), returned as JSON",
+        variables: &["goal", "max_steps"],
+        default: include_str!("../prompts/planner.md"),
+    },
+    PromptDef {
+        name: "autotag",
+        description:
+            "L / M / S categories and tags for a saved memory (tagging.rs), returned as JSON",
+        variables: &["categories", "max_tags"],
+        default: include_str!("../prompts/autotag.md"),
+    },
+    PromptDef {
+        name: "graph",
+        description:
+            "Entities and relations pulled out of the user's message (graph.rs), returned as JSON",
+        variables: &["max_facts"],
+        default: include_str!("../prompts/graph.md"),
+    },
+    PromptDef {
+        name: "journal",
+        description: "Daily / weekly journal written from activity, conversations, plans and memories (journal.rs)",
+        variables: &["period", "date", "activity", "sessions", "plans", "memories"],
+        default: include_str!("../prompts/journal.md"),
+    },
+    PromptDef {
+        name: "resume",
+        description: "Re-entry brief for resume_context: recent sessions, open plans and files on a topic (resume.rs)",
+        variables: &["topic", "sessions", "plans", "files", "facts"],
+        default: include_str!("../prompts/resume.md"),
+    },
+    PromptDef {
+        name: "redact",
+        description: "Personal names found by the local model before a cloud call (redact.rs), returned as JSON",
+        variables: &["text"],
+        default: include_str!("../prompts/redact.md"),
+    },
+    PromptDef {
+        name: "web_digest",
+        description: "Digest of the top pages read for SEARCH, citing them as [1], [2] ... (web.rs)",
+        variables: &[],
+        default: include_str!("../prompts/web_digest.md"),
+    },
+];
+
+struct PersonaDef {
+    name: &'static str,
+    description: &'static str,
+    overlay: &'static str,
+}
+
+const PERSONAS: &[PersonaDef] = &[
+    PersonaDef {
+        name: "professional",
+        description: "Polite professional assistant (business Japanese)",
+        overlay: include_str!("../prompts/personas/professional.md"),
+    },
+    PersonaDef {
+        name: "terse_engineer",
+        description: "Terse engineer: minimum words, code first",
+        overlay: include_str!("../prompts/personas/terse_engineer.md"),
+    },
+    PersonaDef {
+        name: "casual_ja",
+        description: "Casual Japanese (タメ口)",
+        overlay: include_str!("../prompts/personas/casual_ja.md"),
+    },
+    PersonaDef {
+        name: "english_only",
+        description: "Always answer in English",
+        overlay: include_str!("../prompts/personas/english_only.md"),
+    },
+];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PromptInfo {
+    pub name: String,
+    pub description: String,
+    pub variables: Vec<String>,
+    pub customized: bool, // app_data_dir/prompts に上書きがある
+    pub path: Option<String>,
+    pub content: String,
+    pub default_content: String,
+}
+
+fn def(name: &str) -> Result<&'static PromptDef, String> {
+    PROMPTS.iter().find(|p| p.name == name).ok_or_else(|| {
+        let names: Vec<&str> = PROMPTS.iter().map(|p| p.name).collect();
+        format!("unknown prompt '{}': use {}", name, names.join(" / "))
+    })
+}
+
+fn prompts_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|d| d.join("prompts"))
+}
+
+fn override_path(app: &AppHandle, name: &str) -> Option<PathBuf> {
+    prompts_dir(app).map(|d| d.join(format!("{}.md", name)))
+}
+
+fn read_override(app: &AppHandle, name: &str) -> Option<String> {
+    let p = override_path(app, name)?;
+    fs::read_to_string(p).ok().filter(|s| !s.trim().is_empty())
+}
+
+// テンプレート本体（上書き → 既定値）
+pub fn template(app: &AppHandle, name: &str) -> String {
+    let Ok(d) = def(name) else {
+        return String::new();
+    };
+    read_override(app, name)
+        .unwrap_or_else(|| d.default.to_string())
+        .trim_end()
+        .to_string()
+}
+
+// {{var}} を置き換えたプロンプト
+pub fn render(app: &AppHandle, name: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = template(app, name);
+    for (k, v) in vars {
+        out = out.replace(&format!("{{{{{}}}}}", k), v);
+    }
+    out
+}
+
+pub fn list_prompts(app: &AppHandle) -> Vec<PromptInfo> {
+    PROMPTS
+        .iter()
+        .map(|d| {
+            let custom = read_override(app, d.name);
+            PromptInfo {
+                name: d.name.to_string(),
+                description: d.description.to_string(),
+                variables: d.variables.iter().map(|v| v.to_string()).collect(),
+                customized: custom.is_some(),
+                path: override_path(app, d.name).map(|p| p.to_string_lossy().to_string()),
+                content: custom
+                    .unwrap_or_else(|| d.default.to_string())
+                    .trim_end()
+                    .to_string(),
+                default_content: d.default.trim_end().to_string(),
+            }
+        })
+        .collect()
+}
+
+// 空文字で上書きを消して既定値に戻す
+pub fn update_prompt(app: &AppHandle, name: &str, content: &str) -> Result<PromptInfo, String> {
+    let d = def(name)?;
+    let path = override_path(app, name).ok_or("app data dir unavailable")?;
+
+    if content.trim().is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
+        info!("📝 [Prompts] {} reset to default", name);
+    } else {
+        // 必要な変数が消えていたら Axis が壊れるので弾く
+        let missing: Vec<&str> = d
+            .variables
+            .iter()
+            .filter(|v| !content.contains(&format!("{{{{{}}}}}", v)))
+            .copied()
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "prompt '{}' must contain {}",
+                name,
+                missing
+                    .iter()
+                    .map(|v| format!("{{{{{}}}}}", v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, content).map_err(|e| e.to_string())?;
+        info!("📝 [Prompts] {} updated", name);
+    }
+
+    list_prompts(app)
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("unknown prompt '{}'", name))
+}
+
+// ---------- ペルソナ ----------
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PersonaInfo {
+    pub name: String,
+    pub description: String,
+    pub builtin: bool,
+    pub customized: bool, // app_data_dir/prompts/personas にファイルがある
+    pub overlay: String,
+}
+
+fn personas_dir(app: &AppHandle) -> Option<PathBuf> {
+    prompts_dir(app).map(|d| d.join("personas"))
+}
+
+fn read_persona_file(app: &AppHandle, name: &str) -> Option<String> {
+    let p = personas_dir(app)?.join(format!("{}.md", name));
+    fs::read_to_string(p).ok().filter(|s| !s.trim().is_empty())
+}
+
+pub fn list_personas(app: &AppHandle) -> Vec<PersonaInfo> {
+    let mut out: Vec<PersonaInfo> = PERSONAS
+        .iter()
+        .map(|d| {
+            let custom = read_persona_file(app, d.name);
+            PersonaInfo {
+                name: d.name.to_string(),
+                description: d.description.to_string(),
+                builtin: true,
+                customized: custom.is_some(),
+                overlay: custom
+                    .unwrap_or_else(|| d.overlay.to_string())
+                    .trim_end()
+                    .to_string(),
+            }
+        })
+        .collect();
+
+    // ユーザーが足したペルソナ
+    let entries = personas_dir(app).and_then(|d| fs::read_dir(d).ok());
+    for entry in entries.into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if out.iter().any(|p| p.name == name) {
+            continue;
+        }
+        if let Some(overlay) = read_persona_file(app, name) {
+            out.push(PersonaInfo {
+                name: name.to_string(),
+                description: "Custom persona".to_string(),
+                builtin: false,
+                customized: true,
+                overlay: overlay.trim_end().to_string(),
+            });
+        }
+    }
+    out
+}
+
+// 名前を確認して正規化。"" / "default" は None（ペルソナなし）
+pub fn resolve_persona(app: &AppHandle, name: &str) -> Result<Option<String>, String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() || name == "default" || name == "none" {
+        return Ok(None);
+    }
+    let personas = list_personas(app);
+    if personas.iter().any(|p| p.name == name) {
+        return Ok(Some(name));
+    }
+    let names: Vec<&str> = personas.iter().map(|p| p.name.as_str()).collect();
+    Err(format!(
+        "unknown persona '{}': use {} or default",
+        name,
+        names.join(" / ")
+    ))
+}
+
+pub fn persona_overlay(app: &AppHandle, name: &str) -> Option<String> {
+    list_personas(app)
+        .into_iter()
+        .find(|p| p.name == name)
+        .map(|p| p.overlay)
+}
+
+// ベースのプロンプトにペルソナを重ねる（無ければそのまま）
+pub fn with_persona(base: String, overlay: Option<&str>) -> String {
+    match overlay {
+        Some(o) if !o.trim().is_empty() => format!("{}\n\n[PERSONA]\n{}", base, o.trim()),
+        _ => base,
+    }
+}
@@ -0,0 +1,171 @@
+// src-tauri/src/providers.rs
+//
+// 追加の OpenAI 互換プロバイダ (settings.providers)。コードを変えずに Azure OpenAI / OpenRouter などを足す
+// - settings.providers のキーがエイリアス。Commander の target / set_session_provider / ensemble_members /
+//   critic_reviewer / 各 *_writer などで gpt / gemini と同じように使える（組み込みの名前は上書きできない）
+// - kind ごとの URL と認証ヘッダー:
+//   azure       {base_url}/openai/deployments/{deployment}/chat/completions?api-version=...  "api-key: <key>"
+//   openrouter  {base_url}/chat/completions（既定 https://openrouter.ai/api/v1）            "Authorization: Bearer <key>"
+//   openai      {base_url}/chat/completions（任意の OpenAI 互換）                          "Authorization: Bearer <key>"
+// - キーはエイリアス毎に secrets.rs へ（set_api_key("provider:<alias>")。keyring の "api-key:provider:<alias>"）
+//   base_url は自由に書けるので、組み込みのキー (OpenAI / Gemini ...) をここから送らせない。headers は全部の依頼に付ける
+// - タイムアウト / レート制限 / 予算 / 到達性の確認はこのエイリアスで (ai.rs / budget.rs / system.rs)
+
+use crate::settings::{self, ProviderConfig};
+use reqwest::Url;
+
+// Commander が元から使う名前（settings.providers では使えない）
+const RESERVED: &[&str] = &[
+    "gpt", "gemini", "grok", "llama", "local", "ensemble", "auto",
+];
+
+const AZURE_API_VERSION: &str = "2024-10-21";
+const OPENROUTER_URL: &str = "https://openrouter.ai/api/v1";
+
+pub enum Auth {
+    Bearer,
+    ApiKey, // Azure の "api-key" ヘッダー
+}
+
+pub struct Endpoint {
+    pub url: String,
+    pub model: String,
+    pub key_name: String, // secrets::api_key に渡す名前 ("provider:<alias>")
+    pub auth: Auth,
+    pub headers: Vec<(String, String)>,
+}
+
+pub fn get(alias: &str) -> Option<ProviderConfig> {
+    if RESERVED.contains(&alias) {
+        return None;
+    }
+    settings::current().providers.get(alias).cloned()
+}
+
+pub fn is_custom(alias: &str) -> bool {
+    get(alias).is_some()
+}
+
+pub fn names() -> Vec<String> {
+    settings::current()
+        .providers
+        .keys()
+        .filter(|k| !RESERVED.contains(&k.as_str()))
+        .cloned()
+        .collect()
+}
+
+pub fn endpoint(alias: &str) -> Result<Endpoint, String> {
+    let p = get(alias).ok_or_else(|| format!("unknown provider alias '{}'", alias))?;
+    let base = p.base_url.trim().trim_end_matches('/');
+    let model = p.model_name();
+    let (url, auth) = match p.kind.trim() {
+        "azure" => {
+            let deployment = p.deployment.trim();
+            if base.is_empty() || deployment.is_empty() {
+                return Err(format!(
+                    "provider '{}' (azure) needs base_url and deployment",
+                    alias
+                ));
+            }
+            let url = format!(
+                "{}/openai/deployments/{}/chat/completions?api-version={}",
+                base,
+                deployment,
+                api_version(&p)
+            );
+            (url, Auth::ApiKey)
+        }
+        "openrouter" => {
+            let base = if base.is_empty() {
+                OPENROUTER_URL
+            } else {
+                base
+            };
+            (chat_url(base), Auth::Bearer)
+        }
+        "openai" => {
+            if base.is_empty() {
+                return Err(format!("provider '{}' (openai) needs base_url", alias));
+            }
+            (chat_url(base), Auth::Bearer)
+        }
+        other => {
+            return Err(format!(
+                "provider '{}' has unknown kind '{}': use azure / openrouter / openai",
+                alias, other
+            ))
+        }
+    };
+    if model.is_empty() {
+        return Err(format!("provider '{}' needs a model", alias));
+    }
+    // 以前の「secrets の名前」指定。組み込みのキーを別の宛先に送ることになるので受け付けない
+    if !p.api_key.trim().is_empty() {
+        return Err(format!(
+            "provider '{}': api_key is no longer used; store its own key with set_api_key(\"provider:{}\")",
+            alias, alias
+        ));
+    }
+    Ok(Endpoint {
+        url,
+        model,
+        key_name: format!("provider:{}", alias),
+        auth,
+        headers: p.headers.into_iter().collect(),
+    })
+}
+
+// ".../v1" と ".../v1/chat/completions" のどちらを書かれても
+fn chat_url(base: &str) -> String {
+    if base.ends_with("/chat/completions") {
+        base.to_string()
+    } else {
+        format!("{}/chat/completions", base)
+    }
+}
+
+fn api_version(p: &ProviderConfig) -> &str {
+    match p.api_version.trim() {
+        "" => AZURE_API_VERSION,
+        v => v,
+    }
+}
+
+// 到達性の確認に使うホスト (system.rs)
+pub fn host(alias: &str) -> Option<String> {
+    let url = Url::parse(&endpoint(alias).ok()?.url).ok()?;
+    url.host_str().map(|h| h.to_string())
+}
+
+// test_api_key("provider:<alias>") 用。キーが通るか確かめるモデル一覧の URL
+pub fn models_url(alias: &str) -> Result<String, String> {
+    let endpoint = endpoint(alias)?;
+    let p = get(alias).ok_or_else(|| format!("unknown provider alias '{}'", alias))?;
+    let base = p.base_url.trim().trim_end_matches('/');
+    Ok(match p.kind.trim() {
+        "azure" => format!("{}/openai/models?api-version={}", base, api_version(&p)),
+        _ => format!("{}/models", endpoint.url.trim_end_matches("/chat/completions")),
+    })
+}
+
+// Commander の {{providers}}
+pub fn prompt_list() -> String {
+    let lines: Vec<String> = names()
+        .iter()
+        .filter_map(|name| {
+            let p = get(name)?;
+            Some(format!(
+                "- \"{}\" = {} ({})",
+                name,
+                p.model_name(),
+                p.kind.trim()
+            ))
+        })
+        .collect();
+    if lines.is_empty() {
+        "- (none)".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
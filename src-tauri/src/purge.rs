@@ -0,0 +1,382 @@
+// src-tauri/src/purge.rs
+//
+// 会話データの削除（GDPR 的な「全部消して」「このプロバイダに送った分を消して」）
+// - purge_all_data(before_date?): 日付の前（無ければ全部）の履歴 / メモリ / memory.db の行 / trace / 送信記録 / 添付
+//   memory.db は目標 / 信念 / 資料 / フィードの記事 / スケジュール / undo の記録 / 監査記録も。app_data_dir/logs も
+// - purge_provider_data(provider): そのプロバイダが答えたログ（ResponseMeta.target）と
+//   そのログから作ったメモリ / trace / 実績 / 知識グラフの事実 / memory.db の往復 / そのプロバイダへの送信記録
+// - dry_run なら消さずに件数だけ返す（SQLite は同じ DELETE を流してから rollback するので数は正確）
+// - 順番: 全部先に決める → memory.db を 1 トランザクションで消す → 消すファイルを staging に移す
+//   → history.json を書き直す → commit。途中で失敗したらファイルと history.json を元に戻す
+//   commit できたら staging を消す（消せなかった分は failed に数える）
+// - 監査記録は消した後に残りの鎖を繋ぎ直す（audit.rs）。undo の記録が消えた退避ファイルも消す
+// - 残すもの: 設定 / マクロ / フィードの登録 / 利用額
+
+use crate::activity;
+use crate::audit;
+use crate::budget;
+use crate::db::AxisDatabase;
+use crate::logging;
+use crate::memory::{self, MemoryEntry, MemoryMeta};
+use crate::objects;
+use crate::retention;
+use crate::storage::{self, InteractionLog};
+use crate::undo;
+use chrono::Local;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+// memory.db から消す行（db.rs の begin_purge に渡す）
+#[derive(Debug, Clone, Default)]
+pub struct PurgeRows {
+    pub before: Option<i64>,  // これより前の行を時刻で（i64::MAX = 全部）
+    pub log_ids: Vec<String>, // traces / model_outcomes / kg_relations.source_log
+    pub answers: Vec<(String, String)>, // (session_id, 回答) messages のその往復
+    pub sessions: Vec<String>, // 履歴が無くなったセッション
+    pub memory_ids: Vec<String>,
+    pub provider: Option<String>, // data_sharing のそのプロバイダに送った分（裏の呼び出しも）
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    pub scope: String, // "all" / "before YYYY-MM-DD" / "provider <name>"
+    pub logs: usize,
+    pub sessions: usize, // 履歴が丸ごと無くなったセッション
+    pub memories: usize,
+    pub archived: usize, // retention.rs のアーカイブ
+    pub attachments: usize, // undo の退避分も
+    pub log_files: usize,   // app_data_dir/logs
+    pub freed_bytes: u64,
+    pub rows: BTreeMap<String, usize>, // memory.db のテーブル毎
+    pub failed: usize,
+}
+
+enum Scope {
+    Before(i64),
+    Provider(String),
+}
+
+impl Scope {
+    fn log_matches(&self, log: &InteractionLog) -> bool {
+        match self {
+            Scope::Before(before) => log.timestamp < *before,
+            Scope::Provider(p) => answered_by(log) == *p,
+        }
+    }
+
+    fn memory_matches(&self, meta: &MemoryMeta, purged_logs: &HashSet<String>) -> bool {
+        let from_purged_log = meta
+            .references
+            .iter()
+            .filter_map(|r| r.strip_prefix("log:"))
+            .any(|id| purged_logs.contains(id));
+        match self {
+            Scope::Before(before) => meta.created_at_ms < *before || from_purged_log,
+            Scope::Provider(p) => {
+                from_purged_log
+                    || meta
+                        .provider
+                        .as_deref()
+                        .is_some_and(|m| m.eq_ignore_ascii_case(p))
+            }
+        }
+    }
+}
+
+// 消すファイルはまず app_data_dir/purge-staging/<時刻> に移しておき、
+// commit できたら staging ごと消す / できなければ元の場所に戻す
+struct Staging {
+    dir: PathBuf,
+    moved: Vec<(PathBuf, PathBuf)>, // (元, staging)
+}
+
+impl Staging {
+    fn new(app: &AppHandle) -> Result<Self, String> {
+        let dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("purge-staging")
+            .join(Local::now().timestamp_millis().to_string());
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        Ok(Self {
+            dir,
+            moved: Vec::new(),
+        })
+    }
+
+    fn stage(&mut self, path: &Path) -> Result<(), String> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let to = self.dir.join(self.moved.len().to_string());
+        fs::rename(path, &to).map_err(|e| format!("{}: {}", path.display(), e))?;
+        self.moved.push((path.to_path_buf(), to));
+        Ok(())
+    }
+
+    fn restore(self) {
+        for (from, to) in self.moved.iter().rev() {
+            if let Err(e) = fs::rename(to, from) {
+                warn!("⚠️ [Purge] failed to restore {}: {}", from.display(), e);
+            }
+        }
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+
+    // 消せなかったファイルの数
+    fn finish(self) -> usize {
+        match fs::remove_dir_all(&self.dir) {
+            Ok(()) => 0,
+            Err(e) => {
+                warn!("⚠️ [Purge] failed to delete {}: {}", self.dir.display(), e);
+                self.moved.len()
+            }
+        }
+    }
+}
+
+// 実際に答えたエイリアス。meta の無い古いログ / 取り込んだログは "Llama -> gpt" の右側
+fn answered_by(log: &InteractionLog) -> String {
+    log.meta
+        .as_ref()
+        .map(|m| m.target.clone())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| {
+            log.provider_used
+                .rsplit("->")
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .trim()
+        .to_lowercase()
+}
+
+fn attachment_ids(entry: &MemoryEntry) -> impl Iterator<Item = &String> {
+    entry
+        .input
+        .attachments
+        .iter()
+        .chain(entry.output.attachments.iter())
+        .map(|a| &a.object_id)
+}
+
+pub fn purge_all(
+    app: &AppHandle,
+    before_date: Option<&str>,
+    dry_run: bool,
+) -> Result<PurgeReport, String> {
+    match before_date.map(str::trim).filter(|d| !d.is_empty()) {
+        Some(day) => {
+            let date = activity::parse_day(Some(day))?;
+            let (before, _) = activity::day_bounds(date)?;
+            let scope = format!("before {}", date.format("%Y-%m-%d"));
+            run(app, Scope::Before(before), scope, dry_run)
+        }
+        None => run(app, Scope::Before(i64::MAX), "all".to_string(), dry_run),
+    }
+}
+
+pub fn purge_provider(
+    app: &AppHandle,
+    provider: &str,
+    dry_run: bool,
+) -> Result<PurgeReport, String> {
+    let provider = provider.trim().to_lowercase();
+    if provider.is_empty() {
+        return Err("purge_provider_data needs a provider".to_string());
+    }
+    let scope = format!("provider {}", provider);
+    run(app, Scope::Provider(provider), scope, dry_run)
+}
+
+fn run(app: &AppHandle, scope: Scope, label: String, dry_run: bool) -> Result<PurgeReport, String> {
+    let mut report = PurgeReport {
+        dry_run,
+        scope: label,
+        ..Default::default()
+    };
+
+    // 1. 履歴: 当てはまるログと、その答え直しの枝
+    let all_logs = storage::get_all_logs(app)?;
+    let mut purged: HashSet<String> = all_logs
+        .iter()
+        .filter(|l| scope.log_matches(l))
+        .map(|l| l.id.clone())
+        .collect();
+    purged.extend(
+        all_logs
+            .iter()
+            .filter(|l| l.branch_of.as_ref().is_some_and(|b| purged.contains(b)))
+            .map(|l| l.id.clone())
+            .collect::<Vec<_>>(),
+    );
+    let (gone, kept): (Vec<&InteractionLog>, Vec<&InteractionLog>) =
+        all_logs.iter().partition(|l| purged.contains(&l.id));
+    let kept_sessions: HashSet<&str> = kept.iter().map(|l| l.session_id.as_str()).collect();
+    let emptied: Vec<String> = gone
+        .iter()
+        .map(|l| l.session_id.clone())
+        .filter(|s| !kept_sessions.contains(s.as_str()))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    report.logs = gone.len();
+    report.sessions = emptied.len();
+
+    // 2. メモリと、消すメモリだけが使っていた添付
+    let (gone_memories, kept_memories): (Vec<_>, Vec<_>) = memory::export_all(app)?
+        .into_iter()
+        .partition(|(_, meta)| scope.memory_matches(meta, &purged));
+    let kept_attachments: HashSet<String> = kept_memories
+        .iter()
+        .flat_map(|(entry, _)| attachment_ids(entry).cloned())
+        .collect();
+    let memory_ids: Vec<String> = gone_memories.iter().map(|(_, m)| m.id.clone()).collect();
+    report.memories = memory_ids.len();
+
+    let archived: Vec<_> = retention::archived(app)?
+        .into_iter()
+        .filter(|(_, meta)| scope.memory_matches(meta, &purged))
+        .collect();
+    report.archived = archived.len();
+
+    // 3. memory.db（commit はまだ）
+    let rows = PurgeRows {
+        before: match scope {
+            Scope::Before(before) => Some(before),
+            Scope::Provider(_) => None,
+        },
+        log_ids: purged.iter().cloned().collect(),
+        answers: match scope {
+            Scope::Before(_) => Vec::new(),
+            Scope::Provider(_) => gone
+                .iter()
+                .map(|l| (l.session_id.clone(), l.ai_response.clone()))
+                .collect(),
+        },
+        sessions: emptied,
+        memory_ids: memory_ids.clone(),
+        provider: match &scope {
+            Scope::Before(_) => None,
+            Scope::Provider(p) => Some(p.clone()),
+        },
+    };
+    let db = AxisDatabase::open(app)?;
+    let _audit = audit::lock();
+    let audit_intact = audit::chain_intact(&db)?;
+    let backups_before = undo::backup_ids_in(&db)?;
+    let (tx, counts) = db.begin_purge(&rows).map_err(|e| e.to_string())?;
+    if counts.iter().any(|(t, _)| t == "action_audit") {
+        if audit_intact {
+            audit::rechain(&db)?;
+        } else {
+            warn!("⚠️ [Purge] audit chain was already broken, leaving it unchained");
+        }
+    }
+    // undo の記録が消えて使われなくなった退避分も添付と一緒に消す
+    let still_used: HashSet<String> = kept_attachments
+        .into_iter()
+        .chain(undo::backup_ids_in(&db)?)
+        .collect();
+    let attachments: Vec<String> = gone_memories
+        .iter()
+        .flat_map(|(entry, _)| attachment_ids(entry).cloned())
+        .chain(backups_before)
+        .filter(|id| !still_used.contains(id))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    report.attachments = attachments.len();
+    report.rows = counts.into_iter().collect();
+
+    // ログファイル（プロバイダ指定では日ごとのファイルを分けられないので対象外）
+    let (log_files, clear_current_log) = match scope {
+        Scope::Before(before) => (
+            logging::files_before(before),
+            before > Local::now().timestamp_millis(),
+        ),
+        Scope::Provider(_) => (Vec::new(), false),
+    };
+    report.log_files = log_files.len() + usize::from(clear_current_log);
+    if dry_run {
+        // tx は commit せずに捨てる（rollback）
+        return Ok(report);
+    }
+
+    // 4. 消すファイルを staging に移す → history.json → commit
+    //    どこかで失敗したらファイルと history.json を戻す（tx は捨てれば rollback）
+    let mut staging = Staging::new(app)?;
+    let mut stage_all = || -> Result<(), String> {
+        for id in &memory_ids {
+            for path in memory::entry_files(app, id)? {
+                staging.stage(&path)?;
+            }
+        }
+        for (path, _) in &archived {
+            staging.stage(path)?;
+        }
+        for id in &attachments {
+            report.freed_bytes += objects::load_meta(app, id).map(|m| m.size).unwrap_or(0);
+            for path in objects::files(app, id)? {
+                staging.stage(&path)?;
+            }
+        }
+        for path in &log_files {
+            staging.stage(path)?;
+        }
+        Ok(())
+    };
+    if let Err(e) = stage_all() {
+        staging.restore();
+        return Err(format!("purge failed, nothing was deleted: {}", e));
+    }
+    if report.logs > 0 {
+        let kept: Vec<InteractionLog> = kept.into_iter().cloned().collect();
+        if let Err(e) = storage::replace_all_logs(app, &kept) {
+            staging.restore();
+            return Err(format!("purge failed, nothing was deleted: {}", e));
+        }
+    }
+    if let Err(e) = tx.commit() {
+        if report.logs > 0 {
+            if let Err(restore) = storage::replace_all_logs(app, &all_logs) {
+                warn!("⚠️ [Purge] failed to restore history.json: {}", restore);
+            }
+        }
+        staging.restore();
+        return Err(format!("purge failed, nothing was deleted: {}", e));
+    }
+
+    // 5. commit できたので staging を消す。メモリの検索索引もここで
+    report.failed += staging.finish();
+    for id in &memory_ids {
+        if let Err(e) = memory::delete_entry(app, id) {
+            warn!("⚠️ [Purge] failed to delete memory {}: {}", id, e);
+            report.failed += 1;
+        }
+    }
+    if clear_current_log {
+        logging::clear_current();
+    }
+    budget::forget_cached();
+
+    info!(
+        "🗑️ [Purge] {}: {} logs / {} memories / {} archived / {} attachments / {} log files / {} rows ({} failed)",
+        report.scope,
+        report.logs,
+        report.memories,
+        report.archived,
+        report.attachments,
+        report.log_files,
+        report.rows.values().sum::<usize>(),
+        report.failed
+    );
+    Ok(report)
+}
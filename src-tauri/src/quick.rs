@@ -0,0 +1,94 @@
+// src-tauri/src/quick.rs
+//
+// quick-ask ウィンドウの文脈（ホットキーを押した時に見ていたアプリ）
+// - ホットキー押下時、quick-ask が前面に出る前に前面ウィンドウのタイトル / プロセス名を控える
+// - settings.quick_ask_screenshot = true なら画面も撮っておき、聞かれた時に Vision で説明させる
+// - 前面がブラウザなら、拡張から届いた今のタブの URL も (connectors/browser.rs)
+// - quick_ask コマンドで「これ要約して」のような入力にその文脈を付けて ask_axis へ
+
+use crate::connectors::browser;
+use crate::settings;
+use crate::vision;
+use chrono::Local;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+pub const SESSION_ID: &str = "quick";
+
+// これより古い文脈は使わない（ホットキーを押さずにウィンドウを再利用した時など）
+const CONTEXT_TTL_MS: i64 = 10 * 60 * 1000;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct QuickContext {
+    pub title: String,
+    pub app: String,
+    pub captured_at: i64,
+    pub has_screenshot: bool,
+    #[serde(skip)]
+    pub screenshot: Option<String>, // base64 PNG
+}
+
+static CONTEXT: Mutex<Option<QuickContext>> = Mutex::new(None);
+
+// ホットキーのスレッドから、quick-ask を出す直前に呼ぶ
+pub fn capture(app: &AppHandle, title: String, app_name: String) {
+    let screenshot = if settings::current().quick_ask_screenshot {
+        match vision::take_screenshot() {
+            Ok(b64) => Some(b64),
+            Err(e) => {
+                warn!("⚠️ [Quick] screenshot failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let ctx = QuickContext {
+        title,
+        app: app_name,
+        captured_at: Local::now().timestamp_millis(),
+        has_screenshot: screenshot.is_some(),
+        screenshot,
+    };
+    info!(title = %ctx.title, "⚡ [Quick] context ({})", ctx.app);
+    let _ = app.emit("axis-quick-context", &ctx);
+    if let Ok(mut guard) = CONTEXT.lock() {
+        *guard = Some(ctx);
+    }
+}
+
+pub fn current() -> Option<QuickContext> {
+    let now = Local::now().timestamp_millis();
+    CONTEXT
+        .lock()
+        .ok()?
+        .clone()
+        .filter(|c| now - c.captured_at < CONTEXT_TTL_MS)
+        .filter(|c| !c.title.is_empty() || c.screenshot.is_some())
+}
+
+// ask_axis に渡す入力（文脈 + 依頼）
+pub fn with_context(ctx: &QuickContext, vision_report: Option<&str>, input: &str) -> String {
+    let mut out = String::from("[Active Window]\n");
+    if ctx.app.is_empty() {
+        out.push_str(&format!("{}\n", ctx.title));
+    } else {
+        out.push_str(&format!("{} ({})\n", ctx.title, ctx.app));
+    }
+    // タイトルだけでは "YouTube" のように何を見ているか分からないので、拡張から届いた URL も
+    if browser::is_browser(&ctx.app) {
+        if let Some(tab) = browser::current() {
+            out.push_str(&format!("Tab: {} <{}>\n", tab.title, tab.url));
+        }
+    }
+    if let Some(report) = vision_report {
+        out.push_str(&format!("\n[Screen]\n{}\n", report));
+    }
+    out.push_str(&format!(
+        "\n(「これ」「この画面」は上のウィンドウのこと)\n\n{}",
+        input
+    ));
+    out
+}
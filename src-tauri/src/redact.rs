@@ -0,0 +1,254 @@
+// src-tauri/src/redact.rs
+//
+// クラウドに送る前の個人情報の伏せ字 (settings.redact_pii)
+// - 検出: メールアドレス / 電話番号 / ファイルパス（Windows のドライブ付き、~ / /home / /Users 以下）は正規表現
+//   名前は settings.redact_terms と、redact_ner なら送る文全部をローカルモデル (prompts/redact.md) に聞いて
+//   （依頼文から先に。長いときは NER_MAX_CHUNKS 回まで）
+// - 同じ文字列は同じ [EMAIL_1] / [PHONE_1] / [PATH_1] / [NAME_1] に置き換え、返ってきた答えの中で元に戻す
+// - ai.rs の call_openai_compatible / call_google と lib.rs の send_llm_request（Commander / Vision）から
+//   ローカルモデル (call_local) は通さない（外に出ないので）
+
+use crate::ai;
+use crate::prompts;
+use crate::settings;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use tracing::{info, warn};
+
+// 名前の検出は NER_TEXT_CHARS 字ずつ、1 回の依頼で NER_MAX_CHUNKS 回まで（残りは正規表現と redact_terms だけ）
+const NER_TEXT_CHARS: usize = 2000;
+const NER_MAX_CHUNKS: usize = 12;
+// 1 回の依頼で Commander / Worker / Critic が同じ文を送るので、名前の検出結果は少しだけ覚えておく
+const NER_CACHE: usize = 16;
+
+#[derive(Debug, Clone, Default)]
+pub struct Redaction {
+    pairs: Vec<(String, String)>, // (置き換え先, 元の文字列)
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct NerReply {
+    names: Vec<String>,
+}
+
+fn email_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap())
+}
+
+fn phone_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\+?\(?\d[\d\s().-]{8,}\d").unwrap())
+}
+
+fn path_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"(?:[A-Za-z]:\\|~/|/home/|/Users/)[^\s"'<>|`]+"#).unwrap())
+}
+
+// "2026-10-15" / "2026/10/15" のような日付（"2026-10-15 12" を電話番号と見ないように）
+fn date_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?:19|20)\d{2}[-/.](?:0?[1-9]|1[0-2])[-/.](?:0?[1-9]|[12]\d|3[01])").unwrap()
+    })
+}
+
+// 桁数が電話番号らしく、+ / 0 始まりか区切りがあるもの（タイムスタンプや金額は除く）
+fn looks_like_phone(m: &str) -> bool {
+    let digits = m.chars().filter(|c| c.is_ascii_digit()).count();
+    (10..=15).contains(&digits)
+        && (m.starts_with('+') || m.starts_with('0') || m.contains(['-', ' ', '(']))
+        && !date_re().is_match(m)
+}
+
+// (依頼文, 見つかった名前)
+type NerEntry = (String, Vec<String>);
+
+fn ner_cache() -> &'static Mutex<VecDeque<NerEntry>> {
+    static CACHE: OnceLock<Mutex<VecDeque<NerEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+// ローカルモデルに名前を挙げさせる。失敗したら空（正規表現の分だけで送る）
+async fn local_names(text: String) -> Vec<String> {
+    if let Some((_, names)) = ner_cache()
+        .lock()
+        .ok()
+        .and_then(|c| c.iter().find(|(t, _)| *t == text).cloned())
+    {
+        return names;
+    }
+    let Some(app) = ai::app_handle() else {
+        return Vec::new();
+    };
+    let sys = prompts::render(app, "redact", &[("text", &text)]);
+    let model = settings::current().models.local;
+    let names = match ai::call_local(&model, &sys, &text).await {
+        Ok(raw) => match (raw.find('{'), raw.rfind('}')) {
+            (Some(start), Some(end)) if start < end => {
+                serde_json::from_str::<NerReply>(&raw[start..=end])
+                    .map(|r| r.names)
+                    .unwrap_or_default()
+            }
+            _ => Vec::new(),
+        },
+        Err(e) => {
+            warn!("⚠️ [Redact] local name detection failed: {}", e);
+            return Vec::new();
+        }
+    };
+    if let Ok(mut cache) = ner_cache().lock() {
+        cache.push_back((text, names.clone()));
+        if cache.len() > NER_CACHE {
+            cache.pop_front();
+        }
+    }
+    names
+}
+
+impl Redaction {
+    fn add(&mut self, kind: &str, original: &str) {
+        let original = original.trim();
+        if original.chars().count() < 2 || self.pairs.iter().any(|(_, o)| o == original) {
+            return;
+        }
+        let n = self
+            .pairs
+            .iter()
+            .filter(|(p, _)| p.starts_with(&format!("[{}_", kind)))
+            .count();
+        self.pairs
+            .push((format!("[{}_{}]", kind, n + 1), original.to_string()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    // 長いものから置き換える（メールの中の名前などを先に潰さないよう）
+    pub fn apply(&self, text: &str) -> String {
+        let mut pairs: Vec<&(String, String)> = self.pairs.iter().collect();
+        pairs.sort_by_key(|(_, o)| std::cmp::Reverse(o.len()));
+        let mut out = text.to_string();
+        for (placeholder, original) in pairs {
+            out = out.replace(original.as_str(), placeholder);
+        }
+        out
+    }
+
+    // 文字列の content と、Vision の [{"type": "text", "text": ...}] の text
+    pub fn apply_value(&self, value: &mut Value) {
+        match value {
+            Value::String(s) => *s = self.apply(s),
+            Value::Array(parts) => {
+                for part in parts {
+                    if let Some(Value::String(s)) = part.get_mut("text") {
+                        *s = self.apply(s);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn restore(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for (placeholder, original) in &self.pairs {
+            out = out.replace(placeholder.as_str(), original);
+        }
+        out
+    }
+}
+
+// texts 全体から伏せるものを決める（システムプロンプトに入れた履歴 / メモリの名前も）
+pub async fn prepare(provider: &str, texts: &[&str]) -> Redaction {
+    let cfg = settings::current();
+    let mut redaction = Redaction::default();
+    if !cfg.redact_pii || provider == "local" {
+        return redaction;
+    }
+    for text in texts {
+        for m in email_re().find_iter(text) {
+            redaction.add("EMAIL", m.as_str());
+        }
+        for m in phone_re().find_iter(text) {
+            if looks_like_phone(m.as_str()) {
+                redaction.add("PHONE", m.as_str());
+            }
+        }
+        for m in path_re().find_iter(text) {
+            redaction.add("PATH", m.as_str().trim_end_matches(['.', ',', ')', ']']));
+        }
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    if cfg.redact_ner {
+        let chunks: Vec<String> = texts
+            .iter()
+            .rev()
+            .flat_map(|t| {
+                let chars: Vec<char> = t.chars().collect();
+                chars
+                    .chunks(NER_TEXT_CHARS)
+                    .map(|c| c.iter().collect::<String>())
+                    .filter(|c| !c.trim().is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        if chunks.len() > NER_MAX_CHUNKS {
+            warn!(
+                "⚠️ [Redact] name detection limited to {} of {} chunks",
+                NER_MAX_CHUNKS,
+                chunks.len()
+            );
+        }
+        for chunk in chunks.into_iter().take(NER_MAX_CHUNKS) {
+            names.extend(local_names(chunk).await);
+        }
+    }
+    // redact_terms は大文字小文字を区別しないので、実際に出てきた表記で登録する
+    for term in cfg
+        .redact_terms
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+    {
+        if let Ok(re) = Regex::new(&format!("(?i){}", regex::escape(term))) {
+            for text in texts {
+                names.extend(re.find_iter(text).map(|m| m.as_str().to_string()));
+            }
+        }
+    }
+    for name in names {
+        if texts.iter().any(|t| t.contains(name.as_str())) {
+            redaction.add("NAME", &name);
+        }
+    }
+
+    if !redaction.is_empty() {
+        info!(
+            "🕵️ [Redact] masked {} item(s) before sending to {}",
+            redaction.pairs.len(),
+            provider
+        );
+    }
+    redaction
+}
+
+// send_llm_request 用: メッセージの content から文字の部分を取り出す
+pub fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
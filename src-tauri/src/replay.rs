@@ -0,0 +1,125 @@
+// src-tauri/src/replay.rs
+//
+// 記録 (trace.rs) からの再生。どの API も呼ばず、アクションも実行しない (axis_core::replay)
+// - replay_trace(log_id): その依頼をもう一度通して、振り分け / アクション / 囲み方 / 答えが記録と同じか
+//   prompts やパーサ、ポリシーを変えた後の回帰確認用（違いは mismatches に）
+// - settings.replay_mode: ask_axis は同じ依頼文の一番新しい記録から答える（オフラインのデモ用）
+//   記録が無ければエラー。再生した答えはどこにも保存しない（記録を上書きしないように）
+
+use crate::db::AxisDatabase;
+use crate::storage::{self, AxisToken, InteractionLog};
+use crate::{settings, trace};
+use axis_core::replay;
+use chrono::Local;
+use serde::Serialize;
+use std::time::Instant;
+use tauri::AppHandle;
+use tracing::info;
+use uuid::Uuid;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReplayResult {
+    pub log_id: String,
+    pub input: String,
+    pub target: String,
+    pub actions: Vec<String>,
+    pub held: Vec<String>,
+    pub answer: String,
+    pub recorded_answer: String,
+    pub mismatches: Vec<String>,
+    pub matches: bool,
+}
+
+async fn run(t: &trace::Trace) -> (replay::Replayed, String) {
+    let mut replayed =
+        replay::replay(&t.recording(), settings::current().confirm_after_untrusted).await;
+    let recorded_answer = if t.report.is_empty() {
+        t.worker_output.clone()
+    } else {
+        t.report.clone()
+    };
+    if replayed.answer != recorded_answer {
+        replayed
+            .mismatches
+            .push("answer differs from the recording".to_string());
+    }
+    (replayed, recorded_answer)
+}
+
+pub async fn replay_trace(app: &AppHandle, log_id: &str) -> Result<ReplayResult, String> {
+    let t = trace::get(app, log_id)?;
+    if t.worker_output.is_empty() {
+        return Err(format!(
+            "trace {} was recorded before replay was supported (no worker output)",
+            log_id
+        ));
+    }
+    let (replayed, recorded_answer) = run(&t).await;
+    info!(
+        "🎞️ [Replay] {}: {} mismatch(es)",
+        log_id,
+        replayed.mismatches.len()
+    );
+    Ok(ReplayResult {
+        log_id: t.log_id.clone(),
+        input: t.input.clone(),
+        target: replayed.decision.target,
+        actions: replayed.actions,
+        held: replayed.held,
+        answer: replayed.answer,
+        recorded_answer,
+        matches: replayed.mismatches.is_empty(),
+        mismatches: replayed.mismatches,
+    })
+}
+
+// replay_mode の ask_axis
+pub async fn answer(
+    app: &AppHandle,
+    input: &str,
+    session_id: &str,
+) -> Result<InteractionLog, String> {
+    let started = Instant::now();
+    let t = AxisDatabase::open(app)?
+        .latest_trace_for_input(input)
+        .map_err(|e| e.to_string())?
+        .filter(|t| !t.worker_output.is_empty())
+        .ok_or_else(|| format!("[Replay] no recorded answer for '{}'", input.trim()))?;
+    let (replayed, _) = run(&t).await;
+    info!(
+        "🎞️ [Replay] answered from trace {} ({} mismatch(es))",
+        t.log_id,
+        replayed.mismatches.len()
+    );
+
+    let now_ts = Local::now().timestamp_millis();
+    Ok(InteractionLog {
+        id: Uuid::new_v4().to_string(),
+        session_id: session_id.to_string(),
+        timestamp: now_ts,
+        user_tokens: input
+            .split_whitespace()
+            .enumerate()
+            .map(|(i, text)| AxisToken {
+                id: format!("{}-{}", now_ts, i),
+                text: text.to_string(),
+                timestamp: now_ts,
+                tags: vec![],
+            })
+            .collect(),
+        ai_response: replayed.answer,
+        provider_used: format!("Replay -> {}", replayed.decision.target),
+        feedback: None,
+        meta: Some(storage::ResponseMeta {
+            target: replayed.decision.target,
+            model: "replay".to_string(),
+            task_type: replayed.decision.task_type,
+            strategy: "replay".to_string(),
+            reason: format!("replayed from trace {}", t.log_id),
+            latency_ms: started.elapsed().as_millis() as i64,
+            actions: replayed.actions,
+            ..Default::default()
+        }),
+        branch_of: None,
+    })
+}
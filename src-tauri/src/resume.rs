@@ -0,0 +1,311 @@
+// src-tauri/src/resume.rs
+//
+// 「続きから」: トピック / プロジェクト名から再開メモを作って新しいセッションを始める (resume_context)
+// - 関連するセッション: search_conversations で引いた分をセッション毎にスコアを足して上位 MAX_SESSIONS 件
+//   それぞれ直近 EXCERPTS 往復を材料に
+// - 終わっていないプラン: 関連セッションで作ったもの + ゴールにトピックの語が入っているもの
+// - 作ったファイル: 関連セッションで SAVE したもの（undo で戻したもの / もう無いものは除く）+ パスにトピックが入っているもの
+// - 知識グラフの事実 (graph::build_context)
+// - settings.resume_writer のモデルが prompts/resume.md で再開メモに
+//   → 新しいセッション（タイトル "Resume: <topic>"）の最初のやり取りとして保存。次の依頼からそのまま文脈に入る
+
+use crate::conversations::{self, SearchFilters};
+use crate::db::AxisDatabase;
+use crate::notify::DeepLink;
+use crate::storage::{self, InteractionLog};
+use crate::{ai, graph, importer, plans, prompts, settings, undo};
+use chrono::Local;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tauri::AppHandle;
+use tracing::info;
+use uuid::Uuid;
+
+const MAX_SESSIONS: usize = 5;
+const EXCERPTS: usize = 3; // セッション毎の直近の往復
+const MAX_FILES: usize = 15;
+const SEARCH_HITS: usize = 60;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ResumeSession {
+    pub session_id: String,
+    pub title: String,
+    pub last_at: i64,
+    pub score: f32,
+    pub link: DeepLink,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct OpenPlan {
+    pub id: String,
+    pub goal: String,
+    pub status: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ResumeBrief {
+    pub topic: String,
+    pub session_id: String, // 再開メモを入れた新しいセッション
+    pub brief: String,
+    pub sessions: Vec<ResumeSession>,
+    pub plans: Vec<OpenPlan>,
+    pub files: Vec<String>,
+    pub link: DeepLink,
+}
+
+fn user_text(log: &InteractionLog) -> String {
+    log.user_tokens
+        .iter()
+        .map(|t| t.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn clip(text: &str, n: usize) -> String {
+    let one_line = text.replace('\n', " ");
+    if one_line.chars().count() > n {
+        one_line.chars().take(n).collect::<String>() + "…"
+    } else {
+        one_line
+    }
+}
+
+// 2 文字以上の語（小文字）
+fn topic_terms(topic: &str) -> Vec<String> {
+    topic
+        .split_whitespace()
+        .map(|t| t.to_lowercase())
+        .filter(|t| t.chars().count() >= 2)
+        .collect()
+}
+
+fn related_sessions(
+    app: &AppHandle,
+    topic: &str,
+    logs: &HashMap<String, Vec<InteractionLog>>,
+) -> Result<Vec<ResumeSession>, String> {
+    let filters = SearchFilters {
+        limit: Some(SEARCH_HITS),
+        ..Default::default()
+    };
+    let mut by_session: HashMap<String, (f32, i64)> = HashMap::new();
+    for hit in conversations::search(app, topic, &filters)? {
+        let e = by_session.entry(hit.session_id).or_insert((0.0, 0));
+        e.0 += hit.score;
+        e.1 = e.1.max(hit.timestamp);
+    }
+    let mut sessions: Vec<ResumeSession> = by_session
+        .into_iter()
+        .map(|(session_id, (score, last_hit))| {
+            let session_logs = logs.get(&session_id);
+            let title = session_logs
+                .and_then(|l| l.first())
+                .map(|l| clip(&user_text(l), 60))
+                .unwrap_or_else(|| session_id.clone());
+            let last_at = session_logs
+                .and_then(|l| l.last())
+                .map(|l| l.timestamp)
+                .unwrap_or(last_hit);
+            ResumeSession {
+                link: DeepLink::session(&session_id),
+                session_id,
+                title,
+                last_at,
+                score,
+            }
+        })
+        .collect();
+    sessions.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.last_at.cmp(&a.last_at))
+    });
+    sessions.truncate(MAX_SESSIONS);
+    Ok(sessions)
+}
+
+fn open_plans(app: &AppHandle, terms: &[String], sessions: &HashSet<&str>) -> Vec<OpenPlan> {
+    plans::list(app, 100)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| matches!(p.status.as_str(), "planned" | "running" | "paused"))
+        .filter(|p| {
+            let goal = p.goal.to_lowercase();
+            sessions.contains(p.session_id.as_str()) || terms.iter().any(|t| goal.contains(t))
+        })
+        .map(|p| OpenPlan {
+            done: p.steps.iter().filter(|s| s.status == "done").count(),
+            total: p.steps.len(),
+            id: p.id,
+            goal: p.goal,
+            status: p.status,
+        })
+        .collect()
+}
+
+fn generated_files(app: &AppHandle, terms: &[String], sessions: &HashSet<&str>) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for entry in undo::list(app, 500).unwrap_or_default() {
+        if entry.undone_at.is_some() || files.contains(&entry.path) {
+            continue;
+        }
+        let path = entry.path.to_lowercase();
+        let related =
+            sessions.contains(entry.session_id.as_str()) || terms.iter().any(|t| path.contains(t));
+        if related && Path::new(&entry.path).exists() {
+            files.push(entry.path);
+        }
+        if files.len() >= MAX_FILES {
+            break;
+        }
+    }
+    files
+}
+
+fn sessions_text(
+    sessions: &[ResumeSession],
+    logs: &HashMap<String, Vec<InteractionLog>>,
+) -> String {
+    let mut out = String::new();
+    for s in sessions {
+        out.push_str(&format!(
+            "## {} (last active {})\n",
+            s.title,
+            chrono::DateTime::from_timestamp_millis(s.last_at)
+                .map(|d| d.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default()
+        ));
+        let recent = logs.get(&s.session_id).map(Vec::as_slice).unwrap_or(&[]);
+        for log in recent.iter().rev().take(EXCERPTS).rev() {
+            out.push_str(&format!(
+                "- User: {}\n  Axis: {}\n",
+                clip(&user_text(log), 200),
+                clip(&log.ai_response, 300)
+            ));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn or_none(lines: Vec<String>) -> String {
+    if lines.is_empty() {
+        "(none)".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+pub async fn resume_context(app: &AppHandle, topic: &str) -> Result<ResumeBrief, String> {
+    let topic = topic.trim();
+    if topic.is_empty() {
+        return Err("resume_context needs a topic".to_string());
+    }
+    // セッション毎に時刻順（答え直しの枝は除く）
+    let mut logs: HashMap<String, Vec<InteractionLog>> = HashMap::new();
+    for log in storage::get_all_logs(app)?
+        .into_iter()
+        .filter(|l| l.branch_of.is_none())
+    {
+        logs.entry(log.session_id.clone()).or_default().push(log);
+    }
+    for l in logs.values_mut() {
+        l.sort_by_key(|l| l.timestamp);
+    }
+
+    let terms = topic_terms(topic);
+    let sessions = related_sessions(app, topic, &logs)?;
+    let session_ids: HashSet<&str> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+    let plans = open_plans(app, &terms, &session_ids);
+    let files = generated_files(app, &terms, &session_ids);
+    if sessions.is_empty() && plans.is_empty() && files.is_empty() {
+        return Err(format!("nothing found about '{}'", topic));
+    }
+
+    let cfg = settings::current();
+    let alias = cfg.resume_writer.trim().to_lowercase();
+    let facts = graph::build_context(app, topic);
+    let sys = prompts::render(
+        app,
+        "resume",
+        &[
+            ("topic", topic),
+            ("sessions", &sessions_text(&sessions, &logs)),
+            (
+                "plans",
+                &or_none(
+                    plans
+                        .iter()
+                        .map(|p| {
+                            format!("- {} [{}] {}/{} steps", p.goal, p.status, p.done, p.total)
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                "files",
+                &or_none(files.iter().map(|f| format!("- {}", f)).collect()),
+            ),
+            (
+                "facts",
+                if facts.is_empty() {
+                    "(none)"
+                } else {
+                    facts.trim()
+                },
+            ),
+        ],
+    );
+    let request = format!("Continue where I left off: {}", topic);
+    let brief = ai::call_alias(&alias, &cfg.models.for_alias(&alias), &sys, &request)
+        .await?
+        .trim()
+        .to_string();
+
+    // 新しいセッションの最初のやり取りにする（run_axis の履歴にそのまま入る）
+    let session_id = Uuid::new_v4().to_string();
+    let now = Local::now().timestamp_millis();
+    AxisDatabase::open(app)?
+        .import_conversation(
+            &session_id,
+            &format!("Resume: {}", topic),
+            &[("user", &request, now), ("assistant", &brief, now)],
+        )
+        .map_err(|e| e.to_string())?;
+    storage::save_log(
+        app,
+        &InteractionLog {
+            id: Uuid::new_v4().to_string(),
+            session_id: session_id.clone(),
+            timestamp: now,
+            user_tokens: importer::tokens(&request, now),
+            ai_response: brief.clone(),
+            provider_used: format!("Resume -> {}", alias),
+            feedback: None,
+            meta: None,
+            branch_of: None,
+        },
+    )?;
+    info!(
+        topic = %topic,
+        "🔁 [Resume] brief from {} sessions / {} plans / {} files -> {}",
+        sessions.len(),
+        plans.len(),
+        files.len(),
+        session_id
+    );
+
+    Ok(ResumeBrief {
+        topic: topic.to_string(),
+        link: DeepLink::session(&session_id),
+        session_id,
+        brief,
+        sessions,
+        plans,
+        files,
+    })
+}
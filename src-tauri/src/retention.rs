@@ -0,0 +1,152 @@
+// src-tauri/src/retention.rs
+//
+// Axis メモリの整理（古くて importance の下がった ShortTerm を片付けて、保存先と検索を軽く保つ）
+// - 対象: ShortTerm で、最後に使われてから memory_prune_after_days 以上経ち、
+//   減衰後の importance (memory::effective_importance) が memory_prune_threshold 未満のもの
+//   👍 が付いているものは残す
+// - memory_prune_action: "archive" は axis_memory/archive/<id>.json に entry + meta を書いてから消す / "delete" はそのまま消す
+// - 起動 STARTUP_DELAY 後と、その後 RUN_INTERVAL 毎。prune_memories で手動でも（dry_run なら数えるだけ）
+// - アーカイブも purge_all_data / purge_provider_data の対象 (archived)
+
+use crate::crypto;
+use crate::memory::{self, MemoryKind, MemoryMeta};
+use crate::settings;
+use chrono::Utc;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const STARTUP_DELAY: Duration = Duration::from_secs(5 * 60);
+const RUN_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PruneReport {
+    pub dry_run: bool,
+    pub scanned: usize,
+    pub candidates: usize,
+    pub archived: usize,
+    pub deleted: usize,
+    pub failed: usize,
+}
+
+fn archive_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("axis_memory")
+        .join("archive");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+// アーカイブ済みの (ファイル, meta)。purge.rs が消す対象を選ぶ用
+pub fn archived(app: &AppHandle) -> Result<Vec<(PathBuf, MemoryMeta)>, String> {
+    let mut out = Vec::new();
+    for item in fs::read_dir(archive_dir(app)?).map_err(|e| e.to_string())? {
+        let path = item.map_err(|e| e.to_string())?.path();
+        if path.extension().is_none_or(|e| e != "json") {
+            continue;
+        }
+        let meta = crypto::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+            .and_then(|v| serde_json::from_value::<MemoryMeta>(v["meta"].clone()).ok());
+        if let Some(meta) = meta {
+            out.push((path, meta));
+        }
+    }
+    Ok(out)
+}
+
+fn is_candidate(meta: &MemoryMeta, now: i64, after_days: u64, threshold: f32) -> bool {
+    let since = meta.last_accessed_ms.unwrap_or(meta.created_at_ms);
+    matches!(meta.kind, MemoryKind::ShortTerm)
+        && now - since >= after_days as i64 * DAY_MS
+        && meta.feedback.as_ref().is_none_or(|f| f.rating <= 0)
+        && memory::effective_importance(meta, now) < threshold
+}
+
+fn archive(app: &AppHandle, id: &str) -> Result<(), String> {
+    let entry = memory::load_entry(app, id)?;
+    let meta = memory::load_meta(app, id)?;
+    let json = serde_json::to_string_pretty(&serde_json::json!({ "entry": entry, "meta": meta }))
+        .map_err(|e| e.to_string())?;
+    crypto::write(archive_dir(app)?.join(format!("{}.json", id)), json)?;
+    memory::delete_entry(app, id)
+}
+
+pub fn prune(app: &AppHandle, dry_run: bool) -> Result<PruneReport, String> {
+    let cfg = settings::current();
+    let mut report = PruneReport {
+        dry_run,
+        ..Default::default()
+    };
+    if cfg.memory_prune_after_days == 0 {
+        return Ok(report);
+    }
+    let now = Utc::now().timestamp_millis();
+    let mut ids = Vec::new();
+    memory::visit_meta(app, |meta| {
+        report.scanned += 1;
+        if is_candidate(
+            meta,
+            now,
+            cfg.memory_prune_after_days,
+            cfg.memory_prune_threshold,
+        ) {
+            ids.push(meta.id.clone());
+        }
+    })?;
+    report.candidates = ids.len();
+    if dry_run {
+        return Ok(report);
+    }
+
+    let archive_first = cfg.memory_prune_action != "delete";
+    for id in ids {
+        let result = if archive_first {
+            archive(app, &id)
+        } else {
+            memory::delete_entry(app, &id)
+        };
+        match result {
+            Ok(()) if archive_first => report.archived += 1,
+            Ok(()) => report.deleted += 1,
+            Err(e) => {
+                warn!("⚠️ [Retention] failed to prune {}: {}", id, e);
+                report.failed += 1;
+            }
+        }
+    }
+    if report.archived + report.deleted > 0 {
+        info!(
+            "🧹 [Retention] pruned {} memories ({} archived, {} deleted, {} scanned)",
+            report.archived + report.deleted,
+            report.archived,
+            report.deleted,
+            report.scanned
+        );
+    }
+    Ok(report)
+}
+
+// setup で呼ぶ
+pub fn spawn_pruner(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            let handle = app.clone();
+            match tauri::async_runtime::spawn_blocking(move || prune(&handle, false)).await {
+                Ok(Err(e)) => warn!("⚠️ [Retention] prune failed: {}", e),
+                Err(e) => warn!("⚠️ [Retention] prune task failed: {}", e),
+                _ => {}
+            }
+            tokio::time::sleep(RUN_INTERVAL).await;
+        }
+    });
+}
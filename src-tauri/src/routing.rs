@@ -0,0 +1,199 @@
+// src-tauri/src/routing.rs
+//
+// Commander を呼ばずに済む入力の振り分け（高速パス）
+// - 挨拶 / 相槌 / お礼など明らかな雑談 → casual_chat として Llama へ
+// - 「続けて」「もっと詳しく」などの続き → 直前の応答と同じ target / task_type
+// - settings.fast_path_routing = false で無効化（下のキャッシュも）
+//
+// ルーティング結果のキャッシュ
+// - Commander の決定をセッション毎に覚えておき、ほぼ同じ依頼（文字 bigram が似ている）や
+//   「JSON で」「表にして」のような直前への注文（入力がその注文だけの時）は、そのまま同じ target / task_type を使う
+
+use crate::storage::ResponseMeta;
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// これより長い入力は雑談でも Commander に任せる
+const MAX_FAST_CHARS: usize = 40;
+
+#[derive(Debug, Clone)]
+pub struct FastRoute {
+    pub strategy: &'static str, // "fast_path" / "cached"
+    pub target: String,
+    pub task_type: String,
+    pub reason: String,
+}
+
+fn casual_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)^(hi|hello|hey|yo|good (morning|afternoon|evening|night)|thanks?( you)?|thx|ok(ay)?|cool|nice|great|bye|おはよう(ございます)?|こんにちは|こんばんは|ありがとう(ございます)?|どうも|了解(です)?|りょうかい|おけ|おっけー|よろしく(お願いします)?|おやすみ(なさい)?|はい|うん|いいね|すごい|なるほど|お疲れ(様|さま)?(です)?)[\s!！。.、,~〜♪wｗ]*$",
+        )
+        .expect("casual regex")
+    })
+}
+
+fn follow_up_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)^(continue|go on|more|keep going|tell me more|続けて|続き|つづき|もっと(詳しく)?|詳しく|他には|それで)[\s!！?？。.、,~〜]*$",
+        )
+        .expect("follow-up regex")
+    })
+}
+
+// 文字 bigram の Jaccard（日本語でも空白に頼らない）
+pub fn similarity(a: &str, b: &str) -> f32 {
+    if a.trim().to_lowercase() == b.trim().to_lowercase() {
+        return 1.0;
+    }
+    jaccard(&bigrams(a), &bigrams(b))
+}
+
+fn jaccard(a: &HashSet<(char, char)>, b: &HashSet<(char, char)>) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / a.union(b).count() as f32
+}
+
+fn bigrams(s: &str) -> HashSet<(char, char)> {
+    let chars: Vec<char> = s
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+// 高速パスで決められれば Some。previous は同じセッションの直前の応答
+pub fn fast_path(input: &str, previous: Option<&ResponseMeta>) -> Option<FastRoute> {
+    let text = input.trim();
+    if text.is_empty() || text.chars().count() > MAX_FAST_CHARS {
+        return None;
+    }
+
+    if follow_up_re().is_match(text) {
+        // 直前もエラー時の退避先なら、改めて Commander に選ばせる
+        let prev = previous.filter(|m| !m.failover && !m.target.is_empty())?;
+        return Some(FastRoute {
+            strategy: "fast_path",
+            target: prev.target.clone(),
+            task_type: prev.task_type.clone(),
+            reason: "直前の依頼の続き（高速パス）".to_string(),
+        });
+    }
+
+    if casual_re().is_match(text) {
+        return Some(FastRoute {
+            strategy: "fast_path",
+            target: "llama".to_string(),
+            task_type: "casual_chat".to_string(),
+            reason: "挨拶・相槌（高速パス）".to_string(),
+        });
+    }
+
+    None
+}
+
+// ---------- ルーティングキャッシュ ----------
+
+const CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+const CACHE_PER_SESSION: usize = 8;
+const CACHE_SIMILARITY: f32 = 0.75;
+
+struct CachedRoute {
+    grams: HashSet<(char, char)>,
+    text: String,
+    target: String,
+    task_type: String,
+    at: Instant,
+}
+
+static CACHE: OnceLock<Mutex<HashMap<String, VecDeque<CachedRoute>>>> = OnceLock::new();
+
+fn cache() -> &'static Mutex<HashMap<String, VecDeque<CachedRoute>>> {
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// 直前の答えへの注文（形式・言語・長さの指定）。入力全体がその注文だけの時に限る
+// （"now open Chrome" / "convert config.toml to json" / "英語で書かれたメールを要約して" は別の依頼）
+fn modifier_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?i)^(?:",
+            // "and in JSON please" / "make it shorter" / "ok, as a table"
+            r"(?:(?:and|also|now|but|ok|okay)[,\s]+)?(?:please\s+)?(?:(?:can you\s+)?(?:make it|put it|write it|say it|give it|answer)\s+)?",
+            r"(?:(?:in|as)\s+(?:json|yaml|csv|markdown|a table|a list|a bullet list|bullet points|english|japanese)|shorter|longer|simpler|briefly|more briefly)",
+            r"(?:,?\s+please)?[\s.!?]*",
+            // 「JSONで」「表にして」「もっと短くしてください」
+            r"|(?:(?:じゃあ|では|あと|今度は|それを|これを)[、,\s]*)?(?:もっと|もう少し)?",
+            r"(?:json|yaml|csv|表|箇条書き|英語|日本語|短く|簡単に|手短に)(?:で|に)?",
+            r"(?:して|書いて|まとめて|答えて|言って)?(?:お願い(?:します)?|ください|ちょうだい)?[\s!！?？。.、,~〜]*",
+            r")$",
+        ))
+        .expect("modifier regex")
+    })
+}
+
+// Commander の決定を覚える
+pub fn remember(session_id: &str, input: &str, target: &str, task_type: &str) {
+    let Ok(mut map) = cache().lock() else { return };
+    let list = map.entry(session_id.to_string()).or_default();
+    list.retain(|c| c.at.elapsed() < CACHE_TTL);
+    list.push_back(CachedRoute {
+        grams: bigrams(input),
+        text: input.trim().to_lowercase(),
+        target: target.to_string(),
+        task_type: task_type.to_string(),
+        at: Instant::now(),
+    });
+    while list.len() > CACHE_PER_SESSION {
+        list.pop_front();
+    }
+}
+
+// 使い回せる決定があれば Some
+pub fn cached(session_id: &str, input: &str) -> Option<FastRoute> {
+    let Ok(mut map) = cache().lock() else {
+        return None;
+    };
+    let list = map.get_mut(session_id)?;
+    list.retain(|c| c.at.elapsed() < CACHE_TTL);
+
+    let text = input.trim();
+    if text.chars().count() <= MAX_FAST_CHARS && modifier_re().is_match(text) {
+        let last = list.back()?;
+        return Some(FastRoute {
+            strategy: "cached",
+            target: last.target.clone(),
+            task_type: last.task_type.clone(),
+            reason: "直前の依頼への注文（ルーティングキャッシュ）".to_string(),
+        });
+    }
+
+    let grams = bigrams(text);
+    let lower = text.to_lowercase();
+    list.iter()
+        .rev()
+        .map(|c| {
+            let score = if c.text == lower {
+                1.0
+            } else {
+                jaccard(&grams, &c.grams)
+            };
+            (score, c)
+        })
+        .find(|(score, _)| *score >= CACHE_SIMILARITY)
+        .map(|(_, c)| FastRoute {
+            strategy: "cached",
+            target: c.target.clone(),
+            task_type: c.task_type.clone(),
+            reason: "似た依頼のルーティングを再利用（ルーティングキャッシュ）".to_string(),
+        })
+}
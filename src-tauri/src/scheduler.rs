@@ -0,0 +1,368 @@
+// src-tauri/src/scheduler.rs
+//
+// Axis スケジューラ（リマインダー / 定期プロンプト）
+// - 永続化: memory.db の scheduled_tasks テーブル
+// - 実行: tokio のバックグラウンドループが 30 秒おきに期限切れタスクを拾う
+//
+// 時刻指定 (when) の書式:
+//   "17:00"              次に来る 17:00 に 1 回
+//   "2026-01-31 09:30"   指定日時に 1 回
+//   "daily 22:00"        毎日
+//   "weekdays 09:00"     平日（月〜金）
+//   "weekly mon 10:00"   毎週
+//   "every 30m" / "every 2h"  一定間隔
+
+use crate::db::AxisDatabase;
+use crate::notify::{self, DeepLink};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+const TICK_SECS: u64 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduledTask {
+    pub id: String,
+    pub title: String,
+    pub action: String, // "notify" / "prompt" / "briefing"
+    pub payload: String,
+    pub session_id: Option<String>,
+    pub repeat_spec: String, // 空文字 = 単発
+    pub next_run_at: i64,
+    pub last_run_at: Option<i64>,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Repeat {
+    Once,
+    Daily(NaiveTime),
+    Weekdays(NaiveTime),
+    Weekly(Weekday, NaiveTime),
+    Every(i64), // ms
+}
+
+// ---------- 時刻指定のパース ----------
+
+fn parse_time(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M")
+        .map_err(|_| format!("invalid time '{}': expected HH:MM", s.trim()))
+}
+
+fn parse_weekday(s: &str) -> Result<Weekday, String> {
+    match s.trim().to_lowercase().as_str() {
+        "mon" | "monday" | "月" => Ok(Weekday::Mon),
+        "tue" | "tuesday" | "火" => Ok(Weekday::Tue),
+        "wed" | "wednesday" | "水" => Ok(Weekday::Wed),
+        "thu" | "thursday" | "木" => Ok(Weekday::Thu),
+        "fri" | "friday" | "金" => Ok(Weekday::Fri),
+        "sat" | "saturday" | "土" => Ok(Weekday::Sat),
+        "sun" | "sunday" | "日" => Ok(Weekday::Sun),
+        other => Err(format!("invalid weekday '{}'", other)),
+    }
+}
+
+fn parse_interval(s: &str) -> Result<i64, String> {
+    let s = s.trim().to_lowercase();
+    let (num, unit_ms) = if let Some(n) = s.strip_suffix('m') {
+        (n, 60_000)
+    } else if let Some(n) = s.strip_suffix('h') {
+        (n, 3_600_000)
+    } else if let Some(n) = s.strip_suffix('d') {
+        (n, 86_400_000)
+    } else {
+        return Err(format!("invalid interval '{}': use e.g. 30m / 2h / 1d", s));
+    };
+    let n: i64 = num
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid interval '{}'", s))?;
+    if n <= 0 {
+        return Err("interval must be positive".to_string());
+    }
+    Ok(n * unit_ms)
+}
+
+// when 文字列 → (Repeat, 保存用の repeat_spec, 単発時の日時)
+fn parse_when(when: &str) -> Result<(Repeat, String, Option<NaiveDateTime>), String> {
+    let w = when.trim();
+    let lower = w.to_lowercase();
+
+    if let Some(rest) = lower.strip_prefix("daily ") {
+        return Ok((Repeat::Daily(parse_time(rest)?), lower.clone(), None));
+    }
+    if let Some(rest) = lower.strip_prefix("weekdays ") {
+        return Ok((Repeat::Weekdays(parse_time(rest)?), lower.clone(), None));
+    }
+    if let Some(rest) = lower.strip_prefix("weekly ") {
+        let (day, time) = rest
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| "weekly needs '<day> HH:MM'".to_string())?;
+        return Ok((
+            Repeat::Weekly(parse_weekday(day)?, parse_time(time)?),
+            lower.clone(),
+            None,
+        ));
+    }
+    if let Some(rest) = lower.strip_prefix("every ") {
+        return Ok((Repeat::Every(parse_interval(rest)?), lower.clone(), None));
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(w, "%Y-%m-%d %H:%M") {
+        return Ok((Repeat::Once, String::new(), Some(dt)));
+    }
+    // "HH:MM" は次に来るその時刻に 1 回だけ（次回計算は Daily を流用、spec は空 = 単発）
+    if let Ok(t) = parse_time(w) {
+        return Ok((Repeat::Daily(t), String::new(), None));
+    }
+
+    Err(format!(
+        "unrecognized schedule '{}'. Use HH:MM, YYYY-MM-DD HH:MM, daily HH:MM, weekdays HH:MM, weekly <day> HH:MM or every <n>m|h|d",
+        w
+    ))
+}
+
+fn local_ms(dt: NaiveDateTime) -> Option<i64> {
+    Local
+        .from_local_datetime(&dt)
+        .earliest()
+        .map(|d| d.timestamp_millis())
+}
+
+// after より後で time に一致する最初の日時（曜日フィルタ付き）
+fn next_matching(
+    after: DateTime<Local>,
+    time: NaiveTime,
+    accept: impl Fn(Weekday) -> bool,
+) -> Option<i64> {
+    let mut date: NaiveDate = after.date_naive();
+    for _ in 0..8 {
+        if accept(date.weekday()) {
+            if let Some(ms) = local_ms(date.and_time(time)) {
+                if ms > after.timestamp_millis() {
+                    return Some(ms);
+                }
+            }
+        }
+        date = date.succ_opt()?;
+    }
+    None
+}
+
+fn next_run(repeat: &Repeat, after: DateTime<Local>) -> Option<i64> {
+    match repeat {
+        Repeat::Once => None,
+        Repeat::Daily(t) => next_matching(after, *t, |_| true),
+        Repeat::Weekdays(t) => {
+            next_matching(after, *t, |d| !matches!(d, Weekday::Sat | Weekday::Sun))
+        }
+        Repeat::Weekly(day, t) => next_matching(after, *t, |d| d == *day),
+        Repeat::Every(ms) => Some(after.timestamp_millis() + ms),
+    }
+}
+
+// ---------- 登録 / 一覧 / 削除 ----------
+
+pub fn create_task(
+    app: &AppHandle,
+    title: &str,
+    when: &str,
+    action: &str,
+    payload: &str,
+    session_id: Option<String>,
+) -> Result<ScheduledTask, String> {
+    if !matches!(action, "notify" | "prompt" | "briefing") {
+        return Err(format!(
+            "unknown action '{}': use notify, prompt or briefing",
+            action
+        ));
+    }
+
+    let (repeat, repeat_spec, once_at) = parse_when(when)?;
+    let now = Local::now();
+
+    let next_run_at = match once_at {
+        Some(dt) => local_ms(dt).ok_or_else(|| format!("invalid local time '{}'", when))?,
+        None => next_run(&repeat, now)
+            .ok_or_else(|| format!("cannot compute next run for '{}'", when))?,
+    };
+
+    let task = ScheduledTask {
+        id: Uuid::new_v4().to_string(),
+        title: title.trim().to_string(),
+        action: action.to_string(),
+        payload: payload.trim().to_string(),
+        session_id,
+        repeat_spec,
+        next_run_at,
+        last_run_at: None,
+        enabled: true,
+        created_at: now.timestamp_millis(),
+    };
+
+    let db = AxisDatabase::open(app)?;
+    db.insert_scheduled_task(&task).map_err(|e| e.to_string())?;
+
+    info!(
+        title = %task.title,
+        "⏰ [Scheduler] registered ({}) next={}",
+        if task.repeat_spec.is_empty() {
+            "once"
+        } else {
+            &task.repeat_spec
+        },
+        task.next_run_at
+    );
+    Ok(task)
+}
+
+pub fn list_tasks(app: &AppHandle) -> Result<Vec<ScheduledTask>, String> {
+    let db = AxisDatabase::open(app)?;
+    db.list_scheduled_tasks().map_err(|e| e.to_string())
+}
+
+pub fn cancel_task(app: &AppHandle, id: &str) -> Result<bool, String> {
+    let db = AxisDatabase::open(app)?;
+    db.delete_scheduled_task(id).map_err(|e| e.to_string())
+}
+
+// SCHEDULE: アクション用。message は "<message>" / "PROMPT: <prompt>" / "BRIEFING"
+pub fn create_from_action(
+    app: &AppHandle,
+    when: &str,
+    message: &str,
+    session_id: &str,
+) -> Result<ScheduledTask, String> {
+    let body = message.trim();
+
+    let (action, payload) = match body.strip_prefix("PROMPT:") {
+        Some(p) => ("prompt", p.trim()),
+        None if body.eq_ignore_ascii_case("BRIEFING") => ("briefing", ""),
+        None => ("notify", body),
+    };
+    let title: String = if action == "briefing" {
+        "Morning briefing".to_string()
+    } else {
+        payload.chars().take(40).collect()
+    };
+
+    create_task(
+        app,
+        &title,
+        when,
+        action,
+        payload,
+        Some(session_id.to_string()),
+    )
+}
+
+// ---------- 実行ループ ----------
+
+async fn run_task(app: &AppHandle, task: &ScheduledTask) {
+    match task.action.as_str() {
+        // 通知とセッションへの記録は feeds.rs 側で行う
+        "briefing" => {
+            if let Err(e) = crate::feeds::run_briefing(app).await {
+                warn!("⚠️ [Scheduler] briefing failed: {}", e);
+                notify::notify(
+                    app,
+                    &format!("⏰ {}", task.title),
+                    &format!("Briefing failed: {}", e),
+                    Some(DeepLink::new("task", &task.id)),
+                );
+            }
+        }
+        "prompt" => {
+            let session_id = task
+                .session_id
+                .clone()
+                .unwrap_or_else(|| format!("scheduler-{}", task.id));
+            let result =
+                crate::ask_axis(app.clone(), task.payload.clone(), session_id.clone()).await;
+            let message = match result {
+                Ok(answer) => answer,
+                Err(e) => format!("Scheduled prompt failed: {}", e),
+            };
+            let _ = app.emit(
+                "axis-scheduler-event",
+                json!({
+                    "task_id": task.id,
+                    "title": task.title,
+                    "action": task.action,
+                    "session_id": session_id,
+                    "message": message,
+                }),
+            );
+            notify::notify(
+                app,
+                &format!("⏰ {}", task.title),
+                &message,
+                Some(DeepLink::session(&session_id)),
+            );
+        }
+        _ => {
+            let _ = app.emit(
+                "axis-scheduler-event",
+                json!({
+                    "task_id": task.id,
+                    "title": task.title,
+                    "action": task.action,
+                    "session_id": task.session_id,
+                    "message": task.payload,
+                }),
+            );
+            notify::notify(
+                app,
+                &format!("⏰ {}", task.title),
+                &task.payload,
+                Some(DeepLink::new("task", &task.id)),
+            );
+        }
+    }
+}
+
+async fn tick(app: &AppHandle) -> Result<(), String> {
+    let now = Local::now();
+    let due = {
+        let db = AxisDatabase::open(app)?;
+        db.due_scheduled_tasks(now.timestamp_millis())
+            .map_err(|e| e.to_string())?
+    };
+
+    for task in due {
+        info!(title = %task.title, text = %task.action, "⏰ [Scheduler] firing");
+
+        // 先に次回時刻を確定させる（実行中の二重発火防止）
+        let next = if task.repeat_spec.is_empty() {
+            None
+        } else {
+            parse_when(&task.repeat_spec)
+                .ok()
+                .and_then(|(repeat, _, _)| next_run(&repeat, now))
+        };
+        {
+            let db = AxisDatabase::open(app)?;
+            db.mark_scheduled_task_run(&task.id, now.timestamp_millis(), next)
+                .map_err(|e| e.to_string())?;
+        }
+
+        run_task(app, &task).await;
+    }
+    Ok(())
+}
+
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = tick(&app).await {
+                warn!("⚠️ [Scheduler] tick failed: {}", e);
+            }
+            tokio::time::sleep(Duration::from_secs(TICK_SECS)).await;
+        }
+    });
+}
@@ -0,0 +1,297 @@
+// src-tauri/src/search.rs
+//
+// Web 検索プロバイダの切り替え
+// - SearchProvider を実装したものを settings.search_providers の順に試し、最初に結果が出たものを使う
+// - 実装: wikipedia (REST API) / duckduckgo (HTML) / brave (API) / searxng (JSON) / bing (API)
+// - API キーは secrets (brave / bing)。SearXNG はインスタンス URL (settings.searxng_url)
+// - プロバイダごとに最小間隔を置く（settings.search_min_interval_ms、無ければ既定値）
+
+use crate::secrets;
+use crate::settings;
+use crate::sharing;
+use crate::web::{self, SearchResult};
+use scraper::Html;
+use serde_json::Value;
+use tracing::{info, warn};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+pub const MAX_RESULTS: usize = 5;
+
+pub type SearchFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<SearchResult>, String>> + Send + 'a>>;
+
+pub trait SearchProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    // 連続で叩く時の最小間隔（settings で上書き可）
+    fn default_interval(&self) -> Duration;
+    fn search<'a>(&'a self, query: &'a str) -> SearchFuture<'a>;
+}
+
+// ---------- 実装 ----------
+
+struct Wikipedia {
+    lang_setting: String, // "auto" / "ja" / "en" ...
+}
+
+impl SearchProvider for Wikipedia {
+    fn name(&self) -> &'static str {
+        "wikipedia"
+    }
+    // 1 回の検索で summary + infobox を数回叩く
+    fn default_interval(&self) -> Duration {
+        Duration::from_millis(200)
+    }
+    fn search<'a>(&'a self, query: &'a str) -> SearchFuture<'a> {
+        Box::pin(async move {
+            let lang = web::wikipedia_lang(query, &self.lang_setting);
+            web::search_wikipedia(query, &lang).await
+        })
+    }
+}
+
+struct DuckDuckGo;
+
+impl SearchProvider for DuckDuckGo {
+    fn name(&self) -> &'static str {
+        "duckduckgo"
+    }
+    // HTML 版は短い間隔で叩くとブロックされる
+    fn default_interval(&self) -> Duration {
+        Duration::from_millis(2000)
+    }
+    fn search<'a>(&'a self, query: &'a str) -> SearchFuture<'a> {
+        Box::pin(web::search_duckduckgo(query))
+    }
+}
+
+struct Brave;
+
+impl SearchProvider for Brave {
+    fn name(&self) -> &'static str {
+        "brave"
+    }
+    // 無料プランは 1 秒 1 リクエスト
+    fn default_interval(&self) -> Duration {
+        Duration::from_millis(1100)
+    }
+    fn search<'a>(&'a self, query: &'a str) -> SearchFuture<'a> {
+        Box::pin(async move {
+            let key = secrets::require_api_key("brave")?;
+            let v = get_json(
+                crate::ai::client_for("web")?
+                    .get("https://api.search.brave.com/res/v1/web/search")
+                    .query(&[("q", query), ("count", &MAX_RESULTS.to_string())])
+                    .header("Accept", "application/json")
+                    .header("X-Subscription-Token", key),
+                "brave",
+            )
+            .await?;
+            Ok(collect(
+                v.pointer("/web/results"),
+                "title",
+                "url",
+                "description",
+            ))
+        })
+    }
+}
+
+struct SearXng {
+    base_url: String,
+}
+
+impl SearchProvider for SearXng {
+    fn name(&self) -> &'static str {
+        "searxng"
+    }
+    fn default_interval(&self) -> Duration {
+        Duration::from_millis(1000)
+    }
+    fn search<'a>(&'a self, query: &'a str) -> SearchFuture<'a> {
+        Box::pin(async move {
+            if self.base_url.is_empty() {
+                return Err("searxng_url is not set".to_string());
+            }
+            // インスタンス側で format=json を有効にしておく必要がある
+            let v = get_json(
+                crate::ai::client_for("web")?
+                    .get(format!("{}/search", self.base_url.trim_end_matches('/')))
+                    .query(&[("q", query), ("format", "json")])
+                    .header("Accept", "application/json"),
+                "searxng",
+            )
+            .await?;
+            Ok(collect(v.get("results"), "title", "url", "content"))
+        })
+    }
+}
+
+struct Bing;
+
+impl SearchProvider for Bing {
+    fn name(&self) -> &'static str {
+        "bing"
+    }
+    fn default_interval(&self) -> Duration {
+        Duration::from_millis(350)
+    }
+    fn search<'a>(&'a self, query: &'a str) -> SearchFuture<'a> {
+        Box::pin(async move {
+            let key = secrets::require_api_key("bing")?;
+            let v = get_json(
+                crate::ai::client_for("web")?
+                    .get("https://api.bing.microsoft.com/v7.0/search")
+                    .query(&[("q", query), ("count", &MAX_RESULTS.to_string())])
+                    .header("Ocp-Apim-Subscription-Key", key),
+                "bing",
+            )
+            .await?;
+            Ok(collect(
+                v.pointer("/webPages/value"),
+                "name",
+                "url",
+                "snippet",
+            ))
+        })
+    }
+}
+
+async fn get_json(req: reqwest::RequestBuilder, name: &str) -> Result<Value, String> {
+    let res = req
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error("web", &e))?;
+    let code = res.status();
+    if !code.is_success() {
+        let body: String = res
+            .text()
+            .await
+            .unwrap_or_default()
+            .chars()
+            .take(200)
+            .collect();
+        return Err(format!("{} search failed [{}]: {}", name, code, body));
+    }
+    res.json()
+        .await
+        .map_err(|e| format!("{} JSON Error: {}", name, e))
+}
+
+// Brave の説明文などは <strong> 付きなのでタグを落とす
+fn strip_tags(s: &str) -> String {
+    Html::parse_fragment(s)
+        .root_element()
+        .text()
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn collect(list: Option<&Value>, title: &str, url: &str, snippet: &str) -> Vec<SearchResult> {
+    let field = |item: &Value, key: &str| {
+        item.get(key)
+            .and_then(|v| v.as_str())
+            .map(strip_tags)
+            .unwrap_or_default()
+    };
+    list.and_then(|l| l.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .map(|item| SearchResult {
+                    title: field(item, title),
+                    link: field(item, url),
+                    snippet: field(item, snippet),
+                    facts: vec![],
+                })
+                .filter(|r| !r.title.is_empty() && !r.link.is_empty())
+                .take(MAX_RESULTS)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ---------- 選択と順序 ----------
+
+fn provider(name: &str, cfg: &settings::Settings) -> Option<Box<dyn SearchProvider>> {
+    match name.trim().to_lowercase().as_str() {
+        // grokipedia は以前のダミー実装の名前。設定に残っていても百科事典として扱う
+        "wikipedia" | "wiki" | "grokipedia" => Some(Box::new(Wikipedia {
+            lang_setting: cfg.wikipedia_lang.clone(),
+        })),
+        "duckduckgo" | "ddg" => Some(Box::new(DuckDuckGo)),
+        "brave" => Some(Box::new(Brave)),
+        "searxng" | "searx" => Some(Box::new(SearXng {
+            base_url: cfg.searxng_url.trim().to_string(),
+        })),
+        "bing" => Some(Box::new(Bing)),
+        _ => None,
+    }
+}
+
+// 前回の呼び出しから interval 経つまで待つ（次に使える時刻を先に予約する）
+async fn wait_turn(name: &str, interval: Duration) {
+    if interval.is_zero() {
+        return;
+    }
+    static NEXT: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+    let wait = {
+        let Ok(mut next) = NEXT.get_or_init(|| Mutex::new(HashMap::new())).lock() else {
+            return;
+        };
+        let now = Instant::now();
+        let slot = next.get(name).copied().filter(|t| *t > now).unwrap_or(now);
+        next.insert(name.to_string(), slot + interval);
+        slot - now
+    };
+    if !wait.is_zero() {
+        info!(
+            "⏳ [Search] {} rate limit: waiting {}ms",
+            name,
+            wait.as_millis()
+        );
+        tokio::time::sleep(wait).await;
+    }
+}
+
+// 設定順に試して (使ったプロバイダ名, 結果) を返す
+// 全部エラーならエラーをまとめて返す。結果 0 件だけなら空で Ok
+pub async fn search(query: &str) -> Result<(String, Vec<SearchResult>), String> {
+    let cfg = settings::current();
+    let mut errors = Vec::new();
+
+    for name in &cfg.search_providers {
+        let Some(p) = provider(name, &cfg) else {
+            errors.push(format!("unknown search provider '{}'", name));
+            continue;
+        };
+        let interval = cfg
+            .search_min_interval_ms
+            .get(p.name())
+            .map(|ms| Duration::from_millis(*ms))
+            .unwrap_or_else(|| p.default_interval());
+        wait_turn(p.name(), interval).await;
+
+        sharing::record(p.name(), "search", "", query, (0, 0));
+        match p.search(query).await {
+            Ok(res) if !res.is_empty() => {
+                info!("🔎 [Search] {}: {} results", p.name(), res.len());
+                return Ok((p.name().to_string(), res));
+            }
+            Ok(_) => info!("🔎 [Search] {}: no hits, trying next", p.name()),
+            Err(e) => {
+                warn!("⚠️ [Search] {} failed: {}", p.name(), e);
+                errors.push(format!("{}: {}", p.name(), e));
+            }
+        }
+    }
+
+    if !errors.is_empty() && errors.len() == cfg.search_providers.len() {
+        return Err(errors.join(" / "));
+    }
+    Ok((String::new(), Vec::new()))
+}
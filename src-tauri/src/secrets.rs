@@ -0,0 +1,288 @@
+// src-tauri/src/secrets.rs
+//
+// API キーの保管（OS の資格情報ストア）
+// - 保存先: keyring (service "axis-os", user "api-key:<provider>")
+// - 読み込み: keyring → 無ければ従来どおり env (.env) にフォールバック
+// - provider 名は Commander のエイリアス (llama / gpt / gemini / grok) でも会社名でも可
+// - settings.providers の追加プロバイダは "provider:<alias>"（user "api-key:provider:<alias>"。env には無い）
+//   組み込みのキーとは別に持つ（base_url は自由に書けるので、組み込みのキーを他所へ送らせない）
+
+use serde::Serialize;
+use tracing::info;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Mutex, OnceLock};
+
+const KEYRING_SERVICE: &str = "axis-os";
+
+// (正式名, 別名, env 変数)
+const PROVIDERS: &[(&str, &[&str], &str)] = &[
+    ("nvidia", &["llama"], "NVIDIA_API_KEY"),
+    ("openai", &["gpt"], "OPENAI_API_KEY"),
+    ("gemini", &["google"], "GEMINI_API_KEY"),
+    ("xai", &["grok"], "XAI_API_KEY"),
+    // LOOK の視覚モデルの候補 (vision.rs)
+    ("anthropic", &["claude"], "ANTHROPIC_API_KEY"),
+    // IMAGE_GEN の生成先の一つ (imagegen.rs)
+    ("stability", &[], "STABILITY_API_KEY"),
+    ("brave", &[], "BRAVE_API_KEY"),
+    ("bing", &["azure"], "BING_API_KEY"),
+    ("newsapi", &["news"], "NEWSAPI_KEY"),
+    // Google Calendar の OAuth クライアントシークレット（デスクトップアプリ用クライアント）
+    ("google", &["gcal"], "GOOGLE_CLIENT_SECRET"),
+    // IMAP のパスワード（Gmail 等はアプリパスワード）
+    ("imap", &["email"], "IMAP_PASSWORD"),
+    // Home Assistant の長期アクセストークン
+    ("homeassistant", &["ha", "home_assistant"], "HA_TOKEN"),
+];
+
+// keyring は毎回 OS を呼ぶので読めた値は覚えておく（set で更新）
+static CACHE: OnceLock<Mutex<HashMap<String, Option<String>>>> = OnceLock::new();
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ApiKeyStatus {
+    pub provider: String,
+    pub source: String, // "keyring" / "env" / "missing"
+}
+
+fn resolve(provider: &str) -> Result<(&'static str, &'static str), String> {
+    let p = provider.trim().to_lowercase();
+    PROVIDERS
+        .iter()
+        .find(|(name, aliases, _)| *name == p || aliases.contains(&p.as_str()))
+        .map(|(name, _, env_var)| (*name, *env_var))
+        .ok_or_else(|| {
+            format!(
+                "unknown provider '{}': use nvidia / openai / gemini / xai / anthropic / stability / brave / bing / newsapi / google / imap / homeassistant, or provider:<alias> for settings.providers",
+                provider
+            )
+        })
+}
+
+const PROVIDER_PREFIX: &str = "provider:";
+
+// "provider:<alias>" で、settings.providers にあるエイリアスなら Some(alias)
+fn custom_alias(provider: &str) -> Option<String> {
+    let alias = provider.trim().strip_prefix(PROVIDER_PREFIX)?.trim();
+    crate::providers::is_custom(alias).then(|| alias.to_string())
+}
+
+fn entry(name: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, &format!("api-key:{}", name))
+        .map_err(|e| format!("keyring unavailable: {}", e))
+}
+
+// user = keyring のユーザー名 ("api-key:<provider>" / "token:<name>")
+fn read_entry(user: &str) -> Option<String> {
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(v) = cache.lock().ok().and_then(|c| c.get(user).cloned()) {
+        return v;
+    }
+    let value = keyring::Entry::new(KEYRING_SERVICE, user)
+        .ok()
+        .and_then(|e| e.get_password().ok())
+        .filter(|k| !k.trim().is_empty());
+    if let Ok(mut c) = cache.lock() {
+        c.insert(user.to_string(), value.clone());
+    }
+    value
+}
+
+fn keyring_key(name: &str) -> Option<String> {
+    read_entry(&format!("api-key:{}", name))
+}
+
+fn forget(user: &str) {
+    if let Some(cache) = CACHE.get() {
+        if let Ok(mut c) = cache.lock() {
+            c.remove(user);
+        }
+    }
+}
+
+fn env_key(env_var: &str) -> Option<String> {
+    env::var(env_var).ok().filter(|k| !k.trim().is_empty())
+}
+
+// 呼び出し側用: keyring → env の順で探す
+pub fn api_key(provider: &str) -> Option<String> {
+    if let Some(alias) = custom_alias(provider) {
+        return keyring_key(&format!("{}{}", PROVIDER_PREFIX, alias));
+    }
+    let (name, env_var) = resolve(provider).ok()?;
+    keyring_key(name).or_else(|| env_key(env_var))
+}
+
+// 見つからない時のエラーメッセージ込み
+pub fn require_api_key(provider: &str) -> Result<String, String> {
+    if let Some(alias) = custom_alias(provider) {
+        return api_key(provider).ok_or_else(|| {
+            format!(
+                "no key for provider '{}' (set it with set_api_key(\"{}{}\"))",
+                alias, PROVIDER_PREFIX, alias
+            )
+        });
+    }
+    let (_, env_var) = resolve(provider)?;
+    api_key(provider)
+        .ok_or_else(|| format!("{} missing (set it with set_api_key or in .env)", env_var))
+}
+
+// 空文字で削除
+pub fn set_api_key(provider: &str, key: &str) -> Result<ApiKeyStatus, String> {
+    let custom = custom_alias(provider).map(|alias| format!("{}{}", PROVIDER_PREFIX, alias));
+    let name = match &custom {
+        Some(name) => name.as_str(),
+        None => resolve(provider)?.0,
+    };
+    let e = entry(name)?;
+    let key = key.trim();
+    if key.is_empty() {
+        match e.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(err) => return Err(format!("failed to delete key: {}", err)),
+        }
+    } else {
+        e.set_password(key)
+            .map_err(|err| format!("failed to store key: {}", err))?;
+    }
+    forget(&format!("api-key:{}", name));
+    info!(
+        "🔑 [Secrets] {} key {}",
+        name,
+        if key.is_empty() { "removed" } else { "stored" }
+    );
+    Ok(status(name))
+}
+
+fn status(name: &str) -> ApiKeyStatus {
+    // 追加プロバイダ ("provider:<alias>") は env に無いので resolve は失敗して "" のまま
+    let env_var = resolve(name).map(|(_, v)| v).unwrap_or_default();
+    let source = if keyring_key(name).is_some() {
+        "keyring"
+    } else if env_key(env_var).is_some() {
+        "env"
+    } else {
+        "missing"
+    };
+    ApiKeyStatus {
+        provider: name.to_string(),
+        source: source.to_string(),
+    }
+}
+
+// キーの値は返さない（どこから来ているかだけ）
+pub fn list_api_keys() -> Vec<ApiKeyStatus> {
+    PROVIDERS
+        .iter()
+        .map(|(name, _, _)| status(name))
+        .chain(
+            crate::providers::names()
+                .iter()
+                .map(|alias| status(&format!("{}{}", PROVIDER_PREFIX, alias))),
+        )
+        .collect()
+}
+
+// OAuth のリフレッシュトークン等（service "axis-os", user "token:<name>"）。空文字で削除
+pub fn token(name: &str) -> Option<String> {
+    read_entry(&format!("token:{}", name))
+}
+
+pub fn set_token(name: &str, value: &str) -> Result<(), String> {
+    let key = format!("token:{}", name);
+    let e = keyring::Entry::new(KEYRING_SERVICE, &key)
+        .map_err(|e| format!("keyring unavailable: {}", e))?;
+    if value.is_empty() {
+        match e.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(err) => return Err(format!("failed to delete token: {}", err)),
+        }
+    } else {
+        e.set_password(value)
+            .map_err(|err| format!("failed to store token: {}", err))?;
+    }
+    forget(&key);
+    Ok(())
+}
+
+// プロバイダのモデル一覧 API を叩いてキーが通るか確認
+pub async fn test_api_key(provider: &str) -> Result<String, String> {
+    if let Some(alias) = custom_alias(provider) {
+        let key = require_api_key(provider)?;
+        let endpoint = crate::providers::endpoint(&alias)?;
+        let req = crate::ai::client_for(&alias)?.get(crate::providers::models_url(&alias)?);
+        let req = match endpoint.auth {
+            crate::providers::Auth::Bearer => req.bearer_auth(&key),
+            crate::providers::Auth::ApiKey => req.header("api-key", &key),
+        };
+        return check_key_response(&endpoint.key_name, req).await;
+    }
+    let (name, _) = resolve(provider)?;
+    let key = require_api_key(name)?;
+    let client = crate::ai::client_for(name)?;
+
+    let req = match name {
+        // シークレット単体では確かめられない（OAuth の接続時に使う）
+        "google" => {
+            return Ok(format!(
+                "google client secret stored (source: {})",
+                status(name).source
+            ))
+        }
+        // HTTP ではなく実際にログインしてみる
+        "imap" => return crate::connectors::email::test_login().await,
+        "homeassistant" => return crate::connectors::home_assistant::test_connection().await,
+        "gemini" => client.get(format!(
+            "https://generativelanguage.googleapis.com/v1beta/models?key={}",
+            key
+        )),
+        "openai" => client
+            .get("https://api.openai.com/v1/models")
+            .bearer_auth(&key),
+        "xai" => client.get("https://api.x.ai/v1/models").bearer_auth(&key),
+        "stability" => client
+            .get("https://api.stability.ai/v1/user/account")
+            .bearer_auth(&key),
+        "anthropic" => client
+            .get("https://api.anthropic.com/v1/models")
+            .header("x-api-key", &key)
+            .header("anthropic-version", "2023-06-01"),
+        "brave" => client
+            .get("https://api.search.brave.com/res/v1/web/search?q=test&count=1")
+            .header("X-Subscription-Token", &key),
+        "bing" => client
+            .get("https://api.bing.microsoft.com/v7.0/search?q=test&count=1")
+            .header("Ocp-Apim-Subscription-Key", &key),
+        "newsapi" => client
+            .get("https://newsapi.org/v2/top-headlines?country=us&pageSize=1")
+            .header("X-Api-Key", &key),
+        _ => client
+            .get("https://integrate.api.nvidia.com/v1/models")
+            .bearer_auth(&key),
+    };
+
+    check_key_response(name, req).await
+}
+
+async fn check_key_response(name: &str, req: reqwest::RequestBuilder) -> Result<String, String> {
+    let res = req
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error(name, &e))?;
+    let code = res.status();
+    if code.is_success() {
+        Ok(format!("{} key OK (source: {})", name, status(name).source))
+    } else if code.as_u16() == 401 || code.as_u16() == 403 {
+        Err(format!("{} rejected the key [{}]", name, code))
+    } else {
+        let body: String = res
+            .text()
+            .await
+            .unwrap_or_default()
+            .chars()
+            .take(200)
+            .collect();
+        Err(format!("{} key test failed [{}]: {}", name, code, body))
+    }
+}
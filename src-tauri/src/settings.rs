@@ -0,0 +1,808 @@
+// src-tauri/src/settings.rs
+//
+// 設定 (app_data_dir/settings.json)
+// - 型付きの Settings を JSON に保存。get_settings / update_settings コマンドで読み書き
+// - ファイルを外部で書き換えても数秒で反映（更新時刻を見て読み直し、axis-settings-changed を発火）
+// - 互換のため、従来の env 変数が設定されていればファイルより優先（overrides に列挙）
+// - learn_alias: Worker の LEARN_ALIAS で EXEC の別名を足す（ファイルに書いて反映）
+
+use crate::logging;
+use axis_core::apps;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ModelSettings {
+    pub core: String, // Commander (NVIDIA)
+    pub gpt: String,
+    pub gemini: String,
+    pub grok: String,
+    pub local: String, // Ollama 等のモデル名
+}
+
+impl Default for ModelSettings {
+    fn default() -> Self {
+        Self {
+            core: "meta/llama-3.1-70b-instruct".to_string(),
+            gpt: "gpt-5-nano".to_string(),
+            gemini: "gemini-2.5-flash".to_string(),
+            grok: "grok-4-1-fast-reasoning".to_string(),
+            local: "llama3.1".to_string(),
+        }
+    }
+}
+
+impl ModelSettings {
+    // Commander のエイリアス → モデル名 (llama / 不明 → core)
+    pub fn for_alias(&self, alias: &str) -> String {
+        match alias {
+            "gpt" => self.gpt.clone(),
+            "gemini" => self.gemini.clone(),
+            "grok" => self.grok.clone(),
+            "local" => self.local.clone(),
+            other => crate::providers::get(other)
+                .map(|p| p.model_name())
+                .unwrap_or_else(|| self.core.clone()),
+        }
+    }
+}
+
+// LOOK の視覚モデル (vision.rs)。失敗したら次の候補へ
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct VisionModelSettings {
+    pub gpt: String,
+    pub gemini: String,
+    pub claude: String,
+    pub llama: String, // NVIDIA
+}
+
+impl Default for VisionModelSettings {
+    fn default() -> Self {
+        Self {
+            gpt: "gpt-4o".to_string(),
+            gemini: "gemini-2.5-flash".to_string(),
+            claude: "claude-sonnet-4-5".to_string(),
+            llama: "meta/llama-3.2-11b-vision-instruct".to_string(),
+        }
+    }
+}
+
+// IMAGE_GEN のモデル (imagegen.rs)。stability はエンドポイント名 ("core" / "ultra" / "sd3")
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ImageModelSettings {
+    pub openai: String,
+    pub imagen: String,
+    pub stability: String,
+}
+
+impl Default for ImageModelSettings {
+    fn default() -> Self {
+        Self {
+            openai: "dall-e-3".to_string(),
+            imagen: "imagen-4.0-generate-001".to_string(),
+            stability: "core".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct TimeoutSettings {
+    pub connect_secs: Option<u64>,
+    pub request_secs: Option<u64>,
+}
+
+// プロバイダ毎の利用額の上限 (budget.rs)。None は上限なし
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct BudgetLimit {
+    pub daily_usd: Option<f64>,
+    pub monthly_usd: Option<f64>,
+}
+
+// 追加の OpenAI 互換プロバイダ (providers.rs)。settings.providers のキーがエイリアスになる
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ProviderConfig {
+    pub kind: String,        // "azure" / "openrouter" / "openai"（任意の OpenAI 互換）
+    pub base_url: String, // azure: "https://<resource>.openai.azure.com"、openai: ".../v1"（openrouter は空で既定）
+    pub model: String,    // azure では空ならデプロイ名
+    pub deployment: String, // azure のデプロイ名
+    pub api_version: String, // azure の api-version（空で既定）
+    pub api_key: String, // 使わない（キーは set_api_key("provider:<alias>") でエイリアス毎に。空でなければエラー）
+    pub headers: BTreeMap<String, String>, // 追加のヘッダー（OpenRouter の HTTP-Referer / X-Title など）
+}
+
+impl ProviderConfig {
+    pub fn model_name(&self) -> String {
+        if self.model.trim().is_empty() {
+            self.deployment.trim().to_string()
+        } else {
+            self.model.trim().to_string()
+        }
+    }
+}
+
+// USD / 100 万トークン
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ModelPrice {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+}
+
+// None は ai.rs の既定値
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct RateLimitSettings {
+    pub requests_per_minute: Option<u32>, // 0 で無制限
+    pub burst: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub models: ModelSettings,
+    pub local_llm_url: String,
+    // "default" + プロバイダ名 (llama / gpt / gemini / grok / local / web)
+    pub timeouts: BTreeMap<String, TimeoutSettings>,
+    pub rate_limits: BTreeMap<String, RateLimitSettings>, // timeouts と同じキー (ai.rs の送信待ち)
+    pub memory_backend: String,                           // "json" / "sqlite"
+    pub memory_direct_threshold: f32,
+    pub observer_idle_secs: u64,
+    pub notify_long_task_secs: u64,
+    pub confirm_actions: Vec<String>, // 確認が必要なアクション ("*" = 全部)
+    pub confirm_after_untrusted: bool, // 検索結果 / メール等が履歴やメモリに入ったセッションの操作と外への送信は確認に回す (untrusted.rs)
+    pub encrypt_at_rest: bool,
+    pub fast_path_routing: bool, // 挨拶や「続けて」は Commander を通さない (routing.rs)
+    pub ensemble_members: Vec<String>, // "ensemble" で同時に聞くエイリアス
+    pub ensemble_judge: String,  // 候補を選ぶ / 統合するモデルのエイリアス
+    pub critic_enabled: bool,    // コード / 数学の回答を別モデルが見直す (critic.rs)
+    pub critic_task_types: Vec<String>,
+    pub critic_reviewer: String,
+    pub planner: String,       // PLAN: の手順を作るモデルのエイリアス (plans.rs)
+    pub plan_auto_start: bool, // 手順を出したらそのまま実行する（既定は false: start_plan を待つ）
+    pub plan_max_steps: usize,
+    pub hotkey: String, // quick-ask を呼び出すグローバルホットキー（空文字で無効）
+    pub quick_ask_screenshot: bool, // ホットキー押下時に画面も撮って quick-ask の文脈にする
+    pub minimize_to_tray: bool, // メインウィンドウを閉じてもトレイに残る（終了はトレイの Quit）
+    pub start_on_login: bool, // ログイン時にトレイに常駐した状態で起動する (autostart.rs)
+    pub voice_enabled: bool, // 返事を読み上げる（フロントの音声合成。トレイの Toggle Voice）
+    pub web_fetch_pages: usize, // SEARCH で本文まで読む上位件数（0 で従来どおりリンクのみ）
+    pub web_summarizer: String, // 取得した本文を要約するモデルのエイリアス
+    // 検索プロバイダを試す順 (wikipedia / duckduckgo / brave / searxng / bing)
+    pub search_providers: Vec<String>,
+    pub wikipedia_lang: String, // "auto"（クエリの文字で ja / en）か言語コード
+    pub searxng_url: String,    // 例: "https://searx.example.org"
+    pub search_min_interval_ms: BTreeMap<String, u64>, // プロバイダ別の最小間隔（無ければ既定値）
+    // FETCH: の宛先 (allow が空なら全部可、deny が優先。"example.com" はサブドメインも含む)
+    pub fetch_allow_domains: Vec<String>,
+    pub fetch_deny_domains: Vec<String>,
+    pub fetch_max_chars: usize, // FETCH: で system_context に入れる本文の上限
+    pub weather_location: String, // WEATHER: で場所を言わなかった時の地名
+    pub news_source: String,    // "rss" / "newsapi"
+    pub news_feeds: Vec<String>, // RSS / Atom の URL
+    pub news_country: String,   // NewsAPI のトップニュースの国 (jp / us ...)
+    pub feed_poll_minutes: u64, // 購読フィードを読み直す間隔（0 で止める）
+    pub briefing_writer: String, // ブリーフィングを書くモデルのエイリアス (feeds.rs)
+    pub calendar_ics: Vec<String>, // .ics のパスか URL (http / https / webcal)
+    pub calendar_warn_minutes: u64, // 会議の何分前に知らせるか（0 で止める）
+    pub google_calendar_client_id: String, // OAuth クライアント ID（シークレットは secrets "google"）
+    pub email_imap_host: String,           // 例: "imap.gmail.com"（パスワードは secrets "imap"）
+    pub email_imap_port: u16,              // TLS (993)
+    pub email_user: String,
+    pub email_mailbox: String, // 既定 "INBOX"
+    pub media_backend: String, // MEDIA: の送り先 "keys"（メディアキー）/ "spotify"
+    pub spotify_client_id: String,
+    pub spotify_redirect_port: u16, // Spotify に登録する http://127.0.0.1:<port>/callback
+    pub home_assistant_url: String, // 例: "http://homeassistant.local:8123"（トークンは secrets "homeassistant"）
+    pub ha_allowed_entities: Vec<String>, // HA: で操作してよいエンティティ ("light.*" / "scene.good_night")
+    // RUN: で確認なしに実行してよいコマンド（引数まで完全一致。"*" は - / で始まらない引数 1 つ。それ以外は確認ダイアログ）
+    pub run_allow_commands: Vec<String>,
+    pub run_allow_flags: Vec<String>, // 許可したコマンドの後ろに足してよいフラグ（値を取らないもの）
+    pub run_timeout_secs: u64,
+    pub run_max_output_chars: usize, // system_context に入れる出力の上限
+    pub project_folders: Vec<String>, // Git リポジトリを探すフォルダ（相対パスは SAVE と同じくデスクトップ基準）
+    pub log_level: String, // "info" / "info,web=debug" のようにモジュール毎にも (logging.rs)
+    pub budgets: BTreeMap<String, BudgetLimit>, // プロバイダ名 (gpt / gemini / grok / llama) → 上限
+    pub budget_warn_ratio: f32, // 上限のこの割合を超えたら警告
+    pub model_prices: BTreeMap<String, ModelPrice>, // モデル名の前方一致 → 単価（budget.rs の既定値を上書き）
+    pub auto_tag_enabled: bool, // 保存したメモリに L / M / S の分類とタグを付ける (tagging.rs)
+    pub auto_tagger: String,    // 分類を考えるモデルのエイリアス（既定はローカル。クラウドにするとやり取りが送られる）
+    // ShortTerm メモリの importance が半分になる日数（最後に使われてから。0 で減衰なし）
+    pub memory_decay_half_life_days: f32,
+    pub memory_prune_after_days: u64, // これより長く使われていない ShortTerm を整理の対象に（0 で止める）
+    pub memory_prune_threshold: f32,  // 減衰後の importance がこれ未満なら整理
+    pub memory_prune_action: String,  // "archive"（axis_memory/archive へ移す）/ "delete"
+    pub memory_dedup_threshold: f32, // 保存時、文字 3-gram の類似度がこれ以上なら既存の 1 件にまとめる（0 で止める）
+    pub graph_enabled: bool,         // 依頼文から事実を抜き出して知識グラフに (graph.rs)
+    pub graph_extractor: String,     // 抜き出すモデルのエイリアス（既定はローカル。クラウドにするとローカルで答えた依頼は抜き出さない）
+    pub journal_time: String, // 毎日この時刻 (HH:MM) に日記を書く（空文字で止める。既定は止めてある）(journal.rs)
+    pub journal_weekly_day: String, // この曜日 (mon..sun) は週のまとめも（空文字で止める）
+    pub journal_writer: String, // 日記を書くモデルのエイリアス（既定はローカル。一日の記録を丸ごと渡す）
+    pub journal_folder: String, // Markdown の写しを置くフォルダ（空文字で書かない。相対パスはデスクトップ基準）
+    pub resume_writer: String,  // resume_context の再開メモを書くモデルのエイリアス (resume.rs)
+    pub redact_pii: bool, // クラウドに送る前にメール / 電話番号 / パス / 名前を [EMAIL_1] などに置き換え、答えで戻す (redact.rs)
+    pub redact_ner: bool, // 名前の検出にローカルモデル (settings.models.local) も使う
+    pub redact_terms: Vec<String>, // いつも伏せる語（自分の名前 / 社名など。大文字小文字は区別しない）
+    pub local_only: bool, // クラウド / Web には一切出ず、全部ローカルモデルで (local_only.rs。env: AXIS_LOCAL_ONLY)
+    pub local_vision_model: String, // vision の候補 local で使う Ollama の視覚モデル（空文字で候補から外す。ローカル専用モードは OCR で読む）
+    pub replay_mode: bool, // 答えを記録 (trace.rs) から再生する。API は呼ばない（デモ / 回帰確認用。replay.rs。env: AXIS_REPLAY_MODE）
+    pub data_sharing_log_days: u64, // 外部に送った中身の記録を残す日数（0 で記録しない）(sharing.rs)
+    pub api_server_enabled: bool, // スクリプト / エディタ / 他のエージェント用の HTTP / MCP API を 127.0.0.1 に開く (api_server.rs。env: AXIS_API_SERVER)
+    pub api_server_port: u16,     // env: AXIS_API_PORT
+    pub plugins_enabled: bool, // app_data_dir/plugins/ のプラグインのアクションを使う (plugins.rs。env: AXIS_PLUGINS_ENABLED)
+    pub providers: BTreeMap<String, ProviderConfig>, // エイリアス → Azure OpenAI / OpenRouter 等の接続先 (providers.rs)
+    pub vision_provider: String, // LOOK で画面を読むモデル "auto"（vision_profiles.json の点数順）/ gpt / gemini / claude / llama / local を先頭に (vision.rs)
+    pub vision_models: VisionModelSettings, // local は local_vision_model
+    pub document_provider: String, // ask_document で文書を読むモデルのエイリアス（"gemini" は PDF / 画像もそのまま渡す）(documents.rs)
+    pub image_provider: String, // IMAGE_GEN の生成先 "openai"（DALL·E / gpt-image）/ "imagen"（Google。キーは gemini）/ "stability" (imagegen.rs)
+    pub image_models: ImageModelSettings,
+    pub image_aspect: String, // IMAGE_GEN で縦横比を言わなかった時 ("1:1" / "16:9" / "9:16")
+    pub image_folder: String, // 生成した画像の書き出し先（相対パスはデスクトップ基準）
+    pub transcription_provider: String, // ingest_audio の文字起こし "openai"（Whisper）/ "gemini" / "local"（OpenAI 互換の Whisper サーバー）(audio.rs)
+    pub transcription_model: String,    // openai のモデル ("whisper-1" / "gpt-4o-transcribe")
+    pub local_whisper_url: String, // whisper.cpp / faster-whisper 等の /v1/audio/transcriptions
+    pub audio_summarizer: String,  // 文字起こしを要約するモデルのエイリアス
+    pub browser_bridge_enabled: bool, // ブラウザ拡張 (browser-extension/) から今のタブを受け取る。API サーバーも要る (connectors/browser.rs。env: AXIS_BROWSER_BRIDGE)
+    pub look_source: String, // LOOK の読み方 "auto"（UI Automation で前面ウィンドウの文字を読み、足りなければスクリーンショット）/ "screenshot" (uia.rs。env: AXIS_LOOK_SOURCE)
+    pub app_aliases: BTreeMap<String, BTreeMap<String, String>>, // OS ("windows" / "macos" / "linux") → EXEC の別名 → アプリ名 / 実行ファイル（"browser" → "chrome.exe"。組み込みの名前より先。LEARN_ALIAS でも増える）
+    pub app_catalog_refresh_hours: u64, // インストール済みアプリの一覧 (Get-StartApps) を読み直す間隔（0 で止める。app_catalog.rs）
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            models: ModelSettings::default(),
+            local_llm_url: "http://localhost:11434/v1/chat/completions".to_string(),
+            timeouts: BTreeMap::new(),
+            rate_limits: BTreeMap::new(),
+            memory_backend: "json".to_string(),
+            memory_direct_threshold: 6.0,
+            observer_idle_secs: 300,
+            notify_long_task_secs: 15,
+            confirm_actions: vec!["KILL".to_string()],
+            confirm_after_untrusted: true,
+            encrypt_at_rest: false,
+            fast_path_routing: true,
+            ensemble_members: vec!["gpt".to_string(), "gemini".to_string(), "grok".to_string()],
+            ensemble_judge: "llama".to_string(),
+            critic_enabled: false,
+            critic_task_types: vec!["code_edit".to_string(), "math_solve".to_string()],
+            critic_reviewer: "grok".to_string(),
+            planner: "gpt".to_string(),
+            plan_auto_start: false,
+            plan_max_steps: 12,
+            hotkey: "Ctrl+Alt+Space".to_string(),
+            quick_ask_screenshot: false,
+            minimize_to_tray: true,
+            start_on_login: false,
+            voice_enabled: false,
+            web_fetch_pages: 3,
+            web_summarizer: "gpt".to_string(),
+            search_providers: vec!["wikipedia".to_string(), "duckduckgo".to_string()],
+            wikipedia_lang: "auto".to_string(),
+            searxng_url: String::new(),
+            search_min_interval_ms: BTreeMap::new(),
+            fetch_allow_domains: vec![],
+            fetch_deny_domains: vec![],
+            fetch_max_chars: 8000,
+            weather_location: "Tokyo".to_string(),
+            news_source: "rss".to_string(),
+            news_feeds: vec!["https://www3.nhk.or.jp/rss/news/cat0.xml".to_string()],
+            news_country: "jp".to_string(),
+            feed_poll_minutes: 30,
+            briefing_writer: "gpt".to_string(),
+            calendar_ics: vec![],
+            calendar_warn_minutes: 10,
+            google_calendar_client_id: String::new(),
+            email_imap_host: String::new(),
+            email_imap_port: 993,
+            email_user: String::new(),
+            email_mailbox: "INBOX".to_string(),
+            media_backend: "keys".to_string(),
+            spotify_client_id: String::new(),
+            spotify_redirect_port: 8898,
+            home_assistant_url: String::new(),
+            ha_allowed_entities: vec![],
+            project_folders: vec![],
+            run_allow_commands: [
+                "dir",
+                "dir *",
+                "where *",
+                "ipconfig",
+                "ping *",
+                "git status",
+                "git log",
+                "git diff",
+                "cargo check",
+                "cargo build",
+                "cargo test",
+                "npm test",
+                "npm run build",
+            ]
+            .iter()
+            .map(|c| c.to_string())
+            .collect(),
+            run_allow_flags: ["--release", "--quiet", "-q", "--oneline", "--stat", "--short"]
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+            run_timeout_secs: 120,
+            run_max_output_chars: 8000,
+            log_level: "info".to_string(),
+            budgets: BTreeMap::new(),
+            budget_warn_ratio: 0.8,
+            model_prices: BTreeMap::new(),
+            auto_tag_enabled: false,
+            auto_tagger: "local".to_string(),
+            memory_decay_half_life_days: 30.0,
+            memory_prune_after_days: 90,
+            memory_prune_threshold: 0.1,
+            memory_prune_action: "archive".to_string(),
+            memory_dedup_threshold: 0.9,
+            graph_enabled: false,
+            graph_extractor: "local".to_string(),
+            journal_time: String::new(),
+            journal_weekly_day: "sun".to_string(),
+            journal_writer: "local".to_string(),
+            journal_folder: String::new(),
+            resume_writer: "gpt".to_string(),
+            redact_pii: false,
+            redact_ner: false,
+            redact_terms: Vec::new(),
+            local_only: false,
+            local_vision_model: "llava".to_string(),
+            replay_mode: false,
+            data_sharing_log_days: 30,
+            api_server_enabled: false,
+            api_server_port: 7341,
+            plugins_enabled: true,
+            providers: BTreeMap::new(),
+            vision_provider: "auto".to_string(),
+            vision_models: VisionModelSettings::default(),
+            document_provider: "gemini".to_string(),
+            image_provider: "openai".to_string(),
+            image_models: ImageModelSettings::default(),
+            image_aspect: "1:1".to_string(),
+            image_folder: "AxisImages".to_string(),
+            transcription_provider: "openai".to_string(),
+            transcription_model: "whisper-1".to_string(),
+            local_whisper_url: "http://localhost:8000/v1/audio/transcriptions".to_string(),
+            audio_summarizer: "gemini".to_string(),
+            browser_bridge_enabled: false,
+            look_source: "auto".to_string(),
+            app_aliases: BTreeMap::new(),
+            app_catalog_refresh_hours: 6,
+        }
+    }
+}
+
+// get_settings の戻り値
+#[derive(Serialize, Debug, Clone)]
+pub struct SettingsView {
+    pub path: Option<String>,
+    pub settings: Settings,     // ファイルに保存されている値
+    pub effective: Settings,    // env 上書き後の実際に使われる値
+    pub overrides: Vec<String>, // 上書きしている env 変数名
+}
+
+#[derive(Default)]
+struct Store {
+    path: Option<PathBuf>,
+    mtime: Option<SystemTime>,
+    file: Settings,
+    effective: Settings,
+    overrides: Vec<String>,
+}
+
+static STORE: RwLock<Option<Store>> = RwLock::new(None);
+
+fn env_str(key: &str, overrides: &mut Vec<String>) -> Option<String> {
+    let v = env::var(key).ok().filter(|v| !v.trim().is_empty())?;
+    overrides.push(key.to_string());
+    Some(v.trim().to_string())
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str, overrides: &mut Vec<String>) -> Option<T> {
+    let v = env::var(key).ok()?.trim().parse::<T>().ok()?;
+    overrides.push(key.to_string());
+    Some(v)
+}
+
+// 従来の env 変数を上に被せる
+fn apply_env(mut s: Settings) -> (Settings, Vec<String>) {
+    let mut o = Vec::new();
+
+    if let Some(v) = env_str("AI_MODEL", &mut o) {
+        s.models.core = v;
+    }
+    if let Some(v) = env_str("GPT_MODEL", &mut o) {
+        s.models.gpt = v;
+    }
+    if let Some(v) = env_str("GEMINI_MODEL", &mut o) {
+        s.models.gemini = v;
+    }
+    if let Some(v) = env_str("GROK_MODEL", &mut o) {
+        s.models.grok = v;
+    }
+    if let Some(v) = env_str("LOCAL_MODEL", &mut o) {
+        s.models.local = v;
+    }
+    if let Some(v) = env_str("LOCAL_LLM_URL", &mut o) {
+        s.local_llm_url = v;
+    }
+    if let Some(v) = env_parse("AXIS_LOCAL_ONLY", &mut o) {
+        s.local_only = v;
+    }
+    if let Some(v) = env_parse("AXIS_REPLAY_MODE", &mut o) {
+        s.replay_mode = v;
+    }
+    if let Some(v) = env_parse("AXIS_API_SERVER", &mut o) {
+        s.api_server_enabled = v;
+    }
+    if let Some(v) = env_parse("AXIS_API_PORT", &mut o) {
+        s.api_server_port = v;
+    }
+    if let Some(v) = env_parse("AXIS_PLUGINS_ENABLED", &mut o) {
+        s.plugins_enabled = v;
+    }
+    if let Some(v) = env_str("AXIS_VISION_PROVIDER", &mut o) {
+        s.vision_provider = v.to_lowercase();
+    }
+    if let Some(v) = env_parse("AXIS_BROWSER_BRIDGE", &mut o) {
+        s.browser_bridge_enabled = v;
+    }
+    if let Some(v) = env_str("AXIS_LOOK_SOURCE", &mut o) {
+        s.look_source = v.to_lowercase();
+    }
+    if let Some(v) = env_str("MEMORY_BACKEND", &mut o) {
+        s.memory_backend = v.to_lowercase();
+    }
+    if let Some(v) = env_parse("MEMORY_DIRECT_THRESHOLD", &mut o) {
+        s.memory_direct_threshold = v;
+    }
+    if let Some(v) = env_parse("OBSERVER_IDLE_SECS", &mut o) {
+        s.observer_idle_secs = v;
+    }
+    if let Some(v) = env_parse("NOTIFY_LONG_TASK_SECS", &mut o) {
+        s.notify_long_task_secs = v;
+    }
+    if let Some(v) = env_str("AXIS_CONFIRM_ACTIONS", &mut o) {
+        s.confirm_actions = v
+            .split(',')
+            .map(|a| a.trim().to_uppercase())
+            .filter(|a| !a.is_empty())
+            .collect();
+    }
+    if let Some(v) = env_str("AXIS_ENCRYPT_AT_REST", &mut o) {
+        s.encrypt_at_rest = matches!(v.to_lowercase().as_str(), "1" | "true" | "on" | "yes");
+    }
+
+    if let Some(v) = env_parse("AXIS_WEB_FETCH_PAGES", &mut o) {
+        s.web_fetch_pages = v;
+    }
+
+    if let Some(v) = env_str("AXIS_SEARCH_PROVIDERS", &mut o) {
+        s.search_providers = v
+            .split(',')
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty())
+            .collect();
+    }
+    if let Some(v) = env_str("SEARXNG_URL", &mut o) {
+        s.searxng_url = v;
+    }
+
+    if let Some(v) = env_str("AXIS_WEATHER_LOCATION", &mut o) {
+        s.weather_location = v;
+    }
+
+    if let Some(v) = env_str("AXIS_CALENDAR_ICS", &mut o) {
+        s.calendar_ics = v
+            .split(',')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+    }
+    if let Some(v) = env_str("GOOGLE_CLIENT_ID", &mut o) {
+        s.google_calendar_client_id = v;
+    }
+
+    if let Some(v) = env_str("IMAP_HOST", &mut o) {
+        s.email_imap_host = v;
+    }
+    if let Some(v) = env_parse("IMAP_PORT", &mut o) {
+        s.email_imap_port = v;
+    }
+    if let Some(v) = env_str("IMAP_USER", &mut o) {
+        s.email_user = v;
+    }
+
+    if let Some(v) = env_str("AXIS_MEDIA_BACKEND", &mut o) {
+        s.media_backend = v.to_lowercase();
+    }
+    if let Some(v) = env_str("SPOTIFY_CLIENT_ID", &mut o) {
+        s.spotify_client_id = v;
+    }
+
+    if let Some(v) = env_str("HA_URL", &mut o) {
+        s.home_assistant_url = v;
+    }
+
+    if let Some(v) = env_parse("AXIS_RUN_TIMEOUT_SECS", &mut o) {
+        s.run_timeout_secs = v;
+    }
+
+    if let Some(v) = env_str("AXIS_PROJECT_FOLDERS", &mut o) {
+        s.project_folders = v
+            .split(';')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+    }
+
+    if let Some(v) = env_str("AXIS_LOG", &mut o) {
+        s.log_level = v;
+    }
+
+    if let Ok(v) = env::var("AXIS_HOTKEY") {
+        o.push("AXIS_HOTKEY".to_string());
+        s.hotkey = v.trim().to_string();
+    }
+
+    // タイムアウト: LLM_* が既定、<PROVIDER>_* がプロバイダ別
+    for (name, prefix) in [
+        ("default", "LLM"),
+        ("llama", "LLAMA"),
+        ("gpt", "GPT"),
+        ("gemini", "GEMINI"),
+        ("grok", "GROK"),
+        ("local", "LOCAL"),
+        ("web", "WEB"),
+    ] {
+        let connect = env_parse::<u64>(&format!("{}_CONNECT_TIMEOUT_SECS", prefix), &mut o);
+        let request = env_parse::<u64>(&format!("{}_TIMEOUT_SECS", prefix), &mut o);
+        if connect.is_some() || request.is_some() {
+            let t = s.timeouts.entry(name.to_string()).or_default();
+            t.connect_secs = connect.or(t.connect_secs);
+            t.request_secs = request.or(t.request_secs);
+        }
+    }
+
+    (s, o)
+}
+
+fn settings_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|d| d.join("settings.json"))
+}
+
+fn mtime(path: &Option<PathBuf>) -> Option<SystemTime> {
+    path.as_ref()?.metadata().ok()?.modified().ok()
+}
+
+fn read_file(path: &Option<PathBuf>) -> Settings {
+    let Some(p) = path else {
+        return Settings::default();
+    };
+    match fs::read_to_string(p) {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+            warn!(
+                "⚠️ [Settings] settings.json parse error, using defaults: {}",
+                e
+            );
+            Settings::default()
+        }),
+        Err(_) => Settings::default(),
+    }
+}
+
+fn store(path: Option<PathBuf>, file: Settings) {
+    let (effective, overrides) = apply_env(file.clone());
+    logging::configure(&effective.log_level);
+    let next = Store {
+        mtime: mtime(&path),
+        path,
+        file,
+        effective,
+        overrides,
+    };
+    if let Ok(mut s) = STORE.write() {
+        *s = Some(next);
+    }
+}
+
+// 実際に使う値（init 前なら既定値 + env）
+pub fn current() -> Settings {
+    if let Some(s) = STORE
+        .read()
+        .ok()
+        .and_then(|s| s.as_ref().map(|s| s.effective.clone()))
+    {
+        return s;
+    }
+    apply_env(Settings::default()).0
+}
+
+pub fn view() -> SettingsView {
+    let guard = STORE.read().ok();
+    match guard.as_ref().and_then(|s| s.as_ref()) {
+        Some(s) => SettingsView {
+            path: s.path.as_ref().map(|p| p.to_string_lossy().to_string()),
+            settings: s.file.clone(),
+            effective: s.effective.clone(),
+            overrides: s.overrides.clone(),
+        },
+        None => {
+            let (effective, overrides) = apply_env(Settings::default());
+            SettingsView {
+                path: None,
+                settings: Settings::default(),
+                effective,
+                overrides,
+            }
+        }
+    }
+}
+
+// 起動時（setup）に呼ぶ: 読み込み + 変更監視
+pub fn init(app: &AppHandle) {
+    let path = settings_path(app);
+    store(path.clone(), read_file(&path));
+
+    let app = app.clone();
+    thread::spawn(move || loop {
+        thread::sleep(WATCH_INTERVAL);
+        let known = STORE
+            .read()
+            .ok()
+            .and_then(|s| s.as_ref().and_then(|s| s.mtime));
+        if mtime(&path) != known {
+            info!("⚙️ [Settings] settings.json changed, reloading");
+            store(path.clone(), read_file(&path));
+            let _ = app.emit("axis-settings-changed", view());
+        }
+    });
+}
+
+pub fn update(app: &AppHandle, settings: Settings) -> Result<SettingsView, String> {
+    if !(0.0..=100.0).contains(&settings.memory_direct_threshold) {
+        return Err("memory_direct_threshold must be within 0..=100".to_string());
+    }
+    if !matches!(settings.memory_backend.as_str(), "json" | "sqlite") {
+        return Err(format!(
+            "unknown memory_backend '{}': use json or sqlite",
+            settings.memory_backend
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&settings.budget_warn_ratio) {
+        return Err("budget_warn_ratio must be within 0..=1".to_string());
+    }
+    if settings.memory_decay_half_life_days < 0.0 {
+        return Err("memory_decay_half_life_days must not be negative".to_string());
+    }
+    if !(0.0..=1.0).contains(&settings.memory_prune_threshold) {
+        return Err("memory_prune_threshold must be within 0..=1".to_string());
+    }
+    if !(0.0..=1.0).contains(&settings.memory_dedup_threshold) {
+        return Err("memory_dedup_threshold must be within 0..=1".to_string());
+    }
+    let journal_time = settings.journal_time.trim();
+    if !journal_time.is_empty() && chrono::NaiveTime::parse_from_str(journal_time, "%H:%M").is_err()
+    {
+        return Err(format!(
+            "invalid journal_time '{}': expected HH:MM",
+            journal_time
+        ));
+    }
+    let weekly_day = settings.journal_weekly_day.trim();
+    if !weekly_day.is_empty() && weekly_day.parse::<chrono::Weekday>().is_err() {
+        return Err(format!(
+            "invalid journal_weekly_day '{}': use mon..sun",
+            weekly_day
+        ));
+    }
+    for (name, target) in settings.app_aliases.values().flatten() {
+        apps::check_alias(name, target)?;
+    }
+    if !matches!(settings.memory_prune_action.as_str(), "archive" | "delete") {
+        return Err(format!(
+            "unknown memory_prune_action '{}': use archive or delete",
+            settings.memory_prune_action
+        ));
+    }
+
+    let path = settings_path(app).ok_or("app data dir unavailable")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    store(Some(path), settings);
+    let view = view();
+    let _ = app.emit("axis-settings-changed", &view);
+    Ok(view)
+}
+
+// 今の OS の別名 (EXEC)
+pub fn app_aliases() -> BTreeMap<String, String> {
+    current()
+        .app_aliases
+        .remove(env::consts::OS)
+        .unwrap_or_default()
+}
+
+// LEARN_ALIAS で先にしてよいフォルダ（インストールしたアプリの置き場所）
+fn alias_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = ["ProgramFiles", "ProgramFiles(x86)", "ProgramW6432"]
+        .iter()
+        .filter_map(|v| env::var(v).ok())
+        .map(PathBuf::from)
+        .collect();
+    for base in ["APPDATA", "ProgramData"] {
+        if let Ok(dir) = env::var(base) {
+            roots.push(PathBuf::from(dir).join(r"Microsoft\Windows\Start Menu"));
+        }
+    }
+    if cfg!(target_os = "macos") {
+        roots.push(PathBuf::from("/Applications"));
+    } else if cfg!(target_os = "linux") {
+        roots.extend(["/usr/bin", "/usr/local/bin", "/opt", "/snap/bin"].map(PathBuf::from));
+    }
+    roots
+}
+
+// Worker の LEARN_ALIAS が使える別名か（apps::check_learned + パスはアプリの置き場所の中だけ）
+// policy で確認待ちに積む前と、承認された後の保存の両方で
+pub fn check_learned_alias(name: &str, target: &str) -> Result<(String, String), String> {
+    let (name, target) = apps::check_learned(name, target)?;
+    if target.contains(['\\', '/']) {
+        let path = std::path::Path::new(&target)
+            .canonicalize()
+            .map_err(|_| format!("'{}' not found", target))?;
+        if !alias_roots()
+            .iter()
+            .filter_map(|r| r.canonicalize().ok())
+            .any(|root| path.starts_with(root))
+        {
+            return Err(format!(
+                "'{}' is outside Program Files and the Start menu",
+                target
+            ));
+        }
+    }
+    Ok((name, target))
+}
+
+// LEARN_ALIAS: 今の OS の別名に足して保存（同じ名前なら置き換え）。policy で承認されたものだけ
+pub fn learn_alias(app: &AppHandle, name: &str, target: &str) -> Result<(String, String), String> {
+    let (name, target) = check_learned_alias(name, target)?;
+    let mut s = view().settings;
+    let aliases = s
+        .app_aliases
+        .entry(env::consts::OS.to_string())
+        .or_default();
+    aliases.retain(|k, _| apps::alias_key(k) != name);
+    aliases.insert(name.clone(), target.clone());
+    update(app, s)?;
+    info!("⚙️ [Settings] EXEC alias '{}' -> '{}'", name, target);
+    Ok((name, target))
+}
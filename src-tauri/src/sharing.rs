@@ -0,0 +1,155 @@
+// src-tauri/src/sharing.rs
+//
+// 外部プロバイダに送った中身の記録（何を・どこに出したかを後から確かめる用。memory.db の data_sharing）
+// - 送る直前に ai.rs / lib.rs の HTTP 呼び出しから record（伏せ字 (redact.rs) の後、実際に出る文面のまま）
+//   ローカルモデル (call_local) は外に出ないので記録しない
+// - LLM 以外に出るもの: 検索語 (search.rs、provider = 検索プロバイダ) / FETCH などで読む URL (web.rs、"web")
+//   画像生成のプロンプト (imagegen.rs) / Home Assistant のサービス呼び出し / Spotify の操作 / ニュース / 天気
+// - 1 回の依頼の分は run_axis が scope で包んで log_id / session_id を付ける（裏の自動タグ等は付かない）
+//   シークレットセッション (incognito.rs) は本文を残さず、プロバイダ / 大きさ / 中身の種類だけ
+// - sections: 文面に入っていたもの（history / memory / known_facts / recall / vision / system_info / untrusted_content / screenshot）
+// - settings.data_sharing_log_days より古い分は記録の度に消す（0 で記録しない）
+// - get_data_sharing_log で log_id / provider を指定して見る
+
+use crate::ai;
+use crate::db::AxisDatabase;
+use crate::settings;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use tracing::warn;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+// 文面の目印 → 種類
+const SECTIONS: &[(&str, &str)] = &[
+    ("\nAxis: ", "history"),
+    ("[Relevant Memories]", "memory"),
+    ("[Known Facts]", "known_facts"),
+    ("[Recall: related past messages]", "recall"),
+    ("[Vision Report]", "vision"),
+    ("[System]", "system_info"),
+    ("<<<UNTRUSTED", "untrusted_content"),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct Interaction {
+    pub log_id: String,
+    pub session_id: String,
+    pub incognito: bool,
+}
+
+tokio::task_local! {
+    static INTERACTION: Interaction;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SharedPayload {
+    pub id: i64,
+    pub provider: String,
+    pub model: String,
+    pub log_id: Option<String>,
+    pub session_id: Option<String>,
+    pub system_prompt: String,
+    pub user_input: String,
+    pub omitted: bool, // シークレットセッションなので本文は残していない
+    pub chars: usize,  // 送った文字数（system + user）
+    pub images: usize, // スクリーンショット等（画像そのものは残さない）
+    pub image_bytes: usize,
+    pub sections: Vec<String>,
+    pub created_at: i64,
+}
+
+// run_axis 1 回分をこの中で走らせる
+pub async fn scope<F: Future>(interaction: Interaction, f: F) -> F::Output {
+    INTERACTION.scope(interaction, f).await
+}
+
+// spawn した先でも同じやり取りとして記録したい時に（ensemble.rs）
+pub fn current() -> Option<Interaction> {
+    INTERACTION.try_with(|i| i.clone()).ok()
+}
+
+pub async fn inherit<F: Future>(interaction: Option<Interaction>, f: F) -> F::Output {
+    match interaction {
+        Some(i) => INTERACTION.scope(i, f).await,
+        None => f.await,
+    }
+}
+
+fn sections(text: &str, images: usize) -> Vec<String> {
+    let mut out: Vec<String> = SECTIONS
+        .iter()
+        .filter(|(marker, _)| text.contains(marker))
+        .map(|(_, name)| name.to_string())
+        .collect();
+    if images > 0 {
+        out.push("screenshot".to_string());
+    }
+    out
+}
+
+// images: (枚数, base64 の合計バイト数)
+pub fn record(
+    provider: &str,
+    model: &str,
+    system_prompt: &str,
+    user_input: &str,
+    images: (usize, usize),
+) {
+    let days = settings::current().data_sharing_log_days;
+    if days == 0 {
+        return;
+    }
+    let Some(app) = ai::app_handle() else {
+        return;
+    };
+    let interaction = current();
+    let omitted = interaction.as_ref().is_some_and(|i| i.incognito);
+    let now = Utc::now().timestamp_millis();
+    let payload = SharedPayload {
+        provider: provider.to_string(),
+        model: model.to_string(),
+        log_id: interaction.as_ref().map(|i| i.log_id.clone()),
+        session_id: interaction.as_ref().map(|i| i.session_id.clone()),
+        system_prompt: if omitted {
+            String::new()
+        } else {
+            system_prompt.to_string()
+        },
+        user_input: if omitted {
+            String::new()
+        } else {
+            user_input.to_string()
+        },
+        omitted,
+        chars: system_prompt.chars().count() + user_input.chars().count(),
+        images: images.0,
+        image_bytes: images.1,
+        sections: sections(&format!("{}\n{}", system_prompt, user_input), images.0),
+        created_at: now,
+        ..Default::default()
+    };
+    let result = AxisDatabase::open(app).and_then(|db| {
+        db.add_data_share(&payload)
+            .and_then(|_| db.prune_data_shares(now - days as i64 * DAY_MS))
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        warn!(
+            "⚠️ [Sharing] failed to record payload for {}: {}",
+            provider, e
+        );
+    }
+}
+
+pub fn list(
+    app: &tauri::AppHandle,
+    log_id: Option<&str>,
+    provider: Option<&str>,
+    limit: usize,
+) -> Result<Vec<SharedPayload>, String> {
+    AxisDatabase::open(app)?
+        .data_shares(log_id, provider, limit)
+        .map_err(|e| e.to_string())
+}
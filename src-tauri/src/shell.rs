@@ -1,17 +1,34 @@
 // src-tauri/src/shell.rs
 // v0.4.1 Fix: "Liar Logic" Removal (AppID Search + Explorer Launch)
 
+use std::env;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::AtomicBool;
 use std::thread;
 use std::time::Duration;
-use enigo::{Enigo, Key, Keyboard, Settings, Direction};
+use enigo::{Button, Coordinate, Direction, Enigo, Key, Keyboard, Mouse, Settings};
+use axis_core::{apps, keys};
+use tracing::info;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
 // 1. アプリ起動 (AppID経由の確実な起動)
+// 利用者の別名 (settings.app_aliases / LEARN_ALIAS) が先。先がパス / 実行ファイルならそのまま、アプリ名なら下の検索へ
+// 組み込みの名前 → インストール済みアプリの一覧 (app_catalog.rs) → スタートメニューの検索 (PowerShell) の順
 pub fn execute_command(app_req: &str) -> String {
-    let request = app_req.trim();
+    let aliases = crate::settings::app_aliases();
+    let request = match apps::resolve(&aliases, app_req) {
+        Some(target) => {
+            info!("🚀 [Exec] alias '{}' -> '{}'", app_req.trim(), target);
+            if apps::is_command(target) {
+                return launch_simple(target, target);
+            }
+            target
+        }
+        None => app_req.trim(),
+    };
     let request_lower = request.to_lowercase();
     
     // 優先: よく使うシステムコマンド (これらは動いているはず)
@@ -24,6 +41,15 @@ pub fn execute_command(app_req: &str) -> String {
         _ => {} 
     };
 
+    // インストール済みアプリの一覧 (app_catalog.rs) にあれば PowerShell 無しで
+    if let Some(app) = crate::app_catalog::find(request) {
+        let res = launch_app_id(&app.name, &app.id);
+        if res.starts_with("Success") {
+            crate::app_catalog::record_launch(&app.id);
+        }
+        return res;
+    }
+
     // --- ユニバーサル検索ロジック変更 ---
     // PowerShellで「起動」するのではなく、「AppID」だけを取得する。
     // ※ AppID = Windowsがアプリを管理するための絶対住所
@@ -46,15 +72,7 @@ pub fn execute_command(app_req: &str) -> String {
                 // 見つからない場合は正直に言う
                 format!("Failed: Application '{}' not found in Start Menu.", request)
             } else {
-                // ★ここが修正点: explorer.exe に AppID を渡して起動させる
-                // これで「裏でこっそり失敗する」のを防ぐ
-                let launch_cmd = format!("shell:AppsFolder\\{}", app_id);
-                
-                // explorer.exe は通常ウインドウを表示してくれる
-                match Command::new("explorer").arg(&launch_cmd).spawn() {
-                    Ok(_) => format!("Success: Launched '{}' (ID: {}).", request, app_id),
-                    Err(e) => format!("Error: Found ID {} but failed to launch. {}", app_id, e)
-                }
+                launch_app_id(request, &app_id)
             }
         },
         Err(e) => format!("Error executing shell search: {}", e),
@@ -62,6 +80,16 @@ pub fn execute_command(app_req: &str) -> String {
 }
 
 // 補助関数
+// ★ここが修正点: explorer.exe に AppID を渡して起動させる
+// これで「裏でこっそり失敗する」のを防ぐ（explorer.exe は通常ウインドウを表示してくれる）
+fn launch_app_id(name: &str, app_id: &str) -> String {
+    let launch_cmd = format!("shell:AppsFolder\\{}", app_id);
+    match Command::new("explorer").arg(&launch_cmd).spawn() {
+        Ok(_) => format!("Success: Launched '{}' (ID: {}).", name, app_id),
+        Err(e) => format!("Error: Found ID {} but failed to launch. {}", app_id, e)
+    }
+}
+
 fn launch_simple(cmd: &str, name: &str) -> String {
     Command::new("cmd").args(&["/C", "start", "", cmd]).spawn()
         .map(|_| format!("Success: Launched {}.", name))
@@ -74,45 +102,608 @@ fn launch_simple_args(cmd: &str, args: Vec<&str>, name: &str) -> String {
         .unwrap_or_else(|e| format!("Error launching {}: {}", name, e))
 }
 
-// --- 以下、入力・キー操作系（変更なし） ---
+// --- 以下、入力・キー操作系 ---
+// 宛先があれば UI Automation でウィンドウと入力欄を探してフォーカスを確かめてから打つ (uia.rs)
+// "<window> > <field>" で入力欄の名前も指定できる。見つからない / 移らない時は打たずに Failed を返す
+// 宛先が無い / "current" の時は今のフォーカスにそのまま（利用者が切り替える間だけ待つ）
 pub fn type_text(text: &str, target_window: Option<&str>) -> String {
-    let mut enigo = Enigo::new(&Settings::default()).unwrap();
-    
-    if let Some(target) = target_window {
-        let ps_script = format!(
-            "$ws = New-Object -ComObject WScript.Shell; \
-             $p = Get-Process | Where-Object {{ $_.MainWindowTitle -like '*{}*' -or $_.ProcessName -like '*{}*' }} | Select-Object -First 1; \
-             if ($p) {{ $ws.AppActivate($p.Id) }}", 
-            target, target
-        );
-        let _ = Command::new("powershell")
-            .args(&["-NoProfile", "-WindowStyle", "Hidden", "-ExecutionPolicy", "Bypass", "-Command", &ps_script])
-            .creation_flags(0x08000000).output();
-        thread::sleep(Duration::from_millis(1000));
-    } else {
-        thread::sleep(Duration::from_millis(2000)); 
-    }
+    let target = target_window.map(str::trim).filter(|t| !t.is_empty() && !t.eq_ignore_ascii_case("current"));
+    let focused = match target {
+        Some(t) => {
+            let (window, field) = match t.split_once(" > ") {
+                Some((w, f)) => (w, Some(f)),
+                None => (t, None),
+            };
+            match crate::uia::focus_field(window, field) {
+                Ok(label) => Some(label),
+                Err(e) => return format!("Failed: TYPE target not ready, nothing was typed: {}", e),
+            }
+        }
+        None => {
+            thread::sleep(Duration::from_millis(2000));
+            None
+        }
+    };
 
+    let mut enigo = match Enigo::new(&Settings::default()) {
+        Ok(e) => e,
+        Err(e) => return format!("Error: {}", e),
+    };
     if let Err(e) = enigo.text(text) { return format!("Error typing text: {}", e); }
-    
-    if let Some(t) = target_window {
-        format!("Focused '{}' and Typed: '{}'", t, text)
-    } else {
-        format!("Typed: '{}'", text)
+
+    match focused {
+        Some(label) => format!("Typed into {}: '{}'", label, text),
+        None => format!("Typed: '{}'", text),
     }
 }
 
+// PRESS: "ctrl+shift+s" / "f5" / "tab x3" / "ctrl+k, ctrl+c"（書式は axis_core::keys）
 pub fn press_key(key_name: &str) -> String {
-    let mut enigo = Enigo::new(&Settings::default()).unwrap();
+    let presses = match keys::parse(key_name) {
+        Ok(p) => p,
+        Err(e) => return format!("Error: {}", e),
+    };
+    let mut enigo = match Enigo::new(&Settings::default()) {
+        Ok(e) => e,
+        Err(e) => return format!("Error: {}", e),
+    };
     thread::sleep(Duration::from_millis(300));
-    let result = match key_name.to_lowercase().as_str() {
-        "enter" | "return" => enigo.key(Key::Return, Direction::Click),
-        "tab" => enigo.key(Key::Tab, Direction::Click),
-        "space" => enigo.key(Key::Space, Direction::Click),
-        "backspace" => enigo.key(Key::Backspace, Direction::Click),
-        "windows" | "super" | "meta" => enigo.key(Key::Meta, Direction::Click),
-        "escape" | "esc" => enigo.key(Key::Escape, Direction::Click),
-        _ => return "Error: Unknown key.".to_string(),
-    };
-    match result { Ok(_) => format!("Pressed: [{}]", key_name), Err(e) => format!("Error: {}", e) }
-}
\ No newline at end of file
+    for press in &presses {
+        for _ in 0..press.times {
+            if let Err(e) = chord(&mut enigo, press) { return format!("Error: {}", e); }
+            thread::sleep(Duration::from_millis(30));
+        }
+    }
+    format!("Pressed: [{}]", key_name.trim())
+}
+
+// 修飾キーを押したままキーを押して離す。途中で失敗しても修飾キーは必ず離す
+fn chord(enigo: &mut Enigo, press: &keys::Press) -> Result<(), String> {
+    let key = enigo_key(press.key)?;
+    let mut held = Vec::new();
+    let mut result = Ok(());
+    for m in &press.modifiers {
+        let m = modifier_key(*m);
+        if let Err(e) = enigo.key(m, Direction::Press) {
+            result = Err(e.to_string());
+            break;
+        }
+        held.push(m);
+    }
+    if result.is_ok() {
+        result = enigo.key(key, Direction::Click).map_err(|e| e.to_string());
+    }
+    for m in held.into_iter().rev() {
+        let _ = enigo.key(m, Direction::Release);
+    }
+    result
+}
+
+fn modifier_key(m: keys::Modifier) -> Key {
+    match m {
+        keys::Modifier::Ctrl => Key::Control,
+        keys::Modifier::Alt => Key::Alt,
+        keys::Modifier::Shift => Key::Shift,
+        keys::Modifier::Meta => Key::Meta,
+    }
+}
+
+fn enigo_key(key: keys::Key) -> Result<Key, String> {
+    use keys::Key as K;
+    let key = match key {
+        K::Char(c) => Key::Unicode(c),
+        K::F(n) => match n {
+            1 => Key::F1, 2 => Key::F2, 3 => Key::F3, 4 => Key::F4, 5 => Key::F5, 6 => Key::F6,
+            7 => Key::F7, 8 => Key::F8, 9 => Key::F9, 10 => Key::F10, 11 => Key::F11, 12 => Key::F12,
+            13 => Key::F13, 14 => Key::F14, 15 => Key::F15, 16 => Key::F16, 17 => Key::F17,
+            18 => Key::F18, 19 => Key::F19, 20 => Key::F20,
+            #[cfg(not(target_os = "macos"))]
+            21 => Key::F21,
+            #[cfg(not(target_os = "macos"))]
+            22 => Key::F22,
+            #[cfg(not(target_os = "macos"))]
+            23 => Key::F23,
+            #[cfg(not(target_os = "macos"))]
+            24 => Key::F24,
+            _ => return Err(format!("F{} is not supported on this OS", n)),
+        },
+        K::Enter => Key::Return,
+        K::Tab => Key::Tab,
+        K::Space => Key::Space,
+        K::Backspace => Key::Backspace,
+        K::Delete => Key::Delete,
+        #[cfg(not(target_os = "macos"))]
+        K::Insert => Key::Insert,
+        #[cfg(target_os = "macos")]
+        K::Insert => return Err("Insert is not supported on this OS".to_string()),
+        K::Escape => Key::Escape,
+        K::Up => Key::UpArrow,
+        K::Down => Key::DownArrow,
+        K::Left => Key::LeftArrow,
+        K::Right => Key::RightArrow,
+        K::Home => Key::Home,
+        K::End => Key::End,
+        K::PageUp => Key::PageUp,
+        K::PageDown => Key::PageDown,
+        K::CapsLock => Key::CapsLock,
+        K::PrintScreen => Key::PrintScr,
+        K::Modifier(m) => modifier_key(m),
+    };
+    Ok(key)
+}
+
+// CLICK: <x>,<y> [right|double]（画面の絶対座標。マクロの再生用）
+pub fn click(arg: &str) -> String {
+    let mut words = arg.split_whitespace();
+    let Some((x, y)) = words.next().and_then(|p| p.split_once(',')) else {
+        return "Error: CLICK needs <x>,<y>.".to_string();
+    };
+    let (Ok(x), Ok(y)) = (x.trim().parse::<i32>(), y.trim().parse::<i32>()) else {
+        return "Error: CLICK needs numeric <x>,<y>.".to_string();
+    };
+    let mode = words.next().unwrap_or("left").to_lowercase();
+    let mut enigo = match Enigo::new(&Settings::default()) {
+        Ok(e) => e,
+        Err(e) => return format!("Error: {}", e),
+    };
+    let button = if mode == "right" { Button::Right } else { Button::Left };
+    let times = if mode == "double" { 2 } else { 1 };
+    let result = enigo.move_mouse(x, y, Coordinate::Abs).and_then(|_| {
+        for _ in 0..times {
+            enigo.button(button, Direction::Click)?;
+            thread::sleep(Duration::from_millis(60));
+        }
+        Ok(())
+    });
+    match result { Ok(_) => format!("Clicked: ({}, {}) {}", x, y, mode), Err(e) => format!("Error: {}", e) }
+}
+
+// メディアキー (MEDIA: のキー送信側)。action: toggle / next / prev / mute / volume
+// 音量は絶対値を指定できないので、いったん 0 まで下げてから 1 回 2% ずつ上げる
+pub fn media_key(action: &str, volume: Option<u32>) -> String {
+    let mut enigo = match Enigo::new(&Settings::default()) {
+        Ok(e) => e,
+        Err(e) => return format!("Error: {}", e),
+    };
+    let click = |enigo: &mut Enigo, key: Key, times: u32| -> Result<(), String> {
+        for _ in 0..times {
+            enigo.key(key, Direction::Click).map_err(|e| e.to_string())?;
+            thread::sleep(Duration::from_millis(10));
+        }
+        Ok(())
+    };
+    let result = match action {
+        "toggle" => click(&mut enigo, Key::MediaPlayPause, 1),
+        "next" => click(&mut enigo, Key::MediaNextTrack, 1),
+        "prev" => click(&mut enigo, Key::MediaPrevTrack, 1),
+        "mute" => click(&mut enigo, Key::VolumeMute, 1),
+        "volume" => {
+            let target = volume.unwrap_or(50).min(100);
+            click(&mut enigo, Key::VolumeDown, 50).and_then(|_| click(&mut enigo, Key::VolumeUp, target / 2))
+        }
+        _ => return format!("Error: Unknown media action '{}'.", action),
+    };
+    match result {
+        Ok(_) if action == "volume" => format!("Success: Volume set to {}%.", volume.unwrap_or(50).min(100)),
+        Ok(_) => format!("Success: Media {}.", action),
+        Err(e) => format!("Error: {}", e),
+    }
+}
+
+// --- ウィンドウ / プロセス管理 (KILL / FOCUS / MINIMIZE) ---
+
+// 落とすと OS ごと不安定になるもの + Axis 自身
+const PROTECTED_PROCESSES: &[&str] = &[
+    "system", "csrss", "winlogon", "wininit", "services", "lsass", "smss", "dwm", "explorer", "axis-os",
+];
+
+// PowerShell の単引用符文字列用エスケープ
+fn ps_quote(s: &str) -> String {
+    s.trim().replace('\'', "''")
+}
+
+fn is_protected(name: &str) -> bool {
+    PROTECTED_PROCESSES.contains(&name.trim_end_matches(".exe").to_lowercase().as_str())
+}
+
+// KILL: <pid> の前に。PID の先と、taskkill /T で一緒に落ちる子孫に守るものが無いこと
+fn check_kill_pid(pid: u32) -> Result<(), String> {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes();
+    let root = sysinfo::Pid::from_u32(pid);
+    let Some(process) = sys.process(root) else {
+        return Err(format!("no process with PID {}", pid));
+    };
+    let own = sysinfo::Pid::from_u32(std::process::id());
+    let mut tree = vec![root];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        let children: Vec<sysinfo::Pid> = sys
+            .processes()
+            .iter()
+            .filter(|(child, p)| p.parent() == Some(parent) && !tree.contains(child))
+            .map(|(child, _)| *child)
+            .collect();
+        tree.extend(children);
+        i += 1;
+    }
+    for id in tree {
+        let name = sys.process(id).map(|p| p.name().to_string()).unwrap_or_default();
+        if id == own || is_protected(&name) {
+            return Err(format!(
+                "PID {} ({}) is or runs a protected process ({}).",
+                pid,
+                process.name(),
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn kill_process(target: &str) -> String {
+    let target = target.trim();
+    if target.is_empty() {
+        return "Error: KILL needs a process name or PID.".to_string();
+    }
+
+    let mut cmd = Command::new("taskkill");
+    if let Ok(pid) = target.parse::<u32>() {
+        // PID でも名前を引いて同じ規則で（/T で落とす子プロセスも含めて）
+        if let Err(e) = check_kill_pid(pid) {
+            return format!("Refused: {}", e);
+        }
+        cmd.args(["/PID", &pid.to_string(), "/T", "/F"]);
+    } else {
+        if is_protected(target) {
+            return format!("Refused: '{}' is a protected system process.", target);
+        }
+        let image = format!("{}.exe", target.trim_end_matches(".exe"));
+        cmd.args(["/IM", &image, "/T", "/F"]);
+    }
+
+    match cmd.creation_flags(0x08000000).output() {
+        Ok(o) if o.status.success() => format!("Success: Terminated '{}'.", target),
+        Ok(o) => format!(
+            "Failed: Could not terminate '{}'. {}",
+            target,
+            String::from_utf8_lossy(&o.stderr).trim()
+        ),
+        Err(e) => format!("Error executing taskkill: {}", e),
+    }
+}
+
+// タイトル or プロセス名でウィンドウを探し、ShowWindow の nCmdShow を適用
+// まず win32.rs で直接。使えなかった時だけ PowerShell 版
+fn apply_window_state(target: &str, show_cmd: i32, bring_front: bool) -> Result<String, String> {
+    let t = ps_quote(target);
+    if t.is_empty() {
+        return Err("window title is empty".to_string());
+    }
+    match crate::win32::show_window(target, show_cmd, bring_front) {
+        Ok(Some(title)) => return Ok(title),
+        Ok(None) => return Err(format!("window '{}' not found", target.trim())),
+        Err(e) => info!("🪟 [Window] native lookup failed, using PowerShell: {}", e),
+    }
+    let ps_script = format!(
+        r#"
+      Add-Type @"
+        using System;
+        using System.Runtime.InteropServices;
+        public class AxisWin {{
+          [DllImport("user32.dll")] public static extern bool ShowWindow(IntPtr hWnd, int nCmdShow);
+          [DllImport("user32.dll")] public static extern bool SetForegroundWindow(IntPtr hWnd);
+        }}
+"@
+      $p = Get-Process | Where-Object {{ $_.MainWindowHandle -ne 0 -and ($_.MainWindowTitle -like '*{t}*' -or $_.ProcessName -like '*{t}*') }} | Select-Object -First 1
+      if (-not $p) {{ Write-Output 'NOT_FOUND'; exit }}
+      [AxisWin]::ShowWindow($p.MainWindowHandle, {show_cmd}) > $null
+      if (${front}) {{ [AxisWin]::SetForegroundWindow($p.MainWindowHandle) > $null }}
+      Write-Output $p.MainWindowTitle
+    "#,
+        t = t,
+        show_cmd = show_cmd,
+        front = if bring_front { "true" } else { "false" }
+    );
+
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-WindowStyle", "Hidden", "-ExecutionPolicy", "Bypass", "-Command", &ps_script])
+        .creation_flags(0x08000000)
+        .output()
+        .map_err(|e| format!("Error executing shell: {}", e))?;
+
+    let title = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if title == "NOT_FOUND" || title.is_empty() {
+        Err(format!("window '{}' not found", target.trim()))
+    } else {
+        Ok(title)
+    }
+}
+
+pub fn focus_window(target: &str) -> String {
+    // 9 = SW_RESTORE（最小化されていても戻す）
+    match apply_window_state(target, 9, true) {
+        Ok(title) => format!("Success: Focused '{}'.", title),
+        Err(e) => format!("Failed: {}", e),
+    }
+}
+
+pub fn minimize_window(target: &str) -> String {
+    // 6 = SW_MINIMIZE
+    match apply_window_state(target, 6, false) {
+        Ok(title) => format!("Success: Minimized '{}'.", title),
+        Err(e) => format!("Failed: {}", e),
+    }
+}
+
+// --- ファイルを開く / エクスプローラーで表示（SAVE の後のフォローアップ） ---
+
+// SAVE の保存先（デスクトップ）
+pub fn desktop_dir() -> PathBuf {
+    Path::new(&env::var("USERPROFILE").unwrap_or(".".to_string())).join("Desktop")
+}
+
+// フォルダの無いファイル名は SAVE と同じくデスクトップのものとみなす
+pub fn resolve_user_path(target: &str) -> PathBuf {
+    let p = Path::new(target.trim().trim_matches('"'));
+    if p.is_absolute() {
+        p.to_path_buf()
+    } else {
+        desktop_dir().join(p)
+    }
+}
+
+// 既定のアプリで開くと中身が実行されるもの（SAVE で書いたスクリプトを OPEN_FILE で動かさせない）
+// 実行したい時は RUN:（許可リスト / 確認を通る）
+const EXECUTABLE_EXTS: &[&str] = &[
+    "exe", "com", "bat", "cmd", "ps1", "psm1", "vbs", "vbe", "js", "jse", "wsf", "wsh", "lnk", "url",
+    "msi", "msp", "scr", "hta", "cpl", "pif", "reg", "jar", "appref-ms", "application", "scf", "inf",
+];
+
+pub fn is_executable_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXECUTABLE_EXTS.contains(&e.to_lowercase().as_str()))
+}
+
+pub fn open_file(target: &str) -> String {
+    let path = resolve_user_path(target);
+    if !path.exists() {
+        return format!("Failed: '{}' not found.", path.display());
+    }
+    if is_executable_path(&path) {
+        return format!(
+            "Refused: '{}' is an executable or script. OPEN_FILE only opens documents; use RUN: to execute commands.",
+            path.display()
+        );
+    }
+    // 既定のアプリで開く（start の第 1 引数はウィンドウタイトル）
+    Command::new("cmd")
+        .args(["/C", "start", ""])
+        .arg(&path)
+        .creation_flags(0x08000000)
+        .spawn()
+        .map(|_| format!("Success: Opened '{}'.", path.display()))
+        .unwrap_or_else(|e| format!("Error opening '{}': {}", path.display(), e))
+}
+
+// 既定のブラウザで URL を開く（cmd の start だと & で切れるので url.dll 経由）
+pub fn open_url(url: &str) -> String {
+    let url = url.trim();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return format!("Failed: '{}' is not an http(s) URL.", url);
+    }
+    Command::new("rundll32")
+        .arg("url.dll,FileProtocolHandler")
+        .arg(url)
+        .creation_flags(0x08000000)
+        .spawn()
+        .map(|_| format!("Success: Opened {}.", url))
+        .unwrap_or_else(|e| format!("Error opening {}: {}", url, e))
+}
+
+// --- コマンド実行 (RUN:) ---
+// cmd /C で実行して stdout / stderr を取る。timeout で止め、出力は max_chars まで
+pub struct RunOutput {
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+}
+
+impl RunOutput {
+    // system_context 用
+    pub fn summary(&self) -> String {
+        let status = match (self.timed_out, self.exit_code) {
+            (true, _) => "timed out".to_string(),
+            (false, Some(code)) => format!("exit {}", code),
+            (false, None) => "killed".to_string(),
+        };
+        let mut out = format!("$ {} ({})\n", self.command, status);
+        if !self.stdout.trim().is_empty() {
+            out.push_str(&format!("[stdout]\n{}\n", self.stdout.trim_end()));
+        }
+        if !self.stderr.trim().is_empty() {
+            out.push_str(&format!("[stderr]\n{}\n", self.stderr.trim_end()));
+        }
+        out
+    }
+}
+
+// パイプから読みながら頭と尻尾だけ残す（全部を溜めてから切らない）
+struct Captured {
+    head: Vec<u8>,
+    tail: Vec<u8>,
+    total: usize,
+}
+
+fn read_capped(mut pipe: impl std::io::Read, max_bytes: usize) -> Captured {
+    let (head_cap, tail_cap) = (max_bytes / 4, max_bytes - max_bytes / 4);
+    let mut out = Captured { head: Vec::new(), tail: Vec::new(), total: 0 };
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = match pipe.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        out.total += n;
+        let to_head = n.min(head_cap - out.head.len());
+        out.head.extend_from_slice(&buf[..to_head]);
+        out.tail.extend_from_slice(&buf[to_head..n]);
+        if out.tail.len() > tail_cap * 2 {
+            out.tail.drain(..out.tail.len() - tail_cap);
+        }
+    }
+    if out.tail.len() > tail_cap {
+        out.tail.drain(..out.tail.len() - tail_cap);
+    }
+    out
+}
+
+// UTF-8 でなければ cmd の既定 (CP932) とみなす
+fn decode(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => encoding_rs::SHIFT_JIS.decode(bytes).0.into_owned(),
+    }
+}
+
+// 長い出力は頭と尻尾を残す（ビルドのエラーは最後に出ることが多い）
+fn cap_output(captured: &Captured, max_chars: usize) -> String {
+    let kept = captured.head.len() + captured.tail.len();
+    if captured.total > kept {
+        return format!(
+            "{}\n... ({} bytes omitted) ...\n{}",
+            decode(&captured.head),
+            captured.total - kept,
+            String::from_utf8_lossy(&captured.tail).trim_start_matches('\u{FFFD}')
+        );
+    }
+    let text = decode(&[captured.head.as_slice(), captured.tail.as_slice()].concat());
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= max_chars {
+        return text;
+    }
+    let head: String = chars[..max_chars / 4].iter().collect();
+    let tail: String = chars[chars.len() - max_chars * 3 / 4..].iter().collect();
+    format!("{}\n... ({} chars omitted) ...\n{}", head, chars.len() - max_chars, tail)
+}
+
+// cancel: バックグラウンドタスク (tasks.rs) から止められるように
+pub fn run_captured(command: &str, cwd: Option<&Path>, timeout: Duration, max_chars: usize, cancel: Option<&AtomicBool>) -> Result<RunOutput, String> {
+    use std::process::Stdio;
+
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .creation_flags(0x08000000);
+    if let Some(dir) = cwd {
+        cmd.current_dir(dir);
+    }
+    let mut child = cmd.spawn().map_err(|e| format!("failed to start '{}': {}", command, e))?;
+
+    // パイプが詰まらないよう別スレッドで読み続ける
+    // 1 文字は UTF-8 で 4 バイトまで
+    let out_pipe = child.stdout.take().ok_or("no stdout")?;
+    let err_pipe = child.stderr.take().ok_or("no stderr")?;
+    let out_reader = thread::spawn(move || read_capped(out_pipe, max_chars * 4));
+    let err_reader = thread::spawn(move || read_capped(err_pipe, max_chars * 2));
+
+    let started = std::time::Instant::now();
+    let mut timed_out = false;
+    let status = loop {
+        let cancelled = cancel.is_some_and(|c| c.load(std::sync::atomic::Ordering::Relaxed));
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break Some(status),
+            None if cancelled || started.elapsed() >= timeout => {
+                timed_out = !cancelled;
+                // cmd の子プロセスごと落とす
+                let _ = Command::new("taskkill")
+                    .args(["/F", "/T", "/PID", &child.id().to_string()])
+                    .creation_flags(0x08000000)
+                    .output();
+                let _ = child.kill();
+                break child.wait().ok();
+            }
+            None => thread::sleep(Duration::from_millis(100)),
+        }
+    };
+
+    let empty = || Captured { head: Vec::new(), tail: Vec::new(), total: 0 };
+    let stdout = out_reader.join().unwrap_or_else(|_| empty());
+    let stderr = err_reader.join().unwrap_or_else(|_| empty());
+    Ok(RunOutput {
+        command: command.to_string(),
+        exit_code: if timed_out { None } else { status.and_then(|s| s.code()) },
+        stdout: cap_output(&stdout, max_chars),
+        stderr: cap_output(&stderr, max_chars / 2),
+        timed_out,
+    })
+}
+
+// "@ <folder>" の解決（絶対パス / project_folders のリポジトリ名 / デスクトップ基準の相対パス）
+pub fn resolve_run_cwd(dir: &str) -> PathBuf {
+    let p = Path::new(dir.trim());
+    if p.is_absolute() {
+        p.to_path_buf()
+    } else {
+        crate::connectors::git::resolve_repo(dir).unwrap_or_else(|_| resolve_user_path(dir))
+    }
+}
+
+// 確認なしで RUN してよいフォルダか: project_folders の下で、SAVE の書き込み先（デスクトップ）の中ではないこと
+// （モデルが SAVE したファイルの入ったフォルダで npm test などを勝手に動かさせない）
+pub fn run_cwd_trusted(dir: &Path) -> bool {
+    let Ok(dir) = dir.canonicalize() else {
+        return false;
+    };
+    if desktop_dir().canonicalize().is_ok_and(|desktop| dir.starts_with(&desktop)) {
+        return false;
+    }
+    crate::settings::current()
+        .project_folders
+        .iter()
+        .filter_map(|f| resolve_user_path(f).canonicalize().ok())
+        .any(|root| dir.starts_with(root))
+}
+
+// RUN: <command> [@ <folder or repo name>]（policy から。確認の要否は policy::run_allowed）
+pub fn run_shell(command: &str, cwd: Option<&str>) -> String {
+    run_shell_with(command, cwd, None)
+}
+
+pub fn run_shell_with(command: &str, cwd: Option<&str>, cancel: Option<&AtomicBool>) -> String {
+    let command = command.trim();
+    if command.is_empty() {
+        return "Error: RUN needs a command.".to_string();
+    }
+    let cwd = cwd.map(str::trim).filter(|d| !d.is_empty()).map(resolve_run_cwd);
+    if let Some(dir) = &cwd {
+        if !dir.is_dir() {
+            return format!("Error: folder '{}' not found.", dir.display());
+        }
+    }
+    let cfg = crate::settings::current();
+    info!(input = %command, "🖥️ [Run] (cwd: {:?})", cwd);
+    match run_captured(command, cwd.as_deref(), Duration::from_secs(cfg.run_timeout_secs.max(1)), cfg.run_max_output_chars, cancel) {
+        Ok(out) => out.summary(),
+        Err(e) => format!("Error: {}", e),
+    }
+}
+
+pub fn reveal_in_explorer(target: &str) -> String {
+    let path = resolve_user_path(target);
+    if !path.exists() {
+        return format!("Failed: '{}' not found.", path.display());
+    }
+    // /select,"<path>" は引数の引用符をそのまま渡さないと効かない
+    Command::new("explorer")
+        .raw_arg(format!("/select,\"{}\"", path.display()))
+        .spawn()
+        .map(|_| format!("Success: Revealed '{}' in Explorer.", path.display()))
+        .unwrap_or_else(|e| format!("Error revealing '{}': {}", path.display(), e))
+}
@@ -2,6 +2,8 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::Manager; // パス取得に必須
 use serde::{Serialize, Deserialize};
+use crate::critic::CriticReport;
+use crate::ensemble::EnsembleCandidate;
 
 // --- 構造体定義 ---
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -13,6 +15,73 @@ pub struct AxisToken {
     pub tags: Vec<String>,
 }
 
+// 👍/👎 (rate_response)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResponseFeedback {
+    pub rating: i64, // +1 / -1
+    #[serde(default)]
+    pub comment: Option<String>,
+    pub rated_at: i64,
+}
+
+// 応答のメタデータ（どのモデルが / なぜ / どう答えたか）
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ResponseMeta {
+    pub target: String, // 実際に答えたエイリアス (gpt / gemini / grok / llama / local / ensemble)
+    pub model: String,
+    #[serde(default)]
+    pub task_type: String,
+    #[serde(default)]
+    pub strategy: String, // general / fallback / offline / pinned ...
+    #[serde(default)]
+    pub reason: String,
+    // set_session_provider で固定されている時のプロバイダ
+    #[serde(default)]
+    pub session_override: Option<String>,
+    #[serde(default)]
+    pub failover: bool, // タイムアウトでローカル LLM に切り替えた
+    #[serde(default)]
+    pub latency_ms: i64,
+    // アンサンブル: 各モデルの候補と、どれを採ったか (provider / "merged")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<EnsembleCandidate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ensemble_choice: Option<String>,
+    // 検証フェーズの結果（コード / 数学）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub critic: Option<CriticReport>,
+    // set_persona で選ばれていたペルソナ
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persona: Option<String>,
+    // 実行したアクション（SAVE は中身を省いてファイル名だけ）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<String>,
+    // SEARCH で参照した出典
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<Source>,
+    // SAVE で書き出したファイル（UI から開く / エクスプローラーで表示）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<SavedFile>,
+    // 外の文面 (検索結果 / ページ / メール ...) を読んで答えた。履歴に入れる時は囲む (untrusted.rs)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub untrusted: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Source {
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SavedFile {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    #[serde(default)]
+    pub preview: String, // 先頭の数行
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct InteractionLog {
     pub id: String,
@@ -21,6 +90,13 @@ pub struct InteractionLog {
     pub user_tokens: Vec<AxisToken>,
     pub ai_response: String,
     pub provider_used: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback: Option<ResponseFeedback>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ResponseMeta>,
+    // regenerate_response で作った答え直し: 元のログの id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_of: Option<String>,
 }
 
 // --- ヘルパー: パスの一元管理 ---
@@ -46,7 +122,8 @@ pub fn get_all_logs(app: &tauri::AppHandle) -> Result<Vec<InteractionLog>, Strin
         return Ok(Vec::new());
     }
 
-    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    // 暗号化されていれば復号して読む (crypto.rs)
+    let content = crate::crypto::read_to_string(path)?;
     // JSONパースに失敗したら空配列を返す（クラッシュ防止）
     let logs: Vec<InteractionLog> = serde_json::from_str(&content).unwrap_or_default();
     
@@ -57,12 +134,12 @@ pub fn get_all_logs(app: &tauri::AppHandle) -> Result<Vec<InteractionLog>, Strin
 pub fn save_log(app: &tauri::AppHandle, log: &InteractionLog) -> Result<(), String> {
     let path = get_history_path(app)?;
     
-    // 既存のログを読み込んで追加
-    let mut logs = get_all_logs(app).unwrap_or_default();
+    // 既存のログを読み込んで追加（復号に失敗した時に空で上書きしないよう、エラーは返す）
+    let mut logs = get_all_logs(app)?;
     logs.push(log.clone());
     
     let json = serde_json::to_string_pretty(&logs).map_err(|e| e.to_string())?;
-    fs::write(path, json).map_err(|e| e.to_string())?;
+    crate::crypto::write(path, json)?;
     Ok(())
 }
 
@@ -71,7 +148,7 @@ pub fn delete_session_log(app: &tauri::AppHandle, target_session_id: &str) -> Re
     let path = get_history_path(app)?;
     
     // 既存のログを読み込む
-    let logs = get_all_logs(app).unwrap_or_default();
+    let logs = get_all_logs(app)?;
     
     // 対象ID以外を残すフィルタリング
     let new_logs: Vec<InteractionLog> = logs.into_iter()
@@ -79,7 +156,54 @@ pub fn delete_session_log(app: &tauri::AppHandle, target_session_id: &str) -> Re
         .collect();
         
     let json = serde_json::to_string_pretty(&new_logs).map_err(|e| e.to_string())?;
-    fs::write(path, json).map_err(|e| e.to_string())?;
+    crate::crypto::write(path, json)?;
     
     Ok(())
-}
\ No newline at end of file
+}
+
+// 4. 評価の記録: 前の評価と更新後のログを返す（feedback=None で取り消し）
+pub fn set_feedback(
+    app: &tauri::AppHandle,
+    log_id: &str,
+    feedback: Option<ResponseFeedback>,
+) -> Result<(Option<ResponseFeedback>, InteractionLog), String> {
+    let path = get_history_path(app)?;
+    let mut logs = get_all_logs(app)?;
+    let log = logs
+        .iter_mut()
+        .find(|l| l.id == log_id)
+        .ok_or_else(|| format!("log not found: {}", log_id))?;
+    let previous = std::mem::replace(&mut log.feedback, feedback);
+    let updated = log.clone();
+
+    let json = serde_json::to_string_pretty(&logs).map_err(|e| e.to_string())?;
+    crate::crypto::write(path, json)?;
+    Ok((previous, updated))
+}
+
+// 5. 履歴の取り込み (Import): 同じ id のログは追加しない。追加件数を返す
+pub fn merge_logs(app: &tauri::AppHandle, incoming: Vec<InteractionLog>) -> Result<usize, String> {
+    let path = get_history_path(app)?;
+    let mut logs = get_all_logs(app)?;
+    let known: std::collections::HashSet<String> = logs.iter().map(|l| l.id.clone()).collect();
+
+    let before = logs.len();
+    logs.extend(incoming.into_iter().filter(|l| !known.contains(&l.id)));
+    let added = logs.len() - before;
+    if added == 0 {
+        return Ok(0);
+    }
+    logs.sort_by_key(|l| l.timestamp);
+
+    let json = serde_json::to_string_pretty(&logs).map_err(|e| e.to_string())?;
+    crate::crypto::write(path, json)?;
+    Ok(added)
+}
+
+// 6. 履歴の書き換え (purge.rs): 残す分だけを丸ごと書き直す
+pub fn replace_all_logs(app: &tauri::AppHandle, logs: &[InteractionLog]) -> Result<(), String> {
+    let path = get_history_path(app)?;
+    let json = serde_json::to_string_pretty(logs).map_err(|e| e.to_string())?;
+    crate::crypto::write(path, json)?;
+    Ok(())
+}
@@ -1,5 +1,9 @@
-use sysinfo::{System, RefreshKind, CpuRefreshKind, MemoryRefreshKind};
+use sysinfo::{System, RefreshKind, CpuRefreshKind, MemoryRefreshKind, ProcessRefreshKind, Components, Disks};
+use starship_battery::units::ratio::percent;
+use starship_battery::units::thermodynamic_temperature::degree_celsius;
+use starship_battery::units::time::second;
 use serde::Serialize;
+use tracing::info;
 use std::thread;
 use std::time::Duration;
 
@@ -10,6 +14,94 @@ pub struct SystemStats {
     pub memory_total: u64,
     pub battery_level: u8,
     pub is_charging: bool,
+    // ★追加: 実バッテリー / 温度
+    pub has_battery: bool,
+    pub battery_state: String, // charging / discharging / full / empty / unknown / ac
+    pub battery_time_remaining_secs: Option<u64>, // 放電中=残り時間, 充電中=満充電まで
+    pub battery_health: Option<u8>,
+    pub cpu_temp_c: Option<f32>,    // CPU 系センサーの最高値
+    pub max_temp_c: Option<f32>,    // 全センサーの最高値
+    pub thermal_critical: bool,     // いずれかのセンサーが critical 以上
+    // ★追加: ドライブ毎の容量
+    pub disks: Vec<DiskInfo>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DiskInfo {
+    pub mount_point: String, // "C:\\" など
+    pub name: String,
+    pub file_system: String,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub is_removable: bool,
+}
+
+struct BatteryInfo {
+    level: u8,
+    state: String,
+    charging: bool,
+    time_remaining_secs: Option<u64>,
+    health: Option<u8>,
+    temp_c: Option<f32>,
+}
+
+// 最初のバッテリーを読む（デスクトップ等で無ければ None）
+fn read_battery() -> Option<BatteryInfo> {
+    let manager = starship_battery::Manager::new().ok()?;
+    let battery = manager.batteries().ok()?.flatten().next()?;
+
+    let state = battery.state();
+    let (state_str, charging) = match state {
+        starship_battery::State::Charging => ("charging", true),
+        starship_battery::State::Discharging => ("discharging", false),
+        starship_battery::State::Full => ("full", true),
+        starship_battery::State::Empty => ("empty", false),
+        _ => ("unknown", false),
+    };
+    let time_remaining_secs = match state {
+        starship_battery::State::Charging => battery.time_to_full(),
+        starship_battery::State::Discharging => battery.time_to_empty(),
+        _ => None,
+    }
+    .map(|t| t.get::<second>() as u64);
+
+    Some(BatteryInfo {
+        level: battery.state_of_charge().get::<percent>().round().clamp(0.0, 100.0) as u8,
+        state: state_str.to_string(),
+        charging,
+        time_remaining_secs,
+        health: Some(battery.state_of_health().get::<percent>().round().clamp(0.0, 100.0) as u8),
+        temp_c: battery.temperature().map(|t| t.get::<degree_celsius>()),
+    })
+}
+
+struct ThermalInfo {
+    cpu_c: Option<f32>,
+    max_c: Option<f32>,
+    critical: bool,
+}
+
+// 温度センサー（Windows では WMI 経由のため、権限次第で空になる）
+fn read_thermals() -> ThermalInfo {
+    let components = Components::new_with_refreshed_list();
+    let mut info = ThermalInfo { cpu_c: None, max_c: None, critical: false };
+
+    for c in components.list() {
+        let t = c.temperature();
+        if !t.is_finite() || t <= 0.0 {
+            continue;
+        }
+        info.max_c = Some(info.max_c.map_or(t, |m| m.max(t)));
+
+        let label = c.label().to_lowercase();
+        if label.contains("cpu") || label.contains("core") || label.contains("package") || label.contains("tctl") {
+            info.cpu_c = Some(info.cpu_c.map_or(t, |m| m.max(t)));
+        }
+        if c.critical().is_some_and(|crit| crit > 0.0 && t >= crit) {
+            info.critical = true;
+        }
+    }
+    info
 }
 
 pub fn get_system_stats() -> SystemStats {
@@ -36,23 +128,72 @@ pub fn get_system_stats() -> SystemStats {
     let mem_used = sys.used_memory();
     let mem_total = sys.total_memory();
 
+    let battery = read_battery();
+    let thermals = read_thermals();
+    let battery_temp = battery.as_ref().and_then(|b| b.temp_c);
+
+    let (battery_level, is_charging) = match &battery {
+        Some(b) => (b.level, b.charging),
+        None => (100, true), // バッテリー無し = AC 電源（従来どおり）
+    };
+
     SystemStats {
         cpu_usage: cpu_avg as u8,
         memory_used: mem_used,
         memory_total: mem_total,
-        battery_level: 100, // デスクトップ想定
-        is_charging: true,
+        battery_level,
+        is_charging,
+        has_battery: battery.is_some(),
+        battery_state: battery.as_ref().map(|b| b.state.clone()).unwrap_or_else(|| "ac".to_string()),
+        battery_time_remaining_secs: battery.as_ref().and_then(|b| b.time_remaining_secs),
+        battery_health: battery.as_ref().and_then(|b| b.health),
+        cpu_temp_c: thermals.cpu_c,
+        max_temp_c: match (thermals.max_c, battery_temp) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        },
+        thermal_critical: thermals.critical,
+        disks: read_disks(),
     }
 }
 
+fn read_disks() -> Vec<DiskInfo> {
+    let disks = Disks::new_with_refreshed_list();
+    let mut list: Vec<DiskInfo> = disks
+        .list()
+        .iter()
+        .filter(|d| d.total_space() > 0)
+        .map(|d| DiskInfo {
+            mount_point: d.mount_point().to_string_lossy().to_string(),
+            name: d.name().to_string_lossy().to_string(),
+            file_system: d.file_system().to_string_lossy().to_string(),
+            total_bytes: d.total_space(),
+            free_bytes: d.available_space(),
+            is_removable: d.is_removable(),
+        })
+        .collect();
+    list.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    list
+}
+
 // src-tauri/src/system.rs の既存コードの下に追加
 
+use std::collections::HashMap;
+use std::fs;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::Instant;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-// 起動中のアプリ一覧（ウィンドウタイトル）を取得
+// 起動中のアプリ一覧（ウィンドウタイトル）を取得。win32.rs で直接、使えなければ PowerShell
 pub fn get_running_apps() -> Vec<String> {
+    match crate::win32::top_windows() {
+        Ok(windows) => return windows.into_iter().map(|w| w.title).collect(),
+        Err(e) => info!("🪟 [System] native window list failed, using PowerShell: {}", e),
+    }
     let ps_script = "Get-Process | Where-Object { $_.MainWindowTitle -ne '' } | Select-Object -ExpandProperty MainWindowTitle";
     
     let output = Command::new("powershell")
@@ -70,4 +211,399 @@ pub fn get_running_apps() -> Vec<String> {
         },
         Err(_) => vec![],
     }
-}
\ No newline at end of file
+}
+
+// --- プロセス単位のリソース監視 ---
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f32,    // 全コア合計を 100% とした値
+    pub memory_bytes: u64,
+    pub window_title: Option<String>,
+}
+
+// PID → メインウィンドウタイトル（前面に近いもの）
+fn window_titles_by_pid() -> HashMap<u32, String> {
+    match crate::win32::top_windows() {
+        Ok(windows) => {
+            let mut titles = HashMap::new();
+            for w in windows {
+                titles.entry(w.pid).or_insert(w.title);
+            }
+            return titles;
+        }
+        Err(e) => info!("🪟 [System] native window list failed, using PowerShell: {}", e),
+    }
+    let ps_script = "Get-Process | Where-Object { $_.MainWindowTitle -ne '' } | ForEach-Object { \"$($_.Id)`t$($_.MainWindowTitle)\" }";
+
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", ps_script])
+        .creation_flags(0x08000000)
+        .output();
+
+    match output {
+        Ok(o) => String::from_utf8_lossy(&o.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (pid, title) = line.trim().split_once('\t')?;
+                Some((pid.trim().parse::<u32>().ok()?, title.trim().to_string()))
+            })
+            .collect(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+// 上位 n 件のプロセス (sort_by: "cpu" | "memory")
+pub fn get_top_processes(n: usize, sort_by: &str) -> Result<Vec<ProcessInfo>, String> {
+    let by_cpu = match sort_by.trim().to_lowercase().as_str() {
+        "cpu" => true,
+        "memory" | "mem" | "ram" | "" => false,
+        other => return Err(format!("unknown sort_by '{}': use cpu or memory", other)),
+    };
+
+    let mut sys = System::new_with_specifics(
+        RefreshKind::new()
+            .with_cpu(CpuRefreshKind::new())
+            .with_processes(ProcessRefreshKind::new().with_cpu().with_memory()),
+    );
+    // プロセスの CPU 使用率は 2 回の計測差分で出る
+    thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL.max(Duration::from_millis(200)));
+    sys.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu().with_memory());
+
+    let cpu_count = sys.cpus().len().max(1) as f32;
+    let titles = window_titles_by_pid();
+
+    let mut procs: Vec<ProcessInfo> = sys
+        .processes()
+        .iter()
+        .map(|(pid, p)| ProcessInfo {
+            pid: pid.as_u32(),
+            name: p.name().to_string(),
+            cpu_usage: p.cpu_usage() / cpu_count,
+            memory_bytes: p.memory(),
+            window_title: titles.get(&pid.as_u32()).cloned(),
+        })
+        .collect();
+
+    if by_cpu {
+        procs.sort_by(|a, b| b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap_or(std::cmp::Ordering::Equal));
+    } else {
+        procs.sort_by_key(|p| std::cmp::Reverse(p.memory_bytes));
+    }
+    procs.truncate(n.max(1));
+    Ok(procs)
+}
+
+// LLM 用の表形式
+pub fn format_processes(procs: &[ProcessInfo]) -> String {
+    let mut out = String::new();
+    for (i, p) in procs.iter().enumerate() {
+        out.push_str(&format!(
+            "{}. {} (PID {}) CPU {:.1}% / MEM {:.0} MB{}\n",
+            i + 1,
+            p.name,
+            p.pid,
+            p.cpu_usage,
+            p.memory_bytes as f64 / 1024.0 / 1024.0,
+            p.window_title
+                .as_deref()
+                .map(|t| format!(" [{}]", t))
+                .unwrap_or_default()
+        ));
+    }
+    out
+}
+
+// --- ディスク使用量の分析 ---
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DirUsage {
+    pub path: String,
+    pub size_bytes: u64,
+    pub file_count: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DiskUsageReport {
+    pub root: String,
+    pub total_bytes: u64,       // 走査できた分の合計
+    pub free_bytes: Option<u64>, // root を含むドライブの空き
+    pub largest: Vec<DirUsage>, // 直下のディレクトリ（大きい順）
+    pub loose_files_bytes: u64, // root 直下のファイル合計
+    pub skipped: u64,           // 権限等で読めなかったエントリ数
+    pub truncated: bool,        // エントリ数上限で打ち切った
+}
+
+// 走査の上限（C:\ 全体を舐めても固まらないように）
+const MAX_SCAN_ENTRIES: u64 = 500_000;
+
+struct ScanState {
+    entries: u64,
+    skipped: u64,
+}
+
+// シンボリックリンク / ジャンクションは辿らない
+fn dir_size(path: &Path, state: &mut ScanState) -> (u64, u64) {
+    let Ok(read) = fs::read_dir(path) else {
+        state.skipped += 1;
+        return (0, 0);
+    };
+    let (mut size, mut files) = (0u64, 0u64);
+    for entry in read {
+        if state.entries >= MAX_SCAN_ENTRIES {
+            break;
+        }
+        state.entries += 1;
+        let Ok(entry) = entry else {
+            state.skipped += 1;
+            continue;
+        };
+        let Ok(meta) = fs::symlink_metadata(entry.path()) else {
+            state.skipped += 1;
+            continue;
+        };
+        if meta.is_dir() {
+            let (s, f) = dir_size(&entry.path(), state);
+            size += s;
+            files += f;
+        } else if meta.is_file() {
+            size += meta.len();
+            files += 1;
+        }
+    }
+    (size, files)
+}
+
+// path 直下のディレクトリを大きい順に top 件
+pub fn analyze_disk_usage(path: &str, top: usize) -> Result<DiskUsageReport, String> {
+    let root = PathBuf::from(path.trim());
+    if !root.is_dir() {
+        return Err(format!("'{}' is not a directory", root.display()));
+    }
+
+    let mut state = ScanState { entries: 0, skipped: 0 };
+    let mut largest = Vec::new();
+    let mut loose_files_bytes = 0u64;
+
+    for entry in fs::read_dir(&root).map_err(|e| e.to_string())? {
+        let Ok(entry) = entry else {
+            state.skipped += 1;
+            continue;
+        };
+        let Ok(meta) = fs::symlink_metadata(entry.path()) else {
+            state.skipped += 1;
+            continue;
+        };
+        if meta.is_dir() {
+            let (size_bytes, file_count) = dir_size(&entry.path(), &mut state);
+            largest.push(DirUsage {
+                path: entry.path().to_string_lossy().to_string(),
+                size_bytes,
+                file_count,
+            });
+        } else if meta.is_file() {
+            loose_files_bytes += meta.len();
+        }
+    }
+
+    let total_bytes = largest.iter().map(|d| d.size_bytes).sum::<u64>() + loose_files_bytes;
+    largest.sort_by_key(|d| std::cmp::Reverse(d.size_bytes));
+    largest.truncate(top.max(1));
+
+    // 一番長く一致するマウントポイントが root のドライブ
+    let root_str = root.to_string_lossy().to_lowercase();
+    let free_bytes = read_disks()
+        .into_iter()
+        .filter(|d| root_str.starts_with(&d.mount_point.to_lowercase()))
+        .max_by_key(|d| d.mount_point.len())
+        .map(|d| d.free_bytes);
+
+    Ok(DiskUsageReport {
+        root: root.to_string_lossy().to_string(),
+        total_bytes,
+        free_bytes,
+        largest,
+        loose_files_bytes,
+        skipped: state.skipped,
+        truncated: state.entries >= MAX_SCAN_ENTRIES,
+    })
+}
+
+fn fmt_gb(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / 1024.0 / 1024.0 / 1024.0)
+}
+
+// LLM 用: ドライブ一覧
+pub fn format_disks(disks: &[DiskInfo]) -> String {
+    let mut out = String::new();
+    for d in disks {
+        let used = d.total_bytes.saturating_sub(d.free_bytes);
+        out.push_str(&format!(
+            "- {} ({}) {} used / {} total, {} free ({:.0}% used)\n",
+            d.mount_point,
+            d.file_system,
+            fmt_gb(used),
+            fmt_gb(d.total_bytes),
+            fmt_gb(d.free_bytes),
+            used as f64 * 100.0 / d.total_bytes.max(1) as f64
+        ));
+    }
+    out
+}
+
+// LLM 用: 大きいディレクトリ一覧
+pub fn format_disk_usage(report: &DiskUsageReport) -> String {
+    let mut out = format!("Scanned {} ({} total", report.root, fmt_gb(report.total_bytes));
+    if let Some(free) = report.free_bytes {
+        out.push_str(&format!(", drive free {}", fmt_gb(free)));
+    }
+    out.push_str(")\n");
+    for (i, d) in report.largest.iter().enumerate() {
+        out.push_str(&format!(
+            "{}. {} {} ({} files)\n",
+            i + 1,
+            d.path,
+            fmt_gb(d.size_bytes),
+            d.file_count
+        ));
+    }
+    if report.loose_files_bytes > 0 {
+        out.push_str(&format!("- (files directly in root) {}\n", fmt_gb(report.loose_files_bytes)));
+    }
+    if report.skipped > 0 || report.truncated {
+        out.push_str(&format!(
+            "Note: {} entries unreadable{}\n",
+            report.skipped,
+            if report.truncated { ", scan truncated at entry limit" } else { "" }
+        ));
+    }
+    out
+}
+
+// --- ネットワーク到達性 ---
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EndpointStatus {
+    pub name: String, // Commander のエイリアス (gpt / gemini / grok / llama)
+    pub host: String,
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NetworkStatus {
+    pub online: bool, // どれか 1 つでも届けばオンライン
+    pub checked_at: i64,
+    pub endpoints: Vec<EndpointStatus>,
+}
+
+impl NetworkStatus {
+    // Commander の target が使うエンドポイントに届くか
+    pub fn reachable(&self, target: &str) -> bool {
+        let ok = |name: &str| self.endpoints.iter().any(|e| e.name == name && e.reachable);
+        match target {
+            "ensemble" => ok("gpt") && ok("gemini"),
+            "gpt" | "gemini" | "grok" => ok(target),
+            t if crate::providers::is_custom(t) => ok(t),
+            _ => ok("llama"),
+        }
+    }
+}
+
+const PROVIDER_ENDPOINTS: &[(&str, &str)] = &[
+    ("llama", "integrate.api.nvidia.com"),
+    ("gpt", "api.openai.com"),
+    ("gemini", "generativelanguage.googleapis.com"),
+    ("grok", "api.x.ai"),
+];
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1500);
+// ask_axis 毎に叩かないよう結果を使い回す
+const NETWORK_CACHE_MS: i64 = 30_000;
+
+static NETWORK_CACHE: Mutex<Option<NetworkStatus>> = Mutex::new(None);
+
+// 組み込み + settings.providers のホスト (providers.rs)
+fn provider_endpoints() -> Vec<(String, String)> {
+    let custom = crate::providers::names()
+        .into_iter()
+        .filter_map(|name| crate::providers::host(&name).map(|host| (name, host)));
+    PROVIDER_ENDPOINTS
+        .iter()
+        .map(|(name, host)| (name.to_string(), host.to_string()))
+        .chain(custom)
+        .collect()
+}
+
+// host:443 への TCP 接続時間（DNS 解決失敗 = 到達不可）
+fn probe(host: &str) -> Option<u64> {
+    let addr = (host, 443).to_socket_addrs().ok()?.next()?;
+    let started = Instant::now();
+    TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).ok()?;
+    Some(started.elapsed().as_millis() as u64)
+}
+
+// force=false ならキャッシュが新しい間はそれを返す
+pub fn check_network(force: bool) -> NetworkStatus {
+    let now = chrono::Local::now().timestamp_millis();
+    // ローカル専用モードは測りもしない（全部届かない扱い → run_axis はローカルへ / local_only.rs）
+    if crate::local_only::enabled() {
+        return NetworkStatus {
+            online: false,
+            checked_at: now,
+            endpoints: provider_endpoints()
+                .into_iter()
+                .map(|(name, host)| EndpointStatus {
+                    name,
+                    host,
+                    reachable: false,
+                    latency_ms: None,
+                })
+                .collect(),
+        };
+    }
+    if !force {
+        if let Ok(cache) = NETWORK_CACHE.lock() {
+            if let Some(status) = cache.as_ref().filter(|s| now - s.checked_at < NETWORK_CACHE_MS) {
+                return status.clone();
+            }
+        }
+    }
+
+    // 全エンドポイントを並列に叩く（最悪でも PROBE_TIMEOUT 程度で返る）
+    let endpoints: Vec<EndpointStatus> = thread::scope(|scope| {
+        let handles: Vec<_> = provider_endpoints()
+            .into_iter()
+            .map(|(name, host)| {
+                scope.spawn(move || {
+                    let latency = probe(&host);
+                    (name, host, latency)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|h| h.join().ok())
+            .map(|(name, host, latency)| EndpointStatus {
+                name,
+                host,
+                reachable: latency.is_some(),
+                latency_ms: latency,
+            })
+            .collect()
+    });
+
+    let status = NetworkStatus {
+        online: endpoints.iter().any(|e| e.reachable),
+        checked_at: now,
+        endpoints,
+    };
+    if !status.online {
+        info!("📡 [Network] offline: no provider endpoint reachable");
+    }
+    if let Ok(mut cache) = NETWORK_CACHE.lock() {
+        *cache = Some(status.clone());
+    }
+    status
+}
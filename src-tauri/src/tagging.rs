@@ -0,0 +1,298 @@
+// src-tauri/src/tagging.rs
+//
+// メモリ / セッションのタグと付箋 (Stickies: L 大分類 / M 中分類 / S 小分類)
+// - set_memory_labels: MemoryMeta の tags / stickies を書き換える（None の項目はそのまま、空の Stickies で外す）
+//   id はメモリの id か "log:<InteractionLog.id>"（画面はログ単位で持っているので）
+// - set_session_labels: セッション単位の分は memory.db の session_labels に
+// - 自動タグ付け: run_axis がメモリを保存した後に settings.auto_tagger（既定はローカル。既定で off）が分類とタグを提案
+//   手で付けた分類は上書きしない。タグは足すだけ。既にある分類を渡して表記揺れを抑える
+// - list_labels: 使われているタグ / 分類と件数（絞り込み UI 用）
+// - search_conversations の絞り込み (tags / sticky) は conversations.rs から log_labels / session_labels_map で
+
+use crate::ai;
+use crate::db::AxisDatabase;
+use crate::memory::{self, MemoryMeta, Stickies};
+use crate::prompts;
+use crate::settings;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+const MAX_TAGS: usize = 12;
+const AUTO_TAGS: usize = 5; // 自動で足すのは 1 回でこれだけ
+const PROMPT_CATEGORIES: usize = 30; // 既存の分類はよく使われている順にこれだけ渡す
+const PROMPT_TEXT_CHARS: usize = 2000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SessionLabels {
+    pub session_id: String,
+    pub tags: Vec<String>,
+    pub stickies: Option<Stickies>,
+    pub updated_at: i64,
+}
+
+// (tags, stickies)
+pub type LogLabels = (Vec<String>, Option<Stickies>);
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CategoryCount {
+    pub l: String,
+    pub m: String,
+    pub s: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct LabelSummary {
+    pub tags: Vec<TagCount>,
+    pub categories: Vec<CategoryCount>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct AutoTagReply {
+    l: String,
+    m: String,
+    s: String,
+    tags: Vec<String>,
+}
+
+// 小文字 / 先頭の # を外す / 重複を除く
+pub fn normalize_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').trim().to_lowercase();
+        if !tag.is_empty() && !out.contains(&tag) {
+            out.push(tag);
+        }
+    }
+    out.truncate(MAX_TAGS);
+    out
+}
+
+// 前後の空白を落とし、全部空なら None
+fn clean_stickies(stickies: Stickies) -> Option<Stickies> {
+    let stickies = Stickies {
+        l: stickies.l.trim().to_string(),
+        m: stickies.m.trim().to_string(),
+        s: stickies.s.trim().to_string(),
+    };
+    (!stickies.l.is_empty() || !stickies.m.is_empty() || !stickies.s.is_empty()).then_some(stickies)
+}
+
+// 分類のどれか (L / M / S) が一致するか
+pub fn sticky_matches(stickies: Option<&Stickies>, want: &str) -> bool {
+    stickies.is_some_and(|s| {
+        [&s.l, &s.m, &s.s]
+            .iter()
+            .any(|c| !c.is_empty() && c.to_lowercase() == want.to_lowercase())
+    })
+}
+
+fn resolve_memory_id(app: &AppHandle, id: &str) -> Result<String, String> {
+    if !id.starts_with("log:") {
+        return Ok(id.to_string());
+    }
+    let mut found = None;
+    memory::visit_meta(app, |meta| {
+        if found.is_none() && meta.references.iter().any(|r| r == id) {
+            found = Some(meta.id.clone());
+        }
+    })?;
+    found.ok_or_else(|| format!("no memory for {}", id))
+}
+
+pub fn set_memory_labels(
+    app: &AppHandle,
+    id: &str,
+    tags: Option<Vec<String>>,
+    stickies: Option<Stickies>,
+) -> Result<MemoryMeta, String> {
+    let id = resolve_memory_id(app, id)?;
+    let entry = memory::load_entry(app, &id)?;
+    let mut meta = memory::load_meta(app, &id)?;
+    if let Some(tags) = tags {
+        meta.tags = normalize_tags(tags);
+    }
+    if let Some(stickies) = stickies {
+        meta.stickies = clean_stickies(stickies);
+    }
+    meta.updated_at_ms = Utc::now().timestamp_millis();
+    memory::save_entry_and_meta(app, &entry, &meta)?;
+    Ok(meta)
+}
+
+pub fn session_labels(app: &AppHandle, session_id: &str) -> Result<SessionLabels, String> {
+    Ok(AxisDatabase::open(app)?
+        .session_labels(session_id)
+        .map_err(|e| e.to_string())?
+        .unwrap_or_else(|| SessionLabels {
+            session_id: session_id.to_string(),
+            ..Default::default()
+        }))
+}
+
+pub fn set_session_labels(
+    app: &AppHandle,
+    session_id: &str,
+    tags: Option<Vec<String>>,
+    stickies: Option<Stickies>,
+) -> Result<SessionLabels, String> {
+    let mut labels = session_labels(app, session_id)?;
+    if let Some(tags) = tags {
+        labels.tags = normalize_tags(tags);
+    }
+    if let Some(stickies) = stickies {
+        labels.stickies = clean_stickies(stickies);
+    }
+    labels.updated_at = Utc::now().timestamp_millis();
+    AxisDatabase::open(app)?
+        .set_session_labels(&labels)
+        .map_err(|e| e.to_string())?;
+    Ok(labels)
+}
+
+// session_id → SessionLabels
+pub fn session_labels_map(app: &AppHandle) -> Result<HashMap<String, SessionLabels>, String> {
+    Ok(AxisDatabase::open(app)?
+        .all_session_labels()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|l| (l.session_id.clone(), l))
+        .collect())
+}
+
+// InteractionLog.id → そのやり取りのメモリのタグ / 分類
+pub fn log_labels(app: &AppHandle) -> Result<HashMap<String, LogLabels>, String> {
+    let mut out = HashMap::new();
+    memory::visit_meta(app, |meta| {
+        if meta.tags.is_empty() && meta.stickies.is_none() {
+            return;
+        }
+        for r in &meta.references {
+            if let Some(log_id) = r.strip_prefix("log:") {
+                out.insert(
+                    log_id.to_string(),
+                    (meta.tags.clone(), meta.stickies.clone()),
+                );
+            }
+        }
+    })?;
+    Ok(out)
+}
+
+// メモリとセッションの両方を数える。件数の多い順
+pub fn list_labels(app: &AppHandle) -> Result<LabelSummary, String> {
+    let mut tags: BTreeMap<String, usize> = BTreeMap::new();
+    let mut categories: BTreeMap<(String, String, String), usize> = BTreeMap::new();
+    let mut count = |t: &[String], s: Option<&Stickies>| {
+        for tag in t {
+            *tags.entry(tag.clone()).or_default() += 1;
+        }
+        if let Some(s) = s {
+            *categories
+                .entry((s.l.clone(), s.m.clone(), s.s.clone()))
+                .or_default() += 1;
+        }
+    };
+    memory::visit_meta(app, |meta| count(&meta.tags, meta.stickies.as_ref()))?;
+    for labels in session_labels_map(app)?.values() {
+        count(&labels.tags, labels.stickies.as_ref());
+    }
+
+    let mut tags: Vec<TagCount> = tags
+        .into_iter()
+        .map(|(tag, count)| TagCount { tag, count })
+        .collect();
+    tags.sort_by_key(|t| Reverse(t.count));
+    let mut categories: Vec<CategoryCount> = categories
+        .into_iter()
+        .map(|((l, m, s), count)| CategoryCount { l, m, s, count })
+        .collect();
+    categories.sort_by_key(|c| Reverse(c.count));
+    Ok(LabelSummary { tags, categories })
+}
+
+fn clip(text: &str) -> String {
+    text.chars().take(PROMPT_TEXT_CHARS).collect()
+}
+
+pub async fn auto_tag(app: &AppHandle, id: &str) -> Result<(), String> {
+    let cfg = settings::current();
+    let alias = cfg.auto_tagger.trim().to_lowercase();
+    // ほぼ同じやり取りが既存の分にまとめられた時は、もう分類が付いていれば何もしない
+    if memory::load_meta(app, id)?.stickies.is_some() {
+        return Ok(());
+    }
+    let entry = memory::load_entry(app, id)?;
+    let existing: Vec<String> = list_labels(app)?
+        .categories
+        .into_iter()
+        .take(PROMPT_CATEGORIES)
+        .map(|c| format!("- {} / {} / {}", c.l, c.m, c.s))
+        .collect();
+    let categories = if existing.is_empty() {
+        "(none yet)".to_string()
+    } else {
+        existing.join("\n")
+    };
+    let sys = prompts::render(
+        app,
+        "autotag",
+        &[
+            ("categories", &categories),
+            ("max_tags", &AUTO_TAGS.to_string()),
+        ],
+    );
+    // やり取りは user 側に 1 回だけ（system に同じものを重ねて送らない）
+    let exchange = format!(
+        "[USER]\n{}\n\n[ASSISTANT]\n{}",
+        clip(&entry.input.text),
+        clip(&entry.output.text)
+    );
+    let raw = ai::call_alias(&alias, &cfg.models.for_alias(&alias), &sys, &exchange).await?;
+    let start = raw.find('{').ok_or("tagger returned no JSON")?;
+    let end = raw.rfind('}').ok_or("tagger returned no JSON")?;
+    let reply: AutoTagReply =
+        serde_json::from_str(&raw[start..=end]).map_err(|e| format!("tagger JSON: {}", e))?;
+
+    // 呼んでいる間に手で付けられた分を消さないよう読み直してから
+    let mut meta = memory::load_meta(app, id)?;
+    if meta.stickies.is_none() {
+        meta.stickies = clean_stickies(Stickies {
+            l: reply.l,
+            m: reply.m,
+            s: reply.s,
+        });
+    }
+    meta.tags = normalize_tags(
+        meta.tags
+            .into_iter()
+            .chain(reply.tags.into_iter().take(AUTO_TAGS)),
+    );
+    meta.updated_at_ms = Utc::now().timestamp_millis();
+    memory::save_entry_and_meta(app, &entry, &meta)?;
+    info!(text = ?(&meta.stickies, &meta.tags), "🏷️ [Tagging] tagged {}", id);
+    Ok(())
+}
+
+// run_axis の保存後に呼ぶ（応答は待たせない）
+pub fn spawn_auto_tag(app: AppHandle, id: String) {
+    if !settings::current().auto_tag_enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = auto_tag(&app, &id).await {
+            warn!("⚠️ [Tagging] auto-tag failed for {}: {}", id, e);
+        }
+    });
+}
@@ -0,0 +1,336 @@
+// src-tauri/src/tasks.rs
+//
+// 長くかかる処理のバックグラウンドタスク
+// - spawn(kind, label, ...) で裏に回して ID をすぐ返す（ask_axis は待たない）
+// - 状態は memory.db の tasks に残す（queued → running → done / failed / cancelled）
+// - 進み具合は TaskHandle::progress → axis-task-progress イベント（TaskInfo そのまま）
+// - cancel(id) はフラグを立てて、タスクの future を落とす（RUN は子プロセスも止める）
+// - start(kind, argument) が UI / BACKGROUND: から使う入口（run / ask / briefing / poll_feeds）
+// - depth は入れ子の深さ。タスクの中の BACKGROUND: は断る（ask がタスクを生み続けないように）
+// - 終わったら通知（DeepLink "task"）。アプリを落とした時に走っていたものは次の起動で failed にする
+
+use crate::db::AxisDatabase;
+use crate::notify::{self, DeepLink};
+use crate::{feeds, policy, settings, shell};
+use chrono::Local;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+const PROGRESS_EVENT: &str = "axis-task-progress";
+// 結果は長くなりがちなので保存する分だけ
+const RESULT_MAX_CHARS: usize = 20_000;
+// タスクの中からさらにタスクは作らない
+const MAX_DEPTH: u32 = 1;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TaskInfo {
+    pub id: String,
+    pub kind: String, // "run" / "ask" / "briefing" / "poll_feeds"
+    pub label: String,
+    pub session_id: String,
+    pub status: String, // queued / running / done / failed / cancelled
+    pub progress: f32,  // 0.0 ..= 1.0（分からない時は 0 のまま message だけ）
+    pub message: String,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+}
+
+// 走っているタスクのキャンセルフラグ (Tauri managed state)
+#[derive(Default)]
+pub struct TaskManager(Mutex<HashMap<String, Arc<AtomicBool>>>);
+
+#[derive(Clone)]
+pub struct TaskHandle {
+    app: AppHandle,
+    id: String,
+    cancel: Arc<AtomicBool>,
+}
+
+fn save(app: &AppHandle, task: &TaskInfo) {
+    if let Err(e) =
+        AxisDatabase::open(app).and_then(|db| db.save_task(task).map_err(|e| e.to_string()))
+    {
+        warn!("⚠️ [Tasks] failed to save {}: {}", task.id, e);
+    }
+    let _ = app.emit(PROGRESS_EVENT, task);
+}
+
+fn load(app: &AppHandle, id: &str) -> Result<TaskInfo, String> {
+    AxisDatabase::open(app)?
+        .get_task(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("task '{}' not found", id))
+}
+
+impl TaskHandle {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    // 子プロセスを止める等、同期処理の中で見る用
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancel.clone()
+    }
+
+    pub fn progress(&self, fraction: f32, message: &str) {
+        if let Ok(mut task) = load(&self.app, &self.id) {
+            if task.status != "running" {
+                return;
+            }
+            task.progress = fraction.clamp(0.0, 1.0);
+            task.message = message.to_string();
+            save(&self.app, &task);
+        }
+    }
+}
+
+async fn wait_cancel(flag: Arc<AtomicBool>) {
+    while !flag.load(Ordering::Relaxed) {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+fn finish(app: &AppHandle, id: &str, outcome: Result<String, String>, cancelled: bool) {
+    if let Some(state) = app.try_state::<TaskManager>() {
+        if let Ok(mut map) = state.0.lock() {
+            map.remove(id);
+        }
+    }
+    let Ok(mut task) = load(app, id) else {
+        return;
+    };
+    task.finished_at = Some(Local::now().timestamp_millis());
+    match (cancelled, outcome) {
+        (true, _) => {
+            task.status = "cancelled".to_string();
+            task.message = "cancelled".to_string();
+        }
+        (false, Ok(result)) => {
+            task.status = "done".to_string();
+            task.progress = 1.0;
+            task.message = "done".to_string();
+            task.result = Some(result.chars().take(RESULT_MAX_CHARS).collect());
+        }
+        (false, Err(e)) => {
+            task.status = "failed".to_string();
+            task.message = "failed".to_string();
+            task.error = Some(e);
+        }
+    }
+    info!(input = %task.label, "🧵 [Tasks] {} ({})", task.status, task.id);
+    save(app, &task);
+    if task.status != "cancelled" {
+        let body = task
+            .result
+            .clone()
+            .or_else(|| task.error.clone())
+            .unwrap_or_default();
+        notify::notify(
+            app,
+            &format!("🧵 Task {}: {}", task.status, task.label),
+            &body.chars().take(200).collect::<String>(),
+            Some(DeepLink::new("task", &task.id)),
+        );
+    }
+}
+
+pub fn spawn<F, Fut>(
+    app: &AppHandle,
+    kind: &str,
+    label: &str,
+    session_id: &str,
+    job: F,
+) -> Result<TaskInfo, String>
+where
+    F: FnOnce(TaskHandle) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<String, String>> + Send + 'static,
+{
+    let task = TaskInfo {
+        id: Uuid::new_v4().to_string(),
+        kind: kind.to_string(),
+        label: label.chars().take(120).collect(),
+        session_id: session_id.to_string(),
+        status: "queued".to_string(),
+        created_at: Local::now().timestamp_millis(),
+        ..Default::default()
+    };
+    AxisDatabase::open(app)?
+        .save_task(&task)
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit(PROGRESS_EVENT, &task);
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    if let Some(state) = app.try_state::<TaskManager>() {
+        if let Ok(mut map) = state.0.lock() {
+            map.insert(task.id.clone(), cancel.clone());
+        }
+    }
+    let handle = TaskHandle {
+        app: app.clone(),
+        id: task.id.clone(),
+        cancel: cancel.clone(),
+    };
+
+    let app = app.clone();
+    let id = task.id.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Ok(mut t) = load(&app, &id) {
+            t.status = "running".to_string();
+            t.started_at = Some(Local::now().timestamp_millis());
+            save(&app, &t);
+        }
+        let outcome = tokio::select! {
+            r = job(handle) => Some(r),
+            _ = wait_cancel(cancel.clone()) => None,
+        };
+        let cancelled = cancel.load(Ordering::Relaxed);
+        finish(
+            &app,
+            &id,
+            outcome.unwrap_or_else(|| Err("cancelled".to_string())),
+            cancelled,
+        );
+    });
+    info!(input = %task.label, "🧵 [Tasks] queued ({})", task.id);
+    Ok(task)
+}
+
+// RUN: の待ち時間はタイムアウトに対する経過時間を進み具合にする
+async fn run_job(task: TaskHandle, command: String, cwd: Option<String>) -> Result<String, String> {
+    let timeout = settings::current().run_timeout_secs.max(1);
+    let flag = task.cancel_flag();
+    let job = tauri::async_runtime::spawn_blocking(move || {
+        shell::run_shell_with(&command, cwd.as_deref(), Some(&flag))
+    });
+    tokio::pin!(job);
+    let started = Instant::now();
+    loop {
+        tokio::select! {
+            r = &mut job => return r.map_err(|e| e.to_string()),
+            _ = tokio::time::sleep(Duration::from_secs(2)) => {
+                let secs = started.elapsed().as_secs();
+                task.progress(secs as f32 / timeout as f32, &format!("running ({}s)", secs));
+            }
+        }
+    }
+}
+
+pub fn start(
+    app: &AppHandle,
+    kind: &str,
+    argument: &str,
+    cwd: Option<&str>,
+    session_id: &str,
+    depth: u32,
+) -> Result<TaskInfo, String> {
+    if depth >= MAX_DEPTH {
+        warn!(input = %argument, "🧵 [Tasks] refused nested {} task (depth {})", kind, depth);
+        return Err("nested BACKGROUND is not allowed inside a background task".to_string());
+    }
+    let argument = argument.trim().to_string();
+    match kind {
+        "run" => {
+            // 確認の要る RUN は裏に回さない（承認ダイアログを通す）
+            if !policy::run_allowed(&argument, cwd) || policy::requires_confirmation("RUN") {
+                return Err(format!(
+                    "'{}' needs confirmation; use RUN: instead of a background task",
+                    argument
+                ));
+            }
+            let label = match cwd {
+                Some(cwd) => format!("RUN: {} @ {}", argument, cwd),
+                None => format!("RUN: {}", argument),
+            };
+            let cwd = cwd.map(str::to_string);
+            spawn(app, kind, &label, session_id, move |task| {
+                run_job(task, argument, cwd)
+            })
+        }
+        "ask" => {
+            if argument.is_empty() {
+                return Err("ask task needs a prompt".to_string());
+            }
+            let app2 = app.clone();
+            spawn(
+                app,
+                kind,
+                &argument.clone(),
+                session_id,
+                move |task| async move {
+                    // 実行中の会話に割り込まないよう、タスクごとのセッションで
+                    let session = format!("task-{}", task.id());
+                    task.progress(0.0, "thinking");
+                    crate::ask_task(app2, argument, session, depth + 1).await
+                },
+            )
+        }
+        "briefing" => {
+            let app2 = app.clone();
+            spawn(app, kind, "Briefing", session_id, move |_| async move {
+                feeds::run_briefing(&app2).await.map(|b| b.text)
+            })
+        }
+        "poll_feeds" => {
+            let app2 = app.clone();
+            spawn(app, kind, "Poll feeds", session_id, move |_| async move {
+                let report = feeds::poll_all(&app2).await?;
+                Ok(format!(
+                    "{} feeds, {} new items, {} errors\n{}",
+                    report.feeds,
+                    report.new_items,
+                    report.errors.len(),
+                    report.errors.join("\n")
+                ))
+            })
+        }
+        other => Err(format!(
+            "unknown task kind '{}': use run / ask / briefing / poll_feeds",
+            other
+        )),
+    }
+}
+
+pub fn cancel(app: &AppHandle, id: &str) -> Result<bool, String> {
+    let flag = app
+        .try_state::<TaskManager>()
+        .and_then(|state| state.0.lock().ok().and_then(|map| map.get(id).cloned()));
+    match flag {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+pub fn list(app: &AppHandle, limit: usize) -> Result<Vec<TaskInfo>, String> {
+    AxisDatabase::open(app)?
+        .list_tasks(limit)
+        .map_err(|e| e.to_string())
+}
+
+pub fn get(app: &AppHandle, id: &str) -> Result<TaskInfo, String> {
+    load(app, id)
+}
+
+// 起動時: 前回落ちた時に途中だったもの
+pub fn recover(app: &AppHandle) {
+    let now = Local::now().timestamp_millis();
+    match AxisDatabase::open(app)
+        .and_then(|db| db.fail_unfinished_tasks(now).map_err(|e| e.to_string()))
+    {
+        Ok(0) => {}
+        Ok(n) => info!("🧵 [Tasks] marked {} interrupted tasks as failed", n),
+        Err(e) => warn!("⚠️ [Tasks] recover failed: {}", e),
+    }
+}
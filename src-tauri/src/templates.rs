@@ -0,0 +1,450 @@
+// src-tauri/src/templates.rs
+//
+// テンプレートからの文書生成 (GENERATE_FROM_TEMPLATE: <template> [> <出力名>] ||| <JSON>)
+// - テンプレートは app_data_dir/templates/ に .md / .docx を置く（請求書、週報など）
+// - {{field}} を JSON の値で置き換える（{{client.name}} で入れ子も）。{{today}} は指定が無ければ今日の日付
+// - {{#items}} ... {{/items}} は配列の要素ごとに繰り返す（中では要素のフィールド、文字列の配列なら {{.}}）
+// - .docx は word/document.xml とヘッダー / フッターを段落ごとに置き換える
+//   Word が {{ }} を複数の run に分けていても段落の文字をまとめて見る（置き換えた段落は最初の run の書式になる）
+//   繰り返しは 1 つの段落の中だけ
+// - 足りないフィールドがあれば書かずにエラーを返す（モデルがユーザーに聞く）
+// - 出力先はデスクトップ。既定の名前は "<テンプレート名> <日付>.<ext>"、既にあれば "(2)" を付ける
+// - 書き込みは undo::write_file を通す（UNDO_LAST で消せる）
+
+use crate::filegen::{self, Mode, Saved};
+use crate::{shell, undo, xlsx};
+use chrono::{DateTime, Local};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const KINDS: &[&str] = &["md", "docx"];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TemplateInfo {
+    pub name: String, // 拡張子なし（GENERATE_FROM_TEMPLATE で使う名前）
+    pub file: String,
+    pub kind: String,        // md / docx
+    pub fields: Vec<String>, // "client", "items[].amount" など
+    pub updated_at: i64,
+}
+
+fn templates_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("templates");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn kind_of(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    KINDS.contains(&ext.as_str()).then_some(ext)
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+pub fn list(app: &AppHandle) -> Result<Vec<TemplateInfo>, String> {
+    let mut out = Vec::new();
+    for entry in fs::read_dir(templates_dir(app)?).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let Some(kind) = kind_of(&path) else {
+            continue;
+        };
+        let text = match read_text(&path, &kind) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("⚠️ [Templates] skipped {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let updated_at = fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .map(|t| DateTime::<Local>::from(t).timestamp_millis())
+            .unwrap_or(0);
+        out.push(TemplateInfo {
+            name: stem(&path),
+            file: path.to_string_lossy().to_string(),
+            kind,
+            fields: fields(&text),
+            updated_at,
+        });
+    }
+    out.sort_by_key(|t| t.name.to_lowercase());
+    Ok(out)
+}
+
+// "invoice" / "invoice.docx"（大文字小文字は区別しない）
+fn find(app: &AppHandle, name: &str) -> Result<TemplateInfo, String> {
+    let name = name.trim().to_lowercase();
+    let templates = list(app)?;
+    templates
+        .iter()
+        .find(|t| {
+            t.name.to_lowercase() == name || format!("{}.{}", t.name, t.kind).to_lowercase() == name
+        })
+        .cloned()
+        .ok_or_else(|| {
+            let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+            format!(
+                "template '{}' not found (available: {})",
+                name,
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            )
+        })
+}
+
+fn placeholder_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{\{\s*([#/]?)\s*([^{}]*?)\s*\}\}").unwrap())
+}
+
+// テンプレートが使うフィールド（繰り返しの中は "items[].name"）
+fn fields(text: &str) -> Vec<String> {
+    let mut sections: Vec<String> = Vec::new();
+    let mut out: Vec<String> = Vec::new();
+    for cap in placeholder_re().captures_iter(text) {
+        let name = &cap[2];
+        let field = match &cap[1] {
+            "/" => {
+                sections.pop();
+                continue;
+            }
+            "#" => {
+                let field = match sections.last() {
+                    Some(outer) => format!("{}[].{}", outer, name),
+                    None => name.to_string(),
+                };
+                sections.push(field.clone());
+                field
+            }
+            _ if name == "." || name == "today" => continue,
+            _ => match sections.last() {
+                Some(outer) => format!("{}[].{}", outer, name),
+                None => name.to_string(),
+            },
+        };
+        if !out.contains(&field) {
+            out.push(field);
+        }
+    }
+    out
+}
+
+fn value_text(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        Value::Array(items) => items.iter().map(value_text).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+// 内側（繰り返しの要素）から順に探す
+fn lookup<'a>(name: &str, scopes: &[&'a Value]) -> Option<&'a Value> {
+    if name == "." {
+        return scopes.last().copied();
+    }
+    scopes
+        .iter()
+        .rev()
+        .find_map(|scope| name.split('.').try_fold(*scope, |v, key| v.get(key)))
+}
+
+fn note_missing(missing: &mut Vec<String>, name: &str) {
+    if !missing.iter().any(|m| m == name) {
+        missing.push(name.to_string());
+    }
+}
+
+// 行にタグだけがある時、その行の改行ごと消す（表の行の間に空行が入らないように）
+fn strip_newline(s: &str) -> &str {
+    s.strip_prefix("\r\n")
+        .or_else(|| s.strip_prefix('\n'))
+        .unwrap_or(s)
+}
+
+fn fill(text: &str, scopes: &[&Value], missing: &mut Vec<String>) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let key = after[..end].trim();
+        rest = &after[end + 2..];
+
+        if let Some(section) = key.strip_prefix('#') {
+            let section = section.trim();
+            let Some(close) = placeholder_re()
+                .captures_iter(rest)
+                .find(|c| &c[1] == "/" && &c[2] == section)
+                .and_then(|c| c.get(0))
+            else {
+                // 閉じていないものはそのまま残す
+                out.push_str(&format!("{{{{{}}}}}", key));
+                continue;
+            };
+            let body = strip_newline(&rest[..close.start()]);
+            let tail = &rest[close.end()..];
+            rest = if body.len() < close.start() {
+                strip_newline(tail)
+            } else {
+                tail
+            };
+            let items: Vec<&Value> = match lookup(section, scopes) {
+                Some(Value::Array(items)) => items.iter().collect(),
+                Some(Value::Bool(false) | Value::Null) => Vec::new(),
+                Some(v) => vec![v],
+                None => {
+                    note_missing(missing, section);
+                    Vec::new()
+                }
+            };
+            for item in items {
+                let mut inner = scopes.to_vec();
+                inner.push(item);
+                out.push_str(&fill(body, &inner, missing));
+            }
+        } else if key.starts_with('/') {
+            // 対応する {{#...}} が無い閉じタグ
+        } else if let Some(v) = lookup(key, scopes) {
+            out.push_str(&value_text(v));
+        } else if key == "today" {
+            out.push_str(&Local::now().format("%Y-%m-%d").to_string());
+        } else {
+            note_missing(missing, key);
+            out.push_str(&format!("{{{{{}}}}}", key));
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// ---------- docx ----------
+
+// <w:p/>（空の段落）は飛ばす
+fn paragraph_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?s)<w:p(?:\s[^>]*[^/])?>.*?</w:p>").unwrap())
+}
+
+fn text_run_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"<w:t(?:\s[^>]*)?>([^<]*)</w:t>").unwrap())
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn paragraph_text(p: &str) -> String {
+    text_run_re()
+        .captures_iter(p)
+        .map(|c| unescape(&c[1]))
+        .collect()
+}
+
+// 置き換えた文字は最初の <w:t> にまとめ、残りの <w:t> は空にする
+fn fill_paragraph(p: &str, scopes: &[&Value], missing: &mut Vec<String>) -> String {
+    let text = paragraph_text(p);
+    if !text.contains("{{") {
+        return p.to_string();
+    }
+    let filled = xlsx::escape(&fill(&text, scopes, missing))
+        .replace('\n', r#"</w:t><w:br/><w:t xml:space="preserve">"#);
+    let mut first = true;
+    text_run_re()
+        .replace_all(p, |_: &Captures| {
+            if std::mem::take(&mut first) {
+                format!(r#"<w:t xml:space="preserve">{}</w:t>"#, filled)
+            } else {
+                "<w:t></w:t>".to_string()
+            }
+        })
+        .to_string()
+}
+
+fn is_docx_part(name: &str) -> bool {
+    name == "word/document.xml"
+        || (name.starts_with("word/header") || name.starts_with("word/footer"))
+            && name.ends_with(".xml")
+}
+
+fn zip_err(e: zip::result::ZipError) -> String {
+    format!("docx Error: {}", e)
+}
+
+fn docx_parts(path: &Path) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(zip_err)?;
+    let mut parts = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_err)?;
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+        parts.push((entry.name().to_string(), bytes));
+    }
+    Ok(parts)
+}
+
+fn docx_text(parts: &[(String, Vec<u8>)]) -> String {
+    parts
+        .iter()
+        .filter(|(name, _)| is_docx_part(name))
+        .flat_map(|(_, bytes)| {
+            let xml = String::from_utf8_lossy(bytes).to_string();
+            paragraph_re()
+                .find_iter(&xml)
+                .map(|m| paragraph_text(m.as_str()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn fill_docx(
+    parts: Vec<(String, Vec<u8>)>,
+    fields: &Value,
+    missing: &mut Vec<String>,
+) -> Result<Vec<(String, Vec<u8>)>, String> {
+    parts
+        .into_iter()
+        .map(|(name, bytes)| {
+            if !is_docx_part(&name) {
+                return Ok((name, bytes));
+            }
+            let xml = String::from_utf8(bytes).map_err(|e| format!("{}: {}", name, e))?;
+            let filled = paragraph_re()
+                .replace_all(&xml, |c: &Captures| {
+                    fill_paragraph(&c[0], &[fields], missing)
+                })
+                .into_owned();
+            Ok((name, filled.into_bytes()))
+        })
+        .collect()
+}
+
+fn zip_parts(parts: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let opts = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, bytes) in parts {
+        zip.start_file(name.as_str(), opts).map_err(zip_err)?;
+        zip.write_all(bytes).map_err(|e| e.to_string())?;
+    }
+    Ok(zip.finish().map_err(zip_err)?.into_inner())
+}
+
+fn read_text(path: &Path, kind: &str) -> Result<String, String> {
+    match kind {
+        "docx" => Ok(docx_text(&docx_parts(path)?)),
+        _ => fs::read_to_string(path).map_err(|e| e.to_string()),
+    }
+}
+
+// "invoice > ACME 2026-10.docx" → ("invoice", Some("ACME 2026-10.docx"))
+fn parse_target(spec: &str) -> (&str, Option<&str>) {
+    match spec.split_once('>') {
+        Some((template, output)) if !output.trim().is_empty() => {
+            (template.trim(), Some(output.trim()))
+        }
+        _ => (spec.trim().trim_end_matches('>').trim(), None),
+    }
+}
+
+pub fn generate(
+    app: &AppHandle,
+    session_id: &str,
+    spec: &str,
+    fields_json: &str,
+) -> Result<Saved, String> {
+    let (name, output) = parse_target(spec);
+    let template = find(app, name)?;
+    let fields: Value = serde_json::from_str(fields_json.trim())
+        .map_err(|e| format!("fields must be a JSON object: {}", e))?;
+    if !fields.is_object() {
+        return Err("fields must be a JSON object".to_string());
+    }
+
+    let source = Path::new(&template.file);
+    let mut missing = Vec::new();
+    let (bytes, text) = if template.kind == "docx" {
+        let parts = fill_docx(docx_parts(source)?, &fields, &mut missing)?;
+        (zip_parts(&parts)?, docx_text(&parts))
+    } else {
+        let raw = fs::read_to_string(source).map_err(|e| e.to_string())?;
+        let text = fill(&raw, &[&fields], &mut missing);
+        (text.clone().into_bytes(), text)
+    };
+    if !missing.is_empty() {
+        return Err(format!(
+            "template '{}' needs these fields: {}. Nothing was written. Ask the user for them.",
+            template.name,
+            missing.join(", ")
+        ));
+    }
+
+    // 出力名に拡張子が無ければテンプレートと同じ（形式は変えない）
+    let mut file_name = output
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} {}", template.name, Local::now().format("%Y-%m-%d")));
+    if kind_of(Path::new(&file_name)).as_deref() != Some(template.kind.as_str()) {
+        file_name = format!("{}.{}", file_name, template.kind);
+    }
+    let mut path = shell::desktop_dir().join(&file_name);
+    let renamed = path.exists();
+    if renamed {
+        path = filegen::unused_name(&path);
+    }
+    let entry = undo::write_file(app, session_id, "GENERATE_FROM_TEMPLATE", &path, &bytes)?;
+    info!("📄 [Templates] {} -> {}", template.name, path.display());
+    Ok(Saved {
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or(file_name),
+        path,
+        content: bytes,
+        text,
+        mode: if renamed { Mode::New } else { Mode::Create },
+        replaced: entry.restorable(),
+    })
+}
+
+// worker プロンプトの {{templates}}
+pub fn prompt_list(app: &AppHandle) -> String {
+    let templates = list(app).unwrap_or_default();
+    if templates.is_empty() {
+        return "(none)".to_string();
+    }
+    templates
+        .iter()
+        .map(|t| format!("- {} (.{}): {}", t.name, t.kind, t.fields.join(", ")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,37 @@
+// src-tauri/src/trace.rs
+//
+// ask_axis 1 回分の処理の記録の保存と読み出し（中身と組み立ては axis_core::trace / orchestrator）
+// - memory.db の traces に InteractionLog.id をキーにして保存、get_trace(log_id) で返す
+//   RETENTION_MS より古いものは保存のついでに消す
+// - Worker のシステムプロンプトはハッシュだけ (hash_prompt)
+
+use crate::db::AxisDatabase;
+use crate::objects;
+use tauri::AppHandle;
+use tracing::warn;
+
+pub use axis_core::trace::{ActionTrace, Trace};
+
+const RETENTION_MS: i64 = 14 * 24 * 60 * 60 * 1000;
+
+pub fn hash_prompt(prompt: &str) -> String {
+    objects::hash(prompt.as_bytes())[..16].to_string()
+}
+
+pub fn save(app: &AppHandle, trace: &Trace) {
+    let result = AxisDatabase::open(app).and_then(|db| {
+        db.insert_trace(trace)
+            .and_then(|_| db.prune_traces(trace.created_at - RETENTION_MS))
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        warn!("⚠️ [Trace] failed to save trace: {}", e);
+    }
+}
+
+pub fn get(app: &AppHandle, log_id: &str) -> Result<Trace, String> {
+    AxisDatabase::open(app)?
+        .trace(log_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("no trace for log {}", log_id))
+}
@@ -0,0 +1,122 @@
+// src-tauri/src/tray.rs
+//
+// システムトレイ（ウィンドウを閉じても裏で動き続ける常駐モード）
+// - メニュー: Quick Ask / Open Axis / Pause Observer / Voice / Show Usage Today / Quit
+// - 左クリックでメインウィンドウを出す
+// - settings.minimize_to_tray が true ならメインウィンドウの × は隠すだけ（lib.rs の on_window_event）
+// - チェック付きの項目は、UI 側から変えられた時も refresh で合わせる
+
+use crate::{hotkey, notify, observer, outcomes, settings};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Wry};
+use tracing::warn;
+
+const TRAY_ID: &str = "axis";
+
+// チェックの付け外しをするメニュー項目 (Tauri managed state)
+pub struct TrayMenu {
+    pause: CheckMenuItem<Wry>,
+    voice: CheckMenuItem<Wry>,
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(w) = app.get_webview_window("main") {
+        let _ = w.show();
+        let _ = w.unminimize();
+        let _ = w.set_focus();
+    }
+}
+
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let quick = MenuItem::with_id(app, "quick_ask", "Quick Ask", true, None::<&str>)?;
+    let open = MenuItem::with_id(app, "open", "Open Axis", true, None::<&str>)?;
+    let pause = CheckMenuItem::with_id(
+        app,
+        "pause_observer",
+        "Pause Observer",
+        true,
+        false,
+        None::<&str>,
+    )?;
+    let voice = CheckMenuItem::with_id(
+        app,
+        "toggle_voice",
+        "Voice",
+        true,
+        settings::current().voice_enabled,
+        None::<&str>,
+    )?;
+    let usage = MenuItem::with_id(app, "usage_today", "Show Usage Today", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &quick,
+            &open,
+            &PredefinedMenuItem::separator(app)?,
+            &pause,
+            &voice,
+            &usage,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
+    app.manage(TrayMenu { pause, voice });
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Axis")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "quick_ask" => hotkey::show_quick_window(app),
+            "open" => show_main_window(app),
+            "pause_observer" => {
+                let paused = !observer::current_presence(app).paused;
+                observer::set_paused(app, paused);
+                refresh(app);
+            }
+            "toggle_voice" => {
+                let mut s = settings::view().settings;
+                s.voice_enabled = !s.voice_enabled;
+                if let Err(e) = settings::update(app, s) {
+                    warn!("⚠️ [Tray] failed to toggle voice: {}", e);
+                }
+                refresh(app);
+            }
+            "usage_today" => {
+                let body = outcomes::usage_today(app)
+                    .map(|u| u.summarize())
+                    .unwrap_or_else(|e| format!("Usage unavailable: {}", e));
+                notify::notify(app, "Axis usage today", &body, None);
+            }
+            "quit" => app.exit(0),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+// チェックの状態を実際の値に合わせる（トレイ以外から変えられた時も）
+pub fn refresh(app: &AppHandle) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let _ = menu
+        .pause
+        .set_checked(observer::current_presence(app).paused);
+    let _ = menu.voice.set_checked(settings::current().voice_enabled);
+}
@@ -0,0 +1,515 @@
+// src-tauri/src/uia.rs
+//
+// 前面ウィンドウの中身を UI Automation で読む (LOOK / read_active_window)
+// - スクリーンショット + 視覚モデルより正確で安い: エディタの本文 / ブラウザのページ / ダイアログの文言とボタンをそのまま文字で
+// - 読むのは control view の木（MAX_DEPTH / MAX_NODES まで）。画面外 / スクロールバー / タイトルバーの部品は省く
+// - 値は ValuePattern、文書 (document / edit) は TextPattern の全文。TextPattern で読めた要素の子は本文と重なるので辿らない
+// - パスワード欄の値は読まない
+// - Axis 自身のウィンドウが前面の時（チャットから LOOK した時）は、その下の見えているウィンドウを読む
+// - 文面への変換は axis_core::vision::outline。Windows 以外では使えない（LOOK はスクリーンショットに戻る）
+// - focus_field: TYPE: <text> @ <window> [> <field>] の宛先。ウィンドウと入力欄を探して UIA でフォーカスし、
+//   フォーカスが本当にそこへ移ったのを確かめてから打つ (shell::type_text)。見つからなければ打たずに理由を返す
+// - wait_for: WAITFOR: window <title> / text <string>。出てくるまで木を読み直して待つ（固定の WAIT より速く / 確実に）
+
+use axis_core::vision::{self as core_vision, UiNode};
+use serde::Serialize;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+const MAX_DEPTH: usize = 25;
+const MAX_NODES: usize = 800;
+const OUTLINE_CHARS: usize = 20_000;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct WindowContent {
+    pub title: String,
+    pub nodes: usize,
+    pub outline: String,
+}
+
+pub async fn read_active_window() -> Result<WindowContent, String> {
+    let (title, root, nodes) = tokio::task::spawn_blocking(platform::read)
+        .await
+        .map_err(|e| e.to_string())??;
+    let outline = core_vision::outline(&root, OUTLINE_CHARS);
+    info!(
+        "🪟 [UIA] {}: {} elements, {} chars",
+        title,
+        nodes,
+        outline.len()
+    );
+    Ok(WindowContent {
+        title,
+        nodes,
+        outline,
+    })
+}
+
+// 入力欄にフォーカスして、その説明（"edit \"Text Editor\" in \"Untitled - Notepad\""）を返す
+pub fn focus_field(window: &str, field: Option<&str>) -> Result<String, String> {
+    platform::focus_field(
+        window.trim(),
+        field.map(str::trim).filter(|f| !f.is_empty()),
+    )
+}
+
+// WAITFOR の結果（Worker への文面）。text は前面ウィンドウの名前 / 値 / 本文のどこかに含まれれば
+pub fn wait_for(kind: &str, target: &str, timeout_secs: u64) -> String {
+    if !cfg!(target_os = "windows") {
+        return "Failed: WAITFOR needs UI Automation, which is only available on Windows"
+            .to_string();
+    }
+    // text は木を全部読むので間を長めに
+    let poll = Duration::from_millis(if kind == "text" { 500 } else { 250 });
+    let started = Instant::now();
+    let mut last_error = None;
+    loop {
+        let found = match kind {
+            "window" => platform::window_title(target),
+            _ => platform::read().map(|(title, root, _)| {
+                let text = core_vision::outline(&root, usize::MAX).to_lowercase();
+                text.contains(&target.to_lowercase()).then_some(title)
+            }),
+        };
+        match found {
+            Ok(Some(title)) => {
+                let secs = started.elapsed().as_secs_f64();
+                info!("🪟 [UIA] {} '{}' after {:.1}s", kind, target, secs);
+                return match kind {
+                    "window" => format!("Success: window '{}' is open ({:.1}s).", title, secs),
+                    _ => format!(
+                        "Success: '{}' appeared in '{}' ({:.1}s).",
+                        target, title, secs
+                    ),
+                };
+            }
+            Ok(None) => {}
+            Err(e) => last_error = Some(e),
+        }
+        if started.elapsed() >= Duration::from_secs(timeout_secs) {
+            let why = last_error
+                .map(|e| format!(" (last error: {})", e))
+                .unwrap_or_default();
+            return format!(
+                "Failed: timed out after {}s waiting for {} '{}'{}",
+                timeout_secs, kind, target, why
+            );
+        }
+        thread::sleep(poll);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+        COINIT_MULTITHREADED,
+    };
+    use windows::Win32::UI::Accessibility::{
+        CUIAutomation, IUIAutomation, IUIAutomationElement, IUIAutomationTextPattern,
+        IUIAutomationTreeWalker, IUIAutomationValuePattern, UIA_TextPatternId, UIA_ValuePatternId,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindow, GetWindowTextW, GetWindowThreadProcessId, IsIconic,
+        IsWindowVisible, SetForegroundWindow, ShowWindow, GW_HWNDNEXT, SW_RESTORE,
+    };
+
+    // 中身を読まない部品
+    const SKIP_ROLES: &[&str] = &["scroll bar", "thumb", "separator", "title bar"];
+
+    // UIA_*ControlTypeId (50000〜)。CurrentLocalizedControlType は OS の言語で返るので自前で
+    fn role(id: i32) -> &'static str {
+        const ROLES: &[&str] = &[
+            "button",
+            "calendar",
+            "check box",
+            "combo box",
+            "edit",
+            "hyperlink",
+            "image",
+            "list item",
+            "list",
+            "menu",
+            "menu bar",
+            "menu item",
+            "progress bar",
+            "radio button",
+            "scroll bar",
+            "slider",
+            "spinner",
+            "status bar",
+            "tab",
+            "tab item",
+            "text",
+            "tool bar",
+            "tool tip",
+            "tree",
+            "tree item",
+            "custom",
+            "group",
+            "thumb",
+            "data grid",
+            "data item",
+            "document",
+            "split button",
+            "window",
+            "pane",
+            "header",
+            "header item",
+            "table",
+            "title bar",
+            "separator",
+            "semantic zoom",
+            "app bar",
+        ];
+        usize::try_from(id - 50000)
+            .ok()
+            .and_then(|i| ROLES.get(i))
+            .copied()
+            .unwrap_or("element")
+    }
+
+    fn title_of(hwnd: HWND) -> String {
+        let mut buf = [0u16; 512];
+        let len = unsafe { GetWindowTextW(hwnd, &mut buf) }.max(0) as usize;
+        String::from_utf16_lossy(&buf[..len])
+    }
+
+    // 前面から z 順に、Axis 以外の見えていてタイトルのあるウィンドウ
+    fn target_window() -> Option<HWND> {
+        let own = std::process::id();
+        let mut hwnd = unsafe { GetForegroundWindow() };
+        for _ in 0..200 {
+            if hwnd.0.is_null() {
+                return None;
+            }
+            let mut pid = 0u32;
+            unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+            let shown = unsafe { IsWindowVisible(hwnd).as_bool() && !IsIconic(hwnd).as_bool() };
+            if pid != own && shown && !title_of(hwnd).trim().is_empty() {
+                return Some(hwnd);
+            }
+            hwnd = unsafe { GetWindow(hwnd, GW_HWNDNEXT) }.ok()?;
+        }
+        None
+    }
+
+    // COM の初期化と IUIAutomation。既に初期化済み (S_FALSE) でも対にして閉じる
+    fn with_automation<T>(
+        f: impl FnOnce(&IUIAutomation) -> Result<T, String>,
+    ) -> Result<T, String> {
+        unsafe {
+            let init = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let result = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| format!("UI Automation is unavailable: {}", e))
+                .and_then(|automation: IUIAutomation| f(&automation));
+            if init.is_ok() {
+                CoUninitialize();
+            }
+            result
+        }
+    }
+
+    pub fn read() -> Result<(String, UiNode, usize), String> {
+        with_automation(|automation| unsafe { read_window(automation) })
+    }
+
+    unsafe fn read_window(automation: &IUIAutomation) -> Result<(String, UiNode, usize), String> {
+        let hwnd = target_window().ok_or("No window to read")?;
+        let title = title_of(hwnd);
+        let root = automation
+            .ElementFromHandle(hwnd)
+            .map_err(|e| format!("{}: {}", title, e))?;
+        let walker = automation.ControlViewWalker().map_err(|e| e.to_string())?;
+        let mut budget = MAX_NODES;
+        let tree = node(&walker, &root, 0, &mut budget).unwrap_or_default();
+        Ok((title, tree, MAX_NODES - budget))
+    }
+
+    unsafe fn node(
+        walker: &IUIAutomationTreeWalker,
+        el: &IUIAutomationElement,
+        depth: usize,
+        budget: &mut usize,
+    ) -> Option<UiNode> {
+        if *budget == 0 {
+            return None;
+        }
+        let role = role(el.CurrentControlType().map(|t| t.0).unwrap_or(0));
+        let offscreen = el
+            .CurrentIsOffscreen()
+            .map(|b| b.as_bool())
+            .unwrap_or(false);
+        if depth > 0 && (offscreen || SKIP_ROLES.contains(&role)) {
+            return None;
+        }
+        *budget -= 1;
+        let name = el.CurrentName().map(|s| s.to_string()).unwrap_or_default();
+        let password = el.CurrentIsPassword().map(|b| b.as_bool()).unwrap_or(true);
+
+        let mut value = String::new();
+        let mut whole_text = false;
+        if !password {
+            if let Ok(pattern) =
+                el.GetCurrentPatternAs::<IUIAutomationValuePattern>(UIA_ValuePatternId)
+            {
+                value = pattern
+                    .CurrentValue()
+                    .map(|s| s.to_string())
+                    .unwrap_or_default();
+            }
+            if value.trim().is_empty() && matches!(role, "document" | "edit") {
+                if let Ok(text) = el
+                    .GetCurrentPatternAs::<IUIAutomationTextPattern>(UIA_TextPatternId)
+                    .and_then(|p| p.DocumentRange())
+                    .and_then(|r| r.GetText(core_vision::VALUE_CHARS as i32))
+                {
+                    value = text.to_string();
+                    whole_text = !value.trim().is_empty();
+                }
+            }
+        }
+
+        let mut children = Vec::new();
+        if depth < MAX_DEPTH && !whole_text {
+            let mut child = walker.GetFirstChildElement(el).ok();
+            while let Some(c) = child {
+                if *budget == 0 {
+                    break;
+                }
+                if let Some(n) = node(walker, &c, depth + 1, budget) {
+                    children.push(n);
+                }
+                child = walker.GetNextSiblingElement(&c).ok();
+            }
+        }
+        Some(UiNode {
+            role: role.to_string(),
+            name,
+            value,
+            children,
+        })
+    }
+
+    // ---------- 入力欄へのフォーカス (focus_field) ----------
+
+    // 入力を受ける部品
+    const FIELD_ROLES: &[&str] = &["edit", "document", "combo box"];
+    // フォーカスが移ったか確かめる回数（50ms おき）
+    const FOCUS_CHECKS: usize = 10;
+
+    fn lower_contains(haystack: &str, needle: &str) -> bool {
+        haystack.to_lowercase().contains(&needle.to_lowercase())
+    }
+
+    fn label(el: &IUIAutomationElement) -> String {
+        let name = unsafe { el.CurrentName() }
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+        let role = role(unsafe { el.CurrentControlType() }.map(|t| t.0).unwrap_or(0));
+        if name.trim().is_empty() {
+            role.to_string()
+        } else {
+            format!("{} \"{}\"", role, name.trim())
+        }
+    }
+
+    pub fn focus_field(window: &str, field: Option<&str>) -> Result<String, String> {
+        with_automation(|automation| unsafe { focus(automation, window, field) })
+    }
+
+    // 見つからなければ Ok(None)
+    pub fn window_title(window: &str) -> Result<Option<String>, String> {
+        with_automation(|automation| unsafe {
+            let walker = automation.ControlViewWalker().map_err(|e| e.to_string())?;
+            Ok(find_window(automation, &walker, window)
+                .ok()
+                .map(|w| w.CurrentName().map(|s| s.to_string()).unwrap_or_default()))
+        })
+    }
+
+    // タイトルに window を含むトップレベルのウィンドウ。無ければプロセス名 ("notepad" / "chrome.exe")
+    unsafe fn find_window(
+        automation: &IUIAutomation,
+        walker: &IUIAutomationTreeWalker,
+        window: &str,
+    ) -> Result<IUIAutomationElement, String> {
+        let root = automation.GetRootElement().map_err(|e| e.to_string())?;
+        let own = std::process::id() as i32;
+        let mut sys = sysinfo::System::new();
+        sys.refresh_processes();
+        let mut titles = Vec::new();
+        let mut by_process = None;
+        let mut child = walker.GetFirstChildElement(&root).ok();
+        while let Some(c) = child {
+            let pid = c.CurrentProcessId().unwrap_or(0);
+            let title = c.CurrentName().map(|s| s.to_string()).unwrap_or_default();
+            if pid != own && !title.trim().is_empty() {
+                if lower_contains(&title, window) {
+                    return Ok(c);
+                }
+                let process = sys
+                    .process(sysinfo::Pid::from_u32(pid as u32))
+                    .map(|p| p.name().trim_end_matches(".exe").to_string())
+                    .unwrap_or_default();
+                if by_process.is_none()
+                    && !process.is_empty()
+                    && lower_contains(&process, window.trim_end_matches(".exe"))
+                {
+                    by_process = Some(c.clone());
+                }
+                titles.push(title);
+            }
+            child = walker.GetNextSiblingElement(&c).ok();
+        }
+        by_process.ok_or_else(|| {
+            titles.truncate(10);
+            format!(
+                "no window matching '{}' (open: {})",
+                window,
+                titles.join(" / ")
+            )
+        })
+    }
+
+    // 画面に出ていて使える入力欄を木の順に
+    unsafe fn fields(
+        walker: &IUIAutomationTreeWalker,
+        el: &IUIAutomationElement,
+        depth: usize,
+        budget: &mut usize,
+        out: &mut Vec<IUIAutomationElement>,
+    ) {
+        let mut child = walker.GetFirstChildElement(el).ok();
+        while let Some(c) = child {
+            if *budget == 0 {
+                return;
+            }
+            *budget -= 1;
+            let role = role(c.CurrentControlType().map(|t| t.0).unwrap_or(0));
+            let usable = c.CurrentIsEnabled().map(|b| b.as_bool()).unwrap_or(false)
+                && !c.CurrentIsOffscreen().map(|b| b.as_bool()).unwrap_or(true);
+            if usable {
+                let focusable = c
+                    .CurrentIsKeyboardFocusable()
+                    .map(|b| b.as_bool())
+                    .unwrap_or(false);
+                if focusable && FIELD_ROLES.contains(&role) {
+                    out.push(c.clone());
+                }
+                if depth < MAX_DEPTH {
+                    fields(walker, &c, depth + 1, budget, out);
+                }
+            }
+            child = walker.GetNextSiblingElement(&c).ok();
+        }
+    }
+
+    unsafe fn focus(
+        automation: &IUIAutomation,
+        window: &str,
+        field: Option<&str>,
+    ) -> Result<String, String> {
+        let walker = automation.ControlViewWalker().map_err(|e| e.to_string())?;
+        let win = find_window(automation, &walker, window)?;
+        let title = win.CurrentName().map(|s| s.to_string()).unwrap_or_default();
+        if let Ok(hwnd) = win.CurrentNativeWindowHandle() {
+            if IsIconic(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+            let _ = SetForegroundWindow(hwnd);
+        }
+        let _ = win.SetFocus();
+
+        let mut candidates = Vec::new();
+        let mut budget = MAX_NODES;
+        fields(&walker, &win, 0, &mut budget, &mut candidates);
+        let target = match field {
+            Some(field) => candidates
+                .iter()
+                .find(|c| lower_contains(&label(c), field))
+                .cloned()
+                .ok_or_else(|| {
+                    let names: Vec<String> = candidates.iter().take(10).map(label).collect();
+                    format!(
+                        "no field matching '{}' in '{}' (fields: {})",
+                        field,
+                        title,
+                        if names.is_empty() {
+                            "none".to_string()
+                        } else {
+                            names.join(" / ")
+                        }
+                    )
+                })?,
+            // 指定が無ければ、そのウィンドウで今フォーカスのある入力欄。無ければ最初の入力欄
+            None => {
+                let focused = automation.GetFocusedElement().ok();
+                let current = focused.and_then(|f| {
+                    candidates
+                        .iter()
+                        .find(|c| {
+                            automation
+                                .CompareElements(&f, *c)
+                                .map(|b| b.as_bool())
+                                .unwrap_or(false)
+                        })
+                        .cloned()
+                });
+                current
+                    .or_else(|| candidates.first().cloned())
+                    .ok_or_else(|| format!("no text field in '{}'", title))?
+            }
+        };
+
+        let _ = target.SetFocus();
+        for _ in 0..FOCUS_CHECKS {
+            let focused = automation.GetFocusedElement().ok();
+            let on_target = focused.as_ref().is_some_and(|f| {
+                automation
+                    .CompareElements(f, &target)
+                    .map(|b| b.as_bool())
+                    .unwrap_or(false)
+            });
+            if on_target {
+                let described = format!("{} in \"{}\"", label(&target), title);
+                info!("🪟 [UIA] focused {}", described);
+                return Ok(described);
+            }
+            thread::sleep(Duration::from_millis(50));
+            let _ = target.SetFocus();
+        }
+        let now = automation
+            .GetFocusedElement()
+            .map(|f| label(&f))
+            .unwrap_or_else(|_| "nothing".to_string());
+        Err(format!(
+            "could not focus {} in '{}' (focus is on {})",
+            label(&target),
+            title,
+            now
+        ))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::*;
+
+    pub fn read() -> Result<(String, UiNode, usize), String> {
+        Err("UI Automation is only available on Windows".to_string())
+    }
+
+    pub fn focus_field(_window: &str, _field: Option<&str>) -> Result<String, String> {
+        Err("UI Automation is only available on Windows".to_string())
+    }
+
+    pub fn window_title(_window: &str) -> Result<Option<String>, String> {
+        Err("UI Automation is only available on Windows".to_string())
+    }
+}
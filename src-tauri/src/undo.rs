@@ -0,0 +1,133 @@
+// src-tauri/src/undo.rs
+//
+// エージェントのファイル操作の取り消し
+// - SAVE などでファイルを書く時は write_file を通す。書く前の中身を objects/ に退避して memory.db の file_journal に 1 行
+// - undo_last: 最新の（まだ取り消していない）書き込みを戻す。新規作成だったものは消す
+//   書いた後に人が編集していたら（ハッシュが違えば）戻さない
+// - バックアップは新しい方から KEEP_ENTRIES 件分だけ objects の gc から守る
+
+use crate::db::AxisDatabase;
+use crate::objects;
+use chrono::Local;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+const KEEP_ENTRIES: usize = 200;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct JournalEntry {
+    pub id: i64,
+    pub session_id: String,
+    pub action: String, // "SAVE" など
+    pub path: String,
+    pub backup_id: Option<String>, // None = 新規作成
+    pub written_hash: String,
+    pub created_at: i64,
+    pub undone_at: Option<i64>,
+}
+
+impl JournalEntry {
+    // UNDO_LAST で前の中身に戻せる（退避できて、ジャーナルにも載った）
+    pub fn restorable(&self) -> bool {
+        self.id > 0 && self.backup_id.is_some()
+    }
+}
+
+// 書く前の中身を退避してから書く
+pub fn write_file(
+    app: &AppHandle,
+    session_id: &str,
+    action: &str,
+    path: &Path,
+    bytes: &[u8],
+) -> Result<JournalEntry, String> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let backup_id = if path.is_file() {
+        let previous = fs::read(path).map_err(|e| e.to_string())?;
+        Some(objects::save(app, &previous, "application/octet-stream", &name)?.id)
+    } else {
+        None
+    };
+    fs::write(path, bytes).map_err(|e| e.to_string())?;
+
+    let mut entry = JournalEntry {
+        id: 0,
+        session_id: session_id.to_string(),
+        action: action.to_string(),
+        path: path.to_string_lossy().to_string(),
+        backup_id,
+        written_hash: objects::hash(bytes),
+        created_at: Local::now().timestamp_millis(),
+        undone_at: None,
+    };
+    // 書けたのにジャーナルだけ失敗した時は、書き込み自体は成功として扱う（id は 0 のまま = 戻せない）
+    match AxisDatabase::open(app)
+        .and_then(|db| db.insert_journal_entry(&entry).map_err(|e| e.to_string()))
+    {
+        Ok(id) => entry.id = id,
+        Err(e) => warn!("⚠️ [Undo] failed to journal {}: {}", entry.path, e),
+    }
+    Ok(entry)
+}
+
+pub fn undo_last(app: &AppHandle, session_id: Option<&str>) -> Result<String, String> {
+    let db = AxisDatabase::open(app)?;
+    let entry = db
+        .last_journal_entry(session_id)
+        .map_err(|e| e.to_string())?
+        .ok_or("nothing to undo")?;
+    let path = Path::new(&entry.path);
+
+    if let Ok(current) = fs::read(path) {
+        if objects::hash(&current) != entry.written_hash {
+            return Err(format!(
+                "{} was changed after {} wrote it; not undoing",
+                entry.path, entry.action
+            ));
+        }
+    }
+    let message = match &entry.backup_id {
+        Some(id) => {
+            let (previous, _) = objects::load(app, id)
+                .map_err(|e| format!("backup of {} is gone: {}", entry.path, e))?;
+            fs::write(path, previous).map_err(|e| e.to_string())?;
+            format!("Restored the previous version of {}", entry.path)
+        }
+        None => {
+            if path.exists() {
+                fs::remove_file(path).map_err(|e| e.to_string())?;
+            }
+            format!(
+                "Removed {} (it was created by {})",
+                entry.path, entry.action
+            )
+        }
+    };
+    db.mark_journal_undone(entry.id, Local::now().timestamp_millis())
+        .map_err(|e| e.to_string())?;
+    info!(text = %message, "↩️ [Undo] restored");
+    Ok(message)
+}
+
+pub fn list(app: &AppHandle, limit: usize) -> Result<Vec<JournalEntry>, String> {
+    AxisDatabase::open(app)?
+        .list_journal(limit)
+        .map_err(|e| e.to_string())
+}
+
+// objects::gc 用（読めなければ gc ごと止める）
+pub fn backup_ids(app: &AppHandle) -> Result<Vec<String>, String> {
+    backup_ids_in(&AxisDatabase::open(app)?)
+}
+
+// purge.rs: begin_purge のトランザクションの中から（消した後に残る分）
+pub fn backup_ids_in(db: &AxisDatabase) -> Result<Vec<String>, String> {
+    db.journal_backup_ids(KEEP_ENTRIES)
+        .map_err(|e| e.to_string())
+}
@@ -1,8 +1,28 @@
 // src-tauri/src/vision.rs
+//
+// 画面の撮影と、それを読む視覚モデル (axis_core::vision の VisionModel)
+// - 候補: gpt (gpt-4o) / gemini / claude / llama (NVIDIA の Llama Vision) / local (Ollama の llava 等)
+// - settings.vision_provider が "auto" なら vision_profiles.json の点数順。名前なら先頭にして残りは点数順
+//   app_data_dir/vision_profiles.json があれば名前ごとに上書き
+// - キーが無い / 具合が悪い / 予算切れのものは外す。失敗したら次の候補へ
+// - ローカル専用モードは視覚モデルを使わず、端末内の OCR で画面の文字だけ読む (ocr.rs / local_only.rs)
+// - record_screen: 数秒間の画面を一定間隔で撮り、変化の大きいコマを 1 枚に並べて読ませる (RECORD_SCREEN)
+//   1 枚にまとめるのでどの視覚モデルでも同じようにフォールバックできる
 
+use crate::{ai, ocr, prompts, secrets, settings, AiMessage};
+use axis_core::vision::{self as core_vision, VisionModel, VisionScore};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
 use screenshots::Screen;
 use std::io::Cursor;
-use image::ImageOutputFormat;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageOutputFormat, Rgba, RgbaImage};
 use base64::{Engine as _, engine::general_purpose};
 
 // 画面を撮影してBase64文字列で返す関数
@@ -29,4 +49,216 @@ pub fn take_screenshot() -> Result<String, String> {
     let base64_str = general_purpose::STANDARD.encode(buffer);
     
     Ok(base64_str)
-}
\ No newline at end of file
+}
+
+const DEFAULT_PROFILES: &str = include_str!("vision_profiles.json");
+
+struct Models;
+
+impl VisionModel for Models {
+    async fn describe(&self, name: &str, prompt: &str, png_base64: &str) -> Result<String, String> {
+        let cfg = settings::current();
+        let models = &cfg.vision_models;
+        match name {
+            "gpt" => ai::call_openai_vision(&models.gpt, prompt, png_base64).await,
+            "gemini" => {
+                let image = ai::Attachment {
+                    mime: "image/png".to_string(),
+                    name: "screenshot.png".to_string(),
+                    bytes: general_purpose::STANDARD.decode(png_base64).map_err(|e| e.to_string())?,
+                };
+                ai::call_google_with(&models.gemini, "", prompt, &[image]).await
+            }
+            "claude" => ai::call_claude_vision(&models.claude, prompt, png_base64).await,
+            "local" => ai::call_local_vision(cfg.local_vision_model.trim(), prompt, png_base64).await,
+            "llama" => {
+                let messages = vec![AiMessage {
+                    role: "user".to_string(),
+                    content: json!([
+                        { "type": "text", "text": prompt },
+                        { "type": "image_url", "image_url": { "url": format!("data:image/png;base64,{}", png_base64) } }
+                    ]),
+                }];
+                crate::send_llm_request(&models.llama, messages, 0.5).await
+            }
+            other => Err(format!("unknown vision model '{}'", other)),
+        }
+    }
+}
+
+// 埋め込みの既定値に app_data_dir/vision_profiles.json を被せる
+fn profiles() -> HashMap<String, VisionScore> {
+    let mut scores: HashMap<String, VisionScore> = serde_json::from_str(DEFAULT_PROFILES).unwrap_or_default();
+    let path = ai::app_handle()
+        .and_then(|app| app.path().app_data_dir().ok())
+        .map(|d| d.join("vision_profiles.json"))
+        .filter(|p| p.exists());
+    if let Some(path) = path {
+        match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|t| serde_json::from_str::<HashMap<String, VisionScore>>(&t).map_err(|e| e.to_string()))
+        {
+            Ok(custom) => scores.extend(custom),
+            Err(e) => warn!("⚠️ [Vision] {} unreadable, using defaults: {}", path.display(), e),
+        }
+    }
+    scores
+}
+
+fn available(name: &str, cfg: &settings::Settings) -> bool {
+    match name {
+        "local" => !cfg.local_vision_model.trim().is_empty(),
+        "gpt" | "gemini" | "claude" | "llama" => {
+            secrets::api_key(name).is_some() && crate::provider_unavailable(name).is_none()
+        }
+        _ => false,
+    }
+}
+
+// 使えるものを順に試す。Ok の中身は (使ったモデル, 説明)
+pub async fn describe(prompt: &str, png_base64: &str) -> Result<(String, String), String> {
+    // ローカル専用モードは OCR。prompt は使わない（文字を読むだけ）
+    if crate::local_only::enabled() {
+        let text = ocr::read(png_base64).await?;
+        return Ok((
+            "ocr".to_string(),
+            format!(
+                "[OCR ({}): text on screen only, images and layout are not described]\n{}",
+                ocr::ENGINE,
+                text
+            ),
+        ));
+    }
+    let cfg = settings::current();
+    let candidates: Vec<(String, VisionScore)> = profiles()
+        .into_iter()
+        .filter(|(name, _)| available(name, &cfg))
+        .collect();
+    let order = core_vision::order(cfg.vision_provider.trim(), &candidates);
+    let run = core_vision::describe(&Models, &order, prompt, png_base64).await;
+    let used = run.used.unwrap_or_default();
+    if !run.failures.is_empty() && !used.is_empty() {
+        info!("👁️ [Vision] answered by {} after {} failure(s)", used, run.failures.len());
+    }
+    run.result.map(|text| (used, text))
+}
+
+// --- 画面の録画 (record_screen) ---
+const MAX_RECORD_SECS: u64 = 30;
+const MAX_FRAMES: usize = 30; // 1 秒に 2 コマまで
+const MAX_KEYFRAMES: usize = 6;
+const FRAME_WIDTH: u32 = 960; // 撮ったらすぐ縮める（30 コマでも数十 MB）
+const TILE_WIDTH: u32 = 640;
+const TILE_GAP: u32 = 8;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ScreenRecording {
+    pub seconds: u64,
+    pub frames: usize,
+    pub keyframes_ms: Vec<u64>, // 録画の開始から
+    pub sheet_base64: String,   // 並べた PNG（メモリの添付用）
+    pub description: String,
+}
+
+fn shrink(image: &RgbaImage) -> RgbaImage {
+    let (w, h) = image.dimensions();
+    if w <= FRAME_WIDTH {
+        return image.clone();
+    }
+    imageops::resize(image, FRAME_WIDTH, (h * FRAME_WIDTH / w).max(1), FilterType::Triangle)
+}
+
+fn capture_clip(seconds: u64) -> Result<Vec<(u64, RgbaImage)>, String> {
+    let screens = Screen::all().map_err(|e| e.to_string())?;
+    let screen = screens.first().ok_or("No screen found")?;
+    let count = ((seconds * 2) as usize).clamp(2, MAX_FRAMES);
+    let interval = Duration::from_millis(seconds * 1000 / (count as u64 - 1));
+    let started = Instant::now();
+    let mut frames = Vec::with_capacity(count);
+    for i in 0..count {
+        if let Some(wait) = (interval * i as u32).checked_sub(started.elapsed()) {
+            thread::sleep(wait);
+        }
+        let at = started.elapsed().as_millis() as u64;
+        let image = screen.capture().map_err(|e| e.to_string())?;
+        frames.push((at, shrink(&image)));
+    }
+    Ok(frames)
+}
+
+// 縮小したグレースケール同士の平均の差 (0.0〜1.0)
+fn change(a: &RgbaImage, b: &RgbaImage) -> f32 {
+    let small = |img: &RgbaImage| imageops::grayscale(&imageops::resize(img, 64, 36, FilterType::Triangle));
+    let (a, b) = (small(a), small(b));
+    let total: u64 = a
+        .pixels()
+        .zip(b.pixels())
+        .map(|(p, q)| (p.0[0] as i32 - q.0[0] as i32).unsigned_abs() as u64)
+        .sum();
+    total as f32 / (64.0 * 36.0 * 255.0)
+}
+
+// 左から右、上から下に時刻順
+fn contact_sheet(frames: &[&RgbaImage]) -> RgbaImage {
+    let n = frames.len() as u32;
+    let cols = if n <= 4 { n.min(2) } else { 3 };
+    let rows = n.div_ceil(cols);
+    let (w, h) = frames[0].dimensions();
+    let tile_h = (h * TILE_WIDTH / w).max(1);
+    let mut sheet = RgbaImage::from_pixel(
+        cols * TILE_WIDTH + (cols - 1) * TILE_GAP,
+        rows * tile_h + (rows - 1) * TILE_GAP,
+        Rgba([0, 0, 0, 255]),
+    );
+    for (i, frame) in frames.iter().enumerate() {
+        let tile = imageops::resize(*frame, TILE_WIDTH, tile_h, FilterType::Triangle);
+        let (col, row) = (i as u32 % cols, i as u32 / cols);
+        imageops::overlay(&mut sheet, &tile, (col * (TILE_WIDTH + TILE_GAP)) as i64, (row * (tile_h + TILE_GAP)) as i64);
+    }
+    sheet
+}
+
+pub async fn record_screen(app: &AppHandle, seconds: u64) -> Result<ScreenRecording, String> {
+    let seconds = seconds.clamp(1, MAX_RECORD_SECS);
+    info!("🎬 [Vision] recording the screen for {}s", seconds);
+    let (frames, keyframes_ms, sheet_base64) = tokio::task::spawn_blocking(move || -> Result<_, String> {
+        let frames = capture_clip(seconds)?;
+        let changes: Vec<f32> = (0..frames.len())
+            .map(|i| if i == 0 { 0.0 } else { change(&frames[i - 1].1, &frames[i].1) })
+            .collect();
+        let picked = core_vision::keyframes(&changes, MAX_KEYFRAMES);
+        let sheet = contact_sheet(&picked.iter().map(|&i| &frames[i].1).collect::<Vec<_>>());
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(sheet)
+            .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+            .map_err(|e| e.to_string())?;
+        let keyframes_ms: Vec<u64> = picked.iter().map(|&i| frames[i].0).collect();
+        Ok((frames.len(), keyframes_ms, general_purpose::STANDARD.encode(png)))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let times = keyframes_ms
+        .iter()
+        .map(|ms| format!("{:.1}s", *ms as f64 / 1000.0))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let prompt = prompts::render(
+        app,
+        "vision_clip",
+        &[
+            ("count", &keyframes_ms.len().to_string()),
+            ("seconds", &seconds.to_string()),
+            ("times", &times),
+        ],
+    );
+    info!("🎬 [Vision] {} frames, {} keyframes", frames, keyframes_ms.len());
+    let (_, description) = describe(&prompt, &sheet_base64).await?;
+    Ok(ScreenRecording {
+        seconds,
+        frames,
+        keyframes_ms,
+        sheet_base64,
+        description,
+    })
+}
@@ -1,33 +1,43 @@
 // src-tauri/src/web.rs
 use reqwest::header::USER_AGENT;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use serde::{Serialize, Deserialize};
+use tracing::{info, warn};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+const BROWSER_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+// 1 ページあたりに残す本文の長さ（要約モデルへの入力を抑える）
+const MAX_PAGE_CHARS: usize = 3000;
+// FETCH: で 1 ページから落とす上限（これを超えた分は読まない）
+const FETCH_MAX_BYTES: usize = 2 * 1024 * 1024;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub title: String,
     pub link: String,
     pub snippet: String,
+    // 百科事典の infobox 等の構造化データ（項目, 値）
+    #[serde(default)]
+    pub facts: Vec<(String, String)>,
 }
 
 pub async fn search_duckduckgo(query: &str) -> Result<Vec<SearchResult>, String> {
     // クエリの前後の空白を除去し、URLエンコード（念のため）
     let url = format!("https://html.duckduckgo.com/html/?q={}", query.trim());
     
-    println!("🌐 [Grok] Searching: [{}]", query.trim());
+    info!(query = %query.trim(), "🌐 [Grok] Searching");
 
-    let client = reqwest::Client::new();
+    let client = crate::ai::client_for("web")?;
     let res = client.get(&url)
         // 最新のChromeのふりをする
-        .header(USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+        .header(USER_AGENT, BROWSER_UA)
         .send()
         .await
         .map_err(|e| format!("Network error: {}", e))?;
 
     let html_text = res.text().await.map_err(|e| format!("Read error: {}", e))?;
-    
-    // デバッグ: 本当にHTMLが取れているか確認したければコメントアウトを外す
-    // println!("📄 HTML Length: {}", html_text.len());
 
     let document = Html::parse_document(&html_text);
 
@@ -44,7 +54,7 @@ pub async fn search_duckduckgo(query: &str) -> Result<Vec<SearchResult>, String>
         };
         
         let link = match element.select(&title_selector).next() {
-            Some(el) => el.value().attr("href").unwrap_or("").to_string(),
+            Some(el) => resolve_ddg_link(el.value().attr("href").unwrap_or("")),
             None => continue,
         };
 
@@ -54,34 +64,505 @@ pub async fn search_duckduckgo(query: &str) -> Result<Vec<SearchResult>, String>
         };
 
         if !title.is_empty() {
-            results.push(SearchResult { title, link, snippet });
+            results.push(SearchResult { title, link, snippet, facts: vec![] });
         }
-        if results.len() >= 5 { break; }
+        if results.len() >= crate::search::MAX_RESULTS { break; }
     }
 
     if results.is_empty() {
-        println!("⚠️ [Grok] No results found. (Maybe blocked?)");
+        warn!("⚠️ [Grok] No results found. (Maybe blocked?)");
     } else {
-        println!("✅ [Grok] Success! Found {} links.", results.len());
+        info!("✅ [Grok] Success! Found {} links.", results.len());
         // 最初の1件のタイトルを表示して確認
         if let Some(first) = results.first() {
-             println!("   Top result: {}", first.title);
+             info!(title = %first.title, "   Top result");
         }
     }
 
     Ok(results)
 }
 
-// src-tauri/src/web.rs の既存コードの末尾に追加
+// ---------- 百科事典 (Wikipedia REST API) ----------
+// 事実系の INQUIRY はまずここで引く。出典つきの要約と infobox の項目を返す
 
-// ★追加: Grokipedia検索（テスト用ダミー実装）
-// 常に「空の結果」を返すことで、lib.rs 側のフォールバック処理(DDGへの切り替え)を作動させる
-pub async fn search_grokipedia(query: &str) -> Result<Vec<SearchResult>, String> {
-    println!("📚 Grokipedia Search: '{}' (Simulating...)", query);
-    
-    // ここに将来的に本物のAPI実装を入れる
-    // 今は「該当なし」として空のベクタを返す
-    let results: Vec<SearchResult> = Vec::new();
+const WIKI_UA: &str = "AxisOS/0.1 (desktop assistant; https://github.com/mametora311-glitch/axis-os)";
+const WIKI_MAX_PAGES: usize = 3;
+const WIKI_MAX_FACTS: usize = 12;
+
+// "auto" の時はクエリに日本語が含まれていれば ja、それ以外は en
+pub fn wikipedia_lang(query: &str, setting: &str) -> String {
+    let setting = setting.trim().to_lowercase();
+    if !setting.is_empty() && setting != "auto" {
+        return setting;
+    }
+    let has_japanese = query.chars().any(|c| {
+        matches!(c, '\u{3040}'..='\u{30ff}' | '\u{4e00}'..='\u{9fff}' | '\u{ff66}'..='\u{ff9f}')
+    });
+    if has_japanese { "ja".to_string() } else { "en".to_string() }
+}
+
+async fn wiki_json(url: &str) -> Result<serde_json::Value, String> {
+    let client = crate::ai::client_for("web")?;
+    let res = client.get(url)
+        .header(USER_AGENT, WIKI_UA)
+        .send()
+        .await
+        .map_err(|e| crate::ai::describe_request_error("web", &e))?;
+    if !res.status().is_success() {
+        return Err(format!("Wikipedia HTTP {}", res.status()));
+    }
+    res.json().await.map_err(|e| format!("Wikipedia JSON Error: {}", e))
+}
+
+// infobox (表の th / td) を「項目: 値」に
+async fn wiki_infobox(lang: &str, key: &str) -> Vec<(String, String)> {
+    let url = format!("https://{}.wikipedia.org/api/rest_v1/page/html/{}", lang, key);
+    let Ok(client) = crate::ai::client_for("web") else { return vec![] };
+    let html = match client.get(&url).header(USER_AGENT, WIKI_UA).send().await {
+        Ok(res) if res.status().is_success() => res.text().await.unwrap_or_default(),
+        _ => return vec![],
+    };
+
+    let document = Html::parse_document(&html);
+    let row_sel = Selector::parse("table.infobox tr").unwrap();
+    let th_sel = Selector::parse("th").unwrap();
+    let td_sel = Selector::parse("td").unwrap();
+
+    let mut facts = Vec::new();
+    for row in document.select(&row_sel) {
+        let (Some(th), Some(td)) = (row.select(&th_sel).next(), row.select(&td_sel).next()) else { continue };
+        let label = clean_text(th);
+        let value = clean_text(td);
+        if label.is_empty() || value.is_empty() || value.chars().count() > 200 {
+            continue;
+        }
+        facts.push((label, value));
+        if facts.len() >= WIKI_MAX_FACTS { break; }
+    }
+    facts
+}
+
+pub async fn search_wikipedia(query: &str, lang: &str) -> Result<Vec<SearchResult>, String> {
+    info!(query = %query.trim(), "📚 [Wikipedia] Searching ({})", lang);
+
+    let search_url = reqwest::Url::parse_with_params(
+        &format!("https://{}.wikipedia.org/w/rest.php/v1/search/page", lang),
+        &[("q", query.trim()), ("limit", &WIKI_MAX_PAGES.to_string())],
+    )
+    .map_err(|e| e.to_string())?;
+    let found = wiki_json(search_url.as_str()).await?;
+    let keys: Vec<String> = found
+        .get("pages")
+        .and_then(|p| p.as_array())
+        .map(|pages| pages.iter().filter_map(|p| p.get("key")?.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
 
+    let mut results = Vec::new();
+    for (i, key) in keys.iter().enumerate() {
+        let summary = match wiki_json(&format!("https://{}.wikipedia.org/api/rest_v1/page/summary/{}", lang, key)).await {
+            Ok(v) => v,
+            Err(e) => {
+                warn!("⚠️ [Wikipedia] summary failed for {}: {}", key, e);
+                continue;
+            }
+        };
+        // 曖昧さ回避ページは中身がないので飛ばす
+        if summary.get("type").and_then(|t| t.as_str()) == Some("disambiguation") {
+            continue;
+        }
+        let get = |ptr: &str| summary.pointer(ptr).and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let extract = get("/extract");
+        if extract.is_empty() {
+            continue;
+        }
+        let description = get("/description");
+        let snippet = if description.is_empty() { extract } else { format!("{} — {}", description, extract) };
+        let mut link = get("/content_urls/desktop/page");
+        if link.is_empty() {
+            link = format!("https://{}.wikipedia.org/wiki/{}", lang, key);
+        }
+
+        // infobox は一番上のページだけ（HTML 全体を取るので重い）
+        let facts = if i == 0 { wiki_infobox(lang, key).await } else { vec![] };
+        results.push(SearchResult { title: get("/title"), link, snippet, facts });
+    }
+
+    info!("📚 [Wikipedia] {} articles", results.len());
     Ok(results)
-}
\ No newline at end of file
+}
+
+// DDG の結果リンクは "//duckduckgo.com/l/?uddg=<本当のURL>&rut=..." のリダイレクトなので中身を取り出す
+fn resolve_ddg_link(href: &str) -> String {
+    let absolute = if href.starts_with("//") { format!("https:{}", href) } else { href.to_string() };
+    match reqwest::Url::parse(&absolute) {
+        Ok(u) => u
+            .query_pairs()
+            .find(|(k, _)| k == "uddg")
+            .map(|(_, v)| v.to_string())
+            .unwrap_or(absolute),
+        Err(_) => absolute,
+    }
+}
+
+// ---------- ページ本文の取得と抽出 ----------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageExtract {
+    pub url: String,
+    pub title: String,
+    pub text: String,
+}
+
+fn clean_text(el: ElementRef) -> String {
+    el.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// 本文以外（メニュー・広告・フッター等）の中にある要素か
+fn in_boilerplate(el: ElementRef) -> bool {
+    el.ancestors().filter_map(ElementRef::wrap).any(|a| {
+        let v = a.value();
+        if matches!(v.name(), "nav" | "header" | "footer" | "aside" | "form" | "script" | "style" | "noscript") {
+            return true;
+        }
+        let marker = format!("{} {}", v.attr("class").unwrap_or(""), v.attr("id").unwrap_or("")).to_lowercase();
+        ["comment", "sidebar", "footer", "menu", "banner", "cookie", "share", "related", "advert"]
+            .iter()
+            .any(|k| marker.contains(k))
+    })
+}
+
+// readability 風の本文抽出:
+// 段落 (<p>) を親要素ごとに集計し、文章量が一番多いブロックを本文とみなす
+pub fn extract_readable(html: &str, max_chars: usize) -> (String, String) {
+    let document = Html::parse_document(html);
+    let title_sel = Selector::parse("title").unwrap();
+    let para_sel = Selector::parse("p").unwrap();
+    let block_sel = Selector::parse("h1, h2, h3, p, li, pre, blockquote").unwrap();
+
+    let title = document
+        .select(&title_sel)
+        .next()
+        .map(clean_text)
+        .unwrap_or_default();
+
+    let mut scores: HashMap<_, usize> = HashMap::new();
+    for p in document.select(&para_sel) {
+        if in_boilerplate(p) {
+            continue;
+        }
+        let len = clean_text(p).chars().count();
+        // 短い段落（ボタン文言・キャプション等）は数えない
+        if len < 25 {
+            continue;
+        }
+        if let Some(parent) = p.parent().and_then(ElementRef::wrap) {
+            *scores.entry(parent.id()).or_default() += len;
+            // 祖父母にも半分加点（段落が div で小分けにされている記事向け）
+            if let Some(grand) = parent.parent().and_then(ElementRef::wrap) {
+                *scores.entry(grand.id()).or_default() += len / 2;
+            }
+        }
+    }
+
+    let root = scores
+        .into_iter()
+        .max_by_key(|(_, score)| *score)
+        .and_then(|(id, _)| document.tree.get(id))
+        .and_then(ElementRef::wrap)
+        .unwrap_or_else(|| document.root_element());
+
+    let mut text = String::new();
+    for block in root.select(&block_sel) {
+        if in_boilerplate(block) {
+            continue;
+        }
+        // li の中の p など、入れ子で二重に拾わない
+        if block.ancestors().filter_map(ElementRef::wrap).take_while(|a| a.id() != root.id()).any(|a| {
+            matches!(a.value().name(), "p" | "li" | "pre" | "blockquote")
+        }) {
+            continue;
+        }
+        let line = clean_text(block);
+        if line.is_empty() {
+            continue;
+        }
+        text.push_str(&line);
+        text.push('\n');
+        if text.chars().count() >= max_chars {
+            break;
+        }
+    }
+
+    let text: String = text.chars().take(max_chars).collect();
+    (title, text.trim().to_string())
+}
+
+// 検索結果の URL も外から来たものなので FETCH: と同じ確かめ方で (fetch_url)
+pub async fn fetch_page(url: &str) -> Result<PageExtract, String> {
+    let cfg = crate::settings::current();
+    fetch_url(url, &cfg.fetch_allow_domains, &cfg.fetch_deny_domains, MAX_PAGE_CHARS).await
+}
+
+// 上限バイト数までだけ読む（巨大なページやファイルで固まらないように）
+async fn read_capped(mut res: reqwest::Response, max_bytes: usize) -> Result<String, String> {
+    let mut buf: Vec<u8> = Vec::new();
+    while let Some(chunk) = res.chunk().await.map_err(|e| format!("Read error: {}", e))? {
+        let room = max_bytes.saturating_sub(buf.len());
+        buf.extend_from_slice(&chunk[..chunk.len().min(room)]);
+        if buf.len() >= max_bytes {
+            info!("✂️ [Web] page truncated at {} bytes", max_bytes);
+            break;
+        }
+    }
+    Ok(String::from_utf8_lossy(&buf).to_string())
+}
+
+async fn fetch_with(client: &reqwest::Client, url: &str, max_chars: usize) -> Result<PageExtract, String> {
+    crate::sharing::record("web", "fetch", "", url, (0, 0));
+    let res = client.get(url)
+        .header(USER_AGENT, BROWSER_UA)
+        .send()
+        .await
+        .map_err(|e| match std::error::Error::source(&e) {
+            // redirect policy で止めた理由は source 側にある
+            Some(why) if e.is_redirect() => format!("Network Error: web: {}", why),
+            _ => crate::ai::describe_request_error("web", &e),
+        })?;
+
+    if !res.status().is_success() {
+        return Err(format!("HTTP {} from {}", res.status(), url));
+    }
+    let content_type = res
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("text/html")
+        .to_lowercase();
+    let is_plain = content_type.contains("text/plain") || content_type.contains("markdown");
+    if !content_type.contains("html") && !is_plain {
+        return Err(format!("not an HTML page: {} ({})", url, content_type));
+    }
+    let final_url = res.url().to_string();
+
+    let body = read_capped(res, FETCH_MAX_BYTES).await?;
+    let (title, text) = if is_plain {
+        (String::new(), body.chars().take(max_chars).collect::<String>().trim().to_string())
+    } else {
+        extract_readable(&body, max_chars)
+    };
+    if text.is_empty() {
+        return Err(format!("no readable content: {}", url));
+    }
+    Ok(PageExtract { url: final_url, title, text })
+}
+
+// ---------- FETCH: <url> ----------
+// モデルが選んだ URL も来るので、スキーム・宛先を先に確かめる
+
+fn host_matches(host: &str, domain: &str) -> bool {
+    let domain = domain.trim().trim_start_matches("*.").to_lowercase();
+    !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+}
+
+// FETCH: でたどるリダイレクトの上限
+const FETCH_MAX_REDIRECTS: usize = 5;
+
+fn is_private_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0 // 0.0.0.0/8
+        || (a == 100 && (b & 0xc0) == 64) // CGNAT 100.64.0.0/10
+        || (a == 198 && (b & 0xfe) == 18) // 198.18.0.0/15
+        || a >= 240
+}
+
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_v4(ip),
+        IpAddr::V6(ip) => {
+            let s = ip.segments();
+            // ::ffff:a.b.c.d / ::a.b.c.d（::1 と :: もここで 0.0.0.x になる）/ NAT64 の 64:ff9b::a.b.c.d は中の IPv4 で
+            if let Some(v4) = ip.to_ipv4() {
+                return is_private_v4(v4);
+            }
+            if s[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [hi, lo] = [s[6], s[7]];
+                return is_private_v4(Ipv4Addr::new(
+                    (hi >> 8) as u8,
+                    hi as u8,
+                    (lo >> 8) as u8,
+                    lo as u8,
+                ));
+            }
+            ip.is_multicast() || (s[0] & 0xfe00) == 0xfc00 || (s[0] & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+// ループバック / プライベート IP / ローカル名は allow に書かない限り拒否
+fn is_private_host(host: &str) -> bool {
+    if host == "localhost" || host.ends_with(".localhost") || host.ends_with(".local") || host.ends_with(".internal") {
+        return true;
+    }
+    match host.trim_matches(|c| c == '[' || c == ']').parse::<IpAddr>() {
+        Ok(ip) => is_private_ip(ip),
+        Err(_) => false,
+    }
+}
+
+// 名前を引いた先が内側のアドレスなら拒否（公開名で 127.0.0.1 や 169.254.169.254 を指すもの）
+fn check_resolved(host: &str, addrs: &[SocketAddr]) -> Result<(), String> {
+    if addrs.is_empty() {
+        return Err(format!("{} did not resolve", host));
+    }
+    match addrs.iter().find(|a| is_private_ip(a.ip())) {
+        Some(a) => Err(format!("{} resolves to a local / private address ({})", host, a.ip())),
+        None => Ok(()),
+    }
+}
+
+// FETCH のクライアントの名前解決。引いた先が内側なら繋がない（最初の宛先もリダイレクト先も）
+// 確かめたアドレスにそのまま繋ぐので、確かめた後に引き直させる DNS rebinding も効かない
+// IP をそのまま書いた URL は名前を引かないので check_fetch_url の is_private_host で止める
+struct PublicResolver {
+    allow: Vec<String>,
+}
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_lowercase();
+        let allowed = self.allow.iter().any(|d| host_matches(&host, d));
+        Box::pin(async move {
+            // ポートは reqwest が URL のものに差し替える
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await
+                .map_err(|e| format!("cannot resolve {}: {}", host, e))?
+                .collect();
+            if !allowed {
+                check_resolved(&host, &addrs)?;
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+pub fn check_fetch_url(url: &str, allow: &[String], deny: &[String]) -> Result<reqwest::Url, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("invalid URL '{}': {}", url.trim(), e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("only http / https can be fetched: {}", parsed));
+    }
+    let host = parsed.host_str().unwrap_or("").to_lowercase();
+    if host.is_empty() {
+        return Err(format!("URL has no host: {}", parsed));
+    }
+    if deny.iter().any(|d| host_matches(&host, d)) {
+        return Err(format!("{} is blocked by fetch_deny_domains", host));
+    }
+    let allowed = allow.iter().any(|d| host_matches(&host, d));
+    if !allow.is_empty() && !allowed {
+        return Err(format!("{} is not in fetch_allow_domains", host));
+    }
+    if is_private_host(&host) && !allowed {
+        return Err(format!("{} is a local / private address (add it to fetch_allow_domains to allow)", host));
+    }
+    Ok(parsed)
+}
+
+pub async fn fetch_url(url: &str, allow: &[String], deny: &[String], max_chars: usize) -> Result<PageExtract, String> {
+    let parsed = check_fetch_url(url, allow, deny)?;
+    info!(query = %parsed, "🌐 [Fetch]");
+    crate::local_only::check("web")?;
+    let t = crate::ai::timeouts_for("web");
+
+    // リダイレクト先は辿る前に 1 歩ずつ同じ規則で確かめる（内側へ飛ばされてから弾くのでは遅い）
+    // 宛先のアドレスは PublicResolver が引く時に確かめる（redirect policy の中では名前を引かない）
+    let (hop_allow, hop_deny) = (allow.to_vec(), deny.to_vec());
+    let client = reqwest::Client::builder()
+        .connect_timeout(t.connect)
+        .timeout(t.request)
+        .dns_resolver(Arc::new(PublicResolver {
+            allow: allow.to_vec(),
+        }))
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= FETCH_MAX_REDIRECTS {
+                return attempt.error(format!("more than {} redirects", FETCH_MAX_REDIRECTS));
+            }
+            match check_fetch_url(attempt.url().as_str(), &hop_allow, &hop_deny) {
+                Ok(_) => attempt.follow(),
+                Err(e) => attempt.error(format!("redirected: {}", e)),
+            }
+        }))
+        .build()
+        .map_err(|e| e.to_string())?;
+    fetch_with(&client, parsed.as_str(), max_chars).await
+}
+
+// 上位 n 件を同時に取りに行く（失敗したページは飛ばす）
+pub async fn fetch_top(results: &[SearchResult], n: usize) -> Vec<PageExtract> {
+    let handles: Vec<_> = results
+        .iter()
+        .filter(|r| r.link.starts_with("http"))
+        .take(n)
+        .map(|r| {
+            let url = r.link.clone();
+            tauri::async_runtime::spawn(async move { fetch_page(&url).await })
+        })
+        .collect();
+
+    let mut pages = Vec::new();
+    for h in handles {
+        match h.await {
+            Ok(Ok(page)) => pages.push(page),
+            Ok(Err(e)) => warn!("⚠️ [Web] fetch skipped: {}", e),
+            Err(e) => warn!("⚠️ [Web] fetch task failed: {}", e),
+        }
+    }
+    info!("📄 [Web] fetched {}/{} pages", pages.len(), n.min(results.len()));
+    pages
+}
+
+// 取得した本文を要約して system_context 用のダイジェストにする
+// 要約モデルが使えない時は各ページの冒頭をそのまま並べる
+pub async fn digest(
+    app: &tauri::AppHandle,
+    query: &str,
+    pages: &[PageExtract],
+    alias: &str,
+    model: &str,
+) -> String {
+    if pages.is_empty() {
+        return String::new();
+    }
+    let mut material = String::new();
+    for (i, p) in pages.iter().enumerate() {
+        material.push_str(&axis_core::untrusted::wrap(
+            "web_page",
+            &format!("[{}] {} ({})\n{}", i + 1, p.title, p.url, p.text),
+        ));
+        material.push('\n');
+    }
+
+    let sys = crate::prompts::render(app, "web_digest", &[]);
+    let user = format!("Query: {}\n\n{}", query, material);
+
+    match crate::ai::call_alias(alias, model, &sys, &user).await {
+        Ok(summary) if !summary.trim().is_empty() => summary.trim().to_string(),
+        other => {
+            if let Err(e) = other {
+                warn!("⚠️ [Web] digest failed ({}), using raw excerpts: {}", alias, e);
+            }
+            pages
+                .iter()
+                .enumerate()
+                .map(|(i, p)| format!("[{}] {}", i + 1, p.text.chars().take(600).collect::<String>()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
@@ -0,0 +1,311 @@
+// src-tauri/src/win32.rs
+//
+// これまで PowerShell を立ち上げていた問い合わせを windows クレートで直接（1 回 200〜500ms かかり、AV のふるまい検知にも触れていた）
+// - active_window / idle_ms: 前面ウィンドウの (タイトル, プロセス名) と無操作時間 (observer.rs / idle.rs)
+// - top_windows: タスクバーに出るトップレベルのウィンドウ (EnumWindows。APPS / PROCESSES のタイトル。system.rs)
+// - show_window: タイトルかプロセス名で探して ShowWindow / SetForegroundWindow (FOCUS / MINIMIZE。shell.rs)
+// - start_apps: スタートメニューのアプリ (AppsFolder を COM で列挙。Get-StartApps と同じ AppID。app_catalog.rs)
+// - media_session: 今のメディアセッションを再生 / 一時停止 (GlobalSystemMediaTransportControls。media.rs)
+//   Ok(false) は再生中のセッションが無い / 受け付けなかった
+// - Err はこの方法が使えなかった時だけ。呼び出し側は従来の PowerShell 版に落とす（見つからないのは Ok(None) / 空）
+// - Windows 以外はどれも Err
+
+#[derive(Debug, Clone)]
+pub struct TopWindow {
+    pub title: String,
+    pub pid: u32,
+    pub process: String, // 拡張子なし ("chrome")
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use std::ffi::c_void;
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::{CloseHandle, BOOL, HANDLE, HWND, LPARAM};
+    use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
+    use windows::Win32::System::Com::{
+        CoInitializeEx, CoTaskMemFree, CoUninitialize, IBindCtx, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::System::Threading::{
+        AttachThreadInput, GetCurrentThreadId, OpenProcess, QueryFullProcessImageNameW,
+        PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+    use windows::Win32::UI::Shell::{
+        BHID_EnumItems, FOLDERID_AppsFolder, IEnumShellItems, IShellItem, SHGetKnownFolderItem,
+        KF_FLAG_DEFAULT, SIGDN, SIGDN_NORMALDISPLAY, SIGDN_PARENTRELATIVEPARSING,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetForegroundWindow, GetWindow, GetWindowLongW, GetWindowTextW,
+        GetWindowThreadProcessId, IsWindowVisible, SetForegroundWindow, ShowWindow, GWL_EXSTYLE,
+        GW_OWNER, SHOW_WINDOW_CMD, WS_EX_TOOLWINDOW,
+    };
+
+    fn lower_contains(haystack: &str, needle: &str) -> bool {
+        haystack
+            .to_lowercase()
+            .contains(&needle.trim().to_lowercase())
+    }
+
+    // FOCUS / MINIMIZE の宛先。タイトルを先に、無ければプロセス名で（前面から z 順の最初のもの）
+    fn pick(windows: &[TopWindow], target: &str) -> Option<usize> {
+        let process = target.trim().trim_end_matches(".exe");
+        windows
+            .iter()
+            .position(|w| lower_contains(&w.title, target))
+            .or_else(|| {
+                windows
+                    .iter()
+                    .position(|w| lower_contains(&w.process, process))
+            })
+    }
+
+    fn title_of(hwnd: HWND) -> String {
+        let mut buf = [0u16; 512];
+        let len = unsafe { GetWindowTextW(hwnd, &mut buf) }.max(0) as usize;
+        String::from_utf16_lossy(&buf[..len])
+    }
+
+    fn pid_of(hwnd: HWND) -> u32 {
+        let mut pid = 0u32;
+        unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+        pid
+    }
+
+    // 実行ファイル名（拡張子なし）。権限の高いプロセス等で読めなければ空
+    fn process_name(pid: u32) -> String {
+        unsafe {
+            let Ok(handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+                return String::new();
+            };
+            let mut buf = [0u16; 1024];
+            let mut len = buf.len() as u32;
+            let ok = QueryFullProcessImageNameW(
+                handle,
+                PROCESS_NAME_WIN32,
+                PWSTR(buf.as_mut_ptr()),
+                &mut len,
+            )
+            .is_ok();
+            let _ = CloseHandle(handle);
+            if !ok {
+                return String::new();
+            }
+            let path = String::from_utf16_lossy(&buf[..len as usize]);
+            std::path::Path::new(&path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        }
+    }
+
+    pub fn active_window() -> Result<(String, String), String> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        if hwnd.0.is_null() {
+            return Err("no foreground window".to_string());
+        }
+        Ok((title_of(hwnd), process_name(pid_of(hwnd))))
+    }
+
+    pub fn idle_ms() -> Option<u64> {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+            return None;
+        }
+        Some(unsafe { GetTickCount() }.wrapping_sub(info.dwTime) as u64)
+    }
+
+    // 仮想デスクトップの別の画面 / 中断中のストアアプリなど、見えるはずでも出ていないもの
+    fn cloaked(hwnd: HWND) -> bool {
+        let mut value = 0u32;
+        unsafe {
+            DwmGetWindowAttribute(
+                hwnd,
+                DWMWA_CLOAKED,
+                &mut value as *mut u32 as *mut c_void,
+                std::mem::size_of::<u32>() as u32,
+            )
+        }
+        .map(|_| value != 0)
+        .unwrap_or(false)
+    }
+
+    // Alt+Tab に出るもの: 見えていて、持ち主が無く、ツールウィンドウでなく、タイトルがある
+    fn listed(hwnd: HWND) -> bool {
+        unsafe {
+            let owned = GetWindow(hwnd, GW_OWNER)
+                .map(|o| !o.0.is_null())
+                .unwrap_or(false);
+            let tool = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0 != 0;
+            IsWindowVisible(hwnd).as_bool() && !owned && !tool && !cloaked(hwnd)
+        }
+    }
+
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let found = &mut *(lparam.0 as *mut Vec<HWND>);
+        if listed(hwnd) && !title_of(hwnd).trim().is_empty() {
+            found.push(hwnd);
+        }
+        true.into()
+    }
+
+    fn enum_windows() -> Result<Vec<HWND>, String> {
+        let mut found: Vec<HWND> = Vec::new();
+        unsafe { EnumWindows(Some(collect), LPARAM(&mut found as *mut Vec<HWND> as isize)) }
+            .map_err(|e| format!("EnumWindows failed: {}", e))?;
+        Ok(found)
+    }
+
+    fn describe(hwnd: HWND) -> TopWindow {
+        let pid = pid_of(hwnd);
+        TopWindow {
+            title: title_of(hwnd).trim().to_string(),
+            pid,
+            process: process_name(pid),
+        }
+    }
+
+    pub fn top_windows() -> Result<Vec<TopWindow>, String> {
+        Ok(enum_windows()?.into_iter().map(describe).collect())
+    }
+
+    // 裏にいるプロセスからは SetForegroundWindow が効かないことがあるので、前面のスレッドに入力をつないでもう一度
+    unsafe fn bring_to_front(hwnd: HWND) -> bool {
+        if SetForegroundWindow(hwnd).as_bool() {
+            return true;
+        }
+        let front = GetWindowThreadProcessId(GetForegroundWindow(), None);
+        let own = GetCurrentThreadId();
+        if front == 0 || front == own {
+            return false;
+        }
+        let _ = AttachThreadInput(own, front, true);
+        let ok = SetForegroundWindow(hwnd).as_bool();
+        let _ = AttachThreadInput(own, front, false);
+        ok
+    }
+
+    pub fn show_window(
+        target: &str,
+        show_cmd: i32,
+        bring_front: bool,
+    ) -> Result<Option<String>, String> {
+        let handles = enum_windows()?;
+        let windows: Vec<TopWindow> = handles.iter().map(|&hwnd| describe(hwnd)).collect();
+        let Some(i) = pick(&windows, target) else {
+            return Ok(None);
+        };
+        let (hwnd, found) = (handles[i], &windows[i]);
+        unsafe {
+            let _ = ShowWindow(hwnd, SHOW_WINDOW_CMD(show_cmd));
+            if bring_front && !bring_to_front(hwnd) {
+                tracing::warn!("⚠️ [Win32] could not bring '{}' to the front", found.title);
+            }
+        }
+        Ok(Some(found.title.clone()))
+    }
+
+    unsafe fn display_name(item: &IShellItem, kind: SIGDN) -> Option<String> {
+        let name = item.GetDisplayName(kind).ok()?;
+        let text = name.to_string().ok();
+        CoTaskMemFree(Some(name.0 as *const c_void));
+        text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+    }
+
+    unsafe fn list_apps_folder() -> Result<Vec<(String, String)>, String> {
+        let folder: IShellItem =
+            SHGetKnownFolderItem(&FOLDERID_AppsFolder, KF_FLAG_DEFAULT, HANDLE::default())
+                .map_err(|e| format!("AppsFolder is unavailable: {}", e))?;
+        let items: IEnumShellItems = folder
+            .BindToHandler(None::<&IBindCtx>, &BHID_EnumItems)
+            .map_err(|e| format!("AppsFolder could not be listed: {}", e))?;
+        let mut apps = Vec::new();
+        loop {
+            let mut batch = [None];
+            let mut fetched = 0u32;
+            if items.Next(&mut batch, Some(&mut fetched)).is_err() || fetched == 0 {
+                break;
+            }
+            let Some(item) = batch[0].take() else {
+                break;
+            };
+            // 親 (AppsFolder) から見た名前が AppID（shell:AppsFolder\<id> で起動できる）
+            if let (Some(id), Some(name)) = (
+                display_name(&item, SIGDN_PARENTRELATIVEPARSING),
+                display_name(&item, SIGDN_NORMALDISPLAY),
+            ) {
+                apps.push((id, name));
+            }
+        }
+        Ok(apps)
+    }
+
+    pub fn media_session(play: bool) -> Result<bool, String> {
+        use windows::Media::Control::GlobalSystemMediaTransportControlsSessionManager;
+        let manager = GlobalSystemMediaTransportControlsSessionManager::RequestAsync()
+            .and_then(|op| op.get())
+            .map_err(|e| e.to_string())?;
+        let Ok(session) = manager.GetCurrentSession() else {
+            return Ok(false);
+        };
+        let op = if play {
+            session.TryPlayAsync()
+        } else {
+            session.TryPauseAsync()
+        };
+        op.and_then(|op| op.get()).map_err(|e| e.to_string())
+    }
+
+    pub fn start_apps() -> Result<Vec<(String, String)>, String> {
+        unsafe {
+            let init = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            let result = list_apps_folder();
+            if init.is_ok() {
+                CoUninitialize();
+            }
+            result
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::*;
+
+    const UNSUPPORTED: &str = "native window calls are only available on Windows";
+
+    pub fn active_window() -> Result<(String, String), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn idle_ms() -> Option<u64> {
+        None
+    }
+
+    pub fn top_windows() -> Result<Vec<TopWindow>, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn show_window(
+        _target: &str,
+        _show_cmd: i32,
+        _bring_front: bool,
+    ) -> Result<Option<String>, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn start_apps() -> Result<Vec<(String, String)>, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn media_session(_play: bool) -> Result<bool, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+pub use platform::{active_window, idle_ms, media_session, show_window, start_apps, top_windows};
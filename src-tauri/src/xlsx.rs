@@ -0,0 +1,263 @@
+// src-tauri/src/xlsx.rs
+//
+// SAVE: <name>.xlsx ||| <表> で本物の Excel ファイルを作る
+// - 表の書き方（どれでも可）:
+//   {"sheets": [{"name": "...", "headers": [...], "rows": [[...], ...]}]}
+//   {"headers": [...], "rows": [[...], ...]} / [[見出し...], [値...]] / [{"列": 値, ...}, ...] / CSV・TSV
+// - 1 行目は見出し（太字 + 背景色、固定表示、オートフィルタ）。列幅は中身の長さから
+// - 数値に見える値は数値セルにする（"007" のような先頭 0 付きは文字列のまま）
+// - 書き出しは rust_xlsxwriter に任せる（OOXML は手で組まない）
+
+use rust_xlsxwriter::{Color, Format, Workbook, Worksheet, XlsxError};
+use serde_json::Value;
+
+const MAX_SHEETS: usize = 20;
+// Excel の上限
+const MAX_ROWS: usize = 1_048_576;
+const MAX_COLS: usize = 16_384;
+
+pub struct Sheet {
+    pub name: String,
+    pub rows: Vec<Vec<Value>>, // 先頭が見出し
+}
+
+fn cell_text(v: &Value) -> String {
+    match v {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+// 1 つの表 (headers + rows / 配列の配列 / オブジェクトの配列)
+fn table_from_json(v: &Value) -> Option<Vec<Vec<Value>>> {
+    if let (Some(headers), Some(rows)) = (v.get("headers"), v.get("rows")) {
+        let mut out = vec![headers.as_array()?.clone()];
+        for row in rows.as_array()? {
+            out.push(row.as_array().cloned().unwrap_or_else(|| vec![row.clone()]));
+        }
+        return Some(out);
+    }
+    let list = v.as_array()?;
+    if list.iter().all(|r| r.is_array()) {
+        return Some(list.iter().filter_map(|r| r.as_array().cloned()).collect());
+    }
+    if list.iter().all(|r| r.is_object()) {
+        let mut headers: Vec<String> = Vec::new();
+        for obj in list.iter().filter_map(|r| r.as_object()) {
+            for k in obj.keys() {
+                if !headers.contains(k) {
+                    headers.push(k.clone());
+                }
+            }
+        }
+        let mut out = vec![headers.iter().map(|h| Value::String(h.clone())).collect()];
+        for obj in list.iter().filter_map(|r| r.as_object()) {
+            out.push(
+                headers
+                    .iter()
+                    .map(|h| obj.get(h).cloned().unwrap_or(Value::Null))
+                    .collect(),
+            );
+        }
+        return Some(out);
+    }
+    None
+}
+
+// ダブルクォート対応の CSV / TSV
+fn parse_delimited(text: &str) -> Vec<Vec<Value>> {
+    let sep = if text.lines().next().is_some_and(|l| l.contains('\t')) {
+        '\t'
+    } else {
+        ','
+    };
+    let mut rows = Vec::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = !quoted,
+                c if c == sep && !quoted => {
+                    row.push(Value::String(field.trim().to_string()));
+                    field.clear();
+                }
+                c => field.push(c),
+            }
+        }
+        row.push(Value::String(field.trim().to_string()));
+        rows.push(row);
+    }
+    rows
+}
+
+fn clean_sheet_name(name: &str, index: usize) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(31)
+        .collect();
+    if name.trim().is_empty() {
+        format!("Sheet{}", index + 1)
+    } else {
+        name.trim().to_string()
+    }
+}
+
+pub fn parse_sheets(content: &str) -> Result<Vec<Sheet>, String> {
+    let content = content.trim();
+    let mut sheets = Vec::new();
+    match serde_json::from_str::<Value>(content) {
+        Ok(v) => {
+            if let Some(list) = v.get("sheets").and_then(|s| s.as_array()) {
+                for (i, s) in list.iter().take(MAX_SHEETS).enumerate() {
+                    let rows = table_from_json(s)
+                        .ok_or_else(|| format!("sheet {} has no headers/rows", i + 1))?;
+                    let name = s.get("name").and_then(|n| n.as_str()).unwrap_or("");
+                    sheets.push(Sheet {
+                        name: clean_sheet_name(name, i),
+                        rows,
+                    });
+                }
+            } else {
+                let rows = table_from_json(&v).ok_or(
+                    "xlsx content must be {\"headers\": [...], \"rows\": [[...]]}, an array of rows, or CSV",
+                )?;
+                let name = v.get("name").and_then(|n| n.as_str()).unwrap_or("");
+                sheets.push(Sheet {
+                    name: clean_sheet_name(name, 0),
+                    rows,
+                });
+            }
+        }
+        Err(_) => sheets.push(Sheet {
+            name: "Sheet1".to_string(),
+            rows: parse_delimited(content),
+        }),
+    }
+    if sheets.iter().all(|s| s.rows.is_empty()) {
+        return Err("xlsx content has no rows".to_string());
+    }
+    Ok(sheets)
+}
+
+pub fn escape(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\t'))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// "1,234" や "007" は Excel に任せず文字列のまま
+fn as_number(v: &Value) -> Option<f64> {
+    match v {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => {
+            let s = s.trim();
+            let digits = s.trim_start_matches('-');
+            let leading_zero =
+                digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+            if s.is_empty() || leading_zero || s.starts_with('+') {
+                return None;
+            }
+            s.parse::<f64>().ok().filter(|f| f.is_finite())
+        }
+        _ => None,
+    }
+}
+
+fn display_width(s: &str) -> usize {
+    s.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+fn xlsx_err(e: XlsxError) -> String {
+    format!("xlsx Error: {}", e)
+}
+
+fn write_sheet(sheet: &mut Worksheet, rows: &[Vec<Value>], header: &Format) -> Result<(), String> {
+    let cols = rows.iter().map(|r| r.len()).max().unwrap_or(0).max(1);
+    if rows.len() > MAX_ROWS || cols > MAX_COLS {
+        return Err(format!(
+            "xlsx Error: table is too large ({} rows x {} columns)",
+            rows.len(),
+            cols
+        ));
+    }
+    let mut widths = vec![8usize; cols];
+    for (r, row) in rows.iter().enumerate() {
+        for (c, v) in row.iter().enumerate() {
+            let w = cell_text(v).lines().map(display_width).max().unwrap_or(0);
+            widths[c] = widths[c].max(w + 2).min(60);
+            let (r, c) = (r as u32, c as u16);
+            // 見出し行は数値でも文字列 + 見出しスタイル
+            match (r, v) {
+                (_, Value::Null) => continue,
+                (0, v) => sheet.write_string_with_format(r, c, cell_text(v), header),
+                (_, Value::Bool(b)) => sheet.write_boolean(r, c, *b),
+                (_, v) => match as_number(v) {
+                    Some(n) => sheet.write_number(r, c, n),
+                    None => sheet.write_string(r, c, cell_text(v)),
+                },
+            }
+            .map_err(xlsx_err)?;
+        }
+    }
+    for (c, w) in widths.iter().enumerate() {
+        sheet
+            .set_column_width(c as u16, *w as f64)
+            .map_err(xlsx_err)?;
+    }
+    if rows.len() > 1 {
+        sheet.set_freeze_panes(1, 0).map_err(xlsx_err)?;
+        sheet
+            .autofilter(0, 0, (rows.len() - 1) as u32, (cols - 1) as u16)
+            .map_err(xlsx_err)?;
+    }
+    Ok(())
+}
+
+pub fn build(sheets: &[Sheet]) -> Result<Vec<u8>, String> {
+    let mut workbook = Workbook::new();
+    let header = Format::new()
+        .set_bold()
+        .set_background_color(Color::RGB(0xD9E1F2));
+    let mut used: Vec<String> = Vec::new();
+    for sheet in sheets {
+        // 同じ名前のシートは Excel が開けないので番号を付ける
+        let mut name = sheet.name.clone();
+        let mut k = 2;
+        while used.iter().any(|u| u.eq_ignore_ascii_case(&name)) {
+            name = format!("{} {}", sheet.name.chars().take(27).collect::<String>(), k);
+            k += 1;
+        }
+        used.push(name.clone());
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&name).map_err(xlsx_err)?;
+        write_sheet(worksheet, &sheet.rows, &header)?;
+    }
+    workbook.save_to_buffer().map_err(xlsx_err)
+}
+
+// プレビュー用（先頭のシートをタブ区切りで）
+pub fn preview(sheets: &[Sheet]) -> String {
+    sheets
+        .first()
+        .map(|s| {
+            s.rows
+                .iter()
+                .map(|r| r.iter().map(cell_text).collect::<Vec<_>>().join("\t"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
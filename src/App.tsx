@@ -21,6 +21,55 @@ interface InteractionLog {
   user_tokens: AxisToken[];
   ai_response: string;
   provider_used: string;
+  feedback?: ResponseFeedback;
+  meta?: ResponseMeta;
+  branch_of?: string; // regenerate_response で作った答え直しの元ログ
+}
+
+interface ResponseMeta {
+  target: string;
+  model: string;
+  task_type: string;
+  strategy: string;
+  reason: string;
+  session_override: string | null; // set_session_provider で固定中のプロバイダ
+  failover: boolean;
+  latency_ms: number;
+  candidates?: EnsembleCandidate[]; // アンサンブル時の各モデルの回答
+  ensemble_choice?: string;
+  critic?: CriticReport; // コード / 数学の見直し結果
+  persona?: string; // set_persona で選ばれていたペルソナ
+  files?: SavedFile[]; // SAVE で書き出したファイル
+}
+
+interface SavedFile {
+  path: string;
+  name: string;
+  size: number;
+  preview: string; // 先頭の数行
+}
+
+interface CriticReport {
+  reviewer: string;
+  model: string;
+  verdict: string; // ok / fix / error
+  critique: string;
+  corrected: boolean;
+  latency_ms: number;
+}
+
+interface EnsembleCandidate {
+  provider: string;
+  model: string;
+  text: string;
+  error: string | null;
+  latency_ms: number;
+}
+
+interface ResponseFeedback {
+  rating: number; // +1 / -1
+  comment?: string | null;
+  rated_at: number;
 }
 
 // --- System Vitals ---
@@ -30,6 +79,23 @@ interface SystemStats {
   memory_total: number;   // bytes
   battery_level: number;  // 0-100
   is_charging: boolean;
+  has_battery: boolean;
+  battery_state: string;  // charging / discharging / full / empty / unknown / ac
+  battery_time_remaining_secs: number | null;
+  battery_health: number | null;
+  cpu_temp_c: number | null;
+  max_temp_c: number | null;
+  thermal_critical: boolean;
+  disks: DiskInfo[];
+}
+
+interface DiskInfo {
+  mount_point: string;
+  name: string;
+  file_system: string;
+  total_bytes: number;
+  free_bytes: number;
+  is_removable: boolean;
 }
 
 // --- Boot Sequence ---
@@ -124,9 +190,27 @@ const StatusPanel = ({ stats }: { stats: SystemStats | null }) => (
     <div className="axis-status-line">
       <span className="axis-status-label">POWER</span>
       <span className="axis-status-value">
-        {stats ? `${stats.battery_level}%` : "AC NET"}
+        {stats && stats.has_battery
+          ? `${stats.battery_level}%${stats.is_charging ? " ⚡" : ""}`
+          : "AC NET"}
       </span>
     </div>
+    <div className="axis-status-line">
+      <span className="axis-status-label">THERMAL</span>
+      <span className="axis-status-value" style={{ color: stats?.thermal_critical ? 'var(--axis-danger)' : 'var(--axis-primary)' }}>
+        {stats && (stats.cpu_temp_c ?? stats.max_temp_c) != null
+          ? `${Math.round((stats.cpu_temp_c ?? stats.max_temp_c) as number)}°C`
+          : "N/A"}
+      </span>
+    </div>
+    {stats?.disks?.filter(d => !d.is_removable).map(d => (
+      <div className="axis-status-line" key={d.mount_point}>
+        <span className="axis-status-label">DISK {d.mount_point}</span>
+        <span className="axis-status-value" style={{ color: d.free_bytes < d.total_bytes * 0.1 ? 'var(--axis-danger)' : 'var(--axis-primary)' }}>
+          {`${bytesToGB(d.free_bytes)} GB FREE`}
+        </span>
+      </div>
+    ))}
   </div>
 );
 
@@ -350,6 +434,27 @@ const App: React.FC = () => {
     }
   };
 
+  // 👍/👎（同じ評価をもう一度押すと取り消し）
+  const handleRate = async (log: InteractionLog, rating: number) => {
+    const next = log.feedback?.rating === rating ? 0 : rating;
+    try {
+      const updated = await invoke<InteractionLog>("rate_response", { logId: log.id, rating: next, comment: null });
+      setLogs(prev => prev.map(l => (l.id === updated.id ? updated : l)));
+    } catch (err) { console.error("Rate Error:", err); }
+  };
+
+  // ↻ 答え直し（provider 未指定なら Commander が選び直す）
+  const handleRegenerate = async (log: InteractionLog) => {
+    if (isThinking) return;
+    setIsThinking(true);
+    try {
+      await invoke<InteractionLog>("regenerate_response", { logId: log.id, provider: null });
+      const updated = await invoke<InteractionLog[]>("fetch_history");
+      setLogs(updated);
+    } catch (err) { console.error("Regenerate Error:", err); }
+    finally { setIsThinking(false); }
+  };
+
   // 2. キー入力の監視部（Shift+Enterか、ただのEnterかを仕分ける門番）
   const handleKeyDown = (e: React.KeyboardEvent<HTMLTextAreaElement>) => {
     if (e.key === "Enter") {
@@ -535,17 +640,52 @@ const App: React.FC = () => {
 
               {currentLogs.map((log) => (
                 <React.Fragment key={log.id}>
-                  <div className="axis-msg user">
+                  {!log.branch_of && <div className="axis-msg user">
                     <span className="axis-msg-sender">OPERATOR</span>
                     <div className="axis-msg-bubble">
                       {log.user_tokens.map(t => t.text).join(" ")}
                     </div>
-                  </div>
+                  </div>}
                   <div className="axis-msg ai">
-                    <span className="axis-msg-sender">{log.provider_used}</span>
+                    <span className="axis-msg-sender" title={log.meta ? `${log.meta.model} — ${log.meta.reason}` : undefined}>
+                      {log.branch_of ? "↻ " : ""}{log.provider_used}{log.meta?.session_override ? ` 📌${log.meta.session_override}` : ""}{log.meta?.persona ? ` 🎭${log.meta.persona}` : ""}
+                    </span>
                     <div className="axis-msg-bubble">
                       {log.ai_response}
                     </div>
+                    {log.meta?.files?.map(f => (
+                      <div key={f.path} className="axis-msg-bubble" style={{ marginTop: '4px', fontSize: '12px' }}>
+                        <div style={{ display: 'flex', gap: '8px', alignItems: 'center' }}>
+                          <span style={{ fontFamily: 'var(--axis-font-mono)' }}>📄 {f.name} ({f.size} B)</span>
+                          <button onClick={() => invoke("open_file", { path: f.path })} title="Open">開く</button>
+                          <button onClick={() => invoke("reveal_in_explorer", { path: f.path })} title="Show in Explorer">場所を表示</button>
+                        </div>
+                        {f.preview && <pre style={{ margin: '6px 0 0', whiteSpace: 'pre-wrap', opacity: 0.8 }}>{f.preview}</pre>}
+                      </div>
+                    ))}
+                    {log.provider_used !== "Observer" && (
+                      <div style={{ display: 'flex', gap: '6px', marginTop: '4px', fontSize: '12px' }}>
+                        {[1, -1].map(r => (
+                          <button
+                            key={r}
+                            onClick={() => handleRate(log, r)}
+                            style={{
+                              background: 'none', border: 'none', cursor: 'pointer', padding: 0,
+                              opacity: log.feedback?.rating === r ? 1 : 0.35,
+                            }}
+                          >
+                            {r > 0 ? "👍" : "👎"}
+                          </button>
+                        ))}
+                        <button
+                          onClick={() => handleRegenerate(log)}
+                          title="Regenerate"
+                          style={{ background: 'none', border: 'none', cursor: 'pointer', padding: 0, opacity: 0.35 }}
+                        >
+                          ↻
+                        </button>
+                      </div>
+                    )}
                   </div>
                 </React.Fragment>
               ))}
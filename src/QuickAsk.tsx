@@ -0,0 +1,92 @@
+import { useEffect, useRef, useState } from "react";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+// =========================================================================================
+//  Quick Ask (グローバルホットキーで開く小さな入力欄)
+//  - 入力は quick_ask へ（ホットキーを押した時に見ていたウィンドウを文脈として付ける）
+//  - Esc / フォーカスが外れたら隠す（閉じずに使い回す）
+// =========================================================================================
+
+// ホットキーを押した時の前面ウィンドウ (quick.rs)
+interface QuickContext {
+  title: string;
+  app: string;
+  captured_at: number;
+  has_screenshot: boolean;
+}
+
+export default function QuickAsk() {
+  const [input, setInput] = useState("");
+  const [answer, setAnswer] = useState("");
+  const [isThinking, setIsThinking] = useState(false);
+  const [context, setContext] = useState<QuickContext | null>(null);
+  const [useContext, setUseContext] = useState(true);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  // 再表示されたら入力欄にフォーカスし、文脈を取り直す
+  useEffect(() => {
+    const refresh = () => {
+      inputRef.current?.focus();
+      invoke<QuickContext | null>("get_quick_context").then(setContext).catch(console.error);
+      setUseContext(true);
+    };
+    refresh();
+    const unlistenFocus = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) refresh();
+    });
+    const unlistenContext = listen<QuickContext>("axis-quick-context", e => setContext(e.payload));
+    return () => {
+      unlistenFocus.then(f => f());
+      unlistenContext.then(f => f());
+    };
+  }, []);
+
+  const hide = () => {
+    setAnswer("");
+    getCurrentWindow().hide();
+  };
+
+  const send = async () => {
+    const text = input.trim();
+    if (!text || isThinking) return;
+    setInput("");
+    setIsThinking(true);
+    try {
+      const res = await invoke<string>("quick_ask", { input: text, useContext });
+      setAnswer(res);
+    } catch (err) {
+      setAnswer(`Error: ${err}`);
+    } finally {
+      setIsThinking(false);
+    }
+  };
+
+  return (
+    <div className="axis-quick" data-tauri-drag-region>
+      <input
+        ref={inputRef}
+        className="axis-quick-input"
+        value={input}
+        placeholder={isThinking ? "Thinking..." : "Ask Axis..."}
+        disabled={isThinking}
+        onChange={e => setInput(e.target.value)}
+        onKeyDown={e => {
+          if (e.key === "Enter" && !e.nativeEvent.isComposing) send();
+          if (e.key === "Escape") hide();
+        }}
+      />
+      {context && (
+        <button
+          className={`axis-quick-context ${useContext ? "" : "off"}`}
+          onClick={() => setUseContext(v => !v)}
+          title="クリックで文脈を付ける / 外す"
+        >
+          📎 {context.title || context.app}{context.has_screenshot ? " 🖼" : ""}
+        </button>
+      )}
+      {answer && <div className="axis-quick-answer">{answer}</div>}
+    </div>
+  );
+}
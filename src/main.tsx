@@ -1,10 +1,12 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import QuickAsk from "./QuickAsk";
 import "./styles.css";
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {/* ホットキーで開く quick-ask ウィンドウは index.html?quick=1 */}
+    {new URLSearchParams(window.location.search).has("quick") ? <QuickAsk /> : <App />}
   </React.StrictMode>
 );